@@ -26,10 +26,12 @@
  * SOFTWARE.
  */
 // Deps
-use crate::filetransfer::{FileTransferParams, FileTransferProtocol};
+use crate::filetransfer::{FileTransferParams, FileTransferProtocol, SlowLink};
 use crate::host::{HostError, Localhost};
 use crate::system::config_client::ConfigClient;
 use crate::system::environment;
+use crate::system::exit_hooks::{self, ExitEvent, Session};
+use crate::system::startup_profile;
 use crate::system::theme_provider::ThemeProvider;
 use crate::ui::activities::{
     auth::AuthActivity, filetransfer::FileTransferActivity, setup::SetupActivity, Activity,
@@ -40,7 +42,7 @@ use crate::ui::context::Context;
 // Namespaces
 use std::path::{Path, PathBuf};
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// ### NextActivity
 ///
@@ -58,29 +60,53 @@ pub struct ActivityManager {
     context: Option<Context>,
     interval: Duration,
     local_dir: PathBuf,
+    session: Option<Session>, // Last session with a remote host, exported to exit hooks
 }
 
 impl ActivityManager {
     /// ### new
     ///
-    /// Initializes a new Activity Manager
-    pub fn new(local_dir: &Path, interval: Duration) -> Result<ActivityManager, HostError> {
+    /// Initializes a new Activity Manager.
+    /// In safe mode, the default configuration and theme are used, instead of reading them
+    pub fn new(
+        local_dir: &Path,
+        interval: Duration,
+        safe_mode: bool,
+    ) -> Result<ActivityManager, HostError> {
         // Prepare Context
         // Initialize configuration client
-        let (config_client, error): (ConfigClient, Option<String>) =
-            match Self::init_config_client() {
+        let (config_client, error): (ConfigClient, Option<String>) = match safe_mode {
+            true => {
+                warn!("Safe mode enabled; configuration, bookmarks and theme won't be loaded");
+                (ConfigClient::degraded(), None)
+            }
+            false => match Self::init_config_client() {
                 Ok(cli) => (cli, None),
                 Err(err) => {
                     error!("Failed to initialize config client: {}", err);
                     (ConfigClient::degraded(), Some(err))
                 }
-            };
-        let theme_provider: ThemeProvider = Self::init_theme_provider();
-        let ctx: Context = Context::new(config_client, theme_provider, error);
+            },
+        };
+        startup_profile::mark("load configuration");
+        // The theme is read once needed, not to delay the first paint
+        let theme_provider: ThemeProvider = match safe_mode {
+            true => ThemeProvider::degraded(),
+            false => Self::init_theme_provider(),
+        };
+        let mut ctx: Context = Context::new(config_client, theme_provider, error);
+        ctx.set_safe_mode(safe_mode);
+        // Serve transfer metrics
+        if let Some(port) = ctx.config().get_metrics_port() {
+            if let Err(err) = ctx.metrics().serve(port) {
+                error!("Could not serve metrics on port {}: {}", port, err);
+            }
+        }
         Ok(ActivityManager {
             context: Some(ctx),
             local_dir: local_dir.to_path_buf(),
             interval,
+            session: None,
         })
     }
 
@@ -92,6 +118,13 @@ impl ActivityManager {
         self.context.as_mut().unwrap().set_ftparams(params);
     }
 
+    /// ### set_slow_link
+    ///
+    /// Simulate the network conditions of `link` on transfers
+    pub fn set_slow_link(&mut self, link: SlowLink) {
+        self.context.as_mut().unwrap().set_slow_link(link);
+    }
+
     /// ### run
     ///
     ///
@@ -110,7 +143,15 @@ impl ActivityManager {
             }
         }
         // Drop context
+        let on_quit: Option<String> = self
+            .context
+            .as_ref()
+            .and_then(|ctx| ctx.config().get_on_quit().map(String::from));
         drop(self.context.take());
+        // Run hook once the terminal has been restored
+        if let Some(command) = on_quit {
+            Self::run_exit_hook(command.as_str(), ExitEvent::Quit, self.session.as_ref());
+        }
     }
 
     // -- Activity Loops
@@ -186,6 +227,7 @@ impl ActivityManager {
                 return None;
             }
         };
+        ctx.theme_provider_mut().ensure_loaded();
         // If ft params is None, return None
         let ft_params: &FileTransferParams = match ctx.ft_params() {
             Some(ft_params) => ft_params,
@@ -196,6 +238,7 @@ impl ActivityManager {
         };
         // Prepare activity
         let protocol: FileTransferProtocol = ft_params.protocol;
+        let started: Instant = Instant::now();
         let host: Localhost = match Localhost::new(self.local_dir.clone()) {
             Ok(host) => host,
             Err(err) => {
@@ -205,7 +248,20 @@ impl ActivityManager {
                 return None;
             }
         };
-        let mut activity: FileTransferActivity = FileTransferActivity::new(host, protocol);
+        let mut activity: FileTransferActivity =
+            match FileTransferActivity::new(host, protocol, ctx.safe_mode()) {
+                Ok(activity) => activity,
+                Err(err) => {
+                    // Go back to authentication and report the error there
+                    error!("Failed to initialize file transfer client: {}", err);
+                    ctx.set_error(format!(
+                        "Could not initialize file transfer client: {}",
+                        err
+                    ));
+                    self.context = Some(ctx);
+                    return Some(NextActivity::Authentication);
+                }
+            };
         // Prepare result
         let result: Option<NextActivity>;
         // Create activity
@@ -235,6 +291,19 @@ impl ActivityManager {
         }
         // Destroy activity
         self.context = activity.on_destroy();
+        // Session has ended
+        if let Some(ctx) = self.context.as_ref() {
+            if let Some(params) = ctx.ft_params() {
+                self.session = Some(Session::new(
+                    params,
+                    self.local_dir.as_path(),
+                    started.elapsed(),
+                ));
+            }
+            if let Some(command) = ctx.config().get_on_disconnect() {
+                Self::run_exit_hook(command, ExitEvent::Disconnect, self.session.as_ref());
+            }
+        }
         result
     }
 
@@ -247,13 +316,14 @@ impl ActivityManager {
         // Prepare activity
         let mut activity: SetupActivity = SetupActivity::default();
         // Get context
-        let ctx: Context = match self.context.take() {
+        let mut ctx: Context = match self.context.take() {
             Some(ctx) => ctx,
             None => {
                 error!("Failed to start SetupActivity: context is None");
                 return None;
             }
         };
+        ctx.theme_provider_mut().ensure_loaded();
         // Create activity
         activity.on_create(ctx);
         loop {
@@ -275,6 +345,20 @@ impl ActivityManager {
 
     // -- misc
 
+    /// ### run_exit_hook
+    ///
+    /// Run the hook configured for `event`; failures are only logged
+    fn run_exit_hook(command: &str, event: ExitEvent, session: Option<&Session>) {
+        info!("Running {} hook: {}", event.name(), command);
+        match exit_hooks::run(command, event, session) {
+            Ok(status) if status.success() => {
+                info!("{} hook exited successfully", event.name());
+            }
+            Ok(status) => warn!("{} hook exited with {}", event.name(), status),
+            Err(err) => error!("Could not run {} hook: {}", event.name(), err),
+        }
+    }
+
     /// ### init_config_client
     ///
     /// Initialize configuration client
@@ -311,13 +395,7 @@ impl ActivityManager {
                     Some(config_dir) => {
                         // Get config client paths
                         let theme_path: PathBuf = environment::get_theme_path(config_dir.as_path());
-                        match ThemeProvider::new(theme_path.as_path()) {
-                            Ok(provider) => provider,
-                            Err(err) => {
-                                error!("Could not initialize theme provider with file '{}': {}; using theme provider in degraded mode", theme_path.display(), err);
-                                ThemeProvider::degraded()
-                            }
-                        }
+                        ThemeProvider::lazy(theme_path.as_path())
                     }
                     None => {
                         error!("This system doesn't provide a configuration directory; using theme provider in degraded mode");
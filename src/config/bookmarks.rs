@@ -25,20 +25,26 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+use super::serialization::UnknownKeys;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[derive(Deserialize, Serialize, std::fmt::Debug)]
 /// ## UserHosts
 ///
 /// UserHosts contains all the hosts saved by the user in the data storage
-/// It contains both `Bookmark`
+/// It contains both `Bookmark` and the remote paths marked as favorite for each host
 pub struct UserHosts {
     pub bookmarks: HashMap<String, Bookmark>,
     pub recents: HashMap<String, Bookmark>,
+    #[serde(default)]
+    pub favorites: HashMap<String, Vec<String>>, // Host key => favorite remote paths
+    #[serde(flatten)]
+    pub unknown: UnknownKeys, // Keys unknown to termscp; kept as they are
 }
 
-#[derive(Deserialize, Serialize, std::fmt::Debug, PartialEq)]
+#[derive(Deserialize, Serialize, std::fmt::Debug, PartialEq, Clone)]
 /// ## Bookmark
 ///
 /// Bookmark describes a single bookmark entry in the user hosts storage
@@ -48,6 +54,49 @@ pub struct Bookmark {
     pub protocol: String,
     pub username: String,
     pub password: Option<String>, // Password is optional; base64, aes-128 encrypted password
+    #[serde(default)]
+    pub proxy: Option<String>, // Proxy url to connect through; base64, aes-128 encrypted as the password
+    #[serde(default)]
+    pub filters: Vec<String>, // Include/exclude rules for recursive transfers
+    #[serde(default)]
+    pub mapping: Option<PathMapping>, // Local <-> remote root mapping
+    #[serde(default)]
+    pub tls: Option<TlsConfig>, // Client certificate and CA bundle for FTPS
+    #[serde(default)]
+    pub trash: Option<PathBuf>, // Remote directory where deleted entries are moved to
+    #[serde(default)]
+    pub directory: Option<PathBuf>, // Remote directory to enter once connected
+    #[serde(default)]
+    pub local_directory: Option<PathBuf>, // Local directory to enter once connected
+    #[serde(default)]
+    pub agent_forwarding: bool, // Forward the local ssh agent to the commands executed on the remote
+    #[serde(default)]
+    pub compression: Option<bool>, // Compress the ssh transport; if unset, the configuration decides
+    #[serde(default)]
+    pub group: Option<String>, // Group the bookmark is listed under
+    #[serde(flatten)]
+    pub unknown: UnknownKeys, // Keys unknown to termscp; kept as they are
+}
+
+#[derive(Deserialize, Serialize, std::fmt::Debug, PartialEq, Clone)]
+/// ## PathMapping
+///
+/// PathMapping describes a local directory and the remote directory mirroring it
+pub struct PathMapping {
+    pub local: PathBuf,
+    pub remote: PathBuf,
+}
+
+#[derive(Deserialize, Serialize, std::fmt::Debug, PartialEq, Clone, Default)]
+/// ## TlsConfig
+///
+/// TlsConfig describes the client certificate to authenticate with and the CA bundle to trust
+/// when connecting to an FTPS server. The client certificate is a PKCS#12 archive, the CA bundle a PEM file
+pub struct TlsConfig {
+    #[serde(default)]
+    pub client_cert: Option<PathBuf>,
+    #[serde(default)]
+    pub ca_bundle: Option<PathBuf>,
 }
 
 impl Default for UserHosts {
@@ -55,6 +104,8 @@ impl Default for UserHosts {
         Self {
             bookmarks: HashMap::new(),
             recents: HashMap::new(),
+            favorites: HashMap::new(),
+            unknown: UnknownKeys::default(),
         }
     }
 }
@@ -72,6 +123,7 @@ mod tests {
         let bookmarks: UserHosts = UserHosts::default();
         assert_eq!(bookmarks.bookmarks.len(), 0);
         assert_eq!(bookmarks.recents.len(), 0);
+        assert_eq!(bookmarks.favorites.len(), 0);
     }
 
     #[test]
@@ -82,6 +134,17 @@ mod tests {
             protocol: String::from("SFTP"),
             username: String::from("root"),
             password: Some(String::from("password")),
+            proxy: None,
+            filters: Vec::new(),
+            mapping: None,
+            tls: None,
+            trash: None,
+            directory: None,
+            local_directory: None,
+            agent_forwarding: false,
+            compression: None,
+            group: None,
+            unknown: UnknownKeys::default(),
         };
         let recent: Bookmark = Bookmark {
             address: String::from("192.168.1.2"),
@@ -89,6 +152,17 @@ mod tests {
             protocol: String::from("SCP"),
             username: String::from("admin"),
             password: Some(String::from("password")),
+            proxy: None,
+            filters: Vec::new(),
+            mapping: None,
+            tls: None,
+            trash: None,
+            directory: None,
+            local_directory: None,
+            agent_forwarding: false,
+            compression: None,
+            group: None,
+            unknown: UnknownKeys::default(),
         };
         let mut bookmarks: HashMap<String, Bookmark> = HashMap::with_capacity(1);
         bookmarks.insert(String::from("test"), bookmark);
@@ -97,6 +171,8 @@ mod tests {
         let hosts: UserHosts = UserHosts {
             bookmarks: bookmarks,
             recents: recents,
+            favorites: HashMap::new(),
+            unknown: UnknownKeys::default(),
         };
         // Verify
         let bookmark: &Bookmark = hosts.bookmarks.get(&String::from("test")).unwrap();
@@ -0,0 +1,52 @@
+//! ## Journal
+//!
+//! `journal` is the module which provides data types and de/serializer for the journal of the transfers in progress
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Deserialize, Serialize, std::fmt::Debug, Default, PartialEq)]
+/// ## TransferJournal
+///
+/// TransferJournal contains the batch transfer in progress with each host.
+/// Transfers are removed once they're over, so the ones left have been interrupted
+pub struct TransferJournal {
+    #[serde(default)]
+    pub hosts: HashMap<String, JournalEntry>, // Host key => transfer in progress
+}
+
+#[derive(Deserialize, Serialize, std::fmt::Debug, PartialEq, Clone)]
+/// ## JournalEntry
+///
+/// JournalEntry describes a batch transfer: the entries to transfer and where to
+pub struct JournalEntry {
+    pub upload: bool, // Whether entries are sent to remote; otherwise they're downloaded
+    pub sources: Vec<PathBuf>,
+    pub dest: PathBuf,
+    pub dst_name: Option<String>,
+    pub started: String, // Local time the transfer was started at
+}
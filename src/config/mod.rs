@@ -29,6 +29,8 @@
 pub use params::*;
 
 pub mod bookmarks;
+pub mod journal;
 pub mod params;
 pub mod serialization;
 pub mod themes;
+pub mod usage;
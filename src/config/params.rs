@@ -26,6 +26,7 @@
  * SOFTWARE.
  */
 // Locals
+use super::serialization::UnknownKeys;
 use crate::filetransfer::FileTransferProtocol;
 
 // Ext
@@ -41,6 +42,8 @@ use std::path::PathBuf;
 pub struct UserConfig {
     pub user_interface: UserInterfaceConfig,
     pub remote: RemoteConfig,
+    #[serde(flatten)]
+    pub unknown: UnknownKeys, // Keys unknown to termscp; kept as they are
 }
 
 #[derive(Deserialize, Serialize, std::fmt::Debug)]
@@ -55,6 +58,33 @@ pub struct UserInterfaceConfig {
     pub group_dirs: Option<String>,
     pub file_fmt: Option<String>, // Refers to local host (for backward compatibility)
     pub remote_file_fmt: Option<String>, // @! Since 0.5.0
+    pub low_bandwidth: Option<bool>, // @! Since 0.6.1
+    pub delta_transfer: Option<bool>, // @! Since 0.6.1
+    pub skip_unchanged: Option<bool>, // @! Since 0.6.1
+    pub preserve_owner: Option<bool>, // @! Since 0.6.1
+    pub symlinks: Option<String>, // @! Since 0.6.1; skip, follow or recreate
+    pub show_banner: Option<bool>, // @! Since 0.6.1
+    pub download_dir: Option<PathBuf>, // @! Since 0.6.1
+    pub recents_size: Option<usize>, // @! Since 0.6.1; 0 disables recents
+    pub recents_max_age: Option<u64>, // @! Since 0.6.1; days
+    pub stall_timeout: Option<u64>, // @! Since 0.6.1; seconds, 0 disables the watchdog
+    pub transfer_retries: Option<usize>, // @! Since 0.6.1; 0 disables retries
+    pub diff_tool: Option<String>, // @! Since 0.6.1; command with `{local}` and `{remote}` placeholders
+    pub merge_tool: Option<String>, // @! Since 0.6.1; command with `{local}` and `{remote}` placeholders
+    pub prefetch_dirs: Option<bool>, // @! Since 0.6.1
+    pub file_sorting: Option<String>, // @! Since 0.6.1
+    pub size_units: Option<String>, // @! Since 0.6.1; si or binary
+    pub clock: Option<String>,      // @! Since 0.6.1; 24h or 12h
+    pub date_format: Option<String>, // @! Since 0.6.1; strftime syntax
+    pub parallel_transfers: Option<usize>, // @! Since 0.6.1; 1 transfers files one by one
+    pub webhook_url: Option<String>, // @! Since 0.6.1; POSTed to after batch transfers
+    pub webhook_template: Option<String>, // @! Since 0.6.1; JSON body with placeholders
+    pub metrics_file: Option<PathBuf>, // @! Since 0.6.1; Prometheus metrics, rewritten after each transfer
+    pub metrics_port: Option<u16>,     // @! Since 0.6.1; Prometheus metrics served on localhost
+    pub on_disconnect: Option<String>, // @! Since 0.6.1; command run when a session ends
+    pub on_quit: Option<String>,       // @! Since 0.6.1; command run when termscp exits
+    #[serde(flatten)]
+    pub unknown: UnknownKeys, // Keys unknown to termscp; kept as they are
 }
 
 #[derive(Deserialize, Serialize, std::fmt::Debug)]
@@ -62,7 +92,11 @@ pub struct UserInterfaceConfig {
 ///
 /// Contains configuratio related to remote hosts
 pub struct RemoteConfig {
+    pub proxy: Option<String>,              // @! Since 0.6.1
+    pub compression: Option<bool>,          // @! Since 0.6.1; zlib compression of the ssh transport
     pub ssh_keys: HashMap<String, PathBuf>, // Association between host name and path to private key
+    #[serde(flatten)]
+    pub unknown: UnknownKeys, // Keys unknown to termscp; kept as they are
 }
 
 impl Default for UserConfig {
@@ -70,6 +104,7 @@ impl Default for UserConfig {
         UserConfig {
             user_interface: UserInterfaceConfig::default(),
             remote: RemoteConfig::default(),
+            unknown: UnknownKeys::default(),
         }
     }
 }
@@ -85,8 +120,34 @@ impl Default for UserInterfaceConfig {
             show_hidden_files: false,
             check_for_updates: Some(true),
             group_dirs: None,
+            file_sorting: None,
+            size_units: None,
+            clock: None,
+            date_format: None,
             file_fmt: None,
             remote_file_fmt: None,
+            low_bandwidth: None,
+            delta_transfer: None,
+            skip_unchanged: None,
+            preserve_owner: None,
+            symlinks: None,
+            show_banner: None,
+            download_dir: None,
+            recents_size: None,
+            recents_max_age: None,
+            stall_timeout: None,
+            transfer_retries: None,
+            diff_tool: None,
+            merge_tool: None,
+            prefetch_dirs: None,
+            parallel_transfers: None,
+            webhook_url: None,
+            webhook_template: None,
+            metrics_file: None,
+            metrics_port: None,
+            on_disconnect: None,
+            on_quit: None,
+            unknown: UnknownKeys::default(),
         }
     }
 }
@@ -94,7 +155,10 @@ impl Default for UserInterfaceConfig {
 impl Default for RemoteConfig {
     fn default() -> Self {
         RemoteConfig {
+            proxy: None,
+            compression: None,
             ssh_keys: HashMap::new(),
+            unknown: UnknownKeys::default(),
         }
     }
 }
@@ -114,25 +178,91 @@ mod tests {
             String::from("192.168.1.31"),
             PathBuf::from("/tmp/private.key"),
         );
-        let remote: RemoteConfig = RemoteConfig { ssh_keys: keys };
+        let remote: RemoteConfig = RemoteConfig {
+            proxy: Some(String::from("socks5://127.0.0.1:1080")),
+            compression: Some(true),
+            ssh_keys: keys,
+            unknown: UnknownKeys::default(),
+        };
         let ui: UserInterfaceConfig = UserInterfaceConfig {
             default_protocol: String::from("SFTP"),
             text_editor: PathBuf::from("nano"),
             show_hidden_files: true,
             check_for_updates: Some(true),
             group_dirs: Some(String::from("first")),
+            file_sorting: Some(String::from("by_extension")),
+            size_units: Some(String::from("binary")),
+            clock: Some(String::from("12h")),
+            date_format: Some(String::from("%d/%m/%Y")),
             file_fmt: Some(String::from("{NAME}")),
             remote_file_fmt: Some(String::from("{USER}")),
+            low_bandwidth: Some(true),
+            delta_transfer: Some(true),
+            skip_unchanged: Some(true),
+            preserve_owner: Some(true),
+            symlinks: Some(String::from("recreate")),
+            show_banner: Some(false),
+            download_dir: Some(PathBuf::from("/home/omar/Downloads")),
+            recents_size: Some(8),
+            recents_max_age: Some(30),
+            stall_timeout: Some(45),
+            transfer_retries: Some(5),
+            diff_tool: Some(String::from("vimdiff {local} {remote}")),
+            merge_tool: None,
+            prefetch_dirs: Some(true),
+            parallel_transfers: Some(4),
+            webhook_url: Some(String::from("http://localhost:8080/hooks/termscp")),
+            webhook_template: None,
+            metrics_file: Some(PathBuf::from("/var/lib/node_exporter/termscp.prom")),
+            metrics_port: Some(9184),
+            on_disconnect: Some(String::from("notify-send \"termscp: $TERMSCP_HOST\"")),
+            on_quit: None,
+            unknown: UnknownKeys::default(),
         };
         assert_eq!(ui.default_protocol, String::from("SFTP"));
         assert_eq!(ui.text_editor, PathBuf::from("nano"));
         assert_eq!(ui.show_hidden_files, true);
         assert_eq!(ui.check_for_updates, Some(true));
         assert_eq!(ui.group_dirs, Some(String::from("first")));
+        assert_eq!(ui.file_sorting, Some(String::from("by_extension")));
+        assert_eq!(ui.size_units.as_deref(), Some("binary"));
+        assert_eq!(ui.clock.as_deref(), Some("12h"));
+        assert_eq!(ui.date_format.as_deref(), Some("%d/%m/%Y"));
         assert_eq!(ui.file_fmt, Some(String::from("{NAME}")));
+        assert_eq!(ui.low_bandwidth, Some(true));
+        assert_eq!(ui.delta_transfer, Some(true));
+        assert_eq!(ui.skip_unchanged, Some(true));
+        assert_eq!(ui.preserve_owner, Some(true));
+        assert_eq!(ui.symlinks.as_deref(), Some("recreate"));
+        assert_eq!(ui.show_banner, Some(false));
+        assert_eq!(ui.download_dir, Some(PathBuf::from("/home/omar/Downloads")));
+        assert_eq!(ui.recents_size, Some(8));
+        assert_eq!(ui.recents_max_age, Some(30));
+        assert_eq!(ui.stall_timeout, Some(45));
+        assert_eq!(ui.transfer_retries, Some(5));
+        assert_eq!(ui.diff_tool.as_deref(), Some("vimdiff {local} {remote}"));
+        assert!(ui.merge_tool.is_none());
+        assert_eq!(ui.prefetch_dirs, Some(true));
+        assert_eq!(ui.parallel_transfers, Some(4));
+        assert_eq!(
+            ui.webhook_url.as_deref(),
+            Some("http://localhost:8080/hooks/termscp")
+        );
+        assert!(ui.webhook_template.is_none());
+        assert_eq!(
+            ui.metrics_file,
+            Some(PathBuf::from("/var/lib/node_exporter/termscp.prom"))
+        );
+        assert_eq!(ui.metrics_port, Some(9184));
+        assert_eq!(
+            ui.on_disconnect.as_deref(),
+            Some("notify-send \"termscp: $TERMSCP_HOST\"")
+        );
+        assert!(ui.on_quit.is_none());
         let cfg: UserConfig = UserConfig {
             user_interface: ui,
             remote: remote,
+            unknown: UnknownKeys::default(),
         };
         assert_eq!(
             *cfg.remote
@@ -141,6 +271,8 @@ mod tests {
                 .unwrap(),
             PathBuf::from("/tmp/private.key")
         );
+        assert_eq!(cfg.remote.proxy.as_deref(), Some("socks5://127.0.0.1:1080"));
+        assert_eq!(cfg.remote.compression, Some(true));
         assert_eq!(cfg.user_interface.default_protocol, String::from("SFTP"));
         assert_eq!(cfg.user_interface.text_editor, PathBuf::from("nano"));
         assert_eq!(cfg.user_interface.show_hidden_files, true);
@@ -25,9 +25,10 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::io::{Read, Write};
 use thiserror::Error;
+use toml::value::Table;
 
 /// ## SerializerError
 ///
@@ -69,6 +70,13 @@ impl SerializerError {
         err.msg = Some(msg);
         err
     }
+
+    /// ### kind
+    ///
+    /// Get the kind of the error
+    pub fn kind(&self) -> &SerializerErrorKind {
+        &self.kind
+    }
 }
 
 impl std::fmt::Display for SerializerError {
@@ -80,24 +88,50 @@ impl std::fmt::Display for SerializerError {
     }
 }
 
+/// ## UnknownKeys
+///
+/// UnknownKeys holds the keys of a table which termscp doesn't know, such as the ones written by newer versions
+/// or by external tools. Flattened into a struct, it keeps them untouched, so that they're written back on save
+#[derive(Deserialize, Serialize, std::fmt::Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct UnknownKeys(Table);
+
+impl UnknownKeys {
+    /// ### is_empty
+    ///
+    /// Returns whether there is no unknown key
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// ### keys
+    ///
+    /// Iterate over unknown keys
+    pub fn keys(&self) -> impl Iterator<Item = &String> + '_ {
+        self.0.keys()
+    }
+
+    /// ### warn
+    ///
+    /// Report unknown keys found in table at `path` (empty for the root table)
+    pub fn warn(&self, path: &str) {
+        for key in self.keys() {
+            match path.is_empty() {
+                true => warn!("Unknown key \"{}\"; it will be kept as it is", key),
+                false => warn!("Unknown key \"{}.{}\"; it will be kept as it is", path, key),
+            }
+        }
+    }
+}
+
 /// ### serialize
 ///
-/// Serialize `UserHosts` into TOML and write content to writable
+/// Serialize `serializable` into a TOML string and write content to writable
 pub fn serialize<S>(serializable: &S, mut writable: Box<dyn Write>) -> Result<(), SerializerError>
 where
     S: Serialize + Sized,
 {
-    // Serialize content
-    let data: String = match toml::ser::to_string(serializable) {
-        Ok(dt) => dt,
-        Err(err) => {
-            return Err(SerializerError::new_ex(
-                SerializerErrorKind::Serialization,
-                err.to_string(),
-            ))
-        }
-    };
-    trace!("Serialized new bookmarks data: {}", data);
+    let data: String = serialize_to_string(serializable)?;
     // Write file
     match writable.write_all(data.as_bytes()) {
         Ok(_) => Ok(()),
@@ -108,6 +142,26 @@ where
     }
 }
 
+/// ### serialize_to_string
+///
+/// Serialize `serializable` into a TOML string
+pub fn serialize_to_string<S>(serializable: &S) -> Result<String, SerializerError>
+where
+    S: Serialize + Sized,
+{
+    // Serialize content; going through `Value` puts values before tables, wherever unknown keys are
+    match toml::Value::try_from(serializable).and_then(|value| toml::ser::to_string(&value)) {
+        Ok(data) => {
+            trace!("Serialized new bookmarks data: {}", data);
+            Ok(data)
+        }
+        Err(err) => Err(SerializerError::new_ex(
+            SerializerErrorKind::Serialization,
+            err.to_string(),
+        )),
+    }
+}
+
 /// ### deserialize
 ///
 /// Read data from readable and deserialize its content as TOML
@@ -123,9 +177,19 @@ where
             err.to_string(),
         ));
     }
+    deserialize_from_str(data.as_str())
+}
+
+/// ### deserialize_from_str
+///
+/// Deserialize `data` as TOML
+pub fn deserialize_from_str<S>(data: &str) -> Result<S, SerializerError>
+where
+    S: DeserializeOwned + Sized + std::fmt::Debug,
+{
     trace!("Read bookmarks from file: {}", data);
     // Deserialize
-    match toml::de::from_str(data.as_str()) {
+    match toml::de::from_str(data) {
         Ok(deserialized) => {
             debug!("Read bookmarks from file {:?}", deserialized);
             Ok(deserialized)
@@ -144,10 +208,10 @@ mod tests {
     use pretty_assertions::assert_eq;
     use std::collections::HashMap;
     use std::io::{Seek, SeekFrom};
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use tuirealm::tui::style::Color;
 
-    use crate::config::bookmarks::{Bookmark, UserHosts};
+    use crate::config::bookmarks::{Bookmark, PathMapping, TlsConfig, UserHosts};
     use crate::config::params::UserConfig;
     use crate::config::themes::Theme;
     use crate::utils::test_helpers::create_file_ioers;
@@ -199,7 +263,59 @@ mod tests {
         assert_eq!(cfg.user_interface.text_editor, PathBuf::from("vim"));
         assert_eq!(cfg.user_interface.show_hidden_files, true);
         assert_eq!(cfg.user_interface.check_for_updates.unwrap(), true);
+        assert_eq!(cfg.user_interface.low_bandwidth.unwrap(), true);
+        assert_eq!(cfg.user_interface.delta_transfer.unwrap(), true);
+        assert_eq!(cfg.user_interface.skip_unchanged.unwrap(), true);
+        assert_eq!(cfg.user_interface.preserve_owner.unwrap(), true);
+        assert_eq!(cfg.user_interface.symlinks.as_deref(), Some("skip"));
+        assert_eq!(cfg.user_interface.show_banner.unwrap(), false);
+        assert_eq!(
+            cfg.user_interface.download_dir,
+            Some(PathBuf::from("/home/omar/Downloads"))
+        );
+        assert_eq!(cfg.user_interface.recents_size, Some(8));
+        assert_eq!(cfg.user_interface.recents_max_age, Some(30));
+        assert_eq!(cfg.user_interface.stall_timeout, Some(45));
+        assert_eq!(cfg.user_interface.transfer_retries, Some(2));
+        assert_eq!(
+            cfg.user_interface.diff_tool.as_deref(),
+            Some("vimdiff {local} {remote}")
+        );
+        assert_eq!(
+            cfg.user_interface.merge_tool.as_deref(),
+            Some("meld {local} {remote}")
+        );
+        assert_eq!(cfg.user_interface.prefetch_dirs, Some(true));
+        assert_eq!(cfg.user_interface.parallel_transfers, Some(4));
+        assert_eq!(
+            cfg.user_interface.webhook_url.as_deref(),
+            Some("http://localhost:8080/hooks/termscp")
+        );
+        assert_eq!(
+            cfg.user_interface.webhook_template.as_deref(),
+            Some("{\"text\": \"{direction} {event}\"}")
+        );
+        assert_eq!(
+            cfg.user_interface.metrics_file,
+            Some(PathBuf::from("/var/lib/node_exporter/termscp.prom"))
+        );
+        assert_eq!(cfg.user_interface.metrics_port, Some(9184));
+        assert_eq!(
+            cfg.user_interface.on_disconnect.as_deref(),
+            Some("ssh-add -D")
+        );
+        assert_eq!(
+            cfg.user_interface.on_quit.as_deref(),
+            Some("xclip -selection clipboard /dev/null")
+        );
         assert_eq!(cfg.user_interface.group_dirs, Some(String::from("last")));
+        assert_eq!(
+            cfg.user_interface.file_sorting,
+            Some(String::from("by_owner"))
+        );
+        assert_eq!(cfg.user_interface.size_units.as_deref(), Some("binary"));
+        assert_eq!(cfg.user_interface.clock.as_deref(), Some("12h"));
+        assert_eq!(cfg.user_interface.date_format.as_deref(), Some("%Y-%m-%d"));
         assert_eq!(
             cfg.user_interface.file_fmt,
             Some(String::from("{NAME} {PEX}"))
@@ -208,6 +324,9 @@ mod tests {
             cfg.user_interface.remote_file_fmt,
             Some(String::from("{NAME} {USER}")),
         );
+        // Verify remote
+        assert_eq!(cfg.remote.proxy.as_deref(), Some("socks5://127.0.0.1:1080"));
+        assert_eq!(cfg.remote.compression, Some(true));
         // Verify keys
         assert_eq!(
             *cfg.remote
@@ -241,9 +360,36 @@ mod tests {
         assert_eq!(cfg.user_interface.text_editor, PathBuf::from("vim"));
         assert_eq!(cfg.user_interface.show_hidden_files, true);
         assert_eq!(cfg.user_interface.group_dirs, None);
+        assert_eq!(cfg.user_interface.file_sorting, None);
+        assert!(cfg.user_interface.size_units.is_none());
+        assert!(cfg.user_interface.clock.is_none());
+        assert!(cfg.user_interface.date_format.is_none());
         assert!(cfg.user_interface.check_for_updates.is_none());
+        assert!(cfg.user_interface.low_bandwidth.is_none());
+        assert!(cfg.user_interface.delta_transfer.is_none());
+        assert!(cfg.user_interface.skip_unchanged.is_none());
+        assert!(cfg.user_interface.preserve_owner.is_none());
+        assert!(cfg.user_interface.symlinks.is_none());
+        assert!(cfg.user_interface.show_banner.is_none());
+        assert!(cfg.user_interface.download_dir.is_none());
+        assert!(cfg.user_interface.recents_size.is_none());
+        assert!(cfg.user_interface.recents_max_age.is_none());
+        assert!(cfg.user_interface.stall_timeout.is_none());
+        assert!(cfg.user_interface.transfer_retries.is_none());
+        assert!(cfg.user_interface.diff_tool.is_none());
+        assert!(cfg.user_interface.merge_tool.is_none());
+        assert!(cfg.user_interface.prefetch_dirs.is_none());
+        assert!(cfg.user_interface.parallel_transfers.is_none());
+        assert!(cfg.user_interface.webhook_url.is_none());
+        assert!(cfg.user_interface.webhook_template.is_none());
+        assert!(cfg.user_interface.metrics_file.is_none());
+        assert!(cfg.user_interface.metrics_port.is_none());
+        assert!(cfg.user_interface.on_disconnect.is_none());
+        assert!(cfg.user_interface.on_quit.is_none());
         assert!(cfg.user_interface.file_fmt.is_none());
         assert!(cfg.user_interface.remote_file_fmt.is_none());
+        assert!(cfg.remote.proxy.is_none());
+        assert!(cfg.remote.compression.is_none());
         // Verify keys
         assert_eq!(
             *cfg.remote
@@ -289,6 +435,73 @@ mod tests {
         assert!(deserialize::<UserConfig>(Box::new(toml_file)).is_ok());
     }
 
+    #[test]
+    fn test_config_serialization_params_unknown_keys() {
+        let mut toml_file: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
+        let file_content: &str = r#"
+        version = 2
+
+        [user_interface]
+        default_protocol = "SCP"
+        text_editor = "vim"
+        show_hidden_files = true
+        future_option = "yes"
+
+        [remote]
+        keepalive = 30
+
+        [remote.ssh_keys]
+        "192.168.1.31" = "/home/omar/.ssh/raspberry.key"
+
+        [plugins.sync]
+        enabled = true
+        "#;
+        toml_file.write_all(file_content.as_bytes()).unwrap();
+        let mut cfg: UserConfig = deserialize(Box::new(toml_file.reopen().unwrap()))
+            .ok()
+            .unwrap();
+        // Unknown keys are collected
+        let mut keys: Vec<&String> = cfg.unknown.keys().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["plugins", "version"]);
+        assert_eq!(
+            cfg.user_interface.unknown.keys().collect::<Vec<&String>>(),
+            vec!["future_option"]
+        );
+        assert_eq!(
+            cfg.remote.unknown.keys().collect::<Vec<&String>>(),
+            vec!["keepalive"]
+        );
+        assert_eq!(cfg.user_interface.default_protocol, String::from("SCP"));
+        assert_eq!(cfg.remote.ssh_keys.len(), 1);
+        // Edit and save
+        cfg.user_interface.show_hidden_files = false;
+        let toml_file: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
+        let writer: Box<dyn Write> = Box::new(std::fs::File::create(toml_file.path()).unwrap());
+        assert!(serialize(&cfg, writer).is_ok());
+        // Unknown keys have been written back
+        let data: String = std::fs::read_to_string(toml_file.path()).unwrap();
+        let value: toml::Value = toml::from_str(data.as_str()).unwrap();
+        assert_eq!(value["version"].as_integer(), Some(2));
+        assert_eq!(
+            value["user_interface"]["future_option"].as_str(),
+            Some("yes")
+        );
+        assert_eq!(
+            value["user_interface"]["show_hidden_files"].as_bool(),
+            Some(false)
+        );
+        assert_eq!(value["remote"]["keepalive"].as_integer(), Some(30));
+        assert_eq!(value["plugins"]["sync"]["enabled"].as_bool(), Some(true));
+        // And configuration can still be read
+        let cfg: UserConfig = deserialize(Box::new(std::fs::File::open(toml_file.path()).unwrap()))
+            .ok()
+            .unwrap();
+        assert_eq!(cfg.remote.ssh_keys.len(), 1);
+        assert!(!cfg.user_interface.unknown.is_empty());
+        assert!(UserConfig::default().unknown.is_empty());
+    }
+
     #[test]
     fn test_config_serialization_params_fail_write() {
         let toml_file: tempfile::NamedTempFile = tempfile::NamedTempFile::new().ok().unwrap();
@@ -315,10 +528,39 @@ mod tests {
         text_editor = "vim"
         show_hidden_files = true
         check_for_updates = true
+        low_bandwidth = true
+        delta_transfer = true
+        skip_unchanged = true
+        preserve_owner = true
+        symlinks = "skip"
+        show_banner = false
+        download_dir = "/home/omar/Downloads"
+        recents_size = 8
+        recents_max_age = 30
+        stall_timeout = 45
+        transfer_retries = 2
+        diff_tool = "vimdiff {local} {remote}"
+        merge_tool = "meld {local} {remote}"
+        prefetch_dirs = true
+        parallel_transfers = 4
+        webhook_url = "http://localhost:8080/hooks/termscp"
+        webhook_template = '{"text": "{direction} {event}"}'
+        metrics_file = "/var/lib/node_exporter/termscp.prom"
+        metrics_port = 9184
+        on_disconnect = "ssh-add -D"
+        on_quit = "xclip -selection clipboard /dev/null"
         group_dirs = "last"
+        file_sorting = "by_owner"
+        size_units = "binary"
+        clock = "12h"
+        date_format = "%Y-%m-%d"
         file_fmt = "{NAME} {PEX}"
         remote_file_fmt = "{NAME} {USER}"
 
+        [remote]
+        proxy = "socks5://127.0.0.1:1080"
+        compression = true
+
         [remote.ssh_keys]
         "192.168.1.31" = "/home/omar/.ssh/raspberry.key"
         "192.168.1.32" = "/home/omar/.ssh/beaglebone.key"
@@ -398,6 +640,34 @@ mod tests {
         assert_eq!(host.protocol, String::from("FTPS"));
         assert_eq!(host.username, String::from("aws001"));
         assert_eq!(host.password, None);
+        assert_eq!(host.proxy.as_deref(), Some("http://proxy.local:3128"));
+        assert_eq!(
+            host.filters,
+            vec![String::from("+ keep.log"), String::from("- *.log")]
+        );
+        let mapping: &PathMapping = host.mapping.as_ref().unwrap();
+        assert_eq!(mapping.local, PathBuf::from("/home/omar/project"));
+        assert_eq!(mapping.remote, PathBuf::from("/var/www/project"));
+        let tls: &TlsConfig = host.tls.as_ref().unwrap();
+        assert_eq!(
+            tls.client_cert.as_deref(),
+            Some(Path::new("/home/omar/.certs/aws001.p12"))
+        );
+        assert!(tls.ca_bundle.is_none());
+        assert_eq!(
+            host.trash.as_deref(),
+            Some(Path::new("/home/aws001/.trash"))
+        );
+        assert!(hosts.bookmarks.get("msi-estrem").unwrap().tls.is_none());
+        assert!(hosts.bookmarks.get("msi-estrem").unwrap().trash.is_none());
+        assert!(hosts.bookmarks.get("msi-estrem").unwrap().mapping.is_none());
+        assert!(hosts.bookmarks.get("msi-estrem").unwrap().proxy.is_none());
+        assert!(hosts
+            .bookmarks
+            .get("msi-estrem")
+            .unwrap()
+            .filters
+            .is_empty());
     }
 
     #[test]
@@ -421,6 +691,17 @@ mod tests {
                 protocol: String::from("SFTP"),
                 username: String::from("root"),
                 password: None,
+                proxy: None,
+                filters: Vec::new(),
+                mapping: None,
+                tls: None,
+                trash: None,
+                directory: None,
+                local_directory: None,
+                agent_forwarding: false,
+                compression: None,
+                group: None,
+                unknown: UnknownKeys::default(),
             },
         );
         bookmarks.insert(
@@ -431,6 +712,23 @@ mod tests {
                 protocol: String::from("SFTP"),
                 username: String::from("cvisintin"),
                 password: Some(String::from("password")),
+                proxy: Some(String::from("socks5://127.0.0.1:1080")),
+                filters: vec![String::from("- *.log"), String::from("- .git/")],
+                mapping: Some(PathMapping {
+                    local: PathBuf::from("/home/cvisintin/project"),
+                    remote: PathBuf::from("/var/www/project"),
+                }),
+                tls: Some(TlsConfig {
+                    client_cert: None,
+                    ca_bundle: Some(PathBuf::from("/etc/ssl/private-ca.pem")),
+                }),
+                trash: Some(PathBuf::from("/home/cvisintin/.trash")),
+                directory: None,
+                local_directory: None,
+                agent_forwarding: false,
+                compression: None,
+                group: None,
+                unknown: UnknownKeys::default(),
             },
         );
         let mut recents: HashMap<String, Bookmark> = HashMap::with_capacity(1);
@@ -442,14 +740,72 @@ mod tests {
                 protocol: String::from("SCP"),
                 username: String::from("omar"),
                 password: Some(String::from("aaa")),
+                proxy: None,
+                filters: Vec::new(),
+                mapping: None,
+                tls: None,
+                trash: None,
+                directory: None,
+                local_directory: None,
+                agent_forwarding: false,
+                compression: None,
+                group: None,
+                unknown: UnknownKeys::default(),
             },
         );
         let tmpfile: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
         // Serialize
-        let hosts: UserHosts = UserHosts { bookmarks, recents };
+        let hosts: UserHosts = UserHosts {
+            bookmarks,
+            recents,
+            favorites: HashMap::new(),
+            unknown: UnknownKeys::default(),
+        };
         assert!(serialize(&hosts, Box::new(tmpfile)).is_ok());
     }
 
+    #[test]
+    fn test_config_serializer_bookmarks_unknown_keys() {
+        let mut toml_file: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
+        let file_content: &str = r#"
+        [bookmarks]
+        raspberrypi2 = { address = "192.168.1.31", port = 22, protocol = "SFTP", username = "root", color = "red" }
+
+        [recents]
+        ISO20201215T094000Z = { address = "172.16.104.10", port = 22, protocol = "SCP", username = "root" }
+
+        [tags]
+        raspberrypi2 = ["home"]
+        "#;
+        toml_file.write_all(file_content.as_bytes()).unwrap();
+        let hosts: UserHosts = deserialize(Box::new(toml_file.reopen().unwrap()))
+            .ok()
+            .unwrap();
+        assert_eq!(hosts.unknown.keys().collect::<Vec<&String>>(), vec!["tags"]);
+        let bookmark: &Bookmark = hosts.bookmarks.get("raspberrypi2").unwrap();
+        assert_eq!(
+            bookmark.unknown.keys().collect::<Vec<&String>>(),
+            vec!["color"]
+        );
+        assert!(hosts
+            .recents
+            .get("ISO20201215T094000Z")
+            .unwrap()
+            .unknown
+            .is_empty());
+        // Save and check unknown keys are still there
+        let toml_file: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
+        let writer: Box<dyn Write> = Box::new(std::fs::File::create(toml_file.path()).unwrap());
+        assert!(serialize(&hosts, writer).is_ok());
+        let data: String = std::fs::read_to_string(toml_file.path()).unwrap();
+        let value: toml::Value = toml::from_str(data.as_str()).unwrap();
+        assert_eq!(
+            value["bookmarks"]["raspberrypi2"]["color"].as_str(),
+            Some("red")
+        );
+        assert_eq!(value["tags"]["raspberrypi2"][0].as_str(), Some("home"));
+    }
+
     #[test]
     fn test_config_serialization_theme_serialize() {
         let mut theme: Theme = Theme::default();
@@ -481,7 +837,7 @@ mod tests {
         [bookmarks]
         raspberrypi2 = { address = "192.168.1.31", port = 22, protocol = "SFTP", username = "root", password = "mypassword" }
         msi-estrem = { address = "192.168.1.30", port = 22, protocol = "SFTP", username = "cvisintin", password = "mysecret" }
-        aws-server-prod1 = { address = "51.23.67.12", port = 21, protocol = "FTPS", username = "aws001" }
+        aws-server-prod1 = { address = "51.23.67.12", port = 21, protocol = "FTPS", username = "aws001", proxy = "http://proxy.local:3128", filters = ["+ keep.log", "- *.log"], mapping = { local = "/home/omar/project", remote = "/var/www/project" }, tls = { client_cert = "/home/omar/.certs/aws001.p12" }, trash = "/home/aws001/.trash" }
 
         [recents]
         ISO20201215T094000Z = { address = "172.16.104.10", port = 22, protocol = "SCP", username = "root" }
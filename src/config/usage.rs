@@ -0,0 +1,76 @@
+//! ## Usage
+//!
+//! `usage` is the module which provides data types and de/serializer for the bandwidth usage statistics
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Deserialize, Serialize, std::fmt::Debug, Default, PartialEq)]
+/// ## UsageStats
+///
+/// UsageStats contains the amount of bytes transferred with each host, month by month
+pub struct UsageStats {
+    #[serde(default)]
+    pub hosts: HashMap<String, BTreeMap<String, Usage>>, // Host key => month (YYYY-MM) => usage
+}
+
+#[derive(Deserialize, Serialize, std::fmt::Debug, Default, PartialEq, Clone, Copy)]
+/// ## Usage
+///
+/// Usage describes the amount of bytes transferred with a host in a period
+pub struct Usage {
+    #[serde(default)]
+    pub uploaded: u64,
+    #[serde(default)]
+    pub downloaded: u64,
+}
+
+impl Usage {
+    /// ### total
+    ///
+    /// Get the total amount of bytes transferred
+    pub fn total(&self) -> u64 {
+        self.uploaded.saturating_add(self.downloaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_config_usage_total() {
+        let usage: Usage = Usage {
+            uploaded: 1024,
+            downloaded: 2048,
+        };
+        assert_eq!(usage.total(), 3072);
+        assert_eq!(Usage::default().total(), 0);
+    }
+}
@@ -0,0 +1,74 @@
+//! ## Builder
+//!
+//! `builder` provides the file transfer client builder
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{registry, FileTransfer, FileTransferError, FileTransferProtocol};
+use crate::system::sshkey_storage::SshKeyStorage;
+
+/// ## Builder
+///
+/// Builder instantiates the file transfer client for a protocol
+pub struct Builder;
+
+impl Builder {
+    /// ### build
+    ///
+    /// Build the file transfer client for `protocol`.
+    /// `ssh_keys` is used to authenticate with SFTP and SCP; use `SshKeyStorage::empty()` to
+    /// authenticate with password only.
+    /// Fails if `protocol` is a custom protocol which has not been registered or whose backend fails
+    pub fn build(
+        protocol: FileTransferProtocol,
+        ssh_keys: SshKeyStorage,
+    ) -> Result<Box<dyn FileTransfer>, FileTransferError> {
+        registry::build(protocol, ssh_keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_filetransfer_builder() {
+        let protocols: [FileTransferProtocol; 8] = [
+            FileTransferProtocol::Sftp,
+            FileTransferProtocol::Scp,
+            FileTransferProtocol::Ftp(false),
+            FileTransferProtocol::Ftp(true),
+            FileTransferProtocol::WebDav(false),
+            FileTransferProtocol::WebDav(true),
+            FileTransferProtocol::Http(false),
+            FileTransferProtocol::Http(true),
+        ];
+        for protocol in protocols.iter() {
+            let client: Box<dyn FileTransfer> =
+                Builder::build(*protocol, SshKeyStorage::empty()).unwrap();
+            assert!(!client.is_connected());
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! ## Capabilities
+//!
+//! `capabilities` describes the operations a connected server supports, probed once at connect time
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+/// ## Capability
+///
+/// Capability describes an optional operation a server may not support
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    RenameOver,  // Rename onto an existing file, replacing it
+    SetStat,     // Change permissions and owner
+    StatVfs,     // Report file system usage
+    Symlinks,    // Read and create symbolic links
+    Exec,        // Execute shell commands
+    RangedReads, // Read files from an offset
+}
+
+impl Capability {
+    /// ### all
+    ///
+    /// Get all the capabilities
+    pub fn all() -> &'static [Capability] {
+        &[
+            Capability::RenameOver,
+            Capability::SetStat,
+            Capability::StatVfs,
+            Capability::Symlinks,
+            Capability::Exec,
+            Capability::RangedReads,
+        ]
+    }
+
+    /// ### description
+    ///
+    /// Get a short description of what the capability allows to do
+    pub fn description(&self) -> &'static str {
+        match self {
+            Capability::RenameOver => "replace files when renaming",
+            Capability::SetStat => "change permissions and owner",
+            Capability::StatVfs => "report file system usage",
+            Capability::Symlinks => "handle symbolic links",
+            Capability::Exec => "execute commands",
+            Capability::RangedReads => "download files in segments",
+        }
+    }
+}
+
+/// ## Capabilities
+///
+/// Capabilities tells which of the optional operations the connected server supports.
+/// The default value supports nothing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities {
+    rename_over: bool,
+    set_stat: bool,
+    stat_vfs: bool,
+    symlinks: bool,
+    exec: bool,
+    ranged_reads: bool,
+}
+
+impl Capabilities {
+    /// ### with
+    ///
+    /// Set whether `capability` is supported
+    pub fn with(mut self, capability: Capability, supported: bool) -> Self {
+        *self.flag_mut(capability) = supported;
+        self
+    }
+
+    /// ### supports
+    ///
+    /// Returns whether `capability` is supported
+    pub fn supports(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::RenameOver => self.rename_over,
+            Capability::SetStat => self.set_stat,
+            Capability::StatVfs => self.stat_vfs,
+            Capability::Symlinks => self.symlinks,
+            Capability::Exec => self.exec,
+            Capability::RangedReads => self.ranged_reads,
+        }
+    }
+
+    /// ### unsupported
+    ///
+    /// Get the capabilities which are not supported
+    pub fn unsupported(&self) -> Vec<Capability> {
+        Capability::all()
+            .iter()
+            .copied()
+            .filter(|x| !self.supports(*x))
+            .collect()
+    }
+
+    fn flag_mut(&mut self, capability: Capability) -> &mut bool {
+        match capability {
+            Capability::RenameOver => &mut self.rename_over,
+            Capability::SetStat => &mut self.set_stat,
+            Capability::StatVfs => &mut self.stat_vfs,
+            Capability::Symlinks => &mut self.symlinks,
+            Capability::Exec => &mut self.exec,
+            Capability::RangedReads => &mut self.ranged_reads,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_filetransfer_capabilities() {
+        let capabilities: Capabilities = Capabilities::default();
+        assert_eq!(capabilities.unsupported().len(), Capability::all().len());
+        let capabilities: Capabilities = capabilities
+            .with(Capability::Exec, true)
+            .with(Capability::SetStat, true)
+            .with(Capability::SetStat, false);
+        assert!(capabilities.supports(Capability::Exec));
+        assert!(!capabilities.supports(Capability::SetStat));
+        assert_eq!(
+            capabilities.unsupported(),
+            vec![
+                Capability::RenameOver,
+                Capability::SetStat,
+                Capability::StatVfs,
+                Capability::Symlinks,
+                Capability::RangedReads
+            ]
+        );
+        assert_eq!(Capability::Exec.description(), "execute commands");
+    }
+}
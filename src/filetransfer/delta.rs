@@ -0,0 +1,892 @@
+//! ## Delta
+//!
+//! `delta` provides the rsync-style delta upload, which updates a remote file sending only the data
+//! it doesn't already have.
+//!
+//! The remote file is split into blocks, whose signature (a weak rolling checksum and a strong md5
+//! checksum for each block) is computed on the remote host. The local file is then scanned with a
+//! window as large as a block, moved forward by one byte at a time: wherever the window matches a
+//! remote block, the block is reused, wherever it doesn't, local data is sent.
+//! The new file is then rebuilt on the remote host from the blocks of the current one and the data
+//! sent, and replaces the current one only once complete.
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use md5::{Digest, Md5};
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Smallest block size used to compute signatures
+pub const MIN_BLOCK_SIZE: u64 = 65536;
+/// Largest block size used to compute signatures
+pub const MAX_BLOCK_SIZE: u64 = 8388608;
+/// Amount of blocks a file is split into, unless the block size gets out of bounds
+const BLOCKS_PER_FILE: u64 = 1024;
+/// Largest file whose weak checksums are calculated with `od` and `awk`, which are slow, when `python3` is missing
+pub const AWK_MAX_SIZE: u64 = 67108864;
+/// Modulus of the sums of the rolling checksum
+const ROLLING_MODULUS: u32 = 65536;
+
+/// Block checksum
+pub type Checksum = [u8; 16];
+
+/// ## Signature
+///
+/// Signature describes a file through the weak and the strong checksum of each of its blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    size: u64,
+    block_size: u64,
+    blocks: Vec<Checksum>,
+    weak: Vec<u32>,
+}
+
+impl Signature {
+    /// ### new
+    ///
+    /// Instantiates a new Signature for a file of `size` bytes, given the strong and the weak checksum of each block
+    pub fn new(size: u64, block_size: u64, blocks: Vec<Checksum>, weak: Vec<u32>) -> Self {
+        Self {
+            size,
+            block_size,
+            blocks,
+            weak,
+        }
+    }
+
+    /// ### parse
+    ///
+    /// Parse the output of the command returned by `signature_command`:
+    /// the file size on the first line, then the strong checksum of each block, one per line,
+    /// then the two sums of the weak checksum of each block, one block per line
+    pub fn parse(output: &str, block_size: u64) -> Result<Self, String> {
+        let mut lines = output.lines().map(|x| x.trim()).filter(|x| !x.is_empty());
+        let size: u64 = match lines.next().map(|x| x.parse::<u64>()) {
+            Some(Ok(size)) => size,
+            _ => return Err(String::from("Missing file size")),
+        };
+        let lines: Vec<&str> = lines.collect();
+        let count: usize = blocks_count(size, block_size) as usize;
+        if lines.len() != count * 2 {
+            return Err(format!(
+                "Expected {} blocks, got {} lines",
+                count,
+                lines.len()
+            ));
+        }
+        let blocks: Vec<Checksum> = lines[..count]
+            .iter()
+            .map(|x| parse_checksum(x.split_whitespace().next().unwrap_or("")))
+            .collect::<Result<Vec<Checksum>, String>>()?;
+        let weak: Vec<u32> = lines[count..]
+            .iter()
+            .map(|x| parse_weak_checksum(x))
+            .collect::<Result<Vec<u32>, String>>()?;
+        Ok(Self::new(size, block_size, blocks, weak))
+    }
+
+    /// ### size
+    ///
+    /// Get the size of the file
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// ### block_size
+    ///
+    /// Get the size of each block
+    pub fn block_size(&self) -> u64 {
+        self.block_size
+    }
+
+    /// ### block
+    ///
+    /// Get the strong checksum of the block at `index`
+    pub fn block(&self, index: usize) -> Option<&Checksum> {
+        self.blocks.get(index)
+    }
+
+    /// ### weak
+    ///
+    /// Get the weak checksum of the block at `index`
+    pub fn weak(&self, index: usize) -> Option<u32> {
+        self.weak.get(index).copied()
+    }
+}
+
+/// ## Op
+///
+/// Op is a step to build the new version of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// Copy `count` blocks of the current remote file, starting from the block at `index`
+    Copy { index: u64, count: u64 },
+    /// Send `len` bytes of the local file, starting from `offset`
+    Literal { offset: u64, len: u64 },
+}
+
+/// ## Rolling
+///
+/// Rolling is the weak checksum used by rsync, which can be moved forward by one byte in constant time.
+/// `a` is the sum of the bytes, `b` the sum of the partial sums of `a`, both modulo 2^16
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rolling {
+    a: u32,
+    b: u32,
+    len: u32,
+}
+
+impl Rolling {
+    /// ### new
+    ///
+    /// Calculate the checksum of `data`
+    fn new(data: &[u8]) -> Self {
+        let (mut a, mut b): (u32, u32) = (0, 0);
+        for byte in data.iter() {
+            a = (a + *byte as u32) % ROLLING_MODULUS;
+            b = (b + a) % ROLLING_MODULUS;
+        }
+        Self {
+            a,
+            b,
+            len: data.len() as u32,
+        }
+    }
+
+    /// ### roll
+    ///
+    /// Move the window forward by one byte, removing `out` from its start and adding `into` to its end
+    fn roll(self, out: u8, into: u8) -> Self {
+        let a: u32 = (self.a + ROLLING_MODULUS - out as u32 + into as u32) % ROLLING_MODULUS;
+        let removed: u32 = (self.len % ROLLING_MODULUS) * out as u32 % ROLLING_MODULUS;
+        let b: u32 = (self.b + ROLLING_MODULUS - removed + a) % ROLLING_MODULUS;
+        Self {
+            a,
+            b,
+            len: self.len,
+        }
+    }
+
+    /// ### value
+    ///
+    /// Get the checksum value
+    fn value(&self) -> u32 {
+        self.a | (self.b << 16)
+    }
+}
+
+/// ## Matcher
+///
+/// Matcher scans a local file looking for the blocks described by the signature of the remote one,
+/// and returns the steps to build the local file from the remote blocks and the local data
+pub struct Matcher<'a, R: Read> {
+    signature: &'a Signature,
+    lookup: HashMap<u32, Vec<usize>>,
+    reader: R,
+    eof: bool,
+    buffer: Vec<u8>,
+    base: u64,
+    pos: usize,
+    literal: u64,
+    rolling: Option<Rolling>,
+    pending: Option<Op>,
+    done: bool,
+}
+
+impl<'a, R: Read> Matcher<'a, R> {
+    /// ### new
+    ///
+    /// Instantiates a new Matcher, which reads the local file from `reader`.
+    /// Only the blocks as large as the block size are looked for: the last block of the remote file is sent again if shorter
+    pub fn new(signature: &'a Signature, reader: R) -> Self {
+        let mut lookup: HashMap<u32, Vec<usize>> = HashMap::new();
+        let full_blocks: usize = (signature.size() / signature.block_size()) as usize;
+        for index in 0..full_blocks {
+            if let Some(weak) = signature.weak(index) {
+                lookup.entry(weak).or_default().push(index);
+            }
+        }
+        Self {
+            signature,
+            lookup,
+            reader,
+            eof: false,
+            buffer: Vec::new(),
+            base: 0,
+            pos: 0,
+            literal: 0,
+            rolling: None,
+            pending: None,
+            done: false,
+        }
+    }
+
+    /// ### position
+    ///
+    /// Get the offset of the local file scanned so far
+    pub fn position(&self) -> u64 {
+        self.base + self.pos as u64
+    }
+
+    /// ### next_op
+    ///
+    /// Get the next step to build the local file; returns `None` once the whole file has been scanned.
+    /// Literal data is returned in chunks as large as a block at most, even if the following chunk is literal too
+    pub fn next_op(&mut self) -> io::Result<Option<Op>> {
+        if let Some(op) = self.pending.take() {
+            return Ok(Some(op));
+        }
+        if self.done {
+            return Ok(None);
+        }
+        let block_size: usize = self.signature.block_size() as usize;
+        loop {
+            // Keep the window and the byte following it in the buffer
+            self.fill(block_size + 1)?;
+            if self.buffer.len() - self.pos < block_size {
+                break;
+            }
+            let offset: u64 = self.position();
+            let window: &[u8] = &self.buffer[self.pos..self.pos + block_size];
+            let rolling: Rolling = match self.rolling {
+                Some(rolling) => rolling,
+                None => Rolling::new(window),
+            };
+            if let Some(index) = self.find(rolling.value(), window, offset) {
+                self.pos += block_size;
+                self.rolling = None;
+                let copy: Op = Op::Copy {
+                    index: index as u64,
+                    count: 1,
+                };
+                let literal: Option<Op> = self.take_literal(offset);
+                self.literal = offset + block_size as u64;
+                return Ok(match literal {
+                    Some(literal) => {
+                        self.pending = Some(copy);
+                        Some(literal)
+                    }
+                    None => Some(copy),
+                });
+            }
+            // Move the window forward
+            self.rolling = match self.buffer.get(self.pos + block_size) {
+                Some(into) => Some(rolling.roll(self.buffer[self.pos], *into)),
+                None => None,
+            };
+            self.pos += 1;
+            // Return literal data once as large as a block
+            if self.position() - self.literal >= block_size as u64 {
+                let literal: Option<Op> = self.take_literal(self.position());
+                self.literal = self.position();
+                return Ok(literal);
+            }
+        }
+        // Send the remaining data
+        self.done = true;
+        self.pos = self.buffer.len();
+        Ok(self.take_literal(self.position()))
+    }
+
+    /// ### fill
+    ///
+    /// Make sure the buffer holds at least `len` bytes from the current position, unless the file is over
+    fn fill(&mut self, len: usize) -> io::Result<()> {
+        if self.eof || self.buffer.len() - self.pos >= len {
+            return Ok(());
+        }
+        // Discard the data before the window
+        self.buffer.drain(..self.pos);
+        self.base += self.pos as u64;
+        self.pos = 0;
+        let target: usize = len * 2;
+        let mut filled: usize = self.buffer.len();
+        self.buffer.resize(target, 0);
+        while filled < target {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(0) => {
+                    self.eof = true;
+                    break;
+                }
+                Ok(bytes) => filled += bytes,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    self.buffer.truncate(filled);
+                    return Err(err);
+                }
+            }
+        }
+        self.buffer.truncate(filled);
+        Ok(())
+    }
+
+    /// ### find
+    ///
+    /// Find the remote block matching `window`, preferring the one at the same `offset`
+    fn find(&self, weak: u32, window: &[u8], offset: u64) -> Option<usize> {
+        let candidates: &Vec<usize> = self.lookup.get(&weak)?;
+        let strong: Checksum = checksum(window);
+        let mut matching = candidates
+            .iter()
+            .copied()
+            .filter(|x| self.signature.block(*x) == Some(&strong));
+        let first: usize = matching.next()?;
+        let aligned: u64 = offset / self.signature.block_size();
+        if offset % self.signature.block_size() == 0 && first as u64 != aligned {
+            if let Some(index) = matching.find(|x| *x as u64 == aligned) {
+                return Some(index);
+            }
+        }
+        Some(first)
+    }
+
+    /// ### take_literal
+    ///
+    /// Get the literal data pending up to `end`, if any
+    fn take_literal(&self, end: u64) -> Option<Op> {
+        match end > self.literal {
+            true => Some(Op::Literal {
+                offset: self.literal,
+                len: end - self.literal,
+            }),
+            false => None,
+        }
+    }
+}
+
+/// ### push
+///
+/// Push `op` to `ops`, merging it with the last one if they're contiguous
+pub fn push(ops: &mut Vec<Op>, op: Op) {
+    match (ops.last_mut(), op) {
+        (
+            Some(Op::Copy { index, count }),
+            Op::Copy {
+                index: next,
+                count: more,
+            },
+        ) if *index + *count == next => *count += more,
+        (
+            Some(Op::Literal { offset, len }),
+            Op::Literal {
+                offset: next,
+                len: more,
+            },
+        ) if *offset + *len == next => *len += more,
+        _ => ops.push(op),
+    }
+}
+
+/// ### literal_size
+///
+/// Get the amount of local data to send
+pub fn literal_size(ops: &[Op]) -> u64 {
+    ops.iter()
+        .map(|x| match x {
+            Op::Literal { len, .. } => *len,
+            Op::Copy { .. } => 0,
+        })
+        .sum()
+}
+
+/// ### block_size
+///
+/// Get the block size to use for a file of `size` bytes
+pub fn block_size(size: u64) -> u64 {
+    ((size + BLOCKS_PER_FILE - 1) / BLOCKS_PER_FILE)
+        .next_power_of_two()
+        .clamp(MIN_BLOCK_SIZE, MAX_BLOCK_SIZE)
+}
+
+/// ### blocks_count
+///
+/// Get the amount of blocks a file of `size` bytes is split into
+pub fn blocks_count(size: u64, block_size: u64) -> u64 {
+    (size + block_size - 1) / block_size
+}
+
+/// ### checksum
+///
+/// Calculate the strong checksum of a block
+pub fn checksum(data: &[u8]) -> Checksum {
+    let mut checksum: Checksum = [0; 16];
+    checksum.copy_from_slice(Md5::digest(data).as_slice());
+    checksum
+}
+
+/// ### weak_checksum
+///
+/// Calculate the weak checksum of a block
+pub fn weak_checksum(data: &[u8]) -> u32 {
+    Rolling::new(data).value()
+}
+
+/// ### signature_command
+///
+/// Get the shell command which prints the signature of `path` on a unix host.
+/// Checksums are calculated in a single pass by `python3`, if available; otherwise strong checksums are
+/// calculated with `md5sum` (or `md5`) and weak ones with `od` and `awk`, which is far slower than a plain
+/// upload: in this case the command fails if the file is larger than `AWK_MAX_SIZE`.
+/// The command fails if `path` is not a file, or is a symbolic link
+pub fn signature_command(path: &Path, block_size: u64) -> String {
+    format!(
+        "f={}; [ -f \"$f\" ] && [ ! -L \"$f\" ] || exit 1; if command -v python3 >/dev/null 2>&1; then python3 -c '{}' \"$f\" {}; exit $?; fi; s=$(wc -c < \"$f\" | tr -d ' '); [ \"$s\" -le {} ] || exit 1; if command -v md5sum >/dev/null 2>&1; then h=md5sum; else h=\"md5 -q\"; fi; echo \"$s\"; o=0; while [ \"$o\" -lt \"$s\" ]; do dd bs={} count=1 2>/dev/null | $h; o=$((o + {})); done < \"$f\"; od -An -v -tu1 < \"$f\" | awk '{{ for (i = 1; i <= NF; i++) {{ a = (a + $i) % {m}; b = (b + a) % {m}; if (++n == {}) {{ print a, b; a = 0; b = 0; n = 0 }} }} }} END {{ if (n > 0) print a, b }}'",
+        quote(path),
+        signature_script(),
+        block_size,
+        AWK_MAX_SIZE,
+        block_size,
+        block_size,
+        block_size,
+        m = ROLLING_MODULUS
+    )
+}
+
+/// ### signature_script
+///
+/// Get the python script which prints the signature of the file passed as first argument, split into blocks
+/// as large as the second argument. The script mustn't contain single quotes, since it's single-quoted in the shell
+fn signature_script() -> String {
+    format!(
+        "import sys, os, hashlib, itertools\nf = open(sys.argv[1], \"rb\")\nn = int(sys.argv[2])\nw = []\nprint(os.fstat(f.fileno()).st_size)\nfor b in iter(lambda: f.read(n), b\"\"):\n    print(hashlib.md5(b).hexdigest())\n    w.append(\"%d %d\" % (sum(b) % {m}, sum(itertools.accumulate(b)) % {m}))\nprint(\"\\n\".join(w))\n",
+        m = ROLLING_MODULUS
+    )
+}
+
+/// ### rebuild_command
+///
+/// Get the shell command which rebuilds `path` following `ops`, taking the literal data, in the same order, from `literal`.
+/// The file is written to a temporary file first, which is given `pex` as permissions, if any, and then moved over `path`
+/// once its size is checked, so that `path` is replaced at once and left untouched if anything fails;
+/// `literal` and the temporary file are removed.
+/// The errors of the command are written to its output, which ends with its exit code (see `rebuild_result`)
+pub fn rebuild_command(
+    path: &Path,
+    literal: &Path,
+    block_size: u64,
+    ops: &[Op],
+    pex: Option<(u8, u8, u8)>,
+) -> String {
+    let mut literal_offset: u64 = 0;
+    let steps: Vec<String> = ops
+        .iter()
+        .map(|op| match op {
+            Op::Copy { index, count } => format!(
+                "dd if=\"$f\" bs={} skip={} count={} 2>/dev/null",
+                block_size, index, count
+            ),
+            Op::Literal { len, .. } => {
+                let step: String =
+                    format!("tail -c +{} \"$l\" | head -c {}", literal_offset + 1, len);
+                literal_offset += len;
+                step
+            }
+        })
+        .collect();
+    let steps: String = match steps.is_empty() {
+        true => String::from(":"),
+        false => steps.join(" && "),
+    };
+    let chmod: String = match pex {
+        Some((u, g, o)) => format!("chmod {}{}{} \"$t\" && ", u, g, o),
+        None => String::new(),
+    };
+    // A pipeline only reports the status of its last command: make sure the new file is complete
+    let size: u64 = ops
+        .iter()
+        .map(|op| match op {
+            Op::Copy { count, .. } => count * block_size,
+            Op::Literal { len, .. } => *len,
+        })
+        .sum();
+    format!(
+        "exec 2>&1; f={}; l={}; t=\"$l.new\"; {{ {}; }} > \"$t\" && [ \"$(wc -c < \"$t\" | tr -d ' ')\" -eq {} ] && {}mv -f \"$t\" \"$f\"; r=$?; rm -f \"$l\" \"$t\"; echo $r",
+        quote(path),
+        quote(literal),
+        steps,
+        size,
+        chmod
+    )
+}
+
+/// ### rebuild_result
+///
+/// Get the result of the command made by `rebuild_command` from its `output`:
+/// the error is made of the lines the command wrote before its exit code, if any
+pub fn rebuild_result(output: &str) -> Result<(), String> {
+    let mut lines: Vec<&str> = output.lines().filter(|x| !x.trim().is_empty()).collect();
+    match lines.pop().map(|x| x.trim()) {
+        Some("0") => Ok(()),
+        Some(code) if lines.is_empty() => Err(format!("rebuild failed with exit code {}", code)),
+        Some(_) => Err(lines.join("\n")),
+        None => Err(String::from("rebuild command returned no output")),
+    }
+}
+
+/// ### quote
+///
+/// Quote `path` as a single shell word: the path is single-quoted, so nothing in it is expanded
+fn quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+/// ### parse_checksum
+///
+/// Parse an hex encoded checksum
+fn parse_checksum(s: &str) -> Result<Checksum, String> {
+    if s.len() != 32 || !s.is_ascii() {
+        return Err(format!("Invalid checksum \"{}\"", s));
+    }
+    let mut checksum: Checksum = [0; 16];
+    for (i, byte) in checksum.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+            .map_err(|_| format!("Invalid checksum \"{}\"", s))?;
+    }
+    Ok(checksum)
+}
+
+/// ### parse_weak_checksum
+///
+/// Parse the two sums of a weak checksum, separated by a whitespace
+fn parse_weak_checksum(s: &str) -> Result<u32, String> {
+    let mut sums = s
+        .split_whitespace()
+        .map(|x| x.parse::<u32>().ok().filter(|x| *x < ROLLING_MODULUS));
+    match (sums.next(), sums.next(), sums.next()) {
+        (Some(Some(a)), Some(Some(b)), None) => Ok(a | (b << 16)),
+        _ => Err(format!("Invalid weak checksum \"{}\"", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_filetransfer_delta_block_size() {
+        assert_eq!(block_size(0), MIN_BLOCK_SIZE);
+        assert_eq!(block_size(1024), MIN_BLOCK_SIZE);
+        assert_eq!(block_size(1073741824), 1048576);
+        assert_eq!(block_size(1073741825), 2097152);
+        assert_eq!(block_size(u64::MAX / 2), MAX_BLOCK_SIZE);
+        assert_eq!(blocks_count(0, 65536), 0);
+        assert_eq!(blocks_count(65536, 65536), 1);
+        assert_eq!(blocks_count(65537, 65536), 2);
+    }
+
+    #[test]
+    fn test_filetransfer_delta_signature() {
+        let output: &str =
+            "10\nd41d8cd98f00b204e9800998ecf8427e  -\n0cc175b9c0f1b6a831c399e269772661\n0 0\n97 97\n";
+        let signature: Signature = Signature::parse(output, 8).ok().unwrap();
+        assert_eq!(signature.size(), 10);
+        assert_eq!(signature.block_size(), 8);
+        assert_eq!(signature.block(0).unwrap(), &checksum(b""));
+        assert_eq!(signature.block(1).unwrap(), &checksum(b"a"));
+        assert!(signature.block(2).is_none());
+        assert_eq!(signature.weak(0).unwrap(), weak_checksum(b""));
+        assert_eq!(signature.weak(1).unwrap(), weak_checksum(b"a"));
+        assert!(signature.weak(2).is_none());
+    }
+
+    #[test]
+    fn test_filetransfer_delta_signature_bad() {
+        assert!(Signature::parse("", 8).is_err());
+        assert!(Signature::parse("abc", 8).is_err());
+        // Missing block
+        assert!(Signature::parse("10\nd41d8cd98f00b204e9800998ecf8427e\n0 0\n", 8).is_err());
+        // Bad checksum
+        assert!(Signature::parse("4\nmd5: not found\n0 0\n", 8).is_err());
+        assert!(Signature::parse("4\nzz1d8cd98f00b204e9800998ecf8427e\n0 0\n", 8).is_err());
+        // Bad weak checksum
+        assert!(Signature::parse("4\nd41d8cd98f00b204e9800998ecf8427e\n0\n", 8).is_err());
+        assert!(Signature::parse("4\nd41d8cd98f00b204e9800998ecf8427e\n65536 0\n", 8).is_err());
+        // Empty file
+        assert_eq!(Signature::parse("0\n", 8).ok().unwrap().size(), 0);
+    }
+
+    #[test]
+    fn test_filetransfer_delta_rolling() {
+        let data: &[u8] = b"the quick brown fox jumps over the lazy dog";
+        let mut rolling: Rolling = Rolling::new(&data[0..8]);
+        for start in 1..data.len() - 8 {
+            rolling = rolling.roll(data[start - 1], data[start + 7]);
+            assert_eq!(rolling.value(), weak_checksum(&data[start..start + 8]));
+        }
+        // Sums wrap around
+        let data: Vec<u8> = vec![0xff; 1024];
+        let rolling: Rolling = Rolling::new(&data[0..512]).roll(0xff, 0xff);
+        assert_eq!(rolling.value(), weak_checksum(&data[1..513]));
+    }
+
+    #[test]
+    fn test_filetransfer_delta_matcher() {
+        let remote: &[u8] = b"aaaabbbbccccdd";
+        let signature: Signature = make_signature(remote, 4);
+        // Same file: the last block is shorter, so it's sent again
+        assert_eq!(
+            diff(&signature, remote),
+            vec![
+                Op::Copy { index: 0, count: 3 },
+                Op::Literal { offset: 12, len: 2 }
+            ]
+        );
+        // Changed in place and appended to
+        let ops: Vec<Op> = diff(&signature, b"aaaaxxxxccccddee");
+        assert_eq!(
+            ops,
+            vec![
+                Op::Copy { index: 0, count: 1 },
+                Op::Literal { offset: 4, len: 4 },
+                Op::Copy { index: 2, count: 1 },
+                Op::Literal { offset: 12, len: 4 }
+            ]
+        );
+        assert_eq!(literal_size(&ops), 8);
+        // Inserted data moves the following blocks
+        let ops: Vec<Op> = diff(&signature, b"aaaa123bbbbcccc");
+        assert_eq!(
+            ops,
+            vec![
+                Op::Copy { index: 0, count: 1 },
+                Op::Literal { offset: 4, len: 3 },
+                Op::Copy { index: 1, count: 2 }
+            ]
+        );
+        assert_eq!(literal_size(&ops), 3);
+        // Nothing in common
+        let ops: Vec<Op> = diff(&signature, b"0123456789");
+        assert_eq!(ops, vec![Op::Literal { offset: 0, len: 10 }]);
+        // Empty
+        assert!(diff(&signature, b"").is_empty());
+        assert!(diff(&make_signature(b"", 4), b"abc").len() == 1);
+    }
+
+    #[test]
+    fn test_filetransfer_delta_rebuild_command() {
+        let ops: Vec<Op> = vec![
+            Op::Copy { index: 0, count: 1 },
+            Op::Literal { offset: 4, len: 3 },
+            Op::Copy { index: 1, count: 2 },
+            Op::Literal { offset: 15, len: 2 },
+        ];
+        assert_eq!(
+            rebuild_command(
+                Path::new("/tmp/omar.txt"),
+                Path::new("/tmp/.literal"),
+                4,
+                &ops,
+                None
+            ),
+            "exec 2>&1; f='/tmp/omar.txt'; l='/tmp/.literal'; t=\"$l.new\"; { dd if=\"$f\" bs=4 skip=0 count=1 2>/dev/null && tail -c +1 \"$l\" | head -c 3 && dd if=\"$f\" bs=4 skip=1 count=2 2>/dev/null && tail -c +4 \"$l\" | head -c 2; } > \"$t\" && [ \"$(wc -c < \"$t\" | tr -d ' ')\" -eq 17 ] && mv -f \"$t\" \"$f\"; r=$?; rm -f \"$l\" \"$t\"; echo $r"
+        );
+        assert_eq!(
+            rebuild_command(
+                Path::new("/tmp/omar.txt"),
+                Path::new("/tmp/.literal"),
+                4,
+                &[],
+                Some((6, 4, 0))
+            ),
+            "exec 2>&1; f='/tmp/omar.txt'; l='/tmp/.literal'; t=\"$l.new\"; { :; } > \"$t\" && [ \"$(wc -c < \"$t\" | tr -d ' ')\" -eq 0 ] && chmod 640 \"$t\" && mv -f \"$t\" \"$f\"; r=$?; rm -f \"$l\" \"$t\"; echo $r"
+        );
+    }
+
+    #[test]
+    fn test_filetransfer_delta_rebuild_result() {
+        assert!(rebuild_result("0\n").is_ok());
+        assert_eq!(
+            rebuild_result("mv: cannot move '/tmp/.literal.new': Permission denied\n1\n")
+                .err()
+                .unwrap(),
+            "mv: cannot move '/tmp/.literal.new': Permission denied"
+        );
+        assert_eq!(
+            rebuild_result("1\n").err().unwrap(),
+            "rebuild failed with exit code 1"
+        );
+        assert!(rebuild_result("").is_err());
+    }
+
+    #[test]
+    fn test_filetransfer_delta_signature_command() {
+        let command: String = signature_command(Path::new("/tmp/omar.txt"), 65536);
+        assert!(command.starts_with("f='/tmp/omar.txt'; [ -f \"$f\" ] && [ ! -L \"$f\" ] || exit 1; if command -v python3 >/dev/null 2>&1; then python3 -c 'import sys"));
+        // The script is single-quoted
+        assert!(!signature_script().contains('\''));
+        // The fallback is limited to small files
+        assert!(command.contains("' \"$f\" 65536; exit $?; fi; s=$(wc -c < \"$f\" | tr -d ' '); [ \"$s\" -le 67108864 ] || exit 1; "));
+        assert!(command.ends_with("do dd bs=65536 count=1 2>/dev/null | $h; o=$((o + 65536)); done < \"$f\"; od -An -v -tu1 < \"$f\" | awk '{ for (i = 1; i <= NF; i++) { a = (a + $i) % 65536; b = (b + a) % 65536; if (++n == 65536) { print a, b; a = 0; b = 0; n = 0 } } } END { if (n > 0) print a, b }'"));
+    }
+
+    #[test]
+    fn test_filetransfer_delta_quote() {
+        assert_eq!(quote(Path::new("/tmp/omar.txt")), "'/tmp/omar.txt'");
+        assert_eq!(
+            quote(Path::new("/tmp/it's \"$(id)\" `id`.txt")),
+            "'/tmp/it'\\''s \"$(id)\" `id`.txt'"
+        );
+        assert!(rebuild_command(
+            Path::new("/tmp/a'; rm -rf ~; '"),
+            Path::new("/tmp/.literal"),
+            4,
+            &[],
+            None
+        )
+        .starts_with("exec 2>&1; f='/tmp/a'\\''; rm -rf ~; '\\'''; l='/tmp/.literal'; "));
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_filetransfer_delta_commands_hostile_name() {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+        use std::path::PathBuf;
+        use std::process::{Command, Stdio};
+        let tmpdir: tempfile::TempDir = tempfile::TempDir::new().unwrap();
+        let canary: PathBuf = tmpdir.path().join("pwned");
+        // The canary is created in the working directory of the shell
+        let path: PathBuf = tmpdir
+            .path()
+            .join("it's \"$(touch pwned)\" `touch pwned`; touch pwned; '");
+        let sh = |cmd: String, input: &[u8]| -> String {
+            let mut child = Command::new("sh")
+                .arg("-c")
+                .arg(cmd)
+                .current_dir(tmpdir.path())
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            child.stdin.take().unwrap().write_all(input).unwrap();
+            String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap()
+        };
+        // Signature
+        let data: Vec<u8> = (0..1000_u32).map(|x| (x * 7 % 251) as u8).collect();
+        std::fs::write(path.as_path(), data.as_slice()).unwrap();
+        let output: String = sh(signature_command(path.as_path(), 64), b"");
+        let signature: Signature = Signature::parse(output.as_str(), 64).ok().unwrap();
+        assert_eq!(signature, make_signature(data.as_slice(), 64));
+        // Rebuild
+        std::fs::write(path.as_path(), b"0123456789").unwrap();
+        let literal: PathBuf = tmpdir.path().join("it's `touch pwned`.literal");
+        std::fs::write(literal.as_path(), b"xyz").unwrap();
+        let ops: Vec<Op> = vec![
+            Op::Literal { offset: 0, len: 1 },
+            Op::Copy { index: 0, count: 1 },
+            Op::Literal { offset: 5, len: 2 },
+        ];
+        assert_eq!(
+            sh(
+                rebuild_command(path.as_path(), literal.as_path(), 4, &ops, Some((6, 0, 0))),
+                b""
+            )
+            .trim(),
+            "0"
+        );
+        assert_eq!(std::fs::read(path.as_path()).unwrap(), b"x0123yz");
+        assert_eq!(
+            std::fs::metadata(path.as_path())
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777,
+            0o600
+        );
+        assert!(!literal.exists());
+        // The file is left untouched if the literal data is missing; the error is reported
+        let err: String = rebuild_result(
+            sh(
+                rebuild_command(path.as_path(), literal.as_path(), 4, &ops, None),
+                b"",
+            )
+            .as_str(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("literal"));
+        assert_eq!(std::fs::read(path.as_path()).unwrap(), b"x0123yz");
+        assert_eq!(std::fs::read_dir(tmpdir.path()).unwrap().count(), 1);
+        // Nothing in the name has been executed
+        assert!(!canary.exists());
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_filetransfer_delta_signature_command_fallback() {
+        use std::os::unix::fs::symlink;
+        use std::path::PathBuf;
+        use std::process::Command;
+        let tmpdir: tempfile::TempDir = tempfile::TempDir::new().unwrap();
+        // PATH has only the tools used by the fallback
+        let bin: PathBuf = tmpdir.path().join("bin");
+        std::fs::create_dir(bin.as_path()).unwrap();
+        for tool in ["wc", "tr", "dd", "md5sum", "od", "awk"].iter() {
+            if let Some(found) = ["/bin", "/usr/bin"]
+                .iter()
+                .map(|x| Path::new(x).join(tool))
+                .find(|x| x.exists())
+            {
+                symlink(found, bin.join(tool)).unwrap();
+            }
+        }
+        let path: PathBuf = tmpdir.path().join("omar.bin");
+        let data: Vec<u8> = (0..1000_u32).map(|x| (x * 13 % 256) as u8).collect();
+        std::fs::write(path.as_path(), data.as_slice()).unwrap();
+        let output = Command::new("/bin/sh")
+            .arg("-c")
+            .arg(signature_command(path.as_path(), 64))
+            .env("PATH", bin.as_path())
+            .output()
+            .unwrap();
+        let output: String = String::from_utf8(output.stdout).unwrap();
+        let signature: Signature = Signature::parse(output.as_str(), 64).ok().unwrap();
+        assert_eq!(signature, make_signature(data.as_slice(), 64));
+    }
+
+    fn make_signature(data: &[u8], block_size: u64) -> Signature {
+        Signature::new(
+            data.len() as u64,
+            block_size,
+            data.chunks(block_size as usize).map(checksum).collect(),
+            data.chunks(block_size as usize)
+                .map(weak_checksum)
+                .collect(),
+        )
+    }
+
+    fn diff(signature: &Signature, local: &[u8]) -> Vec<Op> {
+        let mut matcher: Matcher<&[u8]> = Matcher::new(signature, local);
+        let mut ops: Vec<Op> = Vec::new();
+        while let Some(op) = matcher.next_op().unwrap() {
+            push(&mut ops, op);
+        }
+        assert_eq!(matcher.position(), local.len() as u64);
+        ops
+    }
+}
@@ -0,0 +1,241 @@
+//! ## Fetch
+//!
+//! `fetch` is the module which downloads one-off HTTP(S) resources, outside of any file transfer session
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use super::webdav_transfer::WebDavFileTransfer;
+use super::{FileTransferError, FileTransferErrorType};
+
+// Ext
+use percent_encoding::percent_decode_str;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+use ureq::AgentBuilder;
+
+/// Name used when the resource name can't be told from the URL (e.g. `https://example.com/`)
+const DEFAULT_FILE_NAME: &str = "index.html";
+
+/// ## Fetch
+///
+/// Fetch is an HTTP(S) resource being downloaded
+pub struct Fetch {
+    pub name: String,        // Name the resource should be saved as
+    pub size: Option<usize>, // Content length, if reported by the server
+    reader: Box<dyn Read + Send>,
+}
+
+impl Fetch {
+    /// ### get
+    ///
+    /// Send a GET request for `url` and return the resource once the server has responded
+    pub fn get(url: &str) -> Result<Self, FileTransferError> {
+        let url: &str = url.trim();
+        if !Self::is_http_url(url) {
+            return Err(FileTransferError::new_ex(
+                FileTransferErrorType::BadAddress,
+                format!("\"{}\" is not an HTTP(S) URL", url),
+            ));
+        }
+        let agent = AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(30))
+            .build();
+        debug!("GET {}", url);
+        let response = match agent.get(url).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                error!("GET {} failed: {}", url, code);
+                return Err(WebDavFileTransfer::status_error(
+                    code,
+                    response.status_text(),
+                ));
+            }
+            Err(ureq::Error::Transport(err)) => {
+                error!("GET {} failed: {}", url, err);
+                return Err(FileTransferError::new_ex(
+                    FileTransferErrorType::ConnectionError,
+                    err.to_string(),
+                ));
+            }
+        };
+        // Redirects are followed, so take the name from the final URL
+        let name: String =
+            Self::file_name(response.get_url(), response.header("Content-Disposition"));
+        let size: Option<usize> = response
+            .header("Content-Length")
+            .and_then(|x| x.trim().parse::<usize>().ok());
+        info!("Fetching \"{}\" from {} ({:?} bytes)", name, url, size);
+        Ok(Self {
+            name,
+            size,
+            reader: Box::new(response.into_reader()),
+        })
+    }
+
+    /// ### is_http_url
+    ///
+    /// Returns whether `url` is an HTTP or HTTPS URL
+    pub fn is_http_url(url: &str) -> bool {
+        let lower: String = url.to_ascii_lowercase();
+        ["http://", "https://"]
+            .iter()
+            .any(|scheme| lower.starts_with(scheme) && lower.len() > scheme.len())
+    }
+
+    /// ### file_name
+    ///
+    /// Get the name to save the resource as; the name from `Content-Disposition` is preferred,
+    /// otherwise the last segment of the URL path is used
+    fn file_name(url: &str, content_disposition: Option<&str>) -> String {
+        content_disposition
+            .and_then(Self::disposition_file_name)
+            .or_else(|| Self::url_file_name(url))
+            .and_then(|x| Self::sanitize(x.as_str()))
+            .unwrap_or_else(|| String::from(DEFAULT_FILE_NAME))
+    }
+
+    /// ### disposition_file_name
+    ///
+    /// Get the file name from a `Content-Disposition` header; `filename*` is preferred over `filename`
+    fn disposition_file_name(header: &str) -> Option<String> {
+        let params: Vec<(String, &str)> = header
+            .split(';')
+            .filter_map(|x| {
+                let mut kv = x.splitn(2, '=');
+                let key: String = kv.next()?.trim().to_ascii_lowercase();
+                kv.next().map(|value| (key, value.trim()))
+            })
+            .collect();
+        let extended: Option<String> = params
+            .iter()
+            .find(|(key, _)| key == "filename*")
+            .and_then(|(_, value)| value.rsplit('\'').next())
+            .map(|x| percent_decode_str(x).decode_utf8_lossy().to_string());
+        extended.or_else(|| {
+            params
+                .iter()
+                .find(|(key, _)| key == "filename")
+                .map(|(_, value)| value.trim_matches('"').to_string())
+        })
+    }
+
+    /// ### url_file_name
+    ///
+    /// Get the last segment of the URL path
+    fn url_file_name(url: &str) -> Option<String> {
+        // Strip scheme, query and fragment
+        let url: &str = url.split("://").nth(1).unwrap_or(url);
+        let url: &str = url.split(['?', '#']).next().unwrap_or("");
+        // Skip host
+        let (_, path): (&str, &str) = url.split_once('/')?;
+        path.rsplit('/')
+            .next()
+            .map(|x| percent_decode_str(x).decode_utf8_lossy().to_string())
+    }
+
+    /// ### sanitize
+    ///
+    /// Make sure the name can't point outside of the destination directory
+    fn sanitize(name: &str) -> Option<String> {
+        let name: &str = name.rsplit(['/', '\\']).next()?.trim();
+        match name {
+            "" | "." | ".." => None,
+            name => Path::new(name)
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string()),
+        }
+    }
+}
+
+impl Read for Fetch {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_filetransfer_fetch_is_http_url() {
+        assert_eq!(Fetch::is_http_url("https://example.com/a.txt"), true);
+        assert_eq!(Fetch::is_http_url("HTTP://example.com"), true);
+        assert_eq!(Fetch::is_http_url("http://"), false);
+        assert_eq!(Fetch::is_http_url("ftp://example.com/a.txt"), false);
+        assert_eq!(Fetch::is_http_url("example.com/a.txt"), false);
+    }
+
+    #[test]
+    fn test_filetransfer_fetch_file_name() {
+        // From url
+        assert_eq!(
+            Fetch::file_name("https://example.com/pub/termscp-0.7.0.tar.gz", None),
+            "termscp-0.7.0.tar.gz"
+        );
+        assert_eq!(
+            Fetch::file_name("https://example.com/my%20file.txt?raw=true#top", None),
+            "my file.txt"
+        );
+        assert_eq!(Fetch::file_name("https://example.com/", None), "index.html");
+        assert_eq!(Fetch::file_name("https://example.com", None), "index.html");
+        assert_eq!(
+            Fetch::file_name("https://example.com/a/..", None),
+            "index.html"
+        );
+        // From content disposition
+        assert_eq!(
+            Fetch::file_name(
+                "https://example.com/download?id=4",
+                Some("attachment; filename=\"report.pdf\"")
+            ),
+            "report.pdf"
+        );
+        assert_eq!(
+            Fetch::file_name(
+                "https://example.com/download",
+                Some(
+                    "attachment; filename=\"fallback.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"
+                )
+            ),
+            "résumé.pdf"
+        );
+        assert_eq!(
+            Fetch::file_name(
+                "https://example.com/download",
+                Some("attachment; filename=\"../../.bashrc\"")
+            ),
+            ".bashrc"
+        );
+        assert_eq!(
+            Fetch::file_name("https://example.com/data.csv", Some("inline")),
+            "data.csv"
+        );
+    }
+}
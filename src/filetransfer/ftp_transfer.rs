@@ -25,7 +25,10 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-use super::{FileTransfer, FileTransferError, FileTransferErrorType};
+use super::proxy::{self, Proxy};
+use super::{
+    Capabilities, Capability, FileTransfer, FileTransferError, FileTransferErrorType, TlsOptions,
+};
 use crate::fs::{FsDirectory, FsEntry, FsFile, UnixPex};
 use crate::utils::fmt::shadow_password;
 use crate::utils::path;
@@ -34,8 +37,7 @@ use crate::utils::path;
 use std::convert::TryFrom;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::time::UNIX_EPOCH;
-use suppaftp::native_tls::TlsConnector;
+use std::time::{Duration, UNIX_EPOCH};
 use suppaftp::{
     list::{File, PosixPexQuery},
     status::FILE_UNAVAILABLE,
@@ -43,12 +45,17 @@ use suppaftp::{
     FtpError, FtpStream,
 };
 
+/// Timeout to connect through the proxy
+const PROXY_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// ## FtpFileTransfer
 ///
 /// Ftp file transfer struct
 pub struct FtpFileTransfer {
     stream: Option<FtpStream>,
     ftps: bool,
+    tls: TlsOptions,
+    proxy: Option<Proxy>,
 }
 
 impl FtpFileTransfer {
@@ -56,7 +63,12 @@ impl FtpFileTransfer {
     ///
     /// Instantiates a new `FtpFileTransfer`
     pub fn new(ftps: bool) -> FtpFileTransfer {
-        FtpFileTransfer { stream: None, ftps }
+        FtpFileTransfer {
+            stream: None,
+            ftps,
+            tls: TlsOptions::default(),
+            proxy: None,
+        }
     }
 
     /// ### resolve
@@ -185,9 +197,30 @@ impl FileTransfer for FtpFileTransfer {
         username: Option<String>,
         password: Option<String>,
     ) -> Result<Option<String>, FileTransferError> {
-        // Get stream
-        info!("Connecting to {}:{}", address, port);
-        let mut stream: FtpStream = match FtpStream::connect(format!("{}:{}", address, port)) {
+        // Get stream; through the relay if a proxy is set
+        let target: String = match self.proxy.as_ref() {
+            Some(proxy) => {
+                info!(
+                    "Connecting to {}:{} through proxy {}:{}",
+                    address, port, proxy.address, proxy.port
+                );
+                match proxy::relay_ftp(proxy, address.as_str(), port, PROXY_TIMEOUT) {
+                    Ok(relay) => relay.to_string(),
+                    Err(err) => {
+                        error!("Could not connect through proxy: {}", err);
+                        return Err(FileTransferError::new_ex(
+                            FileTransferErrorType::ConnectionError,
+                            format!("Proxy error: {}", err),
+                        ));
+                    }
+                }
+            }
+            None => {
+                info!("Connecting to {}:{}", address, port);
+                format!("{}:{}", address, port)
+            }
+        };
+        let mut stream: FtpStream = match FtpStream::connect(target) {
             Ok(stream) => stream,
             Err(err) => {
                 error!("Failed to connect: {}", err);
@@ -200,17 +233,13 @@ impl FileTransfer for FtpFileTransfer {
         // If SSL, open secure session
         if self.ftps {
             info!("Setting up TLS stream...");
-            let ctx = match TlsConnector::builder()
-                .danger_accept_invalid_certs(true)
-                .danger_accept_invalid_hostnames(true)
-                .build()
-            {
+            let ctx = match self.tls.connector() {
                 Ok(tls) => tls,
                 Err(err) => {
                     error!("Failed to setup TLS stream: {}", err);
                     return Err(FileTransferError::new_ex(
                         FileTransferErrorType::SslError,
-                        err.to_string(),
+                        err,
                     ));
                 }
             };
@@ -267,6 +296,43 @@ impl FileTransfer for FtpFileTransfer {
             .map(|x| x.to_string()))
     }
 
+    /// ### set_tls
+    ///
+    /// Set the TLS options to use at the next `connect`; only FTPS supports them
+    fn set_tls(&mut self, tls: Option<TlsOptions>) -> Result<(), FileTransferError> {
+        match (tls, self.ftps) {
+            (None, _) => {
+                self.tls = TlsOptions::default();
+                Ok(())
+            }
+            (Some(tls), true) => {
+                self.tls = tls;
+                Ok(())
+            }
+            (Some(_), false) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::UnsupportedFeature,
+                String::from("TLS options require FTPS"),
+            )),
+        }
+    }
+
+    /// ### set_proxy
+    ///
+    /// Set the proxy to connect to the remote server through; only plain FTP supports it,
+    /// since the passive replies of FTPS can't be rewritten to tunnel data connections
+    fn set_proxy(&mut self, proxy: Option<Proxy>) -> Result<(), FileTransferError> {
+        match (proxy, self.ftps) {
+            (Some(_), true) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::UnsupportedFeature,
+                String::from("FTPS can't be used through a proxy"),
+            )),
+            (proxy, _) => {
+                self.proxy = proxy;
+                Ok(())
+            }
+        }
+    }
+
     /// ### disconnect
     ///
     /// Disconnect from the remote server
@@ -297,6 +363,13 @@ impl FileTransfer for FtpFileTransfer {
         self.stream.is_some()
     }
 
+    /// ### capabilities
+    ///
+    /// FTP can only rename; RNTO replaces the target on the common servers
+    fn capabilities(&mut self) -> Capabilities {
+        Capabilities::default().with(Capability::RenameOver, self.is_connected())
+    }
+
     /// ### pwd
     ///
     /// Print working directory
@@ -652,6 +725,7 @@ mod tests {
 
     use pretty_assertions::assert_eq;
     use std::io::{Read, Write};
+    use std::str::FromStr;
     use std::time::Duration;
 
     #[test]
@@ -665,6 +739,62 @@ mod tests {
         assert!(ftp.stream.is_none());
     }
 
+    #[test]
+    fn test_filetransfer_ftp_set_tls() {
+        let tls: TlsOptions = TlsOptions {
+            ca_bundle: Some(PathBuf::from("/etc/ssl/private-ca.pem")),
+            ..TlsOptions::default()
+        };
+        let mut ftp: FtpFileTransfer = FtpFileTransfer::new(false);
+        assert!(ftp.set_tls(None).is_ok());
+        assert_eq!(
+            ftp.set_tls(Some(tls.clone())).err().unwrap().kind(),
+            FileTransferErrorType::UnsupportedFeature
+        );
+        let mut ftps: FtpFileTransfer = FtpFileTransfer::new(true);
+        assert!(ftps.set_tls(Some(tls.clone())).is_ok());
+        assert_eq!(ftps.tls, tls);
+        assert!(ftps.set_tls(None).is_ok());
+        assert_eq!(ftps.tls, TlsOptions::default());
+    }
+
+    #[test]
+    fn test_filetransfer_ftp_proxy() {
+        let proxy: Proxy = Proxy::from_str("socks5://127.0.0.1:1080").ok().unwrap();
+        let mut ftp: FtpFileTransfer = FtpFileTransfer::new(false);
+        assert!(ftp.set_proxy(Some(proxy.clone())).is_ok());
+        assert_eq!(ftp.proxy.as_ref(), Some(&proxy));
+        assert!(ftp.set_proxy(None).is_ok());
+        assert!(ftp.proxy.is_none());
+        // FTPS can't be tunneled
+        let mut ftp: FtpFileTransfer = FtpFileTransfer::new(true);
+        assert!(ftp.set_proxy(None).is_ok());
+        assert_eq!(
+            ftp.set_proxy(Some(proxy)).err().unwrap().kind(),
+            FileTransferErrorType::UnsupportedFeature
+        );
+    }
+
+    #[test]
+    fn test_filetransfer_ftp_connect_proxy_error() {
+        // Nothing listens on the proxy port
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port: u16 = listener.local_addr().unwrap().port();
+        drop(listener);
+        let mut ftp: FtpFileTransfer = FtpFileTransfer::new(false);
+        let proxy: Proxy = Proxy::from_str(format!("socks5://127.0.0.1:{}", port).as_str())
+            .ok()
+            .unwrap();
+        assert!(ftp.set_proxy(Some(proxy)).is_ok());
+        assert_eq!(
+            ftp.connect(String::from("127.0.0.1"), 21, None, None)
+                .err()
+                .unwrap()
+                .kind(),
+            FileTransferErrorType::ConnectionError
+        );
+    }
+
     #[test]
     #[cfg(feature = "with-containers")]
     fn test_filetransfer_ftp_server() {
@@ -701,6 +831,9 @@ mod tests {
         );
         // Make directory (err)
         assert!(ftp.mkdir(PathBuf::from("/root/pommlar").as_path()).is_err());
+        // Make directory with parents (`/home` already exists)
+        assert!(ftp.mkdir_all(Path::new("/home/omar/pommlar")).is_ok());
+        assert!(ftp.mkdir_all(Path::new("/home/omar")).is_ok());
         // Change directory
         assert!(ftp.change_dir(PathBuf::from("/home").as_path()).is_ok());
         // Change directory (err)
@@ -927,6 +1060,7 @@ mod tests {
         assert!(ftp.disconnect().is_err());
         assert!(ftp.list_dir(Path::new("/tmp")).is_err());
         assert!(ftp.mkdir(Path::new("/tmp")).is_err());
+        assert!(ftp.mkdir_all(Path::new("/tmp/a/b")).is_err());
         assert!(ftp
             .remove(&make_fsentry(PathBuf::from("/nowhere"), false))
             .is_err());
@@ -937,6 +1071,7 @@ mod tests {
             )
             .is_err());
         assert!(ftp.pwd().is_err());
+        assert_eq!(ftp.capabilities(), Capabilities::default());
         assert!(ftp.stat(Path::new("/tmp")).is_err());
         assert!(ftp.recv_file(&file).is_err());
         assert!(ftp.send_file(&file, Path::new("/tmp/omar.txt")).is_err());
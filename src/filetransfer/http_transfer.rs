@@ -0,0 +1,708 @@
+//! ## HTTP_transfer
+//!
+//! `http_transfer` is the module which provides a read-only file transfer for HTTP(S) directory indexes
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use super::webdav_transfer::WebDavFileTransfer;
+use super::{FileTransfer, FileTransferError, FileTransferErrorType};
+use crate::fs::{FsDirectory, FsEntry, FsFile};
+use crate::utils::fmt::shadow_password;
+use crate::utils::path;
+
+// Includes
+use chrono::NaiveDateTime;
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use ureq::{Agent, AgentBuilder, Response};
+
+// Regex
+lazy_static! {
+    /**
+     * Regex matches:
+     *  - group 1: Some(double quoted href) | None
+     *  - group 2: Some(single quoted href) | None
+     *  - group 3: Some(unquoted href) | None
+     */
+    static ref LINK_REGEX: Regex = Regex::new(r#"(?is)<a\s[^>]*?href\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))[^>]*>.*?</a>"#).unwrap();
+    static ref TAG_REGEX: Regex = Regex::new(r"<[^>]*>").unwrap();
+    /**
+     * Regex matches:
+     *  - group 1: modification time, either as `31-Aug-2021 10:20` (nginx) or `2021-08-31 10:20` (apache)
+     *  - group 2: size (e.g. `8192` or `1.2K`)
+     *  - group 3: size unit
+     */
+    static ref INDEX_DETAILS_REGEX: Regex = Regex::new(r"(\d{1,2}-[A-Za-z]{3}-\d{4} \d{2}:\d{2}|\d{4}-\d{2}-\d{2} \d{2}:\d{2})(?::\d{2})?\s+(?:(\d+(?:\.\d+)?)([KMGT]?)|-)").unwrap();
+}
+
+/// ## HttpFileTransfer
+///
+/// Read-only file transfer for files served over HTTP(S).
+/// Directories are listed parsing the index page generated by the server (e.g. nginx `autoindex`,
+/// apache `mod_autoindex` or `python -m http.server`)
+pub struct HttpFileTransfer {
+    agent: Option<Agent>,
+    secure: bool,
+    base_url: String,
+    authorization: Option<String>,
+    wrkdir: PathBuf,
+}
+
+impl HttpFileTransfer {
+    /// ### new
+    ///
+    /// Instantiates a new `HttpFileTransfer`. If `secure` is true, HTTPS is used
+    pub fn new(secure: bool) -> HttpFileTransfer {
+        HttpFileTransfer {
+            agent: None,
+            secure,
+            base_url: String::new(),
+            authorization: None,
+            wrkdir: PathBuf::from("/"),
+        }
+    }
+
+    /// ### resolve
+    ///
+    /// Fix provided path; on Windows fixes the backslashes, converting them to slashes
+    /// While on POSIX does nothing
+    #[cfg(target_os = "windows")]
+    fn resolve(p: &Path) -> PathBuf {
+        PathBuf::from(path_slash::PathExt::to_slash_lossy(p).as_str())
+    }
+
+    #[cfg(target_family = "unix")]
+    fn resolve(p: &Path) -> PathBuf {
+        p.to_path_buf()
+    }
+
+    /// ### absolutize
+    ///
+    /// Get absolute path of `p`, using the working directory as base
+    fn absolutize(&self, p: &Path) -> PathBuf {
+        path::absolutize(self.wrkdir.as_path(), Self::resolve(p).as_path())
+    }
+
+    /// ### read_only
+    ///
+    /// Error returned by all the operations which would modify the remote
+    fn read_only() -> FileTransferError {
+        FileTransferError::new_ex(
+            FileTransferErrorType::UnsupportedFeature,
+            String::from("HTTP server is read-only"),
+        )
+    }
+
+    /// ### request
+    ///
+    /// Perform a request on the resource at `uri`
+    fn request(&self, method: &str, uri: &str) -> Result<Response, FileTransferError> {
+        let agent: &Agent = match self.agent.as_ref() {
+            Some(agent) => agent,
+            None => {
+                return Err(FileTransferError::new(
+                    FileTransferErrorType::UninitializedSession,
+                ))
+            }
+        };
+        let url: String = format!("{}{}", self.base_url, uri);
+        debug!("{} {}", method, url);
+        let mut request = agent.request(method, url.as_str());
+        if let Some(authorization) = self.authorization.as_ref() {
+            request = request.set("Authorization", authorization.as_str());
+        }
+        match request.call() {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(code, response)) => {
+                error!("{} {} failed: {}", method, url, code);
+                Err(WebDavFileTransfer::status_error(
+                    code,
+                    response.status_text(),
+                ))
+            }
+            Err(ureq::Error::Transport(err)) => {
+                error!("{} {} failed: {}", method, url, err);
+                Err(FileTransferError::new_ex(
+                    FileTransferErrorType::ConnectionError,
+                    err.to_string(),
+                ))
+            }
+        }
+    }
+
+    /// ### href_to_path
+    ///
+    /// Resolve a link found in the index of `dir`. Returns the path of the entry and whether
+    /// it is a directory, only if the link points to a direct child of `dir`
+    fn href_to_path(dir: &Path, href: &str) -> Option<(PathBuf, bool)> {
+        let href: String = WebDavFileTransfer::decode_xml_entities(href.trim());
+        // Strip query and fragment
+        let href: &str = href.split(['?', '#']).next().unwrap_or("");
+        if href.is_empty() || href.contains(':') {
+            // Sorting links, anchors and absolute URLs (or `mailto:`, `javascript:`...)
+            return None;
+        }
+        let decoded: String = percent_decode_str(href).decode_utf8_lossy().to_string();
+        let is_dir: bool = decoded.ends_with('/');
+        let relative: &Path = Path::new(decoded.as_str());
+        if relative
+            .components()
+            .any(|x| matches!(x, Component::ParentDir | Component::CurDir))
+        {
+            return None;
+        }
+        // Collect components to get rid of the trailing slash
+        let p: PathBuf = path::absolutize(dir, relative).components().collect();
+        match p.parent() == Some(dir) && p.file_name().is_some() {
+            true => Some((p, is_dir)),
+            false => None,
+        }
+    }
+
+    /// ### parse_index_details
+    ///
+    /// Parse modification time and size from the text following a link in the index
+    fn parse_index_details(text: &str) -> (Option<SystemTime>, Option<usize>) {
+        let details = match INDEX_DETAILS_REGEX.captures(text) {
+            Some(details) => details,
+            None => return (None, None),
+        };
+        let time: Option<SystemTime> = details.get(1).and_then(|x| {
+            NaiveDateTime::parse_from_str(x.as_str(), "%d-%b-%Y %H:%M")
+                .or_else(|_| NaiveDateTime::parse_from_str(x.as_str(), "%Y-%m-%d %H:%M"))
+                .ok()
+                .map(|x| UNIX_EPOCH + Duration::from_secs(x.timestamp().max(0) as u64))
+        });
+        let size: Option<usize> = details.get(2).and_then(|x| {
+            let size: f64 = x.as_str().parse::<f64>().ok()?;
+            let multiplier: f64 = match details.get(3).map(|x| x.as_str()) {
+                Some("K") => 1024.0,
+                Some("M") => 1048576.0,
+                Some("G") => 1073741824.0,
+                Some("T") => 1099511627776.0,
+                _ => 1.0,
+            };
+            Some((size * multiplier) as usize)
+        });
+        (time, size)
+    }
+
+    /// ### parse_index
+    ///
+    /// Parse the HTML index of `dir` into a list of `FsEntry`
+    fn parse_index(dir: &Path, body: &str) -> Vec<FsEntry> {
+        let links: Vec<(usize, usize, String)> = LINK_REGEX
+            .captures_iter(body)
+            .filter_map(|link| {
+                let whole = link.get(0)?;
+                let href: &str = link
+                    .get(1)
+                    .or_else(|| link.get(2))
+                    .or_else(|| link.get(3))?
+                    .as_str();
+                Some((whole.start(), whole.end(), href.to_string()))
+            })
+            .collect();
+        let mut entries: Vec<FsEntry> = Vec::with_capacity(links.len());
+        for (i, (_, end, href)) in links.iter().enumerate() {
+            let (abs_path, is_dir) = match Self::href_to_path(dir, href.as_str()) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            if entries.iter().any(|x| x.get_abs_path() == abs_path) {
+                continue;
+            }
+            // Details are placed between the link and the end of the line (or the next link)
+            let next: usize = links.get(i + 1).map(|x| x.0).unwrap_or_else(|| body.len());
+            let text: &str = &body[*end..next];
+            let text: &str = text.split('\n').next().unwrap_or("");
+            let (time, size) = Self::parse_index_details(TAG_REGEX.replace_all(text, " ").as_ref());
+            let time: SystemTime = time.unwrap_or(UNIX_EPOCH);
+            let name: String = abs_path
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default();
+            entries.push(match is_dir {
+                true => FsEntry::Directory(FsDirectory {
+                    name,
+                    abs_path,
+                    last_change_time: time,
+                    last_access_time: time,
+                    creation_time: time,
+                    symlink: None,
+                    user: None,
+                    group: None,
+                    unix_pex: None,
+                }),
+                false => FsEntry::File(FsFile {
+                    name,
+                    size: size.unwrap_or(0),
+                    ftype: abs_path
+                        .extension()
+                        .map(|ext| String::from(ext.to_str().unwrap_or(""))),
+                    abs_path,
+                    last_change_time: time,
+                    last_access_time: time,
+                    creation_time: time,
+                    symlink: None,
+                    user: None,
+                    group: None,
+                    unix_pex: None,
+                }),
+            });
+        }
+        entries
+    }
+}
+
+impl FileTransfer for HttpFileTransfer {
+    /// ### connect
+    ///
+    /// Connect to the remote server
+    fn connect(
+        &mut self,
+        address: String,
+        port: u16,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Result<Option<String>, FileTransferError> {
+        let scheme: &str = match self.secure {
+            true => "https",
+            false => "http",
+        };
+        self.base_url = format!("{}://{}:{}", scheme, address, port);
+        self.wrkdir = PathBuf::from("/");
+        // Credentials are sent only if provided, using basic authentication
+        let password: String = password.unwrap_or_default();
+        self.authorization = username.map(|username| {
+            info!(
+                "Authenticating with username: {}, password: {}",
+                username,
+                shadow_password(password.as_str())
+            );
+            format!(
+                "Basic {}",
+                base64::encode(format!("{}:{}", username, password))
+            )
+        });
+        info!("Connecting to {}", self.base_url);
+        self.agent = Some(
+            AgentBuilder::new()
+                .timeout_connect(Duration::from_secs(30))
+                .build(),
+        );
+        // Probe server; the root might not be served, so accept any non-auth error
+        match self.request("HEAD", "/") {
+            Ok(_) => {}
+            Err(err)
+                if err.kind() == FileTransferErrorType::AuthenticationFailed
+                    || err.kind() == FileTransferErrorType::ConnectionError =>
+            {
+                error!("Failed to connect: {}", err);
+                self.agent = None;
+                return Err(err);
+            }
+            Err(err) => warn!("Could not stat server root: {}", err),
+        }
+        info!("Connection successfully established");
+        Ok(None)
+    }
+
+    /// ### disconnect
+    ///
+    /// Disconnect from the remote server
+    fn disconnect(&mut self) -> Result<(), FileTransferError> {
+        info!("Disconnecting from HTTP server...");
+        match self.agent.take() {
+            Some(_) => {
+                self.authorization = None;
+                Ok(())
+            }
+            None => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
+    /// ### is_connected
+    ///
+    /// Indicates whether the client is connected to remote
+    fn is_connected(&self) -> bool {
+        self.agent.is_some()
+    }
+
+    /// ### pwd
+    ///
+    /// Print working directory
+    fn pwd(&mut self) -> Result<PathBuf, FileTransferError> {
+        info!("PWD");
+        match self.is_connected() {
+            true => Ok(self.wrkdir.clone()),
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
+    /// ### change_dir
+    ///
+    /// Change working directory
+    fn change_dir(&mut self, dir: &Path) -> Result<PathBuf, FileTransferError> {
+        let dir: PathBuf = self.absolutize(dir);
+        info!("Changing directory to {}", dir.display());
+        match self.stat(dir.as_path())? {
+            FsEntry::Directory(_) => {
+                self.wrkdir = dir;
+                Ok(self.wrkdir.clone())
+            }
+            FsEntry::File(_) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::DirStatFailed,
+                format!("\"{}\" is not a directory", dir.display()),
+            )),
+        }
+    }
+
+    /// ### copy
+    ///
+    /// Copy file to destination
+    fn copy(&mut self, _src: &FsEntry, _dst: &Path) -> Result<(), FileTransferError> {
+        Err(Self::read_only())
+    }
+
+    /// ### list_dir
+    ///
+    /// List directory entries
+    fn list_dir(&mut self, path: &Path) -> Result<Vec<FsEntry>, FileTransferError> {
+        let dir: PathBuf = self.absolutize(path);
+        info!("LIST dir {}", dir.display());
+        let response: Response = self.request(
+            "GET",
+            WebDavFileTransfer::encode_path(dir.as_path(), true).as_str(),
+        )?;
+        match response.into_string() {
+            Ok(body) => Ok(Self::parse_index(dir.as_path(), body.as_str())),
+            Err(err) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::DirStatFailed,
+                err.to_string(),
+            )),
+        }
+    }
+
+    /// ### mkdir
+    ///
+    /// Make directory
+    fn mkdir(&mut self, _dir: &Path) -> Result<(), FileTransferError> {
+        Err(Self::read_only())
+    }
+
+    /// ### remove
+    ///
+    /// Remove a file or a directory
+    fn remove(&mut self, _file: &FsEntry) -> Result<(), FileTransferError> {
+        Err(Self::read_only())
+    }
+
+    /// ### rename
+    ///
+    /// Rename file or a directory
+    fn rename(&mut self, _file: &FsEntry, _dst: &Path) -> Result<(), FileTransferError> {
+        Err(Self::read_only())
+    }
+
+    /// ### stat
+    ///
+    /// Stat file and return FsEntry.
+    /// The entry is a directory if the server serves it with a trailing slash
+    fn stat(&mut self, path: &Path) -> Result<FsEntry, FileTransferError> {
+        let path: PathBuf = self.absolutize(path);
+        info!("Stat {}", path.display());
+        let name: String = path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("/"));
+        if let Ok(response) = self.request(
+            "HEAD",
+            WebDavFileTransfer::encode_path(path.as_path(), true).as_str(),
+        ) {
+            let time: SystemTime = response
+                .header("Last-Modified")
+                .and_then(WebDavFileTransfer::parse_http_time)
+                .unwrap_or(UNIX_EPOCH);
+            return Ok(FsEntry::Directory(FsDirectory {
+                name,
+                abs_path: path,
+                last_change_time: time,
+                last_access_time: time,
+                creation_time: time,
+                symlink: None,
+                user: None,
+                group: None,
+                unix_pex: None,
+            }));
+        }
+        let response: Response = self.request(
+            "HEAD",
+            WebDavFileTransfer::encode_path(path.as_path(), false).as_str(),
+        )?;
+        let time: SystemTime = response
+            .header("Last-Modified")
+            .and_then(WebDavFileTransfer::parse_http_time)
+            .unwrap_or(UNIX_EPOCH);
+        Ok(FsEntry::File(FsFile {
+            name,
+            size: response
+                .header("Content-Length")
+                .and_then(|x| x.parse::<usize>().ok())
+                .unwrap_or(0),
+            ftype: path
+                .extension()
+                .map(|ext| String::from(ext.to_str().unwrap_or(""))),
+            abs_path: path,
+            last_change_time: time,
+            last_access_time: time,
+            creation_time: time,
+            symlink: None,
+            user: None,
+            group: None,
+            unix_pex: None,
+        }))
+    }
+
+    /// ### exec
+    ///
+    /// Execute a command on remote host
+    fn exec(&mut self, _cmd: &str) -> Result<String, FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
+    /// ### send_file
+    ///
+    /// Send file to remote; always fails, since HTTP servers are read-only
+    fn send_file(
+        &mut self,
+        _local: &FsFile,
+        _file_name: &Path,
+    ) -> Result<Box<dyn Write>, FileTransferError> {
+        match self.is_connected() {
+            true => Err(Self::read_only()),
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
+    /// ### recv_file
+    ///
+    /// Receive file from remote with provided name
+    /// Returns file and its size
+    fn recv_file(&mut self, file: &FsFile) -> Result<Box<dyn Read>, FileTransferError> {
+        info!("Receiving file {}", file.abs_path.display());
+        let response: Response = self.request(
+            "GET",
+            WebDavFileTransfer::encode_path(file.abs_path.as_path(), false).as_str(),
+        )?;
+        Ok(Box::new(response.into_reader()))
+    }
+
+    /// ### on_sent
+    ///
+    /// Finalize send method; never called, since `send_file` always fails
+    fn on_sent(&mut self, writable: Box<dyn Write>) -> Result<(), FileTransferError> {
+        drop(writable);
+        match self.is_connected() {
+            true => Err(Self::read_only()),
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
+    /// ### on_recv
+    ///
+    /// Finalize recv method.
+    /// This method must be implemented only if necessary; in case you don't need it, just return `Ok(())`
+    /// The purpose of this method is to finalize the connection with the peer when reading data.
+    /// This mighe be necessary for some protocols.
+    /// You must call this method each time you want to finalize the read of the remote file.
+    fn on_recv(&mut self, readable: Box<dyn Read>) -> Result<(), FileTransferError> {
+        info!("Finalizing get");
+        drop(readable);
+        match self.is_connected() {
+            true => Ok(()),
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::utils::test_helpers::{create_sample_file_entry, make_fsentry};
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_filetransfer_http_new() {
+        let client: HttpFileTransfer = HttpFileTransfer::new(true);
+        assert_eq!(client.secure, true);
+        assert!(client.agent.is_none());
+        assert!(client.authorization.is_none());
+        assert_eq!(client.wrkdir, PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_filetransfer_http_href_to_path() {
+        let dir: &Path = Path::new("/pub");
+        assert_eq!(
+            HttpFileTransfer::href_to_path(dir, "omar.txt"),
+            Some((PathBuf::from("/pub/omar.txt"), false))
+        );
+        assert_eq!(
+            HttpFileTransfer::href_to_path(dir, "my%20docs/"),
+            Some((PathBuf::from("/pub/my docs"), true))
+        );
+        assert_eq!(
+            HttpFileTransfer::href_to_path(dir, "/pub/a&amp;b.txt"),
+            Some((PathBuf::from("/pub/a&b.txt"), false))
+        );
+        assert!(HttpFileTransfer::href_to_path(dir, "../").is_none());
+        assert!(HttpFileTransfer::href_to_path(dir, "/").is_none());
+        assert!(HttpFileTransfer::href_to_path(dir, "?C=N;O=D").is_none());
+        assert!(HttpFileTransfer::href_to_path(dir, "#top").is_none());
+        assert!(HttpFileTransfer::href_to_path(dir, "https://example.com/pub/a").is_none());
+        assert!(HttpFileTransfer::href_to_path(dir, "/other/a.txt").is_none());
+        assert!(HttpFileTransfer::href_to_path(dir, "sub/a.txt").is_none());
+    }
+
+    #[test]
+    fn test_filetransfer_http_parse_index_nginx() {
+        let body: &str = r#"<html>
+<head><title>Index of /pub/</title></head>
+<body>
+<h1>Index of /pub/</h1><hr><pre><a href="../">../</a>
+<a href="docs/">docs/</a>                                              31-Aug-2021 10:20                   -
+<a href="omar.txt">omar.txt</a>                                           31-Aug-2021 10:20                8192
+</pre><hr></body>
+</html>"#;
+        let entries: Vec<FsEntry> = HttpFileTransfer::parse_index(Path::new("/pub"), body);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_dir());
+        assert_eq!(entries[0].get_abs_path(), PathBuf::from("/pub/docs"));
+        assert_eq!(
+            entries[0].get_last_change_time(),
+            UNIX_EPOCH + Duration::from_secs(1630405200)
+        );
+        let file: FsFile = entries[1].clone().unwrap_file();
+        assert_eq!(file.name.as_str(), "omar.txt");
+        assert_eq!(file.size, 8192);
+        assert_eq!(file.ftype.as_deref(), Some("txt"));
+    }
+
+    #[test]
+    fn test_filetransfer_http_parse_index_apache() {
+        let body: &str = r#"<table>
+<tr><th valign="top"><img src="/icons/blank.gif" alt="[ICO]"></th><th><a href="?C=N;O=D">Name</a></th><th><a href="?C=M;O=A">Last modified</a></th><th><a href="?C=S;O=A">Size</a></th></tr>
+<tr><td valign="top"><img src="/icons/back.gif" alt="[PARENTDIR]"></td><td><a href="/">Parent Directory</a></td><td>&nbsp;</td><td align="right">  - </td></tr>
+<tr><td valign="top"><img src="/icons/folder.gif" alt="[DIR]"></td><td><a href="docs/">docs/</a></td><td align="right">2021-08-31 10:20  </td><td align="right">  - </td></tr>
+<tr><td valign="top"><img src="/icons/text.gif" alt="[TXT]"></td><td><a href="omar.txt">omar.txt</a></td><td align="right">2021-08-31 10:20  </td><td align="right">1.5K</td></tr>
+</table>"#;
+        let entries: Vec<FsEntry> = HttpFileTransfer::parse_index(Path::new("/pub"), body);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_dir());
+        assert_eq!(entries[0].get_name(), "docs");
+        let file: FsFile = entries[1].clone().unwrap_file();
+        assert_eq!(file.abs_path, PathBuf::from("/pub/omar.txt"));
+        assert_eq!(file.size, 1536);
+        assert_eq!(
+            file.last_change_time,
+            UNIX_EPOCH + Duration::from_secs(1630405200)
+        );
+    }
+
+    #[test]
+    fn test_filetransfer_http_parse_index_python() {
+        let body: &str = r#"<ul>
+<li><a href="docs/">docs/</a></li>
+<li><a href="my%20file.txt">my file.txt</a></li>
+</ul>"#;
+        let entries: Vec<FsEntry> = HttpFileTransfer::parse_index(Path::new("/"), body);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_dir());
+        let file: FsFile = entries[1].clone().unwrap_file();
+        assert_eq!(file.abs_path, PathBuf::from("/my file.txt"));
+        assert_eq!(file.size, 0);
+        assert_eq!(file.last_change_time, UNIX_EPOCH);
+    }
+
+    #[test]
+    fn test_filetransfer_http_read_only() {
+        let mut client: HttpFileTransfer = HttpFileTransfer::new(false);
+        // Pretend to be connected
+        client.agent = Some(AgentBuilder::new().build());
+        let file: FsFile = create_sample_file_entry().0;
+        let entry: FsEntry = make_fsentry(PathBuf::from("/omar.txt"), false);
+        assert_eq!(
+            client
+                .send_file(&file, Path::new("/omar.txt"))
+                .err()
+                .unwrap()
+                .kind(),
+            FileTransferErrorType::UnsupportedFeature
+        );
+        assert!(client.mkdir(Path::new("/docs")).is_err());
+        assert!(client.remove(&entry).is_err());
+        assert!(client.rename(&entry, Path::new("/pippo.txt")).is_err());
+        assert!(client.copy(&entry, Path::new("/pippo.txt")).is_err());
+        assert!(client.exec("ls").is_err());
+    }
+
+    #[test]
+    fn test_filetransfer_http_uninitialized() {
+        let file: FsFile = create_sample_file_entry().0;
+        let mut client: HttpFileTransfer = HttpFileTransfer::new(false);
+        assert!(client.change_dir(Path::new("/tmp")).is_err());
+        assert!(client.disconnect().is_err());
+        assert!(client.list_dir(Path::new("/tmp")).is_err());
+        assert!(client.pwd().is_err());
+        assert!(client.stat(Path::new("/tmp")).is_err());
+        assert!(client.recv_file(&file).is_err());
+        assert_eq!(
+            client
+                .send_file(&file, Path::new("/tmp/omar.txt"))
+                .err()
+                .unwrap()
+                .kind(),
+            FileTransferErrorType::UninitializedSession
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! ## Interactive
+//!
+//! `interactive` authenticates SSH sessions with the keyboard-interactive method, through which servers
+//! ask for the password along with instructions or legal banners meant to be read before authenticating
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use super::{FileTransferError, FileTransferErrorType};
+// Ext
+use ssh2::{KeyboardInteractivePrompt, Prompt, Session};
+
+/// ### userauth_interactive
+///
+/// Authenticate `session` as `username` answering the keyboard-interactive prompts with `password`.
+/// If the server sends a banner or instructions and `acknowledged` is false, authentication is interrupted and
+/// an error of kind `FileTransferErrorType::BannerAcknowledgementRequired`, whose message is the banner, is returned
+pub fn userauth_interactive(
+    session: &Session,
+    username: &str,
+    password: &str,
+    acknowledged: bool,
+) -> Result<(), FileTransferError> {
+    debug!("Authenticating as {} with keyboard-interactive", username);
+    let mut responder: Responder = Responder::new(password, acknowledged);
+    let result = session.userauth_keyboard_interactive(username, &mut responder);
+    if let Some(banner) = responder.banner {
+        info!("Authentication interrupted to show the server banner");
+        return Err(FileTransferError::new_ex(
+            FileTransferErrorType::BannerAcknowledgementRequired,
+            banner,
+        ));
+    }
+    result.map_err(|err| {
+        error!("Authentication failed: {}", err);
+        FileTransferError::new_ex(FileTransferErrorType::AuthenticationFailed, err.to_string())
+    })
+}
+
+/// ## Responder
+///
+/// Answers the keyboard-interactive prompts of the server
+struct Responder {
+    password: String,
+    acknowledged: bool,
+    banner: Option<String>, // Banner which interrupted authentication
+}
+
+impl Responder {
+    fn new(password: &str, acknowledged: bool) -> Self {
+        Self {
+            password: password.to_string(),
+            acknowledged,
+            banner: None,
+        }
+    }
+
+    /// ### banner_text
+    ///
+    /// Get the text to show to the user from the name and the instructions of a prompt request, if any
+    fn banner_text(name: &str, instructions: &str) -> Option<String> {
+        let text: String = [name, instructions]
+            .iter()
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        match text.is_empty() {
+            true => None,
+            false => Some(text),
+        }
+    }
+}
+
+impl KeyboardInteractivePrompt for Responder {
+    fn prompt<'a>(
+        &mut self,
+        name: &str,
+        instructions: &str,
+        prompts: &[Prompt<'a>],
+    ) -> Vec<String> {
+        if !self.acknowledged {
+            if let Some(banner) = Self::banner_text(name, instructions) {
+                // Give no answer, so that authentication fails and the banner can be shown
+                self.banner = Some(banner);
+                return Vec::new();
+            }
+        }
+        // Hidden prompts ask for the password; visible ones can't be answered
+        prompts
+            .iter()
+            .map(|x| match x.echo {
+                true => {
+                    warn!(
+                        "Could not answer keyboard-interactive prompt \"{}\"",
+                        x.text
+                    );
+                    String::new()
+                }
+                false => self.password.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_filetransfer_interactive_responder() {
+        let prompts: Vec<Prompt> = vec![
+            Prompt {
+                text: Cow::Borrowed("Password: "),
+                echo: false,
+            },
+            Prompt {
+                text: Cow::Borrowed("Department: "),
+                echo: true,
+            },
+        ];
+        // No banner
+        let mut responder: Responder = Responder::new("secret", false);
+        assert_eq!(
+            responder.prompt("", " ", prompts.as_slice()),
+            vec![String::from("secret"), String::new()]
+        );
+        assert!(responder.banner.is_none());
+        // Banner not acknowledged
+        let mut responder: Responder = Responder::new("secret", false);
+        assert!(responder
+            .prompt("Authorized use only", "", prompts.as_slice())
+            .is_empty());
+        assert_eq!(responder.banner.as_deref(), Some("Authorized use only"));
+        // Banner acknowledged
+        let mut responder: Responder = Responder::new("secret", true);
+        assert_eq!(
+            responder.prompt("Login", "Authorized use only", prompts.as_slice()),
+            vec![String::from("secret"), String::new()]
+        );
+        assert!(responder.banner.is_none());
+        assert_eq!(
+            Responder::banner_text("Login", "Authorized use only").as_deref(),
+            Some("Login\nAuthorized use only")
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! ## Lock
+//!
+//! `lock` provides the advisory lock convention used to mark remote files as being edited
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use crate::utils::fmt::display_format;
+// Ext
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
+
+const LOCK_SUFFIX: &str = ".lock";
+const OWNER_KEY: &str = "owner:";
+const SINCE_KEY: &str = "since:";
+const SINCE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
+
+/// ## FileLock
+///
+/// FileLock describes an advisory lock on a remote file.
+/// The lock is a hidden sidecar file, named `.<file name>.lock`, placed next to the locked file,
+/// which contains who holds the lock and since when.
+/// Locks are advisory: they don't prevent anyone from writing the file, they just tell who's on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileLock {
+    pub owner: String,
+    pub since: Option<DateTime<Local>>,
+}
+
+impl FileLock {
+    /// ### new
+    ///
+    /// Instantiates a new lock held by `owner` since now
+    pub fn new<S: AsRef<str>>(owner: S) -> Self {
+        Self {
+            owner: owner.as_ref().to_string(),
+            since: Some(Local::now()),
+        }
+    }
+
+    /// ### parse
+    ///
+    /// Parse lock file content. Returns `None` if the owner is missing
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut owner: Option<String> = None;
+        let mut since: Option<DateTime<Local>> = None;
+        for line in content.lines().map(|x| x.trim()) {
+            if let Some(value) = line.strip_prefix(OWNER_KEY) {
+                owner = Some(value.trim().to_string()).filter(|x| !x.is_empty());
+            } else if let Some(value) = line.strip_prefix(SINCE_KEY) {
+                since = DateTime::parse_from_str(value.trim(), SINCE_FORMAT)
+                    .ok()
+                    .map(|x| x.with_timezone(&Local));
+            }
+        }
+        Some(Self {
+            owner: owner?,
+            since,
+        })
+    }
+
+    /// ### to_content
+    ///
+    /// Format lock as lock file content
+    pub fn to_content(&self) -> String {
+        match self.since {
+            Some(since) => format!(
+                "{} {}\n{} {}\n",
+                OWNER_KEY,
+                self.owner,
+                SINCE_KEY,
+                since.format(SINCE_FORMAT)
+            ),
+            None => format!("{} {}\n", OWNER_KEY, self.owner),
+        }
+    }
+
+    /// ### describe
+    ///
+    /// Describe lock as "being edited by X (since ...)"
+    pub fn describe(&self) -> String {
+        match self.since {
+            Some(since) => format!(
+                "being edited by {} since {}",
+                self.owner,
+                since.format(display_format().datetime_format(false).as_str())
+            ),
+            None => format!("being edited by {}", self.owner),
+        }
+    }
+
+    /// ### lock_path
+    ///
+    /// Get the path of the lock file for `path`
+    pub fn lock_path(path: &Path) -> PathBuf {
+        let name: String = path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let lock_name: String = format!(".{}{}", name, LOCK_SUFFIX);
+        match path.parent() {
+            Some(parent) => parent.join(lock_name),
+            None => PathBuf::from(lock_name),
+        }
+    }
+
+    /// ### locked_name
+    ///
+    /// If `name` is the name of a lock file, get the name of the file it locks
+    pub fn locked_name(name: &str) -> Option<&str> {
+        name.strip_prefix('.')
+            .and_then(|x| x.strip_suffix(LOCK_SUFFIX))
+            .filter(|x| !x.is_empty())
+    }
+
+    /// ### local_owner
+    ///
+    /// Get the owner identifier for locks taken from this machine, as `user@hostname`
+    pub fn local_owner() -> String {
+        let hostname: String = hostname::get()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|_| String::from("localhost"));
+        format!("{}@{}", whoami::username(), hostname)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_filetransfer_lock_content() {
+        let lock: FileLock = FileLock::new("omar@laptop");
+        let parsed: FileLock = FileLock::parse(lock.to_content().as_str()).unwrap();
+        assert_eq!(parsed.owner.as_str(), "omar@laptop");
+        assert_eq!(
+            parsed.since.map(|x| x.timestamp()),
+            lock.since.map(|x| x.timestamp())
+        );
+        assert!(parsed
+            .describe()
+            .starts_with("being edited by omar@laptop since "));
+        // Since is optional
+        let lock: FileLock = FileLock::parse("owner: chiara@desktop\nsince: yesterday\n").unwrap();
+        assert_eq!(lock.owner.as_str(), "chiara@desktop");
+        assert!(lock.since.is_none());
+        assert_eq!(lock.describe().as_str(), "being edited by chiara@desktop");
+        // Owner is mandatory
+        assert!(FileLock::parse("since: 2021-08-01T10:00:00+0200\n").is_none());
+        assert!(FileLock::parse("owner:\n").is_none());
+        assert!(FileLock::parse("").is_none());
+    }
+
+    #[test]
+    fn test_filetransfer_lock_paths() {
+        assert_eq!(
+            FileLock::lock_path(Path::new("/etc/nginx/nginx.conf")),
+            PathBuf::from("/etc/nginx/.nginx.conf.lock")
+        );
+        assert_eq!(
+            FileLock::lock_path(Path::new("nginx.conf")),
+            PathBuf::from(".nginx.conf.lock")
+        );
+        assert_eq!(
+            FileLock::locked_name(".nginx.conf.lock"),
+            Some("nginx.conf")
+        );
+        assert_eq!(FileLock::locked_name("nginx.conf.lock"), None);
+        assert_eq!(FileLock::locked_name(".nginx.conf"), None);
+        assert_eq!(FileLock::locked_name(".lock"), None);
+    }
+}
@@ -27,18 +27,44 @@
  */
 // locals
 use crate::fs::{FsEntry, FsFile};
+use crate::utils::random::random_alphanumeric_with_len;
 // ext
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use thiserror::Error;
 use wildmatch::WildMatch;
 // exports
+pub mod builder;
+pub mod capabilities;
+pub mod delta;
+pub mod fetch;
 pub mod ftp_transfer;
+pub mod http_transfer;
+pub mod interactive;
+pub mod lock;
 pub mod params;
+pub mod plugins;
+pub mod proxy;
+pub mod registry;
 pub mod scp_transfer;
+pub mod security_key;
 pub mod sftp_transfer;
-
+pub mod slow_link;
+pub mod tls;
+pub mod tunnel;
+pub mod webdav_transfer;
+
+pub use builder::Builder;
+pub use capabilities::{Capabilities, Capability};
+pub use delta::{Op, Signature};
+pub use fetch::Fetch;
 pub use params::FileTransferParams;
+pub use proxy::Proxy;
+pub use slow_link::SlowLink;
+pub use tls::TlsOptions;
+// Needed to build the clients
+pub use crate::system::sshkey_storage::SshKeyStorage;
 
 /// ## FileTransferProtocol
 ///
@@ -48,7 +74,20 @@ pub use params::FileTransferParams;
 pub enum FileTransferProtocol {
     Sftp,
     Scp,
-    Ftp(bool), // Bool is for secure (true => ftps)
+    Ftp(bool),            // Bool is for secure (true => ftps)
+    WebDav(bool),         // Bool is for secure (true => https)
+    Http(bool),           // Bool is for secure (true => https); read-only
+    Custom(&'static str), // Protocol provided by a backend added to the `registry`; str is its name
+}
+
+/// ## SymlinkMode
+///
+/// SymlinkMode defines what recursive transfers do with symbolic links
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SymlinkMode {
+    Skip,     // Don't transfer symbolic links
+    Follow,   // Transfer the entry the link points to
+    Recreate, // Create a link with the same target on the destination
 }
 
 /// ## FileTransferError
@@ -67,6 +106,33 @@ impl FileTransferError {
     pub fn kind(&self) -> FileTransferErrorType {
         self.code
     }
+
+    /// ### msg
+    ///
+    /// Returns the error message, if any
+    pub fn msg(&self) -> Option<&str> {
+        self.msg.as_deref()
+    }
+
+    /// ### is_permission_denied
+    ///
+    /// Returns whether the error has been caused by a lack of permissions.
+    /// Protocols report it differently, so the message is checked too
+    pub fn is_permission_denied(&self) -> bool {
+        self.code == FileTransferErrorType::PexError
+            || self
+                .msg
+                .as_deref()
+                .map(|x| x.to_lowercase().contains("permission denied"))
+                .unwrap_or(false)
+    }
+
+    /// ### is_timeout
+    ///
+    /// Returns whether the operation has been interrupted because the remote stopped responding
+    pub fn is_timeout(&self) -> bool {
+        self.code == FileTransferErrorType::Timeout
+    }
 }
 
 /// ## FileTransferErrorType
@@ -76,6 +142,10 @@ impl FileTransferError {
 pub enum FileTransferErrorType {
     #[error("Authentication failed")]
     AuthenticationFailed,
+    #[error("Key passphrase required")]
+    KeyPassphraseRequired,
+    #[error("Banner acknowledgement required")]
+    BannerAcknowledgementRequired,
     #[error("Bad address syntax")]
     BadAddress,
     #[error("Connection error")]
@@ -94,6 +164,8 @@ pub enum FileTransferErrorType {
     PexError,
     #[error("Protocol error")]
     ProtocolError,
+    #[error("Operation timed out")]
+    Timeout,
     #[error("Uninitialized session")]
     UninitializedSession,
     #[error("Unsupported feature")]
@@ -127,6 +199,8 @@ impl std::fmt::Display for FileTransferError {
     }
 }
 
+impl std::error::Error for FileTransferError {}
+
 /// ## FileTransfer
 ///
 /// File transfer trait must be implemented by all the file transfers and defines the method used by a generic file transfer
@@ -145,6 +219,78 @@ pub trait FileTransfer {
         password: Option<String>,
     ) -> Result<Option<String>, FileTransferError>;
 
+    /// ### set_proxy
+    ///
+    /// Set the proxy to use to reach the remote server at the next `connect`.
+    /// Protocols which can't be tunneled through a proxy must not re-implement this method
+    fn set_proxy(&mut self, proxy: Option<Proxy>) -> Result<(), FileTransferError> {
+        match proxy {
+            None => Ok(()),
+            Some(_) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::UnsupportedFeature,
+                String::from("this protocol can't be used through a proxy"),
+            )),
+        }
+    }
+
+    /// ### set_tls
+    ///
+    /// Set the TLS options (client certificate, trusted certificate authorities) to use at the next `connect`.
+    /// Protocols which don't use TLS must not re-implement this method
+    fn set_tls(&mut self, tls: Option<TlsOptions>) -> Result<(), FileTransferError> {
+        match tls {
+            None => Ok(()),
+            Some(_) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::UnsupportedFeature,
+                String::from("this protocol doesn't support TLS options"),
+            )),
+        }
+    }
+
+    /// ### set_agent_forwarding
+    ///
+    /// Set whether to forward the local ssh agent to the commands run with `exec`.
+    /// Protocols which can't run commands through ssh must not re-implement this method
+    fn set_agent_forwarding(&mut self, forward: bool) -> Result<(), FileTransferError> {
+        match forward {
+            false => Ok(()),
+            true => Err(FileTransferError::new_ex(
+                FileTransferErrorType::UnsupportedFeature,
+                String::from("this protocol doesn't support agent forwarding"),
+            )),
+        }
+    }
+
+    /// ### set_compression
+    ///
+    /// Set whether to compress the data exchanged with the remote server at the next `connect`.
+    /// Protocols which don't support compression must not re-implement this method
+    fn set_compression(&mut self, _compress: bool) {}
+
+    /// ### set_key_passphrase
+    ///
+    /// Set the passphrase to decrypt the private key with at the next `connect`.
+    /// When the key is protected and the passphrase is missing or wrong, `connect` must return an error of
+    /// kind `FileTransferErrorType::KeyPassphraseRequired`, whose message is the path of the key.
+    /// Protocols which don't authenticate with keys must not re-implement this method
+    fn set_key_passphrase(&mut self, _passphrase: Option<String>) {}
+
+    /// ### set_banner_acknowledged
+    ///
+    /// Set whether the user has read the banner sent by the server before authentication.
+    /// When the server sends a banner which hasn't been acknowledged, `connect` must return an error of
+    /// kind `FileTransferErrorType::BannerAcknowledgementRequired`, whose message is the banner.
+    /// Protocols which can't receive banners must not re-implement this method
+    fn set_banner_acknowledged(&mut self, _acknowledged: bool) {}
+
+    /// ### set_stall_timeout
+    ///
+    /// Set how long a blocking operation may wait for the remote before giving up; `None` waits forever.
+    /// Stalled operations must fail with an error of kind `FileTransferErrorType::Timeout`,
+    /// while stalled streams must fail with an I/O error of kind `std::io::ErrorKind::TimedOut`.
+    /// Protocols which can't detect stalls must not re-implement this method
+    fn set_stall_timeout(&mut self, _timeout: Option<Duration>) {}
+
     /// ### disconnect
     ///
     /// Disconnect from the remote server
@@ -156,6 +302,15 @@ pub trait FileTransfer {
     /// Indicates whether the client is connected to remote
     fn is_connected(&self) -> bool;
 
+    /// ### capabilities
+    ///
+    /// Probe which optional operations the connected server supports.
+    /// It's called once after `connect`; probes must not change anything on the remote.
+    /// Protocols which support none of them must not re-implement this method
+    fn capabilities(&mut self) -> Capabilities {
+        Capabilities::default()
+    }
+
     /// ### pwd
     ///
     /// Print working directory
@@ -185,16 +340,118 @@ pub trait FileTransfer {
     /// In case the directory already exists, it must return an Error of kind `FileTransferErrorType::DirectoryAlreadyExists`
     fn mkdir(&mut self, dir: &Path) -> Result<(), FileTransferError>;
 
+    /// ### mkdir_all
+    ///
+    /// Make directory and all of its missing parents (as `mkdir -p` does).
+    /// Directories which already exist are not reported as errors.
+    /// Protocols which provide a faster way to do so should re-implement this method
+    fn mkdir_all(&mut self, dir: &Path) -> Result<(), FileTransferError> {
+        if !self.is_connected() {
+            return Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            ));
+        }
+        self.iter_mkdir(dir)
+    }
+
+    /// ### iter_mkdir
+    ///
+    /// Create `dir` and each of its ancestors, starting from the outermost one, through `mkdir`.
+    /// NOTE: DON'T RE-IMPLEMENT THIS FUNCTION
+    /// NOTE: don't call this method from outside; consider it as private
+    fn iter_mkdir(&mut self, dir: &Path) -> Result<(), FileTransferError> {
+        let mut ancestors: Vec<&Path> = dir
+            .ancestors()
+            .filter(|x| !x.as_os_str().is_empty() && x.parent().is_some())
+            .collect();
+        ancestors.reverse();
+        for ancestor in ancestors.into_iter() {
+            match self.mkdir(ancestor) {
+                Ok(_) => {}
+                Err(err) if err.kind() == FileTransferErrorType::DirectoryAlreadyExists => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
     /// ### remove
     ///
     /// Remove a file or a directory
     fn remove(&mut self, file: &FsEntry) -> Result<(), FileTransferError>;
 
+    /// ### check_writable
+    ///
+    /// Check whether entries can be created into `dir`, creating and then removing a probe directory into it.
+    /// Used to fail fast before transferring many entries
+    fn check_writable(&mut self, dir: &Path) -> Result<(), FileTransferError> {
+        if !self.is_connected() {
+            return Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            ));
+        }
+        let mut probe: PathBuf = dir.to_path_buf();
+        probe.push(format!(
+            ".termscp-probe-{}",
+            random_alphanumeric_with_len(8)
+        ));
+        debug!("Checking whether {} is writable", dir.display());
+        self.mkdir(probe.as_path())?;
+        // Cleanup; the directory is writable anyway
+        if let Err(err) = self
+            .stat(probe.as_path())
+            .and_then(|entry| self.remove(&entry))
+        {
+            warn!("Could not remove probe {}: {}", probe.display(), err);
+        }
+        Ok(())
+    }
+
     /// ### rename
     ///
     /// Rename file or a directory
     fn rename(&mut self, file: &FsEntry, dst: &Path) -> Result<(), FileTransferError>;
 
+    /// ### chmod
+    ///
+    /// Change the mode of the remote file at `path`.
+    /// Protocols which can't change permissions must not re-implement this method
+    fn chmod(&mut self, _path: &Path, _pex: (u8, u8, u8)) -> Result<(), FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
+    /// ### chown
+    ///
+    /// Change the owner of the remote file at `path`; `owner` has the `user[:group]` syntax.
+    /// Protocols which can't change owner must not re-implement this method
+    fn chown(&mut self, _path: &Path, _owner: &str) -> Result<(), FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
+    /// ### read_link
+    ///
+    /// Get the target of the symbolic link at `path`, as it's written in the link.
+    /// Protocols which can't handle symbolic links must not re-implement this method
+    fn read_link(&mut self, _path: &Path) -> Result<PathBuf, FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
+    /// ### symlink
+    ///
+    /// Create a symbolic link at `path` pointing to `target`.
+    /// Protocols which can't handle symbolic links must not re-implement this method
+    fn symlink(&mut self, _path: &Path, _target: &Path) -> Result<(), FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
     /// ### stat
     ///
     /// Stat file and return FsEntry
@@ -223,6 +480,23 @@ pub trait FileTransfer {
     /// Returns file and its size
     fn recv_file(&mut self, file: &FsFile) -> Result<Box<dyn Read>, FileTransferError>;
 
+    /// ### recv_file_range
+    ///
+    /// Receive `len` bytes of the remote file, starting from `offset`.
+    /// Like for `recv_file`, the stream must be finalized with `on_recv`.
+    /// Protocols which can't read files from an offset must not re-implement this method;
+    /// the ones which can must report `Capability::RangedReads`
+    fn recv_file_range(
+        &mut self,
+        _file: &FsFile,
+        _offset: u64,
+        _len: u64,
+    ) -> Result<Box<dyn Read>, FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
     /// ### on_sent
     ///
     /// Finalize send method.
@@ -241,6 +515,38 @@ pub trait FileTransfer {
     /// You must call this method each time you want to finalize the read of the remote file.
     fn on_recv(&mut self, readable: Box<dyn Read>) -> Result<(), FileTransferError>;
 
+    /// ### signature
+    ///
+    /// Get the signature of the remote file at `path`, split into blocks of `block_size` bytes.
+    /// Along with `rebuild`, it allows to update a remote file sending only the data it doesn't already have.
+    /// Protocols which can't rebuild remote files must not re-implement these methods
+    fn signature(
+        &mut self,
+        _path: &Path,
+        _block_size: u64,
+    ) -> Result<Signature, FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
+    /// ### rebuild
+    ///
+    /// Rebuild the remote file at `path` following `ops`, copying its own blocks of `block_size` bytes and taking the
+    /// literal data, in the same order, from the remote file at `literal`, which is removed afterwards.
+    /// The new file replaces `path` only once complete, keeping its permissions: on error `path` is left untouched
+    fn rebuild(
+        &mut self,
+        _path: &Path,
+        _literal: &Path,
+        _block_size: u64,
+        _ops: &[Op],
+    ) -> Result<(), FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
     /// ### find
     ///
     /// Find files from current directory (in all subdirectories) whose name matches the provided search
@@ -314,6 +620,15 @@ impl std::string::ToString for FileTransferProtocol {
             },
             FileTransferProtocol::Scp => "SCP",
             FileTransferProtocol::Sftp => "SFTP",
+            FileTransferProtocol::WebDav(secure) => match secure {
+                true => "WEBDAVS",
+                false => "WEBDAV",
+            },
+            FileTransferProtocol::Http(secure) => match secure {
+                true => "HTTPS",
+                false => "HTTP",
+            },
+            FileTransferProtocol::Custom(name) => name,
         })
     }
 }
@@ -326,7 +641,33 @@ impl std::str::FromStr for FileTransferProtocol {
             "FTPS" => Ok(FileTransferProtocol::Ftp(true)),
             "SCP" => Ok(FileTransferProtocol::Scp),
             "SFTP" => Ok(FileTransferProtocol::Sftp),
-            _ => Err(s.to_string()),
+            "WEBDAV" => Ok(FileTransferProtocol::WebDav(false)),
+            "WEBDAVS" => Ok(FileTransferProtocol::WebDav(true)),
+            "HTTP" => Ok(FileTransferProtocol::Http(false)),
+            "HTTPS" => Ok(FileTransferProtocol::Http(true)),
+            _ => registry::find(s).ok_or_else(|| s.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for SymlinkMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            SymlinkMode::Skip => "skip",
+            SymlinkMode::Follow => "follow",
+            SymlinkMode::Recreate => "recreate",
+        })
+    }
+}
+
+impl std::str::FromStr for SymlinkMode {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Ok(SymlinkMode::Skip),
+            "follow" => Ok(SymlinkMode::Follow),
+            "recreate" => Ok(SymlinkMode::Recreate),
+            _ => Err(()),
         }
     }
 }
@@ -385,6 +726,22 @@ mod tests {
             FileTransferProtocol::from_str("scp").ok().unwrap(),
             FileTransferProtocol::Scp
         );
+        assert_eq!(
+            FileTransferProtocol::from_str("webdav").ok().unwrap(),
+            FileTransferProtocol::WebDav(false)
+        );
+        assert_eq!(
+            FileTransferProtocol::from_str("WEBDAVS").ok().unwrap(),
+            FileTransferProtocol::WebDav(true)
+        );
+        assert_eq!(
+            FileTransferProtocol::from_str("http").ok().unwrap(),
+            FileTransferProtocol::Http(false)
+        );
+        assert_eq!(
+            FileTransferProtocol::from_str("HTTPS").ok().unwrap(),
+            FileTransferProtocol::Http(true)
+        );
         // Error
         assert!(FileTransferProtocol::from_str("dummy").is_err());
         // To String
@@ -398,6 +755,43 @@ mod tests {
         );
         assert_eq!(FileTransferProtocol::Scp.to_string(), String::from("SCP"));
         assert_eq!(FileTransferProtocol::Sftp.to_string(), String::from("SFTP"));
+        assert_eq!(
+            FileTransferProtocol::WebDav(false).to_string(),
+            String::from("WEBDAV")
+        );
+        assert_eq!(
+            FileTransferProtocol::WebDav(true).to_string(),
+            String::from("WEBDAVS")
+        );
+        assert_eq!(
+            FileTransferProtocol::Http(false).to_string(),
+            String::from("HTTP")
+        );
+        assert_eq!(
+            FileTransferProtocol::Http(true).to_string(),
+            String::from("HTTPS")
+        );
+        assert_eq!(
+            FileTransferProtocol::Custom("GOPHER").to_string(),
+            String::from("GOPHER")
+        );
+    }
+
+    #[test]
+    fn test_filetransfer_mod_symlink_mode() {
+        assert_eq!(SymlinkMode::from_str("skip").unwrap(), SymlinkMode::Skip);
+        assert_eq!(
+            SymlinkMode::from_str("FOLLOW").unwrap(),
+            SymlinkMode::Follow
+        );
+        assert_eq!(
+            SymlinkMode::from_str("recreate").unwrap(),
+            SymlinkMode::Recreate
+        );
+        assert!(SymlinkMode::from_str("copy").is_err());
+        assert_eq!(SymlinkMode::Skip.to_string().as_str(), "skip");
+        assert_eq!(SymlinkMode::Follow.to_string().as_str(), "follow");
+        assert_eq!(SymlinkMode::Recreate.to_string().as_str(), "recreate");
     }
 
     #[test]
@@ -407,6 +801,19 @@ mod tests {
             String::from("non va una mazza"),
         );
         assert_eq!(*err.msg.as_ref().unwrap(), String::from("non va una mazza"));
+        assert_eq!(err.is_permission_denied(), false);
+        assert_eq!(
+            FileTransferError::new(FileTransferErrorType::PexError).is_permission_denied(),
+            true
+        );
+        assert_eq!(
+            FileTransferError::new_ex(
+                FileTransferErrorType::DirStatFailed,
+                String::from("[-31] Permission denied"),
+            )
+            .is_permission_denied(),
+            true
+        );
         assert_eq!(
             format!("{}", err),
             String::from("No such file or directory (non va una mazza)")
@@ -418,6 +825,20 @@ mod tests {
             ),
             String::from("Authentication failed")
         );
+        assert_eq!(
+            format!(
+                "{}",
+                FileTransferError::new(FileTransferErrorType::KeyPassphraseRequired)
+            ),
+            String::from("Key passphrase required")
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                FileTransferError::new(FileTransferErrorType::BannerAcknowledgementRequired)
+            ),
+            String::from("Banner acknowledgement required")
+        );
         assert_eq!(
             format!(
                 "{}",
@@ -488,6 +909,15 @@ mod tests {
             ),
             String::from("Unsupported feature")
         );
+        assert_eq!(
+            format!("{}", FileTransferError::new(FileTransferErrorType::Timeout)),
+            String::from("Operation timed out")
+        );
+        assert_eq!(
+            FileTransferError::new(FileTransferErrorType::Timeout).is_timeout(),
+            true
+        );
+        assert_eq!(err.is_timeout(), false);
         let err = FileTransferError::new(FileTransferErrorType::UnsupportedFeature);
         assert_eq!(err.kind(), FileTransferErrorType::UnsupportedFeature);
     }
@@ -0,0 +1,448 @@
+//! ## Registry
+//!
+//! `registry` is the module which keeps track of the protocol backends available in termscp.
+//! Built-in protocols are registered at startup; other protocols can be added at runtime through `register`,
+//! or built into the termscp binary as plugins (see `plugins`)
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{
+    ftp_transfer::FtpFileTransfer, http_transfer::HttpFileTransfer, plugins,
+    scp_transfer::ScpFileTransfer, sftp_transfer::SftpFileTransfer,
+    webdav_transfer::WebDavFileTransfer, FileTransfer, FileTransferError, FileTransferErrorType,
+    FileTransferProtocol,
+};
+use crate::system::sshkey_storage::SshKeyStorage;
+
+use std::sync::RwLock;
+use thiserror::Error;
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Registry> = RwLock::new(Registry::builtin());
+}
+
+/// ## ProtocolBackend
+///
+/// A protocol backend provides the file transfer client for a protocol.
+/// Implement this trait and `register` the backend to add a protocol to termscp;
+/// the protocol must be a `FileTransferProtocol::Custom`, whose name is used in the protocol radio,
+/// in bookmarks and as scheme in the remote address (e.g. `s3://bucket`)
+pub trait ProtocolBackend: Send + Sync {
+    /// ### protocol
+    ///
+    /// Protocol implemented by the backend
+    fn protocol(&self) -> FileTransferProtocol;
+
+    /// ### default_port
+    ///
+    /// Port used when the user doesn't provide one
+    fn default_port(&self) -> u16;
+
+    /// ### build
+    ///
+    /// Instantiate a new client for the protocol.
+    /// `ssh_keys` contains the keys configured by the user, in case the protocol authenticates with them
+    fn build(&self, ssh_keys: SshKeyStorage) -> Result<Box<dyn FileTransfer>, FileTransferError>;
+}
+
+/// ## Plugin
+///
+/// Constructor of a third-party backend built into the termscp binary; see `plugins`
+pub type Plugin = fn() -> Box<dyn ProtocolBackend>;
+
+/// ## RegistryError
+///
+/// RegistryError describes why a backend couldn't be registered
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    #[error("Protocol \"{0}\" is already registered")]
+    AlreadyRegistered(String),
+    #[error("Bad protocol name \"{0}\": only ASCII letters are allowed")]
+    BadName(String),
+    #[error("Protocol \"{0}\" is built-in; only custom protocols can be registered")]
+    NotCustom(String),
+    #[error("Protocol \"{0}\" is not built-in")]
+    NotBuiltin(String),
+}
+
+/// ## Registry
+///
+/// Registry holds the protocol backends, in the order they're displayed to the user
+struct Registry {
+    backends: Vec<Box<dyn ProtocolBackend>>,
+}
+
+impl Registry {
+    /// ### builtin
+    ///
+    /// Instantiate a registry with the protocols shipped with termscp
+    fn builtin() -> Self {
+        let protocols: [FileTransferProtocol; 8] = [
+            FileTransferProtocol::Sftp,
+            FileTransferProtocol::Scp,
+            FileTransferProtocol::Ftp(false),
+            FileTransferProtocol::Ftp(true),
+            FileTransferProtocol::WebDav(false),
+            FileTransferProtocol::WebDav(true),
+            FileTransferProtocol::Http(false),
+            FileTransferProtocol::Http(true),
+        ];
+        Self {
+            backends: protocols
+                .iter()
+                .filter_map(|x| Builtin::new(*x).ok())
+                .map(|x| Box::new(x) as Box<dyn ProtocolBackend>)
+                .collect(),
+        }
+    }
+
+    /// ### register
+    ///
+    /// Add `backend` to the registry
+    fn register(&mut self, backend: Box<dyn ProtocolBackend>) -> Result<(), RegistryError> {
+        let name: &str = match backend.protocol() {
+            FileTransferProtocol::Custom(name) => name,
+            protocol => return Err(RegistryError::NotCustom(protocol.to_string())),
+        };
+        if name.is_empty() || !name.chars().all(|x| x.is_ascii_alphabetic()) {
+            return Err(RegistryError::BadName(name.to_string()));
+        }
+        if self.find(name).is_some() {
+            return Err(RegistryError::AlreadyRegistered(name.to_string()));
+        }
+        self.backends.push(backend);
+        Ok(())
+    }
+
+    /// ### load
+    ///
+    /// Register the backends built by `plugins`.
+    /// Returns the error of each backend which couldn't be registered; the others are registered anyway
+    fn load(&mut self, plugins: &[Plugin]) -> Vec<RegistryError> {
+        plugins
+            .iter()
+            .filter_map(|plugin| self.register(plugin()).err())
+            .collect()
+    }
+
+    /// ### get
+    ///
+    /// Get the backend for `protocol`
+    fn get(&self, protocol: FileTransferProtocol) -> Option<&dyn ProtocolBackend> {
+        self.backends
+            .iter()
+            .find(|x| x.protocol() == protocol)
+            .map(|x| x.as_ref())
+    }
+
+    /// ### find
+    ///
+    /// Find the protocol whose name is `name` (case insensitive)
+    fn find(&self, name: &str) -> Option<FileTransferProtocol> {
+        self.backends
+            .iter()
+            .map(|x| x.protocol())
+            .find(|x| x.to_string().eq_ignore_ascii_case(name))
+    }
+}
+
+/// ## Builtin
+///
+/// Backend for the protocols shipped with termscp
+struct Builtin {
+    protocol: FileTransferProtocol,
+    port: u16,
+}
+
+impl Builtin {
+    /// ### new
+    ///
+    /// Instantiate the backend for the built-in `protocol`; custom protocols have no built-in backend
+    fn new(protocol: FileTransferProtocol) -> Result<Self, RegistryError> {
+        let port: u16 = match protocol {
+            FileTransferProtocol::Sftp | FileTransferProtocol::Scp => 22,
+            FileTransferProtocol::Ftp(_) => 21,
+            FileTransferProtocol::WebDav(false) | FileTransferProtocol::Http(false) => 80,
+            FileTransferProtocol::WebDav(true) | FileTransferProtocol::Http(true) => 443,
+            FileTransferProtocol::Custom(name) => {
+                return Err(RegistryError::NotBuiltin(name.to_string()))
+            }
+        };
+        Ok(Self { protocol, port })
+    }
+}
+
+impl ProtocolBackend for Builtin {
+    fn protocol(&self) -> FileTransferProtocol {
+        self.protocol
+    }
+
+    fn default_port(&self) -> u16 {
+        self.port
+    }
+
+    fn build(&self, ssh_keys: SshKeyStorage) -> Result<Box<dyn FileTransfer>, FileTransferError> {
+        match self.protocol {
+            FileTransferProtocol::Sftp => Ok(Box::new(SftpFileTransfer::new(ssh_keys))),
+            FileTransferProtocol::Ftp(ftps) => Ok(Box::new(FtpFileTransfer::new(ftps))),
+            FileTransferProtocol::Scp => Ok(Box::new(ScpFileTransfer::new(ssh_keys))),
+            FileTransferProtocol::WebDav(secure) => Ok(Box::new(WebDavFileTransfer::new(secure))),
+            FileTransferProtocol::Http(secure) => Ok(Box::new(HttpFileTransfer::new(secure))),
+            FileTransferProtocol::Custom(name) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::UnsupportedFeature,
+                format!("Protocol {} is not built-in", name),
+            )),
+        }
+    }
+}
+
+/// ### register
+///
+/// Register a backend, making its protocol available everywhere in termscp.
+/// Backends must be registered before the user interface is started
+pub fn register(backend: Box<dyn ProtocolBackend>) -> Result<(), RegistryError> {
+    REGISTRY.write().unwrap().register(backend)
+}
+
+/// ### load_plugins
+///
+/// Register the third-party backends built into the termscp binary (see `plugins`).
+/// The termscp binary calls it at startup, before the user interface is started.
+/// Returns the error of each backend which couldn't be registered
+pub fn load_plugins() -> Vec<RegistryError> {
+    REGISTRY.write().unwrap().load(plugins::PLUGINS)
+}
+
+/// ### protocols
+///
+/// Get all the registered protocols, built-in ones first
+pub fn protocols() -> Vec<FileTransferProtocol> {
+    REGISTRY
+        .read()
+        .unwrap()
+        .backends
+        .iter()
+        .map(|x| x.protocol())
+        .collect()
+}
+
+/// ### find
+///
+/// Find the registered protocol whose name is `name` (case insensitive)
+pub fn find(name: &str) -> Option<FileTransferProtocol> {
+    REGISTRY.read().unwrap().find(name)
+}
+
+/// ### default_port
+///
+/// Get the default port for `protocol`, if registered
+pub fn default_port(protocol: FileTransferProtocol) -> Option<u16> {
+    REGISTRY
+        .read()
+        .unwrap()
+        .get(protocol)
+        .map(|x| x.default_port())
+}
+
+/// ### build
+///
+/// Instantiate the client for `protocol`; fails if the protocol is not registered or if its backend fails
+pub fn build(
+    protocol: FileTransferProtocol,
+    ssh_keys: SshKeyStorage,
+) -> Result<Box<dyn FileTransfer>, FileTransferError> {
+    match REGISTRY.read().unwrap().get(protocol) {
+        Some(backend) => backend.build(ssh_keys),
+        None => Err(FileTransferError::new_ex(
+            FileTransferErrorType::UnsupportedFeature,
+            format!("Protocol {} has not been registered", protocol.to_string()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    struct DummyBackend(&'static str);
+
+    impl ProtocolBackend for DummyBackend {
+        fn protocol(&self) -> FileTransferProtocol {
+            FileTransferProtocol::Custom(self.0)
+        }
+
+        fn default_port(&self) -> u16 {
+            8080
+        }
+
+        fn build(
+            &self,
+            ssh_keys: SshKeyStorage,
+        ) -> Result<Box<dyn FileTransfer>, FileTransferError> {
+            Ok(Box::new(SftpFileTransfer::new(ssh_keys)))
+        }
+    }
+
+    #[test]
+    fn test_filetransfer_registry_builtin() {
+        let registry: Registry = Registry::builtin();
+        assert_eq!(registry.backends.len(), 8);
+        assert_eq!(registry.backends[0].protocol(), FileTransferProtocol::Sftp);
+        assert_eq!(
+            registry.find("webdavs"),
+            Some(FileTransferProtocol::WebDav(true))
+        );
+        assert_eq!(registry.find("S3"), None);
+        assert_eq!(
+            registry
+                .get(FileTransferProtocol::Ftp(true))
+                .unwrap()
+                .default_port(),
+            21
+        );
+        assert_eq!(
+            registry
+                .get(FileTransferProtocol::Http(true))
+                .unwrap()
+                .default_port(),
+            443
+        );
+        assert!(registry.get(FileTransferProtocol::Custom("S3")).is_none());
+        // Custom protocols are not built-in
+        assert_eq!(
+            Builtin::new(FileTransferProtocol::Custom("S3"))
+                .err()
+                .unwrap(),
+            RegistryError::NotBuiltin(String::from("S3"))
+        );
+        let backend: Builtin = Builtin {
+            protocol: FileTransferProtocol::Custom("S3"),
+            port: 0,
+        };
+        assert_eq!(
+            backend.build(SshKeyStorage::empty()).err().unwrap().kind(),
+            FileTransferErrorType::UnsupportedFeature
+        );
+    }
+
+    #[test]
+    fn test_filetransfer_registry_load() {
+        let mut registry: Registry = Registry::builtin();
+        let plugins: [Plugin; 3] = [
+            || Box::new(DummyBackend("GCS")),
+            || Box::new(DummyBackend("my-fs")),
+            || Box::new(DummyBackend("AZURE")),
+        ];
+        assert_eq!(
+            registry.load(&plugins),
+            vec![RegistryError::BadName(String::from("my-fs"))]
+        );
+        assert_eq!(
+            registry.find("gcs"),
+            Some(FileTransferProtocol::Custom("GCS"))
+        );
+        assert_eq!(
+            registry.find("azure"),
+            Some(FileTransferProtocol::Custom("AZURE"))
+        );
+        assert_eq!(registry.backends.len(), 10);
+    }
+
+    #[test]
+    fn test_filetransfer_registry_register() {
+        let mut registry: Registry = Registry::builtin();
+        assert!(registry.register(Box::new(DummyBackend("GCS"))).is_ok());
+        assert_eq!(
+            registry.find("gcs"),
+            Some(FileTransferProtocol::Custom("GCS"))
+        );
+        assert_eq!(
+            registry
+                .get(FileTransferProtocol::Custom("GCS"))
+                .unwrap()
+                .default_port(),
+            8080
+        );
+        // Errors
+        assert_eq!(
+            registry
+                .register(Box::new(DummyBackend("gcs")))
+                .err()
+                .unwrap(),
+            RegistryError::AlreadyRegistered(String::from("gcs"))
+        );
+        assert_eq!(
+            registry
+                .register(Box::new(DummyBackend("sftp")))
+                .err()
+                .unwrap(),
+            RegistryError::AlreadyRegistered(String::from("sftp"))
+        );
+        assert_eq!(
+            registry
+                .register(Box::new(DummyBackend("my-fs")))
+                .err()
+                .unwrap(),
+            RegistryError::BadName(String::from("my-fs"))
+        );
+        assert_eq!(
+            registry.register(Box::new(DummyBackend(""))).err().unwrap(),
+            RegistryError::BadName(String::new())
+        );
+        assert_eq!(
+            registry
+                .register(Box::new(DummyBackend("S3")))
+                .err()
+                .unwrap(),
+            RegistryError::BadName(String::from("S3"))
+        );
+        assert_eq!(
+            registry
+                .register(Box::new(Builtin::new(FileTransferProtocol::Scp).unwrap()))
+                .err()
+                .unwrap(),
+            RegistryError::NotCustom(String::from("SCP"))
+        );
+        assert_eq!(registry.backends.len(), 9);
+    }
+
+    #[test]
+    fn test_filetransfer_registry_global() {
+        // NOTE: don't register anything here: the registry is shared with the other tests
+        assert_eq!(find("ftps"), Some(FileTransferProtocol::Ftp(true)));
+        assert!(find("DUMMY").is_none());
+        assert_eq!(protocols()[1], FileTransferProtocol::Scp);
+        assert_eq!(default_port(FileTransferProtocol::Scp), Some(22));
+        assert_eq!(default_port(FileTransferProtocol::Custom("DUMMY")), None);
+        assert!(build(FileTransferProtocol::Http(false), SshKeyStorage::empty()).is_ok());
+        assert!(build(
+            FileTransferProtocol::Custom("DUMMY"),
+            SshKeyStorage::empty()
+        )
+        .is_err());
+    }
+}
@@ -26,21 +26,30 @@
  * SOFTWARE.
  */
 // Locals
-use super::{FileTransfer, FileTransferError, FileTransferErrorType};
+use super::delta::{self, Op, Signature};
+use super::{
+    interactive, security_key, tunnel, Capabilities, Capability, FileTransfer, FileTransferError,
+    FileTransferErrorType, Proxy,
+};
 use crate::fs::{FsDirectory, FsEntry, FsFile, UnixPex};
+use crate::system::ssh_config::SshHostParams;
 use crate::system::sshkey_storage::SshKeyStorage;
 use crate::utils::fmt::{fmt_time, shadow_password};
 use crate::utils::parser::parse_lstime;
 
 // Includes
 use regex::Regex;
-use ssh2::{Channel, Session};
+use ssh2::{Channel, ErrorCode, Session};
 use std::io::{BufReader, BufWriter, Read, Write};
-use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::net::TcpStream;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
+/// libssh2 error reported when the private key can't be read or decrypted
+const LIBSSH2_ERROR_FILE: i32 = -16;
+const LIBSSH2_ERROR_TIMEOUT: i32 = -9;
+
 /// ## ScpFileTransfer
 ///
 /// SCP file transfer structure
@@ -48,6 +57,12 @@ pub struct ScpFileTransfer {
     session: Option<Session>,
     wrkdir: PathBuf,
     key_storage: SshKeyStorage,
+    key_passphrase: Option<String>,
+    banner_acknowledged: bool,
+    proxy: Option<Proxy>,
+    agent_forwarding: bool,
+    compression: bool,
+    stall_timeout: Option<Duration>,
 }
 
 impl ScpFileTransfer {
@@ -59,9 +74,37 @@ impl ScpFileTransfer {
             session: None,
             wrkdir: PathBuf::from("~"),
             key_storage,
+            key_passphrase: None,
+            banner_acknowledged: false,
+            proxy: None,
+            agent_forwarding: false,
+            compression: false,
+            stall_timeout: None,
+        }
+    }
+
+    /// ### interactive_only
+    ///
+    /// Returns whether the server accepts passwords only through keyboard-interactive prompts
+    fn interactive_only(session: &Session, username: &str) -> bool {
+        match session.auth_methods(username) {
+            Ok(methods) => {
+                let methods: Vec<&str> = methods.split(',').collect();
+                !methods.contains(&"password") && methods.contains(&"keyboard-interactive")
+            }
+            Err(_) => false,
         }
     }
 
+    /// ### stall_timeout_millis
+    ///
+    /// Convert the stall timeout into the libssh2 session timeout, where 0 means no timeout
+    fn stall_timeout_millis(timeout: Option<Duration>) -> u32 {
+        timeout
+            .map(|x| x.as_millis().min(u32::MAX as u128) as u32)
+            .unwrap_or(0)
+    }
+
     /// ### resolve
     ///
     /// Fix provided path; on Windows fixes the backslashes, converting them to slashes
@@ -271,6 +314,43 @@ impl ScpFileTransfer {
         (filename, symlink)
     }
 
+    /// ### shell_set_attr
+    ///
+    /// Run `cmd` (`chmod` or `chown`) with `arg` on the remote file at `path`
+    fn shell_set_attr(
+        &mut self,
+        cmd: &str,
+        arg: &str,
+        path: &Path,
+    ) -> Result<(), FileTransferError> {
+        match self.is_connected() {
+            true => {
+                let path: PathBuf = Self::absolutize(self.wrkdir.as_path(), path);
+                info!("Running {} {} on {}", cmd, arg, path.display());
+                let p: PathBuf = self.wrkdir.clone();
+                match self.perform_shell_cmd_with_path(
+                    p.as_path(),
+                    format!("{} \"{}\" \"{}\"; echo $?", cmd, arg, path.display()).as_str(),
+                ) {
+                    Ok(output) => match output.as_str().trim() == "0" {
+                        true => Ok(()),
+                        false => Err(FileTransferError::new_ex(
+                            FileTransferErrorType::PexError,
+                            format!("\"{}\"", path.display()),
+                        )),
+                    },
+                    Err(err) => Err(FileTransferError::new_ex(
+                        FileTransferErrorType::ProtocolError,
+                        err.to_string(),
+                    )),
+                }
+            }
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
     /// ### perform_shell_cmd_with
     ///
     /// Perform a shell command, but change directory to specified path first
@@ -287,12 +367,30 @@ impl ScpFileTransfer {
     /// Perform a shell command and read the output from shell
     /// This operation is, obviously, blocking.
     fn perform_shell_cmd(&mut self, cmd: &str) -> Result<String, FileTransferError> {
+        self.perform_shell_cmd_ex(cmd, false)
+    }
+
+    /// ### perform_shell_cmd_ex
+    ///
+    /// Perform a shell command and read the output from shell.
+    /// If `forward_agent` is true, the local ssh agent is forwarded to the command
+    fn perform_shell_cmd_ex(
+        &mut self,
+        cmd: &str,
+        forward_agent: bool,
+    ) -> Result<String, FileTransferError> {
         match self.session.as_mut() {
             Some(session) => {
                 debug!("Running command: {}", cmd);
                 // Create channel
                 let mut channel: Channel = match session.channel_session() {
                     Ok(ch) => ch,
+                    Err(err) if err.code() == ErrorCode::Session(LIBSSH2_ERROR_TIMEOUT) => {
+                        return Err(FileTransferError::new_ex(
+                            FileTransferErrorType::Timeout,
+                            format!("Could not open channel: {}", err),
+                        ))
+                    }
                     Err(err) => {
                         return Err(FileTransferError::new_ex(
                             FileTransferErrorType::ProtocolError,
@@ -300,6 +398,12 @@ impl ScpFileTransfer {
                         ))
                     }
                 };
+                // Forward agent
+                if forward_agent {
+                    if let Err(err) = channel.request_auth_agent_forwarding() {
+                        warn!("Could not forward ssh agent: {}", err);
+                    }
+                }
                 // Execute command
                 if let Err(err) = channel.exec(cmd) {
                     return Err(FileTransferError::new_ex(
@@ -316,6 +420,12 @@ impl ScpFileTransfer {
                         debug!("Command output: {}", output);
                         Ok(output)
                     }
+                    Err(err) if err.kind() == std::io::ErrorKind::TimedOut => {
+                        Err(FileTransferError::new_ex(
+                            FileTransferErrorType::Timeout,
+                            format!("Could not read output: {}", err),
+                        ))
+                    }
                     Err(err) => Err(FileTransferError::new_ex(
                         FileTransferErrorType::ProtocolError,
                         format!("Could not read output: {}", err),
@@ -340,41 +450,32 @@ impl FileTransfer for ScpFileTransfer {
         username: Option<String>,
         password: Option<String>,
     ) -> Result<Option<String>, FileTransferError> {
-        // Setup tcp stream
-        info!("Connecting to {}:{}", address, port);
-        let socket_addresses: Vec<SocketAddr> =
-            match format!("{}:{}", address, port).to_socket_addrs() {
-                Ok(s) => s.collect(),
-                Err(err) => {
-                    return Err(FileTransferError::new_ex(
-                        FileTransferErrorType::BadAddress,
-                        err.to_string(),
-                    ))
-                }
-            };
-        let mut tcp: Option<TcpStream> = None;
-        // Try addresses
-        for socket_addr in socket_addresses.iter() {
-            debug!("Trying socket address {}", socket_addr);
-            match TcpStream::connect_timeout(socket_addr, Duration::from_secs(30)) {
-                Ok(stream) => {
-                    debug!("{} succeded", socket_addr);
-                    tcp = Some(stream);
-                    break;
-                }
-                Err(_) => continue,
-            }
+        // Resolve host through ssh config
+        let host_params: SshHostParams = self.key_storage.ssh_host(address.as_str());
+        let host_name: String = host_params
+            .host_name
+            .clone()
+            .unwrap_or_else(|| address.clone());
+        let port: u16 = match (port, host_params.port) {
+            (22, Some(port)) => port,
+            _ => port,
+        };
+        if host_name != address {
+            debug!(
+                "Resolved {} to {}:{} through ssh config",
+                address, host_name, port
+            );
         }
-        // If stream is None, return connection timeout
-        let tcp: TcpStream = match tcp {
-            Some(t) => t,
-            None => {
-                error!("No suitable socket address found; connection timeout");
-                return Err(FileTransferError::new_ex(
-                    FileTransferErrorType::ConnectionError,
-                    String::from("Connection timeout"),
-                ));
-            }
+        // Setup tcp stream
+        let tcp: TcpStream = match host_params.proxy_jump.as_deref() {
+            Some(jump) => tunnel::open_jump_stream(
+                jump,
+                &self.key_storage,
+                self.proxy.as_ref(),
+                host_name.as_str(),
+                port,
+            )?,
+            None => tunnel::open_stream(host_name.as_str(), port, self.proxy.as_ref())?,
         };
         // Create session
         let mut session: Session = match Session::new() {
@@ -389,6 +490,9 @@ impl FileTransfer for ScpFileTransfer {
         };
         // Set TCP stream
         session.set_tcp_stream(tcp);
+        session.set_timeout(Self::stall_timeout_millis(self.stall_timeout));
+        // Compression must be negotiated during the handshake
+        session.set_compress(self.compression);
         // Open connection
         debug!("Initializing handshake");
         if let Err(err) = session.handshake() {
@@ -398,35 +502,67 @@ impl FileTransfer for ScpFileTransfer {
                 err.to_string(),
             ));
         }
-        let username: String = match username {
-            Some(u) => u,
-            None => String::from(""),
-        };
+        let username: String = username.or(host_params.user).unwrap_or_default();
         // Check if it is possible to authenticate using a RSA key
         match self
             .key_storage
-            .resolve(address.as_str(), username.as_str())
+            .identity(address.as_str(), username.as_str())
         {
+            Some(sk_key) if SshKeyStorage::is_security_key(sk_key.as_path()) => {
+                debug!(
+                    "Authenticating with user {} and security key {}",
+                    username,
+                    sk_key.display()
+                );
+                security_key::userauth_security_key(&session, username.as_str(), sk_key.as_path())?;
+            }
             Some(rsa_key) => {
                 debug!(
                     "Authenticating with user {} and RSA key {}",
                     username,
                     rsa_key.display()
                 );
-                // Authenticate with RSA key
+                // Present the OpenSSH certificate of the key, if any
+                let certificate: Option<PathBuf> = self
+                    .key_storage
+                    .certificate(address.as_str(), rsa_key.as_path());
+                if let Some(certificate) = certificate.as_ref() {
+                    debug!("Using certificate {}", certificate.display());
+                }
+                // Authenticate with RSA key; the password is used as passphrase if none has been set
+                let passphrase: Option<&str> =
+                    self.key_passphrase.as_deref().or(password.as_deref());
                 if let Err(err) = session.userauth_pubkey_file(
                     username.as_str(),
-                    None,
+                    certificate.as_deref(),
                     rsa_key.as_path(),
-                    password.as_deref(),
+                    passphrase,
                 ) {
                     error!("Authentication failed: {}", err);
+                    // Key could not be decrypted
+                    if err.code() == ErrorCode::Session(LIBSSH2_ERROR_FILE)
+                        && SshKeyStorage::is_encrypted(rsa_key.as_path())
+                    {
+                        return Err(FileTransferError::new_ex(
+                            FileTransferErrorType::KeyPassphraseRequired,
+                            rsa_key.display().to_string(),
+                        ));
+                    }
                     return Err(FileTransferError::new_ex(
                         FileTransferErrorType::AuthenticationFailed,
                         err.to_string(),
                     ));
                 }
             }
+            None if Self::interactive_only(&session, username.as_str()) => {
+                // The server asks for the password through keyboard-interactive prompts
+                interactive::userauth_interactive(
+                    &session,
+                    username.as_str(),
+                    password.as_deref().unwrap_or(""),
+                    self.banner_acknowledged,
+                )?;
+            }
             None => {
                 // Proceeed with username/password authentication
                 debug!(
@@ -463,7 +599,58 @@ impl FileTransfer for ScpFileTransfer {
             "Connection established; working directory: {}",
             self.wrkdir.display()
         );
-        Ok(banner)
+        // Return message of the day as welcome message
+        Ok(self
+            .perform_shell_cmd("cat /etc/motd 2>/dev/null")
+            .ok()
+            .filter(|x| !x.trim().is_empty()))
+    }
+
+    /// ### set_proxy
+    ///
+    /// Set the proxy to connect to the remote server through
+    fn set_proxy(&mut self, proxy: Option<Proxy>) -> Result<(), FileTransferError> {
+        self.proxy = proxy;
+        Ok(())
+    }
+
+    /// ### set_agent_forwarding
+    ///
+    /// Set whether to forward the local ssh agent to the commands run with `exec`
+    fn set_agent_forwarding(&mut self, forward: bool) -> Result<(), FileTransferError> {
+        self.agent_forwarding = forward;
+        Ok(())
+    }
+
+    /// ### set_compression
+    ///
+    /// Set whether to enable zlib compression of the ssh transport at the next `connect`
+    fn set_compression(&mut self, compress: bool) {
+        self.compression = compress;
+    }
+
+    /// ### set_key_passphrase
+    ///
+    /// Set the passphrase to decrypt the private key with at the next `connect`
+    fn set_key_passphrase(&mut self, passphrase: Option<String>) {
+        self.key_passphrase = passphrase;
+    }
+
+    /// ### set_banner_acknowledged
+    ///
+    /// Set whether the banner sent with the keyboard-interactive prompts has been read by the user
+    fn set_banner_acknowledged(&mut self, acknowledged: bool) {
+        self.banner_acknowledged = acknowledged;
+    }
+
+    /// ### set_stall_timeout
+    ///
+    /// Set how long libssh2 may block waiting for the remote; applies to the current session too
+    fn set_stall_timeout(&mut self, timeout: Option<Duration>) {
+        self.stall_timeout = timeout;
+        if let Some(session) = self.session.as_ref() {
+            session.set_timeout(Self::stall_timeout_millis(timeout));
+        }
     }
 
     /// ### disconnect
@@ -499,6 +686,26 @@ impl FileTransfer for ScpFileTransfer {
         self.session.is_some()
     }
 
+    /// ### capabilities
+    ///
+    /// Everything is done through the shell, so probe it and `df`
+    fn capabilities(&mut self) -> Capabilities {
+        let shell: bool =
+            matches!(self.perform_shell_cmd("echo termscp"), Ok(x) if x.trim() == "termscp");
+        let p: PathBuf = self.wrkdir.clone();
+        let df: bool = shell
+            && matches!(
+                self.perform_shell_cmd_with_path(p.as_path(), "df -P . > /dev/null 2>&1; echo $?"),
+                Ok(x) if x.trim() == "0"
+            );
+        Capabilities::default()
+            .with(Capability::RenameOver, shell)
+            .with(Capability::SetStat, shell)
+            .with(Capability::StatVfs, df)
+            .with(Capability::Symlinks, shell)
+            .with(Capability::Exec, shell)
+    }
+
     /// ### pwd
     ///
     /// Print working directory
@@ -638,6 +845,7 @@ impl FileTransfer for ScpFileTransfer {
                         );
                         Ok(entries)
                     }
+                    Err(err) if err.is_timeout() => Err(err),
                     Err(err) => Err(FileTransferError::new_ex(
                         FileTransferErrorType::ProtocolError,
                         err.to_string(),
@@ -697,6 +905,40 @@ impl FileTransfer for ScpFileTransfer {
         }
     }
 
+    /// ### mkdir_all
+    ///
+    /// Make directory and all of its missing parents using `mkdir -p`.
+    /// If the remote shell doesn't support `-p`, directories are created one by one
+    fn mkdir_all(&mut self, dir: &Path) -> Result<(), FileTransferError> {
+        match self.is_connected() {
+            true => {
+                let dir: PathBuf = Self::resolve(dir);
+                info!("Making directory {} (with parents)", dir.display());
+                let p: PathBuf = self.wrkdir.clone();
+                match self.perform_shell_cmd_with_path(
+                    p.as_path(),
+                    format!("mkdir -p \"{}\"; echo $?", dir.display()).as_str(),
+                ) {
+                    Ok(output) if output.as_str().trim() == "0" => Ok(()),
+                    Ok(output) => {
+                        warn!(
+                            "mkdir -p failed ({}); creating directories one by one",
+                            output.trim()
+                        );
+                        self.iter_mkdir(dir.as_path())
+                    }
+                    Err(err) => Err(FileTransferError::new_ex(
+                        FileTransferErrorType::ProtocolError,
+                        err.to_string(),
+                    )),
+                }
+            }
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
     /// ### remove
     ///
     /// Remove a file or a directory
@@ -778,6 +1020,81 @@ impl FileTransfer for ScpFileTransfer {
         }
     }
 
+    /// ### chmod
+    ///
+    /// Change the mode of the remote file at `path`
+    fn chmod(&mut self, path: &Path, pex: (u8, u8, u8)) -> Result<(), FileTransferError> {
+        let mode: String = format!("{}{}{}", pex.0, pex.1, pex.2);
+        self.shell_set_attr("chmod", mode.as_str(), path)
+    }
+
+    /// ### chown
+    ///
+    /// Change the owner of the remote file at `path`
+    fn chown(&mut self, path: &Path, owner: &str) -> Result<(), FileTransferError> {
+        self.shell_set_attr("chown", owner, path)
+    }
+
+    /// ### read_link
+    ///
+    /// Get the target of the symbolic link at `path`
+    fn read_link(&mut self, path: &Path) -> Result<PathBuf, FileTransferError> {
+        match self.is_connected() {
+            true => {
+                let path: PathBuf = Self::absolutize(self.wrkdir.as_path(), path);
+                let p: PathBuf = self.wrkdir.clone();
+                let output: String = self.perform_shell_cmd_with_path(
+                    p.as_path(),
+                    format!("readlink \"{}\"", path.display()).as_str(),
+                )?;
+                match output.trim_end_matches('\n') {
+                    "" => Err(FileTransferError::new_ex(
+                        FileTransferErrorType::NoSuchFileOrDirectory,
+                        format!("\"{}\" is not a symbolic link", path.display()),
+                    )),
+                    target => Ok(PathBuf::from(target)),
+                }
+            }
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
+    /// ### symlink
+    ///
+    /// Create a symbolic link at `path` pointing to `target`
+    fn symlink(&mut self, path: &Path, target: &Path) -> Result<(), FileTransferError> {
+        match self.is_connected() {
+            true => {
+                let path: PathBuf = Self::absolutize(self.wrkdir.as_path(), path);
+                info!("Linking {} to {}", path.display(), target.display());
+                let p: PathBuf = self.wrkdir.clone();
+                match self
+                    .perform_shell_cmd_with_path(
+                        p.as_path(),
+                        format!(
+                            "ln -s \"{}\" \"{}\"; echo $?",
+                            target.display(),
+                            path.display()
+                        )
+                        .as_str(),
+                    )?
+                    .trim()
+                {
+                    "0" => Ok(()),
+                    _ => Err(FileTransferError::new_ex(
+                        FileTransferErrorType::FileCreateDenied,
+                        format!("\"{}\"", path.display()),
+                    )),
+                }
+            }
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
     /// ### stat
     ///
     /// Stat file and return FsEntry
@@ -831,7 +1148,10 @@ impl FileTransfer for ScpFileTransfer {
             true => {
                 let p: PathBuf = self.wrkdir.clone();
                 info!("Executing command {}", cmd);
-                match self.perform_shell_cmd_with_path(p.as_path(), cmd) {
+                match self.perform_shell_cmd_ex(
+                    format!("cd \"{}\"; {}", p.display(), cmd).as_str(),
+                    self.agent_forwarding,
+                ) {
                     Ok(output) => Ok(output),
                     Err(err) => Err(FileTransferError::new_ex(
                         FileTransferErrorType::ProtocolError,
@@ -966,6 +1286,68 @@ impl FileTransfer for ScpFileTransfer {
         // Nothing to do
         Ok(())
     }
+
+    /// ### signature
+    ///
+    /// Get the signature of the remote file at `path`.
+    /// Checksums are calculated on the remote host through the shell
+    fn signature(&mut self, path: &Path, block_size: u64) -> Result<Signature, FileTransferError> {
+        if !self.is_connected() {
+            return Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            ));
+        }
+        let path: PathBuf = Self::absolutize(self.wrkdir.as_path(), path);
+        debug!("Getting signature of {}", path.display());
+        let output: String =
+            self.perform_shell_cmd(delta::signature_command(path.as_path(), block_size).as_str())?;
+        Signature::parse(output.as_str(), block_size).map_err(|err| {
+            FileTransferError::new_ex(
+                FileTransferErrorType::ProtocolError,
+                format!("Could not get signature of \"{}\": {}", path.display(), err),
+            )
+        })
+    }
+
+    /// ### rebuild
+    ///
+    /// Rebuild the remote file at `path` from its own blocks and the literal data at `literal`.
+    /// The file is rebuilt on the remote host through the shell, keeping the permissions of the current one
+    fn rebuild(
+        &mut self,
+        path: &Path,
+        literal: &Path,
+        block_size: u64,
+        ops: &[Op],
+    ) -> Result<(), FileTransferError> {
+        if !self.is_connected() {
+            return Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            ));
+        }
+        let path: PathBuf = Self::absolutize(self.wrkdir.as_path(), path);
+        let literal: PathBuf = Self::absolutize(self.wrkdir.as_path(), literal);
+        debug!(
+            "Rebuilding {} from {} operations",
+            path.display(),
+            ops.len()
+        );
+        let pex: Option<(u8, u8, u8)> = self
+            .stat(path.as_path())
+            .ok()
+            .and_then(|x| x.get_unix_pex())
+            .map(|(u, g, o)| (u.as_byte(), g.as_byte(), o.as_byte()));
+        let output: String = self.perform_shell_cmd(
+            delta::rebuild_command(path.as_path(), literal.as_path(), block_size, ops, pex)
+                .as_str(),
+        )?;
+        delta::rebuild_result(output.as_str()).map_err(|err| {
+            FileTransferError::new_ex(
+                FileTransferErrorType::ProtocolError,
+                format!("Could not rebuild \"{}\": {}", path.display(), err),
+            )
+        })
+    }
 }
 
 #[cfg(test)]
@@ -1004,6 +1386,11 @@ mod tests {
         assert!(client.session.is_some());
         assert_eq!(client.wrkdir, PathBuf::from("/config"));
         assert_eq!(client.is_connected(), true);
+        // Capabilities
+        let capabilities: Capabilities = client.capabilities();
+        assert!(capabilities.supports(Capability::Exec));
+        assert!(capabilities.supports(Capability::RenameOver));
+        assert!(capabilities.supports(Capability::SetStat));
         // Pwd
         assert_eq!(client.wrkdir.clone(), client.pwd().ok().unwrap());
         // Stat
@@ -1040,6 +1427,11 @@ mod tests {
         assert!(client
             .mkdir(PathBuf::from("/root/aaaaa/pommlar").as_path())
             .is_err());
+        // Make directory with parents
+        assert!(client
+            .mkdir_all(PathBuf::from("/tmp/omar/a/b").as_path())
+            .is_ok());
+        assert!(client.stat(Path::new("/tmp/omar/a/b")).is_ok());
         // Change directory
         assert!(client
             .change_dir(PathBuf::from("/tmp/omar").as_path())
@@ -1336,8 +1728,11 @@ mod tests {
         assert!(scp.change_dir(Path::new("/tmp")).is_err());
         assert!(scp.disconnect().is_err());
         assert!(scp.exec("echo 5").is_err());
+        assert_eq!(scp.capabilities(), Capabilities::default());
         assert!(scp.list_dir(Path::new("/tmp")).is_err());
         assert!(scp.mkdir(Path::new("/tmp")).is_err());
+        assert!(scp.chmod(Path::new("/tmp"), (7, 5, 5)).is_err());
+        assert!(scp.chown(Path::new("/tmp"), "root").is_err());
         assert!(scp.pwd().is_err());
         assert!(scp
             .remove(&make_fsentry(PathBuf::from("/nowhere"), false))
@@ -0,0 +1,98 @@
+//! ## SecurityKey
+//!
+//! `security_key` authenticates SSH sessions with FIDO2 security keys (`sk-` keys) through the ssh agent
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use super::{FileTransferError, FileTransferErrorType};
+use crate::system::sshkey_storage::SshKeyStorage;
+// Ext
+use ssh2::{Agent, PublicKey, Session};
+use std::path::Path;
+
+/// ### userauth_security_key
+///
+/// Authenticate `session` as `username` with the security key `key`.
+/// libssh2 can't sign with security keys, so the signature is requested to the ssh agent, which must
+/// hold the key (`ssh-add`). The call blocks until the user touches the key or the agent gives up
+pub fn userauth_security_key(
+    session: &Session,
+    username: &str,
+    key: &Path,
+) -> Result<(), FileTransferError> {
+    let blob: Vec<u8> = SshKeyStorage::public_key_blob(key).ok_or_else(|| {
+        auth_error(format!(
+            "Could not read public key of security key {}",
+            key.display()
+        ))
+    })?;
+    let mut agent: Agent = session.agent().map_err(|e| agent_error(e.to_string()))?;
+    agent.connect().map_err(|e| agent_error(e.to_string()))?;
+    agent
+        .list_identities()
+        .map_err(|e| agent_error(e.to_string()))?;
+    let identity: Option<PublicKey> = agent
+        .identities()
+        .map_err(|e| agent_error(e.to_string()))?
+        .into_iter()
+        .find(|x| x.blob() == blob.as_slice());
+    let identity: PublicKey = match identity {
+        Some(identity) => identity,
+        None => {
+            let _ = agent.disconnect();
+            return Err(auth_error(format!(
+                "Security key {} is not loaded into ssh agent; add it with `ssh-add {}`",
+                key.display(),
+                key.display()
+            )));
+        }
+    };
+    info!(
+        "Authenticating as {} with security key {}; waiting for user presence",
+        username,
+        key.display()
+    );
+    let result = agent.userauth(username, &identity);
+    let _ = agent.disconnect();
+    result.map_err(|err| {
+        error!("Security key authentication failed: {}", err);
+        auth_error(format!(
+            "Security key authentication failed (has the key been touched?): {}",
+            err
+        ))
+    })
+}
+
+fn auth_error(msg: String) -> FileTransferError {
+    FileTransferError::new_ex(FileTransferErrorType::AuthenticationFailed, msg)
+}
+
+fn agent_error(err: String) -> FileTransferError {
+    error!("Could not query ssh agent: {}", err);
+    auth_error(format!(
+        "Security keys require a running ssh agent: {}",
+        err
+    ))
+}
@@ -26,18 +26,29 @@
  * SOFTWARE.
  */
 // Locals
-use super::{FileTransfer, FileTransferError, FileTransferErrorType};
+use super::delta::{self, Op, Signature};
+use super::{
+    interactive, security_key, tunnel, Capabilities, Capability, FileTransfer, FileTransferError,
+    FileTransferErrorType, Proxy,
+};
 use crate::fs::{FsDirectory, FsEntry, FsFile, UnixPex};
+use crate::system::ssh_config::SshHostParams;
 use crate::system::sshkey_storage::SshKeyStorage;
 use crate::utils::fmt::{fmt_time, shadow_password};
 
 // Includes
-use ssh2::{Channel, FileStat, OpenFlags, OpenType, Session, Sftp};
-use std::io::{BufReader, BufWriter, Read, Write};
-use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use ssh2::{Channel, ErrorCode, FileStat, OpenFlags, OpenType, Session, Sftp};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::net::TcpStream;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
+/// libssh2 error reported when the private key can't be read or decrypted
+const LIBSSH2_ERROR_FILE: i32 = -16;
+const LIBSSH2_ERROR_TIMEOUT: i32 = -9;
+/// SFTP status returned for requests the server doesn't implement
+const SSH_FX_OP_UNSUPPORTED: i32 = 8;
+
 /// ## SftpFileTransfer
 ///
 /// SFTP file transfer structure
@@ -46,6 +57,12 @@ pub struct SftpFileTransfer {
     sftp: Option<Sftp>,
     wrkdir: PathBuf,
     key_storage: SshKeyStorage,
+    key_passphrase: Option<String>,
+    banner_acknowledged: bool,
+    proxy: Option<Proxy>,
+    agent_forwarding: bool,
+    compression: bool,
+    stall_timeout: Option<Duration>,
 }
 
 impl SftpFileTransfer {
@@ -58,6 +75,51 @@ impl SftpFileTransfer {
             sftp: None,
             wrkdir: PathBuf::from("~"),
             key_storage,
+            key_passphrase: None,
+            banner_acknowledged: false,
+            proxy: None,
+            agent_forwarding: false,
+            compression: false,
+            stall_timeout: None,
+        }
+    }
+
+    /// ### interactive_only
+    ///
+    /// Returns whether the server accepts passwords only through keyboard-interactive prompts
+    fn interactive_only(session: &Session, username: &str) -> bool {
+        match session.auth_methods(username) {
+            Ok(methods) => {
+                let methods: Vec<&str> = methods.split(',').collect();
+                !methods.contains(&"password") && methods.contains(&"keyboard-interactive")
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// ### stall_timeout_millis
+    ///
+    /// Convert the stall timeout into the libssh2 session timeout, where 0 means no timeout
+    fn stall_timeout_millis(timeout: Option<Duration>) -> u32 {
+        timeout
+            .map(|x| x.as_millis().min(u32::MAX as u128) as u32)
+            .unwrap_or(0)
+    }
+
+    /// ### read_motd
+    ///
+    /// Read the message of the day of the remote host (`/etc/motd`), if any
+    fn read_motd(&self) -> Option<String> {
+        let mut file = self.sftp.as_ref()?.open(Path::new("/etc/motd")).ok()?;
+        let mut motd: Vec<u8> = Vec::new();
+        if let Err(err) = file.read_to_end(&mut motd) {
+            warn!("Could not read message of the day: {}", err);
+            return None;
+        }
+        let motd: String = String::from_utf8_lossy(motd.as_slice()).to_string();
+        match motd.trim().is_empty() {
+            true => None,
+            false => Some(motd),
         }
     }
 
@@ -211,6 +273,25 @@ impl SftpFileTransfer {
     /// Perform a shell command and read the output from shell
     /// This operation is, obviously, blocking.
     fn perform_shell_cmd(&mut self, cmd: &str) -> Result<String, FileTransferError> {
+        self.perform_shell_cmd_ex(cmd, false)
+    }
+
+    /// ### is_op_supported
+    ///
+    /// Returns whether the result of a probe tells the request is implemented by the server
+    fn is_op_supported<T>(result: Result<T, ssh2::Error>) -> bool {
+        !matches!(result, Err(err) if err.code() == ErrorCode::SFTP(SSH_FX_OP_UNSUPPORTED))
+    }
+
+    /// ### perform_shell_cmd_ex
+    ///
+    /// Perform a shell command and read the output from shell.
+    /// If `forward_agent` is true, the local ssh agent is forwarded to the command
+    fn perform_shell_cmd_ex(
+        &mut self,
+        cmd: &str,
+        forward_agent: bool,
+    ) -> Result<String, FileTransferError> {
         match self.session.as_mut() {
             Some(session) => {
                 // Create channel
@@ -224,6 +305,12 @@ impl SftpFileTransfer {
                         ))
                     }
                 };
+                // Forward agent
+                if forward_agent {
+                    if let Err(err) = channel.request_auth_agent_forwarding() {
+                        warn!("Could not forward ssh agent: {}", err);
+                    }
+                }
                 // Execute command
                 if let Err(err) = channel.exec(cmd) {
                     return Err(FileTransferError::new_ex(
@@ -264,40 +351,32 @@ impl FileTransfer for SftpFileTransfer {
         username: Option<String>,
         password: Option<String>,
     ) -> Result<Option<String>, FileTransferError> {
-        // Setup tcp stream
-        info!("Connecting to {}:{}", address, port);
-        let socket_addresses: Vec<SocketAddr> =
-            match format!("{}:{}", address, port).to_socket_addrs() {
-                Ok(s) => s.collect(),
-                Err(err) => {
-                    return Err(FileTransferError::new_ex(
-                        FileTransferErrorType::BadAddress,
-                        err.to_string(),
-                    ))
-                }
-            };
-        let mut tcp: Option<TcpStream> = None;
-        // Try addresses
-        for socket_addr in socket_addresses.iter() {
-            debug!("Trying socket address {}", socket_addr);
-            match TcpStream::connect_timeout(socket_addr, Duration::from_secs(30)) {
-                Ok(stream) => {
-                    tcp = Some(stream);
-                    break;
-                }
-                Err(_) => continue,
-            }
+        // Resolve host through ssh config
+        let host_params: SshHostParams = self.key_storage.ssh_host(address.as_str());
+        let host_name: String = host_params
+            .host_name
+            .clone()
+            .unwrap_or_else(|| address.clone());
+        let port: u16 = match (port, host_params.port) {
+            (22, Some(port)) => port,
+            _ => port,
+        };
+        if host_name != address {
+            debug!(
+                "Resolved {} to {}:{} through ssh config",
+                address, host_name, port
+            );
         }
-        // If stream is None, return connection timeout
-        let tcp: TcpStream = match tcp {
-            Some(t) => t,
-            None => {
-                error!("No suitable socket address found; connection timeout");
-                return Err(FileTransferError::new_ex(
-                    FileTransferErrorType::ConnectionError,
-                    String::from("Connection timeout"),
-                ));
-            }
+        // Setup tcp stream
+        let tcp: TcpStream = match host_params.proxy_jump.as_deref() {
+            Some(jump) => tunnel::open_jump_stream(
+                jump,
+                &self.key_storage,
+                self.proxy.as_ref(),
+                host_name.as_str(),
+                port,
+            )?,
+            None => tunnel::open_stream(host_name.as_str(), port, self.proxy.as_ref())?,
         };
         // Create session
         let mut session: Session = match Session::new() {
@@ -312,6 +391,9 @@ impl FileTransfer for SftpFileTransfer {
         };
         // Set TCP stream
         session.set_tcp_stream(tcp);
+        session.set_timeout(Self::stall_timeout_millis(self.stall_timeout));
+        // Compression must be negotiated during the handshake
+        session.set_compress(self.compression);
         // Open connection
         debug!("Initializing handshake");
         if let Err(err) = session.handshake() {
@@ -321,35 +403,67 @@ impl FileTransfer for SftpFileTransfer {
                 err.to_string(),
             ));
         }
-        let username: String = match username {
-            Some(u) => u,
-            None => String::from(""),
-        };
+        let username: String = username.or(host_params.user).unwrap_or_default();
         // Check if it is possible to authenticate using a RSA key
         match self
             .key_storage
-            .resolve(address.as_str(), username.as_str())
+            .identity(address.as_str(), username.as_str())
         {
+            Some(sk_key) if SshKeyStorage::is_security_key(sk_key.as_path()) => {
+                debug!(
+                    "Authenticating with user {} and security key {}",
+                    username,
+                    sk_key.display()
+                );
+                security_key::userauth_security_key(&session, username.as_str(), sk_key.as_path())?;
+            }
             Some(rsa_key) => {
                 debug!(
                     "Authenticating with user {} and RSA key {}",
                     username,
                     rsa_key.display()
                 );
-                // Authenticate with RSA key
+                // Present the OpenSSH certificate of the key, if any
+                let certificate: Option<PathBuf> = self
+                    .key_storage
+                    .certificate(address.as_str(), rsa_key.as_path());
+                if let Some(certificate) = certificate.as_ref() {
+                    debug!("Using certificate {}", certificate.display());
+                }
+                // Authenticate with RSA key; the password is used as passphrase if none has been set
+                let passphrase: Option<&str> =
+                    self.key_passphrase.as_deref().or(password.as_deref());
                 if let Err(err) = session.userauth_pubkey_file(
                     username.as_str(),
-                    None,
+                    certificate.as_deref(),
                     rsa_key.as_path(),
-                    password.as_deref(),
+                    passphrase,
                 ) {
                     error!("Authentication failed: {}", err);
+                    // Key could not be decrypted
+                    if err.code() == ErrorCode::Session(LIBSSH2_ERROR_FILE)
+                        && SshKeyStorage::is_encrypted(rsa_key.as_path())
+                    {
+                        return Err(FileTransferError::new_ex(
+                            FileTransferErrorType::KeyPassphraseRequired,
+                            rsa_key.display().to_string(),
+                        ));
+                    }
                     return Err(FileTransferError::new_ex(
                         FileTransferErrorType::AuthenticationFailed,
                         err.to_string(),
                     ));
                 }
             }
+            None if Self::interactive_only(&session, username.as_str()) => {
+                // The server asks for the password through keyboard-interactive prompts
+                interactive::userauth_interactive(
+                    &session,
+                    username.as_str(),
+                    password.as_deref().unwrap_or(""),
+                    self.banner_acknowledged,
+                )?;
+            }
             None => {
                 // Proceeed with username/password authentication
                 debug!(
@@ -404,7 +518,55 @@ impl FileTransfer for SftpFileTransfer {
             banner.as_deref().unwrap_or(""),
             self.wrkdir.display()
         );
-        Ok(banner)
+        // Return message of the day as welcome message
+        Ok(self.read_motd())
+    }
+
+    /// ### set_proxy
+    ///
+    /// Set the proxy to connect to the remote server through
+    fn set_proxy(&mut self, proxy: Option<Proxy>) -> Result<(), FileTransferError> {
+        self.proxy = proxy;
+        Ok(())
+    }
+
+    /// ### set_agent_forwarding
+    ///
+    /// Set whether to forward the local ssh agent to the commands run with `exec`
+    fn set_agent_forwarding(&mut self, forward: bool) -> Result<(), FileTransferError> {
+        self.agent_forwarding = forward;
+        Ok(())
+    }
+
+    /// ### set_compression
+    ///
+    /// Set whether to enable zlib compression of the ssh transport at the next `connect`
+    fn set_compression(&mut self, compress: bool) {
+        self.compression = compress;
+    }
+
+    /// ### set_key_passphrase
+    ///
+    /// Set the passphrase to decrypt the private key with at the next `connect`
+    fn set_key_passphrase(&mut self, passphrase: Option<String>) {
+        self.key_passphrase = passphrase;
+    }
+
+    /// ### set_banner_acknowledged
+    ///
+    /// Set whether the banner sent with the keyboard-interactive prompts has been read by the user
+    fn set_banner_acknowledged(&mut self, acknowledged: bool) {
+        self.banner_acknowledged = acknowledged;
+    }
+
+    /// ### set_stall_timeout
+    ///
+    /// Set how long libssh2 may block waiting for the remote; applies to the current session too
+    fn set_stall_timeout(&mut self, timeout: Option<Duration>) {
+        self.stall_timeout = timeout;
+        if let Some(session) = self.session.as_ref() {
+            session.set_timeout(Self::stall_timeout_millis(timeout));
+        }
     }
 
     /// ### disconnect
@@ -441,6 +603,46 @@ impl FileTransfer for SftpFileTransfer {
         self.session.is_some()
     }
 
+    /// ### capabilities
+    ///
+    /// Probe the server on the working directory. Requests the server doesn't implement
+    /// fail with `SSH_FX_OP_UNSUPPORTED`; any other error (e.g. permission denied) means the request is supported
+    fn capabilities(&mut self) -> Capabilities {
+        let wrkdir: PathBuf = self.wrkdir.clone();
+        let (set_stat, stat_vfs, symlinks): (bool, bool, bool) = match self.sftp.as_ref() {
+            None => (false, false, false),
+            Some(sftp) => {
+                // An empty setstat changes nothing
+                let empty: FileStat = FileStat {
+                    size: None,
+                    uid: None,
+                    gid: None,
+                    perm: None,
+                    atime: None,
+                    mtime: None,
+                };
+                (
+                    Self::is_op_supported(sftp.setstat(wrkdir.as_path(), empty)),
+                    sftp.opendir(wrkdir.as_path())
+                        .and_then(|mut dir| dir.statvfs())
+                        .is_ok(),
+                    Self::is_op_supported(sftp.readlink(wrkdir.as_path())),
+                )
+            }
+        };
+        // Shell may be disabled (e.g. `internal-sftp`)
+        let exec: bool =
+            matches!(self.perform_shell_cmd("echo termscp"), Ok(x) if x.trim() == "termscp");
+        Capabilities::default()
+            // SFTP v3 rename fails if the target exists
+            .with(Capability::RenameOver, false)
+            .with(Capability::SetStat, set_stat)
+            .with(Capability::StatVfs, stat_vfs)
+            .with(Capability::Symlinks, symlinks)
+            .with(Capability::Exec, exec)
+            .with(Capability::RangedReads, self.sftp.is_some())
+    }
+
     /// ### pwd
     ///
     /// Print working directory
@@ -528,6 +730,9 @@ impl FileTransfer for SftpFileTransfer {
                 info!("Getting file entries in {}", path.display());
                 // Get files
                 match sftp.readdir(dir.as_path()) {
+                    Err(err) if err.code() == ErrorCode::Session(LIBSSH2_ERROR_TIMEOUT) => Err(
+                        FileTransferError::new_ex(FileTransferErrorType::Timeout, err.to_string()),
+                    ),
                     Err(err) => Err(FileTransferError::new_ex(
                         FileTransferErrorType::DirStatFailed,
                         err.to_string(),
@@ -653,6 +858,102 @@ impl FileTransfer for SftpFileTransfer {
         }
     }
 
+    /// ### chmod
+    ///
+    /// Change the mode of the remote file at `path`
+    fn chmod(&mut self, path: &Path, pex: (u8, u8, u8)) -> Result<(), FileTransferError> {
+        match self.sftp.as_ref() {
+            None => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+            Some(sftp) => {
+                let path: PathBuf = self.get_abs_path(path);
+                info!("Changing mode for {} to {:?}", path.display(), pex);
+                let stat: FileStat = FileStat {
+                    size: None,
+                    uid: None,
+                    gid: None,
+                    perm: Some(((pex.0 as u32) << 6) | ((pex.1 as u32) << 3) | (pex.2 as u32)),
+                    atime: None,
+                    mtime: None,
+                };
+                sftp.setstat(path.as_path(), stat).map_err(|err| {
+                    FileTransferError::new_ex(FileTransferErrorType::PexError, err.to_string())
+                })
+            }
+        }
+    }
+
+    /// ### chown
+    ///
+    /// Change the owner of the remote file at `path`.
+    /// SFTP only knows about uids and gids, so `chown` is run in a shell, in order to resolve names
+    fn chown(&mut self, path: &Path, owner: &str) -> Result<(), FileTransferError> {
+        match self.is_connected() {
+            true => {
+                let path: PathBuf = self.get_abs_path(path);
+                info!("Changing owner for {} to {}", path.display(), owner);
+                match self
+                    .perform_shell_cmd(
+                        format!("chown \"{}\" \"{}\"; echo $?", owner, path.display()).as_str(),
+                    )?
+                    .trim()
+                {
+                    "0" => Ok(()),
+                    _ => Err(FileTransferError::new_ex(
+                        FileTransferErrorType::PexError,
+                        format!("\"{}\"", path.display()),
+                    )),
+                }
+            }
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
+    /// ### read_link
+    ///
+    /// Get the target of the symbolic link at `path`
+    fn read_link(&mut self, path: &Path) -> Result<PathBuf, FileTransferError> {
+        match self.sftp.as_ref() {
+            None => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+            Some(sftp) => {
+                let path: PathBuf = self.get_abs_path(path);
+                sftp.readlink(path.as_path()).map_err(|err| {
+                    FileTransferError::new_ex(
+                        FileTransferErrorType::NoSuchFileOrDirectory,
+                        err.to_string(),
+                    )
+                })
+            }
+        }
+    }
+
+    /// ### symlink
+    ///
+    /// Create a symbolic link at `path` pointing to `target`
+    fn symlink(&mut self, path: &Path, target: &Path) -> Result<(), FileTransferError> {
+        match self.sftp.as_ref() {
+            None => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+            Some(sftp) => {
+                let path: PathBuf = self.get_abs_path(path);
+                info!("Linking {} to {}", path.display(), target.display());
+                // The link is created at the second argument, pointing at the first one
+                sftp.symlink(target, path.as_path()).map_err(|err| {
+                    FileTransferError::new_ex(
+                        FileTransferErrorType::FileCreateDenied,
+                        err.to_string(),
+                    )
+                })
+            }
+        }
+    }
+
     /// ### stat
     ///
     /// Stat file and return FsEntry
@@ -683,7 +984,10 @@ impl FileTransfer for SftpFileTransfer {
     fn exec(&mut self, cmd: &str) -> Result<String, FileTransferError> {
         info!("Executing command {}", cmd);
         match self.is_connected() {
-            true => match self.perform_shell_cmd_with_path(cmd) {
+            true => match self.perform_shell_cmd_ex(
+                format!("cd \"{}\"; {}", self.wrkdir.display(), cmd).as_str(),
+                self.agent_forwarding,
+            ) {
                 Ok(output) => Ok(output),
                 Err(err) => Err(FileTransferError::new_ex(
                     FileTransferErrorType::ProtocolError,
@@ -767,6 +1071,41 @@ impl FileTransfer for SftpFileTransfer {
         }
     }
 
+    /// ### recv_file_range
+    ///
+    /// Receive `len` bytes of the remote file, starting from `offset`
+    fn recv_file_range(
+        &mut self,
+        file: &FsFile,
+        offset: u64,
+        len: u64,
+    ) -> Result<Box<dyn Read>, FileTransferError> {
+        match self.sftp.as_ref() {
+            None => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+            Some(sftp) => {
+                let remote_path: PathBuf = self.get_remote_path(file.abs_path.as_path())?;
+                info!(
+                    "Receiving {} bytes of {} from {}",
+                    len,
+                    remote_path.display(),
+                    offset
+                );
+                let mut file = sftp.open(remote_path.as_path()).map_err(|err| {
+                    FileTransferError::new_ex(
+                        FileTransferErrorType::NoSuchFileOrDirectory,
+                        err.to_string(),
+                    )
+                })?;
+                file.seek(SeekFrom::Start(offset)).map_err(|err| {
+                    FileTransferError::new_ex(FileTransferErrorType::ProtocolError, err.to_string())
+                })?;
+                Ok(Box::new(BufReader::with_capacity(65536, file.take(len))))
+            }
+        }
+    }
+
     /// ### on_sent
     ///
     /// Finalize send method. This method must be implemented only if necessary.
@@ -786,6 +1125,68 @@ impl FileTransfer for SftpFileTransfer {
     fn on_recv(&mut self, _readable: Box<dyn Read>) -> Result<(), FileTransferError> {
         Ok(())
     }
+
+    /// ### signature
+    ///
+    /// Get the signature of the remote file at `path`.
+    /// Checksums are calculated on the remote host through the shell
+    fn signature(&mut self, path: &Path, block_size: u64) -> Result<Signature, FileTransferError> {
+        if self.sftp.is_none() {
+            return Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            ));
+        }
+        let path: PathBuf = self.get_abs_path(path);
+        debug!("Getting signature of {}", path.display());
+        let output: String =
+            self.perform_shell_cmd(delta::signature_command(path.as_path(), block_size).as_str())?;
+        Signature::parse(output.as_str(), block_size).map_err(|err| {
+            FileTransferError::new_ex(
+                FileTransferErrorType::ProtocolError,
+                format!("Could not get signature of \"{}\": {}", path.display(), err),
+            )
+        })
+    }
+
+    /// ### rebuild
+    ///
+    /// Rebuild the remote file at `path` from its own blocks and the literal data at `literal`.
+    /// The file is rebuilt on the remote host through the shell, keeping the permissions of the current one
+    fn rebuild(
+        &mut self,
+        path: &Path,
+        literal: &Path,
+        block_size: u64,
+        ops: &[Op],
+    ) -> Result<(), FileTransferError> {
+        if self.sftp.is_none() {
+            return Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            ));
+        }
+        let path: PathBuf = self.get_abs_path(path);
+        let literal: PathBuf = self.get_abs_path(literal);
+        debug!(
+            "Rebuilding {} from {} operations",
+            path.display(),
+            ops.len()
+        );
+        let pex: Option<(u8, u8, u8)> = self
+            .stat(path.as_path())
+            .ok()
+            .and_then(|x| x.get_unix_pex())
+            .map(|(u, g, o)| (u.as_byte(), g.as_byte(), o.as_byte()));
+        let output: String = self.perform_shell_cmd(
+            delta::rebuild_command(path.as_path(), literal.as_path(), block_size, ops, pex)
+                .as_str(),
+        )?;
+        delta::rebuild_result(output.as_str()).map_err(|err| {
+            FileTransferError::new_ex(
+                FileTransferErrorType::ProtocolError,
+                format!("Could not rebuild \"{}\": {}", path.display(), err),
+            )
+        })
+    }
 }
 
 #[cfg(test)]
@@ -804,6 +1205,7 @@ mod tests {
         assert!(client.sftp.is_none());
         assert_eq!(client.wrkdir, PathBuf::from("~"));
         assert_eq!(client.is_connected(), false);
+        assert!(client.proxy.is_none());
     }
 
     #[test]
@@ -826,6 +1228,13 @@ mod tests {
         assert!(client.sftp.is_some());
         assert_eq!(client.wrkdir, PathBuf::from("/config"));
         assert_eq!(client.is_connected(), true);
+        // Capabilities
+        let capabilities: Capabilities = client.capabilities();
+        assert!(capabilities.supports(Capability::Exec));
+        assert!(capabilities.supports(Capability::SetStat));
+        assert!(capabilities.supports(Capability::Symlinks));
+        assert!(!capabilities.supports(Capability::RenameOver));
+        assert!(capabilities.supports(Capability::RangedReads));
         // Pwd
         assert_eq!(client.wrkdir.clone(), client.pwd().ok().unwrap());
         // Stat
@@ -955,8 +1364,27 @@ mod tests {
         let mut data: Vec<u8> = vec![0; 1024];
         assert!(readable.read(&mut data).is_ok());
         assert!(client.on_recv(readable).is_ok());
+        // Receive range
+        let mut readable = client.recv_file_range(&file, 4, 8).ok().unwrap();
+        let mut data: Vec<u8> = Vec::new();
+        assert!(readable.read_to_end(&mut data).is_ok());
+        assert_eq!(data.len(), 8);
+        assert!(client.on_recv(readable).is_ok());
         // Receive file (err)
         assert!(client.recv_file(&entry).is_err());
+        // Check writable
+        assert!(client
+            .check_writable(PathBuf::from("/tmp").as_path())
+            .is_ok());
+        assert!(client
+            .list_dir(PathBuf::from("/tmp").as_path())
+            .ok()
+            .unwrap()
+            .iter()
+            .all(|x| !x.get_name().starts_with(".termscp-probe-")));
+        assert!(client
+            .check_writable(PathBuf::from("/this/doesnt/exist").as_path())
+            .is_err());
         // Cleanup
         assert!(client.change_dir(PathBuf::from("/").as_path()).is_ok());
         assert!(client
@@ -1087,9 +1515,13 @@ mod tests {
             )
             .is_err());
         assert!(sftp.exec("echo 5").is_err());
+        assert_eq!(sftp.capabilities(), Capabilities::default());
         assert!(sftp.disconnect().is_err());
         assert!(sftp.list_dir(Path::new("/tmp")).is_err());
         assert!(sftp.mkdir(Path::new("/tmp")).is_err());
+        assert!(sftp.check_writable(Path::new("/tmp")).is_err());
+        assert!(sftp.chmod(Path::new("/tmp"), (7, 5, 5)).is_err());
+        assert!(sftp.chown(Path::new("/tmp"), "root").is_err());
         assert!(sftp.pwd().is_err());
         assert!(sftp
             .remove(&make_fsentry(PathBuf::from("/nowhere"), false))
@@ -1102,6 +1534,7 @@ mod tests {
             .is_err());
         assert!(sftp.stat(Path::new("/tmp")).is_err());
         assert!(sftp.recv_file(&file).is_err());
+        assert!(sftp.recv_file_range(&file, 0, 1).is_err());
         assert!(sftp.send_file(&file, Path::new("/tmp/omar.txt")).is_err());
     }
 }
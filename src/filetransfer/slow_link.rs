@@ -0,0 +1,213 @@
+//! ## SlowLink
+//!
+//! `slow_link` simulates a slow network on transfer streams, to reproduce how termscp behaves on bad links
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Times per second the stream is allowed to move data, when bandwidth is capped
+const SLICES_PER_SECOND: u64 = 10;
+
+/// ## SlowLink
+///
+/// SlowLink describes the network conditions to simulate on transfer streams.
+/// It is expressed as `<latency ms>[:<bandwidth bytes/s>]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SlowLink {
+    pub latency: Duration,      // Delay before the first byte of each stream
+    pub bandwidth: Option<u64>, // Bytes per second
+}
+
+impl SlowLink {
+    /// ### stream
+    ///
+    /// Wrap `inner` to move data through this link
+    pub fn stream<S>(self, inner: S) -> SlowLinkStream<S> {
+        SlowLinkStream {
+            inner,
+            link: self,
+            started: None,
+            transferred: 0,
+        }
+    }
+}
+
+impl FromStr for SlowLink {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (latency, bandwidth): (&str, Option<&str>) = match s.find(':') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+        let latency: u64 = latency
+            .parse()
+            .map_err(|_| format!("Bad latency '{}'", latency))?;
+        let bandwidth: Option<u64> = match bandwidth {
+            None => None,
+            Some(bandwidth) => match bandwidth.parse::<u64>() {
+                Ok(0) | Err(_) => return Err(format!("Bad bandwidth '{}'", bandwidth)),
+                Ok(bandwidth) => Some(bandwidth),
+            },
+        };
+        Ok(SlowLink {
+            latency: Duration::from_millis(latency),
+            bandwidth,
+        })
+    }
+}
+
+/// ## SlowLinkStream
+///
+/// SlowLinkStream delays and caps the data read from or written to `inner` as set in its `SlowLink`
+pub struct SlowLinkStream<S> {
+    inner: S,
+    link: SlowLink,
+    started: Option<Instant>,
+    transferred: u64,
+}
+
+impl<S> SlowLinkStream<S> {
+    /// ### into_inner
+    ///
+    /// Get back the wrapped stream
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// ### slice_len
+    ///
+    /// Wait for the latency on the first call, then return how many of `len` bytes can be moved now
+    fn slice_len(&mut self, len: usize) -> usize {
+        if self.started.is_none() {
+            sleep(self.link.latency);
+            self.started = Some(Instant::now());
+        }
+        match self.link.bandwidth {
+            Some(bandwidth) => {
+                let slice: u64 = (bandwidth / SLICES_PER_SECOND).max(1);
+                len.min(slice as usize)
+            }
+            None => len,
+        }
+    }
+
+    /// ### account
+    ///
+    /// Account `bytes` moved through the link, then sleep until the bandwidth allows them
+    fn account(&mut self, bytes: usize) {
+        self.transferred += bytes as u64;
+        if let (Some(bandwidth), Some(started)) = (self.link.bandwidth, self.started) {
+            let due: Duration = Duration::from_millis(self.transferred * 1000 / bandwidth);
+            let elapsed: Duration = started.elapsed();
+            if due > elapsed {
+                sleep(due - elapsed);
+            }
+        }
+    }
+}
+
+impl<S: Read> Read for SlowLinkStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len: usize = self.slice_len(buf.len());
+        let bytes: usize = self.inner.read(&mut buf[..len])?;
+        self.account(bytes);
+        Ok(bytes)
+    }
+}
+
+impl<S: Write> Write for SlowLinkStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len: usize = self.slice_len(buf.len());
+        let bytes: usize = self.inner.write(&buf[..len])?;
+        self.account(bytes);
+        Ok(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_filetransfer_slow_link_from_str() {
+        assert_eq!(
+            SlowLink::from_str("250").unwrap(),
+            SlowLink {
+                latency: Duration::from_millis(250),
+                bandwidth: None,
+            }
+        );
+        assert_eq!(
+            SlowLink::from_str("0:65536").unwrap(),
+            SlowLink {
+                latency: Duration::from_millis(0),
+                bandwidth: Some(65536),
+            }
+        );
+        assert!(SlowLink::from_str("").is_err());
+        assert!(SlowLink::from_str("fast").is_err());
+        assert!(SlowLink::from_str("100:").is_err());
+        assert!(SlowLink::from_str("100:0").is_err());
+    }
+
+    #[test]
+    fn test_filetransfer_slow_link_read() {
+        let link: SlowLink = SlowLink::from_str("100:1000").unwrap();
+        let mut stream = link.stream(Cursor::new(vec![0u8; 300]));
+        let started: Instant = Instant::now();
+        let mut buffer: Vec<u8> = Vec::new();
+        assert_eq!(stream.read_to_end(&mut buffer).unwrap(), 300);
+        // Latency + 300 bytes at 1000 bytes/s
+        assert!(started.elapsed() >= Duration::from_millis(400));
+        assert_eq!(stream.into_inner().position(), 300);
+    }
+
+    #[test]
+    fn test_filetransfer_slow_link_write() {
+        let link: SlowLink = SlowLink::from_str("0:1000").unwrap();
+        let mut stream = link.stream(Vec::new());
+        let started: Instant = Instant::now();
+        // Writes are sliced to 1/10 of the bandwidth
+        assert_eq!(stream.write(&[0u8; 500]).unwrap(), 100);
+        stream.write_all(&[0u8; 100]).unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(stream.into_inner().len(), 200);
+        // No bandwidth cap
+        let mut stream = SlowLink::default().stream(Vec::new());
+        assert_eq!(stream.write(&[0u8; 500]).unwrap(), 500);
+    }
+}
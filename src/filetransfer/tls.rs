@@ -0,0 +1,159 @@
+//! ## Tls
+//!
+//! `tls` provides the TLS options used to secure FTPS connections
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Ext
+use std::fs;
+use std::path::{Path, PathBuf};
+use suppaftp::native_tls::{Certificate, Identity, TlsConnector};
+
+const PEM_CERTIFICATE_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+
+/// ## TlsOptions
+///
+/// TlsOptions describes the client certificate to authenticate with (mutual TLS)
+/// and the certificate authorities to trust instead of the system ones.
+/// The client certificate is a PKCS#12 archive, with its private key and no password;
+/// the CA bundle is PEM encoded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsOptions {
+    pub client_cert: Option<PathBuf>,
+    pub ca_bundle: Option<PathBuf>,
+}
+
+impl TlsOptions {
+    /// ### connector
+    ///
+    /// Build the TLS connector for these options.
+    /// Certificates are verified only when a CA bundle is provided; otherwise any certificate is accepted
+    pub fn connector(&self) -> Result<TlsConnector, String> {
+        let mut builder = TlsConnector::builder();
+        if let Some(cert) = self.client_cert.as_deref() {
+            let identity: Identity = Identity::from_pkcs12(&read(cert)?, "")
+                .map_err(|e| format!("Bad client certificate: {}", e))?;
+            builder.identity(identity);
+        }
+        match self.ca_bundle.as_deref() {
+            Some(bundle) => {
+                let bundle: String = String::from_utf8(read(bundle)?)
+                    .map_err(|_| String::from("CA bundle is not a PEM file"))?;
+                let certs: Vec<&str> = split_pem_certificates(bundle.as_str());
+                if certs.is_empty() {
+                    return Err(String::from("CA bundle contains no certificate"));
+                }
+                for cert in certs {
+                    let cert: Certificate = Certificate::from_pem(cert.as_bytes())
+                        .map_err(|e| format!("Bad CA certificate: {}", e))?;
+                    builder.add_root_certificate(cert);
+                }
+                builder.disable_built_in_roots(true);
+            }
+            None => {
+                builder
+                    .danger_accept_invalid_certs(true)
+                    .danger_accept_invalid_hostnames(true);
+            }
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+}
+
+/// ### read
+///
+/// Read file at `path`
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))
+}
+
+/// ### split_pem_certificates
+///
+/// Split a PEM bundle into its certificates
+fn split_pem_certificates(bundle: &str) -> Vec<&str> {
+    let mut certs: Vec<&str> = Vec::new();
+    let mut rest: &str = bundle;
+    while let Some(begin) = rest.find(PEM_CERTIFICATE_BEGIN) {
+        match rest[begin..].find(PEM_CERTIFICATE_END) {
+            Some(end) => {
+                let end: usize = begin + end + PEM_CERTIFICATE_END.len();
+                certs.push(&rest[begin..end]);
+                rest = &rest[end..];
+            }
+            None => break,
+        }
+    }
+    certs
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    #[test]
+    fn test_filetransfer_tls_split_pem_certificates() {
+        let bundle: &str = "# Root CA\n-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n\n-----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----\n-----BEGIN CERTIFICATE-----\nCCCC\n";
+        assert_eq!(
+            split_pem_certificates(bundle),
+            vec![
+                "-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----",
+                "-----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----",
+            ]
+        );
+        assert!(split_pem_certificates("").is_empty());
+    }
+
+    #[test]
+    fn test_filetransfer_tls_connector() {
+        // Default options accept any certificate
+        assert!(TlsOptions::default().connector().is_ok());
+        // Missing files
+        let opts: TlsOptions = TlsOptions {
+            client_cert: Some(PathBuf::from("/nowhere/client.p12")),
+            ca_bundle: None,
+        };
+        assert!(opts.connector().is_err());
+        // Not a PKCS#12 archive
+        let mut cert = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(cert, "not an archive").is_ok());
+        let opts: TlsOptions = TlsOptions {
+            client_cert: Some(cert.path().to_path_buf()),
+            ..TlsOptions::default()
+        };
+        assert!(opts.connector().is_err());
+        // Bundle without certificates
+        let mut bundle = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(bundle, "not a certificate").is_ok());
+        let opts: TlsOptions = TlsOptions {
+            ca_bundle: Some(bundle.path().to_path_buf()),
+            ..TlsOptions::default()
+        };
+        assert!(opts.connector().is_err());
+    }
+}
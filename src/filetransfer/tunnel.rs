@@ -0,0 +1,394 @@
+//! ## Tunnel
+//!
+//! `tunnel` opens the tcp streams SSH sessions run on, either directly, through a proxy or
+//! through a jump host (`ProxyJump`)
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use super::{security_key, FileTransferError, FileTransferErrorType, Proxy};
+use crate::system::ssh_config::SshHostParams;
+use crate::system::sshkey_storage::SshKeyStorage;
+// Ext
+use ssh2::{Channel, Session};
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+/// Timeout when connecting to a host
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Time the jump host tunnel sleeps for when there's no data to forward
+const TUNNEL_IDLE_INTERVAL: Duration = Duration::from_millis(5);
+
+/// ## JumpHost
+///
+/// JumpHost describes the host to jump through, expressed as `[user@]host[:port]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpHost {
+    pub address: String,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+}
+
+impl FromStr for JumpHost {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: &str = s.trim();
+        if s.contains(',') {
+            return Err(String::from("multiple jump hosts are not supported"));
+        }
+        let s: &str = s.strip_prefix("ssh://").unwrap_or(s);
+        let (username, host): (Option<String>, &str) = match s.rsplit_once('@') {
+            Some((user, host)) => (Some(user.to_string()), host),
+            None => (None, s),
+        };
+        let (address, port): (&str, Option<u16>) = match host.rsplit_once(':') {
+            Some((address, port)) => match port.parse::<u16>() {
+                Ok(port) => (address, Some(port)),
+                Err(_) => return Err(format!("bad port '{}'", port)),
+            },
+            None => (host, None),
+        };
+        if address.is_empty() {
+            return Err(String::from("missing host"));
+        }
+        Ok(JumpHost {
+            address: address.to_string(),
+            port,
+            username,
+        })
+    }
+}
+
+/// ### open_stream
+///
+/// Open a tcp stream to `address:port`, through `proxy` if set
+pub fn open_stream(
+    address: &str,
+    port: u16,
+    proxy: Option<&Proxy>,
+) -> Result<TcpStream, FileTransferError> {
+    match proxy {
+        Some(proxy) => {
+            info!(
+                "Connecting to {}:{} through proxy {}:{}",
+                address, port, proxy.address, proxy.port
+            );
+            proxy
+                .connect(address, port, CONNECT_TIMEOUT)
+                .map_err(|err| {
+                    error!("Could not connect through proxy: {}", err);
+                    FileTransferError::new_ex(
+                        FileTransferErrorType::ConnectionError,
+                        format!("Proxy error: {}", err),
+                    )
+                })
+        }
+        None => connect_tcp(address, port),
+    }
+}
+
+/// ### open_jump_stream
+///
+/// Open a tcp stream to `address:port` tunneled through the ssh host `jump` (`ProxyJump`).
+/// The jump host is resolved through the ssh config too and is authenticated with the termscp
+/// key or the ssh config identity files for it, otherwise with the ssh agent.
+/// Returns a local stream which is forwarded to `address:port` by the jump host
+pub fn open_jump_stream(
+    jump: &str,
+    key_storage: &SshKeyStorage,
+    proxy: Option<&Proxy>,
+    address: &str,
+    port: u16,
+) -> Result<TcpStream, FileTransferError> {
+    let jump: JumpHost = JumpHost::from_str(jump).map_err(|err| {
+        FileTransferError::new_ex(
+            FileTransferErrorType::BadAddress,
+            format!("Bad ProxyJump: {}", err),
+        )
+    })?;
+    let params: SshHostParams = key_storage.ssh_host(jump.address.as_str());
+    if params.proxy_jump.is_some() {
+        return Err(FileTransferError::new_ex(
+            FileTransferErrorType::UnsupportedFeature,
+            format!("Nested ProxyJump for {} is not supported", jump.address),
+        ));
+    }
+    let jump_address: String = params.host_name.unwrap_or_else(|| jump.address.clone());
+    let jump_port: u16 = jump.port.or(params.port).unwrap_or(22);
+    let jump_host: &str = jump.address.as_str();
+    let username: String = jump
+        .username
+        .or(params.user)
+        .unwrap_or_else(whoami::username);
+    info!(
+        "Connecting to {}:{} through jump host {}@{}:{}",
+        address, port, username, jump_address, jump_port
+    );
+    let identity: Option<PathBuf> = key_storage.identity(jump_host, username.as_str());
+    let certificate: Option<PathBuf> = identity
+        .as_deref()
+        .and_then(|key| key_storage.certificate(jump_host, key));
+    let session: Session = jump_session(
+        open_stream(jump_address.as_str(), jump_port, proxy)?,
+        identity,
+        certificate,
+        username.as_str(),
+    )?;
+    let channel: Channel = session
+        .channel_direct_tcpip(address, port, None)
+        .map_err(|err| {
+            error!("Jump host could not open tunnel: {}", err);
+            FileTransferError::new_ex(
+                FileTransferErrorType::ConnectionError,
+                format!(
+                    "Jump host could not connect to {}:{}: {}",
+                    address, port, err
+                ),
+            )
+        })?;
+    // Bridge the channel to a local socket
+    let tunnel_err = |err: io::Error| {
+        error!("Could not setup tunnel: {}", err);
+        FileTransferError::new_ex(FileTransferErrorType::ConnectionError, err.to_string())
+    };
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").map_err(tunnel_err)?;
+    let local: TcpStream =
+        TcpStream::connect(listener.local_addr().map_err(tunnel_err)?).map_err(tunnel_err)?;
+    let (remote, _) = listener.accept().map_err(tunnel_err)?;
+    thread::spawn(move || {
+        match forward(&session, channel, remote) {
+            Ok(()) => debug!("Jump host tunnel closed"),
+            Err(err) => error!("Jump host tunnel error: {}", err),
+        }
+        // Session is dropped only once the channel is done
+        drop(session);
+    });
+    Ok(local)
+}
+
+/// ### jump_session
+///
+/// Setup the ssh session with the jump host on `tcp`.
+/// If set, `certificate` is presented along with `identity`
+fn jump_session(
+    tcp: TcpStream,
+    identity: Option<PathBuf>,
+    certificate: Option<PathBuf>,
+    username: &str,
+) -> Result<Session, FileTransferError> {
+    let mut session: Session = Session::new().map_err(|err| {
+        FileTransferError::new_ex(FileTransferErrorType::ConnectionError, err.to_string())
+    })?;
+    session.set_tcp_stream(tcp);
+    if let Err(err) = session.handshake() {
+        error!("Jump host handshake failed: {}", err);
+        return Err(FileTransferError::new_ex(
+            FileTransferErrorType::ConnectionError,
+            err.to_string(),
+        ));
+    }
+    let result = match identity {
+        Some(key) if SshKeyStorage::is_security_key(key.as_path()) => {
+            debug!(
+                "Authenticating on jump host as {} with security key {}",
+                username,
+                key.display()
+            );
+            security_key::userauth_security_key(&session, username, key.as_path())?;
+            Ok(())
+        }
+        Some(key) => {
+            debug!(
+                "Authenticating on jump host as {} with key {}",
+                username,
+                key.display()
+            );
+            session.userauth_pubkey_file(username, certificate.as_deref(), key.as_path(), None)
+        }
+        None => {
+            debug!("Authenticating on jump host as {} with ssh agent", username);
+            session.userauth_agent(username)
+        }
+    };
+    match result {
+        Ok(()) if session.authenticated() => Ok(session),
+        Ok(()) => Err(FileTransferError::new_ex(
+            FileTransferErrorType::AuthenticationFailed,
+            String::from("Jump host authentication failed"),
+        )),
+        Err(err) => {
+            error!("Jump host authentication failed: {}", err);
+            Err(FileTransferError::new_ex(
+                FileTransferErrorType::AuthenticationFailed,
+                format!("Jump host: {}", err),
+            ))
+        }
+    }
+}
+
+/// ### forward
+///
+/// Forward data between `channel` and `stream` until one of the two is closed
+fn forward(session: &Session, mut channel: Channel, mut stream: TcpStream) -> io::Result<()> {
+    session.set_blocking(false);
+    stream.set_nonblocking(true)?;
+    let mut buffer: [u8; 16384] = [0; 16384];
+    loop {
+        let mut idle: bool = true;
+        // Local -> remote
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(bytes) => {
+                idle = false;
+                write_all(&mut channel, &buffer[..bytes])?;
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err),
+        }
+        // Remote -> local
+        match channel.read(&mut buffer) {
+            Ok(0) if channel.eof() => break,
+            Ok(0) => {}
+            Ok(bytes) => {
+                idle = false;
+                write_all(&mut stream, &buffer[..bytes])?;
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err),
+        }
+        if idle {
+            thread::sleep(TUNNEL_IDLE_INTERVAL);
+        }
+    }
+    let _ = stream.shutdown(Shutdown::Both);
+    let _ = channel.send_eof();
+    let _ = channel.close();
+    Ok(())
+}
+
+/// ### write_all
+///
+/// Write the entire `buf` into the non-blocking `writer`
+fn write_all<W: Write>(writer: &mut W, mut buf: &[u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+        match writer.write(buf) {
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::WriteZero)),
+            Ok(bytes) => buf = &buf[bytes..],
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(TUNNEL_IDLE_INTERVAL)
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    writer.flush().or_else(|err| match err.kind() {
+        io::ErrorKind::WouldBlock => Ok(()),
+        _ => Err(err),
+    })
+}
+
+/// ### connect_tcp
+///
+/// Open a tcp stream to `address:port`, trying all of its socket addresses
+fn connect_tcp(address: &str, port: u16) -> Result<TcpStream, FileTransferError> {
+    info!("Connecting to {}:{}", address, port);
+    let socket_addresses: Vec<SocketAddr> = match format!("{}:{}", address, port).to_socket_addrs()
+    {
+        Ok(s) => s.collect(),
+        Err(err) => {
+            return Err(FileTransferError::new_ex(
+                FileTransferErrorType::BadAddress,
+                err.to_string(),
+            ))
+        }
+    };
+    // Try addresses
+    for socket_addr in socket_addresses.iter() {
+        debug!("Trying socket address {}", socket_addr);
+        if let Ok(stream) = TcpStream::connect_timeout(socket_addr, CONNECT_TIMEOUT) {
+            debug!("{} succeded", socket_addr);
+            return Ok(stream);
+        }
+    }
+    // No address reachable; connection timeout
+    error!("No suitable socket address found; connection timeout");
+    Err(FileTransferError::new_ex(
+        FileTransferErrorType::ConnectionError,
+        String::from("Connection timeout"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_filetransfer_tunnel_jump_host() {
+        assert_eq!(
+            JumpHost::from_str("bastion").unwrap(),
+            JumpHost {
+                address: String::from("bastion"),
+                port: None,
+                username: None,
+            }
+        );
+        assert_eq!(
+            JumpHost::from_str("omar@bastion.example.com:2022").unwrap(),
+            JumpHost {
+                address: String::from("bastion.example.com"),
+                port: Some(2022),
+                username: Some(String::from("omar")),
+            }
+        );
+        assert_eq!(
+            JumpHost::from_str("ssh://omar@10.0.0.1").unwrap(),
+            JumpHost {
+                address: String::from("10.0.0.1"),
+                port: None,
+                username: Some(String::from("omar")),
+            }
+        );
+        assert!(JumpHost::from_str("bastion:abc").is_err());
+        assert!(JumpHost::from_str("omar@").is_err());
+        assert!(JumpHost::from_str("a.example.com,b.example.com").is_err());
+    }
+
+    #[test]
+    fn test_filetransfer_tunnel_open_stream_bad_address() {
+        assert_eq!(
+            open_stream("this.address.is.invalid:x", 22, None)
+                .err()
+                .unwrap()
+                .kind(),
+            FileTransferErrorType::BadAddress
+        );
+    }
+}
@@ -0,0 +1,1054 @@
+//! ## WebDAV_transfer
+//!
+//! `webdav_transfer` is the module which provides the implementation for the WebDAV file transfer
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use super::{FileTransfer, FileTransferError, FileTransferErrorType};
+use crate::fs::{FsDirectory, FsEntry, FsFile};
+use crate::utils::fmt::shadow_password;
+use crate::utils::path;
+use crate::utils::random::random_alphanumeric_with_len;
+
+// Includes
+use chrono::DateTime;
+use md5::{Digest, Md5};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use regex::Regex;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::NamedTempFile;
+use ureq::{Agent, AgentBuilder, Response};
+
+/// Characters which must be escaped in a path segment
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}')
+    .add(b'/');
+
+/// Body of the PROPFIND requests
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:resourcetype/>
+    <d:getcontentlength/>
+    <d:getlastmodified/>
+    <d:creationdate/>
+  </d:prop>
+</d:propfind>"#;
+
+// Regex
+lazy_static! {
+    /**
+     * Regex matches:
+     *  - group 1: the content of a `response` element of a multistatus
+     */
+    static ref RESPONSE_REGEX: Regex = Regex::new(r"(?s)<(?:[A-Za-z0-9_-]+:)?response(?:\s[^>]*)?>(.*?)</(?:[A-Za-z0-9_-]+:)?response>").unwrap();
+    static ref HREF_REGEX: Regex = Regex::new(r"(?s)<(?:[A-Za-z0-9_-]+:)?href(?:\s[^>]*)?>(.*?)</(?:[A-Za-z0-9_-]+:)?href>").unwrap();
+    static ref CONTENT_LENGTH_REGEX: Regex = Regex::new(r"(?s)<(?:[A-Za-z0-9_-]+:)?getcontentlength(?:\s[^>]*)?>\s*([0-9]+)\s*</").unwrap();
+    static ref LAST_MODIFIED_REGEX: Regex = Regex::new(r"(?s)<(?:[A-Za-z0-9_-]+:)?getlastmodified(?:\s[^>]*)?>(.*?)</").unwrap();
+    static ref CREATION_DATE_REGEX: Regex = Regex::new(r"(?s)<(?:[A-Za-z0-9_-]+:)?creationdate(?:\s[^>]*)?>(.*?)</").unwrap();
+    static ref COLLECTION_REGEX: Regex = Regex::new(r"<(?:[A-Za-z0-9_-]+:)?collection\s*/?>").unwrap();
+    /**
+     * Regex matches:
+     *  - group 1: parameter name
+     *  - group 2: Some(quoted value) | None
+     *  - group 3: Some(unquoted value) | None
+     */
+    static ref AUTH_PARAM_REGEX: Regex = Regex::new(r#"([A-Za-z0-9_-]+)=(?:"([^"]*)"|([^,\s]*))"#).unwrap();
+}
+
+/// ## Authentication
+///
+/// Describes how requests are authenticated
+enum Authentication {
+    None,
+    Basic,
+    Digest(DigestChallenge),
+}
+
+/// ## DigestChallenge
+///
+/// Parameters of a digest authentication challenge sent by the server
+#[derive(Debug, PartialEq)]
+struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    qop: bool,
+    nc: u32,
+}
+
+/// ## RequestBody
+///
+/// Body to send along with a request
+enum RequestBody<'a> {
+    Empty,
+    Xml(&'a str),
+    File(&'a Path),
+}
+
+/// ## WebDavFileTransfer
+///
+/// WebDAV file transfer structure
+pub struct WebDavFileTransfer {
+    agent: Option<Agent>,
+    secure: bool,
+    base_url: String,
+    username: String,
+    password: String,
+    auth: Authentication,
+    wrkdir: PathBuf,
+    upload: Option<(NamedTempFile, PathBuf)>,
+}
+
+impl WebDavFileTransfer {
+    /// ### new
+    ///
+    /// Instantiates a new `WebDavFileTransfer`. If `secure` is true, HTTPS is used
+    pub fn new(secure: bool) -> WebDavFileTransfer {
+        WebDavFileTransfer {
+            agent: None,
+            secure,
+            base_url: String::new(),
+            username: String::new(),
+            password: String::new(),
+            auth: Authentication::None,
+            wrkdir: PathBuf::from("/"),
+            upload: None,
+        }
+    }
+
+    /// ### resolve
+    ///
+    /// Fix provided path; on Windows fixes the backslashes, converting them to slashes
+    /// While on POSIX does nothing
+    #[cfg(target_os = "windows")]
+    fn resolve(p: &Path) -> PathBuf {
+        PathBuf::from(path_slash::PathExt::to_slash_lossy(p).as_str())
+    }
+
+    #[cfg(target_family = "unix")]
+    fn resolve(p: &Path) -> PathBuf {
+        p.to_path_buf()
+    }
+
+    /// ### absolutize
+    ///
+    /// Get absolute path of `p`, using the working directory as base
+    fn absolutize(&self, p: &Path) -> PathBuf {
+        path::absolutize(self.wrkdir.as_path(), Self::resolve(p).as_path())
+    }
+
+    /// ### encode_path
+    ///
+    /// Percent-encode each segment of `p`. If `dir` is true, a trailing slash is appended
+    pub(super) fn encode_path(p: &Path, dir: bool) -> String {
+        let mut encoded: String = p
+            .iter()
+            .map(|x| x.to_string_lossy().to_string())
+            .filter(|x| x != "/" && x != "\\")
+            .map(|x| format!("/{}", utf8_percent_encode(x.as_str(), PATH_SEGMENT)))
+            .collect();
+        if encoded.is_empty() || dir {
+            encoded.push('/');
+        }
+        encoded
+    }
+
+    /// ### href_to_path
+    ///
+    /// Convert the href of a multistatus response into a path
+    fn href_to_path(href: &str) -> PathBuf {
+        let href: String = Self::decode_xml_entities(href.trim());
+        // Strip scheme and authority if href is an absolute URL
+        let href: &str = match href.find("://") {
+            Some(idx) => match href[idx + 3..].find('/') {
+                Some(slash) => &href[idx + 3 + slash..],
+                None => "/",
+            },
+            None => href.as_str(),
+        };
+        let decoded: String = percent_decode_str(href).decode_utf8_lossy().to_string();
+        match decoded.trim_end_matches('/') {
+            "" => PathBuf::from("/"),
+            p => PathBuf::from(p),
+        }
+    }
+
+    /// ### decode_xml_entities
+    ///
+    /// Replace the predefined XML entities with their characters
+    pub(super) fn decode_xml_entities(s: &str) -> String {
+        s.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+
+    /// ### parse_http_time
+    ///
+    /// Parse a time from a `getlastmodified` (RFC 1123) or `creationdate` (RFC 3339) property
+    pub(super) fn parse_http_time(s: &str) -> Option<SystemTime> {
+        let s: &str = s.trim();
+        DateTime::parse_from_rfc2822(s)
+            .or_else(|_| DateTime::parse_from_rfc3339(s))
+            .ok()
+            .map(|x| UNIX_EPOCH + Duration::from_secs(x.timestamp().max(0) as u64))
+    }
+
+    /// ### parse_multistatus
+    ///
+    /// Parse a PROPFIND multistatus response into a list of `FsEntry`
+    fn parse_multistatus(body: &str) -> Vec<FsEntry> {
+        RESPONSE_REGEX
+            .captures_iter(body)
+            .filter_map(|response| {
+                let response: &str = response.get(1)?.as_str();
+                let abs_path: PathBuf =
+                    Self::href_to_path(HREF_REGEX.captures(response)?.get(1)?.as_str());
+                let name: String = abs_path
+                    .file_name()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_else(|| String::from("/"));
+                let last_change_time: SystemTime = LAST_MODIFIED_REGEX
+                    .captures(response)
+                    .and_then(|x| Self::parse_http_time(x.get(1)?.as_str()))
+                    .unwrap_or(UNIX_EPOCH);
+                let creation_time: SystemTime = CREATION_DATE_REGEX
+                    .captures(response)
+                    .and_then(|x| Self::parse_http_time(x.get(1)?.as_str()))
+                    .unwrap_or(last_change_time);
+                Some(match COLLECTION_REGEX.is_match(response) {
+                    true => FsEntry::Directory(FsDirectory {
+                        name,
+                        abs_path,
+                        last_change_time,
+                        last_access_time: last_change_time,
+                        creation_time,
+                        symlink: None,
+                        user: None,
+                        group: None,
+                        unix_pex: None,
+                    }),
+                    false => FsEntry::File(FsFile {
+                        name,
+                        size: CONTENT_LENGTH_REGEX
+                            .captures(response)
+                            .and_then(|x| x.get(1)?.as_str().parse::<usize>().ok())
+                            .unwrap_or(0),
+                        ftype: abs_path
+                            .extension()
+                            .map(|ext| String::from(ext.to_str().unwrap_or(""))),
+                        abs_path,
+                        last_change_time,
+                        last_access_time: last_change_time,
+                        creation_time,
+                        symlink: None,
+                        user: None,
+                        group: None,
+                        unix_pex: None,
+                    }),
+                })
+            })
+            .collect()
+    }
+
+    /// ### parse_challenge
+    ///
+    /// Parse the `WWW-Authenticate` header sent by the server
+    fn parse_challenge(header: &str) -> Option<Authentication> {
+        let (scheme, params): (&str, &str) = match header.trim().find(' ') {
+            Some(idx) => (&header.trim()[..idx], &header.trim()[idx + 1..]),
+            None => (header.trim(), ""),
+        };
+        match scheme.to_ascii_lowercase().as_str() {
+            "basic" => Some(Authentication::Basic),
+            "digest" => {
+                let mut realm: Option<String> = None;
+                let mut nonce: Option<String> = None;
+                let mut opaque: Option<String> = None;
+                let mut qop: bool = false;
+                for param in AUTH_PARAM_REGEX.captures_iter(params) {
+                    let value: String = param
+                        .get(2)
+                        .or_else(|| param.get(3))
+                        .map(|x| x.as_str().to_string())
+                        .unwrap_or_default();
+                    match param.get(1).map(|x| x.as_str().to_ascii_lowercase()) {
+                        Some(key) if key == "realm" => realm = Some(value),
+                        Some(key) if key == "nonce" => nonce = Some(value),
+                        Some(key) if key == "opaque" => opaque = Some(value),
+                        Some(key) if key == "qop" => {
+                            qop = value.split(',').any(|x| x.trim() == "auth")
+                        }
+                        _ => {}
+                    }
+                }
+                Some(Authentication::Digest(DigestChallenge {
+                    realm: realm?,
+                    nonce: nonce?,
+                    opaque,
+                    qop,
+                    nc: 0,
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    /// ### md5_hex
+    ///
+    /// Get the MD5 of `s` as an hex string
+    fn md5_hex(s: &str) -> String {
+        Md5::digest(s.as_bytes())
+            .iter()
+            .map(|x| format!("{:02x}", x))
+            .collect()
+    }
+
+    /// ### digest_response
+    ///
+    /// Compute the response of a digest challenge (RFC 2617).
+    /// `qop` must contain the nonce count and the client nonce when `qop=auth` is used
+    #[allow(clippy::too_many_arguments)]
+    fn digest_response(
+        username: &str,
+        password: &str,
+        realm: &str,
+        nonce: &str,
+        method: &str,
+        uri: &str,
+        qop: Option<(&str, &str)>,
+    ) -> String {
+        let ha1: String = Self::md5_hex(format!("{}:{}:{}", username, realm, password).as_str());
+        let ha2: String = Self::md5_hex(format!("{}:{}", method, uri).as_str());
+        match qop {
+            Some((nc, cnonce)) => {
+                Self::md5_hex(format!("{}:{}:{}:{}:auth:{}", ha1, nonce, nc, cnonce, ha2).as_str())
+            }
+            None => Self::md5_hex(format!("{}:{}:{}", ha1, nonce, ha2).as_str()),
+        }
+    }
+
+    /// ### authorization
+    ///
+    /// Get the value for the `Authorization` header for the provided request
+    fn authorization(&mut self, method: &str, uri: &str) -> Option<String> {
+        match &mut self.auth {
+            Authentication::None => None,
+            Authentication::Basic => Some(format!(
+                "Basic {}",
+                base64::encode(format!("{}:{}", self.username, self.password))
+            )),
+            Authentication::Digest(challenge) => {
+                challenge.nc += 1;
+                let nc: String = format!("{:08x}", challenge.nc);
+                let cnonce: String = random_alphanumeric_with_len(16);
+                let response: String = Self::digest_response(
+                    self.username.as_str(),
+                    self.password.as_str(),
+                    challenge.realm.as_str(),
+                    challenge.nonce.as_str(),
+                    method,
+                    uri,
+                    match challenge.qop {
+                        true => Some((nc.as_str(), cnonce.as_str())),
+                        false => None,
+                    },
+                );
+                let mut header: String = format!(
+                    "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", response=\"{}\", algorithm=MD5",
+                    self.username, challenge.realm, challenge.nonce, uri, response
+                );
+                if challenge.qop {
+                    header
+                        .push_str(format!(", qop=auth, nc={}, cnonce=\"{}\"", nc, cnonce).as_str());
+                }
+                if let Some(opaque) = challenge.opaque.as_ref() {
+                    header.push_str(format!(", opaque=\"{}\"", opaque).as_str());
+                }
+                Some(header)
+            }
+        }
+    }
+
+    /// ### request
+    ///
+    /// Perform a request on the resource at `uri`.
+    /// If the server asks for authentication, the request is performed again with the requested scheme
+    fn request(
+        &mut self,
+        method: &str,
+        uri: &str,
+        headers: &[(&str, &str)],
+        body: RequestBody,
+    ) -> Result<Response, FileTransferError> {
+        let agent: Agent = match self.agent.as_ref() {
+            Some(agent) => agent.clone(),
+            None => {
+                return Err(FileTransferError::new(
+                    FileTransferErrorType::UninitializedSession,
+                ))
+            }
+        };
+        let url: String = format!("{}{}", self.base_url, uri);
+        let mut retried: bool = false;
+        loop {
+            debug!("{} {}", method, url);
+            let mut request = agent.request(method, url.as_str());
+            for (key, value) in headers.iter() {
+                request = request.set(key, value);
+            }
+            if let Some(authorization) = self.authorization(method, uri) {
+                request = request.set("Authorization", authorization.as_str());
+            }
+            let result = match &body {
+                RequestBody::Empty => request.call(),
+                RequestBody::Xml(xml) => request
+                    .set("Content-Type", "application/xml; charset=utf-8")
+                    .send_string(xml),
+                RequestBody::File(p) => {
+                    let file: File = match File::open(p) {
+                        Ok(f) => f,
+                        Err(err) => {
+                            return Err(FileTransferError::new_ex(
+                                FileTransferErrorType::ProtocolError,
+                                err.to_string(),
+                            ))
+                        }
+                    };
+                    let size: u64 = file.metadata().map(|x| x.len()).unwrap_or(0);
+                    request
+                        .set("Content-Length", size.to_string().as_str())
+                        .send(file)
+                }
+            };
+            match result {
+                Ok(response) => return Ok(response),
+                Err(ureq::Error::Status(401, response)) if !retried => {
+                    // Setup authentication from challenge and try again
+                    match response
+                        .header("WWW-Authenticate")
+                        .and_then(Self::parse_challenge)
+                    {
+                        Some(auth) => {
+                            debug!("Server requested authentication; trying again");
+                            self.auth = auth;
+                            retried = true;
+                        }
+                        None => {
+                            return Err(FileTransferError::new_ex(
+                                FileTransferErrorType::AuthenticationFailed,
+                                String::from("unsupported authentication scheme"),
+                            ))
+                        }
+                    }
+                }
+                Err(ureq::Error::Status(code, response)) => {
+                    error!("{} {} failed: {}", method, url, code);
+                    return Err(Self::status_error(code, response.status_text()));
+                }
+                Err(ureq::Error::Transport(err)) => {
+                    error!("{} {} failed: {}", method, url, err);
+                    return Err(FileTransferError::new_ex(
+                        FileTransferErrorType::ConnectionError,
+                        err.to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// ### status_error
+    ///
+    /// Convert an HTTP error status into a `FileTransferError`
+    pub(super) fn status_error(code: u16, text: &str) -> FileTransferError {
+        let kind: FileTransferErrorType = match code {
+            401 => FileTransferErrorType::AuthenticationFailed,
+            403 => FileTransferErrorType::PexError,
+            404 | 409 => FileTransferErrorType::NoSuchFileOrDirectory,
+            _ => FileTransferErrorType::ProtocolError,
+        };
+        FileTransferError::new_ex(kind, format!("{} {}", code, text))
+    }
+
+    /// ### propfind
+    ///
+    /// Perform a PROPFIND request with the provided depth and parse its response
+    fn propfind(&mut self, uri: &str, depth: &str) -> Result<Vec<FsEntry>, FileTransferError> {
+        let response: Response = self.request(
+            "PROPFIND",
+            uri,
+            &[("Depth", depth)],
+            RequestBody::Xml(PROPFIND_BODY),
+        )?;
+        // A redirect means that the collection has been requested without the trailing slash
+        if (300..400).contains(&response.status()) && !uri.ends_with('/') {
+            return self.propfind(format!("{}/", uri).as_str(), depth);
+        }
+        match response.into_string() {
+            Ok(body) => Ok(Self::parse_multistatus(body.as_str())),
+            Err(err) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::ProtocolError,
+                err.to_string(),
+            )),
+        }
+    }
+}
+
+impl FileTransfer for WebDavFileTransfer {
+    /// ### connect
+    ///
+    /// Connect to the remote server
+    fn connect(
+        &mut self,
+        address: String,
+        port: u16,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Result<Option<String>, FileTransferError> {
+        let scheme: &str = match self.secure {
+            true => "https",
+            false => "http",
+        };
+        self.base_url = format!("{}://{}:{}", scheme, address, port);
+        self.username = username.unwrap_or_default();
+        self.password = password.unwrap_or_default();
+        self.auth = Authentication::None;
+        self.wrkdir = PathBuf::from("/");
+        info!(
+            "Connecting to {} with username: {}, password: {}",
+            self.base_url,
+            self.username,
+            shadow_password(self.password.as_str())
+        );
+        self.agent = Some(
+            AgentBuilder::new()
+                .redirects(0)
+                .timeout_connect(Duration::from_secs(30))
+                .build(),
+        );
+        // Probe server; resources other than the root might not be listable, so accept any non-auth error
+        match self.request(
+            "PROPFIND",
+            "/",
+            &[("Depth", "0")],
+            RequestBody::Xml(PROPFIND_BODY),
+        ) {
+            Ok(_) => {}
+            Err(err)
+                if err.kind() == FileTransferErrorType::AuthenticationFailed
+                    || err.kind() == FileTransferErrorType::ConnectionError =>
+            {
+                error!("Failed to connect: {}", err);
+                self.agent = None;
+                return Err(err);
+            }
+            Err(err) => warn!("Could not stat server root: {}", err),
+        }
+        info!("Connection successfully established");
+        Ok(None)
+    }
+
+    /// ### disconnect
+    ///
+    /// Disconnect from the remote server
+    fn disconnect(&mut self) -> Result<(), FileTransferError> {
+        info!("Disconnecting from WebDAV server...");
+        match self.agent.take() {
+            Some(_) => {
+                self.auth = Authentication::None;
+                Ok(())
+            }
+            None => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
+    /// ### is_connected
+    ///
+    /// Indicates whether the client is connected to remote
+    fn is_connected(&self) -> bool {
+        self.agent.is_some()
+    }
+
+    /// ### pwd
+    ///
+    /// Print working directory
+    fn pwd(&mut self) -> Result<PathBuf, FileTransferError> {
+        info!("PWD");
+        match self.is_connected() {
+            true => Ok(self.wrkdir.clone()),
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+
+    /// ### change_dir
+    ///
+    /// Change working directory
+    fn change_dir(&mut self, dir: &Path) -> Result<PathBuf, FileTransferError> {
+        let dir: PathBuf = self.absolutize(dir);
+        info!("Changing directory to {}", dir.display());
+        match self.stat(dir.as_path())? {
+            FsEntry::Directory(_) => {
+                self.wrkdir = dir;
+                Ok(self.wrkdir.clone())
+            }
+            FsEntry::File(_) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::DirStatFailed,
+                format!("\"{}\" is not a directory", dir.display()),
+            )),
+        }
+    }
+
+    /// ### copy
+    ///
+    /// Copy file to destination
+    fn copy(&mut self, src: &FsEntry, dst: &Path) -> Result<(), FileTransferError> {
+        let dst: PathBuf = self.absolutize(dst);
+        info!(
+            "Copying {} to {}",
+            src.get_abs_path().display(),
+            dst.display()
+        );
+        let destination: String = format!(
+            "{}{}",
+            self.base_url,
+            Self::encode_path(dst.as_path(), src.is_dir())
+        );
+        self.request(
+            "COPY",
+            Self::encode_path(src.get_abs_path().as_path(), src.is_dir()).as_str(),
+            &[
+                ("Destination", destination.as_str()),
+                ("Overwrite", "F"),
+                ("Depth", "infinity"),
+            ],
+            RequestBody::Empty,
+        )
+        .map(|_| ())
+    }
+
+    /// ### list_dir
+    ///
+    /// List directory entries
+    fn list_dir(&mut self, path: &Path) -> Result<Vec<FsEntry>, FileTransferError> {
+        let dir: PathBuf = self.absolutize(path);
+        info!("LIST dir {}", dir.display());
+        let entries: Vec<FsEntry> =
+            self.propfind(Self::encode_path(dir.as_path(), true).as_str(), "1")?;
+        // Remove the directory itself from its entries
+        Ok(entries
+            .into_iter()
+            .filter(|x| x.get_abs_path() != dir)
+            .collect())
+    }
+
+    /// ### mkdir
+    ///
+    /// Make directory
+    /// In case the directory already exists, it must return an Error of kind `FileTransferErrorType::DirectoryAlreadyExists`
+    fn mkdir(&mut self, dir: &Path) -> Result<(), FileTransferError> {
+        let dir: PathBuf = self.absolutize(dir);
+        info!("Making directory {}", dir.display());
+        if self.stat(dir.as_path()).is_ok() {
+            error!("Directory {} already exists", dir.display());
+            return Err(FileTransferError::new(
+                FileTransferErrorType::DirectoryAlreadyExists,
+            ));
+        }
+        match self.request(
+            "MKCOL",
+            Self::encode_path(dir.as_path(), true).as_str(),
+            &[],
+            RequestBody::Empty,
+        ) {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == FileTransferErrorType::UninitializedSession => Err(err),
+            Err(err) => Err(FileTransferError::new_ex(
+                FileTransferErrorType::FileCreateDenied,
+                err.to_string(),
+            )),
+        }
+    }
+
+    /// ### remove
+    ///
+    /// Remove a file or a directory
+    fn remove(&mut self, file: &FsEntry) -> Result<(), FileTransferError> {
+        info!("Removing {}", file.get_abs_path().display());
+        self.request(
+            "DELETE",
+            Self::encode_path(file.get_abs_path().as_path(), file.is_dir()).as_str(),
+            &[],
+            RequestBody::Empty,
+        )
+        .map(|_| ())
+    }
+
+    /// ### rename
+    ///
+    /// Rename file or a directory
+    fn rename(&mut self, file: &FsEntry, dst: &Path) -> Result<(), FileTransferError> {
+        let dst: PathBuf = self.absolutize(dst);
+        info!(
+            "Renaming {} to {}",
+            file.get_abs_path().display(),
+            dst.display()
+        );
+        let destination: String = format!(
+            "{}{}",
+            self.base_url,
+            Self::encode_path(dst.as_path(), file.is_dir())
+        );
+        self.request(
+            "MOVE",
+            Self::encode_path(file.get_abs_path().as_path(), file.is_dir()).as_str(),
+            &[("Destination", destination.as_str()), ("Overwrite", "F")],
+            RequestBody::Empty,
+        )
+        .map(|_| ())
+    }
+
+    /// ### stat
+    ///
+    /// Stat file and return FsEntry
+    fn stat(&mut self, path: &Path) -> Result<FsEntry, FileTransferError> {
+        let path: PathBuf = self.absolutize(path);
+        info!("Stat {}", path.display());
+        match self
+            .propfind(Self::encode_path(path.as_path(), false).as_str(), "0")?
+            .into_iter()
+            .next()
+        {
+            Some(entry) => Ok(entry),
+            None => Err(FileTransferError::new_ex(
+                FileTransferErrorType::NoSuchFileOrDirectory,
+                path.display().to_string(),
+            )),
+        }
+    }
+
+    /// ### exec
+    ///
+    /// Execute a command on remote host
+    fn exec(&mut self, _cmd: &str) -> Result<String, FileTransferError> {
+        Err(FileTransferError::new(
+            FileTransferErrorType::UnsupportedFeature,
+        ))
+    }
+
+    /// ### send_file
+    ///
+    /// Send file to remote
+    /// File name is referred to the name of the file as it will be saved
+    /// Data contains the file data
+    /// Returns file and its size
+    fn send_file(
+        &mut self,
+        _local: &FsFile,
+        file_name: &Path,
+    ) -> Result<Box<dyn Write>, FileTransferError> {
+        if !self.is_connected() {
+            return Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            ));
+        }
+        let file_name: PathBuf = self.absolutize(file_name);
+        info!("Sending file {}", file_name.display());
+        // Data is buffered into a temporary file, which is sent with PUT once finalized
+        let tmpfile: NamedTempFile = match NamedTempFile::new() {
+            Ok(f) => f,
+            Err(err) => {
+                return Err(FileTransferError::new_ex(
+                    FileTransferErrorType::FileCreateDenied,
+                    err.to_string(),
+                ))
+            }
+        };
+        let writer: File = match tmpfile.reopen() {
+            Ok(f) => f,
+            Err(err) => {
+                return Err(FileTransferError::new_ex(
+                    FileTransferErrorType::FileCreateDenied,
+                    err.to_string(),
+                ))
+            }
+        };
+        self.upload = Some((tmpfile, file_name));
+        Ok(Box::new(writer))
+    }
+
+    /// ### recv_file
+    ///
+    /// Receive file from remote with provided name
+    /// Returns file and its size
+    fn recv_file(&mut self, file: &FsFile) -> Result<Box<dyn Read>, FileTransferError> {
+        info!("Receiving file {}", file.abs_path.display());
+        let response: Response = self.request(
+            "GET",
+            Self::encode_path(file.abs_path.as_path(), false).as_str(),
+            &[],
+            RequestBody::Empty,
+        )?;
+        Ok(Box::new(response.into_reader()))
+    }
+
+    /// ### on_sent
+    ///
+    /// Finalize send method.
+    /// This method must be implemented only if necessary; in case you don't need it, just return `Ok(())`
+    /// The purpose of this method is to finalize the connection with the peer when writing data.
+    /// This is necessary for some protocols such as FTP.
+    /// You must call this method each time you want to finalize the write of the remote file.
+    fn on_sent(&mut self, writable: Box<dyn Write>) -> Result<(), FileTransferError> {
+        info!("Finalizing put stream");
+        drop(writable);
+        if !self.is_connected() {
+            return Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            ));
+        }
+        match self.upload.take() {
+            Some((tmpfile, file_name)) => self
+                .request(
+                    "PUT",
+                    Self::encode_path(file_name.as_path(), false).as_str(),
+                    &[("Content-Type", "application/octet-stream")],
+                    RequestBody::File(tmpfile.path()),
+                )
+                .map(|_| ()),
+            None => Err(FileTransferError::new_ex(
+                FileTransferErrorType::ProtocolError,
+                String::from("no pending upload"),
+            )),
+        }
+    }
+
+    /// ### on_recv
+    ///
+    /// Finalize recv method.
+    /// This method must be implemented only if necessary; in case you don't need it, just return `Ok(())`
+    /// The purpose of this method is to finalize the connection with the peer when reading data.
+    /// This mighe be necessary for some protocols.
+    /// You must call this method each time you want to finalize the read of the remote file.
+    fn on_recv(&mut self, readable: Box<dyn Read>) -> Result<(), FileTransferError> {
+        info!("Finalizing get");
+        drop(readable);
+        match self.is_connected() {
+            true => Ok(()),
+            false => Err(FileTransferError::new(
+                FileTransferErrorType::UninitializedSession,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::utils::file::open_file;
+    use crate::utils::test_helpers::{create_sample_file_entry, make_fsentry};
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_filetransfer_webdav_new() {
+        let client: WebDavFileTransfer = WebDavFileTransfer::new(true);
+        assert_eq!(client.secure, true);
+        assert!(client.agent.is_none());
+        assert_eq!(client.wrkdir, PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_filetransfer_webdav_encode_path() {
+        assert_eq!(
+            WebDavFileTransfer::encode_path(Path::new("/"), false).as_str(),
+            "/"
+        );
+        assert_eq!(
+            WebDavFileTransfer::encode_path(Path::new("/remote.php/dav/files/omar"), true).as_str(),
+            "/remote.php/dav/files/omar/"
+        );
+        assert_eq!(
+            WebDavFileTransfer::encode_path(Path::new("/docs/my file#1.txt"), false).as_str(),
+            "/docs/my%20file%231.txt"
+        );
+        assert_eq!(
+            WebDavFileTransfer::href_to_path("/docs/my%20file%231.txt"),
+            PathBuf::from("/docs/my file#1.txt")
+        );
+        assert_eq!(
+            WebDavFileTransfer::href_to_path("https://cloud.example.com/docs/a&amp;b/"),
+            PathBuf::from("/docs/a&b")
+        );
+        assert_eq!(WebDavFileTransfer::href_to_path("/"), PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_filetransfer_webdav_parse_multistatus() {
+        let body: &str = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/files/omar/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:collection/></d:resourcetype>
+        <d:getlastmodified>Tue, 31 Aug 2021 10:20:30 GMT</d:getlastmodified>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/files/omar/Readme%20me.md</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype/>
+        <d:getcontentlength>8192</d:getcontentlength>
+        <d:getlastmodified>Tue, 31 Aug 2021 10:20:30 GMT</d:getlastmodified>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:responsedescription>ok</d:responsedescription>
+  </d:response>
+  <D:response xmlns:D="DAV:">
+    <D:href>/files/omar/Photos/</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:resourcetype><D:collection /></D:resourcetype>
+        <D:creationdate>2021-08-30T08:00:00Z</D:creationdate>
+      </D:prop>
+    </D:propstat>
+  </D:response>
+</d:multistatus>"#;
+        let entries: Vec<FsEntry> = WebDavFileTransfer::parse_multistatus(body);
+        assert_eq!(entries.len(), 3);
+        assert!(entries[0].is_dir());
+        assert_eq!(entries[0].get_name(), "omar");
+        assert_eq!(
+            entries[0].get_last_change_time(),
+            UNIX_EPOCH + Duration::from_secs(1630405230)
+        );
+        let file: &FsFile = match &entries[1] {
+            FsEntry::File(f) => f,
+            FsEntry::Directory(_) => panic!("Expected file"),
+        };
+        assert_eq!(file.name.as_str(), "Readme me.md");
+        assert_eq!(file.abs_path, PathBuf::from("/files/omar/Readme me.md"));
+        assert_eq!(file.size, 8192);
+        assert_eq!(file.ftype.as_deref(), Some("md"));
+        assert!(entries[2].is_dir());
+        assert_eq!(entries[2].get_name(), "Photos");
+        assert_eq!(
+            entries[2].get_creation_time(),
+            UNIX_EPOCH + Duration::from_secs(1630310400)
+        );
+    }
+
+    #[test]
+    fn test_filetransfer_webdav_auth() {
+        // Basic
+        assert!(matches!(
+            WebDavFileTransfer::parse_challenge("Basic realm=\"nextcloud\""),
+            Some(Authentication::Basic)
+        ));
+        // Digest
+        match WebDavFileTransfer::parse_challenge("Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"") {
+            Some(Authentication::Digest(challenge)) => assert_eq!(
+                challenge,
+                DigestChallenge {
+                    realm: String::from("testrealm@host.com"),
+                    nonce: String::from("dcd98b7102dd2f0e8b11d0f600bfb0c093"),
+                    opaque: Some(String::from("5ccc069c403ebaf9f0171e9517f40e41")),
+                    qop: true,
+                    nc: 0,
+                }
+            ),
+            _ => panic!("Expected digest challenge"),
+        }
+        assert!(WebDavFileTransfer::parse_challenge("Digest qop=\"auth\"").is_none());
+        assert!(WebDavFileTransfer::parse_challenge("Bearer").is_none());
+        // Digest response (RFC 2617 example)
+        assert_eq!(
+            WebDavFileTransfer::digest_response(
+                "Mufasa",
+                "Circle Of Life",
+                "testrealm@host.com",
+                "dcd98b7102dd2f0e8b11d0f600bfb0c093",
+                "GET",
+                "/dir/index.html",
+                Some(("00000001", "0a4f113b"))
+            )
+            .as_str(),
+            "6629fae49393a05397450978507c4ef1"
+        );
+    }
+
+    #[test]
+    fn test_filetransfer_webdav_uninitialized() {
+        let file: FsFile = create_sample_file_entry().0;
+        let mut client: WebDavFileTransfer = WebDavFileTransfer::new(false);
+        assert!(client.change_dir(Path::new("/tmp")).is_err());
+        assert!(client.disconnect().is_err());
+        assert!(client.list_dir(Path::new("/tmp")).is_err());
+        assert!(client.mkdir(Path::new("/tmp")).is_err());
+        assert!(client
+            .remove(&make_fsentry(PathBuf::from("/nowhere"), false))
+            .is_err());
+        assert!(client
+            .rename(
+                &make_fsentry(PathBuf::from("/nowhere"), false),
+                PathBuf::from("/culonia").as_path()
+            )
+            .is_err());
+        assert!(client.pwd().is_err());
+        assert!(client.stat(Path::new("/tmp")).is_err());
+        assert!(client.recv_file(&file).is_err());
+        assert!(client.send_file(&file, Path::new("/tmp/omar.txt")).is_err());
+        let (_, temp): (FsFile, tempfile::NamedTempFile) = create_sample_file_entry();
+        let readable: Box<dyn Read> = Box::new(std::fs::File::open(temp.path()).unwrap());
+        assert!(client.on_recv(readable).is_err());
+        let (_, temp): (FsFile, tempfile::NamedTempFile) = create_sample_file_entry();
+        let writable: Box<dyn Write> =
+            Box::new(open_file(temp.path(), true, true, true).ok().unwrap());
+        assert!(client.on_sent(writable).is_err());
+    }
+}
@@ -27,9 +27,8 @@
  */
 // Locals
 use super::FsEntry;
-use crate::utils::fmt::{fmt_path_elide, fmt_pex, fmt_time};
+use crate::utils::fmt::{display_format, fmt_path_elide, fmt_pex, fmt_size, fmt_time};
 // Ext
-use bytesize::ByteSize;
 use regex::Regex;
 #[cfg(target_family = "unix")]
 use users::{get_group_by_gid, get_user_by_uid};
@@ -187,13 +186,14 @@ impl Formatter {
         fmt_len: Option<&usize>,
         fmt_extra: Option<&String>,
     ) -> String {
-        // Get date (use extra args as format or default "%b %d %Y %H:%M")
+        // Get date (use extra args as format or the configured date and clock formats)
         let datetime: String = fmt_time(
             fsentry.get_last_access_time(),
             match fmt_extra {
-                Some(fmt) => fmt.as_ref(),
-                None => "%b %d %Y %H:%M",
-            },
+                Some(fmt) => fmt.to_string(),
+                None => display_format().datetime_format(false),
+            }
+            .as_str(),
         );
         // Add to cur str, prefix and the key value
         format!(
@@ -220,9 +220,10 @@ impl Formatter {
         let datetime: String = fmt_time(
             fsentry.get_creation_time(),
             match fmt_extra {
-                Some(fmt) => fmt.as_ref(),
-                None => "%b %d %Y %H:%M",
-            },
+                Some(fmt) => fmt.to_string(),
+                None => display_format().datetime_format(false),
+            }
+            .as_str(),
         );
         // Add to cur str, prefix and the key value
         format!(
@@ -284,9 +285,10 @@ impl Formatter {
         let datetime: String = fmt_time(
             fsentry.get_last_change_time(),
             match fmt_extra {
-                Some(fmt) => fmt.as_ref(),
-                None => "%b %d %Y %H:%M",
-            },
+                Some(fmt) => fmt.to_string(),
+                None => display_format().datetime_format(false),
+            }
+            .as_str(),
         );
         // Add to cur str, prefix and the key value
         format!(
@@ -375,9 +377,9 @@ impl Formatter {
     ) -> String {
         if fsentry.is_file() {
             // Get byte size
-            let size: ByteSize = ByteSize(fsentry.get_size() as u64);
+            let size: String = fmt_size(fsentry.get_size() as u64);
             // Add to cur str, prefix and the key value
-            format!("{}{}{:10}", cur_str, prefix, size.to_string())
+            format!("{}{}{:10}", cur_str, prefix, size)
         } else {
             // Add to cur str, prefix and the key value
             format!("{}{}          ", cur_str, prefix)
@@ -33,7 +33,7 @@ use super::FsEntry;
 use formatter::Formatter;
 // Ext
 use std::cmp::Reverse;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::string::ToString;
@@ -56,6 +56,9 @@ pub enum FileSorting {
     ModifyTime,
     CreationTime,
     Size,
+    Extension,
+    Owner,
+    Permissions,
 }
 
 /// ## GroupDirs
@@ -79,6 +82,7 @@ pub struct FileExplorer {
     pub(crate) opts: ExplorerOpts,            // Explorer options
     pub(crate) fmt: Formatter,                // FsEntry formatter
     files: Vec<FsEntry>,                      // Files in directory
+    marks: BTreeMap<PathBuf, FsEntry>,        // Marked entries, by absolute path
 }
 
 impl Default for FileExplorer {
@@ -92,6 +96,7 @@ impl Default for FileExplorer {
             opts: ExplorerOpts::empty(),
             fmt: Formatter::default(),
             files: Vec::new(),
+            marks: BTreeMap::new(),
         }
     }
 }
@@ -191,6 +196,73 @@ impl FileExplorer {
         filtered.get(idx).copied()
     }
 
+    // Marks
+
+    /// ### mark_files
+    ///
+    /// Mark the files at the provided relative indexes and unmark the other files shown in the
+    /// current directory. Marks in other directories are kept
+    pub fn mark_files(&mut self, indexes: &[usize]) {
+        let opts: ExplorerOpts = self.opts;
+        for (idx, entry) in self
+            .files
+            .iter()
+            .filter(|x| opts.intersects(ExplorerOpts::SHOW_HIDDEN_FILES) || !x.is_hidden())
+            .enumerate()
+        {
+            match indexes.contains(&idx) {
+                true => self.marks.insert(entry.get_abs_path(), entry.clone()),
+                false => self.marks.remove(entry.get_abs_path().as_path()),
+            };
+        }
+    }
+
+    /// ### unmark
+    ///
+    /// Remove mark for entry at `path`
+    pub fn unmark(&mut self, path: &Path) {
+        self.marks.remove(path);
+    }
+
+    /// ### clear_marks
+    ///
+    /// Remove all marks
+    pub fn clear_marks(&mut self) {
+        self.marks.clear();
+    }
+
+    /// ### is_marked
+    ///
+    /// Returns whether entry at `path` is marked
+    pub fn is_marked(&self, path: &Path) -> bool {
+        self.marks.contains_key(path)
+    }
+
+    /// ### iter_marked
+    ///
+    /// Iterate over marked entries, from any directory
+    pub fn iter_marked(&self) -> impl Iterator<Item = &FsEntry> + '_ {
+        self.marks.values()
+    }
+
+    /// ### marked_count
+    ///
+    /// Returns the amount of marked entries
+    pub fn marked_count(&self) -> usize {
+        self.marks.len()
+    }
+
+    /// ### marked_indexes
+    ///
+    /// Returns the relative indexes of the marked files in the current directory
+    pub fn marked_indexes(&self) -> Vec<usize> {
+        self.iter_files()
+            .enumerate()
+            .filter(|(_, x)| self.is_marked(x.get_abs_path().as_path()))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
     // Formatting
 
     /// ### fmt_file
@@ -241,6 +313,9 @@ impl FileExplorer {
             FileSorting::CreationTime => self.sort_files_by_creation_time(),
             FileSorting::ModifyTime => self.sort_files_by_mtime(),
             FileSorting::Size => self.sort_files_by_size(),
+            FileSorting::Extension => self.sort_files_by_extension(),
+            FileSorting::Owner => self.sort_files_by_owner(),
+            FileSorting::Permissions => self.sort_files_by_permissions(),
         }
         // Directories first (NOTE: MUST COME AFTER OTHER SORTING)
         // Group directories if necessary
@@ -284,6 +359,47 @@ impl FileExplorer {
         self.files.sort_by_key(|b: &FsEntry| Reverse(b.get_size()));
     }
 
+    /// ### sort_files_by_extension
+    ///
+    /// Sort files by extension, then by name; files without extension come first
+    fn sort_files_by_extension(&mut self) {
+        self.files.sort_by_key(|x: &FsEntry| {
+            (
+                x.get_ftype().map(|x| x.to_lowercase()),
+                x.get_name().to_lowercase(),
+            )
+        });
+    }
+
+    /// ### sort_files_by_owner
+    ///
+    /// Sort files by owner uid, then by name; files without owner come last
+    fn sort_files_by_owner(&mut self) {
+        self.files.sort_by_key(|x: &FsEntry| {
+            (
+                x.get_user().is_none(),
+                x.get_user(),
+                x.get_name().to_lowercase(),
+            )
+        });
+    }
+
+    /// ### sort_files_by_permissions
+    ///
+    /// Sort files by permissions, then by name; the most permissive come first,
+    /// files without permissions come last
+    fn sort_files_by_permissions(&mut self) {
+        self.files.sort_by_key(|x: &FsEntry| {
+            (
+                Reverse(
+                    x.get_unix_pex()
+                        .map(|(u, g, o)| (u.as_byte(), g.as_byte(), o.as_byte())),
+                ),
+                x.get_name().to_lowercase(),
+            )
+        });
+    }
+
     /// ### sort_files_directories_first
     ///
     /// Sort files; directories come first
@@ -322,6 +438,9 @@ impl ToString for FileSorting {
             FileSorting::ModifyTime => "by_mtime",
             FileSorting::Name => "by_name",
             FileSorting::Size => "by_size",
+            FileSorting::Extension => "by_extension",
+            FileSorting::Owner => "by_owner",
+            FileSorting::Permissions => "by_permissions",
         })
     }
 }
@@ -334,6 +453,9 @@ impl FromStr for FileSorting {
             "by_mtime" => Ok(FileSorting::ModifyTime),
             "by_name" => Ok(FileSorting::Name),
             "by_size" => Ok(FileSorting::Size),
+            "by_extension" => Ok(FileSorting::Extension),
+            "by_owner" => Ok(FileSorting::Owner),
+            "by_permissions" => Ok(FileSorting::Permissions),
             _ => Err(()),
         }
     }
@@ -520,6 +642,56 @@ mod tests {
         assert_eq!(explorer.files.get(2).unwrap().get_name(), "CONTRIBUTING.md");
     }
 
+    #[test]
+    fn test_fs_explorer_sort_by_extension() {
+        let mut explorer: FileExplorer = FileExplorer::default();
+        explorer.set_files(vec![
+            make_fs_file("README.md", Some("md"), Some(0), None),
+            make_fs_file("main.rs", Some("rs"), Some(0), None),
+            make_fs_file("LICENSE", None, Some(0), None),
+            make_fs_file("CHANGELOG.MD", Some("MD"), Some(0), None),
+        ]);
+        explorer.sort_by(FileSorting::Extension);
+        assert_eq!(explorer.files.first().unwrap().get_name(), "LICENSE");
+        assert_eq!(explorer.files.get(1).unwrap().get_name(), "CHANGELOG.MD");
+        assert_eq!(explorer.files.get(2).unwrap().get_name(), "README.md");
+        assert_eq!(explorer.files.get(3).unwrap().get_name(), "main.rs");
+    }
+
+    #[test]
+    fn test_fs_explorer_sort_by_owner() {
+        let mut explorer: FileExplorer = FileExplorer::default();
+        explorer.set_files(vec![
+            make_fs_file("b.txt", None, Some(1000), None),
+            make_fs_file("c.txt", None, None, None),
+            make_fs_file("d.txt", None, Some(0), None),
+            make_fs_file("a.txt", None, Some(1000), None),
+        ]);
+        explorer.sort_by(FileSorting::Owner);
+        assert_eq!(explorer.files.first().unwrap().get_name(), "d.txt");
+        assert_eq!(explorer.files.get(1).unwrap().get_name(), "a.txt");
+        assert_eq!(explorer.files.get(2).unwrap().get_name(), "b.txt");
+        assert_eq!(explorer.files.get(3).unwrap().get_name(), "c.txt");
+    }
+
+    #[test]
+    fn test_fs_explorer_sort_by_permissions() {
+        let mut explorer: FileExplorer = FileExplorer::default();
+        explorer.set_files(vec![
+            make_fs_file("b.txt", None, Some(0), Some((6, 4, 4))),
+            make_fs_file("c.txt", None, Some(0), None),
+            make_fs_file("d.sh", None, Some(0), Some((7, 5, 5))),
+            make_fs_file("a.txt", None, Some(0), Some((6, 4, 4))),
+            make_fs_file("e.key", None, Some(0), Some((6, 0, 0))),
+        ]);
+        explorer.sort_by(FileSorting::Permissions);
+        assert_eq!(explorer.files.first().unwrap().get_name(), "d.sh");
+        assert_eq!(explorer.files.get(1).unwrap().get_name(), "a.txt");
+        assert_eq!(explorer.files.get(2).unwrap().get_name(), "b.txt");
+        assert_eq!(explorer.files.get(3).unwrap().get_name(), "e.key");
+        assert_eq!(explorer.files.get(4).unwrap().get_name(), "c.txt");
+    }
+
     #[test]
     fn test_fs_explorer_sort_by_name_and_dirs_first() {
         let mut explorer: FileExplorer = FileExplorer::default();
@@ -617,6 +789,9 @@ mod tests {
         assert_eq!(FileSorting::ModifyTime.to_string(), "by_mtime");
         assert_eq!(FileSorting::Name.to_string(), "by_name");
         assert_eq!(FileSorting::Size.to_string(), "by_size");
+        assert_eq!(FileSorting::Extension.to_string(), "by_extension");
+        assert_eq!(FileSorting::Owner.to_string(), "by_owner");
+        assert_eq!(FileSorting::Permissions.to_string(), "by_permissions");
         assert_eq!(
             FileSorting::from_str("by_creation_time").ok().unwrap(),
             FileSorting::CreationTime
@@ -633,6 +808,18 @@ mod tests {
             FileSorting::from_str("by_size").ok().unwrap(),
             FileSorting::Size
         );
+        assert_eq!(
+            FileSorting::from_str("by_extension").ok().unwrap(),
+            FileSorting::Extension
+        );
+        assert_eq!(
+            FileSorting::from_str("by_owner").ok().unwrap(),
+            FileSorting::Owner
+        );
+        assert_eq!(
+            FileSorting::from_str("by_permissions").ok().unwrap(),
+            FileSorting::Permissions
+        );
         assert!(FileSorting::from_str("omar").is_err());
         // Group dirs
         assert_eq!(GroupDirs::First.to_string(), "first");
@@ -659,6 +846,43 @@ mod tests {
         assert_eq!(explorer.files.len(), 3);
     }
 
+    #[test]
+    fn test_fs_explorer_marks() {
+        let mut explorer: FileExplorer = FileExplorer::default();
+        explorer.set_files(vec![
+            make_fs_entry("README.md", false),
+            make_fs_entry("src/", true),
+            make_fs_entry(".git/", true),
+            make_fs_entry("CONTRIBUTING.md", false),
+        ]);
+        assert_eq!(explorer.marked_count(), 0);
+        // Mark CONTRIBUTING.md and src/ (hidden files are not shown)
+        explorer.mark_files(&[0, 2]);
+        assert_eq!(explorer.marked_count(), 2);
+        assert!(explorer.is_marked(Path::new("CONTRIBUTING.md")));
+        assert!(explorer.is_marked(Path::new("src/")));
+        assert_eq!(explorer.marked_indexes(), vec![0, 2]);
+        // Change directory; marks are kept
+        explorer.set_files(vec![make_fs_entry("main.rs", false)]);
+        assert!(explorer.marked_indexes().is_empty());
+        explorer.mark_files(&[0]);
+        assert_eq!(explorer.marked_count(), 3);
+        assert_eq!(
+            explorer
+                .iter_marked()
+                .map(|x| x.get_name())
+                .collect::<Vec<&str>>(),
+            vec!["CONTRIBUTING.md", "main.rs", "src/"]
+        );
+        // Unmark only in current directory
+        explorer.mark_files(&[]);
+        assert_eq!(explorer.marked_count(), 2);
+        explorer.unmark(Path::new("src/"));
+        assert_eq!(explorer.marked_count(), 1);
+        explorer.clear_marks();
+        assert_eq!(explorer.marked_count(), 0);
+    }
+
     fn make_fs_entry(name: &str, is_dir: bool) -> FsEntry {
         let t_now: SystemTime = SystemTime::now();
         match is_dir {
@@ -689,6 +913,28 @@ mod tests {
         }
     }
 
+    fn make_fs_file(
+        name: &str,
+        ftype: Option<&str>,
+        user: Option<u32>,
+        pex: Option<(u8, u8, u8)>,
+    ) -> FsEntry {
+        let t_now: SystemTime = SystemTime::now();
+        FsEntry::File(FsFile {
+            name: name.to_string(),
+            abs_path: PathBuf::from(name),
+            last_change_time: t_now,
+            last_access_time: t_now,
+            creation_time: t_now,
+            size: 64,
+            ftype: ftype.map(String::from),
+            symlink: None,
+            user,
+            group: user,
+            unix_pex: pex.map(|(u, g, o)| (UnixPex::from(u), UnixPex::from(g), UnixPex::from(o))),
+        })
+    }
+
     fn make_fs_entry_with_size(name: &str, is_dir: bool, size: usize) -> FsEntry {
         let t_now: SystemTime = SystemTime::now();
         match is_dir {
@@ -0,0 +1,281 @@
+//! ## Filter
+//!
+//! `filter` is the module which provides include/exclude rules for recursive transfers
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use super::FsEntry;
+// Ext
+use std::fmt;
+use std::str::FromStr;
+use wildmatch::WildMatch;
+
+/// ## FilterRule
+///
+/// A single include/exclude rule. Rules are written as `+ GLOB` (include) or `- GLOB` (exclude).
+/// When the glob ends with `/`, the rule applies to directories only.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterRule {
+    Include(String),
+    Exclude(String),
+}
+
+impl FilterRule {
+    /// ### glob
+    ///
+    /// Get rule glob
+    pub fn glob(&self) -> &str {
+        match self {
+            FilterRule::Include(glob) | FilterRule::Exclude(glob) => glob.as_str(),
+        }
+    }
+
+    /// ### is_include
+    ///
+    /// Returns whether rule is an include rule
+    pub fn is_include(&self) -> bool {
+        matches!(self, FilterRule::Include(_))
+    }
+
+    /// ### matches
+    ///
+    /// Returns whether rule matches the entry with the provided name
+    pub fn matches(&self, name: &str, is_dir: bool) -> bool {
+        let glob: &str = self.glob();
+        match glob.strip_suffix('/') {
+            Some(glob) => is_dir && WildMatch::new(glob).matches(name),
+            None => WildMatch::new(glob).matches(name),
+        }
+    }
+}
+
+impl FromStr for FilterRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: &str = s.trim();
+        let (kind, glob): (char, &str) = match s.chars().next() {
+            Some(kind) => (kind, s[kind.len_utf8()..].trim()),
+            None => return Err(String::from("empty rule")),
+        };
+        if glob.is_empty() {
+            return Err(format!("missing glob in rule \"{}\"", s));
+        }
+        match kind {
+            '+' => Ok(FilterRule::Include(glob.to_string())),
+            '-' => Ok(FilterRule::Exclude(glob.to_string())),
+            _ => Err(format!("rule \"{}\" must start with '+' or '-'", s)),
+        }
+    }
+}
+
+impl fmt::Display for FilterRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FilterRule::Include(glob) => write!(f, "+ {}", glob),
+            FilterRule::Exclude(glob) => write!(f, "- {}", glob),
+        }
+    }
+}
+
+/// ## TransferFilter
+///
+/// Ordered list of include/exclude rules. The first rule matching an entry decides whether it's
+/// transferred; entries which don't match any rule are transferred
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransferFilter {
+    rules: Vec<FilterRule>,
+}
+
+impl TransferFilter {
+    /// ### new
+    ///
+    /// Instantiates a new `TransferFilter` from rules
+    pub fn new(rules: Vec<FilterRule>) -> Self {
+        Self { rules }
+    }
+
+    /// ### parse
+    ///
+    /// Parse rules from lines; invalid lines are returned as errors
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Self, String> {
+        lines
+            .iter()
+            .map(|x| FilterRule::from_str(x.as_ref()))
+            .collect::<Result<Vec<FilterRule>, String>>()
+            .map(Self::new)
+    }
+
+    /// ### excluding
+    ///
+    /// Instantiates a new `TransferFilter` excluding the comma separated globs in `globs`
+    pub fn excluding(globs: &str) -> Self {
+        Self::new(
+            globs
+                .split(',')
+                .map(|x| x.trim())
+                .filter(|x| !x.is_empty())
+                .map(|x| FilterRule::Exclude(x.to_string()))
+                .collect(),
+        )
+    }
+
+    /// ### rules
+    ///
+    /// Get rules
+    pub fn rules(&self) -> &[FilterRule] {
+        self.rules.as_slice()
+    }
+
+    /// ### is_empty
+    ///
+    /// Returns whether there are no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// ### matching_rule
+    ///
+    /// Get the index of the first rule matching the entry with the provided name
+    pub fn matching_rule(&self, name: &str, is_dir: bool) -> Option<usize> {
+        self.rules.iter().position(|x| x.matches(name, is_dir))
+    }
+
+    /// ### is_included
+    ///
+    /// Returns whether the entry with the provided name must be transferred
+    pub fn is_included(&self, name: &str, is_dir: bool) -> bool {
+        match self.matching_rule(name, is_dir) {
+            Some(idx) => self.rules[idx].is_include(),
+            None => true,
+        }
+    }
+
+    /// ### accepts
+    ///
+    /// Returns whether `entry` must be transferred
+    pub fn accepts(&self, entry: &FsEntry) -> bool {
+        self.is_included(entry.get_name(), entry.is_dir())
+    }
+
+    /// ### to_lines
+    ///
+    /// Convert rules to lines
+    pub fn to_lines(&self) -> Vec<String> {
+        self.rules.iter().map(|x| x.to_string()).collect()
+    }
+
+    /// ### to_globs
+    ///
+    /// Join the globs of the rules with commas
+    pub fn to_globs(&self) -> String {
+        self.rules
+            .iter()
+            .map(|x| x.glob())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fs_filter_rule() {
+        let rule: FilterRule = FilterRule::from_str("+ *.rs").unwrap();
+        assert_eq!(rule, FilterRule::Include(String::from("*.rs")));
+        assert_eq!(rule.is_include(), true);
+        assert_eq!(rule.glob(), "*.rs");
+        assert_eq!(rule.to_string().as_str(), "+ *.rs");
+        assert!(rule.matches("main.rs", false));
+        assert!(!rule.matches("main.c", false));
+        let rule: FilterRule = FilterRule::from_str("  -target/ ").unwrap();
+        assert_eq!(rule, FilterRule::Exclude(String::from("target/")));
+        assert_eq!(rule.is_include(), false);
+        assert_eq!(rule.to_string().as_str(), "- target/");
+        // Trailing slash matches directories only
+        assert!(rule.matches("target", true));
+        assert!(!rule.matches("target", false));
+        // Bad rules
+        assert!(FilterRule::from_str("").is_err());
+        assert!(FilterRule::from_str("+").is_err());
+        assert!(FilterRule::from_str("*.rs").is_err());
+    }
+
+    #[test]
+    fn test_fs_filter_transfer_filter() {
+        let filter: TransferFilter = TransferFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.is_included("main.rs", false));
+        let filter: TransferFilter =
+            TransferFilter::parse(&["+ keep.log", "- *.log", "- .git/", "- node_modules/"])
+                .unwrap();
+        assert_eq!(filter.rules().len(), 4);
+        // First matching rule wins
+        assert_eq!(filter.matching_rule("keep.log", false), Some(0));
+        assert!(filter.is_included("keep.log", false));
+        assert_eq!(filter.matching_rule("debug.log", false), Some(1));
+        assert!(!filter.is_included("debug.log", false));
+        assert!(!filter.is_included(".git", true));
+        assert!(filter.is_included(".git", false));
+        // No matching rule
+        assert_eq!(filter.matching_rule("main.rs", false), None);
+        assert!(filter.is_included("main.rs", false));
+        // Lines
+        assert_eq!(
+            filter.to_lines(),
+            vec![
+                String::from("+ keep.log"),
+                String::from("- *.log"),
+                String::from("- .git/"),
+                String::from("- node_modules/"),
+            ]
+        );
+        // Bad lines
+        assert!(TransferFilter::parse(&["+ *.rs", "src"]).is_err());
+    }
+
+    #[test]
+    fn test_fs_filter_transfer_filter_excluding() {
+        let filter: TransferFilter = TransferFilter::excluding(" *.log,, target/ ,.git");
+        assert_eq!(
+            filter.rules(),
+            &[
+                FilterRule::Exclude(String::from("*.log")),
+                FilterRule::Exclude(String::from("target/")),
+                FilterRule::Exclude(String::from(".git")),
+            ]
+        );
+        assert!(!filter.is_included("debug.log", false));
+        assert!(!filter.is_included("target", true));
+        assert!(filter.is_included("main.rs", false));
+        assert_eq!(filter.to_globs().as_str(), "*.log, target/, .git");
+        assert!(TransferFilter::excluding("").is_empty());
+    }
+}
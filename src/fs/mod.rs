@@ -27,6 +27,7 @@
  */
 // Mod
 pub mod explorer;
+pub mod filter;
 // Ext
 use std::path::PathBuf;
 use std::time::SystemTime;
@@ -26,6 +26,7 @@
  * SOFTWARE.
  */
 // ext
+use std::collections::HashMap;
 use std::fs::{self, File, Metadata, OpenOptions};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
@@ -85,6 +86,16 @@ impl HostError {
             path: Some(p.to_path_buf()),
         }
     }
+
+    /// ### is_permission_denied
+    ///
+    /// Returns whether the error has been caused by a lack of permissions
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(
+            self.ioerr.as_ref().map(|x| x.kind()),
+            Some(std::io::ErrorKind::PermissionDenied)
+        )
+    }
 }
 
 impl From<HostErrorType> for HostError {
@@ -110,6 +121,23 @@ impl std::fmt::Display for HostError {
     }
 }
 
+impl std::error::Error for HostError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.ioerr
+            .as_ref()
+            .map(|err| err as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// ## Inode
+///
+/// Inode identifies a file on the local file system: paths with the same inode are hard links to the same data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Inode {
+    pub dev: u64,
+    pub ino: u64,
+}
+
 /// ## Localhost
 ///
 /// Localhost is the entity which holds the information about the current directory and host.
@@ -360,7 +388,8 @@ impl Localhost {
 
     /// ### copy
     ///
-    /// Copy file to destination path
+    /// Copy file to destination path.
+    /// Hard links between the copied files are preserved
     pub fn copy(&mut self, entry: &FsEntry, dst: &Path) -> Result<(), HostError> {
         // Get absolute path of dest
         let dst: PathBuf = self.to_abs_path(dst);
@@ -369,19 +398,68 @@ impl Localhost {
             entry.get_abs_path().display(),
             dst.display()
         );
+        self.copy_entry(entry, dst.as_path(), &mut HashMap::new())?;
+        // Reload directory if dst is pwd
+        match dst.is_dir() {
+            true => {
+                if dst == self.pwd().as_path() {
+                    self.files = self.scan_dir(self.wrkdir.as_path())?;
+                } else if let Some(parent) = dst.parent() {
+                    // If parent is pwd, scan directory
+                    if parent == self.pwd().as_path() {
+                        self.files = self.scan_dir(self.wrkdir.as_path())?;
+                    }
+                }
+            }
+            false => {
+                if let Some(parent) = dst.parent() {
+                    // If parent is pwd, scan directory
+                    if parent == self.pwd().as_path() {
+                        self.files = self.scan_dir(self.wrkdir.as_path())?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// ### copy_entry
+    ///
+    /// Copy `entry` to `dst`. A file with many hard links is copied once: the other links to it found
+    /// while copying are linked to the first copy, which is recorded in `copied`
+    fn copy_entry(
+        &mut self,
+        entry: &FsEntry,
+        dst: &Path,
+        copied: &mut HashMap<Inode, PathBuf>,
+    ) -> Result<(), HostError> {
         // Match entry
         match entry {
             FsEntry::File(file) => {
                 // Copy file
                 // If destination path is a directory, push file name
-                let dst: PathBuf = match dst.as_path().is_dir() {
+                let dst: PathBuf = match dst.is_dir() {
                     true => {
-                        let mut p: PathBuf = dst.clone();
+                        let mut p: PathBuf = dst.to_path_buf();
                         p.push(file.name.as_str());
                         p
                     }
-                    false => dst.clone(),
+                    false => dst.to_path_buf(),
+                };
+                // Link to the copy of the same inode, if any
+                let inode: Option<Inode> = match self.hard_links(file.abs_path.as_path()) {
+                    Some((inode, links)) if links > 1 => Some(inode),
+                    _ => None,
                 };
+                if let Some(first) = inode.and_then(|x| copied.get(&x)) {
+                    match std::fs::hard_link(first.as_path(), dst.as_path()) {
+                        Ok(_) => {
+                            info!("File linked to {}", first.display());
+                            return Ok(());
+                        }
+                        Err(err) => debug!("Could not link {}: {}", dst.display(), err),
+                    }
+                }
                 // Copy entry path to dst path
                 if let Err(err) = std::fs::copy(file.abs_path.as_path(), dst.as_path()) {
                     error!("Failed to copy file: {}", err);
@@ -391,50 +469,56 @@ impl Localhost {
                         file.abs_path.as_path(),
                     ));
                 }
+                if let Some(inode) = inode {
+                    copied.insert(inode, dst);
+                }
                 info!("File copied");
             }
             FsEntry::Directory(dir) => {
                 // If destination path doesn't exist, create destination
                 if !dst.exists() {
                     debug!("Directory {} doesn't exist; creating it", dst.display());
-                    self.mkdir(dst.as_path())?;
+                    self.mkdir(dst)?;
                 }
                 // Scan dir
                 let dir_files: Vec<FsEntry> = self.scan_dir(dir.abs_path.as_path())?;
                 // Iterate files
                 for dir_entry in dir_files.iter() {
                     // Calculate dst
-                    let mut sub_dst: PathBuf = dst.clone();
+                    let mut sub_dst: PathBuf = dst.to_path_buf();
                     sub_dst.push(dir_entry.get_name());
                     // Call function recursively
-                    self.copy(dir_entry, sub_dst.as_path())?;
-                }
-            }
-        }
-        // Reload directory if dst is pwd
-        match dst.is_dir() {
-            true => {
-                if dst == self.pwd().as_path() {
-                    self.files = self.scan_dir(self.wrkdir.as_path())?;
-                } else if let Some(parent) = dst.parent() {
-                    // If parent is pwd, scan directory
-                    if parent == self.pwd().as_path() {
-                        self.files = self.scan_dir(self.wrkdir.as_path())?;
-                    }
-                }
-            }
-            false => {
-                if let Some(parent) = dst.parent() {
-                    // If parent is pwd, scan directory
-                    if parent == self.pwd().as_path() {
-                        self.files = self.scan_dir(self.wrkdir.as_path())?;
-                    }
+                    self.copy_entry(dir_entry, sub_dst.as_path(), copied)?;
                 }
             }
         }
         Ok(())
     }
 
+    /// ### hard_links
+    ///
+    /// Get the inode of the file at `path` and the amount of hard links to it
+    #[cfg(target_family = "unix")]
+    pub fn hard_links(&self, path: &Path) -> Option<(Inode, u64)> {
+        fs::metadata(self.to_abs_path(path)).ok().map(|attr| {
+            (
+                Inode {
+                    dev: attr.dev(),
+                    ino: attr.ino(),
+                },
+                attr.nlink(),
+            )
+        })
+    }
+
+    /// ### hard_links
+    ///
+    /// Get the inode of the file at `path` and the amount of hard links to it. UNIX only
+    #[cfg(target_os = "windows")]
+    pub fn hard_links(&self, _path: &Path) -> Option<(Inode, u64)> {
+        None
+    }
+
     /// ### stat
     ///
     /// Stat file and create a FsEntry
@@ -637,6 +721,89 @@ impl Localhost {
         }
     }
 
+    /// ### chown
+    ///
+    /// Change owner of file; `owner` has the same syntax of the `chown` command (`user[:group]`)
+    #[cfg(target_family = "unix")]
+    pub fn chown(&self, path: &Path, owner: &str) -> Result<(), HostError> {
+        let path: PathBuf = self.to_abs_path(path);
+        match std::process::Command::new("chown")
+            .arg(owner)
+            .arg(path.as_path())
+            .output()
+        {
+            Ok(output) if output.status.success() => {
+                info!("Changed owner for {} to {}", path.display(), owner);
+                Ok(())
+            }
+            Ok(output) => {
+                error!(
+                    "Could not change owner for file {}: {}",
+                    path.display(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                Err(HostError::new(
+                    HostErrorType::FileNotAccessible,
+                    None,
+                    path.as_path(),
+                ))
+            }
+            Err(err) => {
+                error!("Failed to run chown: {}", err);
+                Err(HostError::new(
+                    HostErrorType::ExecutionFailed,
+                    Some(err),
+                    path.as_path(),
+                ))
+            }
+        }
+    }
+
+    /// ### read_link
+    ///
+    /// Get the target of the symbolic link at `path`, as it's written in the link
+    pub fn read_link(&self, path: &Path) -> Result<PathBuf, HostError> {
+        let path: PathBuf = self.to_abs_path(path);
+        fs::read_link(path.as_path()).map_err(|err| {
+            error!("Could not read link {}: {}", path.display(), err);
+            HostError::new(HostErrorType::FileNotAccessible, Some(err), path.as_path())
+        })
+    }
+
+    /// ### symlink
+    ///
+    /// Create a symbolic link at `path` pointing to `target`
+    #[cfg(target_family = "unix")]
+    pub fn symlink(&self, path: &Path, target: &Path) -> Result<(), HostError> {
+        let path: PathBuf = self.to_abs_path(path);
+        match std::os::unix::fs::symlink(target, path.as_path()) {
+            Ok(_) => {
+                info!("Linked {} to {}", path.display(), target.display());
+                Ok(())
+            }
+            Err(err) => {
+                error!("Could not create link {}: {}", path.display(), err);
+                Err(HostError::new(
+                    HostErrorType::CouldNotCreateFile,
+                    Some(err),
+                    path.as_path(),
+                ))
+            }
+        }
+    }
+
+    /// ### symlink
+    ///
+    /// Symbolic links can't be created on this platform
+    #[cfg(target_os = "windows")]
+    pub fn symlink(&self, path: &Path, _target: &Path) -> Result<(), HostError> {
+        Err(HostError::new(
+            HostErrorType::CouldNotCreateFile,
+            None,
+            self.to_abs_path(path).as_path(),
+        ))
+    }
+
     /// ### open_file_read
     ///
     /// Open file for read
@@ -700,6 +867,36 @@ impl Localhost {
         }
     }
 
+    /// ### open_file_append
+    ///
+    /// Open file for write, appending to its current content
+    pub fn open_file_append(&self, file: &Path) -> Result<File, HostError> {
+        let file: PathBuf = self.to_abs_path(file);
+        info!("Opening file {} for append", file.display());
+        match OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file.as_path())
+        {
+            Ok(f) => Ok(f),
+            Err(err) => {
+                error!("Failed to open file: {}", err);
+                match self.file_exists(file.as_path()) {
+                    true => Err(HostError::new(
+                        HostErrorType::ReadonlyFile,
+                        Some(err),
+                        file.as_path(),
+                    )),
+                    false => Err(HostError::new(
+                        HostErrorType::FileNotAccessible,
+                        Some(err),
+                        file.as_path(),
+                    )),
+                }
+            }
+        }
+    }
+
     /// ### file_exists
     ///
     /// Returns whether provided file path exists
@@ -831,6 +1028,13 @@ mod tests {
             HostError::new(HostErrorType::CouldNotCreateFile, None, Path::new("/tmp"));
         assert!(error.ioerr.is_none());
         assert_eq!(error.path.as_ref().unwrap(), Path::new("/tmp"));
+        assert_eq!(error.is_permission_denied(), false);
+        let error: HostError = HostError::new(
+            HostErrorType::DirNotAccessible,
+            Some(std::io::Error::from(std::io::ErrorKind::PermissionDenied)),
+            Path::new("/root"),
+        );
+        assert_eq!(error.is_permission_denied(), true);
     }
 
     #[test]
@@ -964,6 +1168,18 @@ mod tests {
         assert!(host.open_file_write(file.path()).is_err());
     }
 
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_host_localhost_open_append() {
+        let host: Localhost = Localhost::new(PathBuf::from("/dev")).ok().unwrap();
+        let file: tempfile::NamedTempFile = create_sample_file();
+        let size: u64 = fs::metadata(file.path()).unwrap().len();
+        let mut fhnd: File = host.open_file_append(file.path()).unwrap();
+        assert!(fhnd.write_all(b"appended").is_ok());
+        drop(fhnd);
+        assert_eq!(fs::metadata(file.path()).unwrap().len(), size + 8);
+    }
+
     #[cfg(target_family = "unix")]
     #[test]
     fn test_host_localhost_symlinks() {
@@ -1011,6 +1227,32 @@ mod tests {
         };
     }
 
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_host_localhost_read_link() {
+        let tmpdir: tempfile::TempDir = tempfile::TempDir::new().unwrap();
+        assert!(File::create(tmpdir.path().join("foo.txt")).is_ok());
+        let host: Localhost = Localhost::new(PathBuf::from(tmpdir.path())).ok().unwrap();
+        // Relative targets are kept as they are
+        assert!(host
+            .symlink(Path::new("bar.txt"), Path::new("foo.txt"))
+            .is_ok());
+        assert_eq!(
+            host.read_link(Path::new("bar.txt")).unwrap(),
+            PathBuf::from("foo.txt")
+        );
+        match host.stat(tmpdir.path().join("bar.txt").as_path()).unwrap() {
+            FsEntry::File(file) => assert!(file.symlink.is_some()),
+            entry => panic!("expected bar.txt to be file: {:?}", entry),
+        }
+        // Link exists
+        assert!(host
+            .symlink(Path::new("bar.txt"), Path::new("foo.txt"))
+            .is_err());
+        // Not a link
+        assert!(host.read_link(Path::new("foo.txt")).is_err());
+    }
+
     #[test]
     #[cfg(target_family = "unix")]
     fn test_host_localhost_mkdir() {
@@ -1113,6 +1355,21 @@ mod tests {
             .is_err());
     }
 
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_host_chown() {
+        let tmpdir: tempfile::TempDir = tempfile::TempDir::new().unwrap();
+        let file: tempfile::NamedTempFile = create_sample_file();
+        let host: Localhost = Localhost::new(PathBuf::from(tmpdir.path())).ok().unwrap();
+        // Chown to current user
+        let owner: String = whoami::username();
+        assert!(host.chown(file.path(), owner.as_str()).is_ok());
+        // Error
+        assert!(host
+            .chown(Path::new("/tmp/krgiogoiegj/kwrgnoerig"), owner.as_str())
+            .is_err());
+    }
+
     #[cfg(target_family = "unix")]
     #[test]
     fn test_host_copy_file_absolute() {
@@ -1196,6 +1453,41 @@ mod tests {
         assert!(host.stat(test_file_path.as_path()).is_ok());
     }
 
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_host_copy_directory_hard_links() {
+        let tmpdir: tempfile::TempDir = tempfile::TempDir::new().unwrap();
+        // Create directory with a file and a hard link to it
+        let dir_src: PathBuf = tmpdir.path().join("test_dir");
+        assert!(std::fs::create_dir(dir_src.as_path()).is_ok());
+        let file1_path: PathBuf = dir_src.join("foo.txt");
+        let mut file1: File = File::create(file1_path.as_path()).ok().unwrap();
+        assert!(file1.write_all(b"Hello world!\n").is_ok());
+        assert!(std::fs::hard_link(file1_path.as_path(), dir_src.join("bar.txt")).is_ok());
+        let mut host: Localhost = Localhost::new(PathBuf::from(tmpdir.path())).ok().unwrap();
+        let (inode, links) = host.hard_links(file1_path.as_path()).unwrap();
+        assert_eq!(links, 2);
+        assert_eq!(
+            host.hard_links(dir_src.join("bar.txt").as_path()).unwrap(),
+            (inode, 2)
+        );
+        // Copy
+        let dir_src_entry: FsEntry = host.files.get(0).unwrap().clone();
+        let dir_dest: PathBuf = tmpdir.path().join("test_dest_dir");
+        assert!(host.copy(&dir_src_entry, dir_dest.as_path()).is_ok());
+        // Copies are linked to each other, not to sources
+        let (copy_inode, links) = host.hard_links(dir_dest.join("foo.txt").as_path()).unwrap();
+        assert_ne!(copy_inode, inode);
+        assert_eq!(links, 2);
+        assert_eq!(
+            host.hard_links(dir_dest.join("bar.txt").as_path()).unwrap(),
+            (copy_inode, 2)
+        );
+        assert!(host
+            .hard_links(tmpdir.path().join("none").as_path())
+            .is_none());
+    }
+
     #[cfg(target_family = "unix")]
     #[test]
     fn test_host_copy_directory_relative() {
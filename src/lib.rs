@@ -1,3 +1,42 @@
+//! # termscp
+//!
+//! termscp is a terminal file transfer and explorer with support for SCP/SFTP/FTP/WebDAV/HTTP.
+//! The `termscp` binary is built on top of this library, which can also be used to embed
+//! the termscp transfer engine in other tools, without the terminal user interface.
+//!
+//! ## Transfer engine
+//!
+//! - [`filetransfer`]: the `FileTransfer` trait, the protocol clients, the connection parameters
+//!   and the [`filetransfer::Builder`], which instantiates the client for a protocol.
+//!   New protocols can be added through [`filetransfer::registry::register`]
+//! - [`host`]: the local host file system (`Localhost`)
+//! - [`fs`]: the file system entries shared by the host and the protocol clients
+//!
+//! The other modules implement the terminal user interface and its configuration: they're public
+//! for the `termscp` binary only, and are not part of the library API.
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//! use termscp::filetransfer::{
+//!     Builder, FileTransfer, FileTransferParams, FileTransferProtocol, SshKeyStorage,
+//! };
+//!
+//! let params = FileTransferParams::new("127.0.0.1")
+//!     .port(21)
+//!     .protocol(FileTransferProtocol::Ftp(false))
+//!     .username(Some("omar"))
+//!     .password(Some("password"));
+//! let mut client: Box<dyn FileTransfer> =
+//!     Builder::build(params.protocol, SshKeyStorage::empty()).unwrap();
+//! client
+//!     .connect(params.address, params.port, params.username, params.password)
+//!     .unwrap();
+//! for entry in client.list_dir(Path::new("/")).unwrap() {
+//!     println!("{}", entry.get_name());
+//! }
+//! client.disconnect().unwrap();
+//! ```
+
 #![doc(html_playground_url = "https://play.rust-lang.org")]
 #![doc(
     html_favicon_url = "https://raw.githubusercontent.com/veeso/termscp/main/assets/images/termscp-128.png"
@@ -47,11 +86,15 @@ extern crate lazy_static;
 extern crate log;
 #[macro_use]
 extern crate magic_crypt;
+extern crate md5;
 extern crate open;
 #[cfg(target_os = "windows")]
 extern crate path_slash;
+extern crate percent_encoding;
 extern crate rand;
 extern crate regex;
+extern crate serde_json;
+extern crate sha2;
 extern crate ssh2;
 extern crate suppaftp;
 extern crate tempfile;
@@ -64,12 +107,22 @@ extern crate users;
 extern crate whoami;
 extern crate wildmatch;
 
-pub mod activity_manager;
-pub mod config;
+// Transfer engine
 pub mod filetransfer;
 pub mod fs;
 pub mod host;
+// Application; public for the termscp binary only
+#[doc(hidden)]
+pub mod activity_manager;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
 pub mod support;
+#[doc(hidden)]
 pub mod system;
-pub mod ui;
+#[cfg(any(test, feature = "test-support"))]
+#[doc(hidden)]
+pub mod test_support;
+pub(crate) mod ui;
+#[doc(hidden)]
 pub mod utils;
@@ -27,41 +27,34 @@ const TERMSCP_AUTHORS: &str = env!("CARGO_PKG_AUTHORS");
 
 // Crates
 extern crate argh;
-#[macro_use]
-extern crate bitflags;
-#[macro_use]
-extern crate lazy_static;
+extern crate crossterm;
 #[macro_use]
 extern crate log;
-#[macro_use]
-extern crate magic_crypt;
 extern crate rpassword;
+extern crate termscp;
 
 // External libs
 use argh::FromArgs;
+use crossterm::tty::IsTty;
 use std::env;
+use std::io;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 // Include
-mod activity_manager;
-mod config;
-mod filetransfer;
-mod fs;
-mod host;
-mod support;
-mod system;
-mod ui;
-mod utils;
-
-// namespaces
-use activity_manager::{ActivityManager, NextActivity};
-use filetransfer::FileTransferParams;
-use system::logging;
+use termscp::activity_manager::{ActivityManager, NextActivity};
+use termscp::filetransfer::{registry, FileTransferParams, SlowLink, TlsOptions};
+use termscp::system::console::{Console, ProgressStyle, Verbosity};
+use termscp::system::{logging, startup_profile};
+use termscp::{support, utils};
 
 enum Task {
     Activity(NextActivity),
     ImportTheme(PathBuf),
+    Bookmark(BookmarkCommand),
+    Usage,
+    Get(String, PathBuf),
 }
 
 #[derive(FromArgs)]
@@ -73,10 +66,26 @@ Please, consider supporting the author <https://www.buymeacoffee.com/veeso>")]
 struct Args {
     #[argh(switch, short = 'c', description = "open termscp configuration")]
     config: bool,
+    #[argh(switch, description = "don't color the output of command line modes")]
+    no_color: bool,
     #[argh(option, short = 'P', description = "provide password from CLI")]
     password: Option<String>,
-    #[argh(switch, short = 'q', description = "disable logging")]
+    #[argh(
+        option,
+        description = "progress style of command line modes: bar, dots or none"
+    )]
+    progress: Option<String>,
+    #[argh(
+        switch,
+        short = 'q',
+        description = "disable logging; command line modes print errors only"
+    )]
     quiet: bool,
+    #[argh(
+        switch,
+        description = "start with the default configuration, without loading bookmarks and theme"
+    )]
+    safe_mode: bool,
     #[argh(option, short = 't', description = "import specified theme")]
     theme: Option<String>,
     #[argh(
@@ -86,6 +95,18 @@ struct Args {
         description = "set UI ticks; default 10ms"
     )]
     ticks: u64,
+    #[argh(
+        option,
+        description = "simulate a slow network on transfers, for testing: <latency ms>[:<bandwidth bytes/s>]"
+    )]
+    slow_link: Option<String>,
+    #[argh(
+        switch,
+        description = "print how long each startup phase took, once termscp exits"
+    )]
+    profile_startup: bool,
+    #[argh(switch, description = "print more details in command line modes")]
+    verbose: bool,
     #[argh(switch, short = 'v', description = "print version")]
     version: bool,
     // -- positional
@@ -94,12 +115,209 @@ struct Args {
         description = "protocol://user@address:port:wrkdir local-wrkdir"
     )]
     positional: Vec<String>,
+    #[argh(subcommand)]
+    command: Option<Command>,
 }
 
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    Config(ConfigArgs),
+    Get(GetArgs),
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "get",
+    description = "download an HTTP(S) resource into the working directory"
+)]
+struct GetArgs {
+    #[argh(
+        option,
+        short = 'o',
+        description = "directory to save the resource into; default the working directory"
+    )]
+    output: Option<String>,
+    #[argh(positional, description = "URL of the resource (http:// or https://)")]
+    url: String,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "config",
+    description = "manage termscp configuration from the command line"
+)]
+struct ConfigArgs {
+    #[argh(subcommand)]
+    command: ConfigCommand,
+}
+
+// argh can't parse boxed subcommands; arguments are parsed only once anyway
+#[allow(clippy::large_enum_variant)]
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum ConfigCommand {
+    Bookmark(BookmarkArgs),
+    Usage(UsageArgs),
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "bookmark",
+    description = "manage bookmarks; bookmarks are printed as JSON"
+)]
+struct BookmarkArgs {
+    #[argh(subcommand)]
+    command: BookmarkCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum BookmarkCommand {
+    Add(BookmarkAddArgs),
+    List(BookmarkListArgs),
+    Remove(BookmarkRemoveArgs),
+    Show(BookmarkShowArgs),
+    MasterPassword(BookmarkMasterPasswordArgs),
+    Export(BookmarkExportArgs),
+    Import(BookmarkImportArgs),
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "add",
+    description = "add a bookmark, replacing the one with the same name"
+)]
+struct BookmarkAddArgs {
+    #[argh(
+        option,
+        short = 'P',
+        description = "password to save with the bookmark"
+    )]
+    password: Option<String>,
+    #[argh(
+        option,
+        description = "proxy to connect through (socks5://[user[:password]@]host:port or http://...)"
+    )]
+    proxy: Option<String>,
+    #[argh(
+        option,
+        description = "client certificate and key (PKCS#12) to authenticate with to FTPS servers"
+    )]
+    tls_cert: Option<PathBuf>,
+    #[argh(
+        option,
+        description = "CA bundle (PEM) to verify the FTPS server certificate with"
+    )]
+    ca_bundle: Option<PathBuf>,
+    #[argh(
+        option,
+        description = "remote directory to move deleted files to, instead of removing them"
+    )]
+    trash: Option<PathBuf>,
+    #[argh(
+        option,
+        description = "local directory to enter once connected (absolute path)"
+    )]
+    local_dir: Option<PathBuf>,
+    #[argh(
+        switch,
+        description = "forward the ssh agent to the commands executed on the remote (SFTP/SCP)"
+    )]
+    forward_agent: bool,
+    #[argh(
+        switch,
+        description = "compress the ssh transport, whatever the configuration says (SFTP/SCP)"
+    )]
+    compress: bool,
+    #[argh(option, description = "group to list the bookmark under")]
+    group: Option<String>,
+    #[argh(positional, description = "bookmark name")]
+    name: String,
+    #[argh(positional, description = "protocol://user@address:port")]
+    remote: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list", description = "list bookmarks")]
+struct BookmarkListArgs {}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "remove", description = "remove a bookmark")]
+struct BookmarkRemoveArgs {
+    #[argh(positional, description = "bookmark name")]
+    name: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "show", description = "show a bookmark")]
+struct BookmarkShowArgs {
+    #[argh(positional, description = "bookmark name")]
+    name: String,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "master-password",
+    description = "set the master password to encrypt bookmarks with"
+)]
+struct BookmarkMasterPasswordArgs {
+    #[argh(switch, description = "remove the master password")]
+    remove: bool,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "export",
+    description = "export bookmarks to a file, to be imported on another machine"
+)]
+struct BookmarkExportArgs {
+    #[argh(
+        switch,
+        description = "export passwords too; the file gets encrypted with a passphrase"
+    )]
+    with_passwords: bool,
+    #[argh(positional, description = "file to export bookmarks to")]
+    file: PathBuf,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "import",
+    description = "import the bookmarks exported to a file"
+)]
+struct BookmarkImportArgs {
+    #[argh(
+        switch,
+        description = "replace the bookmarks with the same name of the imported ones"
+    )]
+    overwrite: bool,
+    #[argh(positional, description = "file to import bookmarks from")]
+    file: PathBuf,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "usage",
+    description = "show the bandwidth used with each host, month by month, as JSON"
+)]
+struct UsageArgs {}
+
 struct RunOpts {
     remote: Option<FileTransferParams>,
     ticks: Duration,
+    slow_link: SlowLink,
     log_enabled: bool,
+    safe_mode: bool,
+    console: Console,
     task: Task,
 }
 
@@ -108,14 +326,21 @@ impl Default for RunOpts {
         Self {
             remote: None,
             ticks: Duration::from_millis(10),
+            slow_link: SlowLink::default(),
             log_enabled: true,
+            safe_mode: false,
+            console: Console::new(Verbosity::Normal, true, ProgressStyle::Bar),
             task: Task::Activity(NextActivity::Authentication),
         }
     }
 }
 
 fn main() {
+    let started: Instant = Instant::now();
     let args: Args = argh::from_env();
+    if args.profile_startup {
+        startup_profile::enable(started);
+    }
     // Parse args
     let mut run_opts: RunOpts = match parse_args(args) {
         Ok(opts) => opts,
@@ -124,11 +349,17 @@ fn main() {
             std::process::exit(255);
         }
     };
+    startup_profile::mark("parse arguments");
     // Setup logging
     if run_opts.log_enabled {
         if let Err(err) = logging::init() {
             eprintln!("Failed to initialize logging: {}", err);
         }
+        startup_profile::mark("setup logging");
+    }
+    // Register the protocols built in as plugins
+    for err in registry::load_plugins() {
+        eprintln!("Could not load protocol plugin: {}", err);
     }
     // Read password from remote
     if let Err(err) = read_password(&mut run_opts) {
@@ -140,6 +371,10 @@ fn main() {
     info!("Starting activity manager...");
     let rc: i32 = run(run_opts);
     info!("termscp terminated");
+    // Print startup timings, now that the terminal is restored
+    if let Some(report) = startup_profile::report() {
+        eprintln!("{}", report);
+    }
     // Then return
     std::process::exit(rc);
 }
@@ -166,12 +401,55 @@ fn parse_args(args: Args) -> Result<RunOpts, String> {
     if args.quiet {
         run_opts.log_enabled = false;
     }
+    // Command line modes output
+    let verbosity: Verbosity = match (args.quiet, args.verbose) {
+        (true, true) => return Err(String::from("--quiet and --verbose can't be used together")),
+        (true, false) => Verbosity::Quiet,
+        (false, true) => Verbosity::Verbose,
+        (false, false) => Verbosity::Normal,
+    };
+    // Bars are redrawn on the same line, which makes sense only on terminals
+    let progress: ProgressStyle = match args.progress {
+        Some(progress) => ProgressStyle::from_str(progress.as_str())
+            .map_err(|err| format!("Bad progress option: {}", err))?,
+        None if io::stderr().is_tty() => ProgressStyle::Bar,
+        None => ProgressStyle::None,
+    };
+    // See <https://no-color.org>
+    let color: bool = !args.no_color && env::var_os("NO_COLOR").is_none();
+    run_opts.console = Console::new(verbosity, color, progress);
+    // Safe mode
+    run_opts.safe_mode = args.safe_mode;
     // Match ticks
     run_opts.ticks = Duration::from_millis(args.ticks);
+    // Simulated network conditions
+    if let Some(slow_link) = args.slow_link {
+        run_opts.slow_link = SlowLink::from_str(slow_link.as_str())
+            .map_err(|err| format!("Bad slow-link option: {}", err))?;
+    }
     // @! extra modes
     if let Some(theme) = args.theme {
         run_opts.task = Task::ImportTheme(PathBuf::from(theme));
     }
+    match args.command {
+        Some(Command::Config(ConfigArgs { command })) => {
+            run_opts.task = match command {
+                ConfigCommand::Bookmark(bookmark) => Task::Bookmark(bookmark.command),
+                ConfigCommand::Usage(_) => Task::Usage,
+            };
+            return Ok(run_opts);
+        }
+        Some(Command::Get(GetArgs { output, url })) => {
+            let dir: PathBuf = match output {
+                Some(dir) => PathBuf::from(dir),
+                None => env::current_dir()
+                    .map_err(|e| format!("Could not get working directory: {}", e))?,
+            };
+            run_opts.task = Task::Get(url, dir);
+            return Ok(run_opts);
+        }
+        None => {}
+    }
     // @! Ordinary mode
     // Remote argument
     if let Some(remote) = args.positional.get(0) {
@@ -233,21 +511,157 @@ fn read_password(run_opts: &mut RunOpts) -> Result<(), String> {
     Ok(())
 }
 
+/// ### run_bookmark_command
+///
+/// Run bookmark command and return its JSON output
+fn run_bookmark_command(command: BookmarkCommand) -> Result<String, String> {
+    let mut client = support::bookmarks_client()?;
+    if client.is_locked() {
+        let password: String = rpassword::read_password_from_tty(Some("Master password: "))
+            .map_err(|_| String::from("Could not read password from prompt"))?;
+        client
+            .unlock(password.as_str())
+            .map_err(|e| format!("Could not unlock bookmarks: {}", e))?;
+    }
+    match command {
+        BookmarkCommand::Add(args) => {
+            let params: FileTransferParams = utils::parser::parse_remote_opt(args.remote.as_str())
+                .map_err(|err| format!("Bad address option: {}", err))?
+                .password(args.password);
+            let tls: Option<TlsOptions> = match (args.tls_cert, args.ca_bundle) {
+                (None, None) => None,
+                (client_cert, ca_bundle) => Some(TlsOptions {
+                    client_cert,
+                    ca_bundle,
+                }),
+            };
+            support::add_bookmark(
+                &mut client,
+                args.name.as_str(),
+                params,
+                support::BookmarkOptions {
+                    proxy: args.proxy,
+                    tls,
+                    trash: args.trash,
+                    local_directory: args.local_dir,
+                    agent_forwarding: args.forward_agent,
+                    compression: args.compress,
+                    group: args.group,
+                },
+            )
+        }
+        BookmarkCommand::List(_) => support::list_bookmarks(&client),
+        BookmarkCommand::Remove(args) => support::remove_bookmark(&mut client, args.name.as_str()),
+        BookmarkCommand::Show(args) => support::show_bookmark(&client, args.name.as_str()),
+        BookmarkCommand::MasterPassword(args) => {
+            let password: Option<String> = match args.remove {
+                true => None,
+                false => Some(read_new_password("master password")?),
+            };
+            support::set_master_password(&mut client, password)
+        }
+        BookmarkCommand::Export(args) => {
+            let passphrase: Option<String> = match args.with_passwords {
+                true => Some(read_new_password("export passphrase")?),
+                false => None,
+            };
+            support::export_bookmarks(&client, args.file.as_path(), passphrase.as_deref())
+        }
+        BookmarkCommand::Import(args) => support::import_bookmarks(
+            &mut client,
+            args.file.as_path(),
+            || {
+                rpassword::read_password_from_tty(Some("Export passphrase: "))
+                    .map_err(|_| String::from("Could not read password from prompt"))
+            },
+            args.overwrite,
+        ),
+    }
+}
+
+/// ### read_new_password
+///
+/// Read a new password, described by `what`, from tty, asking for it twice
+fn read_new_password(what: &str) -> Result<String, String> {
+    let password: String =
+        rpassword::read_password_from_tty(Some(format!("New {}: ", what).as_str()))
+            .map_err(|_| String::from("Could not read password from prompt"))?;
+    if password.is_empty() {
+        return Err(format!("The {} can't be empty", what));
+    }
+    let confirm: String =
+        rpassword::read_password_from_tty(Some(format!("Confirm {}: ", what).as_str()))
+            .map_err(|_| String::from("Could not read password from prompt"))?;
+    match password == confirm {
+        true => Ok(password),
+        false => Err(String::from("Passwords don't match")),
+    }
+}
+
 /// ### run
 ///
 /// Run task and return rc
 fn run(mut run_opts: RunOpts) -> i32 {
+    let console: &mut Console = &mut run_opts.console;
     match run_opts.task {
         Task::ImportTheme(theme) => match support::import_theme(theme.as_path()) {
             Ok(_) => {
-                println!("Theme has been successfully imported!");
+                console.success("Theme has been successfully imported!");
                 0
             }
             Err(err) => {
-                eprintln!("{}", err);
+                console.error(err.as_str());
                 1
             }
         },
+        Task::Bookmark(command) => match run_bookmark_command(command) {
+            Ok(output) => {
+                println!("{}", output);
+                0
+            }
+            Err(err) => {
+                console.error(err.as_str());
+                1
+            }
+        },
+        Task::Usage => match support::usage_client().and_then(|x| support::usage_report(&x)) {
+            Ok(output) => {
+                println!("{}", output);
+                0
+            }
+            Err(err) => {
+                console.error(err.as_str());
+                1
+            }
+        },
+        Task::Get(url, dir) => {
+            console.detail(format!("GET {}", url).as_str());
+            let started: Instant = Instant::now();
+            let mut size: u64 = 0;
+            let result = support::fetch_url(url.as_str(), dir.as_path(), |written, total| {
+                size = written;
+                console.progress(written, total);
+            });
+            console.end_progress();
+            match result {
+                Ok(dest) => {
+                    console.detail(
+                        format!(
+                            "Received {} in {} seconds",
+                            utils::fmt::fmt_size(size),
+                            utils::fmt::fmt_millis(started.elapsed())
+                        )
+                        .as_str(),
+                    );
+                    console.success(format!("Saved \"{}\"", dest.display()).as_str());
+                    0
+                }
+                Err(err) => {
+                    console.error(err.as_str());
+                    1
+                }
+            }
+        }
         Task::Activity(activity) => {
             // Get working directory
             let wrkdir: PathBuf = match env::current_dir() {
@@ -256,7 +670,7 @@ fn run(mut run_opts: RunOpts) -> i32 {
             };
             // Create activity manager (and context too)
             let mut manager: ActivityManager =
-                match ActivityManager::new(wrkdir.as_path(), run_opts.ticks) {
+                match ActivityManager::new(wrkdir.as_path(), run_opts.ticks, run_opts.safe_mode) {
                     Ok(m) => m,
                     Err(err) => {
                         eprintln!("Could not start activity manager: {}", err);
@@ -267,6 +681,7 @@ fn run(mut run_opts: RunOpts) -> i32 {
             if let Some(remote) = run_opts.remote.take() {
                 manager.set_filetransfer_params(remote);
             }
+            manager.set_slow_link(run_opts.slow_link);
             manager.run(activity);
             0
         }
@@ -1,6 +1,7 @@
 //! ## Support
 //!
 //! this module exposes some extra run modes for termscp, meant to be used for "support", such as installing themes
+//! or managing bookmarks
 
 /**
  * MIT License
@@ -26,9 +27,18 @@
  * SOFTWARE.
  */
 // mod
+use crate::filetransfer::{Fetch, FileTransferParams, FileTransferProtocol, Proxy, TlsOptions};
+use crate::system::bookmarks_client::BookmarksClient;
+use crate::system::usage_client::UsageClient;
 use crate::system::{environment, theme_provider::ThemeProvider};
+use crate::utils::crypto;
+use serde_json::{json, Value};
 use std::fs;
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const BOOKMARKS_EXPORT_CONTEXT: &str = "bookmarks-export";
 
 /// ### import_theme
 ///
@@ -51,6 +61,377 @@ pub fn import_theme(p: &Path) -> Result<(), String> {
         .map_err(|e| format!("Could not import theme: {}", e))
 }
 
+/// ### bookmarks_client
+///
+/// Instantiates the bookmarks client on the user's configuration directory
+pub fn bookmarks_client() -> Result<BookmarksClient, String> {
+    let cfg_dir: PathBuf = get_config_dir()?;
+    let bookmarks_file: PathBuf = environment::get_bookmarks_paths(cfg_dir.as_path());
+    BookmarksClient::new(bookmarks_file.as_path(), cfg_dir.as_path(), 16)
+        .map_err(|e| format!("Could not load bookmarks: {}", e))
+}
+
+/// ## BookmarkOptions
+///
+/// Optional per-bookmark settings for `add_bookmark`; each one is saved only if set
+#[derive(Debug, Default)]
+pub struct BookmarkOptions {
+    pub proxy: Option<String>,
+    pub tls: Option<TlsOptions>,
+    pub trash: Option<PathBuf>,
+    pub local_directory: Option<PathBuf>,
+    pub agent_forwarding: bool,
+    pub compression: bool,
+    pub group: Option<String>,
+}
+
+/// ### add_bookmark
+///
+/// Add bookmark `name` for `params`, replacing the existing one with the same name.
+/// The password is saved only if set in `params`; the remote entry directory only if set in `params`;
+/// the other settings only if set in `options`.
+/// Returns the saved bookmark as JSON
+pub fn add_bookmark(
+    client: &mut BookmarksClient,
+    name: &str,
+    params: FileTransferParams,
+    options: BookmarkOptions,
+) -> Result<String, String> {
+    if name.is_empty() {
+        return Err(String::from("Bookmark name can't be empty"));
+    }
+    let BookmarkOptions {
+        proxy,
+        tls,
+        trash,
+        local_directory,
+        agent_forwarding,
+        compression,
+        group,
+    } = options;
+    if let Some(proxy) = proxy.as_ref() {
+        Proxy::from_str(proxy.as_str()).map_err(|e| format!("Bad proxy: {}", e))?;
+    }
+    if let Some(tls) = tls.as_ref() {
+        if params.protocol != FileTransferProtocol::Ftp(true) {
+            return Err(String::from("TLS options are supported by FTPS only"));
+        }
+        tls.connector()
+            .map_err(|e| format!("Bad TLS options: {}", e))?;
+    }
+    if let Some(trash) = trash.as_ref() {
+        if !trash.is_absolute() {
+            return Err(String::from("Remote trash must be an absolute path"));
+        }
+    }
+    if let Some(local_directory) = local_directory.as_ref() {
+        if !local_directory.is_absolute() {
+            return Err(String::from("Local directory must be an absolute path"));
+        }
+    }
+    if agent_forwarding
+        && !matches!(
+            params.protocol,
+            FileTransferProtocol::Sftp | FileTransferProtocol::Scp
+        )
+    {
+        return Err(String::from(
+            "Agent forwarding is supported by SFTP and SCP only",
+        ));
+    }
+    if compression
+        && !matches!(
+            params.protocol,
+            FileTransferProtocol::Sftp | FileTransferProtocol::Scp
+        )
+    {
+        return Err(String::from(
+            "Compression is supported by SFTP and SCP only",
+        ));
+    }
+    let username: String = params.username.unwrap_or_default();
+    let host: String = BookmarksClient::host_key(
+        params.address.as_str(),
+        params.port,
+        params.protocol,
+        username.as_str(),
+    );
+    client.add_bookmark(
+        name.to_string(),
+        params.address,
+        params.port,
+        params.protocol,
+        username,
+        params.password,
+    );
+    if proxy.is_some() {
+        client.set_proxy(host.as_str(), proxy);
+    }
+    if tls.is_some() {
+        client.set_tls(host.as_str(), tls);
+    }
+    if trash.is_some() {
+        client.set_trash(host.as_str(), trash);
+    }
+    if params.entry_directory.is_some() {
+        client.set_directory(host.as_str(), params.entry_directory);
+    }
+    if local_directory.is_some() {
+        client.set_local_directory(host.as_str(), local_directory);
+    }
+    if agent_forwarding {
+        client.set_agent_forwarding(host.as_str(), true);
+    }
+    if compression {
+        client.set_compression(host.as_str(), Some(true));
+    }
+    if group.is_some() {
+        client.set_group(name, group);
+    }
+    client
+        .write_bookmarks()
+        .map_err(|e| format!("Could not write bookmarks: {}", e))?;
+    show_bookmark(client, name)
+}
+
+/// ### list_bookmarks
+///
+/// List bookmarks, sorted by name, as a JSON array
+pub fn list_bookmarks(client: &BookmarksClient) -> Result<String, String> {
+    let mut names: Vec<&String> = client.iter_bookmarks().collect();
+    names.sort();
+    let bookmarks: Vec<Value> = names
+        .into_iter()
+        .filter_map(|x| bookmark_to_json(client, x.as_str()))
+        .collect();
+    to_json(&Value::Array(bookmarks))
+}
+
+/// ### show_bookmark
+///
+/// Get bookmark `name` as JSON
+pub fn show_bookmark(client: &BookmarksClient, name: &str) -> Result<String, String> {
+    match bookmark_to_json(client, name) {
+        Some(bookmark) => to_json(&bookmark),
+        None => Err(format!("No such bookmark \"{}\"", name)),
+    }
+}
+
+/// ### remove_bookmark
+///
+/// Remove bookmark `name`.
+/// Returns the removed bookmark as JSON
+pub fn remove_bookmark(client: &mut BookmarksClient, name: &str) -> Result<String, String> {
+    let bookmark: String = show_bookmark(client, name)?;
+    client.del_bookmark(name);
+    client
+        .write_bookmarks()
+        .map_err(|e| format!("Could not write bookmarks: {}", e))?;
+    Ok(bookmark)
+}
+
+/// ### set_master_password
+///
+/// Set or remove (`None`) the master password the bookmarks file is encrypted with
+pub fn set_master_password(
+    client: &mut BookmarksClient,
+    password: Option<String>,
+) -> Result<String, String> {
+    client
+        .set_master_password(password)
+        .map_err(|e| format!("Could not set master password: {}", e))?;
+    client
+        .write_bookmarks()
+        .map_err(|e| format!("Could not write bookmarks: {}", e))?;
+    to_json(&json!({
+        "master_password": client.has_master_password(),
+    }))
+}
+
+/// ### export_bookmarks
+///
+/// Export bookmarks to `p`. With a `passphrase`, passwords are exported too and the whole file
+/// is encrypted with it; otherwise passwords are left out and the file is plain TOML.
+/// Returns the exported bookmarks names as JSON
+pub fn export_bookmarks(
+    client: &BookmarksClient,
+    p: &Path,
+    passphrase: Option<&str>,
+) -> Result<String, String> {
+    let data: String = client
+        .export_bookmarks(passphrase.is_some())
+        .map_err(|e| format!("Could not export bookmarks: {}", e))?;
+    let data: String = match passphrase {
+        Some(passphrase) => crypto::seal(passphrase, BOOKMARKS_EXPORT_CONTEXT, data.as_str()),
+        None => data,
+    };
+    fs::write(p, data).map_err(|e| format!("Could not write {}: {}", p.display(), e))?;
+    let mut names: Vec<&String> = client.iter_bookmarks().collect();
+    names.sort();
+    to_json(&json!({ "exported": names }))
+}
+
+/// ### import_bookmarks
+///
+/// Merge the bookmarks exported to `p` into the current ones; bookmarks with a name already in use
+/// are replaced only if `overwrite` is true. `passphrase` is called if the file is encrypted.
+/// Returns the imported bookmarks names as JSON
+pub fn import_bookmarks<F>(
+    client: &mut BookmarksClient,
+    p: &Path,
+    passphrase: F,
+    overwrite: bool,
+) -> Result<String, String>
+where
+    F: FnOnce() -> Result<String, String>,
+{
+    let data: String =
+        fs::read_to_string(p).map_err(|e| format!("Could not read {}: {}", p.display(), e))?;
+    let data: String = match crypto::is_sealed(data.trim()) {
+        true => crypto::open(
+            passphrase()?.as_str(),
+            BOOKMARKS_EXPORT_CONTEXT,
+            data.trim(),
+        )
+        .map_err(|_| String::from("Could not decrypt bookmarks: wrong passphrase"))?,
+        false => data,
+    };
+    let imported: Vec<String> = client
+        .import_bookmarks(data.as_str(), overwrite)
+        .map_err(|e| format!("Could not import bookmarks: {}", e))?;
+    client
+        .write_bookmarks()
+        .map_err(|e| format!("Could not write bookmarks: {}", e))?;
+    to_json(&json!({ "imported": imported }))
+}
+
+/// ### bookmark_to_json
+///
+/// Make the JSON representation of bookmark `name`. The password and the proxy are never exported
+fn bookmark_to_json(client: &BookmarksClient, name: &str) -> Option<Value> {
+    let (address, port, protocol, username, password) = client.get_bookmark(name)?;
+    let host: String =
+        BookmarksClient::host_key(address.as_str(), port, protocol, username.as_str());
+    let mapping: Value = match client.get_root_mapping(host.as_str()) {
+        Some((local, remote)) => json!({
+            "local": local,
+            "remote": remote,
+        }),
+        None => Value::Null,
+    };
+    let tls: Value = match client.get_tls(host.as_str()) {
+        Some(tls) => json!({
+            "client_cert": tls.client_cert,
+            "ca_bundle": tls.ca_bundle,
+        }),
+        None => Value::Null,
+    };
+    Some(json!({
+        "name": name,
+        "protocol": protocol.to_string(),
+        "address": address,
+        "port": port,
+        "username": username,
+        "password_saved": password.is_some(),
+        "proxy_saved": client.get_proxy(host.as_str()).is_some(),
+        "filters": client.get_filters(host.as_str()),
+        "mapping": mapping,
+        "tls": tls,
+        "trash": client.get_trash(host.as_str()),
+        "directory": client.get_directory(host.as_str()),
+        "local_directory": client.get_local_directory(host.as_str()),
+        "agent_forwarding": client.get_agent_forwarding(host.as_str()),
+        "compression": client.get_compression(host.as_str()),
+        "group": client.get_group(name),
+    }))
+}
+
+/// ### usage_client
+///
+/// Instantiates the usage client on the user's configuration directory
+pub fn usage_client() -> Result<UsageClient, String> {
+    let cfg_dir: PathBuf = get_config_dir()?;
+    let usage_file: PathBuf = environment::get_usage_path(cfg_dir.as_path());
+    UsageClient::new(usage_file.as_path()).map_err(|e| format!("Could not load usage: {}", e))
+}
+
+/// ### usage_report
+///
+/// Get the bandwidth used with each host, month by month from the most recent one, as a JSON array
+pub fn usage_report(client: &UsageClient) -> Result<String, String> {
+    let hosts: Vec<Value> = client
+        .iter_hosts()
+        .map(|host| {
+            let months: Vec<Value> = client
+                .host_usage(host.as_str())
+                .into_iter()
+                .map(|(month, usage)| {
+                    json!({
+                        "month": month,
+                        "uploaded": usage.uploaded,
+                        "downloaded": usage.downloaded,
+                        "total": usage.total(),
+                    })
+                })
+                .collect();
+            json!({
+                "host": host,
+                "months": months,
+            })
+        })
+        .collect();
+    to_json(&Value::Array(hosts))
+}
+
+/// ### fetch_url
+///
+/// Download the HTTP(S) resource at `url` into `dir` and return the path it has been saved to.
+/// `on_progress` is called with the bytes written so far and the size of the resource, if known.
+/// Existing files are never replaced
+pub fn fetch_url<F>(url: &str, dir: &Path, mut on_progress: F) -> Result<PathBuf, String>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    let mut fetch: Fetch =
+        Fetch::get(url).map_err(|e| format!("Could not fetch {}: {}", url, e))?;
+    let dest: PathBuf = dir.join(fetch.name.as_str());
+    if dest.exists() {
+        return Err(format!("\"{}\" already exists", dest.display()));
+    }
+    let mut file: fs::File = fs::File::create(dest.as_path())
+        .map_err(|e| format!("Could not create \"{}\": {}", dest.display(), e))?;
+    let size: Option<u64> = fetch.size.map(|x| x as u64);
+    let mut written: u64 = 0;
+    let mut buffer: [u8; 65536] = [0; 65536];
+    on_progress(written, size);
+    loop {
+        let result: io::Result<usize> = fetch
+            .read(&mut buffer)
+            .and_then(|bytes| file.write_all(&buffer[..bytes]).map(|_| bytes));
+        match result {
+            Ok(0) => break,
+            Ok(bytes) => {
+                written += bytes as u64;
+                on_progress(written, size);
+            }
+            Err(err) => {
+                // Don't leave a truncated file behind
+                drop(file);
+                let _ = fs::remove_file(dest.as_path());
+                return Err(format!("Could not fetch {}: {}", url, err));
+            }
+        }
+    }
+    Ok(dest)
+}
+
+/// ### to_json
+///
+/// Serialize value to pretty JSON
+fn to_json(value: &Value) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Could not serialize bookmarks: {}", e))
+}
+
 /// ### get_config_dir
 ///
 /// Get configuration directory
@@ -66,3 +447,286 @@ fn get_config_dir() -> Result<PathBuf, String> {
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::filetransfer::FileTransferProtocol;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn make_client(dir: &Path) -> BookmarksClient {
+        let bookmarks_file: PathBuf = dir.join("bookmarks.toml");
+        BookmarksClient::new(bookmarks_file.as_path(), dir, 16)
+            .ok()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_support_bookmarks() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let mut client: BookmarksClient = make_client(tmp_dir.path());
+        assert_eq!(list_bookmarks(&client).ok().unwrap(), "[]");
+        // Add
+        let params: FileTransferParams = FileTransferParams::new("192.168.1.31")
+            .port(2222)
+            .protocol(FileTransferProtocol::Scp)
+            .username(Some("omar"))
+            .password(Some("mypassword"))
+            .entry_directory(Some("/var/www/project"));
+        let bookmark: Value = serde_json::from_str(
+            add_bookmark(
+                &mut client,
+                "raspberry",
+                params,
+                BookmarkOptions {
+                    proxy: Some(String::from("socks5://127.0.0.1:1080")),
+                    local_directory: Some(PathBuf::from("/home/omar/projects/website")),
+                    agent_forwarding: true,
+                    compression: true,
+                    group: Some(String::from("homelab")),
+                    ..BookmarkOptions::default()
+                },
+            )
+            .ok()
+            .unwrap()
+            .as_str(),
+        )
+        .unwrap();
+        assert_eq!(
+            bookmark,
+            json!({
+                "name": "raspberry",
+                "protocol": "SCP",
+                "address": "192.168.1.31",
+                "port": 2222,
+                "username": "omar",
+                "password_saved": true,
+                "proxy_saved": true,
+                "filters": [],
+                "mapping": null,
+                "tls": null,
+                "trash": null,
+                "directory": "/var/www/project",
+                "local_directory": "/home/omar/projects/website",
+                "agent_forwarding": true,
+                "compression": true,
+                "group": "homelab",
+            })
+        );
+        let params: FileTransferParams = FileTransferParams::new("10.0.0.1")
+            .port(21)
+            .protocol(FileTransferProtocol::Ftp(false));
+        assert!(add_bookmark(
+            &mut client,
+            "ftp",
+            params.clone(),
+            BookmarkOptions::default()
+        )
+        .is_ok());
+        assert!(add_bookmark(&mut client, "", params.clone(), BookmarkOptions::default()).is_err());
+        assert!(add_bookmark(
+            &mut client,
+            "ftp",
+            params.clone(),
+            BookmarkOptions {
+                proxy: Some(String::from("proxy.local:8080")),
+                ..BookmarkOptions::default()
+            },
+        )
+        .is_err());
+        // TLS options are for FTPS only
+        assert!(add_bookmark(
+            &mut client,
+            "ftp",
+            params.clone(),
+            BookmarkOptions {
+                tls: Some(TlsOptions::default()),
+                ..BookmarkOptions::default()
+            },
+        )
+        .is_err());
+        // Trash must be absolute
+        assert!(add_bookmark(
+            &mut client,
+            "ftp",
+            params.clone(),
+            BookmarkOptions {
+                trash: Some(PathBuf::from(".trash")),
+                ..BookmarkOptions::default()
+            },
+        )
+        .is_err());
+        // Local directory must be absolute
+        assert!(add_bookmark(
+            &mut client,
+            "ftp",
+            params.clone(),
+            BookmarkOptions {
+                local_directory: Some(PathBuf::from("projects")),
+                ..BookmarkOptions::default()
+            },
+        )
+        .is_err());
+        // Agent forwarding is for SFTP and SCP only
+        assert!(add_bookmark(
+            &mut client,
+            "ftp",
+            params,
+            BookmarkOptions {
+                agent_forwarding: true,
+                ..BookmarkOptions::default()
+            },
+        )
+        .is_err());
+        // Bookmarks have been written
+        let mut client: BookmarksClient = make_client(tmp_dir.path());
+        let bookmarks: Value =
+            serde_json::from_str(list_bookmarks(&client).ok().unwrap().as_str()).unwrap();
+        let names: Vec<&str> = bookmarks
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["ftp", "raspberry"]);
+        let bookmark: Value =
+            serde_json::from_str(show_bookmark(&client, "ftp").ok().unwrap().as_str()).unwrap();
+        assert_eq!(bookmark["password_saved"], json!(false));
+        assert_eq!(bookmark["proxy_saved"], json!(false));
+        assert_eq!(bookmark["username"], json!(""));
+        assert_eq!(bookmark["directory"], json!(null));
+        assert_eq!(bookmark["local_directory"], json!(null));
+        assert_eq!(bookmark["agent_forwarding"], json!(false));
+        assert_eq!(bookmark["compression"], json!(null));
+        assert_eq!(bookmark["group"], json!(null));
+        assert!(show_bookmark(&client, "omar").is_err());
+        // Remove
+        assert!(remove_bookmark(&mut client, "ftp").is_ok());
+        assert!(remove_bookmark(&mut client, "ftp").is_err());
+        let client: BookmarksClient = make_client(tmp_dir.path());
+        assert!(show_bookmark(&client, "ftp").is_err());
+        assert!(show_bookmark(&client, "raspberry").is_ok());
+    }
+
+    #[test]
+    fn test_support_master_password() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let mut client: BookmarksClient = make_client(tmp_dir.path());
+        let params: FileTransferParams = FileTransferParams::new("10.0.0.1")
+            .port(21)
+            .protocol(FileTransferProtocol::Ftp(false));
+        assert!(add_bookmark(&mut client, "ftp", params, BookmarkOptions::default()).is_ok());
+        assert_eq!(
+            set_master_password(&mut client, Some(String::from("omar"))).unwrap(),
+            to_json(&json!({"master_password": true})).unwrap()
+        );
+        // Reload; bookmarks are locked
+        let mut client: BookmarksClient = make_client(tmp_dir.path());
+        assert_eq!(list_bookmarks(&client).ok().unwrap(), "[]");
+        assert!(set_master_password(&mut client, None).is_err());
+        assert!(client.unlock("omar").is_ok());
+        assert!(show_bookmark(&client, "ftp").is_ok());
+        // Remove
+        assert_eq!(
+            set_master_password(&mut client, None).unwrap(),
+            to_json(&json!({"master_password": false})).unwrap()
+        );
+        let client: BookmarksClient = make_client(tmp_dir.path());
+        assert!(show_bookmark(&client, "ftp").is_ok());
+    }
+
+    #[test]
+    fn test_support_export_import_bookmarks() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let mut client: BookmarksClient = make_client(tmp_dir.path());
+        let params: FileTransferParams = FileTransferParams::new("10.0.0.1")
+            .port(21)
+            .protocol(FileTransferProtocol::Ftp(false))
+            .username(Some("omar"))
+            .password(Some("mypassword"));
+        assert!(add_bookmark(&mut client, "ftp", params, BookmarkOptions::default()).is_ok());
+        let plain: PathBuf = tmp_dir.path().join("plain.toml");
+        let sealed: PathBuf = tmp_dir.path().join("sealed.toml");
+        assert_eq!(
+            export_bookmarks(&client, plain.as_path(), None).unwrap(),
+            to_json(&json!({"exported": ["ftp"]})).unwrap()
+        );
+        assert!(!fs::read_to_string(plain.as_path())
+            .unwrap()
+            .contains("mypassword"));
+        assert!(export_bookmarks(&client, sealed.as_path(), Some("secret")).is_ok());
+        assert!(crypto::is_sealed(
+            fs::read_to_string(sealed.as_path()).unwrap().as_str()
+        ));
+        // Import into another storage
+        let other_dir: TempDir = TempDir::new().ok().unwrap();
+        let mut other: BookmarksClient = make_client(other_dir.path());
+        assert!(import_bookmarks(
+            &mut other,
+            sealed.as_path(),
+            || Ok(String::from("wrong")),
+            false
+        )
+        .is_err());
+        assert_eq!(
+            import_bookmarks(
+                &mut other,
+                sealed.as_path(),
+                || Ok(String::from("secret")),
+                false
+            )
+            .unwrap(),
+            to_json(&json!({"imported": ["ftp"]})).unwrap()
+        );
+        // Already there
+        assert_eq!(
+            import_bookmarks(
+                &mut other,
+                plain.as_path(),
+                || Err(String::from("not encrypted")),
+                false
+            )
+            .unwrap(),
+            to_json(&json!({"imported": []})).unwrap()
+        );
+        // Written to file, with the password
+        let other: BookmarksClient = make_client(other_dir.path());
+        assert_eq!(
+            other.get_bookmark("ftp").unwrap().4,
+            Some(String::from("mypassword"))
+        );
+    }
+
+    #[test]
+    fn test_support_usage_report() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let mut client: UsageClient =
+            UsageClient::new(tmp_dir.path().join("usage.toml").as_path()).unwrap();
+        assert_eq!(usage_report(&client).unwrap(), "[]");
+        client.record("SFTP://pi@192.168.1.31:22", "2021-06", 1024, 2048);
+        client.record("SFTP://pi@192.168.1.31:22", "2021-07", 0, 512);
+        client.record("FTP://@10.0.0.1:21", "2021-07", 256, 0);
+        let report: Value = serde_json::from_str(usage_report(&client).unwrap().as_str()).unwrap();
+        assert_eq!(
+            report,
+            json!([
+                {
+                    "host": "FTP://@10.0.0.1:21",
+                    "months": [
+                        { "month": "2021-07", "uploaded": 256, "downloaded": 0, "total": 256 },
+                    ],
+                },
+                {
+                    "host": "SFTP://pi@192.168.1.31:22",
+                    "months": [
+                        { "month": "2021-07", "uploaded": 0, "downloaded": 512, "total": 512 },
+                        { "month": "2021-06", "uploaded": 1024, "downloaded": 2048, "total": 3072 },
+                    ],
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! ## Console
+//!
+//! `console` is the module which prints the output of termscp command line modes,
+//! such as the progress of downloads and the outcome of commands
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use crate::utils::fmt::fmt_size;
+// Ext
+use crossterm::style::{style, Color, Stylize};
+use crossterm::tty::IsTty;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// Width of the `bar` progress style, in characters
+const PROGRESS_BAR_WIDTH: usize = 30;
+/// Bytes each dot stands for, in the `dots` progress style
+const PROGRESS_DOT_SIZE: u64 = 64 * 1024;
+/// Dots printed on each line, in the `dots` progress style
+const PROGRESS_DOTS_PER_LINE: u64 = 64;
+
+/// ## Verbosity
+///
+/// Amount of messages printed by command line modes
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Verbosity {
+    Quiet,   // Errors only
+    Normal,  // Errors and outcomes
+    Verbose, // Errors, outcomes and details
+}
+
+/// ## ProgressStyle
+///
+/// How command line modes display the progress of transfers
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ProgressStyle {
+    Bar,  // Redrawn on the same line; meant for interactive shells
+    Dots, // Appended to the output; meant for logs
+    None,
+}
+
+impl FromStr for ProgressStyle {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bar" => Ok(ProgressStyle::Bar),
+            "dots" => Ok(ProgressStyle::Dots),
+            "none" => Ok(ProgressStyle::None),
+            _ => Err(format!(
+                "unknown progress style \"{}\" (expected bar, dots or none)",
+                s
+            )),
+        }
+    }
+}
+
+/// ## Console
+///
+/// Console prints messages and progress of command line modes, according to the verbosity.
+/// Outcomes go to stdout, while errors, details and progress go to stderr
+pub struct Console {
+    verbosity: Verbosity,
+    color: bool,
+    progress: ProgressStyle,
+    bar: Option<u64>, // Value displayed by the progress bar
+    dots: u64,        // Dots printed so far
+}
+
+impl Console {
+    /// ### new
+    ///
+    /// Instantiates a new `Console`. Colors are used only if `color` is true and the stream is a terminal;
+    /// progress is never displayed when quiet
+    pub fn new(verbosity: Verbosity, color: bool, progress: ProgressStyle) -> Self {
+        Self {
+            verbosity,
+            color,
+            progress: match verbosity {
+                Verbosity::Quiet => ProgressStyle::None,
+                _ => progress,
+            },
+            bar: None,
+            dots: 0,
+        }
+    }
+
+    /// ### verbosity
+    ///
+    /// Get the console verbosity
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    /// ### info
+    ///
+    /// Print the outcome of a command, unless quiet
+    pub fn info(&self, msg: &str) {
+        if self.verbosity >= Verbosity::Normal {
+            println!("{}", msg);
+        }
+    }
+
+    /// ### success
+    ///
+    /// Print the successful outcome of a command in green, unless quiet
+    pub fn success(&self, msg: &str) {
+        if self.verbosity >= Verbosity::Normal {
+            println!("{}", self.paint(msg, Color::Green, io::stdout().is_tty()));
+        }
+    }
+
+    /// ### detail
+    ///
+    /// Print a detail about the command being run, only if verbose
+    pub fn detail(&self, msg: &str) {
+        if self.verbosity >= Verbosity::Verbose {
+            eprintln!(
+                "{}",
+                self.paint(msg, Color::DarkGrey, io::stderr().is_tty())
+            );
+        }
+    }
+
+    /// ### error
+    ///
+    /// Print an error in red; errors are always printed
+    pub fn error(&self, msg: &str) {
+        eprintln!("{}", self.paint(msg, Color::Red, io::stderr().is_tty()));
+    }
+
+    /// ### progress
+    ///
+    /// Report that `written` bytes out of `total` (if known) have been transferred
+    pub fn progress(&mut self, written: u64, total: Option<u64>) {
+        let mut stderr = io::stderr();
+        match self.progress {
+            ProgressStyle::Bar => {
+                // Redraw only when the displayed value changes
+                let value: u64 = match total {
+                    Some(total) if total > 0 => written.saturating_mul(100) / total,
+                    _ => written / PROGRESS_DOT_SIZE,
+                };
+                if self.bar != Some(value) {
+                    self.bar = Some(value);
+                    let _ = write!(stderr, "\r{}", fmt_progress_bar(written, total));
+                    let _ = stderr.flush();
+                }
+            }
+            ProgressStyle::Dots => {
+                let dots: u64 = written / PROGRESS_DOT_SIZE;
+                while self.dots < dots {
+                    self.dots += 1;
+                    let _ = write!(stderr, ".");
+                    if self.dots % PROGRESS_DOTS_PER_LINE == 0 {
+                        let _ = writeln!(stderr, " {}", fmt_size(self.dots * PROGRESS_DOT_SIZE));
+                    }
+                }
+                let _ = stderr.flush();
+            }
+            ProgressStyle::None => {}
+        }
+    }
+
+    /// ### end_progress
+    ///
+    /// Terminate the progress line, once the transfer is over
+    pub fn end_progress(&mut self) {
+        let pending_line: bool = match self.progress {
+            ProgressStyle::Bar => self.bar.is_some(),
+            ProgressStyle::Dots => self.dots % PROGRESS_DOTS_PER_LINE != 0,
+            ProgressStyle::None => false,
+        };
+        if pending_line {
+            eprintln!();
+        }
+        self.bar = None;
+        self.dots = 0;
+    }
+
+    /// ### paint
+    ///
+    /// Color `msg`, if colors are enabled and the stream it's printed to is a terminal
+    fn paint(&self, msg: &str, color: Color, tty: bool) -> String {
+        match self.color && tty {
+            true => style(msg).with(color).to_string(),
+            false => msg.to_string(),
+        }
+    }
+}
+
+/// ### fmt_progress_bar
+///
+/// Format the progress bar of `written` bytes out of `total`; without the total, only the written bytes are displayed
+fn fmt_progress_bar(written: u64, total: Option<u64>) -> String {
+    match total {
+        Some(total) if total > 0 => {
+            let ratio: f64 = (written as f64 / total as f64).min(1.0);
+            let filled: usize = (ratio * PROGRESS_BAR_WIDTH as f64) as usize;
+            format!(
+                "[{}{}] {:>3}% {} / {}",
+                "#".repeat(filled),
+                " ".repeat(PROGRESS_BAR_WIDTH - filled),
+                (ratio * 100.0) as u64,
+                fmt_size(written),
+                fmt_size(total)
+            )
+        }
+        _ => fmt_size(written),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_system_console_progress_style() {
+        assert_eq!(
+            ProgressStyle::from_str("bar").ok().unwrap(),
+            ProgressStyle::Bar
+        );
+        assert_eq!(
+            ProgressStyle::from_str("DOTS").ok().unwrap(),
+            ProgressStyle::Dots
+        );
+        assert_eq!(
+            ProgressStyle::from_str("none").ok().unwrap(),
+            ProgressStyle::None
+        );
+        assert!(ProgressStyle::from_str("spinner").is_err());
+    }
+
+    #[test]
+    fn test_system_console_quiet() {
+        let console: Console = Console::new(Verbosity::Quiet, true, ProgressStyle::Bar);
+        assert_eq!(console.verbosity(), Verbosity::Quiet);
+        assert_eq!(console.progress, ProgressStyle::None);
+        let console: Console = Console::new(Verbosity::Verbose, false, ProgressStyle::Dots);
+        assert_eq!(console.progress, ProgressStyle::Dots);
+        assert_eq!(console.paint("saved", Color::Green, true).as_str(), "saved");
+        assert!(Verbosity::Verbose > Verbosity::Normal);
+    }
+
+    #[test]
+    fn test_system_console_fmt_progress_bar() {
+        assert_eq!(
+            fmt_progress_bar(4096, Some(8192)).as_str(),
+            "[###############               ]  50% 4.1 KB / 8.2 KB"
+        );
+        assert_eq!(
+            fmt_progress_bar(8192, Some(8192)).as_str(),
+            "[##############################] 100% 8.2 KB / 8.2 KB"
+        );
+        assert_eq!(fmt_progress_bar(512, None).as_str(), "512 B");
+    }
+}
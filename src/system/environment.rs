@@ -104,6 +104,35 @@ pub fn get_theme_path(config_dir: &Path) -> PathBuf {
     theme_file
 }
 
+/// ### get_usage_path
+///
+/// Get path for usage client
+/// Returns: path of usage.toml
+pub fn get_usage_path(config_dir: &Path) -> PathBuf {
+    let mut usage_file: PathBuf = PathBuf::from(config_dir);
+    usage_file.push("usage.toml");
+    usage_file
+}
+
+/// ### get_journal_path
+///
+/// Get path for journal client
+/// Returns: path of journal.toml
+pub fn get_journal_path(config_dir: &Path) -> PathBuf {
+    let mut journal_file: PathBuf = PathBuf::from(config_dir);
+    journal_file.push("journal.toml");
+    journal_file
+}
+
+/// ### get_traces_dir
+///
+/// Get path of the directory where transfer traces are written
+pub fn get_traces_dir(config_dir: &Path) -> PathBuf {
+    let mut traces_dir: PathBuf = PathBuf::from(config_dir);
+    traces_dir.push("traces/");
+    traces_dir
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -176,4 +205,28 @@ mod tests {
             PathBuf::from("/home/omar/.config/termscp/theme.toml"),
         );
     }
+
+    #[test]
+    fn test_system_environment_get_usage_path() {
+        assert_eq!(
+            get_usage_path(Path::new("/home/omar/.config/termscp/")),
+            PathBuf::from("/home/omar/.config/termscp/usage.toml"),
+        );
+    }
+
+    #[test]
+    fn test_system_environment_get_journal_path() {
+        assert_eq!(
+            get_journal_path(Path::new("/home/omar/.config/termscp/")),
+            PathBuf::from("/home/omar/.config/termscp/journal.toml"),
+        );
+    }
+
+    #[test]
+    fn test_system_environment_get_traces_dir() {
+        assert_eq!(
+            get_traces_dir(Path::new("/home/omar/.config/termscp/")),
+            PathBuf::from("/home/omar/.config/termscp/traces/"),
+        );
+    }
 }
@@ -0,0 +1,195 @@
+//! ## ExitHooks
+//!
+//! `exit_hooks` is the module which runs the user commands configured to be run when a session ends or when termscp exits
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Deps
+use crate::filetransfer::{FileTransferParams, FileTransferProtocol};
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::Duration;
+
+/// ## ExitEvent
+///
+/// Describes what the hook is run for
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ExitEvent {
+    /// The session with the remote host has ended
+    Disconnect,
+    /// termscp is exiting
+    Quit,
+}
+
+impl ExitEvent {
+    /// ### name
+    ///
+    /// Name of the event, as exported to the hook
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExitEvent::Disconnect => "disconnect",
+            ExitEvent::Quit => "quit",
+        }
+    }
+}
+
+/// ## Session
+///
+/// Metadata of the session with a remote host, exported to the hooks.
+/// The password is never exported
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub protocol: FileTransferProtocol,
+    pub address: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub local_dir: PathBuf,
+    pub duration: Duration,
+}
+
+impl Session {
+    /// ### new
+    ///
+    /// Instantiates a new `Session` from the parameters it was started with and how long it lasted
+    pub fn new(params: &FileTransferParams, local_dir: &Path, duration: Duration) -> Self {
+        Self {
+            protocol: params.protocol,
+            address: params.address.clone(),
+            port: params.port,
+            username: params.username.clone(),
+            local_dir: local_dir.to_path_buf(),
+            duration,
+        }
+    }
+}
+
+/// ### environment
+///
+/// Get the environment variables to export to the hook run for `event`.
+/// Session variables are set only if a session has been established
+pub fn environment(event: ExitEvent, session: Option<&Session>) -> Vec<(&'static str, String)> {
+    let mut env: Vec<(&'static str, String)> = vec![("TERMSCP_EVENT", event.name().to_string())];
+    if let Some(session) = session {
+        env.push(("TERMSCP_PROTOCOL", session.protocol.to_string()));
+        env.push(("TERMSCP_HOST", session.address.clone()));
+        env.push(("TERMSCP_PORT", session.port.to_string()));
+        if let Some(username) = session.username.as_ref() {
+            env.push(("TERMSCP_USER", username.clone()));
+        }
+        env.push((
+            "TERMSCP_LOCAL_DIR",
+            session.local_dir.to_string_lossy().to_string(),
+        ));
+        env.push((
+            "TERMSCP_SESSION_SECONDS",
+            session.duration.as_secs().to_string(),
+        ));
+    }
+    env
+}
+
+/// ### run
+///
+/// Run the hook `command` through the system shell, with the session metadata in its environment,
+/// and wait for it to exit. The hook doesn't get the terminal: its input and output are discarded
+pub fn run(command: &str, event: ExitEvent, session: Option<&Session>) -> io::Result<ExitStatus> {
+    #[cfg(target_family = "unix")]
+    let mut shell: Command = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    };
+    #[cfg(target_os = "windows")]
+    let mut shell: Command = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(command);
+        shell
+    };
+    shell
+        .envs(environment(event, session))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn session() -> Session {
+        let params: FileTransferParams = FileTransferParams::new("example.com")
+            .port(2022)
+            .username(Some("omar"))
+            .password(Some("secret"));
+        Session::new(&params, Path::new("/home/omar"), Duration::from_secs(90))
+    }
+
+    #[test]
+    fn test_system_exit_hooks_environment() {
+        assert_eq!(
+            environment(ExitEvent::Quit, None),
+            vec![("TERMSCP_EVENT", String::from("quit"))]
+        );
+        assert_eq!(
+            environment(ExitEvent::Disconnect, Some(&session())),
+            vec![
+                ("TERMSCP_EVENT", String::from("disconnect")),
+                ("TERMSCP_PROTOCOL", String::from("SFTP")),
+                ("TERMSCP_HOST", String::from("example.com")),
+                ("TERMSCP_PORT", String::from("2022")),
+                ("TERMSCP_USER", String::from("omar")),
+                ("TERMSCP_LOCAL_DIR", String::from("/home/omar")),
+                ("TERMSCP_SESSION_SECONDS", String::from("90")),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_system_exit_hooks_run() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let out: PathBuf = tmp_dir.path().join("hook.txt");
+        let command: String = format!(
+            "echo \"$TERMSCP_EVENT $TERMSCP_HOST:$TERMSCP_PORT\" > '{}'",
+            out.display()
+        );
+        assert!(
+            run(command.as_str(), ExitEvent::Disconnect, Some(&session()))
+                .unwrap()
+                .success()
+        );
+        assert_eq!(
+            std::fs::read_to_string(out.as_path()).unwrap().as_str(),
+            "disconnect example.com:2022\n"
+        );
+        assert!(!run("false", ExitEvent::Quit, None).unwrap().success());
+    }
+}
@@ -0,0 +1,205 @@
+//! ## JournalClient
+//!
+//! `journal_client` is the module which provides an API to keep track of the batch transfers in progress,
+//! so that they can be resumed if termscp exits before they're over
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use crate::config::journal::{JournalEntry, TransferJournal};
+use crate::config::serialization::{deserialize, serialize, SerializerError, SerializerErrorKind};
+// Ext
+use chrono::Local;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+/// ## JournalClient
+///
+/// JournalClient provides a high level API to record the batch transfer in progress with each host
+pub struct JournalClient {
+    journal: TransferJournal,
+    journal_path: PathBuf, // Journal TOML path
+}
+
+impl JournalClient {
+    /// ### new
+    ///
+    /// Instantiates a new `JournalClient`, loading the journal from `journal_path` if it exists
+    pub fn new(journal_path: &Path) -> Result<Self, SerializerError> {
+        info!(
+            "Setting up journal client with journal path {}",
+            journal_path.display()
+        );
+        let mut client: JournalClient = JournalClient {
+            journal: TransferJournal::default(),
+            journal_path: journal_path.to_path_buf(),
+        };
+        if journal_path.exists() {
+            if let Err(err) = client.load() {
+                error!("Couldn't read journal file: {}", err);
+                return Err(err);
+            }
+            debug!("Read journal file");
+        }
+        Ok(client)
+    }
+
+    /// ### make_entry
+    ///
+    /// Make a new journal entry for a transfer starting now
+    pub fn make_entry(
+        upload: bool,
+        sources: Vec<PathBuf>,
+        dest: &Path,
+        dst_name: Option<String>,
+    ) -> JournalEntry {
+        JournalEntry {
+            upload,
+            sources,
+            dest: dest.to_path_buf(),
+            dst_name,
+            started: Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+
+    /// ### pending
+    ///
+    /// Get the transfer with `host` which was interrupted, if any
+    pub fn pending(&self, host: &str) -> Option<&JournalEntry> {
+        self.journal.hosts.get(host)
+    }
+
+    /// ### record
+    ///
+    /// Record the transfer started with `host`, replacing the previous one
+    pub fn record(&mut self, host: &str, entry: JournalEntry) {
+        debug!(
+            "Recording transfer of {} entries with {} into journal",
+            entry.sources.len(),
+            host
+        );
+        self.journal.hosts.insert(host.to_string(), entry);
+    }
+
+    /// ### clear
+    ///
+    /// Remove the transfer with `host` from the journal; returns whether there was one
+    pub fn clear(&mut self, host: &str) -> bool {
+        self.journal.hosts.remove(host).is_some()
+    }
+
+    // -- io
+
+    /// ### load
+    ///
+    /// Load journal from file
+    fn load(&mut self) -> Result<(), SerializerError> {
+        match OpenOptions::new()
+            .read(true)
+            .open(self.journal_path.as_path())
+        {
+            Ok(reader) => {
+                self.journal = deserialize(Box::new(reader))?;
+                Ok(())
+            }
+            Err(err) => {
+                error!("Failed to read journal: {}", err);
+                Err(SerializerError::new_ex(
+                    SerializerErrorKind::Io,
+                    err.to_string(),
+                ))
+            }
+        }
+    }
+
+    /// ### write
+    ///
+    /// Write journal to file
+    pub fn write(&self) -> Result<(), SerializerError> {
+        match OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.journal_path.as_path())
+        {
+            Ok(writer) => serialize(&self.journal, Box::new(writer)),
+            Err(err) => {
+                error!("Failed to write journal: {}", err);
+                Err(SerializerError::new_ex(
+                    SerializerErrorKind::Io,
+                    err.to_string(),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_system_journal_client() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let journal_path: PathBuf = tmp_dir.path().join("journal.toml");
+        let mut client: JournalClient = JournalClient::new(journal_path.as_path()).unwrap();
+        // File is not created until written
+        assert!(!journal_path.exists());
+        let host: &str = "SFTP://pi@192.168.1.31:22";
+        assert!(client.pending(host).is_none());
+        // Record
+        let entry: JournalEntry = JournalClient::make_entry(
+            true,
+            vec![
+                PathBuf::from("/home/omar/site/index.html"),
+                PathBuf::from("/home/omar/site/css/"),
+            ],
+            Path::new("/var/www"),
+            None,
+        );
+        assert_eq!(entry.started.len(), 16);
+        client.record(host, entry.clone());
+        assert_eq!(client.pending(host), Some(&entry));
+        // Write and reload
+        assert!(client.write().is_ok());
+        let mut client: JournalClient = JournalClient::new(journal_path.as_path()).unwrap();
+        assert_eq!(client.pending(host), Some(&entry));
+        // Clear
+        assert_eq!(client.clear(host), true);
+        assert_eq!(client.clear(host), false);
+        assert!(client.pending(host).is_none());
+    }
+
+    #[test]
+    fn test_system_journal_client_bad_file() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let journal_path: PathBuf = tmp_dir.path().join("journal.toml");
+        std::fs::write(journal_path.as_path(), "hosts = 1024\n").unwrap();
+        assert!(JournalClient::new(journal_path.as_path()).is_err());
+    }
+}
@@ -0,0 +1,379 @@
+//! ## Metrics
+//!
+//! `metrics` is the module which counts the transfers made while termscp runs and exports them in the
+//! Prometheus text format, either served over HTTP on localhost or written to a file for the node
+//! exporter textfile collector
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Outcomes counters are kept for, in the order they're exported
+const OUTCOMES: [Outcome; 3] = [Outcome::Completed, Outcome::Failed, Outcome::Aborted];
+/// Time given to clients to send their request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Most header lines read from a request
+const MAX_HEADERS: usize = 64;
+/// Most bytes read from a request, request line and headers included
+const MAX_REQUEST_SIZE: u64 = 16 * 1024;
+
+/// ## Outcome
+///
+/// How a batch transfer ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Completed,
+    Failed,
+    Aborted,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Completed => "completed",
+            Outcome::Failed => "failed",
+            Outcome::Aborted => "aborted",
+        }
+    }
+}
+
+/// ## Metrics
+///
+/// Counters of the transfers made since termscp started, by direction.
+/// Metrics is a handle: clones share the same counters, so that they're kept across sessions
+/// and read by the metrics endpoint
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    totals: Arc<Mutex<Totals>>,
+}
+
+/// ## Totals
+///
+/// Counters of the transfers made in each direction
+#[derive(Debug, Default)]
+struct Totals {
+    upload: Counters,
+    download: Counters,
+}
+
+/// ## Counters
+///
+/// Counters of the transfers made in one direction
+#[derive(Debug, Default)]
+struct Counters {
+    transfers: [u64; 3],          // Transfers ended, by outcome
+    last_ended: [Option<u64>; 3], // Unix time the last transfer ended, by outcome
+    files: u64,                   // Files transferred
+    failed_files: u64,            // Files which couldn't be transferred
+    bytes: u64,                   // Bytes transferred
+}
+
+impl Metrics {
+    /// ### record
+    ///
+    /// Record a transfer which ended at `time` with `outcome`
+    pub fn record(
+        &self,
+        upload: bool,
+        outcome: Outcome,
+        files: usize,
+        failed: usize,
+        bytes: usize,
+        time: SystemTime,
+    ) {
+        let mut totals = self.totals.lock().unwrap();
+        let counters: &mut Counters = match upload {
+            true => &mut totals.upload,
+            false => &mut totals.download,
+        };
+        let index: usize = OUTCOMES.iter().position(|x| *x == outcome).unwrap_or(0);
+        counters.transfers[index] += 1;
+        counters.last_ended[index] = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .ok()
+            .map(|x| x.as_secs());
+        counters.files += files as u64;
+        counters.failed_files += failed as u64;
+        counters.bytes += bytes as u64;
+    }
+
+    /// ### render
+    ///
+    /// Render metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        let totals = self.totals.lock().unwrap();
+        let directions: [(&str, &Counters); 2] =
+            [("upload", &totals.upload), ("download", &totals.download)];
+        let mut out: String = String::new();
+        out.push_str("# HELP termscp_transfers_total Transfers ended, by direction and outcome\n");
+        out.push_str("# TYPE termscp_transfers_total counter\n");
+        for (direction, counters) in directions.iter() {
+            for (outcome, count) in OUTCOMES.iter().zip(counters.transfers.iter()) {
+                out.push_str(
+                    format!(
+                        "termscp_transfers_total{{direction=\"{}\",outcome=\"{}\"}} {}\n",
+                        direction,
+                        outcome.as_str(),
+                        count
+                    )
+                    .as_str(),
+                );
+            }
+        }
+        out.push_str("# HELP termscp_last_transfer_timestamp_seconds Unix time the last transfer ended, by direction and outcome\n");
+        out.push_str("# TYPE termscp_last_transfer_timestamp_seconds gauge\n");
+        for (direction, counters) in directions.iter() {
+            for (outcome, time) in OUTCOMES.iter().zip(counters.last_ended.iter()) {
+                if let Some(time) = time {
+                    out.push_str(
+                        format!(
+                            "termscp_last_transfer_timestamp_seconds{{direction=\"{}\",outcome=\"{}\"}} {}\n",
+                            direction,
+                            outcome.as_str(),
+                            time
+                        )
+                        .as_str(),
+                    );
+                }
+            }
+        }
+        let totals: [(&str, &str, u64, u64); 3] = [
+            (
+                "files",
+                "Files transferred",
+                totals.upload.files,
+                totals.download.files,
+            ),
+            (
+                "failed_files",
+                "Files which couldn't be transferred",
+                totals.upload.failed_files,
+                totals.download.failed_files,
+            ),
+            (
+                "bytes",
+                "Bytes transferred",
+                totals.upload.bytes,
+                totals.download.bytes,
+            ),
+        ];
+        for (name, help, upload, download) in totals.iter() {
+            out.push_str(format!("# HELP termscp_{}_total {}\n", name, help).as_str());
+            out.push_str(format!("# TYPE termscp_{}_total counter\n", name).as_str());
+            out.push_str(
+                format!(
+                    "termscp_{}_total{{direction=\"upload\"}} {}\n",
+                    name, upload
+                )
+                .as_str(),
+            );
+            out.push_str(
+                format!(
+                    "termscp_{}_total{{direction=\"download\"}} {}\n",
+                    name, download
+                )
+                .as_str(),
+            );
+        }
+        out
+    }
+
+    /// ### write
+    ///
+    /// Write metrics to `path`. The file is replaced at once, so that collectors never read
+    /// it half-written
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path: PathBuf = path.with_file_name(tmp_name);
+        let mut file: File = File::create(tmp_path.as_path())?;
+        file.write_all(self.render().as_bytes())?;
+        file.sync_all()?;
+        fs::rename(tmp_path.as_path(), path)
+    }
+
+    /// ### serve
+    ///
+    /// Serve metrics over HTTP on `port` of localhost, from a background thread.
+    /// Returns an error if the port can't be bound
+    pub fn serve(&self, port: u16) -> io::Result<()> {
+        let listener: TcpListener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        info!("Serving metrics on http://localhost:{}/metrics", port);
+        let metrics: Metrics = self.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if let Err(err) = stream.and_then(|x| metrics.respond(x)) {
+                    debug!("Could not answer metrics request: {}", err);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// ### respond
+    ///
+    /// Answer the HTTP request read from `stream`: metrics are returned for `GET /metrics`.
+    /// At most `MAX_REQUEST_SIZE` bytes are read, within `REQUEST_TIMEOUT`
+    fn respond(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_SIZE));
+        let mut request: String = String::new();
+        reader.read_line(&mut request)?;
+        // Skip headers
+        for _ in 0..MAX_HEADERS {
+            let mut header: String = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+        }
+        let (status, body): (&str, String) =
+            match request.split_whitespace().collect::<Vec<&str>>().as_slice() {
+                // The request line has been cut by the size limit
+                _ if !request.ends_with('\n') => ("400 Bad Request", String::from("Bad request\n")),
+                ["GET", "/metrics", ..] => ("200 OK", self.render()),
+                ["GET", ..] => ("404 Not Found", String::from("Not found\n")),
+                _ => (
+                    "405 Method Not Allowed",
+                    String::from("Method not allowed\n"),
+                ),
+            };
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_system_metrics() {
+        let metrics: Metrics = Metrics::default();
+        let time: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(1634400000);
+        metrics.record(true, Outcome::Completed, 8, 0, 1024, time);
+        metrics.record(
+            true,
+            Outcome::Failed,
+            2,
+            1,
+            512,
+            time + Duration::from_secs(60),
+        );
+        metrics.record(false, Outcome::Completed, 1, 0, 64, time);
+        let expected: &str = r#"# HELP termscp_transfers_total Transfers ended, by direction and outcome
+# TYPE termscp_transfers_total counter
+termscp_transfers_total{direction="upload",outcome="completed"} 1
+termscp_transfers_total{direction="upload",outcome="failed"} 1
+termscp_transfers_total{direction="upload",outcome="aborted"} 0
+termscp_transfers_total{direction="download",outcome="completed"} 1
+termscp_transfers_total{direction="download",outcome="failed"} 0
+termscp_transfers_total{direction="download",outcome="aborted"} 0
+# HELP termscp_last_transfer_timestamp_seconds Unix time the last transfer ended, by direction and outcome
+# TYPE termscp_last_transfer_timestamp_seconds gauge
+termscp_last_transfer_timestamp_seconds{direction="upload",outcome="completed"} 1634400000
+termscp_last_transfer_timestamp_seconds{direction="upload",outcome="failed"} 1634400060
+termscp_last_transfer_timestamp_seconds{direction="download",outcome="completed"} 1634400000
+# HELP termscp_files_total Files transferred
+# TYPE termscp_files_total counter
+termscp_files_total{direction="upload"} 10
+termscp_files_total{direction="download"} 1
+# HELP termscp_failed_files_total Files which couldn't be transferred
+# TYPE termscp_failed_files_total counter
+termscp_failed_files_total{direction="upload"} 1
+termscp_failed_files_total{direction="download"} 0
+# HELP termscp_bytes_total Bytes transferred
+# TYPE termscp_bytes_total counter
+termscp_bytes_total{direction="upload"} 1536
+termscp_bytes_total{direction="download"} 64
+"#;
+        assert_eq!(metrics.render().as_str(), expected);
+        // Write
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let path: PathBuf = tmp_dir.path().join("termscp.prom");
+        assert!(metrics.write(path.as_path()).is_ok());
+        assert_eq!(
+            fs::read_to_string(path.as_path()).unwrap().as_str(),
+            expected
+        );
+        assert!(!tmp_dir.path().join("termscp.prom.tmp").exists());
+    }
+
+    #[test]
+    fn test_system_metrics_serve() {
+        let metrics: Metrics = Metrics::default();
+        // Bind a free port
+        let port: u16 = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .and_then(|x| x.local_addr())
+            .map(|x| x.port())
+            .unwrap();
+        assert!(metrics.serve(port).is_ok());
+        // Port is taken
+        assert!(metrics.serve(port).is_err());
+        // Counters are shared with the server
+        metrics
+            .clone()
+            .record(true, Outcome::Completed, 1, 0, 64, SystemTime::now());
+        let get = |path: &str| -> String {
+            let mut stream: TcpStream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+            write!(
+                stream,
+                "GET {} HTTP/1.1\r\nHost: localhost\r\nAccept: */*\r\n\r\n",
+                path
+            )
+            .unwrap();
+            let mut response: String = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response: String = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(metrics.render().as_str()));
+        assert!(response.contains("termscp_files_total{direction=\"upload\"} 1\n"));
+        assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+        // Requests are read up to the size limit
+        let mut stream: TcpStream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream
+            .write_all(vec![b'A'; MAX_REQUEST_SIZE as usize].as_slice())
+            .unwrap();
+        let mut response: String = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    }
+}
@@ -28,8 +28,15 @@
 // modules
 pub mod bookmarks_client;
 pub mod config_client;
+pub mod console;
 pub mod environment;
+pub mod exit_hooks;
+pub mod journal_client;
 pub(self) mod keys;
 pub mod logging;
+pub mod metrics;
+pub mod ssh_config;
 pub mod sshkey_storage;
+pub mod startup_profile;
 pub mod theme_provider;
+pub mod usage_client;
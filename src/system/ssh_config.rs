@@ -0,0 +1,356 @@
+//! ## SshConfig
+//!
+//! `ssh_config` is the module which reads the user's OpenSSH client configuration (`~/.ssh/config`)
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Ext
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use wildmatch::WildMatch;
+
+/// ## SshHostParams
+///
+/// SshHostParams contains the parameters resolved from the ssh config for a host
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SshHostParams {
+    /// Real host name to connect to (`HostName`)
+    pub host_name: Option<String>,
+    /// Port to connect to (`Port`)
+    pub port: Option<u16>,
+    /// User to log in as (`User`)
+    pub user: Option<String>,
+    /// Keys to authenticate with, in the order they have been declared (`IdentityFile`)
+    pub identity_files: Vec<PathBuf>,
+    /// OpenSSH certificates to authenticate with, in the order they have been declared (`CertificateFile`)
+    pub certificate_files: Vec<PathBuf>,
+    /// Host to jump through, expressed as `[user@]host[:port]` (`ProxyJump`)
+    pub proxy_jump: Option<String>,
+}
+
+/// ## HostBlock
+///
+/// A `Host` section of the ssh config
+#[derive(Debug, Clone)]
+struct HostBlock {
+    /// Patterns the host must match; `None` for sections which never match (e.g. `Match`)
+    patterns: Option<Vec<String>>,
+    /// Keywords (lowercase) and values declared in the section
+    params: Vec<(String, String)>,
+}
+
+impl HostBlock {
+    /// ### matches
+    ///
+    /// Returns whether `host` matches at least one pattern and none of the negated patterns
+    fn matches(&self, host: &str) -> bool {
+        let patterns: &Vec<String> = match self.patterns.as_ref() {
+            Some(p) => p,
+            None => return false,
+        };
+        let host: String = host.to_lowercase();
+        let mut matched: bool = false;
+        for pattern in patterns.iter() {
+            match pattern.strip_prefix('!') {
+                Some(negated) if WildMatch::new(negated).matches(host.as_str()) => return false,
+                Some(_) => {}
+                None => matched |= WildMatch::new(pattern.as_str()).matches(host.as_str()),
+            }
+        }
+        matched
+    }
+}
+
+/// ## SshConfig
+///
+/// SshConfig holds the `Host` sections of an OpenSSH client configuration
+#[derive(Debug, Clone, Default)]
+pub struct SshConfig {
+    hosts: Vec<HostBlock>,
+}
+
+impl SshConfig {
+    /// ### user_config
+    ///
+    /// Read the ssh config of the current user (`~/.ssh/config`).
+    /// If the file doesn't exist or can't be read, an empty configuration is returned
+    pub fn user_config() -> Self {
+        let path: PathBuf = match dirs::home_dir() {
+            Some(mut p) => {
+                p.push(".ssh/config");
+                p
+            }
+            None => return Self::default(),
+        };
+        if !path.exists() {
+            return Self::default();
+        }
+        match Self::from_file(path.as_path()) {
+            Ok(config) => {
+                info!("Read ssh config at {}", path.display());
+                config
+            }
+            Err(err) => {
+                error!("Could not read ssh config at {}: {}", path.display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    /// ### from_file
+    ///
+    /// Read the ssh config at `path`
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        fs::read_to_string(path).map(|s| Self::parse(s.as_str()))
+    }
+
+    /// ### parse
+    ///
+    /// Parse the ssh config from its content.
+    /// Keywords declared before the first `Host` apply to every host;
+    /// `Match` sections and `Include` directives are not supported and are ignored
+    pub fn parse(content: &str) -> Self {
+        // Keywords before the first `Host` apply to all hosts
+        let mut hosts: Vec<HostBlock> = vec![HostBlock {
+            patterns: Some(vec![String::from("*")]),
+            params: Vec::new(),
+        }];
+        for (n, line) in content.lines().enumerate() {
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (keyword, value): (String, &str) = Self::split_line(line);
+            match keyword.as_str() {
+                "host" => hosts.push(HostBlock {
+                    patterns: Some(
+                        value
+                            .split_whitespace()
+                            .map(|x| Self::unquote(x).to_lowercase())
+                            .collect(),
+                    ),
+                    params: Vec::new(),
+                }),
+                "match" => {
+                    warn!("ssh config line {}: `Match` is not supported", n + 1);
+                    hosts.push(HostBlock {
+                        patterns: None,
+                        params: Vec::new(),
+                    });
+                }
+                "include" => warn!("ssh config line {}: `Include` is not supported", n + 1),
+                _ => {
+                    if let Some(block) = hosts.last_mut() {
+                        block
+                            .params
+                            .push((keyword, Self::unquote(value).to_string()));
+                    }
+                }
+            }
+        }
+        SshConfig { hosts }
+    }
+
+    /// ### query
+    ///
+    /// Resolve the parameters for `host`. As ssh does, the first value obtained for each keyword
+    /// is used, except for `IdentityFile` and `CertificateFile`, whose values are all collected
+    pub fn query(&self, host: &str) -> SshHostParams {
+        let mut params: SshHostParams = SshHostParams::default();
+        let mut proxy_jump_set: bool = false;
+        for block in self.hosts.iter().filter(|x| x.matches(host)) {
+            for (keyword, value) in block.params.iter() {
+                match keyword.as_str() {
+                    "hostname" if params.host_name.is_none() => {
+                        params.host_name = Some(value.replace("%h", host));
+                    }
+                    "port" if params.port.is_none() => match value.parse::<u16>() {
+                        Ok(port) => params.port = Some(port),
+                        Err(_) => warn!("ssh config: invalid port '{}' for {}", value, host),
+                    },
+                    "user" if params.user.is_none() => {
+                        params.user = Some(value.to_string());
+                    }
+                    "identityfile" => {
+                        params
+                            .identity_files
+                            .push(Self::expand_path(value.replace("%h", host).as_str()));
+                    }
+                    "certificatefile" => {
+                        params
+                            .certificate_files
+                            .push(Self::expand_path(value.replace("%h", host).as_str()));
+                    }
+                    "proxyjump" if !proxy_jump_set => {
+                        proxy_jump_set = true;
+                        if !value.eq_ignore_ascii_case("none") {
+                            params.proxy_jump = Some(value.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        params
+    }
+
+    /// ### split_line
+    ///
+    /// Split a config line into its keyword (lowercase) and its value.
+    /// Keyword and value can be separated either by whitespaces or by `=`
+    fn split_line(line: &str) -> (String, &str) {
+        let end: usize = line
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(line.len());
+        let (keyword, value): (&str, &str) = line.split_at(end);
+        let value: &str = value.trim_start();
+        let value: &str = value.strip_prefix('=').unwrap_or(value).trim();
+        (keyword.to_lowercase(), value)
+    }
+
+    /// ### unquote
+    ///
+    /// Remove the quotes surrounding `value`, if any
+    fn unquote(value: &str) -> &str {
+        match value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+            true => &value[1..value.len() - 1],
+            false => value,
+        }
+    }
+
+    /// ### expand_path
+    ///
+    /// Expand `~` to the user's home directory
+    fn expand_path(path: &str) -> PathBuf {
+        match (path.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rel), Some(mut home)) => {
+                home.push(rel);
+                home
+            }
+            _ => PathBuf::from(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    const SSH_CONFIG: &str = r#"
+# Global options
+IdentityFile /etc/ssh/id_global
+
+Host pi raspberry
+    HostName 192.168.1.31
+    User pi
+    Port 2222
+    IdentityFile "/home/omar/.ssh/id_pi"
+    CertificateFile /home/omar/.ssh/id_pi-cert.pub
+
+Host *.lan !printer.lan
+    User=omar
+    ProxyJump bastion.example.com:2022
+
+Host bastion.example.com
+    ProxyJump none
+
+Match host nas
+    User admin
+
+Host *
+    User root
+    Port 22
+"#;
+
+    #[test]
+    fn test_system_ssh_config_query_alias() {
+        let config: SshConfig = SshConfig::parse(SSH_CONFIG);
+        let params: SshHostParams = config.query("raspberry");
+        assert_eq!(params.host_name.as_deref(), Some("192.168.1.31"));
+        assert_eq!(params.user.as_deref(), Some("pi"));
+        assert_eq!(params.port, Some(2222));
+        assert_eq!(
+            params.identity_files,
+            vec![
+                PathBuf::from("/etc/ssh/id_global"),
+                PathBuf::from("/home/omar/.ssh/id_pi")
+            ]
+        );
+        assert_eq!(
+            params.certificate_files,
+            vec![PathBuf::from("/home/omar/.ssh/id_pi-cert.pub")]
+        );
+        assert!(params.proxy_jump.is_none());
+        // Aliases are case insensitive
+        assert_eq!(config.query("PI"), params);
+    }
+
+    #[test]
+    fn test_system_ssh_config_query_patterns() {
+        let config: SshConfig = SshConfig::parse(SSH_CONFIG);
+        let params: SshHostParams = config.query("nas.lan");
+        assert!(params.host_name.is_none());
+        assert_eq!(params.user.as_deref(), Some("omar"));
+        assert_eq!(params.port, Some(22));
+        assert_eq!(
+            params.proxy_jump.as_deref(),
+            Some("bastion.example.com:2022")
+        );
+        // Negated pattern
+        let params: SshHostParams = config.query("printer.lan");
+        assert_eq!(params.user.as_deref(), Some("root"));
+        assert!(params.proxy_jump.is_none());
+        // `Match` sections are ignored
+        assert_eq!(config.query("nas").user.as_deref(), Some("root"));
+        // ProxyJump none
+        assert!(config.query("bastion.example.com").proxy_jump.is_none());
+    }
+
+    #[test]
+    fn test_system_ssh_config_empty() {
+        let config: SshConfig = SshConfig::parse("");
+        assert_eq!(config.query("192.168.1.31"), SshHostParams::default());
+        assert_eq!(
+            SshConfig::default().query("192.168.1.31"),
+            SshHostParams::default()
+        );
+    }
+
+    #[test]
+    fn test_system_ssh_config_from_file() {
+        let mut tmpfile: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
+        writeln!(tmpfile, "Host pi\n  HostName 192.168.1.31").unwrap();
+        let config: SshConfig = SshConfig::from_file(tmpfile.path()).unwrap();
+        assert_eq!(
+            config.query("pi").host_name.as_deref(),
+            Some("192.168.1.31")
+        );
+        assert!(SshConfig::from_file(Path::new("/this/doesnt/exist")).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! ## StartupProfile
+//!
+//! `startup_profile` is the module which measures how long each phase of termscp startup takes
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Ext
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    static ref PROFILE: Mutex<Option<StartupProfile>> = Mutex::new(None);
+}
+
+/// ## StartupProfile
+///
+/// Startup phases completed so far, each with the time it took
+struct StartupProfile {
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl StartupProfile {
+    /// ### new
+    ///
+    /// Instantiates a new `StartupProfile` for a startup begun at `started`
+    fn new(started: Instant) -> Self {
+        Self {
+            last: started,
+            phases: Vec::new(),
+        }
+    }
+
+    /// ### mark
+    ///
+    /// Record the end of the phase `name` at `now`; the phase began when the previous one ended
+    fn mark(&mut self, name: &'static str, now: Instant) {
+        self.phases
+            .push((name, now.saturating_duration_since(self.last)));
+        self.last = now;
+    }
+}
+
+impl fmt::Display for StartupProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Startup profile:")?;
+        for (name, took) in self.phases.iter() {
+            writeln!(f, "  {:<20}{:>10.1} ms", name, took.as_secs_f64() * 1000.0)?;
+        }
+        let total: Duration = self.phases.iter().map(|(_, took)| *took).sum();
+        write!(
+            f,
+            "  {:<20}{:>10.1} ms",
+            "total",
+            total.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+/// ### enable
+///
+/// Start profiling the startup of termscp, which began at `started`
+pub fn enable(started: Instant) {
+    if let Ok(mut profile) = PROFILE.lock() {
+        *profile = Some(StartupProfile::new(started));
+    }
+}
+
+/// ### mark
+///
+/// Record the end of the startup phase `name`. Does nothing unless profiling is enabled
+pub fn mark(name: &'static str) {
+    if let Ok(mut profile) = PROFILE.lock() {
+        if let Some(profile) = profile.as_mut() {
+            profile.mark(name, Instant::now());
+        }
+    }
+}
+
+/// ### report
+///
+/// Stop profiling and return the phases recorded so far, formatted to be printed.
+/// Returns `None` if profiling is not enabled
+pub fn report() -> Option<String> {
+    PROFILE
+        .lock()
+        .ok()
+        .and_then(|mut profile| profile.take())
+        .map(|profile| profile.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_system_startup_profile() {
+        let started: Instant = Instant::now();
+        let mut profile: StartupProfile = StartupProfile::new(started);
+        profile.mark("load configuration", started + Duration::from_millis(12));
+        profile.mark("first paint", started + Duration::from_micros(20500));
+        assert_eq!(
+            profile.phases,
+            vec![
+                ("load configuration", Duration::from_millis(12)),
+                ("first paint", Duration::from_micros(8500)),
+            ]
+        );
+        assert_eq!(
+            profile.to_string(),
+            String::from(
+                "Startup profile:\n  load configuration        12.0 ms\n  first paint                8.5 ms\n  total                     20.5 ms"
+            )
+        );
+    }
+}
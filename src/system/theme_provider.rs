@@ -26,6 +26,7 @@
  * SOFTWARE.
  */
 // Locals
+use super::startup_profile;
 use crate::config::{
     serialization::{deserialize, serialize, SerializerError, SerializerErrorKind},
     themes::Theme,
@@ -42,6 +43,7 @@ pub struct ThemeProvider {
     theme: Theme,        // Theme loaded
     theme_path: PathBuf, // Theme TOML Path
     degraded: bool,      // Fallback mode; won't work with file system
+    pending: bool,       // The theme file has still to be read
 }
 
 impl ThemeProvider {
@@ -59,6 +61,7 @@ impl ThemeProvider {
             theme: default_theme,
             theme_path: theme_path.to_path_buf(),
             degraded: false,
+            pending: false,
         };
         // If Config file doesn't exist, create it
         if !theme_path.exists() {
@@ -87,9 +90,44 @@ impl ThemeProvider {
             theme: Theme::default(),
             theme_path: PathBuf::default(),
             degraded: true,
+            pending: false,
         }
     }
 
+    /// ### lazy
+    ///
+    /// Instantiates a new `ThemeProvider` which reads the theme file only once `ensure_loaded` is called.
+    /// Until then, the default theme is provided
+    pub fn lazy(theme_path: &Path) -> Self {
+        Self {
+            theme: Theme::default(),
+            theme_path: theme_path.to_path_buf(),
+            degraded: false,
+            pending: true,
+        }
+    }
+
+    /// ### ensure_loaded
+    ///
+    /// Read the theme file, if it hasn't been read yet. Returns whether the theme changed.
+    /// If the file can't be read, the provider falls back to the degraded mode
+    pub fn ensure_loaded(&mut self) -> bool {
+        if !self.pending {
+            return false;
+        }
+        let provider: ThemeProvider = match Self::new(self.theme_path.as_path()) {
+            Ok(provider) => provider,
+            Err(err) => {
+                error!("Could not initialize theme provider with file '{}': {}; using theme provider in degraded mode", self.theme_path.display(), err);
+                Self::degraded()
+            }
+        };
+        let changed: bool = provider.theme != self.theme;
+        *self = provider;
+        startup_profile::mark("load theme");
+        changed
+    }
+
     // -- getters
 
     /// ### theme
@@ -219,6 +257,25 @@ mod test {
         assert_eq!(provider.theme().auth_address, Color::Green); // Unchanged
     }
 
+    #[test]
+    fn test_system_theme_provider_lazy() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let theme_path: PathBuf = get_theme_path(tmp_dir.path());
+        let mut provider: ThemeProvider = ThemeProvider::new(theme_path.as_path()).unwrap();
+        provider.theme_mut().auth_address = Color::Green;
+        assert!(provider.save().is_ok());
+        // Theme file is read only once needed
+        let mut provider: ThemeProvider = ThemeProvider::lazy(theme_path.as_path());
+        assert_eq!(provider.theme().auth_address, Color::Yellow);
+        assert_eq!(provider.ensure_loaded(), true);
+        assert_eq!(provider.theme().auth_address, Color::Green);
+        assert_eq!(provider.ensure_loaded(), false);
+        // Unreadable theme falls back to degraded mode
+        let mut provider: ThemeProvider = ThemeProvider::lazy(Path::new("/tmp/oifoif/omar"));
+        assert_eq!(provider.ensure_loaded(), false);
+        assert_eq!(provider.degraded, true);
+    }
+
     #[test]
     fn test_system_theme_provider_degraded() {
         let mut provider: ThemeProvider = ThemeProvider::degraded();
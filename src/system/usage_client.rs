@@ -0,0 +1,235 @@
+//! ## UsageClient
+//!
+//! `usage_client` is the module which provides an API to account the bandwidth used with each host
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use crate::config::serialization::{deserialize, serialize, SerializerError, SerializerErrorKind};
+use crate::config::usage::{Usage, UsageStats};
+// Ext
+use chrono::Local;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+/// ## UsageClient
+///
+/// UsageClient provides a high level API to account the bytes transferred with each host, month by month
+pub struct UsageClient {
+    stats: UsageStats,
+    usage_path: PathBuf, // Usage TOML path
+}
+
+impl UsageClient {
+    /// ### new
+    ///
+    /// Instantiates a new `UsageClient`, loading statistics from `usage_path` if it exists
+    pub fn new(usage_path: &Path) -> Result<Self, SerializerError> {
+        info!(
+            "Setting up usage client with usage path {}",
+            usage_path.display()
+        );
+        let mut client: UsageClient = UsageClient {
+            stats: UsageStats::default(),
+            usage_path: usage_path.to_path_buf(),
+        };
+        if usage_path.exists() {
+            if let Err(err) = client.load() {
+                error!("Couldn't read usage file: {}", err);
+                return Err(err);
+            }
+            debug!("Read usage file");
+        }
+        Ok(client)
+    }
+
+    /// ### current_month
+    ///
+    /// Get the current month as `YYYY-MM`, which is the period usage is accounted by
+    pub fn current_month() -> String {
+        Local::now().format("%Y-%m").to_string()
+    }
+
+    /// ### record
+    ///
+    /// Account `uploaded` and `downloaded` bytes to `host` in `month`
+    pub fn record(&mut self, host: &str, month: &str, uploaded: u64, downloaded: u64) {
+        if uploaded == 0 && downloaded == 0 {
+            return;
+        }
+        let usage: &mut Usage = self
+            .stats
+            .hosts
+            .entry(host.to_string())
+            .or_default()
+            .entry(month.to_string())
+            .or_default();
+        usage.uploaded = usage.uploaded.saturating_add(uploaded);
+        usage.downloaded = usage.downloaded.saturating_add(downloaded);
+        debug!(
+            "Accounted {} bytes up, {} bytes down to {} in {}",
+            uploaded, downloaded, host, month
+        );
+    }
+
+    /// ### month_usage
+    ///
+    /// Get the usage of `host` in `month`
+    pub fn month_usage(&self, host: &str, month: &str) -> Usage {
+        self.stats
+            .hosts
+            .get(host)
+            .and_then(|x| x.get(month))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// ### host_usage
+    ///
+    /// Get the usage of `host` month by month, from the most recent month
+    pub fn host_usage(&self, host: &str) -> Vec<(String, Usage)> {
+        self.stats
+            .hosts
+            .get(host)
+            .map(|x| x.iter().rev().map(|(m, u)| (m.clone(), *u)).collect())
+            .unwrap_or_default()
+    }
+
+    /// ### iter_hosts
+    ///
+    /// Iterate over the hosts usage has been accounted to, sorted by host key
+    pub fn iter_hosts(&self) -> impl Iterator<Item = &String> + '_ {
+        let mut hosts: Vec<&String> = self.stats.hosts.keys().collect();
+        hosts.sort();
+        hosts.into_iter()
+    }
+
+    // -- io
+
+    /// ### load
+    ///
+    /// Load usage statistics from file
+    fn load(&mut self) -> Result<(), SerializerError> {
+        match OpenOptions::new()
+            .read(true)
+            .open(self.usage_path.as_path())
+        {
+            Ok(reader) => {
+                self.stats = deserialize(Box::new(reader))?;
+                Ok(())
+            }
+            Err(err) => {
+                error!("Failed to read usage: {}", err);
+                Err(SerializerError::new_ex(
+                    SerializerErrorKind::Io,
+                    err.to_string(),
+                ))
+            }
+        }
+    }
+
+    /// ### write
+    ///
+    /// Write usage statistics to file
+    pub fn write(&self) -> Result<(), SerializerError> {
+        match OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.usage_path.as_path())
+        {
+            Ok(writer) => serialize(&self.stats, Box::new(writer)),
+            Err(err) => {
+                error!("Failed to write usage: {}", err);
+                Err(SerializerError::new_ex(
+                    SerializerErrorKind::Io,
+                    err.to_string(),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_system_usage_client_record() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let usage_path: PathBuf = tmp_dir.path().join("usage.toml");
+        let mut client: UsageClient = UsageClient::new(usage_path.as_path()).unwrap();
+        // File is not created until written
+        assert!(!usage_path.exists());
+        let host: &str = "SFTP://pi@192.168.1.31:22";
+        assert_eq!(client.month_usage(host, "2021-06"), Usage::default());
+        assert!(client.host_usage(host).is_empty());
+        // Record
+        client.record(host, "2021-06", 1024, 0);
+        client.record(host, "2021-06", 0, 4096);
+        client.record(host, "2021-07", 512, 512);
+        client.record("FTP://@10.0.0.1:21", "2021-07", 0, 0);
+        assert_eq!(
+            client.month_usage(host, "2021-06"),
+            Usage {
+                uploaded: 1024,
+                downloaded: 4096,
+            }
+        );
+        // Most recent month first
+        assert_eq!(
+            client
+                .host_usage(host)
+                .iter()
+                .map(|(m, u)| (m.as_str(), u.total()))
+                .collect::<Vec<(&str, u64)>>(),
+            vec![("2021-07", 1024), ("2021-06", 5120)]
+        );
+        // Empty records are not accounted
+        assert_eq!(client.iter_hosts().collect::<Vec<&String>>(), vec![host]);
+        // Write and reload
+        assert!(client.write().is_ok());
+        let client: UsageClient = UsageClient::new(usage_path.as_path()).unwrap();
+        assert_eq!(client.month_usage(host, "2021-07").total(), 1024);
+    }
+
+    #[test]
+    fn test_system_usage_client_bad_file() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let usage_path: PathBuf = tmp_dir.path().join("usage.toml");
+        std::fs::write(usage_path.as_path(), "hosts = 1024\n").unwrap();
+        assert!(UsageClient::new(usage_path.as_path()).is_err());
+    }
+
+    #[test]
+    fn test_system_usage_client_current_month() {
+        let month: String = UsageClient::current_month();
+        assert_eq!(month.len(), 7);
+        assert_eq!(month.chars().nth(4), Some('-'));
+    }
+}
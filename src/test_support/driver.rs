@@ -0,0 +1,248 @@
+//! ## Driver
+//!
+//! `driver` provides a headless driver to run activities with a scripted sequence of input events
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::filetransfer::FileTransferParams;
+use crate::system::config_client::ConfigClient;
+use crate::system::theme_provider::ThemeProvider;
+use crate::test_support::Snapshot;
+use crate::ui::activities::{Activity, ExitReason};
+use crate::ui::context::{Context, HeadlessScreen};
+use crate::ui::input::EventQueue;
+// ext
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use tuirealm::tui::buffer::Buffer;
+use tuirealm::tui::layout::Rect;
+
+/// Ticks to run once the script has been consumed, to let the activity settle
+const IDLE_TICKS: usize = 8;
+/// Default size of the headless terminal
+const TERMINAL_WIDTH: u16 = 160;
+const TERMINAL_HEIGHT: u16 = 48;
+
+/// ## ActivityDriver
+///
+/// ActivityDriver runs an activity on a headless context, feeding it with scripted input events.
+/// Configuration and theme are never read from the user's configuration directory.
+///
+/// ```ignore
+/// let mut driver = ActivityDriver::new(activity, Some(params));
+/// driver.key(KeyCode::Esc).key(KeyCode::Enter);
+/// assert!(matches!(driver.run(), Some(ExitReason::Disconnect)));
+/// ```
+pub struct ActivityDriver<A: Activity> {
+    activity: A,
+    script: EventQueue,
+    screen: HeadlessScreen,
+    destroyed: bool,
+}
+
+impl<A: Activity> ActivityDriver<A> {
+    /// ### new
+    ///
+    /// Create the activity on a headless context. `params` are the file transfer parameters
+    /// stored into the context, as the auth activity would do
+    pub fn new(mut activity: A, params: Option<FileTransferParams>) -> Self {
+        let script: EventQueue = Rc::new(RefCell::new(VecDeque::new()));
+        let screen: HeadlessScreen = Rc::new(RefCell::new(Buffer::empty(Rect::new(
+            0,
+            0,
+            TERMINAL_WIDTH,
+            TERMINAL_HEIGHT,
+        ))));
+        let mut context: Context = Context::headless(
+            ConfigClient::degraded(),
+            ThemeProvider::degraded(),
+            script.clone(),
+            screen.clone(),
+        );
+        if let Some(params) = params {
+            context.set_ftparams(params);
+        }
+        activity.on_create(context);
+        Self {
+            activity,
+            script,
+            screen,
+            destroyed: false,
+        }
+    }
+
+    // -- script
+
+    /// ### event
+    ///
+    /// Enqueue an input event
+    pub fn event(&mut self, ev: Event) -> &mut Self {
+        self.script.borrow_mut().push_back(ev);
+        self
+    }
+
+    /// ### key
+    ///
+    /// Enqueue a key press without modifiers
+    pub fn key(&mut self, code: KeyCode) -> &mut Self {
+        self.key_with(code, KeyModifiers::NONE)
+    }
+
+    /// ### key_with
+    ///
+    /// Enqueue a key press with modifiers
+    pub fn key_with(&mut self, code: KeyCode, modifiers: KeyModifiers) -> &mut Self {
+        self.event(Event::Key(KeyEvent { code, modifiers }))
+    }
+
+    /// ### text
+    ///
+    /// Enqueue a key press for each character in `text`
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        for ch in text.chars() {
+            let modifiers: KeyModifiers = match ch.is_uppercase() {
+                true => KeyModifiers::SHIFT,
+                false => KeyModifiers::NONE,
+            };
+            self.key_with(KeyCode::Char(ch), modifiers);
+        }
+        self
+    }
+
+    /// ### resize
+    ///
+    /// Resize the headless terminal and enqueue the resize event, as a terminal emulator would do.
+    /// The ui is laid out on the new size at the next draw
+    pub fn resize(&mut self, width: u16, height: u16) -> &mut Self {
+        self.screen
+            .borrow_mut()
+            .resize(Rect::new(0, 0, width, height));
+        self.event(Event::Resize(width, height))
+    }
+
+    // -- run
+
+    /// ### tick
+    ///
+    /// Run a single activity tick
+    pub fn tick(&mut self) {
+        self.activity.on_draw();
+    }
+
+    /// ### run
+    ///
+    /// Run the activity until all the enqueued events have been consumed, or until the activity
+    /// wants to be umounted. Returns the exit reason, if any
+    pub fn run(&mut self) -> Option<&ExitReason> {
+        let mut idle: usize = 0;
+        while self.activity.will_umount().is_none() && idle < IDLE_TICKS {
+            if self.script.borrow().is_empty() {
+                idle += 1;
+            }
+            self.tick();
+        }
+        self.activity.will_umount()
+    }
+
+    /// ### exit_reason
+    ///
+    /// Returns the reason why the activity wants to be umounted, if any
+    pub fn exit_reason(&self) -> Option<&ExitReason> {
+        self.activity.will_umount()
+    }
+
+    /// ### snapshot
+    ///
+    /// Take a snapshot of what has been drawn on the headless terminal so far
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::from_buffer(&self.screen.borrow())
+    }
+
+    /// ### activity
+    ///
+    /// Get a reference to the driven activity
+    pub fn activity(&self) -> &A {
+        &self.activity
+    }
+
+    /// ### destroy
+    ///
+    /// Destroy the activity and release the context
+    pub fn destroy(mut self) -> Option<Context> {
+        self.destroyed = true;
+        self.activity.on_destroy()
+    }
+}
+
+impl<A: Activity> Drop for ActivityDriver<A> {
+    fn drop(&mut self) {
+        // Make sure raw mode gets disabled
+        if !self.destroyed {
+            let _ = self.activity.on_destroy();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::filetransfer::FileTransferProtocol;
+    use crate::host::Localhost;
+    use crate::test_support::MockFtpServer;
+    use crate::ui::activities::filetransfer::FileTransferActivity;
+
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_test_support_driver_filetransfer() {
+        let server: MockFtpServer = MockFtpServer::start("omar", "password").unwrap();
+        let local: TempDir = TempDir::new().unwrap();
+        let protocol: FileTransferProtocol = FileTransferProtocol::Ftp(false);
+        let params: FileTransferParams = FileTransferParams::new(server.address())
+            .port(server.port())
+            .protocol(protocol)
+            .username(Some("omar"))
+            .password(Some("password"));
+        let activity: FileTransferActivity = FileTransferActivity::new(
+            Localhost::new(local.path().to_path_buf()).unwrap(),
+            protocol,
+            false,
+        )
+        .unwrap();
+        let mut driver = ActivityDriver::new(activity, Some(params));
+        // Connect
+        assert!(driver.run().is_none());
+        assert!(driver.snapshot().contains("127.0.0.1"));
+        // Dismiss banner
+        assert!(driver.snapshot().contains("termscp mock server ready"));
+        driver.key(KeyCode::Esc);
+        // Disconnect
+        driver.key(KeyCode::Esc).key(KeyCode::Enter);
+        assert!(matches!(driver.run(), Some(ExitReason::Disconnect)));
+        assert!(driver.destroy().is_some());
+    }
+}
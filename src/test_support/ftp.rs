@@ -0,0 +1,522 @@
+//! ## Ftp
+//!
+//! `ftp` provides an in-process FTP server, serving the content of a temporary directory.
+//! There's no equivalent for SFTP and SCP, whose tests still run against the containers
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use tempfile::TempDir;
+
+/// ## MockFtpServer
+///
+/// MockFtpServer is a minimal FTP server running in a background thread.
+/// It only supports passive mode and binary transfers, which is everything `FtpFileTransfer` needs.
+/// Files are served from a temporary directory, which is removed when the server is dropped.
+pub struct MockFtpServer {
+    addr: SocketAddr,
+    root: TempDir,
+    running: Arc<AtomicBool>,
+}
+
+impl MockFtpServer {
+    /// ### start
+    ///
+    /// Start a new server listening on a random port on localhost.
+    /// Only the provided credentials are accepted
+    pub fn start(username: &str, password: &str) -> io::Result<Self> {
+        let root: TempDir = TempDir::new()?;
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0")?;
+        let addr: SocketAddr = listener.local_addr()?;
+        let running: Arc<AtomicBool> = Arc::new(AtomicBool::new(true));
+        let credentials: (String, String) = (username.to_string(), password.to_string());
+        let server_root: PathBuf = root.path().to_path_buf();
+        let server_running: Arc<AtomicBool> = running.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if !server_running.load(Ordering::Relaxed) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let mut session: Session =
+                        Session::new(server_root.clone(), credentials.clone());
+                    thread::spawn(move || {
+                        if let Err(err) = session.run(stream) {
+                            debug!("Mock FTP session terminated: {}", err);
+                        }
+                    });
+                }
+            }
+        });
+        Ok(Self {
+            addr,
+            root,
+            running,
+        })
+    }
+
+    /// ### address
+    ///
+    /// Get the address the server is listening on
+    pub fn address(&self) -> String {
+        self.addr.ip().to_string()
+    }
+
+    /// ### port
+    ///
+    /// Get the port the server is listening on
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// ### root
+    ///
+    /// Get the local path of the directory served as `/`
+    pub fn root(&self) -> &Path {
+        self.root.path()
+    }
+}
+
+impl Drop for MockFtpServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        // Wake up listener
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+/// ## Session
+///
+/// A client session on the mock server
+struct Session {
+    root: PathBuf,
+    credentials: (String, String),
+    username: Option<String>,
+    logged_in: bool,
+    wrkdir: PathBuf,
+    passive: Option<TcpListener>,
+    rename_from: Option<PathBuf>,
+}
+
+impl Session {
+    fn new(root: PathBuf, credentials: (String, String)) -> Self {
+        Self {
+            root,
+            credentials,
+            username: None,
+            logged_in: false,
+            wrkdir: PathBuf::from("/"),
+            passive: None,
+            rename_from: None,
+        }
+    }
+
+    /// ### run
+    ///
+    /// Serve client until it quits or disconnects
+    fn run(&mut self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer: TcpStream = stream;
+        reply(&mut writer, 220, "termscp mock server ready")?;
+        loop {
+            let mut line: String = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            let line: &str = line.trim_end_matches(['\r', '\n']);
+            let (cmd, arg): (String, &str) = match line.find(' ') {
+                Some(idx) => (line[..idx].to_ascii_uppercase(), line[idx + 1..].trim()),
+                None => (line.to_ascii_uppercase(), ""),
+            };
+            if cmd == "QUIT" {
+                return reply(&mut writer, 221, "Goodbye");
+            }
+            self.command(&mut writer, cmd.as_str(), arg)?;
+        }
+    }
+
+    /// ### command
+    ///
+    /// Execute a command and reply to client
+    fn command(&mut self, w: &mut TcpStream, cmd: &str, arg: &str) -> io::Result<()> {
+        match cmd {
+            "USER" => {
+                self.username = Some(arg.to_string());
+                self.logged_in = false;
+                reply(w, 331, "Password required")
+            }
+            "PASS" => {
+                let (username, password) = &self.credentials;
+                self.logged_in =
+                    self.username.as_deref() == Some(username.as_str()) && arg == password;
+                match self.logged_in {
+                    true => reply(w, 230, "Logged in"),
+                    false => reply(w, 530, "Login incorrect"),
+                }
+            }
+            "SYST" => reply(w, 215, "UNIX Type: L8"),
+            "NOOP" => reply(w, 200, "OK"),
+            "FEAT" => reply(w, 502, "Not implemented"),
+            _ if !self.logged_in => reply(w, 530, "Not logged in"),
+            "TYPE" => reply(w, 200, "Type set"),
+            "PWD" => reply(
+                w,
+                257,
+                format!("\"{}\" is the current directory", self.wrkdir.display()).as_str(),
+            ),
+            "CWD" => self.change_dir(w, self.virtual_path(arg)),
+            "CDUP" => self.change_dir(w, self.virtual_path("..")),
+            "PASV" => {
+                let listener: TcpListener = TcpListener::bind("127.0.0.1:0")?;
+                let port: u16 = listener.local_addr()?.port();
+                self.passive = Some(listener);
+                reply(
+                    w,
+                    227,
+                    format!(
+                        "Entering Passive Mode (127,0,0,1,{},{})",
+                        port >> 8,
+                        port & 0xff
+                    )
+                    .as_str(),
+                )
+            }
+            "LIST" | "NLST" => {
+                // Ignore flags
+                let path: &str = match arg.starts_with('-') {
+                    true => arg.split_once(' ').map(|x| x.1).unwrap_or(""),
+                    false => arg,
+                };
+                let listing: String = match self.list(self.virtual_path(path).as_path(), cmd) {
+                    Ok(listing) => listing,
+                    Err(err) => return reply(w, 550, err.to_string().as_str()),
+                };
+                self.transfer(w, |data| data.write_all(listing.as_bytes()))
+            }
+            "RETR" => match fs::read(self.local_path(self.virtual_path(arg).as_path())) {
+                Ok(content) => self.transfer(w, |data| data.write_all(content.as_slice())),
+                Err(err) => reply(w, 550, err.to_string().as_str()),
+            },
+            "STOR" => {
+                let p: PathBuf = self.local_path(self.virtual_path(arg).as_path());
+                // File must be written before the transfer is reported as complete
+                self.transfer(w, |data| {
+                    let mut content: Vec<u8> = Vec::new();
+                    data.read_to_end(&mut content)?;
+                    fs::write(p, content)
+                })
+            }
+            "SIZE" => match fs::metadata(self.local_path(self.virtual_path(arg).as_path())) {
+                Ok(meta) if meta.is_file() => reply(w, 213, meta.len().to_string().as_str()),
+                Ok(_) => reply(w, 550, "Not a file"),
+                Err(err) => reply(w, 550, err.to_string().as_str()),
+            },
+            "MKD" => {
+                let p: PathBuf = self.virtual_path(arg);
+                match fs::create_dir(self.local_path(p.as_path())) {
+                    Ok(_) => reply(w, 257, format!("\"{}\" created", p.display()).as_str()),
+                    Err(err) => reply(w, 550, err.to_string().as_str()),
+                }
+            }
+            "RMD" => match fs::remove_dir(self.local_path(self.virtual_path(arg).as_path())) {
+                Ok(_) => reply(w, 250, "Directory removed"),
+                Err(err) => reply(w, 550, err.to_string().as_str()),
+            },
+            "DELE" => match fs::remove_file(self.local_path(self.virtual_path(arg).as_path())) {
+                Ok(_) => reply(w, 250, "File removed"),
+                Err(err) => reply(w, 550, err.to_string().as_str()),
+            },
+            "RNFR" => {
+                let p: PathBuf = self.virtual_path(arg);
+                match self.local_path(p.as_path()).exists() {
+                    true => {
+                        self.rename_from = Some(p);
+                        reply(w, 350, "Ready for destination name")
+                    }
+                    false => reply(w, 550, "No such file or directory"),
+                }
+            }
+            "RNTO" => match self.rename_from.take() {
+                Some(src) => {
+                    let src: PathBuf = self.local_path(src.as_path());
+                    let dst: PathBuf = self.local_path(self.virtual_path(arg).as_path());
+                    match fs::rename(src, dst) {
+                        Ok(_) => reply(w, 250, "Renamed"),
+                        Err(err) => reply(w, 550, err.to_string().as_str()),
+                    }
+                }
+                None => reply(w, 503, "RNFR required first"),
+            },
+            _ => reply(w, 502, "Command not implemented"),
+        }
+    }
+
+    /// ### change_dir
+    ///
+    /// Change working directory to `p`, if it exists
+    fn change_dir(&mut self, w: &mut TcpStream, p: PathBuf) -> io::Result<()> {
+        match self.local_path(p.as_path()).is_dir() {
+            true => {
+                self.wrkdir = p;
+                reply(w, 250, "Directory changed")
+            }
+            false => reply(w, 550, "No such directory"),
+        }
+    }
+
+    /// ### transfer
+    ///
+    /// Open the passive data connection and run `f` on it
+    fn transfer<F>(&mut self, w: &mut TcpStream, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut TcpStream) -> io::Result<()>,
+    {
+        let listener: TcpListener = match self.passive.take() {
+            Some(l) => l,
+            None => return reply(w, 425, "Use PASV first"),
+        };
+        reply(w, 150, "Opening data connection")?;
+        let (mut data, _) = listener.accept()?;
+        let result: io::Result<()> = f(&mut data);
+        let _ = data.shutdown(Shutdown::Both);
+        drop(data);
+        match result {
+            Ok(_) => reply(w, 226, "Transfer complete"),
+            Err(err) => reply(w, 426, err.to_string().as_str()),
+        }
+    }
+
+    /// ### list
+    ///
+    /// List entries at `p` with unix syntax (or just names for NLST)
+    fn list(&self, p: &Path, cmd: &str) -> io::Result<String> {
+        let local: PathBuf = self.local_path(p);
+        let mut entries: Vec<(String, fs::Metadata)> = match local.is_dir() {
+            true => fs::read_dir(local.as_path())?
+                .flatten()
+                .filter_map(|x| {
+                    x.metadata()
+                        .ok()
+                        .map(|m| (x.file_name().to_string_lossy().to_string(), m))
+                })
+                .collect(),
+            false => vec![(
+                p.file_name()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                fs::metadata(local.as_path())?,
+            )],
+        };
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries
+            .iter()
+            .map(|(name, meta)| match cmd {
+                "NLST" => format!("{}\r\n", name),
+                _ => format!("{} {}\r\n", fmt_list_meta(meta), name),
+            })
+            .collect())
+    }
+
+    /// ### virtual_path
+    ///
+    /// Resolve `p` against the working directory. The result never escapes `/`
+    fn virtual_path(&self, p: &str) -> PathBuf {
+        let mut path: PathBuf = PathBuf::from("/");
+        for component in self.wrkdir.join(p).components() {
+            match component {
+                Component::Normal(name) => path.push(name),
+                Component::ParentDir => {
+                    path.pop();
+                }
+                _ => {}
+            }
+        }
+        path
+    }
+
+    /// ### local_path
+    ///
+    /// Get local path for virtual path `p`
+    fn local_path(&self, p: &Path) -> PathBuf {
+        self.root.join(p.strip_prefix("/").unwrap_or(p))
+    }
+}
+
+/// ### reply
+///
+/// Send reply to client
+fn reply(w: &mut TcpStream, code: u32, msg: &str) -> io::Result<()> {
+    write!(w, "{} {}\r\n", code, msg)?;
+    w.flush()
+}
+
+/// ### fmt_list_meta
+///
+/// Format metadata as in `ls -l` output (without name)
+fn fmt_list_meta(meta: &fs::Metadata) -> String {
+    let mtime: DateTime<Utc> = meta
+        .modified()
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now());
+    format!(
+        "{}{} 1 termscp termscp {} {}",
+        match meta.is_dir() {
+            true => 'd',
+            false => '-',
+        },
+        fmt_list_pex(meta),
+        meta.len(),
+        mtime.format("%b %d %Y")
+    )
+}
+
+#[cfg(target_family = "unix")]
+fn fmt_list_pex(meta: &fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    let mode: u32 = meta.permissions().mode();
+    (0..9)
+        .map(|i| match mode & (0o400 >> i) != 0 {
+            true => ['r', 'w', 'x'][i % 3],
+            false => '-',
+        })
+        .collect()
+}
+
+#[cfg(not(target_family = "unix"))]
+fn fmt_list_pex(meta: &fs::Metadata) -> String {
+    match (meta.is_dir(), meta.permissions().readonly()) {
+        (true, _) => String::from("rwxr-xr-x"),
+        (false, true) => String::from("r--r--r--"),
+        (false, false) => String::from("rw-r--r--"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::filetransfer::ftp_transfer::FtpFileTransfer;
+    use crate::filetransfer::{FileTransfer, FileTransferErrorType};
+    use crate::fs::{FsEntry, FsFile};
+    use crate::utils::test_helpers::make_fsentry;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_test_support_ftp_server() {
+        let server: MockFtpServer = MockFtpServer::start("omar", "password").unwrap();
+        let mut client: FtpFileTransfer = FtpFileTransfer::new(false);
+        assert!(client
+            .connect(
+                server.address(),
+                server.port(),
+                Some(String::from("omar")),
+                Some(String::from("password")),
+            )
+            .is_ok());
+        assert_eq!(client.pwd().unwrap(), PathBuf::from("/"));
+        assert_eq!(client.list_dir(Path::new("/")).unwrap().len(), 0);
+        // Make directory and enter it
+        assert!(client.mkdir(Path::new("/home")).is_ok());
+        assert_eq!(
+            client.change_dir(Path::new("/home")).unwrap(),
+            PathBuf::from("/home")
+        );
+        // Upload file
+        let file: FsFile = make_fsentry(PathBuf::from("/tmp/omar.txt"), false).unwrap_file();
+        let mut writer = client
+            .send_file(&file, Path::new("/home/omar.txt"))
+            .unwrap();
+        assert!(writer.write_all(b"Hello, world!").is_ok());
+        assert!(client.on_sent(writer).is_ok());
+        assert_eq!(
+            fs::read(server.root().join("home/omar.txt")).unwrap(),
+            b"Hello, world!".to_vec()
+        );
+        // List directory
+        let files: Vec<FsEntry> = client.list_dir(Path::new("/home")).unwrap();
+        assert_eq!(files.len(), 1);
+        let remote = files.first().unwrap().clone().unwrap_file();
+        assert_eq!(remote.name.as_str(), "omar.txt");
+        assert_eq!(remote.abs_path, PathBuf::from("/home/omar.txt"));
+        assert_eq!(remote.size, 13);
+        // Download file
+        let mut reader = client.recv_file(&remote).unwrap();
+        let mut content: String = String::new();
+        assert!(reader.read_to_string(&mut content).is_ok());
+        assert!(client.on_recv(reader).is_ok());
+        assert_eq!(content.as_str(), "Hello, world!");
+        // Rename and remove
+        assert!(client
+            .rename(&FsEntry::File(remote), Path::new("/home/pippo.txt"))
+            .is_ok());
+        assert!(server.root().join("home/pippo.txt").exists());
+        // Leave the directory before removing it
+        assert!(client.change_dir(Path::new("/")).is_ok());
+        let entry: FsEntry = client.list_dir(Path::new("/")).unwrap().remove(0);
+        assert!(client.remove(&entry).is_ok());
+        assert!(!server.root().join("home").exists());
+        assert!(client.disconnect().is_ok());
+    }
+
+    #[test]
+    fn test_test_support_ftp_server_bad_auth() {
+        let server: MockFtpServer = MockFtpServer::start("omar", "password").unwrap();
+        let mut client: FtpFileTransfer = FtpFileTransfer::new(false);
+        assert_eq!(
+            client
+                .connect(
+                    server.address(),
+                    server.port(),
+                    Some(String::from("omar")),
+                    Some(String::from("wrong")),
+                )
+                .err()
+                .unwrap()
+                .kind(),
+            FileTransferErrorType::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_test_support_ftp_server_chroot() {
+        let server: MockFtpServer = MockFtpServer::start("omar", "password").unwrap();
+        let session: Session = Session::new(
+            server.root().to_path_buf(),
+            (String::from("omar"), String::from("password")),
+        );
+        assert_eq!(session.virtual_path("../../etc"), PathBuf::from("/etc"));
+        assert_eq!(
+            session.local_path(Path::new("/etc")),
+            server.root().join("etc")
+        );
+    }
+}
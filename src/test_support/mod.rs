@@ -0,0 +1,44 @@
+//! ## TestSupport
+//!
+//! `test_support` provides the tools to write end-to-end tests for termscp: in-process
+//! FTP and SFTP servers, a headless driver for activities and snapshots of what activities draw.
+//!
+//! The SFTP server can't execute commands, so SCP and the features relying on the remote shell
+//! still require the containers (`with-containers` feature)
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// modules
+pub mod driver;
+pub mod ftp;
+#[cfg(target_family = "unix")]
+pub mod sftp;
+pub mod snapshot;
+
+// export
+pub use driver::ActivityDriver;
+pub use ftp::MockFtpServer;
+#[cfg(target_family = "unix")]
+pub use sftp::MockSftpServer;
+pub use snapshot::Snapshot;
@@ -0,0 +1,1365 @@
+//! ## Sftp
+//!
+//! `sftp` provides an in-process SFTP server to test the SFTP file transfer without containers
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use openssl::bn::{BigNum, BigNumContext, BigNumRef, MsbOption};
+use openssl::hash::{hash, MessageDigest};
+use openssl::pkey::{PKey, Private};
+use openssl::rand::rand_bytes;
+use openssl::rsa::Rsa;
+use openssl::sign::Signer;
+use openssl::symm::{Cipher, Crypter, Mode};
+use std::collections::HashMap;
+use std::fs::{self, File, FileTimes, Metadata, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+use tempfile::TempDir;
+
+/// Algorithms offered by the server; these are the only ones implemented
+const KEX_ALGORITHM: &str = "diffie-hellman-group14-sha256";
+const HOST_KEY_ALGORITHM: &str = "ssh-rsa";
+const CIPHER: &str = "aes128-ctr";
+const MAC: &str = "hmac-sha2-256";
+/// Oakley group 14 (RFC 3526), whose generator is 2
+const GROUP14_PRIME: &str = "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7EDEE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3BE39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF6955817183995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF";
+/// Window and packet size of the channels opened by the client
+const WINDOW_SIZE: u32 = 2097152;
+const MAX_PACKET_SIZE: u32 = 32768;
+/// Largest chunk returned by a single read request
+const MAX_READ_SIZE: u32 = 65536;
+
+// Transport, authentication and connection messages
+const MSG_DISCONNECT: u8 = 1;
+const MSG_IGNORE: u8 = 2;
+const MSG_UNIMPLEMENTED: u8 = 3;
+const MSG_DEBUG: u8 = 4;
+const MSG_SERVICE_REQUEST: u8 = 5;
+const MSG_SERVICE_ACCEPT: u8 = 6;
+const MSG_KEXINIT: u8 = 20;
+const MSG_NEWKEYS: u8 = 21;
+const MSG_KEXDH_INIT: u8 = 30;
+const MSG_KEXDH_REPLY: u8 = 31;
+const MSG_USERAUTH_REQUEST: u8 = 50;
+const MSG_USERAUTH_FAILURE: u8 = 51;
+const MSG_USERAUTH_SUCCESS: u8 = 52;
+const MSG_GLOBAL_REQUEST: u8 = 80;
+const MSG_REQUEST_FAILURE: u8 = 82;
+const MSG_CHANNEL_OPEN: u8 = 90;
+const MSG_CHANNEL_OPEN_CONFIRMATION: u8 = 91;
+const MSG_CHANNEL_OPEN_FAILURE: u8 = 92;
+const MSG_CHANNEL_WINDOW_ADJUST: u8 = 93;
+const MSG_CHANNEL_DATA: u8 = 94;
+const MSG_CHANNEL_EOF: u8 = 96;
+const MSG_CHANNEL_CLOSE: u8 = 97;
+const MSG_CHANNEL_REQUEST: u8 = 98;
+const MSG_CHANNEL_SUCCESS: u8 = 99;
+const MSG_CHANNEL_FAILURE: u8 = 100;
+
+// SFTP (version 3) packets
+const FXP_INIT: u8 = 1;
+const FXP_VERSION: u8 = 2;
+const FXP_OPEN: u8 = 3;
+const FXP_CLOSE: u8 = 4;
+const FXP_READ: u8 = 5;
+const FXP_WRITE: u8 = 6;
+const FXP_LSTAT: u8 = 7;
+const FXP_FSTAT: u8 = 8;
+const FXP_SETSTAT: u8 = 9;
+const FXP_FSETSTAT: u8 = 10;
+const FXP_OPENDIR: u8 = 11;
+const FXP_READDIR: u8 = 12;
+const FXP_REMOVE: u8 = 13;
+const FXP_MKDIR: u8 = 14;
+const FXP_RMDIR: u8 = 15;
+const FXP_REALPATH: u8 = 16;
+const FXP_STAT: u8 = 17;
+const FXP_RENAME: u8 = 18;
+const FXP_READLINK: u8 = 19;
+const FXP_SYMLINK: u8 = 20;
+const FXP_STATUS: u8 = 101;
+const FXP_HANDLE: u8 = 102;
+const FXP_DATA: u8 = 103;
+const FXP_NAME: u8 = 104;
+const FXP_ATTRS: u8 = 105;
+
+// SFTP open flags, attributes and status codes
+const FXF_READ: u32 = 0x01;
+const FXF_WRITE: u32 = 0x02;
+const FXF_APPEND: u32 = 0x04;
+const FXF_CREAT: u32 = 0x08;
+const FXF_TRUNC: u32 = 0x10;
+const FXF_EXCL: u32 = 0x20;
+const ATTR_SIZE: u32 = 0x01;
+const ATTR_UIDGID: u32 = 0x02;
+const ATTR_PERMISSIONS: u32 = 0x04;
+const ATTR_ACMODTIME: u32 = 0x08;
+const ATTR_EXTENDED: u32 = 0x8000_0000;
+const FX_OK: u32 = 0;
+const FX_EOF: u32 = 1;
+const FX_NO_SUCH_FILE: u32 = 2;
+const FX_PERMISSION_DENIED: u32 = 3;
+const FX_FAILURE: u32 = 4;
+const FX_OP_UNSUPPORTED: u32 = 8;
+
+/// ## MockSftpServer
+///
+/// MockSftpServer is a minimal SSH server running in a background thread, which only provides the SFTP subsystem.
+/// It implements a single set of algorithms, supported by libssh2, and password authentication only;
+/// commands can't be executed, so features relying on the remote shell aren't available.
+/// Files are served from a temporary directory, which is removed when the server is dropped.
+pub struct MockSftpServer {
+    addr: SocketAddr,
+    root: TempDir,
+    running: Arc<AtomicBool>,
+}
+
+impl MockSftpServer {
+    /// ### start
+    ///
+    /// Start a new server listening on a random port on localhost, with a new host key.
+    /// Only the provided credentials are accepted
+    pub fn start(username: &str, password: &str) -> io::Result<Self> {
+        let host_key: PKey<Private> = Rsa::generate(2048)
+            .and_then(PKey::from_rsa)
+            .map_err(invalid_data)?;
+        let root: TempDir = TempDir::new()?;
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0")?;
+        let addr: SocketAddr = listener.local_addr()?;
+        let running: Arc<AtomicBool> = Arc::new(AtomicBool::new(true));
+        let credentials: (String, String) = (username.to_string(), password.to_string());
+        let server_root: PathBuf = root.path().to_path_buf();
+        let server_running: Arc<AtomicBool> = running.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if !server_running.load(Ordering::Relaxed) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let mut session: Session =
+                        Session::new(server_root.clone(), credentials.clone(), host_key.clone());
+                    thread::spawn(move || {
+                        if let Err(err) = session.run(stream) {
+                            debug!("Mock SFTP session terminated: {}", err);
+                        }
+                    });
+                }
+            }
+        });
+        Ok(Self {
+            addr,
+            root,
+            running,
+        })
+    }
+
+    /// ### address
+    ///
+    /// Get the address the server is listening on
+    pub fn address(&self) -> String {
+        self.addr.ip().to_string()
+    }
+
+    /// ### port
+    ///
+    /// Get the port the server is listening on
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// ### root
+    ///
+    /// Get the local path of the directory served as `/`
+    pub fn root(&self) -> &Path {
+        self.root.path()
+    }
+}
+
+impl Drop for MockSftpServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        // Wake up listener
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+/// ## Session
+///
+/// A client connection to the mock server
+struct Session {
+    credentials: (String, String),
+    host_key: PKey<Private>,
+    authenticated: bool,
+    channels: HashMap<u32, Channel>,
+    next_channel: u32,
+    sftp: Sftp,
+}
+
+impl Session {
+    fn new(root: PathBuf, credentials: (String, String), host_key: PKey<Private>) -> Self {
+        Self {
+            credentials,
+            host_key,
+            authenticated: false,
+            channels: HashMap::new(),
+            next_channel: 0,
+            sftp: Sftp::new(root),
+        }
+    }
+
+    /// ### run
+    ///
+    /// Serve client until it disconnects
+    fn run(&mut self, stream: TcpStream) -> io::Result<()> {
+        let mut transport: Transport = Transport::new(stream)?;
+        let client_version: String = transport.exchange_versions()?;
+        self.key_exchange(&mut transport, client_version.as_str())?;
+        loop {
+            let payload: Vec<u8> = transport.read_packet()?;
+            let mut d: Decoder = Decoder::new(payload.as_slice());
+            match d.u8()? {
+                MSG_DISCONNECT => return Ok(()),
+                MSG_IGNORE | MSG_DEBUG | MSG_UNIMPLEMENTED => {}
+                MSG_SERVICE_REQUEST => {
+                    let mut reply: Vec<u8> = vec![MSG_SERVICE_ACCEPT];
+                    reply.put_string(d.string()?);
+                    transport.write_packet(reply.as_slice())?;
+                }
+                MSG_USERAUTH_REQUEST => self.authenticate(&mut transport, &mut d)?,
+                MSG_GLOBAL_REQUEST => {
+                    let _name: String = d.str()?;
+                    if d.bool()? {
+                        transport.write_packet(&[MSG_REQUEST_FAILURE])?;
+                    }
+                }
+                _ if !self.authenticated => {
+                    return Err(invalid_data("Request before authentication"));
+                }
+                MSG_CHANNEL_OPEN => self.open_channel(&mut transport, &mut d)?,
+                MSG_CHANNEL_REQUEST => self.channel_request(&mut transport, &mut d)?,
+                MSG_CHANNEL_DATA => self.channel_data(&mut transport, &mut d)?,
+                MSG_CHANNEL_WINDOW_ADJUST => {
+                    let id: u32 = d.u32()?;
+                    let bytes: u32 = d.u32()?;
+                    if let Some(channel) = self.channels.get_mut(&id) {
+                        channel.window = channel.window.saturating_add(bytes);
+                        channel.flush(&mut transport)?;
+                    }
+                }
+                MSG_CHANNEL_EOF => {
+                    // The subsystem ends once the client has nothing more to send
+                    if let Some(channel) = self.channels.get_mut(&d.u32()?) {
+                        channel.close(&mut transport)?;
+                    }
+                }
+                MSG_CHANNEL_CLOSE => {
+                    if let Some(mut channel) = self.channels.remove(&d.u32()?) {
+                        channel.close(&mut transport)?;
+                    }
+                }
+                _ => {
+                    let mut reply: Vec<u8> = vec![MSG_UNIMPLEMENTED];
+                    reply.put_u32(transport.seq_in.wrapping_sub(1));
+                    transport.write_packet(reply.as_slice())?;
+                }
+            }
+        }
+    }
+
+    /// ### key_exchange
+    ///
+    /// Run the initial key exchange and enable encryption
+    fn key_exchange(&self, transport: &mut Transport, client_version: &str) -> io::Result<()> {
+        // Send and receive algorithms
+        let mut server_kexinit: Vec<u8> = vec![MSG_KEXINIT];
+        server_kexinit.extend_from_slice(random_bytes(16)?.as_slice());
+        for list in [
+            KEX_ALGORITHM,
+            HOST_KEY_ALGORITHM,
+            CIPHER,
+            CIPHER,
+            MAC,
+            MAC,
+            "none",
+            "none",
+            "",
+            "",
+        ]
+        .iter()
+        {
+            server_kexinit.put_string(list.as_bytes());
+        }
+        server_kexinit.put_bool(false);
+        server_kexinit.put_u32(0);
+        transport.write_packet(server_kexinit.as_slice())?;
+        let client_kexinit: Vec<u8> = transport.expect_packet(MSG_KEXINIT)?;
+        // Diffie-Hellman
+        let kexdh_init: Vec<u8> = transport.expect_packet(MSG_KEXDH_INIT)?;
+        let e: BigNum = Decoder::new(&kexdh_init[1..]).mpint()?;
+        let mut ctx: BigNumContext = BigNumContext::new().map_err(invalid_data)?;
+        let p: BigNum = BigNum::from_hex_str(GROUP14_PRIME).map_err(invalid_data)?;
+        let g: BigNum = BigNum::from_u32(2).map_err(invalid_data)?;
+        let mut y: BigNum = BigNum::new().map_err(invalid_data)?;
+        y.rand(512, MsbOption::MAYBE_ZERO, false)
+            .map_err(invalid_data)?;
+        let mut f: BigNum = BigNum::new().map_err(invalid_data)?;
+        f.mod_exp(&g, &y, &p, &mut ctx).map_err(invalid_data)?;
+        let mut k: BigNum = BigNum::new().map_err(invalid_data)?;
+        k.mod_exp(&e, &y, &p, &mut ctx).map_err(invalid_data)?;
+        // Exchange hash, signed with the host key
+        let rsa = self.host_key.rsa().map_err(invalid_data)?;
+        let mut host_key: Vec<u8> = Vec::new();
+        host_key.put_string(HOST_KEY_ALGORITHM.as_bytes());
+        host_key.put_mpint(rsa.e());
+        host_key.put_mpint(rsa.n());
+        let mut exchange: Vec<u8> = Vec::new();
+        exchange.put_string(client_version.as_bytes());
+        exchange.put_string(transport.version.as_bytes());
+        exchange.put_string(client_kexinit.as_slice());
+        exchange.put_string(server_kexinit.as_slice());
+        exchange.put_string(host_key.as_slice());
+        exchange.put_mpint(&e);
+        exchange.put_mpint(&f);
+        exchange.put_mpint(&k);
+        let h: Vec<u8> = sha256(exchange.as_slice())?;
+        let mut signer: Signer =
+            Signer::new(MessageDigest::sha1(), &self.host_key).map_err(invalid_data)?;
+        signer.update(h.as_slice()).map_err(invalid_data)?;
+        let mut signature: Vec<u8> = Vec::new();
+        signature.put_string(HOST_KEY_ALGORITHM.as_bytes());
+        signature.put_string(signer.sign_to_vec().map_err(invalid_data)?.as_slice());
+        let mut reply: Vec<u8> = vec![MSG_KEXDH_REPLY];
+        reply.put_string(host_key.as_slice());
+        reply.put_mpint(&f);
+        reply.put_string(signature.as_slice());
+        transport.write_packet(reply.as_slice())?;
+        // Switch to the new keys; the exchange hash is the session id too
+        transport.write_packet(&[MSG_NEWKEYS])?;
+        transport.expect_packet(MSG_NEWKEYS)?;
+        let derive = |x: u8, len: usize| -> io::Result<Vec<u8>> {
+            let mut data: Vec<u8> = Vec::new();
+            data.put_mpint(&k);
+            data.extend_from_slice(h.as_slice());
+            data.push(x);
+            data.extend_from_slice(h.as_slice());
+            Ok(sha256(data.as_slice())?[..len].to_vec())
+        };
+        transport.keys_in = Some(Keys::new(
+            Mode::Decrypt,
+            derive(b'C', 16)?.as_slice(),
+            derive(b'A', 16)?.as_slice(),
+            derive(b'E', 32)?,
+        )?);
+        transport.keys_out = Some(Keys::new(
+            Mode::Encrypt,
+            derive(b'D', 16)?.as_slice(),
+            derive(b'B', 16)?.as_slice(),
+            derive(b'F', 32)?,
+        )?);
+        Ok(())
+    }
+
+    /// ### authenticate
+    ///
+    /// Handle an authentication request; only passwords are accepted
+    fn authenticate(&mut self, transport: &mut Transport, d: &mut Decoder) -> io::Result<()> {
+        let username: String = d.str()?;
+        let _service: String = d.str()?;
+        let method: String = d.str()?;
+        let (expected_username, expected_password) = &self.credentials;
+        if method == "password" {
+            let _change: bool = d.bool()?;
+            let password: String = d.str()?;
+            self.authenticated = &username == expected_username && &password == expected_password;
+        }
+        match self.authenticated {
+            true => transport.write_packet(&[MSG_USERAUTH_SUCCESS]),
+            false => {
+                let mut reply: Vec<u8> = vec![MSG_USERAUTH_FAILURE];
+                reply.put_string(b"password");
+                reply.put_bool(false);
+                transport.write_packet(reply.as_slice())
+            }
+        }
+    }
+
+    /// ### open_channel
+    ///
+    /// Open a session channel
+    fn open_channel(&mut self, transport: &mut Transport, d: &mut Decoder) -> io::Result<()> {
+        let kind: String = d.str()?;
+        let remote_id: u32 = d.u32()?;
+        let window: u32 = d.u32()?;
+        let max_packet: u32 = d.u32()?;
+        if kind != "session" {
+            let mut reply: Vec<u8> = vec![MSG_CHANNEL_OPEN_FAILURE];
+            reply.put_u32(remote_id);
+            reply.put_u32(3); // Unknown channel type
+            reply.put_string(b"Unsupported channel type");
+            reply.put_string(b"");
+            return transport.write_packet(reply.as_slice());
+        }
+        let id: u32 = self.next_channel;
+        self.next_channel += 1;
+        self.channels.insert(
+            id,
+            Channel {
+                remote_id,
+                window,
+                max_packet: max_packet.min(MAX_PACKET_SIZE),
+                sftp: false,
+                closed: false,
+                input: Vec::new(),
+                output: Vec::new(),
+            },
+        );
+        let mut reply: Vec<u8> = vec![MSG_CHANNEL_OPEN_CONFIRMATION];
+        reply.put_u32(remote_id);
+        reply.put_u32(id);
+        reply.put_u32(WINDOW_SIZE);
+        reply.put_u32(MAX_PACKET_SIZE);
+        transport.write_packet(reply.as_slice())
+    }
+
+    /// ### channel_request
+    ///
+    /// Handle a channel request; only the SFTP subsystem can be started
+    fn channel_request(&mut self, transport: &mut Transport, d: &mut Decoder) -> io::Result<()> {
+        let id: u32 = d.u32()?;
+        let kind: String = d.str()?;
+        let want_reply: bool = d.bool()?;
+        let channel: &mut Channel = match self.channels.get_mut(&id) {
+            Some(channel) => channel,
+            None => return Err(invalid_data("No such channel")),
+        };
+        let success: bool = kind == "subsystem" && d.str()? == "sftp";
+        if success {
+            channel.sftp = true;
+        }
+        if want_reply {
+            let mut reply: Vec<u8> = vec![match success {
+                true => MSG_CHANNEL_SUCCESS,
+                false => MSG_CHANNEL_FAILURE,
+            }];
+            reply.put_u32(channel.remote_id);
+            transport.write_packet(reply.as_slice())?;
+        }
+        Ok(())
+    }
+
+    /// ### channel_data
+    ///
+    /// Handle data sent to a channel, serving the SFTP requests it completes
+    fn channel_data(&mut self, transport: &mut Transport, d: &mut Decoder) -> io::Result<()> {
+        let id: u32 = d.u32()?;
+        let data: &[u8] = d.string()?;
+        let channel: &mut Channel = match self.channels.get_mut(&id) {
+            Some(channel) if channel.sftp => channel,
+            _ => return Err(invalid_data("No such channel")),
+        };
+        channel.input.extend_from_slice(data);
+        // Data is consumed at once: give the window back
+        let mut adjust: Vec<u8> = vec![MSG_CHANNEL_WINDOW_ADJUST];
+        adjust.put_u32(channel.remote_id);
+        adjust.put_u32(data.len() as u32);
+        transport.write_packet(adjust.as_slice())?;
+        while channel.input.len() >= 4 {
+            let len: usize = Decoder::new(&channel.input[..4]).u32()? as usize;
+            if channel.input.len() < 4 + len {
+                break;
+            }
+            let request: Vec<u8> = channel.input.drain(..4 + len).skip(4).collect();
+            let response: Vec<u8> = self.sftp.serve(request.as_slice())?;
+            channel.output.put_string(response.as_slice());
+        }
+        channel.flush(transport)
+    }
+}
+
+/// ## Channel
+///
+/// A channel opened by the client
+struct Channel {
+    remote_id: u32,
+    window: u32,
+    max_packet: u32,
+    sftp: bool,
+    closed: bool,
+    input: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl Channel {
+    /// ### close
+    ///
+    /// Close the channel, unless already closed
+    fn close(&mut self, transport: &mut Transport) -> io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+        for kind in [MSG_CHANNEL_EOF, MSG_CHANNEL_CLOSE].iter() {
+            let mut packet: Vec<u8> = vec![*kind];
+            packet.put_u32(self.remote_id);
+            transport.write_packet(packet.as_slice())?;
+        }
+        Ok(())
+    }
+
+    /// ### flush
+    ///
+    /// Send as much pending output as the client window allows
+    fn flush(&mut self, transport: &mut Transport) -> io::Result<()> {
+        while !self.output.is_empty() && self.window > 0 {
+            let len: usize = self
+                .output
+                .len()
+                .min(self.window as usize)
+                .min(self.max_packet as usize - 64);
+            let mut packet: Vec<u8> = vec![MSG_CHANNEL_DATA];
+            packet.put_u32(self.remote_id);
+            packet.put_string(&self.output[..len]);
+            transport.write_packet(packet.as_slice())?;
+            self.output.drain(..len);
+            self.window -= len as u32;
+        }
+        Ok(())
+    }
+}
+
+/// ## Handle
+///
+/// A file or directory opened by the client
+enum Handle {
+    File(File, PathBuf),
+    Dir(Vec<(String, Metadata)>),
+}
+
+/// ## Sftp
+///
+/// The SFTP subsystem, serving requests with the files in `root`
+struct Sftp {
+    root: PathBuf,
+    handles: HashMap<u32, Handle>,
+    next_handle: u32,
+}
+
+impl Sftp {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            handles: HashMap::new(),
+            next_handle: 0,
+        }
+    }
+
+    /// ### serve
+    ///
+    /// Serve a request and get the response
+    fn serve(&mut self, request: &[u8]) -> io::Result<Vec<u8>> {
+        let mut d: Decoder = Decoder::new(request);
+        let kind: u8 = d.u8()?;
+        if kind == FXP_INIT {
+            let mut response: Vec<u8> = vec![FXP_VERSION];
+            response.put_u32(3);
+            return Ok(response);
+        }
+        let id: u32 = d.u32()?;
+        let mut response: Vec<u8> = Vec::new();
+        match self.request(kind, &mut d) {
+            Ok(Reply::Status(code)) => status(&mut response, id, code, ""),
+            Ok(Reply::Handle(handle)) => {
+                response.push(FXP_HANDLE);
+                response.put_u32(id);
+                response.put_string(handle.to_string().as_bytes());
+            }
+            Ok(Reply::Data(data)) => {
+                response.push(FXP_DATA);
+                response.put_u32(id);
+                response.put_string(data.as_slice());
+            }
+            Ok(Reply::Name(names)) => {
+                response.push(FXP_NAME);
+                response.put_u32(id);
+                response.put_u32(names.len() as u32);
+                for (name, meta) in names.iter() {
+                    response.put_string(name.as_bytes());
+                    response.put_string(name.as_bytes());
+                    put_attrs(&mut response, meta.as_ref());
+                }
+            }
+            Ok(Reply::Attrs(meta)) => {
+                response.push(FXP_ATTRS);
+                response.put_u32(id);
+                put_attrs(&mut response, Some(&meta));
+            }
+            Err(err) => {
+                let code: u32 = match err.kind() {
+                    io::ErrorKind::NotFound => FX_NO_SUCH_FILE,
+                    io::ErrorKind::PermissionDenied => FX_PERMISSION_DENIED,
+                    io::ErrorKind::Unsupported => FX_OP_UNSUPPORTED,
+                    _ => FX_FAILURE,
+                };
+                status(&mut response, id, code, err.to_string().as_str())
+            }
+        }
+        Ok(response)
+    }
+
+    /// ### request
+    ///
+    /// Execute a request
+    fn request(&mut self, kind: u8, d: &mut Decoder) -> io::Result<Reply> {
+        match kind {
+            FXP_OPEN => {
+                let path: PathBuf = self.resolve(d.str()?.as_str())?;
+                let flags: u32 = d.u32()?;
+                let attrs: Attrs = Attrs::decode(d)?;
+                let file: File = OpenOptions::new()
+                    .read(flags & FXF_READ != 0)
+                    .write(flags & FXF_WRITE != 0)
+                    .append(flags & FXF_APPEND != 0)
+                    .create(flags & FXF_CREAT != 0 && flags & FXF_EXCL == 0)
+                    .create_new(flags & FXF_CREAT != 0 && flags & FXF_EXCL != 0)
+                    .truncate(flags & FXF_TRUNC != 0)
+                    .mode(attrs.permissions.unwrap_or(0o644) & 0o777)
+                    .open(path.as_path())?;
+                Ok(self.open(Handle::File(file, path)))
+            }
+            FXP_CLOSE => {
+                let handle: u32 = self.handle(d)?;
+                self.handles.remove(&handle);
+                Ok(Reply::Status(FX_OK))
+            }
+            FXP_READ => {
+                let handle: u32 = self.handle(d)?;
+                let offset: u64 = d.u64()?;
+                let len: u32 = d.u32()?.min(MAX_READ_SIZE);
+                let file: &mut File = self.file(handle)?;
+                file.seek(SeekFrom::Start(offset))?;
+                let mut data: Vec<u8> = Vec::new();
+                file.take(len as u64).read_to_end(&mut data)?;
+                match data.is_empty() {
+                    true => Ok(Reply::Status(FX_EOF)),
+                    false => Ok(Reply::Data(data)),
+                }
+            }
+            FXP_WRITE => {
+                let handle: u32 = self.handle(d)?;
+                let offset: u64 = d.u64()?;
+                let data: &[u8] = d.string()?;
+                let file: &mut File = self.file(handle)?;
+                file.seek(SeekFrom::Start(offset))?;
+                file.write_all(data)?;
+                Ok(Reply::Status(FX_OK))
+            }
+            FXP_LSTAT => Ok(Reply::Attrs(fs::symlink_metadata(
+                self.local_path(d.str()?.as_str()),
+            )?)),
+            FXP_STAT => Ok(Reply::Attrs(fs::metadata(
+                self.resolve(d.str()?.as_str())?,
+            )?)),
+            FXP_FSTAT => {
+                let handle: u32 = self.handle(d)?;
+                Ok(Reply::Attrs(self.file(handle)?.metadata()?))
+            }
+            FXP_SETSTAT => {
+                let path: PathBuf = self.resolve(d.str()?.as_str())?;
+                Attrs::decode(d)?.apply(path.as_path())?;
+                Ok(Reply::Status(FX_OK))
+            }
+            FXP_FSETSTAT => {
+                let path: PathBuf = match self.handles.get(&self.handle(d)?) {
+                    Some(Handle::File(_, path)) => path.clone(),
+                    _ => return Err(invalid_data("Invalid handle")),
+                };
+                Attrs::decode(d)?.apply(path.as_path())?;
+                Ok(Reply::Status(FX_OK))
+            }
+            FXP_OPENDIR => {
+                let path: PathBuf = self.resolve(d.str()?.as_str())?;
+                let mut entries: Vec<(String, Metadata)> = Vec::new();
+                for entry in fs::read_dir(path.as_path())? {
+                    let entry = entry?;
+                    entries.push((
+                        entry.file_name().to_string_lossy().to_string(),
+                        entry.path().symlink_metadata()?,
+                    ));
+                }
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                Ok(self.open(Handle::Dir(entries)))
+            }
+            FXP_READDIR => {
+                let handle: u32 = self.handle(d)?;
+                match self.handles.get_mut(&handle) {
+                    Some(Handle::Dir(entries)) if entries.is_empty() => Ok(Reply::Status(FX_EOF)),
+                    Some(Handle::Dir(entries)) => Ok(Reply::Name(
+                        entries
+                            .drain(..)
+                            .map(|(name, meta)| (name, Some(meta)))
+                            .collect(),
+                    )),
+                    _ => Err(invalid_data("Invalid handle")),
+                }
+            }
+            FXP_REMOVE => {
+                fs::remove_file(self.local_path(d.str()?.as_str()))?;
+                Ok(Reply::Status(FX_OK))
+            }
+            FXP_MKDIR => {
+                let path: PathBuf = self.local_path(d.str()?.as_str());
+                let attrs: Attrs = Attrs::decode(d)?;
+                fs::create_dir(path.as_path())?;
+                if let Some(mode) = attrs.permissions {
+                    fs::set_permissions(path.as_path(), fs::Permissions::from_mode(mode & 0o7777))?;
+                }
+                Ok(Reply::Status(FX_OK))
+            }
+            FXP_RMDIR => {
+                fs::remove_dir(self.local_path(d.str()?.as_str()))?;
+                Ok(Reply::Status(FX_OK))
+            }
+            FXP_REALPATH => Ok(Reply::Name(vec![(
+                virtual_path(Path::new("/"), d.str()?.as_str())
+                    .to_string_lossy()
+                    .to_string(),
+                None,
+            )])),
+            FXP_RENAME => {
+                let src: PathBuf = self.local_path(d.str()?.as_str());
+                let dst: PathBuf = self.local_path(d.str()?.as_str());
+                fs::rename(src, dst)?;
+                Ok(Reply::Status(FX_OK))
+            }
+            FXP_READLINK => Ok(Reply::Name(vec![(
+                fs::read_link(self.local_path(d.str()?.as_str()))?
+                    .to_string_lossy()
+                    .to_string(),
+                None,
+            )])),
+            FXP_SYMLINK => {
+                // As OpenSSH does, the target comes first
+                let target: String = d.str()?;
+                let path: PathBuf = self.local_path(d.str()?.as_str());
+                std::os::unix::fs::symlink(target, path)?;
+                Ok(Reply::Status(FX_OK))
+            }
+            _ => Ok(Reply::Status(FX_OP_UNSUPPORTED)),
+        }
+    }
+
+    /// ### open
+    ///
+    /// Register an open file or directory
+    fn open(&mut self, handle: Handle) -> Reply {
+        let id: u32 = self.next_handle;
+        self.next_handle += 1;
+        self.handles.insert(id, handle);
+        Reply::Handle(id)
+    }
+
+    /// ### handle
+    ///
+    /// Read the handle of a request
+    fn handle(&self, d: &mut Decoder) -> io::Result<u32> {
+        d.str()?
+            .parse::<u32>()
+            .map_err(|_| invalid_data("Invalid handle"))
+    }
+
+    /// ### file
+    ///
+    /// Get the open file with `handle`
+    fn file(&mut self, handle: u32) -> io::Result<&mut File> {
+        match self.handles.get_mut(&handle) {
+            Some(Handle::File(file, _)) => Ok(file),
+            _ => Err(invalid_data("Invalid handle")),
+        }
+    }
+
+    /// ### local_path
+    ///
+    /// Get local path for the path `p` sent by the client
+    fn local_path(&self, p: &str) -> PathBuf {
+        let p: PathBuf = virtual_path(Path::new("/"), p);
+        self.root.join(p.strip_prefix("/").unwrap_or(p.as_path()))
+    }
+
+    /// ### resolve
+    ///
+    /// Get local path for the path `p` sent by the client, following symbolic links,
+    /// whose absolute targets are relative to the served directory as well. The path may not exist
+    fn resolve(&self, p: &str) -> io::Result<PathBuf> {
+        let mut path: PathBuf = virtual_path(Path::new("/"), p);
+        for _ in 0..16 {
+            let local: PathBuf = self.local_path(path.to_string_lossy().as_ref());
+            match fs::symlink_metadata(local.as_path()) {
+                Ok(meta) if meta.file_type().is_symlink() => {}
+                _ => return Ok(local),
+            }
+            let target: PathBuf = fs::read_link(local.as_path())?;
+            let parent: PathBuf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            path = virtual_path(parent.as_path(), target.to_string_lossy().as_ref());
+        }
+        Err(invalid_data("Too many levels of symbolic links"))
+    }
+}
+
+/// ## Reply
+///
+/// The response to an SFTP request
+enum Reply {
+    Status(u32),
+    Handle(u32),
+    Data(Vec<u8>),
+    Name(Vec<(String, Option<Metadata>)>),
+    Attrs(Metadata),
+}
+
+/// ## Attrs
+///
+/// The attributes sent by the client
+struct Attrs {
+    size: Option<u64>,
+    permissions: Option<u32>,
+    times: Option<(u32, u32)>,
+}
+
+impl Attrs {
+    fn decode(d: &mut Decoder) -> io::Result<Self> {
+        let flags: u32 = d.u32()?;
+        let size: Option<u64> = match flags & ATTR_SIZE != 0 {
+            true => Some(d.u64()?),
+            false => None,
+        };
+        if flags & ATTR_UIDGID != 0 {
+            d.u32()?;
+            d.u32()?;
+        }
+        let permissions: Option<u32> = match flags & ATTR_PERMISSIONS != 0 {
+            true => Some(d.u32()?),
+            false => None,
+        };
+        let times: Option<(u32, u32)> = match flags & ATTR_ACMODTIME != 0 {
+            true => Some((d.u32()?, d.u32()?)),
+            false => None,
+        };
+        if flags & ATTR_EXTENDED != 0 {
+            for _ in 0..d.u32()? {
+                d.string()?;
+                d.string()?;
+            }
+        }
+        Ok(Self {
+            size,
+            permissions,
+            times,
+        })
+    }
+
+    /// ### apply
+    ///
+    /// Apply attributes to the file at `path`; owners are ignored
+    fn apply(&self, path: &Path) -> io::Result<()> {
+        if let Some(size) = self.size {
+            OpenOptions::new().write(true).open(path)?.set_len(size)?;
+        }
+        if let Some(mode) = self.permissions {
+            fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o7777))?;
+        }
+        if let Some((atime, mtime)) = self.times {
+            File::open(path)?.set_times(
+                FileTimes::new()
+                    .set_accessed(UNIX_EPOCH + Duration::from_secs(atime as u64))
+                    .set_modified(UNIX_EPOCH + Duration::from_secs(mtime as u64)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// ## Transport
+///
+/// The SSH transport layer, which frames, encrypts and authenticates packets
+struct Transport {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    version: String,
+    seq_in: u32,
+    seq_out: u32,
+    keys_in: Option<Keys>,
+    keys_out: Option<Keys>,
+}
+
+impl Transport {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            version: String::from("SSH-2.0-termscp_mock"),
+            seq_in: 0,
+            seq_out: 0,
+            keys_in: None,
+            keys_out: None,
+        })
+    }
+
+    /// ### exchange_versions
+    ///
+    /// Send the server version and get the client one
+    fn exchange_versions(&mut self) -> io::Result<String> {
+        write!(self.writer, "{}\r\n", self.version)?;
+        loop {
+            let mut line: String = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+            }
+            if line.starts_with("SSH-") {
+                return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+            }
+        }
+    }
+
+    /// ### read_packet
+    ///
+    /// Read the payload of the next packet
+    fn read_packet(&mut self) -> io::Result<Vec<u8>> {
+        let mut packet: Vec<u8> = vec![0; 16];
+        self.reader.read_exact(&mut packet[..4])?;
+        if let Some(keys) = self.keys_in.as_mut() {
+            self.reader.read_exact(&mut packet[4..])?;
+            packet = keys.apply(packet.as_slice())?;
+        }
+        let len: usize = Decoder::new(&packet[..4]).u32()? as usize;
+        if !(5..=262144).contains(&len) {
+            return Err(invalid_data("Invalid packet length"));
+        }
+        let read: usize = match self.keys_in.is_some() {
+            true => 16,
+            false => 4,
+        };
+        let mut rest: Vec<u8> = vec![0; (4 + len).saturating_sub(read)];
+        self.reader.read_exact(rest.as_mut_slice())?;
+        packet.truncate(read);
+        if let Some(keys) = self.keys_in.as_mut() {
+            rest = keys.apply(rest.as_slice())?;
+            packet.extend_from_slice(rest.as_slice());
+            let mut mac: Vec<u8> = vec![0; 32];
+            self.reader.read_exact(mac.as_mut_slice())?;
+            if keys.mac(self.seq_in, packet.as_slice())? != mac {
+                return Err(invalid_data("Bad MAC"));
+            }
+        } else {
+            packet.extend_from_slice(rest.as_slice());
+        }
+        self.seq_in = self.seq_in.wrapping_add(1);
+        let padding: usize = packet[4] as usize;
+        if padding + 1 > len {
+            return Err(invalid_data("Invalid padding"));
+        }
+        Ok(packet[5..4 + len - padding].to_vec())
+    }
+
+    /// ### expect_packet
+    ///
+    /// Read the next packet, which must be of type `kind`; ignored packets are skipped
+    fn expect_packet(&mut self, kind: u8) -> io::Result<Vec<u8>> {
+        loop {
+            let payload: Vec<u8> = self.read_packet()?;
+            match payload.first() {
+                Some(x) if *x == kind => return Ok(payload),
+                Some(&MSG_IGNORE) | Some(&MSG_DEBUG) => {}
+                _ => return Err(invalid_data("Unexpected packet")),
+            }
+        }
+    }
+
+    /// ### write_packet
+    ///
+    /// Send a packet with `payload`
+    fn write_packet(&mut self, payload: &[u8]) -> io::Result<()> {
+        let mut padding: usize = 16 - (5 + payload.len()) % 16;
+        if padding < 4 {
+            padding += 16;
+        }
+        let mut packet: Vec<u8> = Vec::with_capacity(5 + payload.len() + padding + 32);
+        packet.put_u32((1 + payload.len() + padding) as u32);
+        packet.push(padding as u8);
+        packet.extend_from_slice(payload);
+        packet.extend_from_slice(random_bytes(padding)?.as_slice());
+        if let Some(keys) = self.keys_out.as_mut() {
+            let mac: Vec<u8> = keys.mac(self.seq_out, packet.as_slice())?;
+            packet = keys.apply(packet.as_slice())?;
+            packet.extend_from_slice(mac.as_slice());
+        }
+        self.seq_out = self.seq_out.wrapping_add(1);
+        self.writer.write_all(packet.as_slice())?;
+        self.writer.flush()
+    }
+}
+
+/// ## Keys
+///
+/// The cipher and the MAC key for a direction of the transport
+struct Keys {
+    crypter: Crypter,
+    mac_key: Vec<u8>,
+}
+
+impl Keys {
+    fn new(mode: Mode, key: &[u8], iv: &[u8], mac_key: Vec<u8>) -> io::Result<Self> {
+        Ok(Self {
+            crypter: Crypter::new(Cipher::aes_128_ctr(), mode, key, Some(iv))
+                .map_err(invalid_data)?,
+            mac_key,
+        })
+    }
+
+    /// ### apply
+    ///
+    /// Encrypt or decrypt `data`, continuing the key stream
+    fn apply(&mut self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut output: Vec<u8> = vec![0; data.len() + 16];
+        let len: usize = self
+            .crypter
+            .update(data, output.as_mut_slice())
+            .map_err(invalid_data)?;
+        output.truncate(len);
+        Ok(output)
+    }
+
+    /// ### mac
+    ///
+    /// Calculate the MAC of the unencrypted packet with sequence number `seq`
+    fn mac(&self, seq: u32, packet: &[u8]) -> io::Result<Vec<u8>> {
+        let key: PKey<Private> = PKey::hmac(self.mac_key.as_slice()).map_err(invalid_data)?;
+        let mut signer: Signer =
+            Signer::new(MessageDigest::sha256(), &key).map_err(invalid_data)?;
+        signer
+            .update(&seq.to_be_bytes())
+            .and_then(|_| signer.update(packet))
+            .map_err(invalid_data)?;
+        signer.sign_to_vec().map_err(invalid_data)
+    }
+}
+
+/// ## Encode
+///
+/// Encode SSH data types
+trait Encode {
+    fn put_u32(&mut self, value: u32);
+    fn put_u64(&mut self, value: u64);
+    fn put_bool(&mut self, value: bool);
+    fn put_string(&mut self, value: &[u8]);
+    fn put_mpint(&mut self, value: &BigNumRef);
+}
+
+impl Encode for Vec<u8> {
+    fn put_u32(&mut self, value: u32) {
+        self.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn put_u64(&mut self, value: u64) {
+        self.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn put_bool(&mut self, value: bool) {
+        self.push(value as u8);
+    }
+
+    fn put_string(&mut self, value: &[u8]) {
+        self.put_u32(value.len() as u32);
+        self.extend_from_slice(value);
+    }
+
+    fn put_mpint(&mut self, value: &BigNumRef) {
+        let mut bytes: Vec<u8> = value.to_vec();
+        // Positive numbers mustn't have the most significant bit set
+        if bytes.first().map(|x| x & 0x80 != 0).unwrap_or(false) {
+            bytes.insert(0, 0);
+        }
+        self.put_string(bytes.as_slice());
+    }
+}
+
+/// ## Decoder
+///
+/// Decode SSH data types
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(invalid_data("Truncated message"));
+        }
+        let (value, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(value)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> io::Result<bool> {
+        Ok(self.u8()? != 0)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        let mut value: [u8; 4] = [0; 4];
+        value.copy_from_slice(self.take(4)?);
+        Ok(u32::from_be_bytes(value))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        let mut value: [u8; 8] = [0; 8];
+        value.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(value))
+    }
+
+    fn string(&mut self) -> io::Result<&'a [u8]> {
+        let len: usize = self.u32()? as usize;
+        self.take(len)
+    }
+
+    fn str(&mut self) -> io::Result<String> {
+        Ok(String::from_utf8_lossy(self.string()?).to_string())
+    }
+
+    fn mpint(&mut self) -> io::Result<BigNum> {
+        BigNum::from_slice(self.string()?).map_err(invalid_data)
+    }
+}
+
+/// ### virtual_path
+///
+/// Resolve `p` against `wrkdir`. The result never escapes `/`
+fn virtual_path(wrkdir: &Path, p: &str) -> PathBuf {
+    let mut path: PathBuf = PathBuf::from("/");
+    for component in wrkdir.join(p).components() {
+        match component {
+            Component::Normal(name) => path.push(name),
+            Component::ParentDir => {
+                path.pop();
+            }
+            _ => {}
+        }
+    }
+    path
+}
+
+/// ### put_attrs
+///
+/// Encode the attributes of a file; `None` encodes no attribute
+fn put_attrs(buf: &mut Vec<u8>, meta: Option<&Metadata>) {
+    match meta {
+        None => buf.put_u32(0),
+        Some(meta) => {
+            buf.put_u32(ATTR_SIZE | ATTR_UIDGID | ATTR_PERMISSIONS | ATTR_ACMODTIME);
+            buf.put_u64(meta.len());
+            buf.put_u32(meta.uid());
+            buf.put_u32(meta.gid());
+            buf.put_u32(meta.mode());
+            buf.put_u32(meta.atime() as u32);
+            buf.put_u32(meta.mtime() as u32);
+        }
+    }
+}
+
+/// ### status
+///
+/// Encode a status response
+fn status(buf: &mut Vec<u8>, id: u32, code: u32, msg: &str) {
+    buf.push(FXP_STATUS);
+    buf.put_u32(id);
+    buf.put_u32(code);
+    buf.put_string(msg.as_bytes());
+    buf.put_string(b"");
+}
+
+/// ### sha256
+///
+/// Calculate the SHA256 digest of `data`
+fn sha256(data: &[u8]) -> io::Result<Vec<u8>> {
+    hash(MessageDigest::sha256(), data)
+        .map(|x| x.to_vec())
+        .map_err(invalid_data)
+}
+
+/// ### random_bytes
+///
+/// Get `len` random bytes
+fn random_bytes(len: usize) -> io::Result<Vec<u8>> {
+    let mut bytes: Vec<u8> = vec![0; len];
+    rand_bytes(bytes.as_mut_slice()).map_err(invalid_data)?;
+    Ok(bytes)
+}
+
+/// ### invalid_data
+///
+/// Make an `InvalidData` io error from `err`
+fn invalid_data<E: ToString>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::filetransfer::sftp_transfer::SftpFileTransfer;
+    use crate::filetransfer::{FileTransfer, FileTransferErrorType};
+    use crate::fs::{FsEntry, FsFile};
+    use crate::system::sshkey_storage::SshKeyStorage;
+    use crate::utils::test_helpers::make_fsentry;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_test_support_sftp_server() {
+        let server: MockSftpServer = MockSftpServer::start("omar", "password").unwrap();
+        let mut client: SftpFileTransfer = SftpFileTransfer::new(SshKeyStorage::empty());
+        assert!(client
+            .connect(
+                server.address(),
+                server.port(),
+                Some(String::from("omar")),
+                Some(String::from("password")),
+            )
+            .is_ok());
+        assert_eq!(client.pwd().unwrap(), PathBuf::from("/"));
+        assert_eq!(client.list_dir(Path::new("/")).unwrap().len(), 0);
+        // Make directory and enter it
+        assert!(client.mkdir(Path::new("/home")).is_ok());
+        assert_eq!(
+            client.change_dir(Path::new("/home")).unwrap(),
+            PathBuf::from("/home")
+        );
+        // Upload file, larger than a channel packet
+        let content: Vec<u8> = (0..200000_u32).map(|x| (x % 251) as u8).collect();
+        let file: FsFile = make_fsentry(PathBuf::from("/tmp/omar.txt"), false).unwrap_file();
+        let mut writer = client
+            .send_file(&file, Path::new("/home/omar.txt"))
+            .unwrap();
+        assert!(writer.write_all(content.as_slice()).is_ok());
+        assert!(client.on_sent(writer).is_ok());
+        assert_eq!(
+            fs::read(server.root().join("home/omar.txt")).unwrap(),
+            content
+        );
+        // List directory
+        let files: Vec<FsEntry> = client.list_dir(Path::new("/home")).unwrap();
+        assert_eq!(files.len(), 1);
+        let remote = files.first().unwrap().clone().unwrap_file();
+        assert_eq!(remote.name.as_str(), "omar.txt");
+        assert_eq!(remote.abs_path, PathBuf::from("/home/omar.txt"));
+        assert_eq!(remote.size, 200000);
+        // Download file
+        let mut reader = client.recv_file(&remote).unwrap();
+        let mut downloaded: Vec<u8> = Vec::new();
+        assert!(reader.read_to_end(&mut downloaded).is_ok());
+        assert!(client.on_recv(reader).is_ok());
+        assert_eq!(downloaded, content);
+        // Permissions
+        assert!(client.chmod(Path::new("/home/omar.txt"), (6, 0, 0)).is_ok());
+        assert_eq!(
+            fs::metadata(server.root().join("home/omar.txt"))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777,
+            0o600
+        );
+        // Symlinks point inside the served directory
+        assert!(client
+            .symlink(Path::new("/home/link.txt"), Path::new("/home/omar.txt"))
+            .is_ok());
+        assert_eq!(
+            client.read_link(Path::new("/home/link.txt")).unwrap(),
+            PathBuf::from("/home/omar.txt")
+        );
+        assert_eq!(
+            client
+                .stat(Path::new("/home/link.txt"))
+                .unwrap()
+                .unwrap_file()
+                .size,
+            200000
+        );
+        // Commands can't be executed
+        assert!(client.exec("echo termscp").is_err());
+        // Rename and remove
+        assert!(client
+            .rename(&FsEntry::File(remote), Path::new("/home/pippo.txt"))
+            .is_ok());
+        assert!(server.root().join("home/pippo.txt").exists());
+        assert!(client.change_dir(Path::new("/")).is_ok());
+        let entry: FsEntry = client.list_dir(Path::new("/")).unwrap().remove(0);
+        assert!(client.remove(&entry).is_ok());
+        assert!(!server.root().join("home").exists());
+        assert!(client.disconnect().is_ok());
+    }
+
+    #[test]
+    fn test_test_support_sftp_server_bad_auth() {
+        let server: MockSftpServer = MockSftpServer::start("omar", "password").unwrap();
+        let mut client: SftpFileTransfer = SftpFileTransfer::new(SshKeyStorage::empty());
+        assert_eq!(
+            client
+                .connect(
+                    server.address(),
+                    server.port(),
+                    Some(String::from("omar")),
+                    Some(String::from("wrong")),
+                )
+                .err()
+                .unwrap()
+                .kind(),
+            FileTransferErrorType::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_test_support_sftp_server_chroot() {
+        let sftp: Sftp = Sftp::new(PathBuf::from("/srv"));
+        assert_eq!(
+            virtual_path(Path::new("/"), "../../etc"),
+            PathBuf::from("/etc")
+        );
+        assert_eq!(sftp.local_path("/home/../etc"), PathBuf::from("/srv/etc"));
+        assert_eq!(sftp.local_path("omar.txt"), PathBuf::from("/srv/omar.txt"));
+    }
+}
@@ -0,0 +1,213 @@
+//! ## Snapshot
+//!
+//! `snapshot` provides snapshots of what an headless context has drawn, to write regression tests for views
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use textwrap::core::display_width;
+use tuirealm::tui::buffer::Buffer;
+
+/// When set, snapshots are rewritten instead of being compared
+const UPDATE_SNAPSHOTS_ENV: &str = "TERMSCP_UPDATE_SNAPSHOTS";
+/// Directory where snapshots are stored, relative to the crate root
+const SNAPSHOTS_DIR: &str = "tests/snapshots";
+
+/// ## Snapshot
+///
+/// Snapshot is the text content of a screen buffer; one string for each row, with trailing spaces trimmed.
+/// Styles are not part of the snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    rows: Vec<String>,
+}
+
+impl Snapshot {
+    /// ### from_buffer
+    ///
+    /// Take a snapshot of `buffer`
+    pub fn from_buffer(buffer: &Buffer) -> Self {
+        let width: usize = buffer.area.width as usize;
+        let rows: Vec<String> = match width {
+            0 => Vec::new(),
+            _ => buffer
+                .content
+                .chunks(width)
+                .map(|cells| {
+                    let mut row: String = String::with_capacity(width);
+                    // Cells hidden by a wide symbol must be skipped
+                    let mut skip: usize = 0;
+                    for cell in cells.iter() {
+                        if skip == 0 {
+                            row.push_str(cell.symbol.as_str());
+                        }
+                        skip = std::cmp::max(skip, display_width(cell.symbol.as_str()))
+                            .saturating_sub(1);
+                    }
+                    row.trim_end().to_string()
+                })
+                .collect(),
+        };
+        Self { rows }
+    }
+
+    /// ### rows
+    ///
+    /// Get snapshot rows
+    pub fn rows(&self) -> &[String] {
+        self.rows.as_slice()
+    }
+
+    /// ### contains
+    ///
+    /// Returns whether any row contains `text`
+    pub fn contains(&self, text: &str) -> bool {
+        self.rows.iter().any(|x| x.contains(text))
+    }
+
+    /// ### redact
+    ///
+    /// Replace each occurrence of `text` with `replacement`.
+    /// Use it to hide volatile content, such as temporary paths or ports, from the snapshot.
+    /// What follows `text` keeps its column: the replacement is padded with spaces, or takes
+    /// the spaces after `text`, when their widths differ
+    pub fn redact(mut self, text: &str, replacement: &str) -> Self {
+        if !text.is_empty() {
+            self.rows = self
+                .rows
+                .iter()
+                .map(|x| redact_row(x, text, replacement))
+                .collect();
+        }
+        self
+    }
+
+    /// ### assert_matches
+    ///
+    /// Compare the snapshot with the one stored as `name` in `tests/snapshots`.
+    /// If `TERMSCP_UPDATE_SNAPSHOTS` is set, the snapshot is written instead.
+    /// Panics if the stored snapshot doesn't exist, so that a missing snapshot can't pass silently
+    pub fn assert_matches(&self, name: &str) {
+        let path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(SNAPSHOTS_DIR)
+            .join(format!("{}.snap", name));
+        let actual: String = self.to_string();
+        if env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).expect("Failed to create snapshots directory");
+            }
+            fs::write(path.as_path(), actual).expect("Failed to write snapshot");
+            return;
+        }
+        if !path.exists() {
+            panic!(
+                "Snapshot `{}` doesn't exist (run with {}=1 to create it)\nGot:\n{}",
+                name, UPDATE_SNAPSHOTS_ENV, actual
+            );
+        }
+        let expected: String = fs::read_to_string(path.as_path()).expect("Failed to read snapshot");
+        if expected != actual {
+            panic!(
+                "Snapshot `{}` doesn't match (run with {}=1 to update it)\nExpected:\n{}\nGot:\n{}",
+                name, UPDATE_SNAPSHOTS_ENV, expected, actual
+            );
+        }
+    }
+}
+
+/// ### redact_row
+///
+/// Replace each occurrence of `text` in `row` with `replacement`, keeping the columns after it
+fn redact_row(row: &str, text: &str, replacement: &str) -> String {
+    let text_width: usize = display_width(text);
+    let replacement_width: usize = display_width(replacement);
+    let mut redacted: String = String::with_capacity(row.len());
+    let mut rest: &str = row;
+    while let Some(idx) = rest.find(text) {
+        redacted.push_str(&rest[..idx]);
+        redacted.push_str(replacement);
+        rest = &rest[idx + text.len()..];
+        if replacement_width < text_width {
+            redacted.push_str(" ".repeat(text_width - replacement_width).as_str());
+        } else {
+            let spaces: usize = rest.len() - rest.trim_start_matches(' ').len();
+            rest = &rest[std::cmp::min(spaces, replacement_width - text_width)..];
+        }
+    }
+    redacted.push_str(rest);
+    redacted.trim_end().to_string()
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows.iter() {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::tui::layout::Rect;
+
+    #[test]
+    fn test_test_support_snapshot_from_buffer() {
+        let mut buffer: Buffer = Buffer::empty(Rect::new(0, 0, 8, 3));
+        buffer.set_string(0, 0, "termscp", Default::default());
+        buffer.set_string(2, 2, "🦀 ok", Default::default());
+        let snapshot: Snapshot = Snapshot::from_buffer(&buffer);
+        assert_eq!(
+            snapshot.rows(),
+            &["termscp".to_string(), String::new(), "  🦀 ok".to_string()]
+        );
+        assert!(snapshot.contains("ok"));
+        assert_eq!(snapshot.to_string(), "termscp\n\n  🦀 ok\n");
+        // Redact
+        let snapshot: Snapshot = snapshot.redact("termscp", "<app>").redact(" ok", "");
+        assert_eq!(snapshot.to_string(), "<app>\n\n  🦀\n");
+        // Redact keeps columns
+        let mut buffer: Buffer = Buffer::empty(Rect::new(0, 0, 16, 1));
+        buffer.set_string(0, 0, "│vim        │", Default::default());
+        let snapshot: Snapshot = Snapshot::from_buffer(&buffer);
+        assert_eq!(
+            snapshot.clone().redact("vim", "<editor>").to_string(),
+            "│<editor>   │\n"
+        );
+        assert_eq!(snapshot.redact("vim", "x").to_string(), "│x          │\n");
+        assert_eq!(
+            Snapshot::from_buffer(&Buffer::empty(Rect::default()))
+                .rows()
+                .len(),
+            0
+        );
+    }
+}
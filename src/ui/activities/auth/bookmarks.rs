@@ -27,40 +27,62 @@
  */
 // Locals
 use super::{AuthActivity, FileTransferProtocol};
+use crate::config::serialization::{SerializerError, SerializerErrorKind};
 use crate::system::bookmarks_client::BookmarksClient;
 use crate::system::environment;
+use crate::ui::activities::STORE_KEY_BOOKMARKS_MASTER_PASSWORD;
 
 // Ext
-use std::path::PathBuf;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use std::collections::HashSet;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, TryRecvError};
+use std::thread;
+use std::time::{Duration, SystemTime};
 use tui_realm_stdlib::{input::InputPropsBuilder, radio::RadioPropsBuilder};
 use tuirealm::{Payload, PropsBuilder, Value};
 
+/// Receives the bookmarks client initialized by a worker, along with the bookmarks file and configuration directory
+pub(super) type BookmarksLoader =
+    mpsc::Receiver<(Result<BookmarksClient, SerializerError>, PathBuf, PathBuf)>;
+
+/// ## BookmarkRow
+///
+/// BookmarkRow describes a row of the bookmarks list
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum BookmarkRow {
+    Group(String, usize), // Group header, with the amount of bookmarks in the group
+    Bookmark(String),
+}
+
 impl AuthActivity {
     /// ### del_bookmark
     ///
-    /// Delete bookmark
+    /// Delete bookmark at row `idx` of the bookmarks list
     pub(super) fn del_bookmark(&mut self, idx: usize) {
+        let name: Option<String> = self.bookmark_at(idx);
         if let Some(bookmarks_cli) = self.bookmarks_client.as_mut() {
-            // Iterate over kyes
-            let name: Option<&String> = self.bookmarks_list.get(idx);
             if let Some(name) = name {
-                bookmarks_cli.del_bookmark(name);
+                bookmarks_cli.del_bookmark(&name);
                 // Write bookmarks
-                self.write_bookmarks();
+                if self.write_bookmarks() {
+                    self.show_toast(format!("Bookmark \"{}\" deleted", name).as_str());
+                }
+                // Delete element from vec
+                self.bookmarks_list.retain(|b| b.as_str() != name.as_str());
             }
-            // Delete element from vec
-            self.bookmarks_list.remove(idx);
         }
     }
 
     /// ### load_bookmark
     ///
-    /// Load selected bookmark (at index) to input fields
+    /// Load bookmark at row `idx` of the bookmarks list to input fields
     pub(super) fn load_bookmark(&mut self, idx: usize) {
+        let name: Option<String> = self.bookmark_at(idx);
         if let Some(bookmarks_cli) = self.bookmarks_client.as_ref() {
-            // Iterate over bookmarks
-            if let Some(key) = self.bookmarks_list.get(idx) {
-                if let Some(bookmark) = bookmarks_cli.get_bookmark(key) {
+            if let Some(key) = name {
+                if let Some(bookmark) = bookmarks_cli.get_bookmark(key.as_str()) {
                     // Load parameters into components
                     self.load_bookmark_into_gui(
                         bookmark.0, bookmark.1, bookmark.2, bookmark.3, bookmark.4,
@@ -74,6 +96,10 @@ impl AuthActivity {
     ///
     /// Save current input fields as a bookmark
     pub(super) fn save_bookmark(&mut self, name: String, save_password: bool) {
+        if self.bookmarks_locked() {
+            self.mount_error("Bookmarks are locked: unlock them with the master password first");
+            return;
+        }
         let (address, port, protocol, username, password) = self.get_input();
         if let Some(bookmarks_cli) = self.bookmarks_client.as_mut() {
             // Check if password must be saved
@@ -89,7 +115,9 @@ impl AuthActivity {
             };
             bookmarks_cli.add_bookmark(name.clone(), address, port, protocol, username, password);
             // Save bookmarks
-            self.write_bookmarks();
+            if self.write_bookmarks() {
+                self.show_toast(format!("Bookmark \"{}\" saved", name).as_str());
+            }
             // Remove `name` from bookmarks if exists
             self.bookmarks_list.retain(|b| b.as_str() != name.as_str());
             // Push bookmark to list and sort
@@ -97,19 +125,220 @@ impl AuthActivity {
             self.sort_bookmarks();
         }
     }
+
+    /// ### edit_bookmark
+    ///
+    /// Save the values of the edit dialog, root mapping included, to the bookmark at row `idx` of the bookmarks list.
+    /// Returns whether the values were valid and the bookmark could be edited; if not, an error is displayed
+    pub(super) fn edit_bookmark(&mut self, idx: usize) -> bool {
+        if self.bookmarks_locked() {
+            self.mount_error("Bookmarks are locked: unlock them with the master password first");
+            return true;
+        }
+        let name: String = match self.bookmark_at(idx) {
+            Some(name) => name,
+            None => return true,
+        };
+        let (address, port, protocol, username, password) = self.get_bookmark_edit_input();
+        if address.is_empty() {
+            self.mount_error("Invalid host");
+            return false;
+        }
+        if port == 0 {
+            self.mount_error("Invalid port");
+            return false;
+        }
+        let mapping: Option<(PathBuf, PathBuf)> = match self.get_bookmark_edit_mapping() {
+            (local, remote) if local.is_empty() && remote.is_empty() => None,
+            (local, remote) if !local.is_empty() && !remote.is_empty() => {
+                Some((PathBuf::from(local), PathBuf::from(remote)))
+            }
+            _ => {
+                self.mount_error("Set both the local and the remote root, or leave both empty");
+                return false;
+            }
+        };
+        let edited: bool = match self.bookmarks_client.as_mut().map(|bookmarks_cli| {
+            bookmarks_cli.edit_bookmark(name.as_str(), address, port, protocol, username, password)
+        }) {
+            Some(Ok(edited)) => {
+                edited
+                    && self
+                        .bookmarks_client
+                        .as_mut()
+                        .map(|cli| cli.set_bookmark_mapping(name.as_str(), mapping))
+                        .unwrap_or(false)
+            }
+            Some(Err(err)) => {
+                self.mount_error(
+                    format!(
+                        "Could not edit bookmark \"{}\": its proxy can't be decrypted ({}); the bookmark was left unchanged",
+                        name, err
+                    )
+                    .as_str(),
+                );
+                return false;
+            }
+            None => false,
+        };
+        if edited && self.write_bookmarks() {
+            self.show_toast(format!("Bookmark \"{}\" saved", name).as_str());
+        }
+        true
+    }
+
+    /// ### bookmark_rows
+    ///
+    /// Get the rows of the bookmarks list
+    pub(super) fn bookmark_rows(&self) -> Vec<BookmarkRow> {
+        match self.bookmarks_client.as_ref() {
+            Some(cli) => {
+                let bookmarks: Vec<String> = self
+                    .bookmarks_list
+                    .iter()
+                    .filter(|name| match cli.get_bookmark(name.as_str()) {
+                        Some((address, _, _, username, _)) => self.search_matches(&[
+                            name.as_str(),
+                            address.as_str(),
+                            username.as_str(),
+                        ]),
+                        None => false,
+                    })
+                    .cloned()
+                    .collect();
+                // While searching, groups are expanded to show all the matches
+                let expanded: HashSet<String> = HashSet::new();
+                Self::group_bookmarks(
+                    bookmarks.as_slice(),
+                    |name| cli.get_group(name),
+                    match self.search.is_empty() {
+                        true => &self.collapsed_groups,
+                        false => &expanded,
+                    },
+                )
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// ### recent_rows
+    ///
+    /// Get the rows of the recents list
+    pub(super) fn recent_rows(&self) -> Vec<String> {
+        match self.bookmarks_client.as_ref() {
+            Some(cli) => self
+                .recents_list
+                .iter()
+                .filter(|key| match cli.get_recent(key.as_str()) {
+                    Some((address, _, _, username)) => {
+                        self.search_matches(&[address.as_str(), username.as_str()])
+                    }
+                    None => false,
+                })
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// ### set_search
+    ///
+    /// Set the search which filters bookmarks and recents
+    pub(super) fn set_search(&mut self, search: String) {
+        self.search = search;
+    }
+
+    /// ### search_matches
+    ///
+    /// Returns whether any of `fields` matches the current search
+    fn search_matches(&self, fields: &[&str]) -> bool {
+        self.search.is_empty()
+            || fields
+                .iter()
+                .any(|field| Self::fuzzy_match(self.search.as_str(), field))
+    }
+
+    /// ### fuzzy_match
+    ///
+    /// Returns whether the characters of `search` appear in `text` in the same order, ignoring case and whitespaces
+    fn fuzzy_match(search: &str, text: &str) -> bool {
+        let mut text = text.chars().flat_map(char::to_lowercase);
+        search
+            .chars()
+            .filter(|x| !x.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .all(|x| text.any(|y| y == x))
+    }
+
+    /// ### bookmark_at
+    ///
+    /// Get the name of the bookmark at row `idx` of the bookmarks list, if the row is a bookmark
+    pub(super) fn bookmark_at(&self, idx: usize) -> Option<String> {
+        match self.bookmark_rows().into_iter().nth(idx) {
+            Some(BookmarkRow::Bookmark(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// ### toggle_bookmark_group
+    ///
+    /// Collapse or expand the group at row `idx` of the bookmarks list, or the group of the bookmark at that row.
+    /// Returns the row of the group header afterwards
+    pub(super) fn toggle_bookmark_group(&mut self, idx: usize) -> Option<usize> {
+        let group: String = match self.bookmark_rows().into_iter().nth(idx)? {
+            BookmarkRow::Group(group, _) => group,
+            BookmarkRow::Bookmark(name) => self
+                .bookmarks_client
+                .as_ref()?
+                .get_group(name.as_str())?
+                .to_string(),
+        };
+        if !self.collapsed_groups.remove(group.as_str()) {
+            self.collapsed_groups.insert(group.clone());
+        }
+        self.bookmark_rows()
+            .iter()
+            .position(|x| matches!(x, BookmarkRow::Group(g, _) if g == &group))
+    }
+
+    /// ### set_bookmark_group
+    ///
+    /// Move the bookmark at row `idx` of the bookmarks list into `group`; an empty group ungroups it.
+    /// Returns the row of the bookmark afterwards
+    pub(super) fn set_bookmark_group(&mut self, idx: usize, group: String) -> Option<usize> {
+        if self.bookmarks_locked() {
+            self.mount_error("Bookmarks are locked: unlock them with the master password first");
+            return None;
+        }
+        let name: String = self.bookmark_at(idx)?;
+        if let Some(bookmarks_cli) = self.bookmarks_client.as_mut() {
+            bookmarks_cli.set_group(name.as_str(), Some(group));
+            // Expand the group, so that the bookmark stays in sight
+            if let Some(group) = bookmarks_cli.get_group(name.as_str()) {
+                self.collapsed_groups.remove(group);
+            }
+            if self.write_bookmarks() {
+                self.show_toast(format!("Bookmark \"{}\" moved", name).as_str());
+            }
+        }
+        self.bookmark_rows()
+            .iter()
+            .position(|x| matches!(x, BookmarkRow::Bookmark(b) if b == &name))
+    }
+
     /// ### del_recent
     ///
     /// Delete recent
     pub(super) fn del_recent(&mut self, idx: usize) {
+        let name: Option<String> = self.recent_rows().into_iter().nth(idx);
         if let Some(client) = self.bookmarks_client.as_mut() {
-            let name: Option<&String> = self.recents_list.get(idx);
             if let Some(name) = name {
-                client.del_recent(name);
+                client.del_recent(name.as_str());
                 // Write bookmarks
                 self.write_bookmarks();
+                // Delete element from vec
+                self.recents_list.retain(|x| x != &name);
             }
-            // Delete element from vec
-            self.recents_list.remove(idx);
         }
     }
 
@@ -119,7 +348,7 @@ impl AuthActivity {
     pub(super) fn load_recent(&mut self, idx: usize) {
         if let Some(client) = self.bookmarks_client.as_ref() {
             // Iterate over bookmarks
-            if let Some(key) = self.recents_list.get(idx) {
+            if let Some(key) = self.recent_rows().get(idx) {
                 if let Some(bookmark) = client.get_recent(key) {
                     // Load parameters
                     self.load_bookmark_into_gui(
@@ -135,6 +364,10 @@ impl AuthActivity {
     /// Save current input fields as a "recent"
     pub(super) fn save_recent(&mut self) {
         let (address, port, protocol, username, _password) = self.get_input();
+        if self.bookmarks_locked() {
+            // Don't bother users; recents are just not saved
+            return;
+        }
         if let Some(bookmarks_cli) = self.bookmarks_client.as_mut() {
             bookmarks_cli.add_recent(address, port, protocol, username);
             // Save bookmarks
@@ -144,76 +377,296 @@ impl AuthActivity {
 
     /// ### write_bookmarks
     ///
-    /// Write bookmarks to file
-    fn write_bookmarks(&mut self) {
+    /// Write bookmarks to file.
+    /// Returns whether bookmarks have been written
+    fn write_bookmarks(&mut self) -> bool {
         if let Some(bookmarks_cli) = self.bookmarks_client.as_ref() {
             if let Err(err) = bookmarks_cli.write_bookmarks() {
                 self.mount_error(format!("Could not write bookmarks: {}", err).as_str());
+                return false;
+            }
+        }
+        true
+    }
+
+    /// ### repair_bookmarks
+    ///
+    /// Repair the malformed bookmarks file, depending on the choice made in the repair dialog:
+    /// open it in the text editor (0), restore its backup (1) or start with a new file, setting the malformed one aside (2).
+    /// Bookmarks are loaded again afterwards
+    pub(super) fn repair_bookmarks(&mut self, choice: usize) {
+        let bookmarks_file: PathBuf = match environment::init_config_dir() {
+            Ok(Some(dir)) => environment::get_bookmarks_paths(dir.as_path()),
+            _ => return,
+        };
+        let result: Result<Option<PathBuf>, String> = match choice {
+            0 => self
+                .edit_bookmarks_file(bookmarks_file.as_path())
+                .map(|_| None),
+            1 => BookmarksClient::restore_backup(bookmarks_file.as_path())
+                .map(Some)
+                .map_err(|e| e.to_string()),
+            _ => BookmarksClient::set_aside(bookmarks_file.as_path())
+                .map(Some)
+                .map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(broken) => {
+                if let Some(broken) = broken {
+                    self.show_toast(
+                        format!("Malformed bookmarks saved as \"{}\"", broken.display()).as_str(),
+                    );
+                }
+                // Reload bookmarks; the dialog shows up again if the file is still malformed
+                self.init_bookmarks_client();
+                self.view_bookmarks();
+                self.view_recent_connections();
+            }
+            Err(err) => {
+                // Ask again, once the error has been read
+                self.init_bookmarks_client();
+                self.mount_error(format!("Could not repair bookmarks: {}", err).as_str());
             }
         }
     }
 
+    /// ### edit_bookmarks_file
+    ///
+    /// Open the bookmarks file in the text editor, giving it the terminal until the editor exits
+    fn edit_bookmarks_file(&mut self, bookmarks_file: &Path) -> Result<(), String> {
+        env::set_var("EDITOR", self.context().config().get_text_editor());
+        if let Err(err) = disable_raw_mode() {
+            error!("Failed to disable raw mode: {}", err);
+        }
+        #[cfg(not(target_os = "windows"))]
+        self.context_mut().leave_alternate_screen();
+        let result = edit::edit_file(bookmarks_file);
+        #[cfg(not(target_os = "windows"))]
+        {
+            self.context_mut().clear_screen();
+            self.context_mut().enter_alternate_screen();
+        }
+        let _ = enable_raw_mode();
+        result.map_err(|err| format!("Could not open editor: {}", err))
+    }
+
     /// ### init_bookmarks_client
     ///
     /// Initialize bookmarks client
     pub(super) fn init_bookmarks_client(&mut self) {
+        if let Some((bookmarks_file, config_dir_path)) = self.bookmarks_paths() {
+            let recents_size: usize = self.context().config().get_recents_size();
+            let result = BookmarksClient::new(
+                bookmarks_file.as_path(),
+                config_dir_path.as_path(),
+                recents_size,
+            );
+            self.on_bookmarks_client(result, bookmarks_file.as_path(), config_dir_path.as_path());
+        }
+    }
+
+    /// ### load_bookmarks_client
+    ///
+    /// Initialize bookmarks client in a worker thread, since the key storage may take long to answer;
+    /// bookmarks are shown once `poll_bookmarks_client` gets the client
+    pub(super) fn load_bookmarks_client(&mut self) {
+        if let Some((bookmarks_file, config_dir_path)) = self.bookmarks_paths() {
+            let recents_size: usize = self.context().config().get_recents_size();
+            let (tx, rx) = mpsc::channel();
+            thread::spawn(move || {
+                let result = BookmarksClient::new(
+                    bookmarks_file.as_path(),
+                    config_dir_path.as_path(),
+                    recents_size,
+                );
+                let _ = tx.send((result, bookmarks_file, config_dir_path));
+            });
+            self.bookmarks_loader = Some(rx);
+        }
+    }
+
+    /// ### poll_bookmarks_client
+    ///
+    /// Set up the bookmarks client loaded by `load_bookmarks_client`, if ready; if `wait`, block until it is.
+    /// Returns whether the client has been set up
+    pub(super) fn poll_bookmarks_client(&mut self, wait: bool) -> bool {
+        let received = match self.bookmarks_loader.as_ref() {
+            None => return false,
+            Some(loader) if wait => loader.recv().map_err(|_| TryRecvError::Disconnected),
+            Some(loader) => loader.try_recv(),
+        };
+        match received {
+            Ok((result, bookmarks_file, config_dir_path)) => {
+                self.bookmarks_loader = None;
+                self.on_bookmarks_client(
+                    result,
+                    bookmarks_file.as_path(),
+                    config_dir_path.as_path(),
+                );
+                self.view_bookmarks();
+                self.view_recent_connections();
+                true
+            }
+            Err(TryRecvError::Empty) => false,
+            Err(TryRecvError::Disconnected) => {
+                error!("Bookmarks loader terminated unexpectedly");
+                self.bookmarks_loader = None;
+                false
+            }
+        }
+    }
+
+    /// ### bookmarks_paths
+    ///
+    /// Get the paths of the bookmarks file and of the configuration directory, creating the latter if necessary.
+    /// Returns `None` if bookmarks aren't supported on this system
+    fn bookmarks_paths(&mut self) -> Option<(PathBuf, PathBuf)> {
         // Get config dir
         match environment::init_config_dir() {
-            Ok(path) => {
-                // If some configure client, otherwise do nothing; don't bother users telling them that bookmarks are not supported on their system.
-                if let Some(config_dir_path) = path {
-                    let bookmarks_file: PathBuf =
-                        environment::get_bookmarks_paths(config_dir_path.as_path());
-                    // Initialize client
-                    match BookmarksClient::new(
-                        bookmarks_file.as_path(),
-                        config_dir_path.as_path(),
-                        16,
-                    ) {
-                        Ok(cli) => {
-                            // Load bookmarks into list
-                            let mut bookmarks_list: Vec<String> =
-                                Vec::with_capacity(cli.iter_bookmarks().count());
-                            for bookmark in cli.iter_bookmarks() {
-                                bookmarks_list.push(bookmark.clone());
-                            }
-                            // Load recents into list
-                            let mut recents_list: Vec<String> =
-                                Vec::with_capacity(cli.iter_recents().count());
-                            for recent in cli.iter_recents() {
-                                recents_list.push(recent.clone());
-                            }
-                            self.bookmarks_client = Some(cli);
-                            self.bookmarks_list = bookmarks_list;
-                            self.recents_list = recents_list;
-                            // Sort bookmark list
-                            self.sort_bookmarks();
-                            self.sort_recents();
-                        }
-                        Err(err) => {
-                            self.mount_error(
-                                format!(
-                                    "Could not initialize bookmarks (at \"{}\", \"{}\"): {}",
-                                    bookmarks_file.display(),
-                                    config_dir_path.display(),
-                                    err
-                                )
-                                .as_str(),
-                            );
+            // If some configure client, otherwise do nothing; don't bother users telling them that bookmarks are not supported on their system.
+            Ok(path) => path.map(|config_dir_path| {
+                let bookmarks_file: PathBuf =
+                    environment::get_bookmarks_paths(config_dir_path.as_path());
+                (bookmarks_file, config_dir_path)
+            }),
+            Err(err) => {
+                self.mount_error(
+                    format!("Could not initialize configuration directory: {}", err).as_str(),
+                );
+                None
+            }
+        }
+    }
+
+    /// ### on_bookmarks_client
+    ///
+    /// Set up the bookmarks client initialized for `bookmarks_file` or report the error
+    fn on_bookmarks_client(
+        &mut self,
+        result: Result<BookmarksClient, SerializerError>,
+        bookmarks_file: &Path,
+        config_dir_path: &Path,
+    ) {
+        match result {
+            Ok(mut cli) => {
+                // Unlock with the master password already entered, if any
+                if cli.is_locked() {
+                    if let Some(password) = self
+                        .context()
+                        .store()
+                        .get_string(STORE_KEY_BOOKMARKS_MASTER_PASSWORD)
+                    {
+                        if let Err(err) = cli.unlock(password) {
+                            warn!("Could not unlock bookmarks: {}", err);
                         }
                     }
                 }
+                let locked: bool = cli.is_locked();
+                self.bookmarks_client = Some(cli);
+                self.apply_recents_policy();
+                self.load_bookmarks_lists();
+                // Ask for master password
+                if locked {
+                    self.mount_master_password();
+                }
+            }
+            Err(err) if matches!(err.kind(), SerializerErrorKind::Syntax) => {
+                // Don't start with an empty list: let the user choose how to repair the file
+                error!("Bookmarks file is malformed: {}", err);
+                self.mount_bookmarks_repair(err.to_string().as_str());
             }
             Err(err) => {
                 self.mount_error(
-                    format!("Could not initialize configuration directory: {}", err).as_str(),
+                    format!(
+                        "Could not initialize bookmarks (at \"{}\", \"{}\"): {}",
+                        bookmarks_file.display(),
+                        config_dir_path.display(),
+                        err
+                    )
+                    .as_str(),
                 );
             }
         }
     }
 
+    /// ### unlock_bookmarks
+    ///
+    /// Unlock bookmarks with the provided master password.
+    /// The password is kept in the context store, so that other activities can unlock bookmarks too
+    pub(super) fn unlock_bookmarks(&mut self, password: String) -> Result<(), String> {
+        if let Some(bookmarks_cli) = self.bookmarks_client.as_mut() {
+            bookmarks_cli
+                .unlock(password.as_str())
+                .map_err(|err| format!("Could not unlock bookmarks: {}", err))?;
+            self.context_mut()
+                .store_mut()
+                .set_string(STORE_KEY_BOOKMARKS_MASTER_PASSWORD, password);
+            self.apply_recents_policy();
+            self.load_bookmarks_lists();
+        }
+        Ok(())
+    }
+
+    /// ### bookmarks_locked
+    ///
+    /// Returns whether bookmarks are still locked by the master password
+    pub(super) fn bookmarks_locked(&self) -> bool {
+        self.bookmarks_client
+            .as_ref()
+            .map(|cli| cli.is_locked())
+            .unwrap_or(false)
+    }
+
     // -- privates
 
+    /// ### apply_recents_policy
+    ///
+    /// Forget the recents exceeding the configured amount, or saved before the configured amount of days
+    fn apply_recents_policy(&mut self) {
+        let max_age: Option<u64> = self.context().config().get_recents_max_age();
+        let removed: usize = match self.bookmarks_client.as_mut() {
+            Some(cli) if !cli.is_locked() => {
+                cli.trim_recents()
+                    + max_age
+                        .map(|days| {
+                            cli.purge_recents(
+                                Duration::from_secs(days.saturating_mul(86400)),
+                                SystemTime::now(),
+                            )
+                        })
+                        .unwrap_or(0)
+            }
+            _ => 0,
+        };
+        if removed > 0 {
+            info!("Forgot {} recent connections", removed);
+            self.write_bookmarks();
+        }
+    }
+
+    /// ### load_bookmarks_lists
+    ///
+    /// Load bookmarks and recents from bookmarks client into lists
+    fn load_bookmarks_lists(&mut self) {
+        if let Some(cli) = self.bookmarks_client.as_ref() {
+            // Load bookmarks into list
+            let mut bookmarks_list: Vec<String> = Vec::with_capacity(cli.iter_bookmarks().count());
+            for bookmark in cli.iter_bookmarks() {
+                bookmarks_list.push(bookmark.clone());
+            }
+            // Load recents into list
+            let mut recents_list: Vec<String> = Vec::with_capacity(cli.iter_recents().count());
+            for recent in cli.iter_recents() {
+                recents_list.push(recent.clone());
+            }
+            self.bookmarks_list = bookmarks_list;
+            self.recents_list = recents_list;
+            // Sort bookmark list
+            self.sort_bookmarks();
+            self.sort_recents();
+        }
+    }
+
     /// ### sort_bookmarks
     ///
     /// Sort bookmarks in list
@@ -223,6 +676,42 @@ impl AuthActivity {
             .sort_by(|a, b| a.to_lowercase().as_str().cmp(b.to_lowercase().as_str()));
     }
 
+    /// ### group_bookmarks
+    ///
+    /// Make the rows for `bookmarks`: groups come first, sorted by name, each one followed by its bookmarks,
+    /// unless collapsed; then the bookmarks which don't belong to any group.
+    /// Bookmarks keep the order they have in `bookmarks`
+    fn group_bookmarks<'a, F>(
+        bookmarks: &'a [String],
+        group_of: F,
+        collapsed: &HashSet<String>,
+    ) -> Vec<BookmarkRow>
+    where
+        F: Fn(&'a str) -> Option<&'a str>,
+    {
+        let mut groups: Vec<(&str, Vec<&String>)> = Vec::new();
+        let mut ungrouped: Vec<&String> = Vec::new();
+        for name in bookmarks.iter() {
+            match group_of(name.as_str()) {
+                Some(group) => match groups.iter_mut().find(|(g, _)| *g == group) {
+                    Some((_, members)) => members.push(name),
+                    None => groups.push((group, vec![name])),
+                },
+                None => ungrouped.push(name),
+            }
+        }
+        groups.sort_by_key(|a| a.0.to_lowercase());
+        let mut rows: Vec<BookmarkRow> = Vec::with_capacity(bookmarks.len() + groups.len());
+        for (group, members) in groups.into_iter() {
+            rows.push(BookmarkRow::Group(group.to_string(), members.len()));
+            if !collapsed.contains(group) {
+                rows.extend(members.into_iter().cloned().map(BookmarkRow::Bookmark));
+            }
+        }
+        rows.extend(ungrouped.into_iter().cloned().map(BookmarkRow::Bookmark));
+        rows
+    }
+
     /// ### sort_recents
     ///
     /// Sort recents in list
@@ -268,6 +757,74 @@ impl AuthActivity {
                 let props = InputPropsBuilder::from(props).with_value(password).build();
                 self.view.update(super::COMPONENT_INPUT_PASSWORD, props);
             }
+            self.update_password_input();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_activities_auth_bookmarks_group_bookmarks() {
+        let bookmarks: Vec<String> = vec![
+            String::from("aws"),
+            String::from("nas"),
+            String::from("personal"),
+            String::from("raspberry"),
+            String::from("staging"),
+        ];
+        fn group_of(name: &str) -> Option<&str> {
+            match name {
+                "aws" | "staging" => Some("work"),
+                "nas" | "raspberry" => Some("Homelab"),
+                _ => None,
+            }
+        }
+        let mut collapsed: HashSet<String> = HashSet::new();
+        assert_eq!(
+            AuthActivity::group_bookmarks(bookmarks.as_slice(), group_of, &collapsed),
+            vec![
+                BookmarkRow::Group(String::from("Homelab"), 2),
+                BookmarkRow::Bookmark(String::from("nas")),
+                BookmarkRow::Bookmark(String::from("raspberry")),
+                BookmarkRow::Group(String::from("work"), 2),
+                BookmarkRow::Bookmark(String::from("aws")),
+                BookmarkRow::Bookmark(String::from("staging")),
+                BookmarkRow::Bookmark(String::from("personal")),
+            ]
+        );
+        // Collapse group
+        collapsed.insert(String::from("Homelab"));
+        assert_eq!(
+            AuthActivity::group_bookmarks(bookmarks.as_slice(), group_of, &collapsed),
+            vec![
+                BookmarkRow::Group(String::from("Homelab"), 2),
+                BookmarkRow::Group(String::from("work"), 2),
+                BookmarkRow::Bookmark(String::from("aws")),
+                BookmarkRow::Bookmark(String::from("staging")),
+                BookmarkRow::Bookmark(String::from("personal")),
+            ]
+        );
+        // No groups
+        assert_eq!(
+            AuthActivity::group_bookmarks(bookmarks.as_slice(), |_| None, &collapsed).len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_ui_activities_auth_bookmarks_fuzzy_match() {
+        assert!(AuthActivity::fuzzy_match("", "raspberry"));
+        assert!(AuthActivity::fuzzy_match("rasp", "raspberry"));
+        assert!(AuthActivity::fuzzy_match("rpby", "raspberry"));
+        assert!(AuthActivity::fuzzy_match("RaSp", "raspberry"));
+        assert!(AuthActivity::fuzzy_match("192 31", "192.168.1.31"));
+        assert!(!AuthActivity::fuzzy_match("yrr", "raspberry"));
+        assert!(!AuthActivity::fuzzy_match("raspberrypi", "raspberry"));
+    }
+}
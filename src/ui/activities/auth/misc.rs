@@ -26,40 +26,63 @@
  * SOFTWARE.
  */
 use super::{AuthActivity, FileTransferParams, FileTransferProtocol};
+use crate::filetransfer::{registry, Builder, FileTransfer, FileTransferErrorType, Proxy};
+use crate::system::bookmarks_client::BookmarksClient;
+use crate::system::ssh_config::{SshConfig, SshHostParams};
+use crate::system::sshkey_storage::SshKeyStorage;
+use crate::utils::fmt::mask_secret;
+// Ext
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// ## ConnectionProbe
+///
+/// Outcome of a successful connection test
+struct ConnectionProbe {
+    banner: Option<String>, // Welcome message of the server
+    connect: Duration,      // Time taken to connect and authenticate
+    list: Duration,         // Time taken to list the directory
+    wrkdir: PathBuf,        // Directory listed
+    entries: usize,         // Entries in the directory
+}
 
 impl AuthActivity {
     /// ### protocol_opt_to_enum
     ///
     /// Convert radio index for protocol into a `FileTransferProtocol`
     pub(super) fn protocol_opt_to_enum(protocol: usize) -> FileTransferProtocol {
-        match protocol {
-            1 => FileTransferProtocol::Scp,
-            2 => FileTransferProtocol::Ftp(false),
-            3 => FileTransferProtocol::Ftp(true),
-            _ => FileTransferProtocol::Sftp,
-        }
+        registry::protocols()
+            .get(protocol)
+            .copied()
+            .unwrap_or(FileTransferProtocol::Sftp)
     }
 
     /// ### protocol_enum_to_opt
     ///
     /// Convert `FileTransferProtocol` enum into radio group index
     pub(super) fn protocol_enum_to_opt(protocol: FileTransferProtocol) -> usize {
-        match protocol {
-            FileTransferProtocol::Sftp => 0,
-            FileTransferProtocol::Scp => 1,
-            FileTransferProtocol::Ftp(false) => 2,
-            FileTransferProtocol::Ftp(true) => 3,
-        }
+        registry::protocols()
+            .iter()
+            .position(|x| *x == protocol)
+            .unwrap_or(0)
+    }
+
+    /// ### protocol_options
+    ///
+    /// Get the options for the protocol radio group
+    pub(super) fn protocol_options() -> Vec<String> {
+        registry::protocols()
+            .iter()
+            .map(|x| x.to_string())
+            .collect()
     }
 
     /// ### get_default_port_for_protocol
     ///
     /// Get the default port for protocol
     pub(super) fn get_default_port_for_protocol(protocol: FileTransferProtocol) -> u16 {
-        match protocol {
-            FileTransferProtocol::Sftp | FileTransferProtocol::Scp => 22,
-            FileTransferProtocol::Ftp(_) => 21,
-        }
+        registry::default_port(protocol).unwrap_or(22)
     }
 
     /// ### is_port_standard
@@ -83,7 +106,9 @@ impl AuthActivity {
 
     /// ### collect_host_params
     ///
-    /// Get input values from fields or return an error if fields are invalid
+    /// Get input values from fields or return an error if fields are invalid.
+    /// For SFTP and SCP, the user and the port, if not set, are taken from the user's ssh config.
+    /// The entry directory is taken from the bookmark pointing to the host, if any
     pub(super) fn collect_host_params(&self) -> Result<FileTransferParams, &'static str> {
         let (address, port, protocol, username, password): (
             String,
@@ -92,12 +117,62 @@ impl AuthActivity {
             String,
             String,
         ) = self.get_input();
+        self.make_host_params(address, port, protocol, username, password)
+    }
+
+    /// ### collect_bookmark_edit_params
+    ///
+    /// Same as `collect_host_params`, but with the values of the bookmark edit dialog
+    pub(super) fn collect_bookmark_edit_params(&self) -> Result<FileTransferParams, &'static str> {
+        let (address, port, protocol, username, password) = self.get_bookmark_edit_input();
+        self.make_host_params(
+            address,
+            port,
+            protocol,
+            username,
+            password.unwrap_or_default(),
+        )
+    }
+
+    /// ### make_host_params
+    ///
+    /// Make connection parameters from the values typed by the user
+    fn make_host_params(
+        &self,
+        address: String,
+        port: u16,
+        protocol: FileTransferProtocol,
+        username: String,
+        password: String,
+    ) -> Result<FileTransferParams, &'static str> {
         if address.is_empty() {
             return Err("Invalid host");
         }
         if port == 0 {
             return Err("Invalid port");
         }
+        let (port, username): (u16, String) = match protocol {
+            FileTransferProtocol::Sftp | FileTransferProtocol::Scp => {
+                let params: SshHostParams = SshConfig::user_config().query(address.as_str());
+                (
+                    match params.port {
+                        Some(p) if port == Self::get_default_port_for_protocol(protocol) => p,
+                        _ => port,
+                    },
+                    match username.is_empty() {
+                        true => params.user.unwrap_or(username),
+                        false => username,
+                    },
+                )
+            }
+            _ => (port, username),
+        };
+        let entry_directory: Option<PathBuf> = self.bookmarks_client.as_ref().and_then(|cli| {
+            cli.get_directory(
+                BookmarksClient::host_key(address.as_str(), port, protocol, username.as_str())
+                    .as_str(),
+            )
+        });
         Ok(FileTransferParams {
             address,
             port,
@@ -110,7 +185,133 @@ impl AuthActivity {
                 true => None,
                 false => Some(password),
             },
-            entry_directory: None,
+            entry_directory,
+        })
+    }
+
+    /// ### test_connection
+    ///
+    /// Connect with `params`, list the entry directory and disconnect right away, reporting the outcome,
+    /// how long it took and the welcome banner of the server, without leaving the activity.
+    /// If `params` are invalid, the error is reported
+    pub(super) fn test_connection(&mut self, params: Result<FileTransferParams, &'static str>) {
+        let params: FileTransferParams = match params {
+            Ok(params) => params,
+            Err(err) => {
+                self.mount_error(err);
+                return;
+            }
+        };
+        let addr: String = format!("{}:{}", params.address, params.port);
+        self.mount_wait(format!("Connecting to {}…", addr).as_str());
+        self.view();
+        let result: Result<ConnectionProbe, String> = self.probe_connection(&params);
+        self.umount_wait();
+        match result {
+            Ok(probe) => {
+                let mut lines: Vec<String> = vec![
+                    format!("Connection to {} succeeded", addr),
+                    format!("Connected in {} ms", probe.connect.as_millis()),
+                    format!(
+                        "Listed {} entries of \"{}\" in {} ms",
+                        probe.entries,
+                        probe.wrkdir.display(),
+                        probe.list.as_millis()
+                    ),
+                ];
+                if let Some(banner) = probe.banner {
+                    lines.push(String::new());
+                    lines.extend(banner.lines().map(|x| x.to_string()));
+                }
+                self.mount_info(lines.as_slice());
+            }
+            Err(err) => {
+                let err: String = match params.password.as_deref() {
+                    Some(password) => mask_secret(err.as_str(), password),
+                    None => err,
+                };
+                self.mount_error(format!("Could not connect to {}: {}", addr, err).as_str());
+            }
+        }
+    }
+
+    /// ### probe_connection
+    ///
+    /// Connect to the remote described by `params`, using the proxy and the TLS options of its bookmark,
+    /// list the entry directory (or the home directory), then disconnect
+    fn probe_connection(&self, params: &FileTransferParams) -> Result<ConnectionProbe, String> {
+        let host: String = BookmarksClient::host_key(
+            params.address.as_str(),
+            params.port,
+            params.protocol,
+            params.username.as_deref().unwrap_or(""),
+        );
+        let proxy: Option<Proxy> = match self
+            .bookmarks_client
+            .as_ref()
+            .and_then(|x| x.get_proxy(host.as_str()))
+            .or_else(|| self.context().config().get_proxy())
+        {
+            Some(proxy) => {
+                Some(Proxy::from_str(proxy.as_str()).map_err(|e| format!("Bad proxy: {}", e))?)
+            }
+            None => None,
+        };
+        let mut client: Box<dyn FileTransfer> = Builder::build(
+            params.protocol,
+            SshKeyStorage::storage_from_config(self.context().config()),
+        )
+        .map_err(|e| e.to_string())?;
+        client.set_stall_timeout(self.context().config().get_stall_timeout());
+        // Testing doesn't stop at the banner sent before authentication
+        client.set_banner_acknowledged(true);
+        client.set_proxy(proxy).map_err(|e| e.to_string())?;
+        client
+            .set_tls(
+                self.bookmarks_client
+                    .as_ref()
+                    .and_then(|x| x.get_tls(host.as_str())),
+            )
+            .map_err(|e| e.to_string())?;
+        let started: Instant = Instant::now();
+        let banner: Option<String> = client
+            .connect(
+                params.address.clone(),
+                params.port,
+                params.username.clone(),
+                params.password.clone(),
+            )
+            .map_err(|e| match e.kind() {
+                FileTransferErrorType::KeyPassphraseRequired => format!(
+                    "the key \"{}\" is protected by a passphrase; connect to type it",
+                    e.msg().unwrap_or_default()
+                ),
+                _ => e.to_string(),
+            })?;
+        let connect: Duration = started.elapsed();
+        // List the directory the session would start from
+        let started: Instant = Instant::now();
+        let listing: Result<(PathBuf, usize), String> = match params.entry_directory.as_ref() {
+            Some(dir) => Ok(dir.clone()),
+            None => client.pwd().map_err(|e| e.to_string()),
+        }
+        .and_then(|dir| {
+            client
+                .list_dir(dir.as_path())
+                .map(|x| (dir.clone(), x.len()))
+                .map_err(|e| format!("could not list \"{}\": {}", dir.display(), e))
+        });
+        let list: Duration = started.elapsed();
+        if let Err(err) = client.disconnect() {
+            warn!("Could not disconnect after testing the connection: {}", err);
+        }
+        let (wrkdir, entries): (PathBuf, usize) = listing?;
+        Ok(ConnectionProbe {
+            banner: banner.filter(|x| !x.trim().is_empty()),
+            connect,
+            list,
+            wrkdir,
+            entries,
         })
     }
 }
@@ -36,11 +36,17 @@ use super::{Activity, Context, ExitReason};
 use crate::config::themes::Theme;
 use crate::filetransfer::{FileTransferParams, FileTransferProtocol};
 use crate::system::bookmarks_client::BookmarksClient;
+use crate::system::startup_profile;
+use crate::ui::components::toast::ToastQueue;
+use crate::ui::focus::FocusRing;
+use crate::ui::modal::ModalStack;
 use crate::utils::git;
+use bookmarks::BookmarksLoader;
 
 // Includes
 use crossterm::event::Event;
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use std::collections::HashSet;
 use tuirealm::{Update, View};
 
 // -- components
@@ -48,22 +54,59 @@ const COMPONENT_TEXT_H1: &str = "TEXT_H1";
 const COMPONENT_TEXT_H2: &str = "TEXT_H2";
 const COMPONENT_TEXT_NEW_VERSION: &str = "TEXT_NEW_VERSION";
 const COMPONENT_TEXT_NEW_VERSION_NOTES: &str = "TEXTAREA_NEW_VERSION";
+const COMPONENT_TEXT_SAFE_MODE: &str = "TEXT_SAFE_MODE";
 const COMPONENT_TEXT_FOOTER: &str = "TEXT_FOOTER";
 const COMPONENT_TEXT_HELP: &str = "TEXT_HELP";
 const COMPONENT_TEXT_ERROR: &str = "TEXT_ERROR";
+const COMPONENT_TEXT_INFO: &str = "TEXT_INFO";
+const COMPONENT_TEXT_WAIT: &str = "TEXT_WAIT";
 const COMPONENT_TEXT_SIZE_ERR: &str = "TEXT_SIZE_ERR";
 const COMPONENT_INPUT_ADDR: &str = "INPUT_ADDRESS";
 const COMPONENT_INPUT_PORT: &str = "INPUT_PORT";
 const COMPONENT_INPUT_USERNAME: &str = "INPUT_USERNAME";
 const COMPONENT_INPUT_PASSWORD: &str = "INPUT_PASSWORD";
 const COMPONENT_INPUT_BOOKMARK_NAME: &str = "INPUT_BOOKMARK_NAME";
+const COMPONENT_INPUT_BOOKMARK_GROUP: &str = "INPUT_BOOKMARK_GROUP";
+const COMPONENT_INPUT_MASTER_PASSWORD: &str = "INPUT_MASTER_PASSWORD";
+const COMPONENT_INPUT_BOOKMARKS_SEARCH: &str = "INPUT_BOOKMARKS_SEARCH";
+const COMPONENT_INPUT_EDIT_ADDR: &str = "INPUT_EDIT_ADDRESS";
+const COMPONENT_INPUT_EDIT_PORT: &str = "INPUT_EDIT_PORT";
+const COMPONENT_INPUT_EDIT_USERNAME: &str = "INPUT_EDIT_USERNAME";
+const COMPONENT_INPUT_EDIT_PASSWORD: &str = "INPUT_EDIT_PASSWORD";
+const COMPONENT_INPUT_EDIT_LOCAL_ROOT: &str = "INPUT_EDIT_LOCAL_ROOT";
+const COMPONENT_INPUT_EDIT_REMOTE_ROOT: &str = "INPUT_EDIT_REMOTE_ROOT";
+const COMPONENT_RADIO_EDIT_PROTOCOL: &str = "RADIO_EDIT_PROTOCOL";
 const COMPONENT_RADIO_PROTOCOL: &str = "RADIO_PROTOCOL";
 const COMPONENT_RADIO_QUIT: &str = "RADIO_QUIT";
 const COMPONENT_RADIO_BOOKMARK_DEL_BOOKMARK: &str = "RADIO_DELETE_BOOKMARK";
 const COMPONENT_RADIO_BOOKMARK_DEL_RECENT: &str = "RADIO_DELETE_RECENT";
 const COMPONENT_RADIO_BOOKMARK_SAVE_PWD: &str = "RADIO_SAVE_PASSWORD";
+const COMPONENT_RADIO_BOOKMARKS_REPAIR: &str = "RADIO_BOOKMARKS_REPAIR";
 const COMPONENT_BOOKMARKS_LIST: &str = "BOOKMARKS_LIST";
 const COMPONENT_RECENTS_LIST: &str = "RECENTS_LIST";
+const COMPONENT_TOAST: &str = "TOAST";
+
+// -- focus
+const FOCUS_RING_FORM: FocusRing = FocusRing::new(&[
+    COMPONENT_RADIO_PROTOCOL,
+    COMPONENT_INPUT_ADDR,
+    COMPONENT_INPUT_PORT,
+    COMPONENT_INPUT_USERNAME,
+    COMPONENT_INPUT_PASSWORD,
+]);
+const FOCUS_RING_EDIT_BOOKMARK: FocusRing = FocusRing::with_tab(&[
+    COMPONENT_RADIO_EDIT_PROTOCOL,
+    COMPONENT_INPUT_EDIT_ADDR,
+    COMPONENT_INPUT_EDIT_PORT,
+    COMPONENT_INPUT_EDIT_USERNAME,
+    COMPONENT_INPUT_EDIT_PASSWORD,
+    COMPONENT_INPUT_EDIT_LOCAL_ROOT,
+    COMPONENT_INPUT_EDIT_REMOTE_ROOT,
+]);
+
+// Toasts
+const TOAST_TTL: usize = 300; // Ticks
+const TOAST_CAPACITY: usize = 4;
 
 // Store keys
 const STORE_KEY_LATEST_VERSION: &str = "AUTH_LATEST_VERSION";
@@ -76,10 +119,17 @@ pub struct AuthActivity {
     exit_reason: Option<ExitReason>,
     context: Option<Context>,
     view: View,
+    modals: ModalStack, // Popups displayed on top of the form
     bookmarks_client: Option<BookmarksClient>,
-    redraw: bool,                // Should ui actually be redrawned?
-    bookmarks_list: Vec<String>, // List of bookmarks
-    recents_list: Vec<String>,   // list of recents
+    bookmarks_loader: Option<BookmarksLoader>, // Bookmarks client being initialized
+    redraw: bool,                              // Should ui actually be redrawned?
+    bookmarks_list: Vec<String>,               // List of bookmarks
+    recents_list: Vec<String>,                 // list of recents
+    collapsed_groups: HashSet<String>,         // Bookmark groups collapsed in the bookmarks list
+    search: String,                            // Filters bookmarks and recents
+    toasts: ToastQueue,                        // Transient notifications
+    password_visible: bool,                    // Whether password input shows its value
+    deferred_load: bool,                       // Whether theme and bookmarks are still to be loaded
 }
 
 impl Default for AuthActivity {
@@ -97,10 +147,17 @@ impl AuthActivity {
             exit_reason: None,
             context: None,
             view: View::init(),
+            modals: ModalStack::new(COMPONENT_RADIO_PROTOCOL),
             bookmarks_client: None,
+            bookmarks_loader: None,
             redraw: true, // True at startup
             bookmarks_list: Vec::new(),
             recents_list: Vec::new(),
+            collapsed_groups: HashSet::new(),
+            search: String::new(),
+            toasts: ToastQueue::new(TOAST_TTL, TOAST_CAPACITY),
+            password_visible: false,
+            deferred_load: false,
         }
     }
 
@@ -111,6 +168,10 @@ impl AuthActivity {
         debug!("Check for updates...");
         // Check version only if unset in the store
         let ctx: &mut Context = self.context_mut();
+        if ctx.safe_mode() {
+            debug!("Safe mode is enabled; skipping check for updates");
+            return;
+        }
         if !ctx.store().isset(STORE_KEY_LATEST_VERSION) {
             debug!("Version is not set in storage");
             if ctx.config().get_check_for_updates() {
@@ -157,6 +218,21 @@ impl AuthActivity {
         self.context.as_mut().unwrap()
     }
 
+    /// ### load_deferred
+    ///
+    /// Load the theme and the bookmarks, which aren't needed to paint the activity the first time
+    fn load_deferred(&mut self) {
+        if self.context_mut().theme_provider_mut().ensure_loaded() {
+            // Components have been mounted with the default theme; popups keep focus
+            self.init();
+        }
+        if self.bookmarks_client.is_none() && !self.context().safe_mode() {
+            // Bookmarks are viewed once loaded
+            self.load_bookmarks_client();
+        }
+        self.redraw = true;
+    }
+
     /// ### theme
     ///
     /// Returns a reference to theme
@@ -187,17 +263,13 @@ impl Activity for AuthActivity {
         self.check_for_updates();
         // Initialize view
         self.init();
-        // Init bookmarks client
-        if self.bookmarks_client.is_none() {
-            self.init_bookmarks_client();
-            // View bookarmsk
-            self.view_bookmarks();
-            self.view_recent_connections();
-        }
+        // Theme and bookmarks are loaded after the first paint
+        self.deferred_load = true;
         // Verify error state from context
         if let Some(err) = self.context_mut().error() {
             self.mount_error(err.as_str());
         }
+        startup_profile::mark("create activity");
         info!("Activity initialized");
     }
 
@@ -216,12 +288,22 @@ impl Activity for AuthActivity {
             self.redraw = true;
             // Handle on resize
             if let Event::Resize(_, h) = event {
+                self.context_mut().on_resize();
                 self.check_minimum_window_size(h);
             }
             // Handle event on view and update
             let msg = self.view.on(event);
             self.update(msg);
         }
+        // Repaint after resize or reattach
+        if self.context_mut().refresh_terminal_size() {
+            self.redraw = true;
+        }
+        // Dismiss expired toasts
+        if !self.toasts.is_empty() && self.toasts.tick() {
+            self.view_toasts();
+            self.redraw = true;
+        }
         // Redraw if necessary
         if self.redraw {
             // View
@@ -229,6 +311,15 @@ impl Activity for AuthActivity {
             // Set redraw to false
             self.redraw = false;
         }
+        if self.deferred_load {
+            self.deferred_load = false;
+            startup_profile::mark("first paint");
+            self.load_deferred();
+        }
+        if self.poll_bookmarks_client(false) {
+            startup_profile::mark("load bookmarks");
+            self.redraw = true;
+        }
     }
 
     /// ### will_umount
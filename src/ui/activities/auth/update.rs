@@ -28,11 +28,16 @@
 // locals
 use super::{
     AuthActivity, FileTransferProtocol, COMPONENT_BOOKMARKS_LIST, COMPONENT_INPUT_ADDR,
-    COMPONENT_INPUT_BOOKMARK_NAME, COMPONENT_INPUT_PASSWORD, COMPONENT_INPUT_PORT,
-    COMPONENT_INPUT_USERNAME, COMPONENT_RADIO_BOOKMARK_DEL_BOOKMARK,
+    COMPONENT_INPUT_BOOKMARKS_SEARCH, COMPONENT_INPUT_BOOKMARK_GROUP,
+    COMPONENT_INPUT_BOOKMARK_NAME, COMPONENT_INPUT_EDIT_ADDR, COMPONENT_INPUT_EDIT_LOCAL_ROOT,
+    COMPONENT_INPUT_EDIT_PASSWORD, COMPONENT_INPUT_EDIT_PORT, COMPONENT_INPUT_EDIT_REMOTE_ROOT,
+    COMPONENT_INPUT_EDIT_USERNAME, COMPONENT_INPUT_MASTER_PASSWORD, COMPONENT_INPUT_PASSWORD,
+    COMPONENT_INPUT_PORT, COMPONENT_RADIO_BOOKMARKS_REPAIR, COMPONENT_RADIO_BOOKMARK_DEL_BOOKMARK,
     COMPONENT_RADIO_BOOKMARK_DEL_RECENT, COMPONENT_RADIO_BOOKMARK_SAVE_PWD,
-    COMPONENT_RADIO_PROTOCOL, COMPONENT_RADIO_QUIT, COMPONENT_RECENTS_LIST, COMPONENT_TEXT_ERROR,
-    COMPONENT_TEXT_HELP, COMPONENT_TEXT_NEW_VERSION_NOTES, COMPONENT_TEXT_SIZE_ERR,
+    COMPONENT_RADIO_EDIT_PROTOCOL, COMPONENT_RADIO_PROTOCOL, COMPONENT_RADIO_QUIT,
+    COMPONENT_RECENTS_LIST, COMPONENT_TEXT_ERROR, COMPONENT_TEXT_HELP, COMPONENT_TEXT_INFO,
+    COMPONENT_TEXT_NEW_VERSION_NOTES, COMPONENT_TEXT_SIZE_ERR, FOCUS_RING_EDIT_BOOKMARK,
+    FOCUS_RING_FORM,
 };
 use crate::ui::keymap::*;
 use tui_realm_stdlib::InputPropsBuilder;
@@ -47,62 +52,21 @@ impl Update for AuthActivity {
     /// The function exits when returns None
     fn update(&mut self, msg: Option<(String, Msg)>) -> Option<(String, Msg)> {
         let ref_msg: Option<(&str, &Msg)> = msg.as_ref().map(|(s, msg)| (s.as_str(), msg));
+        // Move focus through the auth form
+        if let Some((component, msg)) = ref_msg {
+            if let Some(next) = FOCUS_RING_FORM.on_msg(component, msg) {
+                self.focus_panel(next);
+                return None;
+            }
+            if let Some(next) = FOCUS_RING_EDIT_BOOKMARK.on_msg(component, msg) {
+                self.focus_popup(next);
+                return None;
+            }
+        }
         // Match msg
         match ref_msg {
             None => None, // Exit after None
             Some(msg) => match msg {
-                // Focus ( DOWN )
-                (COMPONENT_RADIO_PROTOCOL, key) if key == &MSG_KEY_DOWN => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_INPUT_ADDR);
-                    None
-                }
-                (COMPONENT_INPUT_ADDR, key) if key == &MSG_KEY_DOWN => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_INPUT_PORT);
-                    None
-                }
-                (COMPONENT_INPUT_PORT, key) if key == &MSG_KEY_DOWN => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_INPUT_USERNAME);
-                    None
-                }
-                (COMPONENT_INPUT_USERNAME, key) if key == &MSG_KEY_DOWN => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_INPUT_PASSWORD);
-                    None
-                }
-                (COMPONENT_INPUT_PASSWORD, key) if key == &MSG_KEY_DOWN => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_RADIO_PROTOCOL);
-                    None
-                }
-                // Focus ( UP )
-                (COMPONENT_INPUT_PASSWORD, key) if key == &MSG_KEY_UP => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_INPUT_USERNAME);
-                    None
-                }
-                (COMPONENT_INPUT_USERNAME, key) if key == &MSG_KEY_UP => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_INPUT_PORT);
-                    None
-                }
-                (COMPONENT_INPUT_PORT, key) if key == &MSG_KEY_UP => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_INPUT_ADDR);
-                    None
-                }
-                (COMPONENT_INPUT_ADDR, key) if key == &MSG_KEY_UP => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_RADIO_PROTOCOL);
-                    None
-                }
-                (COMPONENT_RADIO_PROTOCOL, key) if key == &MSG_KEY_UP => {
-                    // Give focus to port
-                    self.view.active(COMPONENT_INPUT_PASSWORD);
-                    None
-                }
                 // Protocol - On Change
                 (COMPONENT_RADIO_PROTOCOL, Msg::OnChange(Payload::One(Value::Usize(protocol)))) => {
                     // If port is standard, update the current port with default for selected protocol
@@ -116,24 +80,122 @@ impl Update for AuthActivity {
                         }
                     }
                 }
+                // Password visibility
+                (COMPONENT_INPUT_PASSWORD, key) if key == &MSG_KEY_CTRL_V => {
+                    self.toggle_password_visibility();
+                    None
+                }
+                // Password strength
+                (COMPONENT_INPUT_PASSWORD, Msg::OnChange(_)) => {
+                    self.update_password_input();
+                    None
+                }
                 // Bookmarks commands
                 // <RIGHT> / <LEFT>
                 (COMPONENT_BOOKMARKS_LIST, key) if key == &MSG_KEY_RIGHT => {
                     // Give focus to recents
-                    self.view.active(COMPONENT_RECENTS_LIST);
+                    self.focus_panel(COMPONENT_RECENTS_LIST);
                     None
                 }
                 (COMPONENT_RECENTS_LIST, key) if key == &MSG_KEY_LEFT => {
                     // Give focus to bookmarks
-                    self.view.active(COMPONENT_BOOKMARKS_LIST);
+                    self.focus_panel(COMPONENT_BOOKMARKS_LIST);
                     None
                 }
                 // <DEL | 'E'>
                 (COMPONENT_BOOKMARKS_LIST, key)
                     if key == &MSG_KEY_DEL || key == &MSG_KEY_CHAR_E =>
                 {
-                    // Show delete popup
-                    self.mount_bookmark_del_dialog();
+                    // Show delete popup; group headers can't be deleted
+                    if let Some(Payload::One(Value::Usize(index))) =
+                        self.view.get_state(COMPONENT_BOOKMARKS_LIST)
+                    {
+                        if self.bookmark_at(index).is_some() {
+                            self.mount_bookmark_del_dialog();
+                        }
+                    }
+                    None
+                }
+                // </> search bookmarks and recents
+                (COMPONENT_BOOKMARKS_LIST, key) | (COMPONENT_RECENTS_LIST, key)
+                    if key == &MSG_KEY_SLASH =>
+                {
+                    self.mount_bookmarks_search();
+                    None
+                }
+                (
+                    COMPONENT_INPUT_BOOKMARKS_SEARCH,
+                    Msg::OnChange(Payload::One(Value::Str(search))),
+                ) => {
+                    self.set_search(search.clone());
+                    self.view_bookmarks();
+                    self.view_recent_connections()
+                }
+                (COMPONENT_INPUT_BOOKMARKS_SEARCH, Msg::OnSubmit(_)) => {
+                    // Keep the filter and move to results
+                    self.focus_panel(COMPONENT_BOOKMARKS_LIST);
+                    None
+                }
+                (COMPONENT_INPUT_BOOKMARKS_SEARCH, key) if key == &MSG_KEY_DOWN => {
+                    self.focus_panel(COMPONENT_BOOKMARKS_LIST);
+                    None
+                }
+                (COMPONENT_INPUT_BOOKMARKS_SEARCH, key) if key == &MSG_KEY_ESC => {
+                    // Clear the filter
+                    self.umount_bookmarks_search();
+                    self.set_search(String::new());
+                    self.focus_panel(COMPONENT_BOOKMARKS_LIST);
+                    self.view_bookmarks();
+                    self.view_recent_connections()
+                }
+                (COMPONENT_INPUT_BOOKMARKS_SEARCH, _) => None,
+                // <SPACE> collapse/expand group
+                (COMPONENT_BOOKMARKS_LIST, key) if key == &MSG_KEY_SPACE => {
+                    match self.view.get_state(COMPONENT_BOOKMARKS_LIST) {
+                        Some(Payload::One(Value::Usize(index))) => {
+                            match self.toggle_bookmark_group(index) {
+                                Some(row) => self.view_bookmarks_at(Some(row)),
+                                None => None,
+                            }
+                        }
+                        _ => None,
+                    }
+                }
+                // <G> move bookmark to group
+                (COMPONENT_BOOKMARKS_LIST, key) if key == &MSG_KEY_CHAR_G => {
+                    if let Some(Payload::One(Value::Usize(index))) =
+                        self.view.get_state(COMPONENT_BOOKMARKS_LIST)
+                    {
+                        if let Some(name) = self.bookmark_at(index) {
+                            let group: Option<String> = self
+                                .bookmarks_client
+                                .as_ref()
+                                .and_then(|cli| cli.get_group(name.as_str()))
+                                .map(|x| x.to_string());
+                            self.mount_bookmark_group_dialog(group.as_deref());
+                        }
+                    }
+                    None
+                }
+                // <M> edit bookmark
+                (COMPONENT_BOOKMARKS_LIST, key) if key == &MSG_KEY_CHAR_M => {
+                    if let Some(Payload::One(Value::Usize(index))) =
+                        self.view.get_state(COMPONENT_BOOKMARKS_LIST)
+                    {
+                        if let Some(name) = self.bookmark_at(index) {
+                            if let Some(bookmark) = self
+                                .bookmarks_client
+                                .as_ref()
+                                .and_then(|cli| cli.get_bookmark(name.as_str()))
+                            {
+                                let mapping = self
+                                    .bookmarks_client
+                                    .as_ref()
+                                    .and_then(|cli| cli.get_bookmark_mapping(name.as_str()));
+                                self.mount_bookmark_edit_dialog(name.as_str(), bookmark, mapping);
+                            }
+                        }
+                    }
                     None
                 }
                 (COMPONENT_RECENTS_LIST, key) if key == &MSG_KEY_DEL || key == &MSG_KEY_CHAR_E => {
@@ -143,15 +205,24 @@ impl Update for AuthActivity {
                 }
                 // Enter
                 (COMPONENT_BOOKMARKS_LIST, Msg::OnSubmit(Payload::One(Value::Usize(idx)))) => {
-                    self.load_bookmark(*idx);
-                    // Give focus to input password
-                    self.view.active(COMPONENT_INPUT_PASSWORD);
-                    None
+                    match self.bookmark_at(*idx) {
+                        None => match self.toggle_bookmark_group(*idx) {
+                            // Group header; collapse/expand
+                            Some(row) => self.view_bookmarks_at(Some(row)),
+                            None => None,
+                        },
+                        Some(_) => {
+                            self.load_bookmark(*idx);
+                            // Give focus to input password
+                            self.focus_panel(COMPONENT_INPUT_PASSWORD);
+                            None
+                        }
+                    }
                 }
                 (COMPONENT_RECENTS_LIST, Msg::OnSubmit(Payload::One(Value::Usize(idx)))) => {
                     self.load_recent(*idx);
                     // Give focus to input password
-                    self.view.active(COMPONENT_INPUT_PASSWORD);
+                    self.focus_panel(COMPONENT_INPUT_PASSWORD);
                     None
                 }
                 // Bookmark radio
@@ -213,6 +284,123 @@ impl Update for AuthActivity {
                     None
                 }
                 (COMPONENT_RADIO_BOOKMARK_DEL_BOOKMARK, _) => None,
+                // Bookmark group
+                (
+                    COMPONENT_INPUT_BOOKMARK_GROUP,
+                    Msg::OnSubmit(Payload::One(Value::Str(group))),
+                ) => {
+                    let group: String = group.clone();
+                    self.umount_bookmark_group_dialog();
+                    match self.view.get_state(COMPONENT_BOOKMARKS_LIST) {
+                        Some(Payload::One(Value::Usize(index))) => {
+                            match self.set_bookmark_group(index, group) {
+                                Some(row) => self.view_bookmarks_at(Some(row)),
+                                None => None,
+                            }
+                        }
+                        _ => None,
+                    }
+                }
+                (COMPONENT_INPUT_BOOKMARK_GROUP, key) if key == &MSG_KEY_ESC => {
+                    self.umount_bookmark_group_dialog();
+                    None
+                }
+                (COMPONENT_INPUT_BOOKMARK_GROUP, _) => None,
+                // Bookmark edit
+                (COMPONENT_RADIO_EDIT_PROTOCOL, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_EDIT_ADDR, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_EDIT_PORT, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_EDIT_USERNAME, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_EDIT_PASSWORD, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_EDIT_LOCAL_ROOT, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_EDIT_REMOTE_ROOT, Msg::OnSubmit(_)) => {
+                    match self.view.get_state(COMPONENT_BOOKMARKS_LIST) {
+                        Some(Payload::One(Value::Usize(index))) => {
+                            // Keep the dialog open if values are invalid
+                            match self.edit_bookmark(index) {
+                                true => {
+                                    self.umount_bookmark_edit_dialog();
+                                    self.view_bookmarks_at(Some(index))
+                                }
+                                false => None,
+                            }
+                        }
+                        _ => {
+                            self.umount_bookmark_edit_dialog();
+                            None
+                        }
+                    }
+                }
+                (COMPONENT_RADIO_EDIT_PROTOCOL, key)
+                | (COMPONENT_INPUT_EDIT_ADDR, key)
+                | (COMPONENT_INPUT_EDIT_PORT, key)
+                | (COMPONENT_INPUT_EDIT_USERNAME, key)
+                | (COMPONENT_INPUT_EDIT_PASSWORD, key)
+                | (COMPONENT_INPUT_EDIT_LOCAL_ROOT, key)
+                | (COMPONENT_INPUT_EDIT_REMOTE_ROOT, key)
+                    if key == &MSG_KEY_CTRL_T =>
+                {
+                    // Test the edited parameters before saving them
+                    self.test_connection(self.collect_bookmark_edit_params());
+                    None
+                }
+                (COMPONENT_RADIO_EDIT_PROTOCOL, key)
+                | (COMPONENT_INPUT_EDIT_ADDR, key)
+                | (COMPONENT_INPUT_EDIT_PORT, key)
+                | (COMPONENT_INPUT_EDIT_USERNAME, key)
+                | (COMPONENT_INPUT_EDIT_PASSWORD, key)
+                | (COMPONENT_INPUT_EDIT_LOCAL_ROOT, key)
+                | (COMPONENT_INPUT_EDIT_REMOTE_ROOT, key)
+                    if key == &MSG_KEY_ESC =>
+                {
+                    self.umount_bookmark_edit_dialog();
+                    None
+                }
+                (COMPONENT_RADIO_EDIT_PROTOCOL, _)
+                | (COMPONENT_INPUT_EDIT_ADDR, _)
+                | (COMPONENT_INPUT_EDIT_PORT, _)
+                | (COMPONENT_INPUT_EDIT_USERNAME, _)
+                | (COMPONENT_INPUT_EDIT_PASSWORD, _)
+                | (COMPONENT_INPUT_EDIT_LOCAL_ROOT, _)
+                | (COMPONENT_INPUT_EDIT_REMOTE_ROOT, _) => None,
+                // Master password
+                (
+                    COMPONENT_INPUT_MASTER_PASSWORD,
+                    Msg::OnSubmit(Payload::One(Value::Str(password))),
+                ) => {
+                    let password: String = password.clone();
+                    self.umount_master_password();
+                    match self.unlock_bookmarks(password) {
+                        Ok(_) => {
+                            self.view_bookmarks();
+                            self.view_recent_connections()
+                        }
+                        Err(err) => {
+                            self.mount_error(err.as_str());
+                            None
+                        }
+                    }
+                }
+                (COMPONENT_INPUT_MASTER_PASSWORD, key) if key == &MSG_KEY_ESC => {
+                    self.umount_master_password();
+                    None
+                }
+                (COMPONENT_INPUT_MASTER_PASSWORD, _) => None,
+                // Bookmarks repair
+                (
+                    COMPONENT_RADIO_BOOKMARKS_REPAIR,
+                    Msg::OnSubmit(Payload::One(Value::Usize(choice))),
+                ) => {
+                    self.umount_bookmarks_repair();
+                    self.repair_bookmarks(*choice);
+                    None
+                }
+                (COMPONENT_RADIO_BOOKMARKS_REPAIR, key) if key == &MSG_KEY_ESC => {
+                    // Go on without bookmarks; the file is left untouched
+                    self.umount_bookmarks_repair();
+                    None
+                }
+                (COMPONENT_RADIO_BOOKMARKS_REPAIR, _) => None,
                 // Error message
                 (COMPONENT_TEXT_ERROR, key) if key == &MSG_KEY_ESC || key == &MSG_KEY_ENTER => {
                     // Umount text error
@@ -220,6 +408,12 @@ impl Update for AuthActivity {
                     None
                 }
                 (COMPONENT_TEXT_ERROR, _) => None,
+                (COMPONENT_TEXT_INFO, key) if key == &MSG_KEY_ESC || key == &MSG_KEY_ENTER => {
+                    // Umount text info
+                    self.umount_info();
+                    None
+                }
+                (COMPONENT_TEXT_INFO, _) => None,
                 (COMPONENT_TEXT_NEW_VERSION_NOTES, key)
                     if key == &MSG_KEY_ESC || key == &MSG_KEY_ENTER =>
                 {
@@ -246,27 +440,39 @@ impl Update for AuthActivity {
                     None
                 }
                 (COMPONENT_TEXT_HELP, _) => None,
+                // Unlock bookmarks
+                (_, key) if key == &MSG_KEY_CTRL_U => {
+                    if self.bookmarks_locked() {
+                        self.mount_master_password();
+                    }
+                    None
+                }
                 // Enter setup
                 (_, key) if key == &MSG_KEY_CTRL_C => {
                     self.exit_reason = Some(super::ExitReason::EnterSetup);
                     None
                 }
+                // Test connection
+                (_, key) if key == &MSG_KEY_CTRL_T => {
+                    // Bookmarks hold proxy and TLS settings for the host
+                    self.poll_bookmarks_client(true);
+                    self.test_connection(self.collect_host_params());
+                    None
+                }
                 // Save bookmark; show popup
                 (_, key) if key == &MSG_KEY_CTRL_S => {
                     // Show popup
                     self.mount_bookmark_save_dialog();
-                    // Give focus to bookmark name
-                    self.view.active(COMPONENT_INPUT_BOOKMARK_NAME);
                     None
                 }
                 (COMPONENT_INPUT_BOOKMARK_NAME, key) if key == &MSG_KEY_DOWN => {
                     // Give focus to pwd
-                    self.view.active(COMPONENT_RADIO_BOOKMARK_SAVE_PWD);
+                    self.focus_popup(COMPONENT_RADIO_BOOKMARK_SAVE_PWD);
                     None
                 }
                 (COMPONENT_RADIO_BOOKMARK_SAVE_PWD, key) if key == &MSG_KEY_UP => {
-                    // Give focus to pwd
-                    self.view.active(COMPONENT_INPUT_BOOKMARK_NAME);
+                    // Give focus to bookmark name
+                    self.focus_popup(COMPONENT_INPUT_BOOKMARK_NAME);
                     None
                 }
                 // Save bookmark
@@ -320,12 +526,12 @@ impl Update for AuthActivity {
                     if key == &MSG_KEY_TAB =>
                 {
                     // Give focus to address
-                    self.view.active(COMPONENT_INPUT_ADDR);
+                    self.focus_panel(COMPONENT_INPUT_ADDR);
                     None
                 }
                 // Any <TAB>, go to bookmarks
                 (_, key) if key == &MSG_KEY_TAB => {
-                    self.view.active(COMPONENT_BOOKMARKS_LIST);
+                    self.focus_panel(COMPONENT_BOOKMARKS_LIST);
                     None
                 }
                 // On submit on any unhandled (connect)
@@ -356,6 +562,8 @@ impl AuthActivity {
     }
 
     fn on_unhandled_submit(&mut self) -> Option<(String, Msg)> {
+        // Bookmarks must be loaded to save the recent connection
+        self.poll_bookmarks_client(true);
         // Validate fields
         match self.collect_host_params() {
             Err(err) => {
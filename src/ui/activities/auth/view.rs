@@ -26,10 +26,14 @@
  * SOFTWARE.
  */
 // Locals
-use super::{AuthActivity, Context, FileTransferProtocol};
+use super::bookmarks::BookmarkRow;
+use super::{AuthActivity, BookmarksClient, Context, FileTransferProtocol};
 use crate::ui::components::bookmark_list::{BookmarkList, BookmarkListPropsBuilder};
+use crate::ui::components::toast::{Toast, ToastPropsBuilder};
+use crate::utils::crypto::password_strength;
 use crate::utils::ui::draw_area_in;
 // Ext
+use std::path::PathBuf;
 use tui_realm_stdlib::{
     input::{Input, InputPropsBuilder},
     label::{Label, LabelPropsBuilder},
@@ -109,7 +113,7 @@ impl AuthActivity {
                     .with_inverted_color(Color::Black)
                     .with_borders(Borders::ALL, BorderType::Rounded, protocol_color)
                     .with_title("Protocol", Alignment::Left)
-                    .with_options(&["SFTP", "SCP", "FTP", "FTPS"])
+                    .with_options(&Self::protocol_options())
                     .with_value(Self::protocol_enum_to_opt(default_protocol))
                     .rewind(true)
                     .build(),
@@ -184,6 +188,21 @@ impl AuthActivity {
                 )),
             );
         }
+        // Safe mode notice
+        if self.context().safe_mode() {
+            self.view.mount(
+                super::COMPONENT_TEXT_SAFE_MODE,
+                Box::new(Span::new(
+                    SpanPropsBuilder::default()
+                        .with_foreground(Color::LightRed)
+                        .with_spans(vec![
+                            TextSpan::new("SAFE MODE").bold(),
+                            TextSpan::from(": configuration, bookmarks and theme have not been loaded; restart termscp without --safe-mode to use them"),
+                        ])
+                        .build(),
+                )),
+            );
+        }
         // Bookmarks
         self.view.mount(
             super::COMPONENT_BOOKMARKS_LIST,
@@ -208,11 +227,61 @@ impl AuthActivity {
                     .build(),
             )),
         );
+        // Toasts
+        self.view.mount(
+            super::COMPONENT_TOAST,
+            Box::new(Toast::new(
+                ToastPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, key_color)
+                    .build(),
+            )),
+        );
         // Load bookmarks
         let _ = self.view_bookmarks();
         let _ = self.view_recent_connections();
         // Active protocol
-        self.view.active(super::COMPONENT_RADIO_PROTOCOL);
+        self.focus_panel(super::COMPONENT_RADIO_PROTOCOL);
+    }
+
+    // -- focus
+
+    /// ### open_popup
+    ///
+    /// Put the mounted popup `id` on top of the others and give focus to it
+    fn open_popup(&mut self, id: &'static str) {
+        self.modals.open(id);
+        self.view.active(id);
+    }
+
+    /// ### close_popup
+    ///
+    /// Umount the popup `id` and give focus back to the popup below it, or to the form
+    /// if no popup is left. Focus never goes to a component which is hidden behind a popup
+    fn close_popup(&mut self, id: &'static str) {
+        self.view.umount(id);
+        if self.modals.is_open(id) {
+            let focus: &'static str = self.modals.close(id);
+            self.view.active(focus);
+        }
+    }
+
+    /// ### focus_popup
+    ///
+    /// Move focus to `component`, which is part of the popup on top (e.g. a form field)
+    pub(super) fn focus_popup(&mut self, component: &'static str) {
+        self.modals.move_focus(component);
+        self.view.active(component);
+    }
+
+    /// ### focus_panel
+    ///
+    /// Give focus to `component`, one of the form fields or lists behind the popups.
+    /// If a popup is displayed, the component will get focus once all the popups are closed
+    pub(super) fn focus_panel(&mut self, component: &'static str) {
+        self.modals.set_base(component);
+        if self.modals.is_empty() {
+            self.view.active(component);
+        }
     }
 
     /// ### view
@@ -220,7 +289,7 @@ impl AuthActivity {
     /// Display view on canvas
     pub(super) fn view(&mut self) {
         let mut ctx: Context = self.context.take().unwrap();
-        let _ = ctx.terminal().draw(|f| {
+        let _ = ctx.draw(|f| {
             // Check window size
             let height: u16 = f.size().height;
             self.check_minimum_window_size(height);
@@ -254,19 +323,39 @@ impl AuthActivity {
                 )
                 .direction(Direction::Vertical)
                 .split(chunks[0]);
+            // Make room for the search box above bookmarks, if open
+            let search_height: u16 =
+                match self.view.get_props(super::COMPONENT_INPUT_BOOKMARKS_SEARCH) {
+                    Some(props) if props.visible => 3,
+                    _ => 0,
+                };
+            let search_chunks = Layout::default()
+                .constraints([Constraint::Length(search_height), Constraint::Min(3)].as_ref())
+                .direction(Direction::Vertical)
+                .split(chunks[1]);
             // Create bookmark chunks
             let bookmark_chunks = Layout::default()
                 .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
                 .direction(Direction::Horizontal)
-                .split(chunks[1]);
+                .split(search_chunks[1]);
             // Render
             // Auth chunks
             self.view
                 .render(super::COMPONENT_TEXT_H1, f, auth_chunks[0]);
             self.view
                 .render(super::COMPONENT_TEXT_H2, f, auth_chunks[1]);
-            self.view
-                .render(super::COMPONENT_TEXT_NEW_VERSION, f, auth_chunks[2]);
+            match self
+                .view
+                .get_props(super::COMPONENT_TEXT_SAFE_MODE)
+                .is_some()
+            {
+                true => self
+                    .view
+                    .render(super::COMPONENT_TEXT_SAFE_MODE, f, auth_chunks[2]),
+                false => self
+                    .view
+                    .render(super::COMPONENT_TEXT_NEW_VERSION, f, auth_chunks[2]),
+            }
             self.view
                 .render(super::COMPONENT_RADIO_PROTOCOL, f, auth_chunks[3]);
             self.view
@@ -280,11 +369,79 @@ impl AuthActivity {
             self.view
                 .render(super::COMPONENT_TEXT_FOOTER, f, auth_chunks[8]);
             // Bookmark chunks
+            if search_height > 0 {
+                self.view
+                    .render(super::COMPONENT_INPUT_BOOKMARKS_SEARCH, f, search_chunks[0]);
+            }
             self.view
                 .render(super::COMPONENT_BOOKMARKS_LIST, f, bookmark_chunks[0]);
             self.view
                 .render(super::COMPONENT_RECENTS_LIST, f, bookmark_chunks[1]);
+            // Toasts
+            self.view.render(super::COMPONENT_TOAST, f, chunks[1]);
             // Popups
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_MASTER_PASSWORD) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 40, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view
+                        .render(super::COMPONENT_INPUT_MASTER_PASSWORD, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_EDIT_PROTOCOL) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 80);
+                    f.render_widget(Clear, popup);
+                    let popup_chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints(
+                            [
+                                Constraint::Length(3), // protocol
+                                Constraint::Length(3), // host
+                                Constraint::Length(3), // port
+                                Constraint::Length(3), // username
+                                Constraint::Length(3), // password
+                                Constraint::Length(3), // local root
+                                Constraint::Length(3), // remote root
+                            ]
+                            .as_ref(),
+                        )
+                        .split(popup);
+                    self.view
+                        .render(super::COMPONENT_RADIO_EDIT_PROTOCOL, f, popup_chunks[0]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_EDIT_ADDR, f, popup_chunks[1]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_EDIT_PORT, f, popup_chunks[2]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_EDIT_USERNAME, f, popup_chunks[3]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_EDIT_PASSWORD, f, popup_chunks[4]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_EDIT_LOCAL_ROOT, f, popup_chunks[5]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_EDIT_REMOTE_ROOT, f, popup_chunks[6]);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_BOOKMARK_GROUP) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 40, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view
+                        .render(super::COMPONENT_INPUT_BOOKMARK_GROUP, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_BOOKMARKS_REPAIR) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 70, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view
+                        .render(super::COMPONENT_RADIO_BOOKMARKS_REPAIR, f, popup);
+                }
+            }
             if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_ERROR) {
                 if props.visible {
                     let popup = draw_area_in(f.size(), 50, 10);
@@ -293,6 +450,22 @@ impl AuthActivity {
                     self.view.render(super::COMPONENT_TEXT_ERROR, f, popup);
                 }
             }
+            if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_INFO) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 20);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_TEXT_INFO, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_WAIT) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_TEXT_WAIT, f, popup);
+                }
+            }
             if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_SIZE_ERR) {
                 if props.visible {
                     let popup = draw_area_in(f.size(), 80, 20);
@@ -384,36 +557,55 @@ impl AuthActivity {
     ///
     /// Make text span from bookmarks
     pub(super) fn view_bookmarks(&mut self) -> Option<(String, Msg)> {
+        self.view_bookmarks_at(None)
+    }
+
+    /// ### view_bookmarks_at
+    ///
+    /// Make text span from bookmarks, selecting the row at `index` if set
+    pub(super) fn view_bookmarks_at(&mut self, index: Option<usize>) -> Option<(String, Msg)> {
         let bookmarks: Vec<String> = self
-            .bookmarks_list
+            .bookmark_rows()
             .iter()
-            .map(|x| {
-                let entry: (String, u16, FileTransferProtocol, String, _) = self
-                    .bookmarks_client
-                    .as_ref()
-                    .unwrap()
-                    .get_bookmark(x)
-                    .unwrap();
-                format!(
-                    "{} ({}://{}@{}:{})",
-                    x,
-                    entry.2.to_string().to_lowercase(),
-                    entry.3,
-                    entry.0,
-                    entry.1
-                )
+            .map(|row| match row {
+                BookmarkRow::Group(group, size) => format!(
+                    "{} {} ({})",
+                    match self.collapsed_groups.contains(group) {
+                        true => "▸",
+                        false => "▾",
+                    },
+                    group,
+                    size
+                ),
+                BookmarkRow::Bookmark(x) => {
+                    let cli: &BookmarksClient = self.bookmarks_client.as_ref().unwrap();
+                    let entry: (String, u16, FileTransferProtocol, String, _) =
+                        cli.get_bookmark(x).unwrap();
+                    format!(
+                        "{}{} ({}://{}@{}:{})",
+                        match cli.get_group(x) {
+                            Some(_) => "  ",
+                            None => "",
+                        },
+                        x,
+                        entry.2.to_string().to_lowercase(),
+                        entry.3,
+                        entry.0,
+                        entry.1
+                    )
+                }
             })
             .collect();
         match self.view.get_props(super::COMPONENT_BOOKMARKS_LIST) {
             None => None,
             Some(props) => {
-                let msg = self.view.update(
-                    super::COMPONENT_BOOKMARKS_LIST,
-                    BookmarkListPropsBuilder::from(props)
-                        .with_bookmarks(bookmarks)
-                        .build(),
-                );
-                msg
+                let mut builder: BookmarkListPropsBuilder = BookmarkListPropsBuilder::from(props);
+                builder.with_bookmarks(bookmarks);
+                if let Some(index) = index {
+                    builder.with_index(index);
+                }
+                self.view
+                    .update(super::COMPONENT_BOOKMARKS_LIST, builder.build())
             }
         }
     }
@@ -423,7 +615,7 @@ impl AuthActivity {
     /// View recent connections
     pub(super) fn view_recent_connections(&mut self) -> Option<(String, Msg)> {
         let bookmarks: Vec<String> = self
-            .recents_list
+            .recent_rows()
             .iter()
             .map(|x| {
                 let entry: (String, u16, FileTransferProtocol, String) = self
@@ -456,8 +648,69 @@ impl AuthActivity {
         }
     }
 
+    /// ### toggle_password_visibility
+    ///
+    /// Show or hide the value of the password input
+    pub(super) fn toggle_password_visibility(&mut self) {
+        self.password_visible = !self.password_visible;
+        self.update_password_input();
+    }
+
+    /// ### update_password_input
+    ///
+    /// Update the password input with its visibility and the strength of the password typed
+    pub(super) fn update_password_input(&mut self) {
+        let password: String = self.get_input_password();
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_PASSWORD) {
+            let input_type: InputType = match self.password_visible {
+                true => InputType::Text,
+                false => InputType::Password,
+            };
+            let mut notes: Vec<String> = Vec::new();
+            if self.password_visible {
+                notes.push(String::from("visible"));
+            }
+            if !password.is_empty() {
+                notes.push(format!(
+                    "strength: {}",
+                    password_strength(password.as_str()).as_str()
+                ));
+            }
+            let label: String = match notes.is_empty() {
+                true => String::from("Password"),
+                false => format!("Password ({})", notes.join(", ")),
+            };
+            let props = InputPropsBuilder::from(props)
+                .with_input(input_type)
+                .with_label(label, Alignment::Left)
+                .build();
+            self.view.update(super::COMPONENT_INPUT_PASSWORD, props);
+        }
+    }
+
+    /// ### view_toasts
+    ///
+    /// Update toast component with current toasts
+    pub(super) fn view_toasts(&mut self) {
+        if let Some(props) = self.view.get_props(super::COMPONENT_TOAST) {
+            let props = ToastPropsBuilder::from(props)
+                .with_toasts(self.toasts.table())
+                .build();
+            self.view.update(super::COMPONENT_TOAST, props);
+        }
+    }
+
     // -- mount
 
+    /// ### show_toast
+    ///
+    /// Push a new toast to the stack and display it
+    pub(super) fn show_toast(&mut self, text: &str) {
+        let color = self.theme().misc_save_dialog;
+        self.toasts.push(TextSpan::new(text).bold().fg(color));
+        self.view_toasts();
+    }
+
     /// ### mount_error
     ///
     /// Mount error box
@@ -477,14 +730,76 @@ impl AuthActivity {
             )),
         );
         // Give focus to error
-        self.view.active(super::COMPONENT_TEXT_ERROR);
+        self.open_popup(super::COMPONENT_TEXT_ERROR);
     }
 
     /// ### umount_error
     ///
     /// Umount error message
     pub(super) fn umount_error(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_ERROR);
+        self.close_popup(super::COMPONENT_TEXT_ERROR);
+    }
+
+    /// ### mount_info
+    ///
+    /// Mount info box; the first line is the headline
+    pub(super) fn mount_info(&mut self, lines: &[String]) {
+        let info_color = self.theme().misc_save_dialog;
+        let texts: Vec<TextSpan> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, x)| match i {
+                0 => TextSpan::new(x.as_str()).bold(),
+                _ => TextSpan::from(x.as_str()),
+            })
+            .collect();
+        self.view.mount(
+            super::COMPONENT_TEXT_INFO,
+            Box::new(Paragraph::new(
+                ParagraphPropsBuilder::default()
+                    .with_foreground(info_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, info_color)
+                    .with_text_alignment(Alignment::Center)
+                    .with_texts(texts)
+                    .build(),
+            )),
+        );
+        // Give focus to info
+        self.open_popup(super::COMPONENT_TEXT_INFO);
+    }
+
+    /// ### umount_info
+    ///
+    /// Umount info message
+    pub(super) fn umount_info(&mut self) {
+        self.close_popup(super::COMPONENT_TEXT_INFO);
+    }
+
+    /// ### mount_wait
+    ///
+    /// Mount wait box
+    pub(super) fn mount_wait(&mut self, text: &str) {
+        self.view.mount(
+            super::COMPONENT_TEXT_WAIT,
+            Box::new(Paragraph::new(
+                ParagraphPropsBuilder::default()
+                    .with_foreground(Color::White)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                    .bold()
+                    .with_text_alignment(Alignment::Center)
+                    .with_texts(vec![TextSpan::from(text)])
+                    .build(),
+            )),
+        );
+        // Give focus to wait
+        self.open_popup(super::COMPONENT_TEXT_WAIT);
+    }
+
+    /// ### umount_wait
+    ///
+    /// Umount wait message
+    pub(super) fn umount_wait(&mut self) {
+        self.close_popup(super::COMPONENT_TEXT_WAIT);
     }
 
     /// ### mount_size_err
@@ -508,14 +823,14 @@ impl AuthActivity {
             )),
         );
         // Give focus to error
-        self.view.active(super::COMPONENT_TEXT_SIZE_ERR);
+        self.open_popup(super::COMPONENT_TEXT_SIZE_ERR);
     }
 
     /// ### umount_size_err
     ///
     /// Umount error size error
     pub(super) fn umount_size_err(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_SIZE_ERR);
+        self.close_popup(super::COMPONENT_TEXT_SIZE_ERR);
     }
 
     /// ### mount_quit
@@ -537,14 +852,14 @@ impl AuthActivity {
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_RADIO_QUIT);
+        self.open_popup(super::COMPONENT_RADIO_QUIT);
     }
 
     /// ### umount_quit
     ///
     /// Umount quit popup
     pub(super) fn umount_quit(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_QUIT);
+        self.close_popup(super::COMPONENT_RADIO_QUIT);
     }
 
     /// ### mount_bookmark_del_dialog
@@ -567,16 +882,14 @@ impl AuthActivity {
             )),
         );
         // Active
-        self.view
-            .active(super::COMPONENT_RADIO_BOOKMARK_DEL_BOOKMARK);
+        self.open_popup(super::COMPONENT_RADIO_BOOKMARK_DEL_BOOKMARK);
     }
 
     /// ### umount_bookmark_del_dialog
     ///
     /// umount delete bookmark dialog
     pub(super) fn umount_bookmark_del_dialog(&mut self) {
-        self.view
-            .umount(super::COMPONENT_RADIO_BOOKMARK_DEL_BOOKMARK);
+        self.close_popup(super::COMPONENT_RADIO_BOOKMARK_DEL_BOOKMARK);
     }
 
     /// ### mount_bookmark_del_dialog
@@ -599,14 +912,49 @@ impl AuthActivity {
             )),
         );
         // Active
-        self.view.active(super::COMPONENT_RADIO_BOOKMARK_DEL_RECENT);
+        self.open_popup(super::COMPONENT_RADIO_BOOKMARK_DEL_RECENT);
     }
 
     /// ### umount_recent_del_dialog
     ///
     /// umount delete recent dialog
     pub(super) fn umount_recent_del_dialog(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_BOOKMARK_DEL_RECENT);
+        self.close_popup(super::COMPONENT_RADIO_BOOKMARK_DEL_RECENT);
+    }
+
+    /// ### mount_bookmarks_repair
+    ///
+    /// Mount the dialog asking how to repair the malformed bookmarks file
+    pub(super) fn mount_bookmarks_repair(&mut self, err: &str) {
+        let warn_color = self.theme().misc_warn_dialog;
+        self.view.mount(
+            super::COMPONENT_RADIO_BOOKMARKS_REPAIR,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(warn_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, warn_color)
+                    .with_title(
+                        format!("Bookmarks file is malformed: {}", err).as_str(),
+                        Alignment::Center,
+                    )
+                    .with_options(&[
+                        String::from("Edit"),
+                        String::from("Restore backup"),
+                        String::from("Start fresh"),
+                    ])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_BOOKMARKS_REPAIR);
+    }
+
+    /// ### umount_bookmarks_repair
+    ///
+    /// Umount the bookmarks repair dialog
+    pub(super) fn umount_bookmarks_repair(&mut self) {
+        self.close_popup(super::COMPONENT_RADIO_BOOKMARKS_REPAIR);
     }
 
     /// ### mount_bookmark_save_dialog
@@ -646,7 +994,7 @@ impl AuthActivity {
             )),
         );
         // Give focus to input bookmark name
-        self.view.active(super::COMPONENT_INPUT_BOOKMARK_NAME);
+        self.open_popup(super::COMPONENT_INPUT_BOOKMARK_NAME);
     }
 
     /// ### umount_bookmark_save_dialog
@@ -654,7 +1002,247 @@ impl AuthActivity {
     /// Umount bookmark save dialog
     pub(super) fn umount_bookmark_save_dialog(&mut self) {
         self.view.umount(super::COMPONENT_RADIO_BOOKMARK_SAVE_PWD);
-        self.view.umount(super::COMPONENT_INPUT_BOOKMARK_NAME);
+        self.close_popup(super::COMPONENT_INPUT_BOOKMARK_NAME);
+    }
+
+    /// ### mount_bookmark_edit_dialog
+    ///
+    /// Mount the form to edit bookmark `name`, filled with its current parameters and root mapping
+    pub(super) fn mount_bookmark_edit_dialog(
+        &mut self,
+        name: &str,
+        bookmark: (String, u16, FileTransferProtocol, String, Option<String>),
+        mapping: Option<(PathBuf, PathBuf)>,
+    ) {
+        let save_color = self.theme().misc_save_dialog;
+        let (address, port, protocol, username, password) = bookmark;
+        let (local_root, remote_root): (String, String) = mapping
+            .map(|(local, remote)| (local.display().to_string(), remote.display().to_string()))
+            .unwrap_or_default();
+        self.view.mount(
+            super::COMPONENT_RADIO_EDIT_PROTOCOL,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(save_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, save_color)
+                    .with_title(format!("Edit \"{}\"", name).as_str(), Alignment::Center)
+                    .with_options(&Self::protocol_options())
+                    .with_value(Self::protocol_enum_to_opt(protocol))
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_EDIT_ADDR,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(save_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, save_color)
+                    .with_label("Remote host", Alignment::Left)
+                    .with_value(address)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_EDIT_PORT,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(save_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, save_color)
+                    .with_label("Port number", Alignment::Left)
+                    .with_input(InputType::Number)
+                    .with_input_len(5)
+                    .with_value(port.to_string())
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_EDIT_USERNAME,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(save_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, save_color)
+                    .with_label("Username", Alignment::Left)
+                    .with_value(username)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_EDIT_PASSWORD,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(save_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, save_color)
+                    .with_label("Password (empty to not save it)", Alignment::Left)
+                    .with_input(InputType::Password)
+                    .with_value(password.unwrap_or_default())
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_EDIT_LOCAL_ROOT,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(save_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, save_color)
+                    .with_label("Local root (empty for no mapping)", Alignment::Left)
+                    .with_value(local_root)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_EDIT_REMOTE_ROOT,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(save_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, save_color)
+                    .with_label("Remote root (empty for no mapping)", Alignment::Left)
+                    .with_value(remote_root)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_EDIT_PROTOCOL);
+        self.focus_popup(super::COMPONENT_INPUT_EDIT_ADDR);
+    }
+
+    /// ### umount_bookmark_edit_dialog
+    ///
+    /// Umount bookmark edit dialog
+    pub(super) fn umount_bookmark_edit_dialog(&mut self) {
+        self.view.umount(super::COMPONENT_INPUT_EDIT_ADDR);
+        self.view.umount(super::COMPONENT_INPUT_EDIT_PORT);
+        self.view.umount(super::COMPONENT_INPUT_EDIT_USERNAME);
+        self.view.umount(super::COMPONENT_INPUT_EDIT_PASSWORD);
+        self.view.umount(super::COMPONENT_INPUT_EDIT_LOCAL_ROOT);
+        self.view.umount(super::COMPONENT_INPUT_EDIT_REMOTE_ROOT);
+        self.close_popup(super::COMPONENT_RADIO_EDIT_PROTOCOL);
+    }
+
+    /// ### get_bookmark_edit_input
+    ///
+    /// Collect the values of the bookmark edit dialog
+    pub(super) fn get_bookmark_edit_input(
+        &self,
+    ) -> (String, u16, FileTransferProtocol, String, Option<String>) {
+        let input = |component: &str| -> String {
+            match self.view.get_state(component) {
+                Some(Payload::One(Value::Str(x))) => x,
+                _ => String::new(),
+            }
+        };
+        let protocol: FileTransferProtocol =
+            match self.view.get_state(super::COMPONENT_RADIO_EDIT_PROTOCOL) {
+                Some(Payload::One(Value::Usize(x))) => Self::protocol_opt_to_enum(x),
+                _ => FileTransferProtocol::Sftp,
+            };
+        let port: u16 = match self.view.get_state(super::COMPONENT_INPUT_EDIT_PORT) {
+            Some(Payload::One(Value::Usize(x))) if x <= 65535 => x as u16,
+            _ => 0,
+        };
+        let password: String = input(super::COMPONENT_INPUT_EDIT_PASSWORD);
+        (
+            input(super::COMPONENT_INPUT_EDIT_ADDR),
+            port,
+            protocol,
+            input(super::COMPONENT_INPUT_EDIT_USERNAME),
+            Some(password).filter(|x| !x.is_empty()),
+        )
+    }
+
+    /// ### get_bookmark_edit_mapping
+    ///
+    /// Collect the local and the remote root typed in the bookmark edit dialog
+    pub(super) fn get_bookmark_edit_mapping(&self) -> (String, String) {
+        let input = |component: &str| -> String {
+            match self.view.get_state(component) {
+                Some(Payload::One(Value::Str(x))) => x.trim().to_string(),
+                _ => String::new(),
+            }
+        };
+        (
+            input(super::COMPONENT_INPUT_EDIT_LOCAL_ROOT),
+            input(super::COMPONENT_INPUT_EDIT_REMOTE_ROOT),
+        )
+    }
+
+    /// ### mount_bookmark_group_dialog
+    ///
+    /// Mount the input to move the selected bookmark into a group
+    pub(super) fn mount_bookmark_group_dialog(&mut self, group: Option<&str>) {
+        let save_color = self.theme().misc_save_dialog;
+        self.view.mount(
+            super::COMPONENT_INPUT_BOOKMARK_GROUP,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(save_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, save_color)
+                    .with_label("Move to group (empty to ungroup)", Alignment::Center)
+                    .with_value(group.unwrap_or_default().to_string())
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_BOOKMARK_GROUP);
+    }
+
+    /// ### umount_bookmark_group_dialog
+    ///
+    /// Umount bookmark group dialog
+    pub(super) fn umount_bookmark_group_dialog(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_BOOKMARK_GROUP);
+    }
+
+    /// ### mount_bookmarks_search
+    ///
+    /// Mount the search box which filters bookmarks and recents
+    pub(super) fn mount_bookmarks_search(&mut self) {
+        let color = self.theme().auth_bookmarks;
+        let search: String = self.search.clone();
+        self.view.mount(
+            super::COMPONENT_INPUT_BOOKMARKS_SEARCH,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, color)
+                    .with_label("Search bookmarks and recents", Alignment::Left)
+                    .with_value(search)
+                    .build(),
+            )),
+        );
+        self.focus_panel(super::COMPONENT_INPUT_BOOKMARKS_SEARCH);
+    }
+
+    /// ### umount_bookmarks_search
+    ///
+    /// Umount the search box
+    pub(super) fn umount_bookmarks_search(&mut self) {
+        self.view.umount(super::COMPONENT_INPUT_BOOKMARKS_SEARCH);
+    }
+
+    /// ### mount_master_password
+    ///
+    /// Mount master password input, to unlock bookmarks
+    pub(super) fn mount_master_password(&mut self) {
+        let password_color = self.theme().auth_password;
+        self.view.mount(
+            super::COMPONENT_INPUT_MASTER_PASSWORD,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(password_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, password_color)
+                    .with_label("Bookmarks master password", Alignment::Center)
+                    .with_input(InputType::Password)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_MASTER_PASSWORD);
+    }
+
+    /// ### umount_master_password
+    ///
+    /// Umount master password input
+    pub(super) fn umount_master_password(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_MASTER_PASSWORD);
     }
 
     /// ### mount_help
@@ -689,6 +1277,18 @@ impl AuthActivity {
                             .add_col(TextSpan::new("<ENTER>").bold().fg(key_color))
                             .add_col(TextSpan::from("         Connect/Load bookmark"))
                             .add_row()
+                            .add_col(TextSpan::new("<SPACE>").bold().fg(key_color))
+                            .add_col(TextSpan::from("         Collapse/expand bookmark group"))
+                            .add_row()
+                            .add_col(TextSpan::new("</>").bold().fg(key_color))
+                            .add_col(TextSpan::from("             Search bookmarks and recents"))
+                            .add_row()
+                            .add_col(TextSpan::new("<G>").bold().fg(key_color))
+                            .add_col(TextSpan::from("             Move bookmark to group"))
+                            .add_row()
+                            .add_col(TextSpan::new("<M>").bold().fg(key_color))
+                            .add_col(TextSpan::from("             Modify selected bookmark"))
+                            .add_row()
                             .add_col(TextSpan::new("<DEL|E>").bold().fg(key_color))
                             .add_col(TextSpan::from("         Delete selected bookmark"))
                             .add_row()
@@ -697,20 +1297,29 @@ impl AuthActivity {
                             .add_row()
                             .add_col(TextSpan::new("<CTRL+S>").bold().fg(key_color))
                             .add_col(TextSpan::from("        Save bookmark"))
+                            .add_row()
+                            .add_col(TextSpan::new("<CTRL+T>").bold().fg(key_color))
+                            .add_col(TextSpan::from("        Test connection"))
+                            .add_row()
+                            .add_col(TextSpan::new("<CTRL+U>").bold().fg(key_color))
+                            .add_col(TextSpan::from("        Unlock bookmarks"))
+                            .add_row()
+                            .add_col(TextSpan::new("<CTRL+V>").bold().fg(key_color))
+                            .add_col(TextSpan::from("        Show/hide password"))
                             .build(),
                     )
                     .build(),
             )),
         );
         // Active help
-        self.view.active(super::COMPONENT_TEXT_HELP);
+        self.open_popup(super::COMPONENT_TEXT_HELP);
     }
 
     /// ### umount_help
     ///
     /// Umount help
     pub(super) fn umount_help(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_HELP);
+        self.close_popup(super::COMPONENT_TEXT_HELP);
     }
 
     /// ### mount_release_notes
@@ -731,7 +1340,7 @@ impl AuthActivity {
                             .build(),
                     )),
                 );
-                self.view.active(super::COMPONENT_TEXT_NEW_VERSION_NOTES);
+                self.open_popup(super::COMPONENT_TEXT_NEW_VERSION_NOTES);
             }
         }
     }
@@ -740,7 +1349,7 @@ impl AuthActivity {
     ///
     /// Umount release notes text area
     pub(super) fn umount_release_notes(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_NEW_VERSION_NOTES);
+        self.close_popup(super::COMPONENT_TEXT_NEW_VERSION_NOTES);
     }
 
     /// ### get_input
@@ -26,7 +26,7 @@
  * SOFTWARE.
  */
 // locals
-use super::{FileTransferActivity, FsEntry};
+use super::{FileTransferActivity, FsEntry, LogLevel};
 use std::path::PathBuf;
 
 impl FileTransferActivity {
@@ -181,4 +181,137 @@ impl FileTransferActivity {
             }
         }
     }
+
+    /// ### action_set_root_mapping
+    ///
+    /// Map the current local working directory to the current remote working directory
+    /// and save the mapping into the bookmarks for this host
+    pub(crate) fn action_set_root_mapping(&mut self) {
+        let local: PathBuf = self.local().wrkdir.clone();
+        let remote: PathBuf = self.remote().wrkdir.clone();
+        self.log(
+            LogLevel::Info,
+            format!("Mapped \"{}\" to \"{}\"", local.display(), remote.display()),
+        );
+        self.browser.set_root_mapping(local.clone(), remote.clone());
+        // Save mapping into bookmarks
+        let host: String = self.bookmarks_host_key();
+        if let Some(client) = self.bookmarks.as_mut() {
+            if client.set_root_mapping(host.as_str(), local, remote) {
+                if let Err(err) = client.write_bookmarks() {
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!("Could not write bookmarks: {}", err),
+                    );
+                }
+            }
+        }
+    }
+
+    /// ### action_set_entry_directory
+    ///
+    /// Save the current remote working directory into the bookmarks for this host,
+    /// as the directory to enter once connected
+    pub(crate) fn action_set_entry_directory(&mut self) {
+        let remote: PathBuf = self.remote().wrkdir.clone();
+        let host: String = self.bookmarks_host_key();
+        let mut result: Option<Result<(), String>> = None;
+        if let Some(client) = self.bookmarks.as_mut() {
+            if client.set_directory(host.as_str(), Some(remote.clone())) {
+                result = Some(client.write_bookmarks().map_err(|err| err.to_string()));
+            }
+        }
+        match result {
+            Some(Ok(())) => self.log(
+                LogLevel::Info,
+                format!("\"{}\" set as entry directory", remote.display()),
+            ),
+            Some(Err(err)) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not write bookmarks: {}", err),
+            ),
+            None => self.log_and_alert(
+                LogLevel::Warn,
+                String::from("There's no bookmark for this host"),
+            ),
+        }
+    }
+
+    /// ### action_set_local_entry_directory
+    ///
+    /// Save the current local working directory into the bookmarks for this host,
+    /// as the local directory to enter once connected
+    pub(crate) fn action_set_local_entry_directory(&mut self) {
+        let local: PathBuf = self.local().wrkdir.clone();
+        let host: String = self.bookmarks_host_key();
+        let mut result: Option<Result<(), String>> = None;
+        if let Some(client) = self.bookmarks.as_mut() {
+            if client.set_local_directory(host.as_str(), Some(local.clone())) {
+                result = Some(client.write_bookmarks().map_err(|err| err.to_string()));
+            }
+        }
+        match result {
+            Some(Ok(())) => self.log(
+                LogLevel::Info,
+                format!("\"{}\" set as local entry directory", local.display()),
+            ),
+            Some(Err(err)) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not write bookmarks: {}", err),
+            ),
+            None => self.log_and_alert(
+                LogLevel::Warn,
+                String::from("There's no bookmark for this host"),
+            ),
+        }
+    }
+
+    /// ### init_root_mapping
+    ///
+    /// Initialize root mapping from bookmarks for this host;
+    /// if no bookmark defines it, the current working directories are mapped to each other
+    pub(crate) fn init_root_mapping(&mut self) {
+        let host: String = self.bookmarks_host_key();
+        let (local, remote): (PathBuf, PathBuf) = match self
+            .bookmarks
+            .as_ref()
+            .and_then(|x| x.get_root_mapping(host.as_str()))
+        {
+            Some(mapping) => mapping,
+            None => (self.local().wrkdir.clone(), self.remote().wrkdir.clone()),
+        };
+        self.log(
+            LogLevel::Info,
+            format!("Mapped \"{}\" to \"{}\"", local.display(), remote.display()),
+        );
+        self.browser.set_root_mapping(local, remote);
+    }
+
+    /// ### action_jump_remote_to_mapped_dir
+    ///
+    /// Change remote directory to the one matching the local working directory in the root mapping
+    pub(crate) fn action_jump_remote_to_mapped_dir(&mut self) {
+        let local: PathBuf = self.local().wrkdir.clone();
+        match self.browser.map_to_remote(local.as_path()) {
+            Some(remote) => self.remote_changedir(remote.as_path(), true),
+            None => self.log_and_alert(
+                LogLevel::Warn,
+                format!("\"{}\" is outside of the mapped root", local.display()),
+            ),
+        }
+    }
+
+    /// ### action_jump_local_to_mapped_dir
+    ///
+    /// Change local directory to the one matching the remote working directory in the root mapping
+    pub(crate) fn action_jump_local_to_mapped_dir(&mut self) {
+        let remote: PathBuf = self.remote().wrkdir.clone();
+        match self.browser.map_to_local(remote.as_path()) {
+            Some(local) => self.local_changedir(local.as_path(), true),
+            None => self.log_and_alert(
+                LogLevel::Warn,
+                format!("\"{}\" is outside of the mapped root", remote.display()),
+            ),
+        }
+    }
 }
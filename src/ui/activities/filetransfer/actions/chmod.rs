@@ -0,0 +1,229 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, FsEntry, LogLevel, SelectedEntry};
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// ## ChmodOptions
+///
+/// Describes how permissions must be changed
+pub(crate) struct ChmodOptions {
+    pub file_mode: Option<(u8, u8, u8)>, // Mode to apply to files
+    pub dir_mode: Option<(u8, u8, u8)>,  // Mode to apply to directories
+    pub owner: Option<String>,           // Owner to apply to both (`user[:group]`)
+    pub recursive: bool,                 // Apply to the content of directories too
+}
+
+impl ChmodOptions {
+    /// ### mode_for
+    ///
+    /// Get the mode to apply to `entry`
+    fn mode_for(&self, entry: &FsEntry) -> Option<(u8, u8, u8)> {
+        match entry.is_dir() {
+            true => self.dir_mode,
+            false => self.file_mode,
+        }
+    }
+}
+
+impl FileTransferActivity {
+    pub(crate) fn action_local_chmod(&mut self, opts: ChmodOptions) {
+        let entries: Vec<FsEntry> = match self.get_local_selected_entries() {
+            SelectedEntry::One(entry) => vec![entry],
+            SelectedEntry::Many(entries) => {
+                // Marks have been consumed
+                self.local_mut().clear_marks();
+                entries
+            }
+            SelectedEntry::None => return,
+        };
+        self.chmod_entries(entries, &opts, false);
+        self.reload_local_dir();
+    }
+
+    pub(crate) fn action_remote_chmod(&mut self, opts: ChmodOptions) {
+        let entries: Vec<FsEntry> = match self.get_remote_selected_entries() {
+            SelectedEntry::One(entry) => vec![entry],
+            SelectedEntry::Many(entries) => {
+                // Marks have been consumed
+                self.remote_mut().clear_marks();
+                entries
+            }
+            SelectedEntry::None => return,
+        };
+        self.chmod_entries(entries, &opts, true);
+        self.reload_remote_dir();
+    }
+
+    /// ### chmod_entries
+    ///
+    /// Apply `opts` to entries (and to their content, if recursive) on localhost or on remote,
+    /// reporting the progress. The process can be aborted as a transfer
+    fn chmod_entries(&mut self, entries: Vec<FsEntry>, opts: &ChmodOptions, remote: bool) {
+        self.transfer.reset();
+        self.mount_progress_bar(String::from("Changing permissions…"));
+        let low_bandwidth: bool = self.low_bandwidth();
+        let mut last_input_event_fetch: Option<Instant> = None;
+        // Collect targets
+        let mut targets: Vec<FsEntry> = Vec::new();
+        let mut stack: Vec<FsEntry> = entries;
+        while let Some(entry) = stack.pop() {
+            if self.transfer.aborted() {
+                break;
+            }
+            if opts.recursive && entry.is_dir() {
+                self.update_progress_bar(format!("Scanning \"{}\"…", entry.get_name()));
+                self.view();
+                match self.scan_dir_or_deny(entry.get_abs_path().as_path(), remote) {
+                    // Symlinks are not followed, as `chmod -R` does
+                    Ok(children) => stack.extend(children.into_iter().filter(|x| !x.is_symlink())),
+                    Err(err) => self.log(
+                        LogLevel::Error,
+                        format!(
+                            "Could not scan directory \"{}\": {}",
+                            entry.get_abs_path().display(),
+                            err
+                        ),
+                    ),
+                }
+                self.read_input_event();
+            }
+            targets.push(entry);
+        }
+        // Apply
+        self.transfer.full.init(targets.len());
+        self.transfer.partial.init(targets.len());
+        self.transfer.files.init(targets.len());
+        let mut last_progress_val: f64 = 0.0;
+        let mut changed: usize = 0;
+        for entry in targets.iter() {
+            if last_input_event_fetch.is_none()
+                || last_input_event_fetch
+                    .unwrap_or_else(Instant::now)
+                    .elapsed()
+                    .as_millis()
+                    >= Self::input_poll_interval(low_bandwidth)
+            {
+                self.read_input_event();
+                last_input_event_fetch = Some(Instant::now());
+            }
+            if self.transfer.aborted() {
+                break;
+            }
+            self.transfer.files.start_file();
+            match self.chmod_entry(entry, opts, remote) {
+                Ok(_) => changed += 1,
+                Err(err) => self.log(
+                    LogLevel::Error,
+                    format!(
+                        "Could not change permissions for \"{}\": {}",
+                        entry.get_abs_path().display(),
+                        err
+                    ),
+                ),
+            }
+            self.transfer.files.end_file();
+            self.transfer.partial.update_progress(1);
+            self.transfer.full.update_progress(1);
+            if self.should_redraw_progress(low_bandwidth, last_progress_val) {
+                self.update_progress_bar(format!("Changing \"{}\"…", entry.get_name()));
+                self.view();
+                last_progress_val = self.transfer.partial.calc_progress();
+            }
+        }
+        self.umount_progress_bar();
+        match self.transfer.aborted() {
+            true => self.log_and_alert(
+                LogLevel::Warn,
+                format!(
+                    "Changing permissions aborted after {} entries out of {}",
+                    changed,
+                    targets.len()
+                ),
+            ),
+            false => self.log(
+                LogLevel::Info,
+                format!("Changed permissions for {} entries", changed),
+            ),
+        }
+        self.report_denied_entries();
+    }
+
+    /// ### chmod_entry
+    ///
+    /// Change mode and owner for a single entry on localhost or on remote
+    fn chmod_entry(
+        &mut self,
+        entry: &FsEntry,
+        opts: &ChmodOptions,
+        remote: bool,
+    ) -> Result<(), String> {
+        let path: PathBuf = entry.get_abs_path();
+        if let Some(pex) = opts.mode_for(entry) {
+            match remote {
+                true => self
+                    .client
+                    .chmod(path.as_path(), pex)
+                    .map_err(|e| e.to_string())?,
+                false => self.local_chmod(path.as_path(), pex)?,
+            }
+        }
+        if let Some(owner) = opts.owner.as_deref() {
+            match remote {
+                true => self
+                    .client
+                    .chown(path.as_path(), owner)
+                    .map_err(|e| e.to_string())?,
+                false => self.local_chown(path.as_path(), owner)?,
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(target_family = "unix")]
+    fn local_chmod(&mut self, path: &Path, pex: (u8, u8, u8)) -> Result<(), String> {
+        self.host.chmod(path, pex).map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_family = "unix")]
+    fn local_chown(&mut self, path: &Path, owner: &str) -> Result<(), String> {
+        self.host.chown(path, owner).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(target_family = "unix"))]
+    fn local_chmod(&mut self, _path: &Path, _pex: (u8, u8, u8)) -> Result<(), String> {
+        Err(String::from("permissions are not supported on this system"))
+    }
+
+    #[cfg(not(target_family = "unix"))]
+    fn local_chown(&mut self, _path: &Path, _owner: &str) -> Result<(), String> {
+        Err(String::from("owners are not supported on this system"))
+    }
+}
@@ -29,6 +29,8 @@
 use super::{FileTransferActivity, FsEntry, LogLevel, SelectedEntry, TransferPayload};
 use crate::filetransfer::FileTransferErrorType;
 use crate::fs::FsFile;
+use crate::utils::path::duplicate_name;
+
 use std::path::{Path, PathBuf};
 
 impl FileTransferActivity {
@@ -52,6 +54,8 @@ impl FileTransferActivity {
                     dest_path.push(entry.get_name());
                     self.local_copy_file(entry, dest_path.as_path());
                 }
+                // Marks have been consumed
+                self.local_mut().clear_marks();
                 // Reload entries
                 self.reload_local_dir();
             }
@@ -79,6 +83,8 @@ impl FileTransferActivity {
                     dest_path.push(entry.get_name());
                     self.remote_copy_file(entry, dest_path.as_path());
                 }
+                // Marks have been consumed
+                self.remote_mut().clear_marks();
                 // Reload entries
                 self.reload_remote_dir();
             }
@@ -86,6 +92,67 @@ impl FileTransferActivity {
         }
     }
 
+    /// ### action_local_duplicate
+    ///
+    /// Copy selected entries next to themselves, as `copy_of_{NAME}`
+    pub(crate) fn action_local_duplicate(&mut self) {
+        let entries: Vec<FsEntry> = match self.get_local_selected_entries() {
+            SelectedEntry::One(entry) => vec![entry],
+            SelectedEntry::Many(entries) => entries,
+            SelectedEntry::None => vec![],
+        };
+        let mut names: Vec<String> = self
+            .local()
+            .iter_files_all()
+            .map(|x| x.get_name().to_string())
+            .collect();
+        for entry in entries.iter() {
+            let dest_path: PathBuf = Self::duplicate_path(entry, &mut names);
+            self.local_copy_file(entry, dest_path.as_path());
+        }
+        // Reload entries
+        self.reload_local_dir();
+    }
+
+    /// ### action_remote_duplicate
+    ///
+    /// Copy selected entries next to themselves, as `copy_of_{NAME}`.
+    /// Server-side copy is used when supported by the protocol
+    pub(crate) fn action_remote_duplicate(&mut self) {
+        let entries: Vec<FsEntry> = match self.get_remote_selected_entries() {
+            SelectedEntry::One(entry) => vec![entry],
+            SelectedEntry::Many(entries) => entries,
+            SelectedEntry::None => vec![],
+        };
+        let mut names: Vec<String> = self
+            .remote()
+            .iter_files_all()
+            .map(|x| x.get_name().to_string())
+            .collect();
+        for entry in entries.into_iter() {
+            let dest_path: PathBuf = Self::duplicate_path(&entry, &mut names);
+            self.remote_copy_file(entry, dest_path.as_path());
+        }
+        // Reload entries
+        self.reload_remote_dir();
+    }
+
+    /// ### duplicate_path
+    ///
+    /// Get the path for the copy of `entry`, avoiding the names in `names`.
+    /// The chosen name is pushed to `names`
+    fn duplicate_path(entry: &FsEntry, names: &mut Vec<String>) -> PathBuf {
+        let name: String = duplicate_name(entry.get_name(), |x| names.iter().any(|n| n == x));
+        let mut dest_path: PathBuf = entry
+            .get_abs_path()
+            .parent()
+            .map(|x| x.to_path_buf())
+            .unwrap_or_default();
+        dest_path.push(name.as_str());
+        names.push(name);
+        dest_path
+    }
+
     fn local_copy_file(&mut self, entry: &FsEntry, dest: &Path) {
         match self.host.copy(entry, dest) {
             Ok(_) => {
@@ -43,6 +43,8 @@ impl FileTransferActivity {
                     // Delete file
                     self.local_remove_file(entry);
                 }
+                // Marks have been consumed
+                self.local_mut().clear_marks();
                 // Reload entries
                 self.reload_local_dir();
             }
@@ -64,6 +66,8 @@ impl FileTransferActivity {
                     // Delete file
                     self.remote_remove_file(entry);
                 }
+                // Marks have been consumed
+                self.remote_mut().clear_marks();
                 // Reload entries
                 self.reload_remote_dir();
             }
@@ -94,6 +98,12 @@ impl FileTransferActivity {
     }
 
     pub(crate) fn remote_remove_file(&mut self, entry: &FsEntry) {
+        // Move to trash, unless the entry is already in there
+        if let Some(trash) = self.remote_trash() {
+            if !entry.get_abs_path().starts_with(trash.as_path()) {
+                return self.remote_trash_file(entry, trash.as_path());
+            }
+        }
         match self.client.remove(entry) {
             Ok(_) => {
                 self.log(
@@ -0,0 +1,204 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::lib::difftool;
+use super::super::lib::verify::DriftKind;
+use super::{FileTransferActivity, FsEntry, LogLevel, TransferPayload};
+use crate::fs::FsFile;
+// Ext
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+
+impl FileTransferActivity {
+    /// ### action_diff_drift
+    ///
+    /// Compare the local and the remote version of the file at `row` of the drift report
+    /// with the configured diff tool
+    pub(crate) fn action_diff_drift(&mut self, row: usize) {
+        let drift: Option<(PathBuf, PathBuf, DriftKind)> =
+            self.drift_report.as_ref().and_then(|report| {
+                report.drifts.get(row).map(|drift| {
+                    (
+                        report.local.join(drift.path.as_path()),
+                        report.remote.join(drift.path.as_path()),
+                        drift.kind.clone(),
+                    )
+                })
+            });
+        let (local, remote): (PathBuf, PathBuf) = match drift {
+            Some((local, remote, DriftKind::SizeMismatch { .. }))
+            | Some((local, remote, DriftKind::HashMismatch { .. })) => (local, remote),
+            Some(_) => {
+                self.log_and_alert(
+                    LogLevel::Warn,
+                    String::from("Only files existing on both sides can be compared"),
+                );
+                return;
+            }
+            None => return,
+        };
+        let tool: String = match self.config().get_diff_tool() {
+            Some(tool) => tool.to_string(),
+            None => {
+                self.log_and_alert(
+                    LogLevel::Warn,
+                    String::from("No diff tool configured; set it up in the configuration"),
+                );
+                return;
+            }
+        };
+        let file: FsFile = match self.client.stat(remote.as_path()) {
+            Ok(FsEntry::File(file)) => file,
+            Ok(FsEntry::Directory(_)) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("\"{}\" is not a file", remote.display()),
+                );
+                return;
+            }
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not stat \"{}\": {}", remote.display(), err),
+                );
+                return;
+            }
+        };
+        let result: Result<bool, String> = self
+            .download_remote_copy(&file)
+            .and_then(|copy| self.run_diff_tool(tool.as_str(), local.as_path(), copy.as_path()));
+        if let Err(err) = result {
+            self.log_and_alert(LogLevel::Error, err);
+        }
+    }
+
+    /// ### resolve_edit_conflict
+    ///
+    /// Called before writing back a remote file edited in `edited`.
+    /// If `file` has changed on the remote since it was downloaded, the merge tool (if configured)
+    /// is run with the edited file as `{local}` and the current remote file as `{remote}`;
+    /// the merge result is expected to be saved into `{local}`.
+    /// Returns error if the changes must not be written to the remote
+    pub(super) fn resolve_edit_conflict(
+        &mut self,
+        file: &FsFile,
+        edited: &Path,
+    ) -> Result<(), String> {
+        let current: FsFile = match self.client.stat(file.abs_path.as_path()) {
+            Ok(FsEntry::File(current)) => current,
+            // File has been removed or replaced; nothing to merge with
+            _ => return Ok(()),
+        };
+        if current.size == file.size && current.last_change_time == file.last_change_time {
+            return Ok(());
+        }
+        let tool: String = match self.config().get_merge_tool() {
+            Some(tool) => tool.to_string(),
+            None => {
+                self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "File \"{}\" has changed on remote while being edited; overwriting it",
+                        file.abs_path.display()
+                    ),
+                );
+                return Ok(());
+            }
+        };
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "File \"{}\" has changed on remote while being edited; running merge tool",
+                file.abs_path.display()
+            ),
+        );
+        let copy: PathBuf = self.download_remote_copy(&current)?;
+        match self.run_diff_tool(tool.as_str(), edited, copy.as_path())? {
+            true => Ok(()),
+            false => Err(format!(
+                "Merge tool failed; changes to \"{}\" haven't been written",
+                file.abs_path.display()
+            )),
+        }
+    }
+
+    /// ### download_remote_copy
+    ///
+    /// Download remote file into the `remote` directory of the cache, keeping its name,
+    /// so that it can't clash with the temporary files used to edit remote files
+    fn download_remote_copy(&mut self, file: &FsFile) -> Result<PathBuf, String> {
+        let mut tmpfile: PathBuf = match self.cache.as_ref() {
+            Some(cache) => cache.path().join("remote"),
+            None => {
+                return Err(String::from(
+                    "Could not create tempfile: cache not available",
+                ))
+            }
+        };
+        if let Err(err) = create_dir_all(tmpfile.as_path()) {
+            return Err(format!(
+                "Could not create directory \"{}\": {}",
+                tmpfile.display(),
+                err
+            ));
+        }
+        tmpfile.push(file.name.as_str());
+        match self.filetransfer_recv(TransferPayload::File(file.clone()), tmpfile.as_path(), None) {
+            Err(err) => Err(format!(
+                "Could not download {} to temporary file: {}",
+                file.abs_path.display(),
+                err
+            )),
+            Ok(()) => Ok(tmpfile),
+        }
+    }
+
+    /// ### run_diff_tool
+    ///
+    /// Run diff/merge tool command on local and remote paths, giving it the terminal.
+    /// Returns whether the tool exited successfully
+    fn run_diff_tool(&mut self, tool: &str, local: &Path, remote: &Path) -> Result<bool, String> {
+        let command: String = difftool::expand_command(tool, local, remote);
+        self.log(LogLevel::Info, format!("Running \"{}\"…", command));
+        self.suspend_terminal();
+        let result = difftool::run_command(command.as_str());
+        self.resume_terminal();
+        match result {
+            Ok(status) => {
+                if !status.success() {
+                    self.log(
+                        LogLevel::Warn,
+                        format!("\"{}\" exited with {}", command, status),
+                    );
+                }
+                Ok(status.success())
+            }
+            Err(err) => Err(format!("Could not run \"{}\": {}", command, err)),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::browser::FileExplorerTab;
+use super::super::lib::dry_run::{DryRunAction, DryRunReport};
+use super::super::{ConflictAction, DryRunOperation};
+use super::{FileTransferActivity, FsEntry, LogLevel, SelectedEntry, TransferDirection};
+
+use std::path::PathBuf;
+
+impl FileTransferActivity {
+    /// ### action_dry_run_transfer
+    ///
+    /// Show what the pending transfer is going to do with the entries selected in the manifest
+    pub(crate) fn action_dry_run_transfer(&mut self) {
+        let pending = match self.pending_transfer.take() {
+            Some(pending) => pending,
+            None => return,
+        };
+        self.mount_blocking_wait("Checking destination…");
+        let mut report: DryRunReport = DryRunReport::default();
+        // Destination of the directories which contain the current entry, by depth
+        let mut parents: Vec<PathBuf> = Vec::new();
+        for entry in pending.manifest.iter() {
+            parents.truncate(entry.depth);
+            let name: &str = match (entry.depth, pending.dst_name.as_deref()) {
+                (0, Some(name)) => name,
+                _ => entry.name.as_str(),
+            };
+            let target: PathBuf = match parents.last() {
+                Some(parent) => parent.join(name),
+                None => pending.dest.join(name),
+            };
+            if entry.is_dir {
+                parents.push(target.clone());
+            }
+            if !entry.selected {
+                continue;
+            }
+            let existing: Option<FsEntry> = match pending.direction {
+                TransferDirection::Upload => self.client.stat(target.as_path()).ok(),
+                TransferDirection::Download => self.host.stat(target.as_path()).ok(),
+            };
+            let action: Option<DryRunAction> = match (entry.is_dir, existing) {
+                (true, Some(FsEntry::Directory(_))) => None,
+                (true, _) => Some(DryRunAction::Mkdir),
+                (false, Some(FsEntry::File(existing))) => {
+                    match self.skip_unchanged()
+                        && Self::is_unchanged(entry.size, entry.last_change_time, &existing)
+                    {
+                        true => Some(DryRunAction::Skip),
+                        false => Some(match self.options.conflict_policy {
+                            None => DryRunAction::Ask,
+                            Some(ConflictAction::Overwrite) => DryRunAction::Overwrite,
+                            Some(ConflictAction::Skip) => DryRunAction::Skip,
+                            Some(ConflictAction::Resume) => DryRunAction::Resume,
+                            Some(ConflictAction::Rename) => DryRunAction::Rename,
+                        }),
+                    }
+                }
+                (false, _) => Some(DryRunAction::Copy),
+            };
+            if let Some(action) = action {
+                report.push(action, target, entry.size);
+            }
+        }
+        self.umount_wait();
+        self.pending_transfer = Some(pending);
+        self.mount_dry_run_report(&report);
+        self.dry_run = Some(DryRunOperation::Transfer);
+    }
+
+    /// ### action_dry_run_delete
+    ///
+    /// Show the entries which are going to be removed by deleting the selected files
+    pub(crate) fn action_dry_run_delete(&mut self) {
+        let (selected, remote): (SelectedEntry, bool) = match self.browser.tab() {
+            FileExplorerTab::Local => (self.get_local_selected_entries(), false),
+            FileExplorerTab::Remote => (self.get_remote_selected_entries(), true),
+            FileExplorerTab::FindLocal => (self.get_found_selected_entries(), false),
+            FileExplorerTab::FindRemote => (self.get_found_selected_entries(), true),
+        };
+        let entries: Vec<FsEntry> = match selected {
+            SelectedEntry::One(entry) => vec![entry],
+            SelectedEntry::Many(entries) => entries,
+            SelectedEntry::None => return,
+        };
+        self.mount_blocking_wait("Scanning files to remove…");
+        let trash: Option<PathBuf> = match remote {
+            true => self.remote_trash(),
+            false => None,
+        };
+        let mut report: DryRunReport = DryRunReport::default();
+        for entry in entries.iter() {
+            match trash.as_deref() {
+                // Entries are moved to trash as a whole, unless they're already in there
+                Some(trash) if !entry.get_abs_path().starts_with(trash) => {
+                    report.push(DryRunAction::Trash, entry.get_abs_path(), entry.get_size())
+                }
+                _ => self.scan_dry_run_delete(entry, remote, &mut report),
+            }
+        }
+        self.umount_wait();
+        self.report_denied_entries();
+        self.mount_dry_run_report(&report);
+        self.dry_run = Some(DryRunOperation::Delete);
+    }
+
+    /// ### action_close_dry_run
+    ///
+    /// Close the dry run report, returning the operation it was made for
+    pub(crate) fn action_close_dry_run(&mut self) -> Option<DryRunOperation> {
+        self.umount_dry_run_report();
+        self.dry_run.take()
+    }
+
+    /// ### scan_dry_run_delete
+    ///
+    /// Push `entry` to report, after the content of the directory, which is removed first
+    fn scan_dry_run_delete(&mut self, entry: &FsEntry, remote: bool, report: &mut DryRunReport) {
+        if let FsEntry::Directory(dir) = entry {
+            match self.scan_dir_or_deny(dir.abs_path.as_path(), remote) {
+                Ok(mut entries) => {
+                    entries.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+                    for entry in entries.iter() {
+                        self.scan_dry_run_delete(entry, remote, report);
+                    }
+                }
+                Err(err) => self.log(
+                    LogLevel::Error,
+                    format!(
+                        "Could not scan directory \"{}\": {}",
+                        dir.abs_path.display(),
+                        err
+                    ),
+                ),
+            }
+        }
+        report.push(DryRunAction::Delete, entry.get_abs_path(), entry.get_size());
+    }
+}
@@ -26,7 +26,9 @@
  * SOFTWARE.
  */
 // locals
+use super::super::lib::hexdump::HexView;
 use super::{FileTransferActivity, FsEntry, LogLevel, SelectedEntry, TransferPayload};
+use crate::filetransfer::lock::FileLock;
 use crate::fs::FsFile;
 // ext
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
@@ -50,6 +52,22 @@ impl FileTransferActivity {
                     LogLevel::Info,
                     format!("Opening file \"{}\"…", entry.get_abs_path().display()),
                 );
+                // Binary files can only be viewed
+                match Self::is_binary_file(entry.get_abs_path().as_path()) {
+                    Ok(true) => {
+                        self.view_hexdump(
+                            entry.get_name(),
+                            entry.get_abs_path().as_path(),
+                            entry.get_size() as u64,
+                        );
+                        break;
+                    }
+                    Ok(false) => {}
+                    Err(err) => {
+                        self.log_and_alert(LogLevel::Error, err);
+                        continue;
+                    }
+                }
                 // Edit file
                 if let Err(err) = self.edit_local_file(entry.get_abs_path().as_path()) {
                     self.log_and_alert(LogLevel::Error, err);
@@ -70,14 +88,41 @@ impl FileTransferActivity {
         for entry in entries.into_iter() {
             // Check if file
             if let FsEntry::File(file) = entry {
+                // Don't edit files which are being edited by someone else
+                if let Some(lock) = self.remote_file_lock(&file) {
+                    if lock.owner != FileLock::local_owner() {
+                        self.log_and_alert(
+                            LogLevel::Warn,
+                            format!(
+                                "File \"{}\" is {}; remove \"{}\" to edit it anyway",
+                                file.abs_path.display(),
+                                lock.describe(),
+                                FileLock::lock_path(file.abs_path.as_path()).display()
+                            ),
+                        );
+                        continue;
+                    }
+                }
+                if let Err(err) = self.lock_remote_file(&file) {
+                    self.log(
+                        LogLevel::Warn,
+                        format!("Could not lock \"{}\": {}", file.abs_path.display(), err),
+                    );
+                }
                 self.log(
                     LogLevel::Info,
                     format!("Opening file \"{}\"…", file.abs_path.display()),
                 );
                 // Edit file
-                if let Err(err) = self.edit_remote_file(file) {
+                let result: Result<(), String> = self.edit_remote_file(file.clone());
+                self.unlock_remote_file(&file);
+                if let Err(err) = result {
                     self.log_and_alert(LogLevel::Error, err);
                 }
+                // Stop at the first binary file, which is being viewed
+                if self.hex_view.is_some() {
+                    break;
+                }
             }
         }
         // Reload entries
@@ -87,27 +132,60 @@ impl FileTransferActivity {
     /// ### edit_local_file
     ///
     /// Edit a file on localhost
-    fn edit_local_file(&mut self, path: &Path) -> Result<(), String> {
-        // Read first 2048 bytes or less from file to check if it is textual
+    pub(super) fn edit_local_file(&mut self, path: &Path) -> Result<(), String> {
+        if Self::is_binary_file(path)? {
+            return Err("Could not open file in editor: file is binary".to_string());
+        }
+        // Open editor
+        self.suspend_terminal();
+        let result = edit::edit_file(path);
+        self.resume_terminal();
+        match result {
+            Ok(_) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Changes performed through editor saved to \"{}\"!",
+                    path.display()
+                ),
+            ),
+            Err(err) => return Err(format!("Could not open editor: {}", err)),
+        }
+        Ok(())
+    }
+
+    /// ### is_binary_file
+    ///
+    /// Check whether the local file at `path` is binary, reading its first 2048 bytes or less
+    fn is_binary_file(path: &Path) -> Result<bool, String> {
         match OpenOptions::new().read(true).open(path) {
             Ok(mut f) => {
                 // Read
                 let mut buff: [u8; 2048] = [0; 2048];
                 match f.read(&mut buff) {
-                    Ok(size) => {
-                        if content_inspector::inspect(&buff[0..size]).is_binary() {
-                            return Err("Could not open file in editor: file is binary".to_string());
-                        }
-                    }
-                    Err(err) => {
-                        return Err(format!("Could not read file: {}", err));
-                    }
+                    Ok(size) => Ok(content_inspector::inspect(&buff[0..size]).is_binary()),
+                    Err(err) => Err(format!("Could not read file: {}", err)),
                 }
             }
-            Err(err) => {
-                return Err(format!("Could not read file: {}", err));
-            }
+            Err(err) => Err(format!("Could not read file: {}", err)),
         }
+    }
+
+    /// ### view_hexdump
+    ///
+    /// Show the local file at `path` as a read-only hex dump
+    fn view_hexdump(&mut self, name: &str, path: &Path, size: u64) {
+        self.log(
+            LogLevel::Info,
+            format!("\"{}\" is binary; showing it as hex dump", name),
+        );
+        self.hex_view = Some(HexView::new(name, path, size));
+        self.mount_hexdump();
+    }
+
+    /// ### suspend_terminal
+    ///
+    /// Give the terminal back to an external program: disable raw mode and leave alternate screen
+    pub(super) fn suspend_terminal(&mut self) {
         // Put input mode back to normal
         if let Err(err) = disable_raw_mode() {
             error!("Failed to disable raw mode: {}", err);
@@ -117,17 +195,12 @@ impl FileTransferActivity {
         if let Some(ctx) = self.context.as_mut() {
             ctx.leave_alternate_screen();
         }
-        // Open editor
-        match edit::edit_file(path) {
-            Ok(_) => self.log(
-                LogLevel::Info,
-                format!(
-                    "Changes performed through editor saved to \"{}\"!",
-                    path.display()
-                ),
-            ),
-            Err(err) => return Err(format!("Could not open editor: {}", err)),
-        }
+    }
+
+    /// ### resume_terminal
+    ///
+    /// Take the terminal back after an external program has exited
+    pub(super) fn resume_terminal(&mut self) {
         #[cfg(not(target_os = "windows"))]
         if let Some(ctx) = self.context.as_mut() {
             // Clear screen
@@ -137,13 +210,12 @@ impl FileTransferActivity {
         }
         // Re-enable raw mode
         let _ = enable_raw_mode();
-        Ok(())
     }
 
     /// ### edit_remote_file
     ///
     /// Edit file on remote host
-    fn edit_remote_file(&mut self, file: FsFile) -> Result<(), String> {
+    pub(super) fn edit_remote_file(&mut self, file: FsFile) -> Result<(), String> {
         // Create temp file
         let tmpfile: PathBuf = match self.download_file_as_temp(&file) {
             Ok(p) => p,
@@ -153,12 +225,17 @@ impl FileTransferActivity {
         let file_name = file.name.clone();
         let file_path = file.abs_path.clone();
         if let Err(err) = self.filetransfer_recv(
-            TransferPayload::File(file),
+            TransferPayload::File(file.clone()),
             tmpfile.as_path(),
             Some(file_name.clone()),
         ) {
             return Err(format!("Could not open file {}: {}", file_name, err));
         }
+        // Binary files are only viewed, so that the editor can't corrupt them
+        if Self::is_binary_file(tmpfile.as_path())? {
+            self.view_hexdump(file_name.as_str(), tmpfile.as_path(), file.size as u64);
+            return Ok(());
+        }
         // Get current file modification time
         let prev_mtime: SystemTime = match self.host.stat(tmpfile.as_path()) {
             Ok(e) => e.get_last_change_time(),
@@ -195,6 +272,8 @@ impl FileTransferActivity {
                         file_path.display()
                     ),
                 );
+                // Merge with changes made on remote in the meantime
+                self.resolve_edit_conflict(&file, tmpfile.as_path())?;
                 // Get local fs entry
                 let tmpfile_entry: FsFile = match self.host.stat(tmpfile.as_path()) {
                     Ok(e) => e.unwrap_file(),
@@ -206,8 +285,11 @@ impl FileTransferActivity {
                         ))
                     }
                 };
-                // Send file
-                let wrkdir = self.remote().wrkdir.clone();
+                // Send file back to its parent directory
+                let wrkdir: PathBuf = match file_path.parent() {
+                    Some(parent) => parent.to_path_buf(),
+                    None => self.remote().wrkdir.clone(),
+                };
                 if let Err(err) = self.filetransfer_send(
                     TransferPayload::File(tmpfile_entry),
                     wrkdir.as_path(),
@@ -48,7 +48,12 @@ impl FileTransferActivity {
     }
 
     pub(crate) fn action_remote_exec(&mut self, input: String) {
-        match self.client.as_mut().exec(input.as_str()) {
+        // Commands may run for long without printing anything; don't treat them as stalled
+        self.client.set_stall_timeout(None);
+        let result = self.client.as_mut().exec(input.as_str());
+        let stall_timeout = self.config().get_stall_timeout();
+        self.client.set_stall_timeout(stall_timeout);
+        match result {
             Ok(output) => {
                 // Reload files
                 self.log(LogLevel::Info, format!("\"{}\": {}", input, output));
@@ -63,4 +68,50 @@ impl FileTransferActivity {
             }
         }
     }
+
+    /// ### action_toggle_agent_forwarding
+    ///
+    /// Toggle the forwarding of the ssh agent to the commands executed on the remote,
+    /// saving it into the bookmarks for this host
+    pub(crate) fn action_toggle_agent_forwarding(&mut self) {
+        let host: String = self.bookmarks_host_key();
+        let forward: bool = match self.bookmarks.as_ref() {
+            Some(client) => !client.get_agent_forwarding(host.as_str()),
+            None => return,
+        };
+        if let Err(err) = self.client.set_agent_forwarding(forward) {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not set agent forwarding: {}", err),
+            );
+            return;
+        }
+        let mut result: Option<Result<(), String>> = None;
+        if let Some(client) = self.bookmarks.as_mut() {
+            if client.set_agent_forwarding(host.as_str(), forward) {
+                result = Some(client.write_bookmarks().map_err(|err| err.to_string()));
+            }
+        }
+        match result {
+            Some(Ok(())) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Agent forwarding {}",
+                    if forward { "enabled" } else { "disabled" }
+                ),
+            ),
+            Some(Err(err)) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not write bookmarks: {}", err),
+            ),
+            None => {
+                // Restore previous setting
+                let _ = self.client.set_agent_forwarding(false);
+                self.log_and_alert(
+                    LogLevel::Warn,
+                    String::from("There's no bookmark for this host"),
+                );
+            }
+        }
+    }
 }
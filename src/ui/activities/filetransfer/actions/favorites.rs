@@ -0,0 +1,89 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, LogLevel};
+// Ext
+use std::path::PathBuf;
+
+impl FileTransferActivity {
+    /// ### action_add_remote_favorite
+    ///
+    /// Add current remote working directory to favorites for this host
+    pub(crate) fn action_add_remote_favorite(&mut self) {
+        let wrkdir: String = self.remote().wrkdir.display().to_string();
+        let host: String = self.bookmarks_host_key();
+        if let Some(client) = self.bookmarks.as_mut() {
+            client.add_favorite(host.as_str(), wrkdir.clone());
+            match client.write_bookmarks() {
+                Ok(_) => self.log(LogLevel::Info, format!("Added \"{}\" to favorites", wrkdir)),
+                Err(err) => self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not write favorites: {}", err),
+                ),
+            }
+        }
+    }
+
+    /// ### action_del_remote_favorite
+    ///
+    /// Delete favorite at index for this host
+    pub(crate) fn action_del_remote_favorite(&mut self, idx: usize) {
+        let host: String = self.bookmarks_host_key();
+        if let Some(path) = self.remote_favorites().get(idx) {
+            if let Some(client) = self.bookmarks.as_mut() {
+                client.del_favorite(host.as_str(), path.as_str());
+                if let Err(err) = client.write_bookmarks() {
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!("Could not write favorites: {}", err),
+                    );
+                }
+            }
+        }
+    }
+
+    /// ### action_enter_remote_favorite
+    ///
+    /// Change remote working directory to favorite at index
+    pub(crate) fn action_enter_remote_favorite(&mut self, idx: usize) {
+        if let Some(path) = self.remote_favorites().get(idx) {
+            self.remote_changedir(PathBuf::from(path).as_path(), true);
+        }
+    }
+
+    /// ### remote_favorites
+    ///
+    /// Get favorite paths for this host
+    pub(crate) fn remote_favorites(&self) -> Vec<String> {
+        let host: String = self.bookmarks_host_key();
+        match self.bookmarks.as_ref() {
+            Some(client) => client.iter_favorites(host.as_str()).cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+}
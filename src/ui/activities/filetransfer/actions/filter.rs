@@ -0,0 +1,99 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, FsEntry, LogLevel};
+use crate::fs::filter::TransferFilter;
+
+impl FileTransferActivity {
+    /// ### init_transfer_filter
+    ///
+    /// Load transfer filters from bookmarks for this host
+    pub(crate) fn init_transfer_filter(&mut self) {
+        let host: String = self.bookmarks_host_key();
+        let rules: Vec<String> = match self.bookmarks.as_ref() {
+            Some(client) => client.get_filters(host.as_str()),
+            None => Vec::new(),
+        };
+        match TransferFilter::parse(rules.as_slice()) {
+            Ok(filter) => {
+                if !filter.is_empty() {
+                    self.log(
+                        LogLevel::Info,
+                        format!("Loaded {} transfer filter rule(s)", filter.rules().len()),
+                    );
+                }
+                self.filter = filter;
+            }
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Invalid transfer filters in bookmark: {}", err),
+            ),
+        }
+    }
+
+    /// ### action_set_transfer_filter
+    ///
+    /// Set transfer filters from rules and save them into the bookmarks for this host
+    pub(crate) fn action_set_transfer_filter(&mut self, rules: Vec<String>) {
+        let filter: TransferFilter = match TransferFilter::parse(rules.as_slice()) {
+            Ok(filter) => filter,
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Invalid transfer filters: {}", err),
+                );
+                return;
+            }
+        };
+        self.log(
+            LogLevel::Info,
+            format!("Set {} transfer filter rule(s)", filter.rules().len()),
+        );
+        self.filter = filter;
+        // Save filters into bookmarks
+        let host: String = self.bookmarks_host_key();
+        if let Some(client) = self.bookmarks.as_mut() {
+            if client.set_filters(host.as_str(), rules) {
+                if let Err(err) = client.write_bookmarks() {
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!("Could not write bookmarks: {}", err),
+                    );
+                }
+            }
+        }
+    }
+
+    /// ### is_transferred
+    ///
+    /// Returns whether `entry` must be transferred, according to the transfer filters and to the
+    /// exclusions set for the session
+    pub(crate) fn is_transferred(&self, entry: &FsEntry) -> bool {
+        self.options.exclude.accepts(entry) && self.filter.accepts(entry)
+    }
+}
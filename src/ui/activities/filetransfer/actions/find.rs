@@ -72,6 +72,9 @@ impl FileTransferActivity {
     pub(crate) fn action_find_transfer(&mut self, save_as: Option<String>) {
         let wrkdir: PathBuf = match self.browser.tab() {
             FileExplorerTab::FindLocal | FileExplorerTab::Local => self.remote().wrkdir.clone(),
+            FileExplorerTab::FindRemote | FileExplorerTab::Remote if save_as.is_none() => {
+                self.download_dir()
+            }
             FileExplorerTab::FindRemote | FileExplorerTab::Remote => self.local().wrkdir.clone(),
         };
         match self.get_found_selected_entries() {
@@ -0,0 +1,111 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, FsEntry, LogLevel, TransferPayload};
+use crate::config::journal::JournalEntry;
+// Ext
+use std::path::PathBuf;
+
+impl FileTransferActivity {
+    /// ### check_journal
+    ///
+    /// Prompt whether to resume the batch transfer with the remote host which was interrupted by the last exit
+    pub(crate) fn check_journal(&mut self) {
+        let host: String = self.bookmarks_host_key();
+        let entry: Option<JournalEntry> = self
+            .journal
+            .as_ref()
+            .and_then(|x| x.pending(host.as_str()))
+            .cloned();
+        if let Some(entry) = entry {
+            self.mount_radio_journal(&entry);
+        }
+    }
+
+    /// ### action_resume_journal
+    ///
+    /// Start again the batch transfer recorded in the journal.
+    /// Sources which don't exist anymore are skipped
+    pub(crate) fn action_resume_journal(&mut self) {
+        let host: String = self.bookmarks_host_key();
+        let entry: JournalEntry = match self
+            .journal
+            .as_ref()
+            .and_then(|x| x.pending(host.as_str()))
+            .cloned()
+        {
+            Some(entry) => entry,
+            None => return,
+        };
+        let mut entries: Vec<FsEntry> = Vec::with_capacity(entry.sources.len());
+        for source in entry.sources.iter() {
+            let stat: Result<FsEntry, String> = match entry.upload {
+                true => self.host.stat(source.as_path()).map_err(|x| x.to_string()),
+                false => self
+                    .client
+                    .stat(source.as_path())
+                    .map_err(|x| x.to_string()),
+            };
+            match stat {
+                Ok(fsentry) => entries.push(fsentry),
+                Err(err) => self.log(
+                    LogLevel::Warn,
+                    format!("Skipped \"{}\": {}", source.display(), err),
+                ),
+            }
+        }
+        if entries.is_empty() {
+            self.log(
+                LogLevel::Warn,
+                String::from("Nothing left to transfer; discarding interrupted transfer"),
+            );
+            self.journal_clear();
+            return;
+        }
+        // Resume: files already at the destination are prompted for as usual
+        let payload: TransferPayload = match entries.len() {
+            1 => TransferPayload::Any(entries.remove(0)),
+            _ => TransferPayload::Many(entries),
+        };
+        let dest: PathBuf = entry.dest.clone();
+        let result = match entry.upload {
+            true => self.filetransfer_send(payload, dest.as_path(), entry.dst_name),
+            false => self.filetransfer_recv(payload, dest.as_path(), entry.dst_name),
+        };
+        // A single file isn't a batch, so it doesn't clear the journal by itself
+        self.journal_clear();
+        if let Err(err) = result {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not resume transfer: {}", err),
+            );
+        }
+        self.reload_local_dir();
+        self.reload_remote_dir();
+    }
+}
@@ -0,0 +1,145 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, FsEntry, LogLevel};
+use crate::filetransfer::lock::FileLock;
+use crate::fs::FsFile;
+// Ext
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+impl FileTransferActivity {
+    /// ### remote_file_lock
+    ///
+    /// Get the lock on the remote `file`, if any
+    pub(crate) fn remote_file_lock(&mut self, file: &FsFile) -> Option<FileLock> {
+        let lock_path: PathBuf = FileLock::lock_path(file.abs_path.as_path());
+        match self.client.stat(lock_path.as_path()) {
+            Ok(FsEntry::File(lock_file)) => self.read_remote_lock(&lock_file),
+            _ => None,
+        }
+    }
+
+    /// ### lock_remote_file
+    ///
+    /// Write the lock on the remote `file`, held by this machine
+    pub(crate) fn lock_remote_file(&mut self, file: &FsFile) -> Result<(), String> {
+        let tmpfile: PathBuf = match self.cache.as_ref() {
+            Some(cache) => cache.path().join(format!("{}.lock", file.name)),
+            None => return Err(String::from("cache not available")),
+        };
+        let content: String = FileLock::new(FileLock::local_owner()).to_content();
+        fs::write(tmpfile.as_path(), content.as_bytes())
+            .map_err(|e| format!("Could not write \"{}\": {}", tmpfile.display(), e))?;
+        let local: FsFile = match self.host.stat(tmpfile.as_path()) {
+            Ok(entry) => entry.unwrap_file(),
+            Err(err) => return Err(format!("Could not stat \"{}\": {}", tmpfile.display(), err)),
+        };
+        let lock_path: PathBuf = FileLock::lock_path(file.abs_path.as_path());
+        let mut writer: Box<dyn Write> = self
+            .client
+            .send_file(&local, lock_path.as_path())
+            .map_err(|e| e.to_string())?;
+        writer
+            .write_all(content.as_bytes())
+            .map_err(|e| e.to_string())?;
+        self.client.on_sent(writer).map_err(|e| e.to_string())
+    }
+
+    /// ### unlock_remote_file
+    ///
+    /// Remove the lock on the remote `file`, if held by this machine
+    pub(crate) fn unlock_remote_file(&mut self, file: &FsFile) {
+        let lock_path: PathBuf = FileLock::lock_path(file.abs_path.as_path());
+        let lock_file: FsFile = match self.client.stat(lock_path.as_path()) {
+            Ok(FsEntry::File(lock_file)) => lock_file,
+            _ => return,
+        };
+        match self.read_remote_lock(&lock_file) {
+            Some(lock) if lock.owner == FileLock::local_owner() => {}
+            _ => return,
+        }
+        if let Err(err) = self.client.remove(&FsEntry::File(lock_file)) {
+            self.log(
+                LogLevel::Warn,
+                format!("Could not unlock \"{}\": {}", file.abs_path.display(), err),
+            );
+        }
+    }
+
+    /// ### refresh_remote_locks
+    ///
+    /// Read the locks on the files in the remote working directory
+    pub(crate) fn refresh_remote_locks(&mut self) {
+        let lock_files: Vec<(String, FsFile)> = self
+            .remote()
+            .iter_files_all()
+            .filter_map(|x| match x {
+                FsEntry::File(file) => FileLock::locked_name(file.name.as_str())
+                    .map(|name| (name.to_string(), file.clone())),
+                FsEntry::Directory(_) => None,
+            })
+            .collect();
+        let mut locks: HashMap<String, FileLock> = HashMap::with_capacity(lock_files.len());
+        for (name, lock_file) in lock_files.iter() {
+            if let Some(lock) = self.read_remote_lock(lock_file) {
+                locks.insert(name.clone(), lock);
+            }
+        }
+        self.remote_locks = locks;
+    }
+
+    /// ### read_remote_lock
+    ///
+    /// Read and parse remote lock file
+    fn read_remote_lock(&mut self, lock_file: &FsFile) -> Option<FileLock> {
+        let mut reader: Box<dyn Read> = match self.client.recv_file(lock_file) {
+            Ok(reader) => reader,
+            Err(err) => {
+                error!(
+                    "Failed to read lock {}: {}",
+                    lock_file.abs_path.display(),
+                    err
+                );
+                return None;
+            }
+        };
+        let mut content: String = String::new();
+        let result = reader.read_to_string(&mut content);
+        if let Err(err) = self.client.on_recv(reader) {
+            error!(
+                "Failed to finalize read of {}: {}",
+                lock_file.abs_path.display(),
+                err
+            );
+        }
+        result.ok().and_then(|_| FileLock::parse(content.as_str()))
+    }
+}
@@ -0,0 +1,210 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::lib::manifest::TransferManifest;
+use super::{
+    FileTransferActivity, FsEntry, LogLevel, PendingTransfer, TransferDirection, TransferPayload,
+};
+// Ext
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+impl FileTransferActivity {
+    /// ### action_preview_transfer
+    ///
+    /// Scan the entries of a recursive transfer and show the manifest.
+    /// The transfer starts once the user confirms it; if `remove_source` is true, entries are moved
+    pub(crate) fn action_preview_transfer(
+        &mut self,
+        direction: TransferDirection,
+        payload: TransferPayload,
+        dest: PathBuf,
+        dst_name: Option<String>,
+        remove_source: bool,
+    ) {
+        let entries: Vec<FsEntry> = payload.entries();
+        self.mount_blocking_wait("Scanning files to transfer…");
+        let mut manifest: TransferManifest = TransferManifest::default();
+        for entry in entries.iter() {
+            self.scan_manifest_entry(direction, entry, 0, &mut manifest);
+        }
+        self.umount_wait();
+        self.mount_transfer_manifest(&manifest);
+        self.report_denied_entries();
+        self.pending_transfer = Some(PendingTransfer {
+            direction,
+            payload,
+            dest,
+            dst_name,
+            manifest,
+            remove_source,
+        });
+    }
+
+    /// ### action_toggle_manifest_collapsed
+    ///
+    /// Collapse or expand directory at row `row` of the manifest
+    pub(crate) fn action_toggle_manifest_collapsed(&mut self, row: usize) {
+        if let Some(pending) = self.pending_transfer.as_mut() {
+            if let Some(idx) = pending.manifest.visible().get(row) {
+                pending.manifest.toggle_collapsed(*idx);
+            }
+        }
+    }
+
+    /// ### action_toggle_manifest_selected
+    ///
+    /// Select or deselect entry at row `row` of the manifest
+    pub(crate) fn action_toggle_manifest_selected(&mut self, row: usize) {
+        if let Some(pending) = self.pending_transfer.as_mut() {
+            if let Some(idx) = pending.manifest.visible().get(row) {
+                pending.manifest.toggle_selected(*idx);
+            }
+        }
+    }
+
+    /// ### action_filter_manifest
+    ///
+    /// Transfer only the files of the manifest matching the comma separated globs in `input`;
+    /// an empty input transfers all the files
+    pub(crate) fn action_filter_manifest(&mut self, input: &str) {
+        let globs: Vec<&str> = input
+            .split(',')
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty())
+            .collect();
+        let selected: usize = match self.pending_transfer.as_mut() {
+            Some(pending) => {
+                pending.manifest.select_matching(globs.as_slice());
+                pending.manifest.selected_files()
+            }
+            None => return,
+        };
+        match globs.is_empty() {
+            true => self.log(LogLevel::Info, format!("Selected all {} file(s)", selected)),
+            false => self.log(
+                LogLevel::Info,
+                format!(
+                    "Selected {} file(s) matching {}",
+                    selected,
+                    globs.join(", ")
+                ),
+            ),
+        }
+    }
+
+    /// ### action_confirm_transfer
+    ///
+    /// Start the pending transfer, skipping the entries deselected from the manifest
+    pub(crate) fn action_confirm_transfer(&mut self) {
+        if let Some(pending) = self.pending_transfer.take() {
+            self.umount_transfer_manifest();
+            self.transfer.set_excluded(pending.manifest.excluded());
+            let sources: Vec<FsEntry> = pending.payload.entries();
+            if pending.remove_source {
+                self.transfer.start_move();
+            }
+            let result = match pending.direction {
+                TransferDirection::Upload => self.filetransfer_send(
+                    pending.payload,
+                    pending.dest.as_path(),
+                    pending.dst_name,
+                ),
+                TransferDirection::Download => self.filetransfer_recv(
+                    pending.payload,
+                    pending.dest.as_path(),
+                    pending.dst_name,
+                ),
+            };
+            self.transfer.set_excluded(HashSet::new());
+            if pending.remove_source {
+                self.finalize_move(
+                    pending.direction,
+                    sources.as_slice(),
+                    pending.dest.as_path(),
+                    result.is_ok(),
+                );
+            }
+            match (result, pending.direction) {
+                (Err(err), TransferDirection::Upload) => {
+                    self.log_and_alert(LogLevel::Error, format!("Could not upload file: {}", err))
+                }
+                (Err(err), TransferDirection::Download) => {
+                    self.log_and_alert(LogLevel::Error, format!("Could not download file: {}", err))
+                }
+                // Marks have been consumed
+                (Ok(_), TransferDirection::Upload) => self.local_mut().clear_marks(),
+                (Ok(_), TransferDirection::Download) => self.remote_mut().clear_marks(),
+            }
+        }
+    }
+
+    /// ### action_cancel_transfer
+    ///
+    /// Discard the pending transfer
+    pub(crate) fn action_cancel_transfer(&mut self) {
+        self.pending_transfer = None;
+        self.umount_transfer_manifest();
+    }
+
+    /// ### scan_manifest_entry
+    ///
+    /// Push `entry` to manifest; if it is a directory, its content is scanned applying transfer filters
+    fn scan_manifest_entry(
+        &mut self,
+        direction: TransferDirection,
+        entry: &FsEntry,
+        depth: usize,
+        manifest: &mut TransferManifest,
+    ) {
+        manifest.push(entry, depth);
+        if let FsEntry::Directory(dir) = entry {
+            let remote: bool = direction == TransferDirection::Download;
+            match self.scan_dir_or_deny(dir.abs_path.as_path(), remote) {
+                Ok(entries) => {
+                    let mut entries: Vec<FsEntry> = entries
+                        .into_iter()
+                        .filter(|x| self.is_transferred(x))
+                        .collect();
+                    entries.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+                    for entry in entries.iter() {
+                        self.scan_manifest_entry(direction, entry, depth + 1, manifest);
+                    }
+                }
+                Err(err) => self.log(
+                    LogLevel::Error,
+                    format!(
+                        "Could not scan directory \"{}\": {}",
+                        dir.abs_path.display(),
+                        err
+                    ),
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::{COMPONENT_EXPLORER_LOCAL, COMPONENT_EXPLORER_REMOTE};
+use super::{FileTransferActivity, SelectedEntryIndex};
+
+impl FileTransferActivity {
+    /// ### action_local_mark
+    ///
+    /// Store the selection in the local explorer as marks for the current directory.
+    /// Marks in other directories are kept
+    pub(crate) fn action_local_mark(&mut self) {
+        let selected: Vec<usize> = self.get_marked_indexes(COMPONENT_EXPLORER_LOCAL);
+        self.local_mut().mark_files(selected.as_slice());
+    }
+
+    /// ### action_remote_mark
+    ///
+    /// Store the selection in the remote explorer as marks for the current directory.
+    /// Marks in other directories are kept
+    pub(crate) fn action_remote_mark(&mut self) {
+        let selected: Vec<usize> = self.get_marked_indexes(COMPONENT_EXPLORER_REMOTE);
+        self.remote_mut().mark_files(selected.as_slice());
+    }
+
+    fn get_marked_indexes(&self, component: &str) -> Vec<usize> {
+        match self.get_selected_index(component) {
+            SelectedEntryIndex::Many(selected) => selected,
+            _ => Vec::new(),
+        }
+    }
+}
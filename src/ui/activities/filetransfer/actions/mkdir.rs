@@ -27,7 +27,10 @@
  */
 // locals
 use super::{FileTransferActivity, LogLevel};
+use crate::utils::fmt::fmt_path_template;
+
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 impl FileTransferActivity {
     pub(crate) fn action_local_mkdir(&mut self, input: String) {
@@ -48,10 +51,12 @@ impl FileTransferActivity {
         }
     }
     pub(crate) fn action_remote_mkdir(&mut self, input: String) {
+        // Expand date templates (e.g. `logs/{YYYY}/{MM}`)
+        let input: String = fmt_path_template(input.as_str(), SystemTime::now());
         match self
             .client
             .as_mut()
-            .mkdir(PathBuf::from(input.as_str()).as_path())
+            .mkdir_all(PathBuf::from(input.as_str()).as_path())
         {
             Ok(_) => {
                 // Reload files
@@ -25,22 +25,45 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-pub(self) use super::{FileTransferActivity, FsEntry, LogLevel, TransferPayload};
+use super::{
+    FileTransferActivity, FsEntry, LogLevel, Peer, PendingTransfer, TransferDirection,
+    TransferPayload,
+};
 use tuirealm::{Payload, Value};
 
 // actions
 pub(crate) mod change_dir;
+pub(crate) mod chmod;
 pub(crate) mod copy;
 pub(crate) mod delete;
+pub(crate) mod difftool;
+pub(crate) mod dry_run;
 pub(crate) mod edit;
 pub(crate) mod exec;
+pub(crate) mod favorites;
+pub(crate) mod fetch;
+pub(crate) mod filter;
 pub(crate) mod find;
+pub(crate) mod journal;
+pub(crate) mod lock;
+pub(crate) mod manifest;
+pub(crate) mod mark;
 pub(crate) mod mkdir;
+pub(crate) mod move_files;
 pub(crate) mod newfile;
 pub(crate) mod open;
+pub(crate) mod options;
+pub(crate) mod paste;
+pub(crate) mod peer;
+pub(crate) mod prefetch;
 pub(crate) mod rename;
 pub(crate) mod save;
 pub(crate) mod submit;
+pub(crate) mod trace;
+pub(crate) mod trash;
+pub(crate) mod usage;
+pub(crate) mod verify;
+pub(crate) mod watch;
 
 #[derive(Debug)]
 pub(crate) enum SelectedEntry {
@@ -76,6 +99,10 @@ impl FileTransferActivity {
     ///
     /// Get local file entry
     pub(crate) fn get_local_selected_entries(&self) -> SelectedEntry {
+        // Marks may span many directories
+        if self.local().marked_count() > 0 {
+            return SelectedEntry::from(self.local().iter_marked().collect::<Vec<&FsEntry>>());
+        }
         match self.get_selected_index(super::COMPONENT_EXPLORER_LOCAL) {
             SelectedEntryIndex::One(idx) => SelectedEntry::from(self.local().get(idx)),
             SelectedEntryIndex::Many(files) => {
@@ -94,6 +121,10 @@ impl FileTransferActivity {
     ///
     /// Get remote file entry
     pub(crate) fn get_remote_selected_entries(&self) -> SelectedEntry {
+        // Marks may span many directories
+        if self.remote().marked_count() > 0 {
+            return SelectedEntry::from(self.remote().iter_marked().collect::<Vec<&FsEntry>>());
+        }
         match self.get_selected_index(super::COMPONENT_EXPLORER_REMOTE) {
             SelectedEntryIndex::One(idx) => SelectedEntry::from(self.remote().get(idx)),
             SelectedEntryIndex::Many(files) => {
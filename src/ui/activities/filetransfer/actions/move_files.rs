@@ -0,0 +1,165 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, FsEntry, LogLevel, TransferDirection};
+use std::path::{Path, PathBuf};
+
+impl FileTransferActivity {
+    /// ### finalize_move
+    ///
+    /// Remove the source files transferred by the move of `sources` into `dest`, then the source directories
+    /// left empty. Files which haven't been transferred or verified are left in place.
+    /// If the transfer didn't complete, nothing is removed
+    pub(crate) fn finalize_move(
+        &mut self,
+        direction: TransferDirection,
+        sources: &[FsEntry],
+        dest: &Path,
+        completed: bool,
+    ) {
+        let moved: Vec<PathBuf> = self.transfer.take_moved();
+        if !completed || self.transfer.aborted() {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "Move into \"{}\" didn't complete: nothing has been removed from the source",
+                    dest.display()
+                ),
+            );
+            return;
+        }
+        // Remove transferred files
+        let mut removed: usize = 0;
+        for path in moved.iter() {
+            match self.remove_move_source(direction, path.as_path()) {
+                Ok(()) => removed += 1,
+                Err(err) => self.log(
+                    LogLevel::Error,
+                    format!("Could not remove \"{}\": {}", path.display(), err),
+                ),
+            }
+        }
+        // Remove directories left empty
+        let mut left: usize = 0;
+        for entry in sources.iter() {
+            match entry {
+                FsEntry::Directory(dir) if !entry.is_symlink() => {
+                    left += self.prune_move_source(direction, dir.abs_path.as_path());
+                }
+                _ if !moved.contains(&entry.get_abs_path()) => left += 1,
+                _ => {}
+            }
+        }
+        match left {
+            0 => self.log(
+                LogLevel::Info,
+                format!("Moved {} file(s) into \"{}\"", removed, dest.display()),
+            ),
+            _ => self.log(
+                LogLevel::Warn,
+                format!(
+                    "Moved {} file(s) into \"{}\"; {} file(s) which haven't been transferred have been left in place",
+                    removed,
+                    dest.display(),
+                    left
+                ),
+            ),
+        }
+        match direction {
+            TransferDirection::Upload => self.reload_local_dir(),
+            TransferDirection::Download => self.reload_remote_dir(),
+        }
+    }
+
+    /// ### prune_move_source
+    ///
+    /// Remove the directories of the moved directory `dir` which are empty, `dir` included.
+    /// Returns the amount of files left in `dir`
+    fn prune_move_source(&mut self, direction: TransferDirection, dir: &Path) -> usize {
+        let entries: Result<Vec<FsEntry>, String> = match direction {
+            TransferDirection::Upload => self.host.scan_dir(dir).map_err(|x| x.to_string()),
+            TransferDirection::Download => self.client.list_dir(dir).map_err(|x| x.to_string()),
+        };
+        let entries: Vec<FsEntry> = match entries {
+            Ok(entries) => entries,
+            Err(err) => {
+                // Content is unknown; keep directory
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not scan directory \"{}\": {}", dir.display(), err),
+                );
+                return 1;
+            }
+        };
+        let mut left: usize = 0;
+        for entry in entries.iter() {
+            match entry {
+                FsEntry::Directory(sub) if !entry.is_symlink() => {
+                    left += self.prune_move_source(direction, sub.abs_path.as_path());
+                }
+                _ => left += 1,
+            }
+        }
+        if left == 0 {
+            let result: Result<(), String> = match direction {
+                // Don't remove local files which couldn't be scanned
+                TransferDirection::Upload => std::fs::remove_dir(dir).map_err(|x| x.to_string()),
+                TransferDirection::Download => self.remove_move_source(direction, dir),
+            };
+            if let Err(err) = result {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not remove \"{}\": {}", dir.display(), err),
+                );
+            }
+        }
+        left
+    }
+
+    /// ### remove_move_source
+    ///
+    /// Remove the source entry at `path`; on localhost if uploading, on remote otherwise
+    fn remove_move_source(
+        &mut self,
+        direction: TransferDirection,
+        path: &Path,
+    ) -> Result<(), String> {
+        match direction {
+            TransferDirection::Upload => self
+                .host
+                .stat(path)
+                .and_then(|x| self.host.remove(&x))
+                .map_err(|x| x.to_string()),
+            TransferDirection::Download => self
+                .client
+                .stat(path)
+                .and_then(|x| self.client.remove(&x))
+                .map_err(|x| x.to_string()),
+        }
+    }
+}
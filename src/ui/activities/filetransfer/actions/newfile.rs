@@ -30,7 +30,11 @@ use super::{FileTransferActivity, FsEntry, LogLevel};
 use std::path::PathBuf;
 
 impl FileTransferActivity {
-    pub(crate) fn action_local_newfile(&mut self, input: String) {
+    /// ### action_local_newfile
+    ///
+    /// Create an empty file on localhost.
+    /// Returns whether the file has been created
+    pub(crate) fn action_local_newfile(&mut self, input: String) -> bool {
         // Check if file exists
         let mut file_exists: bool = false;
         for file in self.local().iter_files_all() {
@@ -43,26 +47,36 @@ impl FileTransferActivity {
                 LogLevel::Warn,
                 format!("File \"{}\" already exists", input,),
             );
-            return;
+            return false;
         }
         // Create file
         let file_path: PathBuf = PathBuf::from(input.as_str());
-        if let Err(err) = self.host.open_file_write(file_path.as_path()) {
-            self.log_and_alert(
-                LogLevel::Error,
-                format!("Could not create file \"{}\": {}", file_path.display(), err),
-            );
-        } else {
-            self.log(
-                LogLevel::Info,
-                format!("Created file \"{}\"", file_path.display()),
-            );
-        }
+        let created: bool = match self.host.open_file_write(file_path.as_path()) {
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not create file \"{}\": {}", file_path.display(), err),
+                );
+                false
+            }
+            Ok(_) => {
+                self.log(
+                    LogLevel::Info,
+                    format!("Created file \"{}\"", file_path.display()),
+                );
+                true
+            }
+        };
         // Reload files
         self.reload_local_dir();
+        created
     }
 
-    pub(crate) fn action_remote_newfile(&mut self, input: String) {
+    /// ### action_remote_newfile
+    ///
+    /// Create an empty file on the remote host.
+    /// Returns whether the file has been created
+    pub(crate) fn action_remote_newfile(&mut self, input: String) -> bool {
         // Check if file exists
         let mut file_exists: bool = false;
         for file in self.remote().iter_files_all() {
@@ -75,16 +89,19 @@ impl FileTransferActivity {
                 LogLevel::Warn,
                 format!("File \"{}\" already exists", input,),
             );
-            return;
+            return false;
         }
         // Get path on remote
         let file_path: PathBuf = PathBuf::from(input.as_str());
         // Create file (on local)
         match tempfile::NamedTempFile::new() {
-            Err(err) => self.log_and_alert(
-                LogLevel::Error,
-                format!("Could not create tempfile: {}", err),
-            ),
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not create tempfile: {}", err),
+                );
+                false
+            }
             Ok(tfile) => {
                 // Stat tempfile
                 let local_file: FsEntry = match self.host.stat(tfile.path()) {
@@ -93,7 +110,7 @@ impl FileTransferActivity {
                             LogLevel::Error,
                             format!("Could not stat tempfile: {}", err),
                         );
-                        return;
+                        return false;
                     }
                     Ok(f) => f,
                 };
@@ -106,23 +123,68 @@ impl FileTransferActivity {
                         ),
                         Ok(writer) => {
                             // Finalize write
-                            if let Err(err) = self.client.on_sent(writer) {
-                                self.log_and_alert(
-                                    LogLevel::Warn,
-                                    format!("Could not finalize file: {}", err),
-                                );
-                            } else {
-                                self.log(
-                                    LogLevel::Info,
-                                    format!("Created file \"{}\"", file_path.display()),
-                                );
-                            }
+                            let created: bool = match self.client.on_sent(writer) {
+                                Err(err) => {
+                                    self.log_and_alert(
+                                        LogLevel::Warn,
+                                        format!("Could not finalize file: {}", err),
+                                    );
+                                    false
+                                }
+                                Ok(_) => {
+                                    self.log(
+                                        LogLevel::Info,
+                                        format!("Created file \"{}\"", file_path.display()),
+                                    );
+                                    true
+                                }
+                            };
                             // Reload files
                             self.reload_remote_dir();
+                            return created;
                         }
                     }
                 }
+                false
+            }
+        }
+    }
+
+    /// ### action_local_newfile_and_edit
+    ///
+    /// Create an empty file on localhost and open it in the editor
+    pub(crate) fn action_local_newfile_and_edit(&mut self, input: String) {
+        if self.action_local_newfile(input.clone()) {
+            let file_path: PathBuf = self.local().wrkdir.join(input.as_str());
+            if let Err(err) = self.edit_local_file(file_path.as_path()) {
+                self.log_and_alert(LogLevel::Error, err);
+            }
+            self.reload_local_dir();
+        }
+    }
+
+    /// ### action_remote_newfile_and_edit
+    ///
+    /// Create an empty file on the remote host and open it in the editor through a temporary file
+    pub(crate) fn action_remote_newfile_and_edit(&mut self, input: String) {
+        if self.action_remote_newfile(input.clone()) {
+            let file_path: PathBuf = self.remote().wrkdir.join(input.as_str());
+            match self.client.stat(file_path.as_path()) {
+                Ok(FsEntry::File(file)) => {
+                    if let Err(err) = self.edit_remote_file(file) {
+                        self.log_and_alert(LogLevel::Error, err);
+                    }
+                }
+                Ok(FsEntry::Directory(_)) => self.log_and_alert(
+                    LogLevel::Error,
+                    format!("\"{}\" is a directory", file_path.display()),
+                ),
+                Err(err) => self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not stat \"{}\": {}", file_path.display(), err),
+                ),
             }
+            self.reload_remote_dir();
         }
     }
 }
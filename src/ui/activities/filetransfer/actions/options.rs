@@ -0,0 +1,67 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::SessionOptions;
+use super::{FileTransferActivity, LogLevel};
+use crate::utils::fmt::fmt_size;
+
+impl FileTransferActivity {
+    /// ### action_set_session_options
+    ///
+    /// Replace the transfer options of the current session; the configuration is left untouched
+    pub(crate) fn action_set_session_options(&mut self, opts: SessionOptions) {
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Session options: preserve {}; existing files: {}; bandwidth: {}; excluded: {}; verify: {}",
+                match (opts.preserve_mode, opts.preserve_owner) {
+                    (true, true) => "mode and owner",
+                    (true, false) => "mode",
+                    (false, _) => "nothing",
+                },
+                match opts.conflict_policy {
+                    None => String::from("ask"),
+                    Some(action) => format!("{:?}", action).to_lowercase(),
+                },
+                match opts.bandwidth {
+                    None => String::from("unlimited"),
+                    Some(cap) => format!("{}/s", fmt_size(cap)),
+                },
+                match opts.exclude.is_empty() {
+                    true => String::from("none"),
+                    false => opts.exclude.to_globs(),
+                },
+                match opts.verify {
+                    true => "yes",
+                    false => "no",
+                },
+            ),
+        );
+        self.options = opts;
+    }
+}
@@ -0,0 +1,151 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, FsEntry, LogLevel, TransferDirection, TransferPayload};
+use crate::utils::parser::parse_pasted_paths;
+
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use std::path::{Path, PathBuf};
+
+impl FileTransferActivity {
+    /// ### action_read_paste
+    ///
+    /// Having received the typed character `first`, check whether it's the beginning of some text
+    /// pasted into the terminal made of local paths, e.g. files dropped onto the terminal by a file manager.
+    /// If so, the rest of the text is consumed and the paths are returned; otherwise the read characters
+    /// are handled as typed keys
+    pub(crate) fn action_read_paste(&mut self, first: &Event) -> Option<Vec<PathBuf>> {
+        let burst: Vec<Event> = self.context().input_hnd().read_burst();
+        if burst.is_empty() {
+            return None;
+        }
+        let text: String = std::iter::once(first)
+            .chain(burst.iter())
+            .filter_map(|ev| match ev {
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char(ch),
+                    ..
+                }) => Some(*ch),
+                _ => None,
+            })
+            .collect();
+        match parse_pasted_paths(text.as_str()) {
+            Some(paths) => Some(paths),
+            None => {
+                self.context().input_hnd().unread(burst);
+                None
+            }
+        }
+    }
+
+    /// ### action_paste
+    ///
+    /// Ask what to do with the pasted local paths
+    pub(crate) fn action_paste(&mut self, paths: Vec<PathBuf>) {
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Pasted {}",
+                paths
+                    .iter()
+                    .map(|x| format!("\"{}\"", x.display()))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        );
+        self.mount_radio_paste(paths.as_slice());
+        self.pasted = paths;
+    }
+
+    /// ### action_paste_upload
+    ///
+    /// Upload the pasted paths into the remote working directory
+    pub(crate) fn action_paste_upload(&mut self) {
+        let mut entries: Vec<FsEntry> = Vec::with_capacity(self.pasted.len());
+        for path in std::mem::take(&mut self.pasted).iter() {
+            match self.host.stat(path.as_path()) {
+                Ok(entry) => entries.push(entry.get_realfile()),
+                Err(err) => {
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!("Could not stat \"{}\": {}", path.display(), err),
+                    );
+                    return;
+                }
+            }
+        }
+        let wrkdir: PathBuf = self.remote().wrkdir.clone();
+        match entries.len() {
+            0 => {}
+            1 if entries[0].is_dir() => {
+                // Recursive transfer; preview manifest first
+                self.action_preview_transfer(
+                    TransferDirection::Upload,
+                    TransferPayload::Any(entries.remove(0)),
+                    wrkdir,
+                    None,
+                    false,
+                );
+            }
+            1 => {
+                if let Err(err) = self.filetransfer_send(
+                    TransferPayload::Any(entries.remove(0)),
+                    wrkdir.as_path(),
+                    None,
+                ) {
+                    self.log_and_alert(LogLevel::Error, format!("Could not upload file: {}", err));
+                }
+            }
+            _ => {
+                self.action_preview_transfer(
+                    TransferDirection::Upload,
+                    TransferPayload::Many(entries),
+                    wrkdir,
+                    None,
+                    false,
+                );
+            }
+        }
+    }
+
+    /// ### action_paste_goto
+    ///
+    /// Change the local working directory to the first pasted path, or to its parent if it's a file
+    pub(crate) fn action_paste_goto(&mut self) {
+        let pasted: Vec<PathBuf> = std::mem::take(&mut self.pasted);
+        let path: &Path = match pasted.first() {
+            Some(path) => path.as_path(),
+            None => return,
+        };
+        let dir: &Path = match path.is_dir() {
+            true => path,
+            false => path.parent().unwrap_or(path),
+        };
+        self.action_change_local_dir(dir.to_string_lossy().to_string(), false);
+    }
+}
@@ -0,0 +1,170 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, FsEntry, LogLevel, Peer, SelectedEntry};
+use crate::filetransfer::{FileTransfer, FileTransferParams, TlsOptions};
+use crate::utils::parser::parse_remote_opt;
+
+impl FileTransferActivity {
+    /// ### action_set_peer_address
+    ///
+    /// Parse the address of the peer to connect to.
+    /// Returns whether the address is valid
+    pub(crate) fn action_set_peer_address(&mut self, input: &str) -> bool {
+        match parse_remote_opt(input) {
+            Ok(params) => {
+                self.peer_params = Some(params);
+                true
+            }
+            Err(err) => {
+                self.log_and_alert(LogLevel::Error, format!("Bad peer address: {}", err));
+                false
+            }
+        }
+    }
+
+    /// ### action_connect_peer
+    ///
+    /// Connect to the peer whose address has been provided before
+    pub(crate) fn action_connect_peer(&mut self, password: String) {
+        let mut params: FileTransferParams = match self.peer_params.take() {
+            Some(params) => params,
+            None => return,
+        };
+        if !password.is_empty() {
+            params.password = Some(password);
+        }
+        let mut client: Box<dyn FileTransfer> =
+            match Self::make_client(params.protocol, self.config()) {
+                Ok(client) => client,
+                Err(err) => {
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!("Could not connect to peer {}: {}", params.address, err),
+                    );
+                    return;
+                }
+            };
+        let tls: Option<TlsOptions> = self.tls_for(&params);
+        if let Err(err) = self
+            .proxy_for(&params)
+            .and_then(|proxy| client.set_proxy(proxy).map_err(|e| e.to_string()))
+            .and_then(|_| client.set_tls(tls).map_err(|e| e.to_string()))
+        {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not connect to peer {}: {}", params.address, err),
+            );
+            return;
+        }
+        if let Err(err) = client.connect(
+            params.address.clone(),
+            params.port,
+            params.username.clone(),
+            params.password.clone(),
+        ) {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not connect to peer {}: {}", params.address, err),
+            );
+            return;
+        }
+        // Enter entry directory
+        let wrkdir = match params.entry_directory.as_deref() {
+            Some(dir) => client.change_dir(dir),
+            None => client.pwd(),
+        };
+        match wrkdir {
+            Ok(wrkdir) => {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Connected to peer {}:{}; files will be relayed to \"{}\"",
+                        params.address,
+                        params.port,
+                        wrkdir.display()
+                    ),
+                );
+                self.peer = Some(Peer {
+                    client,
+                    params,
+                    wrkdir,
+                });
+            }
+            Err(err) => {
+                let _ = client.disconnect();
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not enter working directory on peer: {}", err),
+                );
+            }
+        }
+        self.refresh_remote_status_bar();
+    }
+
+    /// ### action_disconnect_peer
+    ///
+    /// Disconnect from peer
+    pub(crate) fn action_disconnect_peer(&mut self) {
+        if let Some(mut peer) = self.peer.take() {
+            match peer.client.disconnect() {
+                Ok(_) => self.log(
+                    LogLevel::Info,
+                    format!("Disconnected from peer {}", peer.params.address),
+                ),
+                Err(err) => self.log(
+                    LogLevel::Warn,
+                    format!("Could not disconnect from peer: {}", err),
+                ),
+            }
+            self.refresh_remote_status_bar();
+        }
+    }
+
+    /// ### action_relay_to_peer
+    ///
+    /// Stream selected remote entries to the working directory of the peer
+    pub(crate) fn action_relay_to_peer(&mut self) {
+        let (entries, marked): (Vec<FsEntry>, bool) = match self.get_remote_selected_entries() {
+            SelectedEntry::One(entry) => (vec![entry.get_realfile()], false),
+            SelectedEntry::Many(entries) => (
+                entries.into_iter().map(|x| x.get_realfile()).collect(),
+                true,
+            ),
+            SelectedEntry::None => return,
+        };
+        match self.filetransfer_relay(entries) {
+            Ok(_) if marked => self.remote_mut().clear_marks(),
+            Ok(_) => {}
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not relay files to peer: {}", err),
+            ),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::browser::FileExplorerTab;
+use super::super::COMPONENT_EXPLORER_REMOTE;
+use super::{FileTransferActivity, FsEntry, SelectedEntryIndex};
+// Ext
+use std::path::PathBuf;
+
+impl FileTransferActivity {
+    /// ### action_prefetch_remote_dir
+    ///
+    /// Called while idle: if a remote directory has been highlighted for long enough,
+    /// read its listing ahead of time, so that entering it doesn't have to wait for the remote
+    pub(crate) fn action_prefetch_remote_dir(&mut self) {
+        if self.prefetch.is_none() {
+            return;
+        }
+        let highlighted: Option<PathBuf> = self.highlighted_remote_dir();
+        let dir: PathBuf = match self.prefetch.as_mut().and_then(|x| {
+            x.highlight(highlighted);
+            x.due()
+        }) {
+            Some(dir) => dir,
+            None => return,
+        };
+        match self.client.list_dir(dir.as_path()) {
+            Ok(entries) => {
+                debug!("Prefetched {} ({} entries)", dir.display(), entries.len());
+                if let Some(prefetch) = self.prefetch.as_mut() {
+                    prefetch.insert(dir, entries);
+                }
+            }
+            // Not worth bothering the user: the directory will be listed again when entered
+            Err(err) => debug!("Could not prefetch {}: {}", dir.display(), err),
+        }
+    }
+
+    /// ### highlighted_remote_dir
+    ///
+    /// Get the path of the directory highlighted in the remote explorer, if the remote explorer is active
+    fn highlighted_remote_dir(&self) -> Option<PathBuf> {
+        if !matches!(self.browser.tab(), FileExplorerTab::Remote) {
+            return None;
+        }
+        let idx: usize = match self.get_selected_index(COMPONENT_EXPLORER_REMOTE) {
+            SelectedEntryIndex::One(idx) => idx,
+            _ => return None,
+        };
+        match self.remote().get(idx)? {
+            FsEntry::Directory(dir) => Some(dir.abs_path.clone()),
+            FsEntry::File(file) => match file.symlink.as_deref() {
+                Some(FsEntry::Directory(dir)) => Some(dir.abs_path.clone()),
+                _ => None,
+            },
+        }
+    }
+}
@@ -27,6 +27,7 @@
  */
 // locals
 use super::{FileTransferActivity, FsEntry, LogLevel, SelectedEntry};
+use crate::filetransfer::Capability;
 use std::path::{Path, PathBuf};
 
 impl FileTransferActivity {
@@ -47,6 +48,8 @@ impl FileTransferActivity {
                     dest_path.push(entry.get_name());
                     self.local_rename_file(entry, dest_path.as_path());
                 }
+                // Marks have been consumed
+                self.local_mut().clear_marks();
                 // Reload entries
                 self.reload_local_dir();
             }
@@ -71,6 +74,8 @@ impl FileTransferActivity {
                     dest_path.push(entry.get_name());
                     self.remote_rename_file(entry, dest_path.as_path());
                 }
+                // Marks have been consumed
+                self.remote_mut().clear_marks();
                 // Reload entries
                 self.reload_remote_dir();
             }
@@ -103,6 +108,18 @@ impl FileTransferActivity {
     }
 
     fn remote_rename_file(&mut self, entry: &FsEntry, dest: &Path) {
+        if !self.capabilities.supports(Capability::RenameOver) && self.client.stat(dest).is_ok() {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!(
+                    "Could not move \"{}\" to \"{}\": file exists and the server can't {}",
+                    entry.get_abs_path().display(),
+                    dest.display(),
+                    Capability::RenameOver.description()
+                ),
+            );
+            return;
+        }
         match self.client.as_mut().rename(entry, dest) {
             Ok(_) => {
                 self.log(
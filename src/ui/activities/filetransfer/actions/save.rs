@@ -26,42 +26,72 @@
  * SOFTWARE.
  */
 // locals
-use super::{FileTransferActivity, LogLevel, SelectedEntry, TransferPayload};
+use super::{FileTransferActivity, LogLevel, SelectedEntry, TransferDirection, TransferPayload};
 use std::path::PathBuf;
 
 impl FileTransferActivity {
     pub(crate) fn action_local_saveas(&mut self, input: String) {
-        self.action_local_send_file(Some(input));
+        self.action_local_send_file(Some(input), false);
     }
 
     pub(crate) fn action_remote_saveas(&mut self, input: String) {
-        self.action_remote_recv_file(Some(input));
+        self.action_remote_recv_file(Some(input), false);
     }
 
     pub(crate) fn action_local_send(&mut self) {
-        self.action_local_send_file(None);
+        self.action_local_send_file(None, false);
     }
 
     pub(crate) fn action_remote_recv(&mut self) {
-        self.action_remote_recv_file(None);
+        self.action_remote_recv_file(None, false);
     }
 
-    fn action_local_send_file(&mut self, save_as: Option<String>) {
+    /// ### action_local_move
+    ///
+    /// Upload the selected entries, then remove from localhost the files which have been transferred
+    pub(crate) fn action_local_move(&mut self) {
+        self.action_local_send_file(None, true);
+    }
+
+    /// ### action_remote_move
+    ///
+    /// Download the selected entries, then remove from remote the files which have been transferred
+    pub(crate) fn action_remote_move(&mut self) {
+        self.action_remote_recv_file(None, true);
+    }
+
+    fn action_local_send_file(&mut self, save_as: Option<String>, remove_source: bool) {
         let wrkdir: PathBuf = self.remote().wrkdir.clone();
         match self.get_local_selected_entries() {
+            SelectedEntry::One(entry) if entry.get_realfile().is_dir() => {
+                // Recursive transfer; preview manifest first
+                self.action_preview_transfer(
+                    TransferDirection::Upload,
+                    TransferPayload::Any(entry.get_realfile()),
+                    wrkdir,
+                    save_as,
+                    remove_source,
+                );
+            }
             SelectedEntry::One(entry) => {
-                if let Err(err) = self.filetransfer_send(
+                if remove_source {
+                    self.transfer.start_move();
+                }
+                let result = self.filetransfer_send(
                     TransferPayload::Any(entry.get_realfile()),
                     wrkdir.as_path(),
                     save_as,
-                ) {
-                    {
-                        self.log_and_alert(
-                            LogLevel::Error,
-                            format!("Could not upload file: {}", err),
-                        );
-                        return;
-                    }
+                );
+                if remove_source {
+                    self.finalize_move(
+                        TransferDirection::Upload,
+                        &[entry.get_realfile()],
+                        wrkdir.as_path(),
+                        result.is_ok(),
+                    );
+                }
+                if let Err(err) = result {
+                    self.log_and_alert(LogLevel::Error, format!("Could not upload file: {}", err));
                 }
             }
             SelectedEntry::Many(entries) => {
@@ -70,42 +100,59 @@ impl FileTransferActivity {
                 if let Some(save_as) = save_as {
                     dest_path.push(save_as);
                 }
-                // Iter files
+                // Iter files; preview manifest first
                 let entries = entries.iter().map(|x| x.get_realfile()).collect();
-                if let Err(err) = self.filetransfer_send(
+                self.action_preview_transfer(
+                    TransferDirection::Upload,
                     TransferPayload::Many(entries),
-                    dest_path.as_path(),
+                    dest_path,
                     None,
-                ) {
-                    {
-                        self.log_and_alert(
-                            LogLevel::Error,
-                            format!("Could not upload file: {}", err),
-                        );
-                        return;
-                    }
-                }
+                    remove_source,
+                );
             }
             SelectedEntry::None => {}
         }
     }
 
-    fn action_remote_recv_file(&mut self, save_as: Option<String>) {
-        let wrkdir: PathBuf = self.local().wrkdir.clone();
+    fn action_remote_recv_file(&mut self, save_as: Option<String>, remove_source: bool) {
+        // Names provided with save as are relative to the local working directory
+        let wrkdir: PathBuf = match save_as {
+            Some(_) => self.local().wrkdir.clone(),
+            None => self.download_dir(),
+        };
         match self.get_remote_selected_entries() {
+            SelectedEntry::One(entry) if entry.get_realfile().is_dir() => {
+                // Recursive transfer; preview manifest first
+                self.action_preview_transfer(
+                    TransferDirection::Download,
+                    TransferPayload::Any(entry.get_realfile()),
+                    wrkdir,
+                    save_as,
+                    remove_source,
+                );
+            }
             SelectedEntry::One(entry) => {
-                if let Err(err) = self.filetransfer_recv(
+                if remove_source {
+                    self.transfer.start_move();
+                }
+                let result = self.filetransfer_recv(
                     TransferPayload::Any(entry.get_realfile()),
                     wrkdir.as_path(),
                     save_as,
-                ) {
-                    {
-                        self.log_and_alert(
-                            LogLevel::Error,
-                            format!("Could not download file: {}", err),
-                        );
-                        return;
-                    }
+                );
+                if remove_source {
+                    self.finalize_move(
+                        TransferDirection::Download,
+                        &[entry.get_realfile()],
+                        wrkdir.as_path(),
+                        result.is_ok(),
+                    );
+                }
+                if let Err(err) = result {
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!("Could not download file: {}", err),
+                    );
                 }
             }
             SelectedEntry::Many(entries) => {
@@ -114,23 +161,38 @@ impl FileTransferActivity {
                 if let Some(save_as) = save_as {
                     dest_path.push(save_as);
                 }
-                // Iter files
+                // Iter files; preview manifest first
                 let entries = entries.iter().map(|x| x.get_realfile()).collect();
-                if let Err(err) = self.filetransfer_recv(
+                self.action_preview_transfer(
+                    TransferDirection::Download,
                     TransferPayload::Many(entries),
-                    dest_path.as_path(),
+                    dest_path,
                     None,
-                ) {
-                    {
-                        self.log_and_alert(
-                            LogLevel::Error,
-                            format!("Could not download file: {}", err),
-                        );
-                        return;
-                    }
-                }
+                    remove_source,
+                );
             }
             SelectedEntry::None => {}
         }
     }
+
+    /// ### action_toggle_download_dir
+    ///
+    /// Switch downloads between the default download directory and the local working directory
+    pub(crate) fn action_toggle_download_dir(&mut self) {
+        match self.config().get_download_dir() {
+            Some(dir) => {
+                self.browser.toggle_download_to_default();
+                let dest: String = match self.browser.download_to_default {
+                    true => dir.display().to_string(),
+                    false => String::from("the local working directory"),
+                };
+                self.log(LogLevel::Info, format!("Downloading files into {}", dest));
+                self.refresh_local_status_bar();
+            }
+            None => self.log_and_alert(
+                LogLevel::Warn,
+                String::from("No default download directory is set in the configuration"),
+            ),
+        }
+    }
 }
@@ -0,0 +1,141 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::lib::trace::TransferTrace;
+use super::{FileTransferActivity, LogLevel};
+use crate::system::environment;
+// Ext
+use chrono::Local;
+use std::path::PathBuf;
+use std::time::Duration;
+
+impl FileTransferActivity {
+    /// ### action_toggle_trace
+    ///
+    /// Enable or disable the debug trace of the next transfer
+    pub(crate) fn action_toggle_trace(&mut self) {
+        self.trace_next = !self.trace_next;
+        match self.trace_next {
+            true => self.log(
+                LogLevel::Info,
+                String::from("The next transfer will be traced"),
+            ),
+            false => self.log(LogLevel::Info, String::from("Transfer tracing disabled")),
+        }
+        self.refresh_local_status_bar();
+    }
+
+    /// ### start_trace
+    ///
+    /// Start tracing the transfer described by `transfer`, if tracing has been enabled for it
+    pub(crate) fn start_trace(&mut self, transfer: String) {
+        if !self.trace_next {
+            return;
+        }
+        self.trace_next = false;
+        self.refresh_local_status_bar();
+        let dir: PathBuf = match environment::init_config_dir() {
+            Ok(Some(config_dir)) => environment::get_traces_dir(config_dir.as_path()),
+            _ => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    String::from("Could not trace transfer: no configuration directory"),
+                );
+                return;
+            }
+        };
+        match TransferTrace::create(dir.as_path(), Local::now()) {
+            Ok(mut trace) => {
+                let params: String = self
+                    .context()
+                    .ft_params()
+                    .map(|x| format!("{:?} {}:{}", x.protocol, x.address, x.port))
+                    .unwrap_or_default();
+                trace.event(format!("START {} ({})", transfer, params));
+                self.log(
+                    LogLevel::Info,
+                    format!("Tracing transfer into \"{}\"", trace.path().display()),
+                );
+                self.trace = Some(trace);
+            }
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not create transfer trace: {}", err),
+            ),
+        }
+    }
+
+    /// ### finish_trace
+    ///
+    /// Complete the trace of the current transfer, if any, with its result
+    pub(crate) fn finish_trace(&mut self, result: &Result<(), String>) {
+        let trace: TransferTrace = match self.trace.take() {
+            Some(trace) => trace,
+            None => return,
+        };
+        let outcome: String = match (result, self.transfer.aborted()) {
+            (_, true) => String::from("aborted"),
+            (Ok(_), false) => String::from("ok"),
+            (Err(err), false) => format!("failed ({})", err),
+        };
+        match trace.finish(outcome.as_str()) {
+            Ok(path) => self.log(
+                LogLevel::Info,
+                format!("Transfer trace written to \"{}\"", path.display()),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not write transfer trace: {}", err),
+            ),
+        }
+    }
+
+    /// ### trace_event
+    ///
+    /// Write a protocol-level event to the trace of the current transfer, if any
+    pub(crate) fn trace_event<S: AsRef<str>>(&mut self, event: S) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.event(event);
+        }
+    }
+
+    /// ### trace_chunk
+    ///
+    /// Write the timings of a chunk to the trace of the current transfer, if any
+    pub(crate) fn trace_chunk(
+        &mut self,
+        window: usize,
+        read: usize,
+        read_time: Duration,
+        write_time: Duration,
+    ) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.chunk(window, read, read_time, write_time);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::{FileTransferActivity, FsEntry, LogLevel};
+// Ext
+use chrono::Local;
+use std::path::{Path, PathBuf};
+
+impl FileTransferActivity {
+    /// ### action_remote_purge_trash
+    ///
+    /// Permanently remove all the entries in the remote trash directory for this host
+    pub(crate) fn action_remote_purge_trash(&mut self) {
+        let trash: PathBuf = match self.remote_trash() {
+            Some(trash) => trash,
+            None => return,
+        };
+        let entries: Vec<FsEntry> = match self.client.list_dir(trash.as_path()) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Could not list remote trash \"{}\": {}",
+                        trash.display(),
+                        err
+                    ),
+                );
+                return;
+            }
+        };
+        let mut removed: usize = 0;
+        for entry in entries.iter() {
+            match self.client.remove(entry) {
+                Ok(_) => removed += 1,
+                Err(err) => self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Could not delete file \"{}\": {}",
+                        entry.get_abs_path().display(),
+                        err
+                    ),
+                ),
+            }
+        }
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Purged remote trash \"{}\" ({} entries removed)",
+                trash.display(),
+                removed
+            ),
+        );
+    }
+
+    /// ### remote_trash
+    ///
+    /// Get the remote trash directory saved in the bookmark for this host
+    pub(crate) fn remote_trash(&self) -> Option<PathBuf> {
+        let host: String = self.bookmarks_host_key();
+        self.bookmarks
+            .as_ref()
+            .and_then(|x| x.get_trash(host.as_str()))
+    }
+
+    /// ### remote_trash_file
+    ///
+    /// Move `entry` into the remote `trash` directory, creating it if missing.
+    /// If an entry with the same name is already in the trash, the current time is appended to the name
+    pub(crate) fn remote_trash_file(&mut self, entry: &FsEntry, trash: &Path) {
+        if let Err(err) = self.client.mkdir_all(trash) {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!(
+                    "Could not create remote trash \"{}\": {}",
+                    trash.display(),
+                    err
+                ),
+            );
+            return;
+        }
+        let mut dst: PathBuf = trash.join(entry.get_name());
+        if self.client.stat(dst.as_path()).is_ok() {
+            dst = trash.join(format!(
+                "{}.{}",
+                entry.get_name(),
+                Local::now().format("%Y%m%d%H%M%S")
+            ));
+        }
+        match self.client.rename(entry, dst.as_path()) {
+            Ok(_) => {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Moved \"{}\" to trash (\"{}\")",
+                        entry.get_abs_path().display(),
+                        dst.display()
+                    ),
+                );
+            }
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Could not move \"{}\" to trash: {}",
+                        entry.get_abs_path().display(),
+                        err
+                    ),
+                );
+            }
+        }
+    }
+}
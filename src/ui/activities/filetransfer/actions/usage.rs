@@ -0,0 +1,53 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::FileTransferActivity;
+use crate::config::usage::Usage;
+use crate::system::usage_client::UsageClient;
+
+impl FileTransferActivity {
+    /// ### action_show_usage
+    ///
+    /// Show the bandwidth used with the remote host, month by month
+    pub(crate) fn action_show_usage(&mut self) {
+        let host: String = self.bookmarks_host_key();
+        let usage: Vec<(String, Usage)> = match self.usage.as_ref() {
+            Some(client) => client.host_usage(host.as_str()),
+            None => {
+                self.mount_error("Bandwidth usage is not available");
+                return;
+            }
+        };
+        let current: Usage = self
+            .usage
+            .as_ref()
+            .map(|x| x.month_usage(host.as_str(), UsageClient::current_month().as_str()))
+            .unwrap_or_default();
+        self.mount_usage_report(current, usage.as_slice());
+    }
+}
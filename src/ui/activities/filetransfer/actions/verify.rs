@@ -0,0 +1,327 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::lib::verify::{hash_reader, DriftKind, DriftReport};
+use super::{FileTransferActivity, FsEntry, LogLevel};
+use crate::fs::FsFile;
+use crate::utils::fmt::fmt_time;
+// Ext
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+impl FileTransferActivity {
+    /// ### action_verify
+    ///
+    /// Compare local and remote working directories, without transferring anything,
+    /// and show the drift report
+    pub(crate) fn action_verify(&mut self) {
+        let local: PathBuf = self.local().wrkdir.clone();
+        let remote: PathBuf = self.remote().wrkdir.clone();
+        self.mount_blocking_wait("Verifying local and remote trees...");
+        let mut report: DriftReport = DriftReport::new(local.as_path(), remote.as_path());
+        self.verify_dir(
+            local.as_path(),
+            remote.as_path(),
+            Path::new(""),
+            &mut report,
+        );
+        self.umount_wait();
+        match report.is_clean() {
+            true => self.log(
+                LogLevel::Info,
+                format!(
+                    "Verified \"{}\" against \"{}\": no drift ({} entries)",
+                    local.display(),
+                    remote.display(),
+                    report.checked
+                ),
+            ),
+            false => self.log(
+                LogLevel::Warn,
+                format!(
+                    "Verified \"{}\" against \"{}\": {} drift(s) in {} entries",
+                    local.display(),
+                    remote.display(),
+                    report.drifts.len(),
+                    report.checked
+                ),
+            ),
+        }
+        self.mount_drift_report(&report);
+        self.drift_report = Some(report);
+    }
+
+    /// ### action_export_drift_report
+    ///
+    /// Export last drift report to a JSON file in the local working directory
+    pub(crate) fn action_export_drift_report(&mut self) {
+        let json: String = match self.drift_report.as_ref().map(|x| x.to_json()) {
+            Some(Ok(json)) => json,
+            Some(Err(err)) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not serialize drift report: {}", err),
+                );
+                return;
+            }
+            None => return,
+        };
+        let mut path: PathBuf = self.local().wrkdir.clone();
+        path.push(format!(
+            "termscp-drift-{}.json",
+            fmt_time(SystemTime::now(), "%Y%m%dT%H%M%S")
+        ));
+        match self
+            .host
+            .open_file_write(path.as_path())
+            .map_err(|e| e.to_string())
+            .and_then(|mut f| f.write_all(json.as_bytes()).map_err(|e| e.to_string()))
+        {
+            Ok(_) => {
+                self.log(
+                    LogLevel::Info,
+                    format!("Exported drift report to \"{}\"", path.display()),
+                );
+                self.reload_local_dir();
+            }
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!(
+                    "Could not export drift report to \"{}\": {}",
+                    path.display(),
+                    err
+                ),
+            ),
+        }
+    }
+
+    /// ### verify_transferred
+    ///
+    /// If verification is enabled for the session, compare the file just transferred between
+    /// `local` and `remote`, and alert if they differ
+    pub(crate) fn verify_transferred(&mut self, local: &Path, remote: &Path) {
+        if self.options.verify && !self.verify_transferred_file(local, remote) {
+            // Sources must not be removed by a move if they haven't been verified
+            self.transfer.keep_source(local);
+            self.transfer.keep_source(remote);
+        }
+    }
+
+    /// ### verify_transferred_file
+    ///
+    /// Compare the file transferred between `local` and `remote`. Returns whether they match
+    fn verify_transferred_file(&mut self, local: &Path, remote: &Path) -> bool {
+        let local_file: FsFile = match self.host.stat(local) {
+            Ok(FsEntry::File(file)) => file,
+            Ok(FsEntry::Directory(_)) => return true,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not verify \"{}\": {}", local.display(), err),
+                );
+                return false;
+            }
+        };
+        let remote_file: FsFile = match self.client.stat(remote) {
+            Ok(FsEntry::File(file)) => file,
+            Ok(FsEntry::Directory(_)) => return true,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not verify \"{}\": {}", remote.display(), err),
+                );
+                return false;
+            }
+        };
+        match self.verify_file(&local_file, &remote_file) {
+            None => {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Verified \"{}\" against \"{}\"",
+                        local.display(),
+                        remote.display()
+                    ),
+                );
+                true
+            }
+            Some(kind) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Verification of \"{}\" against \"{}\" failed: {}",
+                        local.display(),
+                        remote.display(),
+                        kind.describe()
+                    ),
+                );
+                false
+            }
+        }
+    }
+
+    /// ### verify_dir
+    ///
+    /// Compare local directory with remote directory; `rel` is the path relative to the roots
+    fn verify_dir(&mut self, local: &Path, remote: &Path, rel: &Path, report: &mut DriftReport) {
+        let local_entries: Vec<FsEntry> = match self.host.scan_dir(local) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not scan directory \"{}\": {}", local.display(), err),
+                );
+                return;
+            }
+        };
+        let remote_entries: Vec<FsEntry> = match self.client.list_dir(remote) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not scan directory \"{}\": {}", remote.display(), err),
+                );
+                return;
+            }
+        };
+        // Apply transfer filters
+        let local_entries: Vec<FsEntry> = local_entries
+            .into_iter()
+            .filter(|x| self.is_transferred(x))
+            .collect();
+        let remote_entries: Vec<FsEntry> = remote_entries
+            .into_iter()
+            .filter(|x| self.is_transferred(x))
+            .collect();
+        for local_entry in local_entries.iter() {
+            let rel_path: PathBuf = rel.join(local_entry.get_name());
+            let remote_entry: Option<&FsEntry> = remote_entries
+                .iter()
+                .find(|x| x.get_name() == local_entry.get_name());
+            match (local_entry, remote_entry) {
+                (_, None) => {
+                    report.checked += 1;
+                    report.push(rel_path, DriftKind::MissingRemote);
+                }
+                (FsEntry::Directory(local_dir), Some(FsEntry::Directory(remote_dir))) => {
+                    self.verify_dir(
+                        local_dir.abs_path.as_path(),
+                        remote_dir.abs_path.as_path(),
+                        rel_path.as_path(),
+                        report,
+                    );
+                }
+                (FsEntry::File(local_file), Some(FsEntry::File(remote_file))) => {
+                    report.checked += 1;
+                    if let Some(kind) = self.verify_file(local_file, remote_file) {
+                        report.push(rel_path, kind);
+                    }
+                }
+                (_, Some(_)) => {
+                    report.checked += 1;
+                    report.push(rel_path, DriftKind::TypeMismatch);
+                }
+            }
+        }
+        // Entries on remote only
+        for remote_entry in remote_entries
+            .iter()
+            .filter(|x| !local_entries.iter().any(|y| y.get_name() == x.get_name()))
+        {
+            report.checked += 1;
+            report.push(rel.join(remote_entry.get_name()), DriftKind::MissingLocal);
+        }
+    }
+
+    /// ### verify_file
+    ///
+    /// Compare local file with remote file; size is compared first, then SHA-256 of the content
+    fn verify_file(&mut self, local: &FsFile, remote: &FsFile) -> Option<DriftKind> {
+        if local.size != remote.size {
+            return Some(DriftKind::SizeMismatch {
+                local: local.size,
+                remote: remote.size,
+            });
+        }
+        let local_hash: String = match self
+            .host
+            .open_file_read(local.abs_path.as_path())
+            .map_err(|e| e.to_string())
+            .and_then(|mut f| hash_reader(&mut f).map_err(|e| e.to_string()))
+        {
+            Ok(hash) => hash,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not read \"{}\": {}", local.abs_path.display(), err),
+                );
+                return None;
+            }
+        };
+        let remote_hash: String = match self.client.recv_file(remote) {
+            Ok(mut reader) => {
+                let hash = hash_reader(&mut reader);
+                if let Err(err) = self.client.on_recv(reader) {
+                    self.log(
+                        LogLevel::Warn,
+                        format!(
+                            "Could not finalize read of \"{}\": {}",
+                            remote.abs_path.display(),
+                            err
+                        ),
+                    );
+                }
+                match hash {
+                    Ok(hash) => hash,
+                    Err(err) => {
+                        self.log(
+                            LogLevel::Error,
+                            format!("Could not read \"{}\": {}", remote.abs_path.display(), err),
+                        );
+                        return None;
+                    }
+                }
+            }
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not read \"{}\": {}", remote.abs_path.display(), err),
+                );
+                return None;
+            }
+        };
+        match local_hash == remote_hash {
+            true => None,
+            false => Some(DriftKind::HashMismatch {
+                local: local_hash,
+                remote: remote_hash,
+            }),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::super::lib::watch::Watch;
+use super::{FileTransferActivity, FsEntry, LogLevel, TransferPayload};
+// Ext
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// How often the watched directory is scanned for changes
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+impl FileTransferActivity {
+    /// ### action_toggle_watch
+    ///
+    /// Start uploading the files changed in the local working directory into the remote working directory;
+    /// or stop, if already watching
+    pub(crate) fn action_toggle_watch(&mut self) {
+        match self.watch.take() {
+            Some(watch) => self.log(
+                LogLevel::Info,
+                format!("Stopped watching \"{}\"", watch.local().display()),
+            ),
+            None => {
+                let local: PathBuf = self.local().wrkdir.clone();
+                // Prefer the remote directory mirroring the local one in the root mapping
+                let remote: PathBuf = self
+                    .browser
+                    .map_to_remote(local.as_path())
+                    .unwrap_or_else(|| self.remote().wrkdir.clone());
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Watching \"{}\": changed files are uploaded into \"{}\"",
+                        local.display(),
+                        remote.display()
+                    ),
+                );
+                self.watch = Some(Watch::new(
+                    local.as_path(),
+                    remote.as_path(),
+                    WATCH_INTERVAL,
+                ));
+            }
+        }
+        self.refresh_local_status_bar();
+    }
+
+    /// ### action_watch_local_dir
+    ///
+    /// Called while idle: upload the files which changed in the watched directory.
+    /// Returns whether anything has been uploaded
+    pub(crate) fn action_watch_local_dir(&mut self) -> bool {
+        let changed: Vec<PathBuf> = match self.watch.as_mut() {
+            Some(watch) => watch.poll(),
+            None => return false,
+        };
+        if changed.is_empty() {
+            return false;
+        }
+        for path in changed.iter() {
+            if let Err(err) = self.watch_upload(path.as_path()) {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not upload \"{}\": {}", path.display(), err),
+                );
+            }
+        }
+        // Uploads may have changed the remote working directory
+        self.reload_remote_dir();
+        self.update_remote_filelist();
+        true
+    }
+
+    /// ### watch_upload
+    ///
+    /// Upload `path`, changed in the watched directory, to its remote counterpart.
+    /// Files excluded by the transfer filters are skipped
+    fn watch_upload(&mut self, path: &Path) -> Result<(), String> {
+        let (local, remote): (PathBuf, PathBuf) = match self.watch.as_ref() {
+            Some(watch) => (watch.local().to_path_buf(), watch.remote_path(path)),
+            None => return Ok(()),
+        };
+        if !self.watch_accepts(local.as_path(), path) {
+            debug!("Skipping {}: excluded by filters", path.display());
+            return Ok(());
+        }
+        // Root mapping may have changed since the watch was started
+        let remote: PathBuf = self.browser.map_to_remote(path).unwrap_or(remote);
+        let file = match self.host.stat(path) {
+            Ok(FsEntry::File(file)) => file,
+            Ok(FsEntry::Directory(_)) => return Ok(()),
+            Err(err) => return Err(err.to_string()),
+        };
+        let parent: PathBuf = remote.parent().map(PathBuf::from).unwrap_or_default();
+        self.client
+            .mkdir_all(parent.as_path())
+            .map_err(|err| err.to_string())?;
+        self.log(
+            LogLevel::Info,
+            format!(
+                "\"{}\" changed; uploading to \"{}\"",
+                path.display(),
+                remote.display()
+            ),
+        );
+        self.filetransfer_send(TransferPayload::File(file), parent.as_path(), None)
+    }
+
+    /// ### watch_accepts
+    ///
+    /// Returns whether `path`, in the watched directory `local`, is accepted by the transfer filters.
+    /// As for recursive uploads, the file is excluded if any of the directories leading to it is excluded
+    fn watch_accepts(&self, local: &Path, path: &Path) -> bool {
+        let relative: &Path = path.strip_prefix(local).unwrap_or(path);
+        let components: Vec<&str> = relative
+            .components()
+            .filter_map(|x| match x {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect();
+        components
+            .iter()
+            .enumerate()
+            .all(|(i, name)| self.filter.is_included(name, i + 1 < components.len()))
+    }
+}
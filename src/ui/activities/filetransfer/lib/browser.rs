@@ -29,6 +29,8 @@ use crate::fs::explorer::{builder::FileExplorerBuilder, FileExplorer, FileSortin
 use crate::fs::FsEntry;
 use crate::system::config_client::ConfigClient;
 
+use std::path::{Path, PathBuf};
+
 /// ## FileExplorerTab
 ///
 /// File explorer tab
@@ -49,6 +51,8 @@ pub struct Browser {
     found: Option<FileExplorer>, // File explorer for find result
     tab: FileExplorerTab,        // Current selected tab
     pub sync_browsing: bool,
+    pub download_to_default: bool, // Downloads go to the default download directory, rather than the local pane
+    root_mapping: Option<(PathBuf, PathBuf)>, // Local root <-> remote root
 }
 
 impl Browser {
@@ -62,6 +66,8 @@ impl Browser {
             found: None,
             tab: FileExplorerTab::Local,
             sync_browsing: false,
+            download_to_default: cli.get_download_dir().is_some(),
+            root_mapping: None,
         }
     }
 
@@ -117,6 +123,53 @@ impl Browser {
         self.sync_browsing = !self.sync_browsing;
     }
 
+    /// ### toggle_download_to_default
+    ///
+    /// Switch downloads between the default download directory and the local working directory
+    pub fn toggle_download_to_default(&mut self) {
+        self.download_to_default = !self.download_to_default;
+    }
+
+    /// ### set_root_mapping
+    ///
+    /// Set the local and remote directories which mirror each other
+    pub fn set_root_mapping(&mut self, local: PathBuf, remote: PathBuf) {
+        self.root_mapping = Some((local, remote));
+    }
+
+    /// ### root_mapping
+    ///
+    /// Get the current root mapping as (local, remote)
+    pub fn root_mapping(&self) -> Option<(&Path, &Path)> {
+        self.root_mapping
+            .as_ref()
+            .map(|(local, remote)| (local.as_path(), remote.as_path()))
+    }
+
+    /// ### map_to_remote
+    ///
+    /// Get the remote path matching `local` according to the root mapping.
+    /// Returns `None` if there's no mapping or if `local` is outside of the local root
+    pub fn map_to_remote(&self, local: &Path) -> Option<PathBuf> {
+        let (local_root, remote_root) = self.root_mapping()?;
+        local
+            .strip_prefix(local_root)
+            .ok()
+            .map(|rel| remote_root.join(rel))
+    }
+
+    /// ### map_to_local
+    ///
+    /// Get the local path matching `remote` according to the root mapping.
+    /// Returns `None` if there's no mapping or if `remote` is outside of the remote root
+    pub fn map_to_local(&self, remote: &Path) -> Option<PathBuf> {
+        let (local_root, remote_root) = self.root_mapping()?;
+        remote
+            .strip_prefix(remote_root)
+            .ok()
+            .map(|rel| local_root.join(rel))
+    }
+
     /// ### build_local_explorer
     ///
     /// Build a file explorer with local host setup
@@ -142,7 +195,7 @@ impl Browser {
         let mut builder: FileExplorerBuilder = FileExplorerBuilder::new();
         // Set common keys
         builder
-            .with_file_sorting(FileSorting::Name)
+            .with_file_sorting(cli.get_file_sorting())
             .with_stack_size(16)
             .with_group_dirs(cli.get_group_dirs())
             .with_hidden_files(cli.get_show_hidden_files());
@@ -162,3 +215,45 @@ impl Browser {
             .build()
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_browser_root_mapping() {
+        let mut browser: Browser = Browser::new(&ConfigClient::degraded());
+        assert!(browser.root_mapping().is_none());
+        assert!(browser.map_to_remote(Path::new("/tmp")).is_none());
+        browser.set_root_mapping(
+            PathBuf::from("/home/omar/project"),
+            PathBuf::from("/var/www/project"),
+        );
+        assert_eq!(
+            browser.root_mapping().unwrap(),
+            (
+                Path::new("/home/omar/project"),
+                Path::new("/var/www/project")
+            )
+        );
+        // Map paths
+        assert_eq!(
+            browser
+                .map_to_remote(Path::new("/home/omar/project/src/css"))
+                .unwrap(),
+            PathBuf::from("/var/www/project/src/css")
+        );
+        assert_eq!(
+            browser.map_to_local(Path::new("/var/www/project")).unwrap(),
+            PathBuf::from("/home/omar/project")
+        );
+        // Outside of mapping
+        assert!(browser.map_to_remote(Path::new("/home/omar")).is_none());
+        assert!(browser
+            .map_to_local(Path::new("/var/www/project2/src"))
+            .is_none());
+    }
+}
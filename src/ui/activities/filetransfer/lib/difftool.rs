@@ -0,0 +1,125 @@
+//! ## Difftool
+//!
+//! `difftool` is the module which expands and runs the external diff and merge tools
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+/// Placeholder replaced with the path of the local file
+pub const PLACEHOLDER_LOCAL: &str = "{local}";
+/// Placeholder replaced with the path of the (temporary copy of the) remote file
+pub const PLACEHOLDER_REMOTE: &str = "{remote}";
+
+/// ### expand_command
+///
+/// Replace placeholders in the tool command with the quoted paths of the files to compare.
+/// If the command has no placeholder at all, the paths are appended as `{local} {remote}`
+pub fn expand_command(template: &str, local: &Path, remote: &Path) -> String {
+    let template: String =
+        match template.contains(PLACEHOLDER_LOCAL) || template.contains(PLACEHOLDER_REMOTE) {
+            true => template.to_string(),
+            false => format!("{} {} {}", template, PLACEHOLDER_LOCAL, PLACEHOLDER_REMOTE),
+        };
+    template
+        .replace(PLACEHOLDER_LOCAL, quote(local).as_str())
+        .replace(PLACEHOLDER_REMOTE, quote(remote).as_str())
+}
+
+/// ### run_command
+///
+/// Run the expanded tool command through the system shell and wait for it to exit
+pub fn run_command(command: &str) -> io::Result<ExitStatus> {
+    #[cfg(target_family = "unix")]
+    let mut shell: Command = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    };
+    #[cfg(target_os = "windows")]
+    let mut shell: Command = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(command);
+        shell
+    };
+    shell.status()
+}
+
+/// ### quote
+///
+/// Quote path for the system shell
+#[cfg(target_family = "unix")]
+fn quote(p: &Path) -> String {
+    format!("'{}'", p.to_string_lossy().replace('\'', "'\\''"))
+}
+
+#[cfg(target_os = "windows")]
+fn quote(p: &Path) -> String {
+    format!("\"{}\"", p.to_string_lossy())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_ui_activities_filetransfer_lib_difftool_expand() {
+        assert_eq!(
+            expand_command(
+                "vimdiff {local} {remote}",
+                Path::new("/home/omar/README.md"),
+                Path::new("/tmp/remote/README.md")
+            )
+            .as_str(),
+            "vimdiff '/home/omar/README.md' '/tmp/remote/README.md'"
+        );
+        assert_eq!(
+            expand_command(
+                "meld --diff {remote} {local}",
+                Path::new("/home/omar/it's.txt"),
+                Path::new("/tmp/remote/it's.txt")
+            )
+            .as_str(),
+            "meld --diff '/tmp/remote/it'\\''s.txt' '/home/omar/it'\\''s.txt'"
+        );
+        // No placeholders
+        assert_eq!(
+            expand_command("diff -u", Path::new("/a.txt"), Path::new("/b.txt")).as_str(),
+            "diff -u '/a.txt' '/b.txt'"
+        );
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_ui_activities_filetransfer_lib_difftool_run() {
+        assert!(run_command("true").unwrap().success());
+        assert!(!run_command("false").unwrap().success());
+    }
+}
@@ -0,0 +1,186 @@
+//! ## DryRun
+//!
+//! `dry_run` is the module which describes what a recursive operation is going to do
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::path::PathBuf;
+
+/// ## DryRunAction
+///
+/// What an operation is going to do with an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DryRunAction {
+    Mkdir,
+    Copy,
+    Overwrite,
+    Resume,
+    Rename,
+    Ask,
+    Skip,
+    Delete,
+    Trash,
+}
+
+impl DryRunAction {
+    const ALL: [DryRunAction; 9] = [
+        DryRunAction::Mkdir,
+        DryRunAction::Copy,
+        DryRunAction::Overwrite,
+        DryRunAction::Resume,
+        DryRunAction::Rename,
+        DryRunAction::Ask,
+        DryRunAction::Skip,
+        DryRunAction::Delete,
+        DryRunAction::Trash,
+    ];
+
+    /// ### describe
+    ///
+    /// Get a short description of the action
+    pub fn describe(&self) -> &'static str {
+        match self {
+            DryRunAction::Mkdir => "create directory",
+            DryRunAction::Copy => "copy",
+            DryRunAction::Overwrite => "overwrite",
+            DryRunAction::Resume => "resume",
+            DryRunAction::Rename => "copy as duplicate",
+            DryRunAction::Ask => "exists (ask)",
+            DryRunAction::Skip => "skip",
+            DryRunAction::Delete => "delete",
+            DryRunAction::Trash => "move to trash",
+        }
+    }
+}
+
+/// ## DryRunEntry
+///
+/// An action on a single entry; path is the destination for transfers, and the removed entry for deletions
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunEntry {
+    pub action: DryRunAction,
+    pub path: PathBuf,
+    pub size: usize,
+}
+
+/// ## DryRunReport
+///
+/// The actions an operation is going to perform, in the order they'll be performed
+#[derive(Debug, Default)]
+pub struct DryRunReport {
+    pub entries: Vec<DryRunEntry>,
+}
+
+impl DryRunReport {
+    /// ### push
+    ///
+    /// Push a new action to the report
+    pub fn push(&mut self, action: DryRunAction, path: PathBuf, size: usize) {
+        self.entries.push(DryRunEntry { action, path, size });
+    }
+
+    /// ### count
+    ///
+    /// Get the amount of entries with `action`
+    pub fn count(&self, action: DryRunAction) -> usize {
+        self.entries.iter().filter(|x| x.action == action).count()
+    }
+
+    /// ### written
+    ///
+    /// Get the amount of bytes which are going to be written
+    pub fn written(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|x| {
+                matches!(
+                    x.action,
+                    DryRunAction::Copy
+                        | DryRunAction::Overwrite
+                        | DryRunAction::Resume
+                        | DryRunAction::Rename
+                        | DryRunAction::Ask
+                )
+            })
+            .map(|x| x.size)
+            .sum()
+    }
+
+    /// ### summary
+    ///
+    /// Get the amount of entries for each action which occurs in the report (e.g. `3 copy, 1 delete`)
+    pub fn summary(&self) -> String {
+        let summary: Vec<String> = DryRunAction::ALL
+            .iter()
+            .map(|x| (x, self.count(*x)))
+            .filter(|(_, count)| *count > 0)
+            .map(|(x, count)| format!("{} {}", count, x.describe()))
+            .collect();
+        match summary.is_empty() {
+            true => String::from("nothing to do"),
+            false => summary.join(", "),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_dry_run() {
+        let mut report: DryRunReport = DryRunReport::default();
+        assert_eq!(report.summary(), "nothing to do");
+        report.push(DryRunAction::Mkdir, PathBuf::from("/tmp/src"), 0);
+        report.push(DryRunAction::Copy, PathBuf::from("/tmp/src/main.rs"), 512);
+        report.push(
+            DryRunAction::Overwrite,
+            PathBuf::from("/tmp/src/lib.rs"),
+            256,
+        );
+        report.push(DryRunAction::Skip, PathBuf::from("/tmp/src/mod.rs"), 128);
+        report.push(DryRunAction::Copy, PathBuf::from("/tmp/README.md"), 1024);
+        assert_eq!(report.count(DryRunAction::Copy), 2);
+        assert_eq!(report.count(DryRunAction::Delete), 0);
+        assert_eq!(report.written(), 1792);
+        assert_eq!(
+            report.summary(),
+            "1 create directory, 2 copy, 1 overwrite, 1 skip"
+        );
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_dry_run_delete() {
+        let mut report: DryRunReport = DryRunReport::default();
+        report.push(DryRunAction::Delete, PathBuf::from("/tmp/src/main.rs"), 512);
+        report.push(DryRunAction::Delete, PathBuf::from("/tmp/src"), 0);
+        report.push(DryRunAction::Trash, PathBuf::from("/tmp/README.md"), 1024);
+        assert_eq!(report.written(), 0);
+        assert_eq!(report.summary(), "2 delete, 1 move to trash");
+        assert_eq!(report.entries[1].action.describe(), "delete");
+    }
+}
@@ -0,0 +1,209 @@
+//! ## HexDump
+//!
+//! `hexdump` formats binary files as a read-only hex dump, since they can't be opened in a text editor
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Ext
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Amount of bytes displayed at once
+pub const BLOCK_SIZE: u64 = 64 * 1024;
+/// Amount of bytes displayed on each line
+const LINE_SIZE: usize = 16;
+
+/// ## HexView
+///
+/// A file being viewed as a hex dump
+#[derive(Debug, Clone)]
+pub struct HexView {
+    pub name: String,
+    pub path: PathBuf, // Local path to read; a temporary copy for remote files
+    pub size: u64,
+    pub offset: u64, // Offset of the block being displayed
+}
+
+impl HexView {
+    pub fn new(name: &str, path: &Path, size: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            size,
+            offset: 0,
+        }
+    }
+
+    /// ### read_block
+    ///
+    /// Read the block starting at the current offset and format it as lines of hex dump
+    pub fn read_block(&self) -> std::io::Result<Vec<String>> {
+        let mut file: File = File::open(self.path.as_path())?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut data: Vec<u8> = Vec::new();
+        file.take(BLOCK_SIZE).read_to_end(&mut data)?;
+        Ok(hexdump(data.as_slice(), self.offset))
+    }
+
+    /// ### next_block
+    ///
+    /// Move to the next block; returns whether the offset changed
+    pub fn next_block(&mut self) -> bool {
+        self.seek(self.offset + BLOCK_SIZE)
+    }
+
+    /// ### prev_block
+    ///
+    /// Move to the previous block; returns whether the offset changed
+    pub fn prev_block(&mut self) -> bool {
+        match self.offset {
+            0 => false,
+            offset => self.seek(offset.saturating_sub(BLOCK_SIZE)),
+        }
+    }
+
+    /// ### seek
+    ///
+    /// Move the displayed block to `offset`, aligned to the beginning of its line.
+    /// Returns whether the offset changed; offsets past the end of the file are ignored
+    pub fn seek(&mut self, offset: u64) -> bool {
+        if offset >= self.size {
+            return false;
+        }
+        let offset: u64 = offset - (offset % LINE_SIZE as u64);
+        let changed: bool = offset != self.offset;
+        self.offset = offset;
+        changed
+    }
+
+    /// ### end
+    ///
+    /// Returns the offset of the last byte displayed
+    pub fn end(&self) -> u64 {
+        std::cmp::min(self.offset + BLOCK_SIZE, self.size).saturating_sub(1)
+    }
+}
+
+/// ### hexdump
+///
+/// Format `data`, read at `offset`, as lines of offset, bytes in hex and their ASCII representation
+pub fn hexdump(data: &[u8], offset: u64) -> Vec<String> {
+    data.chunks(LINE_SIZE)
+        .enumerate()
+        .map(|(i, line)| {
+            let hex: Vec<String> = line.iter().map(|x| format!("{:02x}", x)).collect();
+            let ascii: String = line
+                .iter()
+                .map(|x| match x.is_ascii_graphic() || *x == b' ' {
+                    true => *x as char,
+                    false => '.',
+                })
+                .collect();
+            format!(
+                "{:08x}  {:<47}  |{}|",
+                offset + (i * LINE_SIZE) as u64,
+                hex.join(" "),
+                ascii
+            )
+        })
+        .collect()
+}
+
+/// ### parse_offset
+///
+/// Parse an offset typed by the user, either decimal or hexadecimal if prefixed by `0x`
+pub fn parse_offset(s: &str) -> Option<u64> {
+    let s: &str = s.trim();
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse::<u64>().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_hexdump_format() {
+        let data: Vec<u8> = (0x2e..0x4a).collect();
+        assert_eq!(
+            hexdump(data.as_slice(), 0x20),
+            vec![
+                String::from(
+                    "00000020  2e 2f 30 31 32 33 34 35 36 37 38 39 3a 3b 3c 3d  |./0123456789:;<=|"
+                ),
+                String::from(
+                    "00000030  3e 3f 40 41 42 43 44 45 46 47 48 49              |>?@ABCDEFGHI|"
+                ),
+            ]
+        );
+        assert_eq!(
+            hexdump(&[0x00, 0x20, 0x7f, 0xff], 0),
+            vec![String::from(
+                "00000000  00 20 7f ff                                      |. ..|"
+            )]
+        );
+        assert!(hexdump(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_hexdump_parse_offset() {
+        assert_eq!(parse_offset("1024"), Some(1024));
+        assert_eq!(parse_offset(" 0x400 "), Some(1024));
+        assert_eq!(parse_offset("0XfF"), Some(255));
+        assert_eq!(parse_offset("0x"), None);
+        assert_eq!(parse_offset("abc"), None);
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_hexdump_view() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        let data: Vec<u8> = (0..(BLOCK_SIZE + 40)).map(|x| (x % 256) as u8).collect();
+        tmpfile.write_all(data.as_slice()).unwrap();
+        let mut view: HexView = HexView::new("test.bin", tmpfile.path(), data.len() as u64);
+        assert_eq!(view.read_block().unwrap().len(), (BLOCK_SIZE / 16) as usize);
+        assert_eq!(view.end(), BLOCK_SIZE - 1);
+        // Navigate
+        assert_eq!(view.prev_block(), false);
+        assert_eq!(view.next_block(), true);
+        assert_eq!(view.offset, BLOCK_SIZE);
+        assert_eq!(view.end(), BLOCK_SIZE + 39);
+        assert_eq!(view.read_block().unwrap().len(), 3);
+        assert_eq!(view.next_block(), false);
+        assert_eq!(view.prev_block(), true);
+        assert_eq!(view.offset, 0);
+        // Seek aligns to line
+        assert_eq!(view.seek(0x23), true);
+        assert_eq!(view.offset, 0x20);
+        assert!(view.read_block().unwrap()[0].starts_with("00000020  20 21"));
+        assert_eq!(view.seek(BLOCK_SIZE + 40), false);
+        assert_eq!(view.offset, 0x20);
+    }
+}
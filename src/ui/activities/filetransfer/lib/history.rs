@@ -0,0 +1,115 @@
+//! ## History
+//!
+//! `history` is the module which provides the input history for the file transfer activity
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::collections::{HashMap, VecDeque};
+
+/// ## InputHistory
+///
+/// InputHistory keeps the values submitted to each input popup, from the oldest to the most recent
+pub struct InputHistory {
+    entries: HashMap<&'static str, VecDeque<String>>,
+    capacity: usize,
+}
+
+impl InputHistory {
+    /// ### new
+    ///
+    /// Instantiates a new `InputHistory`, which keeps at most `capacity` entries for each input
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+        }
+    }
+
+    /// ### push
+    ///
+    /// Push a submitted value into the history of `input`.
+    /// If the value was already in the history, it is moved to the most recent position
+    pub fn push(&mut self, input: &'static str, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+        let capacity: usize = self.capacity;
+        let history: &mut VecDeque<String> = self.entries.entry(input).or_default();
+        history.retain(|x| x.as_str() != value);
+        if history.len() >= capacity {
+            history.pop_front();
+        }
+        history.push_back(value.to_string());
+    }
+
+    /// ### get
+    ///
+    /// Get history for `input`
+    pub fn get(&self, input: &str) -> Vec<String> {
+        match self.entries.get(input) {
+            Some(history) => history.iter().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_history() {
+        let mut history: InputHistory = InputHistory::new(3);
+        assert!(history.get("GOTO").is_empty());
+        history.push("GOTO", "/tmp");
+        history.push("GOTO", "");
+        history.push("GOTO", "/home");
+        history.push("EXEC", "ls");
+        assert_eq!(
+            history.get("GOTO"),
+            vec![String::from("/tmp"), String::from("/home")]
+        );
+        assert_eq!(history.get("EXEC"), vec![String::from("ls")]);
+        // Duplicates are moved to the end
+        history.push("GOTO", "/tmp");
+        assert_eq!(
+            history.get("GOTO"),
+            vec![String::from("/home"), String::from("/tmp")]
+        );
+        // Capacity
+        history.push("GOTO", "/var");
+        history.push("GOTO", "/etc");
+        assert_eq!(
+            history.get("GOTO"),
+            vec![
+                String::from("/tmp"),
+                String::from("/var"),
+                String::from("/etc")
+            ]
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! ## Macros
+//!
+//! `macros` is the module which provides keyboard macros recording for the file transfer activity
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use tuirealm::event::Event;
+
+/// ## MacroRecorder
+///
+/// MacroRecorder records input events, so that they can be replayed later
+#[derive(Default)]
+pub struct MacroRecorder {
+    recording: Option<Vec<Event>>, // Events being recorded
+    recorded: Vec<Event>,          // Last recorded macro
+}
+
+impl MacroRecorder {
+    /// ### recording
+    ///
+    /// Returns whether a macro is being recorded
+    pub fn recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// ### start
+    ///
+    /// Start recording a new macro
+    pub fn start(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// ### stop
+    ///
+    /// Stop recording and store the recorded macro.
+    /// The last recorded event is discarded, since it's the one which stopped the recording.
+    /// Returns the amount of events in the recorded macro
+    pub fn stop(&mut self) -> usize {
+        if let Some(mut events) = self.recording.take() {
+            events.pop();
+            self.recorded = events;
+        }
+        self.recorded.len()
+    }
+
+    /// ### record
+    ///
+    /// Record event, if recording
+    pub fn record(&mut self, ev: Event) {
+        if let Some(events) = self.recording.as_mut() {
+            events.push(ev);
+        }
+    }
+
+    /// ### discard_last
+    ///
+    /// Discard last recorded event, if recording
+    pub fn discard_last(&mut self) {
+        if let Some(events) = self.recording.as_mut() {
+            events.pop();
+        }
+    }
+
+    /// ### recorded
+    ///
+    /// Get last recorded macro
+    pub fn recorded(&self) -> &[Event] {
+        self.recorded.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::event::{KeyCode, KeyEvent};
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_macros() {
+        let mut recorder: MacroRecorder = MacroRecorder::default();
+        assert_eq!(recorder.recording(), false);
+        assert!(recorder.recorded().is_empty());
+        // Not recording
+        recorder.record(Event::Key(KeyEvent::from(KeyCode::Char('r'))));
+        assert_eq!(recorder.stop(), 0);
+        // Record
+        recorder.start();
+        assert_eq!(recorder.recording(), true);
+        recorder.record(Event::Key(KeyEvent::from(KeyCode::Char('r'))));
+        recorder.record(Event::Key(KeyEvent::from(KeyCode::Char('@'))));
+        recorder.discard_last();
+        recorder.record(Event::Key(KeyEvent::from(KeyCode::Enter)));
+        recorder.record(Event::Key(KeyEvent::from(KeyCode::Char('Q'))));
+        assert_eq!(recorder.stop(), 2);
+        assert_eq!(recorder.recording(), false);
+        assert_eq!(
+            recorder.recorded(),
+            &[
+                Event::Key(KeyEvent::from(KeyCode::Char('r'))),
+                Event::Key(KeyEvent::from(KeyCode::Enter))
+            ]
+        );
+        // A new recording replaces the previous macro
+        recorder.start();
+        recorder.record(Event::Key(KeyEvent::from(KeyCode::Char('Q'))));
+        assert_eq!(recorder.stop(), 0);
+        assert!(recorder.recorded().is_empty());
+    }
+}
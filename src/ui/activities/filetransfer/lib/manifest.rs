@@ -0,0 +1,311 @@
+//! ## Verify
+//!
+//! `verify` is the module which provides the drift report between local and remote trees
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::fs::FsEntry;
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use wildmatch::WildMatch;
+
+/// ## ManifestEntry
+///
+/// An entry which is going to be transferred
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub size: usize,
+    pub last_change_time: SystemTime,
+    pub is_dir: bool,
+    pub depth: usize,
+    pub selected: bool,
+    pub collapsed: bool,
+}
+
+/// ## TransferManifest
+///
+/// The entries a recursive transfer is going to process.
+/// Entries are stored as a flattened tree, where each directory is followed by its children
+#[derive(Debug, Default)]
+pub struct TransferManifest {
+    entries: Vec<ManifestEntry>,
+}
+
+impl TransferManifest {
+    /// ### push
+    ///
+    /// Push `entry` at `depth`; children must be pushed right after their parent.
+    /// Entries are selected by default, while directories are collapsed
+    pub fn push(&mut self, entry: &FsEntry, depth: usize) {
+        self.entries.push(ManifestEntry {
+            path: entry.get_abs_path(),
+            name: entry.get_name().to_string(),
+            size: match entry {
+                FsEntry::Directory(_) => 0,
+                FsEntry::File(file) => file.size,
+            },
+            last_change_time: entry.get_last_change_time(),
+            is_dir: entry.is_dir(),
+            depth,
+            selected: true,
+            collapsed: entry.is_dir(),
+        });
+    }
+
+    /// ### get
+    ///
+    /// Get entry at index
+    pub fn get(&self, idx: usize) -> Option<&ManifestEntry> {
+        self.entries.get(idx)
+    }
+
+    /// ### iter
+    ///
+    /// Iterate over all the entries, in tree order
+    pub fn iter(&self) -> impl Iterator<Item = &ManifestEntry> {
+        self.entries.iter()
+    }
+
+    /// ### visible
+    ///
+    /// Get the indexes of the entries which are not inside a collapsed directory
+    pub fn visible(&self) -> Vec<usize> {
+        let mut visible: Vec<usize> = Vec::with_capacity(self.entries.len());
+        let mut collapsed_depth: Option<usize> = None;
+        for (idx, entry) in self.entries.iter().enumerate() {
+            if let Some(depth) = collapsed_depth {
+                if entry.depth > depth {
+                    continue;
+                }
+                collapsed_depth = None;
+            }
+            visible.push(idx);
+            if entry.is_dir && entry.collapsed {
+                collapsed_depth = Some(entry.depth);
+            }
+        }
+        visible
+    }
+
+    /// ### toggle_collapsed
+    ///
+    /// Collapse or expand directory at index
+    pub fn toggle_collapsed(&mut self, idx: usize) {
+        if let Some(entry) = self.entries.get_mut(idx) {
+            if entry.is_dir {
+                entry.collapsed = !entry.collapsed;
+            }
+        }
+    }
+
+    /// ### toggle_selected
+    ///
+    /// Select or deselect entry at index, with all its children.
+    /// When an entry is selected, its parents are selected too
+    pub fn toggle_selected(&mut self, idx: usize) {
+        let (depth, selected): (usize, bool) = match self.entries.get(idx) {
+            Some(entry) => (entry.depth, !entry.selected),
+            None => return,
+        };
+        // Entry and children
+        for entry in self.entries[idx..]
+            .iter_mut()
+            .enumerate()
+            .take_while(|(i, x)| *i == 0 || x.depth > depth)
+            .map(|(_, x)| x)
+        {
+            entry.selected = selected;
+        }
+        // Parents
+        if selected {
+            let mut depth: usize = depth;
+            for entry in self.entries[..idx].iter_mut().rev() {
+                if depth == 0 {
+                    break;
+                }
+                if entry.depth < depth {
+                    entry.selected = true;
+                    depth = entry.depth;
+                }
+            }
+        }
+    }
+
+    /// ### select_matching
+    ///
+    /// Select only the files whose name matches one of `globs`; if `globs` is empty, all files are selected.
+    /// Directories are selected anyway, so that the directory structure is recreated
+    pub fn select_matching<S: AsRef<str>>(&mut self, globs: &[S]) {
+        let globs: Vec<WildMatch> = globs.iter().map(|x| WildMatch::new(x.as_ref())).collect();
+        for entry in self.entries.iter_mut() {
+            entry.selected = entry.is_dir
+                || globs.is_empty()
+                || globs.iter().any(|x| x.matches(entry.name.as_str()));
+        }
+    }
+
+    /// ### total_size
+    ///
+    /// Get the total size of the selected files
+    pub fn total_size(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|x| x.selected)
+            .map(|x| x.size)
+            .sum()
+    }
+
+    /// ### selected_files
+    ///
+    /// Get the amount of selected files
+    pub fn selected_files(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|x| x.selected && !x.is_dir)
+            .count()
+    }
+
+    /// ### excluded
+    ///
+    /// Get the paths of the entries which have been deselected
+    pub fn excluded(&self) -> HashSet<PathBuf> {
+        self.entries
+            .iter()
+            .filter(|x| !x.selected)
+            .map(|x| x.path.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::fs::{FsDirectory, FsFile};
+
+    use pretty_assertions::assert_eq;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_manifest() {
+        let mut manifest: TransferManifest = TransferManifest::default();
+        manifest.push(&make_dir("/home/omar/src"), 0);
+        manifest.push(&make_file("/home/omar/src/main.rs", 512), 1);
+        manifest.push(&make_dir("/home/omar/src/lib"), 1);
+        manifest.push(&make_file("/home/omar/src/lib/mod.rs", 256), 2);
+        manifest.push(&make_file("/home/omar/README.md", 1024), 0);
+        assert_eq!(manifest.total_size(), 1792);
+        assert_eq!(manifest.selected_files(), 3);
+        assert!(manifest.excluded().is_empty());
+        // Directories are collapsed
+        assert_eq!(manifest.visible(), vec![0, 4]);
+        manifest.toggle_collapsed(0);
+        assert_eq!(manifest.visible(), vec![0, 1, 2, 4]);
+        manifest.toggle_collapsed(2);
+        assert_eq!(manifest.visible(), vec![0, 1, 2, 3, 4]);
+        // Files can't be collapsed
+        manifest.toggle_collapsed(1);
+        assert_eq!(manifest.get(1).unwrap().collapsed, false);
+        // Deselect directory with children
+        manifest.toggle_selected(2);
+        assert_eq!(manifest.total_size(), 1536);
+        assert_eq!(manifest.selected_files(), 2);
+        assert_eq!(manifest.excluded().len(), 2);
+        assert!(manifest
+            .excluded()
+            .contains(&PathBuf::from("/home/omar/src/lib/mod.rs")));
+        // Deselect root, then select nested file; parents are selected back
+        manifest.toggle_selected(0);
+        assert_eq!(manifest.selected_files(), 1);
+        manifest.toggle_selected(3);
+        assert_eq!(manifest.get(0).unwrap().selected, true);
+        assert_eq!(manifest.get(1).unwrap().selected, false);
+        assert_eq!(manifest.get(2).unwrap().selected, true);
+        assert_eq!(manifest.get(3).unwrap().selected, true);
+        assert_eq!(manifest.total_size(), 1280);
+        assert!(manifest.get(10).is_none());
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_manifest_select_matching() {
+        let mut manifest: TransferManifest = TransferManifest::default();
+        manifest.push(&make_dir("/home/omar/photos"), 0);
+        manifest.push(&make_file("/home/omar/photos/cat.jpg", 512), 1);
+        manifest.push(&make_file("/home/omar/photos/notes.txt", 64), 1);
+        manifest.push(&make_dir("/home/omar/photos/raw"), 1);
+        manifest.push(&make_file("/home/omar/photos/raw/dog.png", 256), 2);
+        manifest.select_matching(&["*.jpg", "*.png"]);
+        assert_eq!(manifest.selected_files(), 2);
+        assert_eq!(manifest.total_size(), 768);
+        // Directories are kept
+        assert_eq!(manifest.get(0).unwrap().selected, true);
+        assert_eq!(manifest.get(3).unwrap().selected, true);
+        assert_eq!(
+            manifest.excluded().into_iter().collect::<Vec<PathBuf>>(),
+            vec![PathBuf::from("/home/omar/photos/notes.txt")]
+        );
+        // No globs selects everything back
+        manifest.select_matching::<&str>(&[]);
+        assert_eq!(manifest.selected_files(), 3);
+        assert!(manifest.excluded().is_empty());
+    }
+
+    fn make_file(path: &str, size: usize) -> FsEntry {
+        let t: SystemTime = SystemTime::now();
+        let path: PathBuf = PathBuf::from(path);
+        FsEntry::File(FsFile {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            abs_path: path,
+            last_change_time: t,
+            last_access_time: t,
+            creation_time: t,
+            size,
+            ftype: None,
+            symlink: None,
+            user: None,
+            group: None,
+            unix_pex: None,
+        })
+    }
+
+    fn make_dir(path: &str) -> FsEntry {
+        let t: SystemTime = SystemTime::now();
+        let path: PathBuf = PathBuf::from(path);
+        FsEntry::Directory(FsDirectory {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            abs_path: path,
+            last_change_time: t,
+            last_access_time: t,
+            creation_time: t,
+            symlink: None,
+            user: None,
+            group: None,
+            unix_pex: None,
+        })
+    }
+}
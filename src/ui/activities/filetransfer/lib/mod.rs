@@ -26,4 +26,18 @@
  * SOFTWARE.
  */
 pub(crate) mod browser;
+pub(crate) mod difftool;
+pub(crate) mod dry_run;
+pub(crate) mod hexdump;
+pub(crate) mod history;
+pub(crate) mod macros;
+pub(crate) mod manifest;
+pub(crate) mod parallel;
+pub(crate) mod prefetch;
+pub(crate) mod remote_env;
+pub(crate) mod report;
+pub(crate) mod trace;
 pub(crate) mod transfer;
+pub(crate) mod verify;
+pub(crate) mod watch;
+pub(crate) mod webhook;
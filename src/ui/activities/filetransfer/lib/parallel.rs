@@ -0,0 +1,517 @@
+//! ## Parallel
+//!
+//! `parallel` is the module which transfers files over many connections at once
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::filetransfer::{
+    Builder, FileTransfer, FileTransferError, FileTransferParams, Proxy, SlowLink, TlsOptions,
+};
+use crate::fs::FsFile;
+use crate::system::sshkey_storage::SshKeyStorage;
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often paused workers check whether they have been resumed
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// ## Job
+///
+/// A file to transfer, or a segment of a file to download.
+/// Segments are written at their offset into the local file, which must already exist
+#[derive(Debug, Clone)]
+pub enum Job {
+    Upload {
+        local: FsFile,
+        remote: PathBuf,
+    },
+    Download {
+        remote: FsFile,
+        local: PathBuf,
+    },
+    Segment {
+        remote: FsFile,
+        local: PathBuf,
+        offset: u64,
+        len: u64,
+    },
+}
+
+impl Job {
+    /// ### segments
+    ///
+    /// Split the download of `remote` into `n` segments of about the same size
+    pub fn segments(remote: &FsFile, local: &Path, n: usize) -> Vec<Job> {
+        let size: u64 = remote.size as u64;
+        let n: u64 = (n.max(1) as u64).min(size.max(1));
+        let segment_size: u64 = (size + n - 1) / n;
+        (0..n)
+            .map(|i| i * segment_size)
+            .filter(|offset| *offset < size || size == 0)
+            .map(|offset| Job::Segment {
+                remote: remote.clone(),
+                local: local.to_path_buf(),
+                offset,
+                len: segment_size.min(size - offset),
+            })
+            .collect()
+    }
+
+    /// ### file
+    ///
+    /// Get the source file of the job
+    pub fn file(&self) -> &FsFile {
+        match self {
+            Job::Upload { local, .. } => local,
+            Job::Download { remote, .. } | Job::Segment { remote, .. } => remote,
+        }
+    }
+
+    /// ### size
+    ///
+    /// Get the amount of bytes to transfer
+    pub fn size(&self) -> usize {
+        match self {
+            Job::Segment { len, .. } => *len as usize,
+            _ => self.file().size,
+        }
+    }
+
+    /// ### destination
+    ///
+    /// Get the path the file is written to
+    #[cfg(test)]
+    pub fn destination(&self) -> &Path {
+        match self {
+            Job::Upload { remote, .. } => remote.as_path(),
+            Job::Download { local, .. } | Job::Segment { local, .. } => local.as_path(),
+        }
+    }
+}
+
+/// ## WorkerEvent
+///
+/// Events reported by the workers; `usize` is the worker id
+pub enum WorkerEvent {
+    Connected(usize),
+    ConnectionFailed(usize, String),
+    Started(usize, Job),
+    Progress(usize, usize), // Bytes written
+    Done(usize, Job, Result<Duration, String>),
+}
+
+/// ## Connector
+///
+/// Everything a worker needs to open its own connection to the remote
+pub struct Connector {
+    pub params: FileTransferParams,
+    pub ssh_keys: SshKeyStorage,
+    pub proxy: Option<Proxy>,
+    pub tls: Option<TlsOptions>,
+    pub agent_forwarding: bool,
+    pub compression: bool,
+    pub key_passphrase: Option<String>,
+    pub banner_acknowledged: bool,
+    pub stall_timeout: Option<Duration>,
+}
+
+impl Connector {
+    /// ### connect
+    ///
+    /// Build a client and connect it to the remote
+    pub fn connect(self) -> Result<Box<dyn FileTransfer>, FileTransferError> {
+        let mut client: Box<dyn FileTransfer> =
+            Builder::build(self.params.protocol, self.ssh_keys)?;
+        client.set_stall_timeout(self.stall_timeout);
+        client.set_key_passphrase(self.key_passphrase);
+        client.set_banner_acknowledged(self.banner_acknowledged);
+        client.set_proxy(self.proxy)?;
+        client.set_tls(self.tls)?;
+        client.set_agent_forwarding(self.agent_forwarding)?;
+        client.set_compression(self.compression);
+        client.connect(
+            self.params.address,
+            self.params.port,
+            self.params.username,
+            self.params.password,
+        )?;
+        Ok(client)
+    }
+}
+
+/// ## WorkerPool
+///
+/// Workers, each one with its own connection, taking jobs from a shared queue
+pub struct WorkerPool {
+    queue: Arc<Mutex<VecDeque<Job>>>,
+    cancel: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    events: Receiver<WorkerEvent>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    /// ### start
+    ///
+    /// Start a worker for each function in `connect`; each worker calls its function to get a connected client,
+    /// then takes jobs from the queue until it's empty
+    pub fn start<F>(jobs: Vec<Job>, connect: Vec<F>, slow_link: SlowLink) -> Self
+    where
+        F: FnOnce() -> Result<Box<dyn FileTransfer>, String> + Send + 'static,
+    {
+        let queue: Arc<Mutex<VecDeque<Job>>> = Arc::new(Mutex::new(jobs.into_iter().collect()));
+        let cancel: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let paused: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let (sender, events) = mpsc::channel();
+        let workers: Vec<JoinHandle<()>> = connect
+            .into_iter()
+            .enumerate()
+            .map(|(id, connect)| {
+                let worker = Worker {
+                    id,
+                    queue: queue.clone(),
+                    cancel: cancel.clone(),
+                    paused: paused.clone(),
+                    events: sender.clone(),
+                    slow_link,
+                };
+                thread::spawn(move || worker.run(connect))
+            })
+            .collect();
+        Self {
+            queue,
+            cancel,
+            paused,
+            events,
+            workers,
+        }
+    }
+
+    /// ### recv
+    ///
+    /// Wait up to `timeout` for the next event.
+    /// Returns `Err(RecvTimeoutError::Disconnected)` once all the workers have terminated
+    pub fn recv(&self, timeout: Duration) -> Result<WorkerEvent, RecvTimeoutError> {
+        self.events.recv_timeout(timeout)
+    }
+
+    /// ### cancel
+    ///
+    /// Tell workers to stop; the file being transferred by each worker is left incomplete
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// ### set_paused
+    ///
+    /// Suspend or resume workers; paused workers keep their connections and streams open
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    /// ### join
+    ///
+    /// Wait for all the workers to terminate and return the jobs nobody took
+    pub fn join(self) -> Vec<Job> {
+        for worker in self.workers.into_iter() {
+            if worker.join().is_err() {
+                error!("A transfer worker panicked");
+            }
+        }
+        let remaining: VecDeque<Job> = std::mem::take(&mut *self.queue.lock().unwrap());
+        remaining.into_iter().collect()
+    }
+}
+
+/// ## Worker
+///
+/// A worker of the pool
+struct Worker {
+    id: usize,
+    queue: Arc<Mutex<VecDeque<Job>>>,
+    cancel: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    events: Sender<WorkerEvent>,
+    slow_link: SlowLink,
+}
+
+impl Worker {
+    /// ### run
+    ///
+    /// Connect and transfer jobs until the queue is empty or the pool is cancelled
+    fn run<F>(self, connect: F)
+    where
+        F: FnOnce() -> Result<Box<dyn FileTransfer>, String>,
+    {
+        let mut client: Box<dyn FileTransfer> = match connect() {
+            Ok(client) => client,
+            Err(err) => {
+                let _ = self
+                    .events
+                    .send(WorkerEvent::ConnectionFailed(self.id, err));
+                return;
+            }
+        };
+        let _ = self.events.send(WorkerEvent::Connected(self.id));
+        while !self.cancel.load(Ordering::Relaxed) {
+            self.wait_resumed();
+            let job: Job = match self.queue.lock().unwrap().pop_front() {
+                Some(job) => job,
+                None => break,
+            };
+            let _ = self.events.send(WorkerEvent::Started(self.id, job.clone()));
+            let started: Instant = Instant::now();
+            let result: Result<Duration, String> = match &job {
+                Job::Upload { local, remote } => self.upload(client.as_mut(), local, remote),
+                Job::Download { remote, local } => self.download(client.as_mut(), remote, local),
+                Job::Segment {
+                    remote,
+                    local,
+                    offset,
+                    len,
+                } => self.download_segment(client.as_mut(), remote, local, *offset, *len),
+            }
+            .map(|_| started.elapsed());
+            let _ = self.events.send(WorkerEvent::Done(self.id, job, result));
+        }
+        if let Err(err) = client.disconnect() {
+            warn!("Worker {} could not disconnect: {}", self.id, err);
+        }
+    }
+
+    /// ### upload
+    ///
+    /// Send local file to remote path
+    fn upload(
+        &self,
+        client: &mut dyn FileTransfer,
+        local: &FsFile,
+        remote: &Path,
+    ) -> Result<(), String> {
+        let mut reader: File = File::open(local.abs_path.as_path())
+            .map_err(|e| format!("Could not open local file: {}", e))?;
+        let writer: Box<dyn Write> = client.send_file(local, remote).map_err(|e| e.to_string())?;
+        let mut writer = self.slow_link.stream(writer);
+        let result = self.copy(&mut reader, &mut writer).map(|_| ());
+        if let Err(err) = client.on_sent(writer.into_inner()) {
+            warn!("Could not finalize remote stream: {}", err);
+        }
+        result
+    }
+
+    /// ### download
+    ///
+    /// Receive remote file and write it to local path
+    fn download(
+        &self,
+        client: &mut dyn FileTransfer,
+        remote: &FsFile,
+        local: &Path,
+    ) -> Result<(), String> {
+        let mut writer: File = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(local)
+            .map_err(|e| format!("Could not open local file: {}", e))?;
+        let reader: Box<dyn Read> = client.recv_file(remote).map_err(|e| e.to_string())?;
+        let mut reader = self.slow_link.stream(reader);
+        let result = self.copy(&mut reader, &mut writer).map(|_| ());
+        if let Err(err) = client.on_recv(reader.into_inner()) {
+            warn!("Could not finalize remote stream: {}", err);
+        }
+        result
+    }
+
+    /// ### download_segment
+    ///
+    /// Receive `len` bytes of remote file from `offset` and write them at the same offset of the local file
+    fn download_segment(
+        &self,
+        client: &mut dyn FileTransfer,
+        remote: &FsFile,
+        local: &Path,
+        offset: u64,
+        len: u64,
+    ) -> Result<(), String> {
+        let mut writer: File = OpenOptions::new()
+            .write(true)
+            .open(local)
+            .and_then(|mut f| f.seek(SeekFrom::Start(offset)).map(|_| f))
+            .map_err(|e| format!("Could not open local file: {}", e))?;
+        let reader: Box<dyn Read> = client
+            .recv_file_range(remote, offset, len)
+            .map_err(|e| e.to_string())?;
+        let mut reader = self.slow_link.stream(reader);
+        let result = self.copy(&mut reader, &mut writer);
+        if let Err(err) = client.on_recv(reader.into_inner()) {
+            warn!("Could not finalize remote stream: {}", err);
+        }
+        match result? {
+            written if written as u64 == len => Ok(()),
+            written => Err(format!(
+                "Segment ended after {} bytes out of {}",
+                written, len
+            )),
+        }
+    }
+
+    /// ### copy
+    ///
+    /// Copy reader into writer, reporting progress, until EOF or until the pool is cancelled.
+    /// Returns the amount of bytes copied
+    fn copy(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<usize, String> {
+        let mut buffer: [u8; 65536] = [0; 65536];
+        let mut written: usize = 0;
+        loop {
+            self.wait_resumed();
+            if self.cancel.load(Ordering::Relaxed) {
+                return Err(String::from("Transfer aborted"));
+            }
+            let bytes: usize = match reader.read(&mut buffer) {
+                Ok(0) => return Ok(written),
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.to_string()),
+            };
+            writer
+                .write_all(&buffer[..bytes])
+                .map_err(|e| e.to_string())?;
+            written += bytes;
+            let _ = self.events.send(WorkerEvent::Progress(self.id, bytes));
+        }
+    }
+
+    /// ### wait_resumed
+    ///
+    /// Block while the pool is paused, unless it gets cancelled
+    fn wait_resumed(&self) {
+        while self.paused.load(Ordering::Relaxed) && !self.cancel.load(Ordering::Relaxed) {
+            thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_parallel_job() {
+        let job: Job = Job::Download {
+            remote: make_file("/home/omar/README.md"),
+            local: PathBuf::from("/tmp/README.md"),
+        };
+        assert_eq!(job.file().name.as_str(), "README.md");
+        assert_eq!(job.destination(), Path::new("/tmp/README.md"));
+        let job: Job = Job::Upload {
+            local: make_file("/tmp/README.md"),
+            remote: PathBuf::from("/home/omar/README.md"),
+        };
+        assert_eq!(job.destination(), Path::new("/home/omar/README.md"));
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_parallel_segments() {
+        let mut file: FsFile = make_file("/home/omar/disk.img");
+        file.size = 10;
+        let segments: Vec<(u64, u64)> = Job::segments(&file, Path::new("/tmp/disk.img"), 3)
+            .into_iter()
+            .map(|x| match x {
+                Job::Segment { offset, len, .. } => (offset, len),
+                _ => panic!("Not a segment"),
+            })
+            .collect();
+        assert_eq!(segments, vec![(0, 4), (4, 4), (8, 2)]);
+        // Never more segments than bytes
+        file.size = 2;
+        let segments: Vec<Job> = Job::segments(&file, Path::new("/tmp/disk.img"), 4);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].size(), 1);
+        assert_eq!(segments[1].destination(), Path::new("/tmp/disk.img"));
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_parallel_connection_failed() {
+        let jobs: Vec<Job> = vec![
+            Job::Download {
+                remote: make_file("/home/omar/a.txt"),
+                local: PathBuf::from("/tmp/a.txt"),
+            },
+            Job::Download {
+                remote: make_file("/home/omar/b.txt"),
+                local: PathBuf::from("/tmp/b.txt"),
+            },
+        ];
+        let pool: WorkerPool = WorkerPool::start(jobs, vec![refuse, refuse], SlowLink::default());
+        let mut failed: usize = 0;
+        loop {
+            match pool.recv(Duration::from_secs(5)) {
+                Ok(WorkerEvent::ConnectionFailed(_, err)) => {
+                    assert_eq!(err.as_str(), "refused");
+                    failed += 1;
+                }
+                Ok(_) => panic!("Unexpected event"),
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => panic!("Workers didn't terminate"),
+            }
+        }
+        assert_eq!(failed, 2);
+        // Jobs are left in the queue
+        assert_eq!(pool.join().len(), 2);
+    }
+
+    fn refuse() -> Result<Box<dyn FileTransfer>, String> {
+        Err(String::from("refused"))
+    }
+
+    fn make_file(path: &str) -> FsFile {
+        let path: PathBuf = PathBuf::from(path);
+        FsFile {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            abs_path: path,
+            last_change_time: SystemTime::UNIX_EPOCH,
+            last_access_time: SystemTime::UNIX_EPOCH,
+            creation_time: SystemTime::UNIX_EPOCH,
+            size: 0,
+            ftype: None,
+            symlink: None,
+            user: None,
+            group: None,
+            unix_pex: None,
+        }
+    }
+}
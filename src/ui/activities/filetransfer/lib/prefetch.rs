@@ -0,0 +1,179 @@
+//! ## Prefetch
+//!
+//! `prefetch` is the module which keeps the listings of the remote directories read ahead of time
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::fs::FsEntry;
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// ## Prefetch
+///
+/// Bounded cache of prefetched directory listings.
+/// It also tracks the highlighted directory, which is prefetched once it has been highlighted
+/// for long enough
+pub struct Prefetch {
+    capacity: usize,
+    ttl: Duration,
+    delay: Duration,
+    listings: VecDeque<(PathBuf, Instant, Vec<FsEntry>)>, // Oldest first
+    highlighted: Option<(PathBuf, Instant, bool)>, // Directory, highlighted since, whether already due
+}
+
+impl Prefetch {
+    /// ### new
+    ///
+    /// Instantiates a new `Prefetch`, which keeps at most `capacity` listings for `ttl`.
+    /// A directory is prefetched after being highlighted for `delay`
+    pub fn new(capacity: usize, ttl: Duration, delay: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            delay,
+            listings: VecDeque::with_capacity(capacity),
+            highlighted: None,
+        }
+    }
+
+    /// ### highlight
+    ///
+    /// Report the currently highlighted directory (`None` if the highlighted entry is not a directory)
+    pub fn highlight(&mut self, dir: Option<PathBuf>) {
+        match (dir, self.highlighted.as_ref()) {
+            (Some(dir), Some((prev, _, _))) if dir.as_path() == prev.as_path() => {}
+            (Some(dir), _) => self.highlighted = Some((dir, Instant::now(), false)),
+            (None, _) => self.highlighted = None,
+        }
+    }
+
+    /// ### due
+    ///
+    /// Returns the directory to prefetch, if the highlighted directory has been highlighted for long enough
+    /// and it hasn't been prefetched yet. Each highlighted directory is returned once
+    pub fn due(&mut self) -> Option<PathBuf> {
+        let dir: PathBuf = match self.highlighted.as_mut() {
+            Some((dir, since, due)) if !*due && since.elapsed() >= self.delay => {
+                *due = true;
+                dir.clone()
+            }
+            _ => return None,
+        };
+        match self.contains(dir.as_path()) {
+            true => None,
+            false => Some(dir),
+        }
+    }
+
+    /// ### insert
+    ///
+    /// Save the listing of `dir`; the oldest listing is evicted if the cache is full
+    pub fn insert(&mut self, dir: PathBuf, entries: Vec<FsEntry>) {
+        self.listings
+            .retain(|(x, _, _)| x.as_path() != dir.as_path());
+        self.listings.push_back((dir, Instant::now(), entries));
+        while self.listings.len() > self.capacity {
+            self.listings.pop_front();
+        }
+    }
+
+    /// ### take
+    ///
+    /// Take the listing of `dir` out of the cache, if any and not expired
+    pub fn take(&mut self, dir: &Path) -> Option<Vec<FsEntry>> {
+        let index: usize = self
+            .listings
+            .iter()
+            .position(|(x, _, _)| x.as_path() == dir)?;
+        self.listings
+            .remove(index)
+            .filter(|(_, time, _)| time.elapsed() < self.ttl)
+            .map(|(_, _, entries)| entries)
+    }
+
+    /// ### clear
+    ///
+    /// Drop all the listings; to be called whenever the remote may have changed
+    pub fn clear(&mut self) {
+        self.listings.clear();
+    }
+
+    /// ### contains
+    ///
+    /// Returns whether a valid listing of `dir` is cached
+    fn contains(&self, dir: &Path) -> bool {
+        self.listings
+            .iter()
+            .any(|(x, time, _)| x.as_path() == dir && time.elapsed() < self.ttl)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_prefetch() {
+        let mut prefetch: Prefetch =
+            Prefetch::new(2, Duration::from_secs(60), Duration::from_secs(0));
+        assert!(prefetch.due().is_none());
+        // Highlight directory
+        prefetch.highlight(Some(PathBuf::from("/home")));
+        assert_eq!(prefetch.due(), Some(PathBuf::from("/home")));
+        assert!(prefetch.due().is_none());
+        prefetch.insert(PathBuf::from("/home"), vec![]);
+        // Already prefetched
+        prefetch.highlight(Some(PathBuf::from("/home")));
+        assert!(prefetch.due().is_none());
+        prefetch.highlight(None);
+        assert!(prefetch.due().is_none());
+        // Capacity is bounded
+        prefetch.insert(PathBuf::from("/var"), vec![]);
+        prefetch.insert(PathBuf::from("/tmp"), vec![]);
+        assert!(prefetch.take(Path::new("/home")).is_none());
+        assert!(prefetch.take(Path::new("/var")).is_some());
+        // Taken listings are gone
+        assert!(prefetch.take(Path::new("/var")).is_none());
+        prefetch.clear();
+        assert!(prefetch.take(Path::new("/tmp")).is_none());
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_prefetch_expired() {
+        let mut prefetch: Prefetch =
+            Prefetch::new(4, Duration::from_secs(0), Duration::from_secs(0));
+        prefetch.insert(PathBuf::from("/home"), vec![]);
+        assert!(prefetch.take(Path::new("/home")).is_none());
+        // Delay
+        let mut prefetch: Prefetch =
+            Prefetch::new(4, Duration::from_secs(60), Duration::from_secs(60));
+        prefetch.highlight(Some(PathBuf::from("/home")));
+        assert!(prefetch.due().is_none());
+    }
+}
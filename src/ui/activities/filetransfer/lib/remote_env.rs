@@ -0,0 +1,150 @@
+//! ## RemoteEnv
+//!
+//! `remote_env` detects the operating system, hostname, kernel and disk usage of the remote host,
+//! so that similar servers can be told apart at a glance
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Locals
+use crate::utils::fmt::fmt_size;
+
+/// Shell command printing the OS name, hostname, kernel release and the usage of the working directory file system,
+/// one per line
+pub const PROBE_COMMAND: &str = "(. /etc/os-release 2>/dev/null && echo \"$PRETTY_NAME\") || uname -s; hostname; uname -r; df -Pk . | tail -n 1";
+
+/// ## RemoteEnv
+///
+/// Environment of the remote host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteEnv {
+    pub os: String,
+    pub hostname: String,
+    pub kernel: String,
+    pub disk: Option<DiskUsage>, // Usage of the working directory file system
+}
+
+/// ## DiskUsage
+///
+/// Usage of a file system, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskUsage {
+    pub total: u64,
+    pub used: u64,
+}
+
+impl RemoteEnv {
+    /// ### parse
+    ///
+    /// Parse the output of `PROBE_COMMAND`; returns `None` if the hostname is missing
+    pub fn parse(output: &str) -> Option<Self> {
+        let mut lines = output.lines().map(|x| x.trim());
+        let os: String = lines.next().unwrap_or_default().to_string();
+        let hostname: String = lines.next().filter(|x| !x.is_empty())?.to_string();
+        let kernel: String = lines.next().unwrap_or_default().to_string();
+        let disk: Option<DiskUsage> = lines.next().and_then(DiskUsage::parse);
+        Some(Self {
+            os,
+            hostname,
+            kernel,
+            disk,
+        })
+    }
+
+    /// ### system
+    ///
+    /// Describe the operating system and the kernel
+    pub fn system(&self) -> String {
+        match (self.os.is_empty(), self.kernel.is_empty()) {
+            (false, false) => format!("{} {}", self.os, self.kernel),
+            (true, false) => self.kernel.clone(),
+            (_, true) => self.os.clone(),
+        }
+    }
+}
+
+impl DiskUsage {
+    /// ### parse
+    ///
+    /// Parse a row of `df -Pk`: file system, 1024-blocks, used, available, capacity, mount point
+    fn parse(row: &str) -> Option<Self> {
+        let columns: Vec<&str> = row.split_whitespace().collect();
+        let total: u64 = columns.get(1)?.parse().ok()?;
+        let used: u64 = columns.get(2)?.parse().ok()?;
+        Some(Self {
+            total: total * 1024,
+            used: used * 1024,
+        })
+    }
+
+    /// ### percent
+    ///
+    /// Get the percentage of the file system in use
+    pub fn percent(&self) -> u64 {
+        match self.total {
+            0 => 0,
+            total => self.used * 100 / total,
+        }
+    }
+}
+
+impl std::fmt::Display for DiskUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}% of {}", self.percent(), fmt_size(self.total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_remote_env_parse() {
+        let env: RemoteEnv = RemoteEnv::parse(
+            "Debian GNU/Linux 11 (bullseye)\nweb-01\n5.10.0-8-amd64\n/dev/sda1 103081248 51540624 46281364 53% /\n",
+        )
+        .unwrap();
+        assert_eq!(env.os.as_str(), "Debian GNU/Linux 11 (bullseye)");
+        assert_eq!(env.hostname.as_str(), "web-01");
+        assert_eq!(env.kernel.as_str(), "5.10.0-8-amd64");
+        assert_eq!(
+            env.system().as_str(),
+            "Debian GNU/Linux 11 (bullseye) 5.10.0-8-amd64"
+        );
+        let disk: DiskUsage = env.disk.unwrap();
+        assert_eq!(disk.total, 103081248 * 1024);
+        assert_eq!(disk.used, 51540624 * 1024);
+        assert_eq!(disk.percent(), 50);
+        // Without df
+        let env: RemoteEnv = RemoteEnv::parse("Darwin\nmac-mini\n20.6.0\n").unwrap();
+        assert_eq!(env.system().as_str(), "Darwin 20.6.0");
+        assert!(env.disk.is_none());
+        // Bad output
+        assert!(RemoteEnv::parse("").is_none());
+        assert!(RemoteEnv::parse("Linux\n\n").is_none());
+        assert_eq!(DiskUsage { total: 0, used: 0 }.percent(), 0);
+    }
+}
@@ -0,0 +1,222 @@
+//! ## Report
+//!
+//! `report` is the module which provides the technical details attached to the errors shown to the user
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use chrono::{DateTime, Local};
+use std::error::Error;
+
+/// ## ErrorReport
+///
+/// ErrorReport describes an error shown to the user: its chain of causes and the context of the operation
+/// which failed. It's meant to be pasted into bug reports
+#[derive(Debug, Clone)]
+pub struct ErrorReport {
+    message: String,
+    chain: Vec<String>,
+    context: Vec<(&'static str, String)>,
+    time: DateTime<Local>,
+}
+
+impl ErrorReport {
+    /// ### new
+    ///
+    /// Instantiates a new ErrorReport from the message displayed to the user, for an alert without an error
+    pub fn new(message: &str) -> ErrorReport {
+        ErrorReport {
+            message: message.to_string(),
+            chain: vec![message.to_string()],
+            context: Vec::new(),
+            time: Local::now(),
+        }
+    }
+
+    /// ### from_error
+    ///
+    /// Instantiates a new ErrorReport for `error`, which occurred while doing what `context` describes.
+    /// The chain is made of the context, the error and its sources
+    pub fn from_error(context: &str, error: &(dyn Error + 'static)) -> ErrorReport {
+        let mut chain: Vec<String> = vec![context.to_string()];
+        chain.extend(
+            std::iter::successors(Some(error), |err| (*err).source()).map(|err| err.to_string()),
+        );
+        ErrorReport {
+            message: format!("{}: {}", context, error),
+            chain,
+            context: Vec::new(),
+            time: Local::now(),
+        }
+    }
+
+    /// ### mask
+    ///
+    /// Apply `mask` to the message and to the chain of errors, in order to hide secrets
+    pub fn mask<F: Fn(&str) -> String>(mut self, mask: F) -> ErrorReport {
+        self.message = mask(self.message.as_str());
+        self.chain = self.chain.iter().map(|err| mask(err.as_str())).collect();
+        self
+    }
+
+    /// ### context
+    ///
+    /// Add `value` for `key` to the operation context. Empty values are discarded
+    pub fn context<S: AsRef<str>>(mut self, key: &'static str, value: S) -> ErrorReport {
+        if !value.as_ref().is_empty() {
+            self.context.push((key, value.as_ref().to_string()));
+        }
+        self
+    }
+
+    /// ### message
+    ///
+    /// Get the message displayed to the user
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+
+    /// ### chain
+    ///
+    /// Get the chain of errors; the first is the outermost
+    #[cfg(test)]
+    pub fn chain(&self) -> &[String] {
+        self.chain.as_slice()
+    }
+
+    /// ### lines
+    ///
+    /// Get the report as lines of text
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = vec![String::from("Error chain:")];
+        lines.extend(
+            self.chain
+                .iter()
+                .enumerate()
+                .map(|(i, err)| format!("  {}. {}", i, err)),
+        );
+        lines.push(String::from("Context:"));
+        lines.extend(
+            self.context
+                .iter()
+                .map(|(key, value)| format!("  {}: {}", key, value)),
+        );
+        lines.push(format!(
+            "  time: {}",
+            self.time.format("%Y-%m-%dT%H:%M:%S%z")
+        ));
+        lines.push(format!("  version: termscp {}", env!("CARGO_PKG_VERSION")));
+        lines
+    }
+}
+
+impl std::fmt::Display for ErrorReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}\n", self.message)?;
+        for line in self.lines() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::filetransfer::{FileTransferError, FileTransferErrorType};
+    use crate::host::{HostError, HostErrorType};
+
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_report_chain() {
+        let err: HostError = HostError::new(
+            HostErrorType::FileNotAccessible,
+            Some(std::io::Error::from(std::io::ErrorKind::PermissionDenied)),
+            Path::new("/tmp/a.txt"),
+        );
+        let report: ErrorReport = ErrorReport::from_error("Could not open file", &err);
+        assert_eq!(
+            report.message(),
+            "Could not open file: Could not access file: permission denied (/tmp/a.txt)"
+        );
+        assert_eq!(
+            report.chain(),
+            &[
+                String::from("Could not open file"),
+                String::from("Could not access file: permission denied (/tmp/a.txt)"),
+                String::from("permission denied"),
+            ]
+        );
+        // Messages aren't split
+        let report: ErrorReport = ErrorReport::new("Transfer failed: connection timeout (30s)");
+        assert_eq!(
+            report.chain(),
+            &[String::from("Transfer failed: connection timeout (30s)")]
+        );
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_report_mask() {
+        let err = std::io::Error::new(std::io::ErrorKind::Other, "bad password foobar");
+        let report: ErrorReport =
+            ErrorReport::from_error("Could not login", &err).mask(|x| x.replace("foobar", "***"));
+        assert_eq!(report.message(), "Could not login: bad password ***");
+        assert_eq!(
+            report.chain(),
+            &[
+                String::from("Could not login"),
+                String::from("bad password ***"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_report_lines() {
+        let err: FileTransferError = FileTransferError::new(FileTransferErrorType::PexError);
+        let report: ErrorReport = ErrorReport::from_error("Could not remove file", &err)
+            .context("host", "SFTP://pi@192.168.1.31:22")
+            .context("remote directory", "/home/pi")
+            .context("local directory", "");
+        let lines: Vec<String> = report.lines();
+        assert_eq!(
+            &lines[..6],
+            &[
+                String::from("Error chain:"),
+                String::from("  0. Could not remove file"),
+                String::from("  1. Not enough permissions"),
+                String::from("Context:"),
+                String::from("  host: SFTP://pi@192.168.1.31:22"),
+                String::from("  remote directory: /home/pi"),
+            ]
+        );
+        assert!(lines[6].starts_with("  time: "));
+        assert!(lines[7].starts_with("  version: termscp "));
+        assert_eq!(lines.len(), 8);
+        assert!(report
+            .to_string()
+            .starts_with("Could not remove file: Not enough permissions\n\nError chain:\n"));
+    }
+}
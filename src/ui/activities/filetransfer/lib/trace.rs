@@ -0,0 +1,178 @@
+//! ## Trace
+//!
+//! `trace` is the module which writes the debug trace of a single transfer
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use chrono::{DateTime, Local};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// ## TransferTrace
+///
+/// Detailed trace of a transfer: protocol events and the timings of each chunk.
+/// It's written to its own file, not to flood the termscp log; writing is best effort
+pub struct TransferTrace {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    started: Instant,
+    chunks: usize,
+    bytes: usize,
+    read_time: Duration,
+    write_time: Duration,
+}
+
+impl TransferTrace {
+    /// ### create
+    ///
+    /// Create a new trace file into `dir`, named after `time`
+    pub fn create(dir: &Path, time: DateTime<Local>) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path: PathBuf = dir.join(format!("trace-{}.log", time.format("%Y%m%d-%H%M%S")));
+        let writer: BufWriter<File> = BufWriter::new(File::create(path.as_path())?);
+        Ok(Self {
+            path,
+            writer,
+            started: Instant::now(),
+            chunks: 0,
+            bytes: 0,
+            read_time: Duration::ZERO,
+            write_time: Duration::ZERO,
+        })
+    }
+
+    /// ### path
+    ///
+    /// Get the path of the trace file
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// ### event
+    ///
+    /// Write a protocol-level event
+    pub fn event<S: AsRef<str>>(&mut self, event: S) {
+        self.write(event.as_ref());
+    }
+
+    /// ### chunk
+    ///
+    /// Write a chunk: `window` is the size of the buffer offered to the reader, `read` the amount
+    /// of bytes it returned; then the time spent reading and writing them
+    pub fn chunk(&mut self, window: usize, read: usize, read_time: Duration, write_time: Duration) {
+        self.chunks += 1;
+        self.bytes += read;
+        self.read_time += read_time;
+        self.write_time += write_time;
+        let line: String = format!(
+            "CHUNK #{} window={} read={} read_time={}us write_time={}us",
+            self.chunks,
+            window,
+            read,
+            read_time.as_micros(),
+            write_time.as_micros()
+        );
+        self.write(line.as_str());
+    }
+
+    /// ### finish
+    ///
+    /// Write the summary of the transfer and flush the trace file
+    pub fn finish(mut self, outcome: &str) -> io::Result<PathBuf> {
+        let summary: String = format!(
+            "END {}: {} chunk(s), {} bytes; read {}ms, write {}ms",
+            outcome,
+            self.chunks,
+            self.bytes,
+            self.read_time.as_millis(),
+            self.write_time.as_millis()
+        );
+        self.write(summary.as_str());
+        self.writer.flush()?;
+        Ok(self.path)
+    }
+
+    /// ### write
+    ///
+    /// Write a line, prefixed by the milliseconds elapsed since the trace started
+    fn write(&mut self, line: &str) {
+        let elapsed: f64 = self.started.elapsed().as_secs_f64() * 1000.0;
+        if let Err(err) = writeln!(self.writer, "{:>12.3} {}", elapsed, line) {
+            error!("Could not write transfer trace: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_trace() {
+        let tmp_dir: TempDir = TempDir::new().unwrap();
+        let dir: PathBuf = tmp_dir.path().join("traces");
+        let time: DateTime<Local> = DateTime::parse_from_rfc3339("2021-07-10T12:30:00+02:00")
+            .unwrap()
+            .with_timezone(&Local);
+        let mut trace: TransferTrace = TransferTrace::create(dir.as_path(), time).unwrap();
+        assert!(trace.path().starts_with(dir.as_path()));
+        assert!(trace
+            .path()
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("trace-2021"));
+        trace.event("OPEN /tmp/a.txt");
+        trace.chunk(
+            65536,
+            1024,
+            Duration::from_micros(150),
+            Duration::from_micros(300),
+        );
+        trace.chunk(
+            65536,
+            512,
+            Duration::from_micros(50),
+            Duration::from_micros(80),
+        );
+        let path: PathBuf = trace.finish("ok").unwrap();
+        let lines: Vec<String> = fs::read_to_string(path.as_path())
+            .unwrap()
+            .lines()
+            .map(|x| x.trim().to_string())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].ends_with("OPEN /tmp/a.txt"));
+        assert!(
+            lines[1].ends_with("CHUNK #1 window=65536 read=1024 read_time=150us write_time=300us")
+        );
+        assert!(lines[3].ends_with("END ok: 2 chunk(s), 1536 bytes; read 0ms, write 0ms"));
+    }
+}
@@ -25,17 +25,31 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-use bytesize::ByteSize;
+use crate::ui::components::transfer_progress::{FilesProgress, Progress};
+use crate::utils::fmt::fmt_size;
+
+use std::collections::{BTreeSet, HashSet};
 use std::fmt;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Amount of bytes to transfer before the ETA takes the per-file overhead into account
+const SMART_ETA_THRESHOLD: usize = 4 * 1024 * 1024;
 
 /// ### TransferStates
 ///
 /// TransferStates contains the states related to the transfer process
 pub struct TransferStates {
-    aborted: bool,               // Describes whether the transfer process has been aborted
-    pub full: ProgressStates,    // full transfer states
-    pub partial: ProgressStates, // Partial transfer states
+    aborted: bool,                // Describes whether the transfer process has been aborted
+    paused: Option<Instant>,      // When the transfer process has been paused, if it is
+    pub full: ProgressStates,     // full transfer states
+    pub partial: ProgressStates,  // Partial transfer states
+    pub files: FilesStates,       // Transferred files states
+    excluded: HashSet<PathBuf>,   // Paths deselected by the user from the transfer manifest
+    denied: BTreeSet<PathBuf>,    // Directories unreadable for lack of permissions
+    moved: Option<Vec<PathBuf>>,  // Source files transferred while moving entries
+    kept: HashSet<PathBuf>,       // Files which failed verification while moving entries
+    last_redraw: Option<Instant>, // Last time the progress has been drawn
 }
 
 /// ### ProgressStates
@@ -47,6 +61,18 @@ pub struct ProgressStates {
     written: usize,
 }
 
+/// ### FilesStates
+///
+/// FilesStates describes how many files have been transferred and the time spent writing their data
+#[derive(Default)]
+pub struct FilesStates {
+    total: usize,
+    done: usize,
+    failed: usize,
+    streaming: Duration,      // Time spent writing data of transferred files
+    current: Option<Instant>, // Start time of the file being transferred
+}
+
 impl Default for TransferStates {
     fn default() -> Self {
         Self::new()
@@ -60,8 +86,15 @@ impl TransferStates {
     pub fn new() -> TransferStates {
         TransferStates {
             aborted: false,
+            paused: None,
             full: ProgressStates::default(),
             partial: ProgressStates::default(),
+            files: FilesStates::default(),
+            excluded: HashSet::new(),
+            denied: BTreeSet::new(),
+            moved: None,
+            kept: HashSet::new(),
+            last_redraw: None,
         }
     }
 
@@ -70,6 +103,9 @@ impl TransferStates {
     /// Re-intiialize transfer states
     pub fn reset(&mut self) {
         self.aborted = false;
+        self.paused = None;
+        self.last_redraw = None;
+        self.denied.clear();
     }
 
     /// ### abort
@@ -85,6 +121,145 @@ impl TransferStates {
     pub fn aborted(&self) -> bool {
         self.aborted
     }
+
+    /// ### pause
+    ///
+    /// Suspend the transfer process
+    pub fn pause(&mut self) {
+        if self.paused.is_none() {
+            self.paused = Some(Instant::now());
+        }
+    }
+
+    /// ### resume
+    ///
+    /// Resume the transfer process; the time spent paused doesn't count in speed and ETA
+    pub fn resume(&mut self) {
+        if let Some(paused) = self.paused.take() {
+            let suspended: Duration = paused.elapsed();
+            self.full.suspend(suspended);
+            self.partial.suspend(suspended);
+            self.files.suspend(suspended);
+        }
+    }
+
+    /// ### paused
+    ///
+    /// Returns whether transfer has been paused
+    pub fn paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// ### redraw_due
+    ///
+    /// Returns whether at least `interval` has elapsed since the last time the progress has been
+    /// drawn. If so, the redraw time is updated
+    pub fn redraw_due(&mut self, interval: Duration) -> bool {
+        match self.last_redraw {
+            Some(last) if last.elapsed() < interval => false,
+            _ => {
+                self.last_redraw = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    /// ### calc_eta
+    ///
+    /// Calculate ETA for the whole transfer as seconds.
+    /// Once `SMART_ETA_THRESHOLD` bytes have been written, the time spent between files (opening,
+    /// closing, creating directories...) is measured and added for each file left, which makes the
+    /// estimate reliable for batches of many small files
+    pub fn calc_eta(&self) -> u64 {
+        if self.full.written < SMART_ETA_THRESHOLD || self.files.done == 0 {
+            return self.full.calc_eta();
+        }
+        let elapsed: f64 = self.full.started.elapsed().as_secs_f64();
+        let streaming: f64 = self.files.streaming().as_secs_f64().min(elapsed);
+        if streaming <= 0.0 {
+            return self.full.calc_eta();
+        }
+        let bytes_per_second: f64 = self.full.written as f64 / streaming;
+        let overhead_per_file: f64 = (elapsed - streaming) / self.files.done as f64;
+        let bytes_left: f64 = self.full.total.saturating_sub(self.full.written) as f64;
+        let files_left: f64 = self.files.total.saturating_sub(self.files.done) as f64;
+        (bytes_left / bytes_per_second + files_left * overhead_per_file).round() as u64
+    }
+
+    /// ### overall_progress
+    ///
+    /// Get progress for the whole transfer
+    pub fn overall_progress(&self) -> Progress {
+        Progress {
+            eta: self.calc_eta(),
+            ..Progress::from(&self.full)
+        }
+    }
+
+    /// ### set_excluded
+    ///
+    /// Set the paths which must not be transferred
+    pub fn set_excluded(&mut self, excluded: HashSet<PathBuf>) {
+        self.excluded = excluded;
+    }
+
+    /// ### is_excluded
+    ///
+    /// Returns whether the entry at `path` must not be transferred
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.excluded.contains(path)
+    }
+
+    /// ### deny
+    ///
+    /// Record that the directory at `path` has been skipped, since it couldn't be read for lack of permissions
+    pub fn deny(&mut self, path: &Path) {
+        self.denied.insert(path.to_path_buf());
+    }
+
+    /// ### take_denied
+    ///
+    /// Take the directories skipped for lack of permissions, sorted by path
+    pub fn take_denied(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.denied).into_iter().collect()
+    }
+
+    /// ### start_move
+    ///
+    /// Start recording the source files transferred, which must be removed once the transfer is over
+    pub fn start_move(&mut self) {
+        self.moved = Some(Vec::new());
+        self.kept.clear();
+    }
+
+    /// ### transferred
+    ///
+    /// Record that the file at `source` has been transferred.
+    /// If entries are being moved and the file hasn't failed verification, the source will be removed
+    pub fn transferred(&mut self, source: &Path) {
+        if let Some(moved) = self.moved.as_mut() {
+            if !self.kept.contains(source) {
+                moved.push(source.to_path_buf());
+            }
+        }
+    }
+
+    /// ### keep_source
+    ///
+    /// Record that the file at `source` failed verification, so it must not be removed by a move
+    pub fn keep_source(&mut self, source: &Path) {
+        if self.moved.is_some() {
+            self.kept.insert(source.to_path_buf());
+        }
+    }
+
+    /// ### take_moved
+    ///
+    /// Stop recording the transferred files and take the source files to remove
+    pub fn take_moved(&mut self) -> Vec<PathBuf> {
+        self.kept.clear();
+        self.moved.take().unwrap_or_default()
+    }
 }
 
 impl Default for ProgressStates {
@@ -97,6 +272,88 @@ impl Default for ProgressStates {
     }
 }
 
+impl FilesStates {
+    /// ### init
+    ///
+    /// Initialize files states for a transfer of `total` files
+    pub fn init(&mut self, total: usize) {
+        self.total = total;
+        self.done = 0;
+        self.failed = 0;
+        self.streaming = Duration::ZERO;
+        self.current = None;
+    }
+
+    /// ### start_file
+    ///
+    /// Report that the data of a new file is being written
+    pub fn start_file(&mut self) {
+        self.current = Some(Instant::now());
+    }
+
+    /// ### end_file
+    ///
+    /// Report that the file being transferred is complete
+    pub fn end_file(&mut self) {
+        if let Some(started) = self.current.take() {
+            self.streaming += started.elapsed();
+        }
+        self.done += 1;
+    }
+
+    /// ### fail_file
+    ///
+    /// Report that the file being transferred couldn't be transferred
+    pub fn fail_file(&mut self) {
+        self.current = None;
+        self.failed += 1;
+    }
+
+    /// ### suspend
+    ///
+    /// Don't count `suspended` in the time spent writing the file being transferred
+    fn suspend(&mut self, suspended: Duration) {
+        if let Some(current) = self.current.as_mut() {
+            *current += suspended;
+        }
+    }
+
+    /// ### done
+    ///
+    /// Get the amount of files transferred
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    /// ### failed
+    ///
+    /// Get the amount of files which couldn't be transferred
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// ### streaming
+    ///
+    /// Get time spent writing file data, including the file being transferred
+    fn streaming(&self) -> Duration {
+        self.streaming + self.current.map(|x| x.elapsed()).unwrap_or(Duration::ZERO)
+    }
+}
+
+impl From<&TransferStates> for FilesProgress {
+    fn from(states: &TransferStates) -> Self {
+        let elapsed: f64 = states.full.started.elapsed().as_secs_f64();
+        FilesProgress {
+            done: states.files.done as u64,
+            total: states.files.total as u64,
+            rate: match elapsed > 0.0 {
+                true => states.files.done as f64 / elapsed,
+                false => 0.0,
+            },
+        }
+    }
+}
+
 impl fmt::Display for ProgressStates {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let eta: String = match self.calc_eta() {
@@ -113,11 +370,22 @@ impl fmt::Display for ProgressStates {
             "{:.2}% - ETA {} ({}/s)",
             self.calc_progress_percentage(),
             eta,
-            ByteSize(self.calc_bytes_per_second())
+            fmt_size(self.calc_bytes_per_second())
         )
     }
 }
 
+impl From<&ProgressStates> for Progress {
+    fn from(states: &ProgressStates) -> Self {
+        Progress {
+            written: states.written as u64,
+            total: states.total as u64,
+            rate: states.calc_bytes_per_second(),
+            eta: states.calc_eta(),
+        }
+    }
+}
+
 impl ProgressStates {
     /// ### init
     ///
@@ -136,6 +404,20 @@ impl ProgressStates {
         self.calc_progress_percentage()
     }
 
+    /// ### rewind
+    ///
+    /// Take back `delta` bytes reported as written, since they have to be transferred again
+    pub fn rewind(&mut self, delta: usize) {
+        self.written = self.written.saturating_sub(delta);
+    }
+
+    /// ### suspend
+    ///
+    /// Don't count `suspended` in the time elapsed since the transfer started
+    fn suspend(&mut self, suspended: Duration) {
+        self.started += suspended;
+    }
+
     /// ### calc_progress
     ///
     /// Calculate progress in a range between 0.0 to 1.0
@@ -154,6 +436,13 @@ impl ProgressStates {
         self.started
     }
 
+    /// ### written
+    ///
+    /// Get the amount of bytes written
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
     /// ### calc_progress_percentage
     ///
     /// Calculate the current transfer progress as percentage
@@ -220,11 +509,21 @@ mod test {
         states.update_progress(256);
         assert_eq!(states.total, 1024);
         assert_eq!(states.written, 256);
+        assert_eq!(states.written(), 256);
         assert_eq!(states.calc_bytes_per_second(), 64); // 256 bytes in 4 seconds
         assert_eq!(states.calc_eta(), 12); // 16 total sub 4
         assert_eq!(states.calc_progress_percentage(), 25.0);
         assert_eq!(states.calc_progress(), 0.25);
         assert_eq!(states.to_string().as_str(), "25.00% - ETA 00:12 (64 B/s)");
+        assert_eq!(
+            Progress::from(&states),
+            Progress {
+                written: 256,
+                total: 1024,
+                rate: 64,
+                eta: 12,
+            }
+        );
         // 100%
         states.started = states.started.checked_sub(Duration::from_secs(12)).unwrap();
         states.update_progress(768);
@@ -235,11 +534,67 @@ mod test {
         assert_eq!(states.calc_progress_percentage(), 100.0);
         assert_eq!(states.calc_progress(), 1.0);
         assert_eq!(states.to_string().as_str(), "100.00% - ETA --:-- (64 B/s)");
+        // Rewind
+        states.rewind(256);
+        assert_eq!(states.written(), 768);
+        states.rewind(1024);
+        assert_eq!(states.written(), 0);
+        states.update_progress(1024);
         // Check if terminated at started
         states.started = Instant::now();
         assert_eq!(states.calc_bytes_per_second(), 1024);
     }
 
+    #[test]
+    fn test_ui_activities_filetransfer_lib_transfer_redraw_due() {
+        let mut states: TransferStates = TransferStates::default();
+        assert_eq!(states.redraw_due(Duration::from_secs(60)), true);
+        assert_eq!(states.redraw_due(Duration::from_secs(60)), false);
+        assert_eq!(states.redraw_due(Duration::ZERO), true);
+        states.reset();
+        assert_eq!(states.redraw_due(Duration::from_secs(60)), true);
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_transfer_smart_eta() {
+        let mut states: TransferStates = TransferStates::default();
+        states.full.init(10 * 1024 * 1024);
+        states.files.init(100);
+        states.full.started = Instant::now().checked_sub(Duration::from_secs(20)).unwrap();
+        // Below threshold, ETA is based on bytes only
+        states.full.update_progress(1024 * 1024);
+        states.files.done = 10;
+        states.files.streaming = Duration::from_secs(10);
+        assert_eq!(states.calc_eta(), states.full.calc_eta());
+        // 5MB written in 10 seconds; 10 seconds spent between 50 files
+        states.full.update_progress(4 * 1024 * 1024);
+        states.files.done = 50;
+        assert_eq!(states.calc_eta(), 20);
+        assert_eq!(states.overall_progress().eta, 20);
+        assert_eq!(
+            states.overall_progress().written,
+            Progress::from(&states.full).written
+        );
+        // Files progress
+        let files: FilesProgress = FilesProgress::from(&states);
+        assert_eq!(files.done, 50);
+        assert_eq!(files.total, 100);
+        assert!(files.rate > 2.4 && files.rate <= 2.5);
+        // File in progress
+        states.files.start_file();
+        assert!(states.files.current.is_some());
+        states.files.end_file();
+        assert!(states.files.current.is_none());
+        assert_eq!(states.files.done(), 51);
+        assert!(states.files.streaming >= Duration::from_secs(10));
+        // Failed file
+        states.files.start_file();
+        states.files.fail_file();
+        assert!(states.files.current.is_none());
+        assert_eq!(states.files.done(), 51);
+        assert_eq!(states.files.failed(), 1);
+    }
+
     #[test]
     fn test_ui_activities_filetransfer_lib_transfer_states() {
         let mut states: TransferStates = TransferStates::default();
@@ -255,5 +610,67 @@ mod test {
         assert_eq!(states.aborted(), true);
         states.reset();
         assert_eq!(states.aborted(), false);
+        // Paused
+        let started: Instant = states.full.started;
+        states.files.start_file();
+        let file_started: Option<Instant> = states.files.current;
+        states.resume(); // Not paused; nothing to do
+        assert_eq!(states.full.started, started);
+        states.pause();
+        assert_eq!(states.paused(), true);
+        std::thread::sleep(Duration::from_millis(20));
+        states.resume();
+        assert_eq!(states.paused(), false);
+        assert!(states.full.started >= started + Duration::from_millis(20));
+        assert!(states.partial.started >= started + Duration::from_millis(20));
+        assert!(states.files.current.unwrap() >= file_started.unwrap() + Duration::from_millis(20));
+        states.pause();
+        states.reset();
+        assert_eq!(states.paused(), false);
+        // Excluded
+        assert_eq!(states.is_excluded(Path::new("/tmp/a.txt")), false);
+        let mut excluded: HashSet<PathBuf> = HashSet::new();
+        excluded.insert(PathBuf::from("/tmp/a.txt"));
+        states.set_excluded(excluded);
+        assert_eq!(states.is_excluded(Path::new("/tmp/a.txt")), true);
+        states.set_excluded(HashSet::new());
+        assert_eq!(states.is_excluded(Path::new("/tmp/a.txt")), false);
+        // Denied
+        states.deny(Path::new("/root/b"));
+        states.deny(Path::new("/root/a"));
+        states.deny(Path::new("/root/b"));
+        assert_eq!(
+            states.take_denied(),
+            vec![PathBuf::from("/root/a"), PathBuf::from("/root/b")]
+        );
+        assert!(states.take_denied().is_empty());
+        states.deny(Path::new("/root/a"));
+        states.reset();
+        assert!(states.take_denied().is_empty());
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_transfer_move() {
+        let mut states: TransferStates = TransferStates::default();
+        // Not moving
+        states.transferred(Path::new("/tmp/a.txt"));
+        assert!(states.take_moved().is_empty());
+        // Moving
+        states.start_move();
+        states.transferred(Path::new("/tmp/a.txt"));
+        states.keep_source(Path::new("/tmp/b.txt"));
+        states.transferred(Path::new("/tmp/b.txt"));
+        states.reset(); // Moved files survive transfer reset
+        states.transferred(Path::new("/tmp/c.txt"));
+        assert_eq!(
+            states.take_moved(),
+            vec![PathBuf::from("/tmp/a.txt"), PathBuf::from("/tmp/c.txt")]
+        );
+        // Stopped
+        states.transferred(Path::new("/tmp/d.txt"));
+        assert!(states.take_moved().is_empty());
+        states.start_move();
+        states.transferred(Path::new("/tmp/b.txt"));
+        assert_eq!(states.take_moved(), vec![PathBuf::from("/tmp/b.txt")]);
     }
 }
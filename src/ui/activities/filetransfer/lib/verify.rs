@@ -0,0 +1,186 @@
+//! ## Verify
+//!
+//! `verify` is the module which provides the drift report between local and remote trees
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// ## DriftKind
+///
+/// Describes how an entry differs between local and remote
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+pub enum DriftKind {
+    MissingLocal,
+    MissingRemote,
+    TypeMismatch,
+    SizeMismatch { local: usize, remote: usize },
+    HashMismatch { local: String, remote: String },
+}
+
+impl DriftKind {
+    /// ### describe
+    ///
+    /// Get a short description of the drift
+    pub fn describe(&self) -> String {
+        match self {
+            DriftKind::MissingLocal => String::from("missing on local"),
+            DriftKind::MissingRemote => String::from("missing on remote"),
+            DriftKind::TypeMismatch => String::from("file/directory mismatch"),
+            DriftKind::SizeMismatch { local, remote } => {
+                format!("size mismatch ({} / {} bytes)", local, remote)
+            }
+            DriftKind::HashMismatch { .. } => String::from("hash mismatch"),
+        }
+    }
+}
+
+/// ## Drift
+///
+/// A single drift entry; path is relative to the compared roots
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Drift {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub kind: DriftKind,
+}
+
+/// ## DriftReport
+///
+/// Result of the comparison between a local and a remote tree
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DriftReport {
+    pub local: PathBuf,
+    pub remote: PathBuf,
+    pub checked: usize,
+    pub drifts: Vec<Drift>,
+}
+
+impl DriftReport {
+    /// ### new
+    ///
+    /// Instantiates a new empty report for the provided roots
+    pub fn new(local: &Path, remote: &Path) -> Self {
+        Self {
+            local: local.to_path_buf(),
+            remote: remote.to_path_buf(),
+            checked: 0,
+            drifts: Vec::new(),
+        }
+    }
+
+    /// ### push
+    ///
+    /// Report a drift for the entry at relative `path`
+    pub fn push(&mut self, path: PathBuf, kind: DriftKind) {
+        self.drifts.push(Drift { path, kind });
+    }
+
+    /// ### is_clean
+    ///
+    /// Returns whether the trees are equal
+    pub fn is_clean(&self) -> bool {
+        self.drifts.is_empty()
+    }
+
+    /// ### to_json
+    ///
+    /// Serialize report to JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+/// ### hash_reader
+///
+/// Read all the data from reader and return its SHA-256 as an hex string
+pub fn hash_reader(reader: &mut dyn Read) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer: [u8; 65536] = [0; 65536];
+    loop {
+        match reader.read(&mut buffer)? {
+            0 => break,
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_verify() {
+        let mut report: DriftReport =
+            DriftReport::new(Path::new("/home/omar/project"), Path::new("/var/www"));
+        assert!(report.is_clean());
+        report.checked = 3;
+        report.push(PathBuf::from("README.md"), DriftKind::MissingRemote);
+        report.push(
+            PathBuf::from("src/main.rs"),
+            DriftKind::SizeMismatch {
+                local: 1024,
+                remote: 512,
+            },
+        );
+        assert_eq!(report.is_clean(), false);
+        assert_eq!(
+            report.drifts[0].kind.describe().as_str(),
+            "missing on remote"
+        );
+        assert_eq!(
+            report.drifts[1].kind.describe().as_str(),
+            "size mismatch (1024 / 512 bytes)"
+        );
+        let json: serde_json::Value = serde_json::from_str(report.to_json().unwrap().as_str())
+            .ok()
+            .unwrap();
+        assert_eq!(json["checked"], 3);
+        assert_eq!(json["drifts"][0]["path"], "README.md");
+        assert_eq!(json["drifts"][0]["kind"], "missing-remote");
+        assert_eq!(json["drifts"][1]["kind"], "size-mismatch");
+        assert_eq!(json["drifts"][1]["local"], 1024);
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_verify_hash() {
+        let mut data: &[u8] = b"hello";
+        assert_eq!(
+            hash_reader(&mut data).unwrap().as_str(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+}
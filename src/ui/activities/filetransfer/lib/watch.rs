@@ -0,0 +1,303 @@
+//! ## Watch
+//!
+//! `watch` polls a local directory for files which changed, so that they can be uploaded as soon as they're saved
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// Ext
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Size and modification time of a file
+type FileState = (u64, SystemTime);
+
+/// ## Watch
+///
+/// Watches a local directory and its subdirectories.
+/// The directory is scanned by a worker thread, so that walking large trees doesn't block the UI.
+/// A file is reported once it has changed and then stayed the same for a whole scan interval,
+/// so that files being written aren't uploaded half-way
+pub struct Watch {
+    local: PathBuf,
+    remote: PathBuf,
+    changes: Receiver<Vec<PathBuf>>, // Files reported by the worker
+    resync: Sender<Sender<()>>,      // Resync requests; the worker acks once done
+}
+
+impl Watch {
+    /// ### new
+    ///
+    /// Start watching `local`, whose changes are uploaded into `remote`, every `interval`.
+    /// Files which already exist are not reported until they change.
+    /// The worker stops once the watch is dropped
+    pub fn new(local: &Path, remote: &Path, interval: Duration) -> Self {
+        let (changes_tx, changes) = mpsc::channel();
+        let (resync, resync_rx) = mpsc::channel();
+        let dir: PathBuf = local.to_path_buf();
+        thread::spawn(move || watch_worker(dir.as_path(), interval, resync_rx, changes_tx));
+        Self {
+            local: local.to_path_buf(),
+            remote: remote.to_path_buf(),
+            changes,
+            resync,
+        }
+    }
+
+    /// ### local
+    ///
+    /// Returns the watched local directory
+    pub fn local(&self) -> &Path {
+        self.local.as_path()
+    }
+
+    /// ### remote
+    ///
+    /// Returns the remote directory changes are uploaded into
+    pub fn remote(&self) -> &Path {
+        self.remote.as_path()
+    }
+
+    /// ### remote_path
+    ///
+    /// Get the remote path `file`, which is in the watched directory, must be uploaded to
+    pub fn remote_path(&self, file: &Path) -> PathBuf {
+        match file.strip_prefix(self.local.as_path()) {
+            Ok(relative) => self.remote.join(relative),
+            Err(_) => self.remote.join(file.file_name().unwrap_or_default()),
+        }
+    }
+
+    /// ### poll
+    ///
+    /// Return the files reported by the worker since the last poll, which are ready to be uploaded,
+    /// sorted by path. Never blocks
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = self.changes.try_iter().flatten().collect();
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+
+    /// ### resync
+    ///
+    /// Consider the current state of the watched directory as uploaded, e.g. after termscp itself
+    /// wrote into it. Waits for the worker to rescan the directory
+    pub fn resync(&mut self) {
+        let (ack_tx, ack) = mpsc::channel();
+        if self.resync.send(ack_tx).is_ok() {
+            let _ = ack.recv();
+        }
+        // Drop changes reported before the resync
+        self.changes.try_iter().for_each(drop);
+    }
+}
+
+/// ### watch_worker
+///
+/// Scan `dir` every `interval`, sending the files ready to be uploaded to `changes`, until the watch
+/// is dropped
+fn watch_worker(
+    dir: &Path,
+    interval: Duration,
+    resync: Receiver<Sender<()>>,
+    changes: Sender<Vec<PathBuf>>,
+) {
+    let mut state: WatchState = WatchState::new(scan(dir));
+    loop {
+        match resync.recv_timeout(interval) {
+            Ok(ack) => {
+                state = WatchState::new(scan(dir));
+                let _ = ack.send(());
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let changed: Vec<PathBuf> = state.update(scan(dir));
+                if !changed.is_empty() && changes.send(changed).is_err() {
+                    break;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// ## WatchState
+///
+/// Tracks the state of the files in the watched directory across scans
+struct WatchState {
+    synced: HashMap<PathBuf, FileState>, // State of the files as last uploaded (or found)
+    seen: HashMap<PathBuf, FileState>,   // State of the files at the previous scan
+}
+
+impl WatchState {
+    /// ### new
+    ///
+    /// Instantiate a new `WatchState`, considering `files` as uploaded
+    fn new(files: HashMap<PathBuf, FileState>) -> Self {
+        Self {
+            synced: files.clone(),
+            seen: files,
+        }
+    }
+
+    /// ### update
+    ///
+    /// Update the state with the result of a new scan and return the files which are ready to be uploaded,
+    /// sorted by path. They're considered uploaded from now on
+    fn update(&mut self, files: HashMap<PathBuf, FileState>) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = files
+            .iter()
+            .filter(|(path, state)| {
+                self.synced.get(*path) != Some(*state) && self.seen.get(*path) == Some(*state)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.sort();
+        for path in changed.iter() {
+            if let Some(state) = files.get(path) {
+                self.synced.insert(path.clone(), *state);
+            }
+        }
+        // Forget removed files
+        self.synced.retain(|path, _| files.contains_key(path));
+        self.seen = files;
+        changed
+    }
+}
+
+/// ### is_temporary
+///
+/// Returns whether `name` looks like the name of a temporary file written by an editor
+fn is_temporary(name: &str) -> bool {
+    name.ends_with('~') || name.ends_with(".swp") || name.ends_with(".swx") || name == "4913"
+}
+
+/// ### scan
+///
+/// Get the state of the files in `dir` and its subdirectories.
+/// Symbolic links aren't followed, and unreadable directories are skipped
+fn scan(dir: &Path) -> HashMap<PathBuf, FileState> {
+    let mut files: HashMap<PathBuf, FileState> = HashMap::new();
+    let mut dirs: Vec<PathBuf> = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(dir.as_path()) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let metadata = match fs::symlink_metadata(entry.path()) {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else if metadata.is_file()
+                && !is_temporary(entry.file_name().to_string_lossy().as_ref())
+            {
+                let mtime: SystemTime = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.insert(entry.path(), (metadata.len(), mtime));
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn write_file(path: &Path, content: &str) {
+        let mut file = fs::File::create(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_watch_state() {
+        let tmpdir: TempDir = TempDir::new().unwrap();
+        let root: &Path = tmpdir.path();
+        write_file(root.join("index.html").as_path(), "<html></html>");
+        let mut state: WatchState = WatchState::new(scan(root));
+        // Existing files are not reported
+        assert!(state.update(scan(root)).is_empty());
+        // New files are reported once they're stable
+        fs::create_dir(root.join("css")).unwrap();
+        write_file(root.join("css/style.css").as_path(), "body {}");
+        write_file(root.join("css/.style.css.swp").as_path(), "swap");
+        assert!(state.update(scan(root)).is_empty());
+        assert_eq!(state.update(scan(root)), vec![root.join("css/style.css")]);
+        assert!(state.update(scan(root)).is_empty());
+        // Changed files
+        write_file(
+            root.join("index.html").as_path(),
+            "<html><body></body></html>",
+        );
+        assert!(state.update(scan(root)).is_empty());
+        assert_eq!(state.update(scan(root)), vec![root.join("index.html")]);
+        // Removed files are forgotten
+        fs::remove_file(root.join("index.html")).unwrap();
+        assert!(state.update(scan(root)).is_empty());
+        assert!(!state.synced.contains_key(&root.join("index.html")));
+    }
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_watch() {
+        let tmpdir: TempDir = TempDir::new().unwrap();
+        let root: &Path = tmpdir.path();
+        write_file(root.join("index.html").as_path(), "<html></html>");
+        let mut watch: Watch = Watch::new(root, Path::new("/var/www"), Duration::from_millis(10));
+        assert_eq!(watch.local(), root);
+        assert_eq!(watch.remote(), Path::new("/var/www"));
+        assert_eq!(
+            watch.remote_path(root.join("css/style.css").as_path()),
+            PathBuf::from("/var/www/css/style.css")
+        );
+        // Wait for the worker to scan the directory
+        watch.resync();
+        // Changes are reported by the worker
+        fs::create_dir(root.join("css")).unwrap();
+        write_file(root.join("css/style.css").as_path(), "body {}");
+        let mut changed: Vec<PathBuf> = Vec::new();
+        for _ in 0..500 {
+            changed = watch.poll();
+            if !changed.is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(changed, vec![root.join("css/style.css")]);
+        // Resync
+        write_file(root.join("index.html").as_path(), "<html/>");
+        watch.resync();
+        thread::sleep(Duration::from_millis(100));
+        assert!(watch.poll().is_empty());
+    }
+}
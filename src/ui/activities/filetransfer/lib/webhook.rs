@@ -0,0 +1,138 @@
+//! ## Webhook
+//!
+//! `webhook` is the module which notifies an HTTP endpoint when a batch transfer finishes
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::system::metrics::Outcome;
+
+use std::thread;
+use std::time::Duration;
+use ureq::AgentBuilder;
+
+/// Body sent when no template is configured
+pub const DEFAULT_TEMPLATE: &str = r#"{"event": "{event}", "direction": "{direction}", "host": "{host}", "path": "{path}", "files": {files}, "failed": {failed}, "bytes": {bytes}, "seconds": {seconds}}"#;
+
+/// ## Notification
+///
+/// Summary of a finished batch transfer
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub outcome: Outcome,
+    pub upload: bool,  // Upload or download
+    pub host: String,  // Remote host
+    pub path: String,  // Path the entries were transferred into
+    pub files: usize,  // Files transferred
+    pub failed: usize, // Files which couldn't be transferred
+    pub bytes: usize,
+    pub seconds: u64,
+}
+
+impl Notification {
+    /// ### render
+    ///
+    /// Fill `template` with the values of the notification.
+    /// Text values are escaped, so that they can be placed between quotes in JSON strings
+    pub fn render(&self, template: &str) -> String {
+        template
+            .replace("{event}", self.outcome.as_str())
+            .replace(
+                "{direction}",
+                match self.upload {
+                    true => "upload",
+                    false => "download",
+                },
+            )
+            .replace("{files}", self.files.to_string().as_str())
+            .replace("{failed}", self.failed.to_string().as_str())
+            .replace("{bytes}", self.bytes.to_string().as_str())
+            .replace("{seconds}", self.seconds.to_string().as_str())
+            // Text values last, since they may contain placeholders themselves
+            .replace("{host}", escape(self.host.as_str()).as_str())
+            .replace("{path}", escape(self.path.as_str()).as_str())
+    }
+}
+
+/// ### send
+///
+/// POST `body` to `url` from a background thread, so that a slow endpoint doesn't block the user.
+/// Since the request outlives the transfer, failures are only written to the log file
+pub fn send(url: String, body: String) {
+    thread::spawn(move || {
+        let agent = AgentBuilder::new().timeout(Duration::from_secs(30)).build();
+        debug!("POST {}", url);
+        match agent
+            .post(url.as_str())
+            .set("Content-Type", "application/json")
+            .send_string(body.as_str())
+        {
+            Ok(_) => info!("Webhook {} notified", url),
+            Err(err) => error!("Could not notify webhook {}: {}", url, err),
+        }
+    });
+}
+
+/// ### escape
+///
+/// Escape `s` to be placed into a JSON string
+fn escape(s: &str) -> String {
+    let quoted: String = serde_json::Value::String(s.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_activities_filetransfer_lib_webhook_render() {
+        let notification: Notification = Notification {
+            outcome: Outcome::Failed,
+            upload: true,
+            host: String::from("example.com"),
+            path: String::from("/var/www/\"site\""),
+            files: 8,
+            failed: 2,
+            bytes: 1024,
+            seconds: 3,
+        };
+        assert_eq!(
+            notification.render(DEFAULT_TEMPLATE).as_str(),
+            r#"{"event": "failed", "direction": "upload", "host": "example.com", "path": "/var/www/\"site\"", "files": 8, "failed": 2, "bytes": 1024, "seconds": 3}"#
+        );
+        assert!(serde_json::from_str::<serde_json::Value>(
+            notification.render(DEFAULT_TEMPLATE).as_str()
+        )
+        .is_ok());
+        assert_eq!(
+            notification
+                .render(r#"{"text": "{direction} to {host} {event}"}"#)
+                .as_str(),
+            r#"{"text": "upload to example.com failed"}"#
+        );
+    }
+}
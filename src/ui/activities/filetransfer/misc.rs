@@ -22,14 +22,28 @@
  * SOFTWARE.
  */
 // Locals
-use super::{ConfigClient, FileTransferActivity, LogLevel, LogRecord};
+use super::lib::remote_env::{self, RemoteEnv};
+use super::{
+    BookmarksClient, ConfigClient, ErrorReport, FileTransferActivity, JournalClient, LogLevel,
+    LogRecord, UsageClient, COMPONENT_EXPLORER_LOCAL, COMPONENT_EXPLORER_REMOTE,
+    COMPONENT_PROGRESS_BAR,
+};
+use crate::filetransfer::{
+    Builder, Capability, FileTransfer, FileTransferError, FileTransferParams, FileTransferProtocol,
+    Proxy, TlsOptions,
+};
+use crate::fs::FsEntry;
 use crate::system::environment;
 use crate::system::sshkey_storage::SshKeyStorage;
-use crate::utils::path;
+use crate::ui::activities::STORE_KEY_BOOKMARKS_MASTER_PASSWORD;
+use crate::ui::input::is_typed_char;
+use crate::utils::{fmt, path, ui};
 // Ext
+use crossterm::event::Event;
 use std::env;
 use std::path::{Path, PathBuf};
-use tuirealm::Update;
+use std::str::FromStr;
+use tuirealm::{Msg, Update};
 
 const LOG_CAPACITY: usize = 256;
 
@@ -38,6 +52,7 @@ impl FileTransferActivity {
     ///
     /// Add message to log events
     pub(super) fn log(&mut self, level: LogLevel, msg: String) {
+        let msg: String = self.mask_password(msg);
         // Log to file
         match level {
             LogLevel::Error => error!("{}", msg),
@@ -52,15 +67,96 @@ impl FileTransferActivity {
         }
         // Eventually push front the new record
         self.log_records.push_front(record);
+        // In low bandwidth mode, log is updated once the transfer is over
+        if self.low_bandwidth() && self.view.get_props(COMPONENT_PROGRESS_BAR).is_some() {
+            return;
+        }
         // Update log
         let msg = self.update_logbox();
         self.update(msg);
     }
 
+    /// ### copy_log_record
+    ///
+    /// Copy log record at `idx` to clipboard
+    pub(super) fn copy_log_record(&mut self, idx: usize) {
+        let line: Option<String> = self.log_records.get(idx).map(|record| {
+            format!(
+                "{} [{}]: {}",
+                record.time.format("%Y-%m-%dT%H:%M:%S%Z"),
+                match record.level {
+                    LogLevel::Error => "ERROR",
+                    LogLevel::Warn => "WARN",
+                    LogLevel::Info => "INFO",
+                },
+                record.msg
+            )
+        });
+        if let Some(line) = line {
+            if let Err(err) = ui::copy_to_clipboard(line.as_str()) {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not copy log record to clipboard: {}", err),
+                );
+            }
+        }
+    }
+
+    /// ### copy_ssh_command
+    ///
+    /// Copy to clipboard a ssh command which opens a shell in the current remote directory
+    pub(super) fn copy_ssh_command(&mut self) {
+        let params = self.context().ft_params().unwrap();
+        let command: Option<String> = match params.protocol {
+            FileTransferProtocol::Sftp | FileTransferProtocol::Scp => {
+                Some(fmt::fmt_ssh_cd_command(
+                    params.username.as_deref(),
+                    params.address.as_str(),
+                    params.port,
+                    self.remote().wrkdir.as_path(),
+                ))
+            }
+            _ => None,
+        };
+        match command {
+            None => self.log_and_alert(
+                LogLevel::Warn,
+                String::from("Opening a shell is supported only for SFTP and SCP"),
+            ),
+            Some(command) => match ui::copy_to_clipboard(command.as_str()) {
+                Ok(_) => self.log(LogLevel::Info, format!("Copied to clipboard: {}", command)),
+                Err(err) => self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not copy ssh command to clipboard: {}", err),
+                ),
+            },
+        }
+    }
+
+    /// ### mask_password
+    ///
+    /// Mask the secrets used to connect to the remote host, if they appear in `msg`:
+    /// the password, the password of the proxy and the passphrase of the SSH key
+    pub(super) fn mask_password(&self, msg: String) -> String {
+        let params: Option<&FileTransferParams> =
+            self.context.as_ref().and_then(|ctx| ctx.ft_params());
+        let password: Option<String> = params.and_then(|params| params.password.clone());
+        let proxy_password: Option<String> = params
+            .and_then(|params| self.proxy_for(params).ok().flatten())
+            .and_then(|proxy| proxy.password);
+        vec![password, proxy_password, self.key_passphrase.clone()]
+            .into_iter()
+            .flatten()
+            .fold(msg, |msg, secret| {
+                fmt::mask_secret(msg.as_str(), secret.as_str())
+            })
+    }
+
     /// ### log_and_alert
     ///
     /// Add message to log events and also display it as an alert
     pub(super) fn log_and_alert(&mut self, level: LogLevel, msg: String) {
+        let msg: String = self.mask_password(msg);
         self.mount_error(msg.as_str());
         self.log(level, msg);
         // Update log
@@ -68,6 +164,211 @@ impl FileTransferActivity {
         self.update(msg);
     }
 
+    /// ### log_and_alert_error
+    ///
+    /// Add `err`, which occurred while doing what `context` describes, to log events and also display it as an alert.
+    /// The details of the alert report the sources of `err`
+    pub(super) fn log_and_alert_error(
+        &mut self,
+        level: LogLevel,
+        context: &str,
+        err: &(dyn std::error::Error + 'static),
+    ) {
+        let report: ErrorReport = ErrorReport::from_error(context, err);
+        let msg: String = self.mask_password(report.message().to_string());
+        self.mount_error_report(report);
+        self.log(level, msg);
+        // Update log
+        let msg = self.update_logbox();
+        self.update(msg);
+    }
+
+    /// ### probe_capabilities
+    ///
+    /// Probe the operations supported by the remote; the unsupported ones are logged,
+    /// since the actions relying on them are disabled
+    pub(super) fn probe_capabilities(&mut self) {
+        self.capabilities = self.client.capabilities();
+        let unsupported: Vec<&str> = self
+            .capabilities
+            .unsupported()
+            .iter()
+            .map(|x| x.description())
+            .collect();
+        if !unsupported.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "The server doesn't support: {}; related actions are disabled",
+                    unsupported.join(", ")
+                ),
+            );
+        }
+    }
+
+    /// ### probe_remote_env
+    ///
+    /// Detect the operating system, hostname, kernel and disk usage of the remote host with a single command,
+    /// if the remote can execute commands. The result is reported in the remote status bar
+    pub(super) fn probe_remote_env(&mut self) {
+        self.remote_env = None;
+        if !self.capabilities.supports(Capability::Exec) {
+            return;
+        }
+        match self.client.exec(remote_env::PROBE_COMMAND) {
+            Ok(output) => match RemoteEnv::parse(output.as_str()) {
+                Some(env) => {
+                    self.log(
+                        LogLevel::Info,
+                        format!("Connected to {} ({})", env.hostname, env.system()),
+                    );
+                    self.remote_env = Some(env);
+                }
+                None => debug!("Could not parse remote environment: {}", output),
+            },
+            Err(err) => debug!("Could not detect remote environment: {}", err),
+        }
+    }
+
+    /// ### check_capability
+    ///
+    /// Returns whether the remote supports `capability`; if it doesn't, logs why `action` is disabled
+    pub(super) fn check_capability(&mut self, capability: Capability, action: &str) -> bool {
+        let supported: bool = self.capabilities.supports(capability);
+        if !supported {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "{} is disabled: the server can't {}",
+                    action,
+                    capability.description()
+                ),
+            );
+        }
+        supported
+    }
+
+    /// ### download_dir
+    ///
+    /// Get the directory to save downloads into, when the destination hasn't been chosen:
+    /// the default download directory, if set and enabled, otherwise the local working directory
+    pub(super) fn download_dir(&mut self) -> PathBuf {
+        let wrkdir: PathBuf = self.local().wrkdir.clone();
+        if !self.browser.download_to_default {
+            return wrkdir;
+        }
+        match self.config().get_download_dir() {
+            Some(dir) if dir.is_dir() => dir,
+            Some(dir) => {
+                self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "Download directory \"{}\" doesn't exist; downloading into \"{}\"",
+                        dir.display(),
+                        wrkdir.display()
+                    ),
+                );
+                wrkdir
+            }
+            None => wrkdir,
+        }
+    }
+
+    /// ### scan_dir_or_deny
+    ///
+    /// List `dir`, on remote if `remote` is set, as part of a recursive scan.
+    /// A directory which can't be read for lack of permissions is listed as empty, so that the scan goes on,
+    /// and recorded to be reported with `report_denied_entries` once the scan is over
+    pub(super) fn scan_dir_or_deny(
+        &mut self,
+        dir: &Path,
+        remote: bool,
+    ) -> Result<Vec<FsEntry>, String> {
+        let result: Result<Vec<FsEntry>, (bool, String)> = match remote {
+            true => self
+                .remote_list_dir(dir)
+                .map_err(|e| (e.is_permission_denied(), e.to_string())),
+            false => self
+                .host
+                .scan_dir(dir)
+                .map_err(|e| (e.is_permission_denied(), e.to_string())),
+        };
+        match result {
+            Err((true, err)) => {
+                debug!("Skipping {}: {}", dir.display(), err);
+                self.transfer.deny(dir);
+                Ok(Vec::new())
+            }
+            result => result.map_err(|(_, err)| err),
+        }
+    }
+
+    /// ### report_denied_entries
+    ///
+    /// Report the directories skipped during the last recursive scan for lack of permissions, if any
+    pub(super) fn report_denied_entries(&mut self) {
+        let denied: Vec<PathBuf> = self.transfer.take_denied();
+        if denied.is_empty() {
+            return;
+        }
+        let paths: Vec<String> = denied.iter().map(|x| x.display().to_string()).collect();
+        self.log(
+            LogLevel::Warn,
+            format!("Permission denied: {}", paths.join(", ")),
+        );
+        self.log_and_alert(
+            LogLevel::Warn,
+            format!("{} entries skipped due to permissions", denied.len()),
+        );
+    }
+
+    /// ### make_error_report
+    ///
+    /// Add the context of the current session to `report`, masking the secrets in it
+    pub(super) fn make_error_report(&self, report: ErrorReport) -> ErrorReport {
+        let host: String = self
+            .context
+            .as_ref()
+            .and_then(|ctx| ctx.ft_params())
+            .map(|params| {
+                BookmarksClient::host_key(
+                    params.address.as_str(),
+                    params.port,
+                    params.protocol,
+                    params.username.as_deref().unwrap_or(""),
+                )
+            })
+            .unwrap_or_default();
+        report
+            .mask(|x| self.mask_password(x.to_string()))
+            .context("host", host)
+            .context(
+                "remote directory",
+                self.remote().wrkdir.display().to_string(),
+            )
+            .context("local directory", self.local().wrkdir.display().to_string())
+    }
+
+    /// ### copy_error_report
+    ///
+    /// Copy the report of the error being displayed to clipboard
+    pub(super) fn copy_error_report(&mut self) {
+        let report: String = match self.error_report.as_ref() {
+            Some(report) => report.to_string(),
+            None => return,
+        };
+        match ui::copy_to_clipboard(report.as_str()) {
+            Ok(_) => self.log(
+                LogLevel::Info,
+                String::from("Copied error report to clipboard"),
+            ),
+            Err(err) => self.log(
+                LogLevel::Error,
+                format!("Could not copy error report to clipboard: {}", err),
+            ),
+        }
+    }
+
     /// ### init_config_client
     ///
     /// Initialize configuration client if possible.
@@ -90,6 +391,282 @@ impl FileTransferActivity {
         }
     }
 
+    /// ### init_bookmarks_client
+    ///
+    /// Initialize bookmarks client if possible, used to store remote favorites.
+    /// Recents are limited to `recents_size`, as configured.
+    /// This function doesn't return errors; favorites are just not available on failure.
+    pub(super) fn init_bookmarks_client(recents_size: usize) -> Option<BookmarksClient> {
+        match environment::init_config_dir() {
+            Ok(Some(termscp_dir)) => {
+                let bookmarks_file: PathBuf =
+                    environment::get_bookmarks_paths(termscp_dir.as_path());
+                match BookmarksClient::new(
+                    bookmarks_file.as_path(),
+                    termscp_dir.as_path(),
+                    recents_size,
+                ) {
+                    Ok(client) => Some(client),
+                    Err(err) => {
+                        error!("Could not initialize bookmarks client: {}", err);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// ### init_usage_client
+    ///
+    /// Initialize the client of the bandwidth usage statistics
+    pub(super) fn init_usage_client() -> Option<UsageClient> {
+        match environment::init_config_dir() {
+            Ok(Some(termscp_dir)) => {
+                let usage_file: PathBuf = environment::get_usage_path(termscp_dir.as_path());
+                match UsageClient::new(usage_file.as_path()) {
+                    Ok(client) => Some(client),
+                    Err(err) => {
+                        error!("Could not initialize usage client: {}", err);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// ### account_usage
+    ///
+    /// Account the bytes transferred with the remote host in the current month
+    pub(super) fn account_usage(&mut self, uploaded: usize, downloaded: usize) {
+        let host: String = self.bookmarks_host_key();
+        if let Some(client) = self.usage.as_mut() {
+            client.record(
+                host.as_str(),
+                UsageClient::current_month().as_str(),
+                uploaded as u64,
+                downloaded as u64,
+            );
+            if let Err(err) = client.write() {
+                error!("Could not write usage: {}", err);
+            }
+        }
+    }
+
+    /// ### init_journal_client
+    ///
+    /// Initialize the client of the journal of the batch transfers in progress
+    pub(super) fn init_journal_client() -> Option<JournalClient> {
+        match environment::init_config_dir() {
+            Ok(Some(termscp_dir)) => {
+                let journal_file: PathBuf = environment::get_journal_path(termscp_dir.as_path());
+                match JournalClient::new(journal_file.as_path()) {
+                    Ok(client) => Some(client),
+                    Err(err) => {
+                        error!("Could not initialize journal client: {}", err);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// ### journal_record
+    ///
+    /// Record the batch transfer starting with the remote host into the journal,
+    /// so that it can be resumed if termscp exits before it's over
+    pub(super) fn journal_record(
+        &mut self,
+        upload: bool,
+        sources: Vec<PathBuf>,
+        dest: &Path,
+        dst_name: Option<String>,
+    ) {
+        let host: String = self.bookmarks_host_key();
+        if let Some(client) = self.journal.as_mut() {
+            client.record(
+                host.as_str(),
+                JournalClient::make_entry(upload, sources, dest, dst_name),
+            );
+            if let Err(err) = client.write() {
+                error!("Could not write journal: {}", err);
+            }
+        }
+    }
+
+    /// ### journal_clear
+    ///
+    /// Remove the transfer with the remote host from the journal, once it's over
+    pub(super) fn journal_clear(&mut self) {
+        let host: String = self.bookmarks_host_key();
+        if let Some(client) = self.journal.as_mut() {
+            if client.clear(host.as_str()) {
+                if let Err(err) = client.write() {
+                    error!("Could not write journal: {}", err);
+                }
+            }
+        }
+    }
+
+    /// ### unlock_bookmarks_client
+    ///
+    /// Unlock bookmarks with the master password entered in the auth form, if they're locked
+    pub(super) fn unlock_bookmarks_client(&mut self) {
+        let password: Option<String> = self
+            .context()
+            .store()
+            .get_string(STORE_KEY_BOOKMARKS_MASTER_PASSWORD)
+            .map(|x| x.to_string());
+        if let Some(client) = self.bookmarks.as_mut() {
+            if client.is_locked() {
+                match password {
+                    Some(password) => {
+                        if let Err(err) = client.unlock(password.as_str()) {
+                            error!("Could not unlock bookmarks: {}", err);
+                        }
+                    }
+                    None => warn!("Bookmarks are locked; bookmarks features won't be available"),
+                }
+            }
+        }
+    }
+
+    /// ### security_key_for
+    ///
+    /// Get the FIDO2 security key SFTP/SCP clients will authenticate with on the host described by `params`, if any
+    pub(super) fn security_key_for(&self, params: &FileTransferParams) -> Option<PathBuf> {
+        if !matches!(
+            params.protocol,
+            FileTransferProtocol::Sftp | FileTransferProtocol::Scp
+        ) {
+            return None;
+        }
+        let storage: SshKeyStorage = Self::make_ssh_storage(self.config());
+        let username: String = params
+            .username
+            .clone()
+            .or_else(|| storage.ssh_host(params.address.as_str()).user)
+            .unwrap_or_default();
+        storage
+            .identity(params.address.as_str(), username.as_str())
+            .filter(|key| SshKeyStorage::is_security_key(key.as_path()))
+    }
+
+    /// ### bookmarks_host_key
+    ///
+    /// Get the key which identifies the current host in bookmarks
+    pub(super) fn bookmarks_host_key(&self) -> String {
+        let params = self.context().ft_params().unwrap();
+        BookmarksClient::host_key(
+            params.address.as_str(),
+            params.port,
+            params.protocol,
+            params.username.as_deref().unwrap_or(""),
+        )
+    }
+
+    /// ### proxy_for
+    ///
+    /// Get the proxy to connect to the host described by `params` through:
+    /// the one saved in its bookmark, otherwise the one set in configuration
+    pub(super) fn proxy_for(&self, params: &FileTransferParams) -> Result<Option<Proxy>, String> {
+        let host: String = BookmarksClient::host_key(
+            params.address.as_str(),
+            params.port,
+            params.protocol,
+            params.username.as_deref().unwrap_or(""),
+        );
+        let proxy: Option<String> = self
+            .bookmarks
+            .as_ref()
+            .and_then(|x| x.get_proxy(host.as_str()))
+            .or_else(|| self.config().get_proxy());
+        match proxy {
+            None => Ok(None),
+            Some(proxy) => Proxy::from_str(proxy.as_str())
+                .map(Some)
+                .map_err(|e| format!("Bad proxy: {}", e)),
+        }
+    }
+
+    /// ### tls_for
+    ///
+    /// Get the TLS options saved in the bookmark of the host described by `params`
+    pub(super) fn tls_for(&self, params: &FileTransferParams) -> Option<TlsOptions> {
+        let host: String = BookmarksClient::host_key(
+            params.address.as_str(),
+            params.port,
+            params.protocol,
+            params.username.as_deref().unwrap_or(""),
+        );
+        self.bookmarks
+            .as_ref()
+            .and_then(|x| x.get_tls(host.as_str()))
+    }
+
+    /// ### local_directory_for
+    ///
+    /// Get the local directory to enter once connected, from the bookmark of the host described by `params`
+    pub(super) fn local_directory_for(&self, params: &FileTransferParams) -> Option<PathBuf> {
+        let host: String = BookmarksClient::host_key(
+            params.address.as_str(),
+            params.port,
+            params.protocol,
+            params.username.as_deref().unwrap_or(""),
+        );
+        self.bookmarks
+            .as_ref()
+            .and_then(|x| x.get_local_directory(host.as_str()))
+    }
+
+    /// ### agent_forwarding_for
+    ///
+    /// Get whether the bookmark of the host described by `params` forwards the ssh agent to the executed commands
+    pub(super) fn agent_forwarding_for(&self, params: &FileTransferParams) -> bool {
+        let host: String = BookmarksClient::host_key(
+            params.address.as_str(),
+            params.port,
+            params.protocol,
+            params.username.as_deref().unwrap_or(""),
+        );
+        self.bookmarks
+            .as_ref()
+            .map(|x| x.get_agent_forwarding(host.as_str()))
+            .unwrap_or(false)
+    }
+
+    /// ### compression_for
+    ///
+    /// Get whether to compress the ssh transport with the host described by `params`:
+    /// its bookmark decides, if it says so, otherwise the configuration does
+    pub(super) fn compression_for(&self, params: &FileTransferParams) -> bool {
+        let host: String = BookmarksClient::host_key(
+            params.address.as_str(),
+            params.port,
+            params.protocol,
+            params.username.as_deref().unwrap_or(""),
+        );
+        self.bookmarks
+            .as_ref()
+            .and_then(|x| x.get_compression(host.as_str()))
+            .unwrap_or_else(|| self.config().get_compression())
+    }
+
+    /// ### make_client
+    ///
+    /// Make file transfer client for `protocol`
+    pub(super) fn make_client(
+        protocol: FileTransferProtocol,
+        cli: &ConfigClient,
+    ) -> Result<Box<dyn FileTransfer>, FileTransferError> {
+        let mut client: Box<dyn FileTransfer> =
+            Builder::build(protocol, Self::make_ssh_storage(cli))?;
+        client.set_stall_timeout(cli.get_stall_timeout());
+        Ok(client)
+    }
+
     /// ### make_ssh_storage
     ///
     /// Make ssh storage from `ConfigClient` if possible, empty otherwise (empty is implicit if degraded)
@@ -110,8 +687,24 @@ impl FileTransferActivity {
     /// Returns whether at least one event has been handled
     pub(super) fn read_input_event(&mut self) -> bool {
         if let Ok(Some(event)) = self.context().input_hnd().read_event() {
+            // Record event, if recording a macro
+            self.macros.record(event);
+            if let Event::Resize(_, _) = event {
+                self.context_mut().on_resize();
+            }
             // Handle event
             let msg = self.view.on(event);
+            // Text pasted into an explorer, e.g. files dropped onto the terminal
+            if let Some((id, Msg::OnKey(_))) = msg.as_ref() {
+                if (id == COMPONENT_EXPLORER_LOCAL || id == COMPONENT_EXPLORER_REMOTE)
+                    && is_typed_char(&event)
+                {
+                    if let Some(paths) = self.action_read_paste(&event) {
+                        self.action_paste(paths);
+                        return true;
+                    }
+                }
+            }
             self.update(msg);
             // Return true
             true
@@ -121,6 +714,44 @@ impl FileTransferActivity {
         }
     }
 
+    /// ### toggle_macro_recording
+    ///
+    /// Start recording a keyboard macro, or stop the current recording
+    pub(super) fn toggle_macro_recording(&mut self) {
+        match self.macros.recording() {
+            false => {
+                self.macros.start();
+                self.log(
+                    LogLevel::Info,
+                    String::from("Recording macro… (press <SHIFT+Q> to stop)"),
+                );
+            }
+            true => {
+                let events: usize = self.macros.stop();
+                self.log(
+                    LogLevel::Info,
+                    format!("Recorded macro with {} key(s); press <@> to replay", events),
+                );
+            }
+        }
+    }
+
+    /// ### replay_macro
+    ///
+    /// Replay last recorded keyboard macro
+    pub(super) fn replay_macro(&mut self) {
+        if self.macros.recording() {
+            // Replaying while recording is not allowed; don't record the replay key
+            self.macros.discard_last();
+            return;
+        }
+        let events = self.macros.recorded().to_vec();
+        for event in events {
+            let msg = self.view.on(event);
+            self.update(msg);
+        }
+    }
+
     /// ### local_to_abs_path
     ///
     /// Convert a path to absolute according to local explorer
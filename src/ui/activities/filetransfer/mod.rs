@@ -36,29 +36,55 @@ pub(self) mod view;
 // locals
 use super::{Activity, Context, ExitReason};
 use crate::config::themes::Theme;
-use crate::filetransfer::ftp_transfer::FtpFileTransfer;
-use crate::filetransfer::scp_transfer::ScpFileTransfer;
-use crate::filetransfer::sftp_transfer::SftpFileTransfer;
-use crate::filetransfer::{FileTransfer, FileTransferProtocol};
+use crate::filetransfer::lock::FileLock;
+use crate::filetransfer::{
+    Capabilities, FileTransfer, FileTransferError, FileTransferParams, FileTransferProtocol,
+    SlowLink, SymlinkMode,
+};
 use crate::fs::explorer::FileExplorer;
+use crate::fs::filter::TransferFilter;
 use crate::fs::FsEntry;
 use crate::host::Localhost;
+use crate::system::bookmarks_client::BookmarksClient;
 use crate::system::config_client::ConfigClient;
+use crate::system::journal_client::JournalClient;
+use crate::system::usage_client::UsageClient;
+use crate::ui::modal::ModalStack;
+use crate::utils::fmt::set_display_format;
 pub(self) use lib::browser;
 use lib::browser::Browser;
+use lib::hexdump::HexView;
+use lib::history::InputHistory;
+use lib::macros::MacroRecorder;
+use lib::parallel::Job;
+use lib::prefetch::Prefetch;
+use lib::remote_env::RemoteEnv;
+use lib::report::ErrorReport;
+use lib::trace::TransferTrace;
 use lib::transfer::TransferStates;
-pub(self) use session::TransferPayload;
+use lib::verify::DriftReport;
+use lib::watch::Watch;
+use session::{Peer, PendingTransfer, TransferDirection, TransferPayload};
 
 // Includes
 use chrono::{DateTime, Local};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
 use tempfile::TempDir;
 use tuirealm::View;
 
 // -- Storage keys
 
 const STORAGE_EXPLORER_WIDTH: &str = "FILETRANSFER_EXPLORER_WIDTH";
+const STORAGE_KEY_PASSPHRASE_PREFIX: &str = "FILETRANSFER_KEY_PASSPHRASE:";
+
+// -- prefetch
+
+const PREFETCH_CAPACITY: usize = 16; // Directory listings
+const PREFETCH_TTL: u64 = 30; // Seconds
+const PREFETCH_DELAY: u64 = 300; // Milliseconds the directory must be highlighted for
 
 // -- components
 
@@ -66,28 +92,57 @@ const COMPONENT_EXPLORER_LOCAL: &str = "EXPLORER_LOCAL";
 const COMPONENT_EXPLORER_REMOTE: &str = "EXPLORER_REMOTE";
 const COMPONENT_EXPLORER_FIND: &str = "EXPLORER_FIND";
 const COMPONENT_LOG_BOX: &str = "LOG_BOX";
-const COMPONENT_PROGRESS_BAR_FULL: &str = "PROGRESS_BAR_FULL";
-const COMPONENT_PROGRESS_BAR_PARTIAL: &str = "PROGRESS_BAR_PARTIAL";
+const COMPONENT_PROGRESS_BAR: &str = "PROGRESS_BAR";
+const COMPONENT_TEXT_BANNER: &str = "TEXT_BANNER";
+const COMPONENT_TEXT_AUTH_BANNER: &str = "TEXT_AUTH_BANNER";
 const COMPONENT_TEXT_ERROR: &str = "TEXT_ERROR";
 const COMPONENT_TEXT_FATAL: &str = "TEXT_FATAL";
 const COMPONENT_TEXT_HELP: &str = "TEXT_HELP";
+const COMPONENT_TEXT_HEXDUMP: &str = "TEXT_HEXDUMP";
 const COMPONENT_TEXT_WAIT: &str = "TEXT_WAIT";
+const COMPONENT_INPUT_CHMOD_DIR: &str = "INPUT_CHMOD_DIR";
+const COMPONENT_INPUT_CHMOD_FILE: &str = "INPUT_CHMOD_FILE";
+const COMPONENT_INPUT_CHOWN: &str = "INPUT_CHOWN";
 const COMPONENT_INPUT_COPY: &str = "INPUT_COPY";
 const COMPONENT_INPUT_EXEC: &str = "INPUT_EXEC";
+const COMPONENT_INPUT_FETCH: &str = "INPUT_FETCH";
 const COMPONENT_INPUT_FIND: &str = "INPUT_FIND";
 const COMPONENT_INPUT_GOTO: &str = "INPUT_GOTO";
+const COMPONENT_INPUT_HEXDUMP_OFFSET: &str = "INPUT_HEXDUMP_OFFSET";
+const COMPONENT_INPUT_KEY_PASSPHRASE: &str = "INPUT_KEY_PASSPHRASE";
+const COMPONENT_INPUT_MANIFEST_FILTER: &str = "INPUT_MANIFEST_FILTER";
 const COMPONENT_INPUT_MKDIR: &str = "INPUT_MKDIR";
 const COMPONENT_INPUT_NEWFILE: &str = "INPUT_NEWFILE";
 const COMPONENT_INPUT_OPEN_WITH: &str = "INPUT_OPEN_WITH";
+const COMPONENT_INPUT_PEER: &str = "INPUT_PEER";
+const COMPONENT_INPUT_PEER_PASSWORD: &str = "INPUT_PEER_PASSWORD";
 const COMPONENT_INPUT_RENAME: &str = "INPUT_RENAME";
 const COMPONENT_INPUT_SAVEAS: &str = "INPUT_SAVEAS";
+const COMPONENT_RADIO_CHMOD_RECURSIVE: &str = "RADIO_CHMOD_RECURSIVE";
 const COMPONENT_RADIO_DELETE: &str = "RADIO_DELETE";
 const COMPONENT_RADIO_DISCONNECT: &str = "RADIO_DISCONNECT";
+const COMPONENT_RADIO_KEY_PASSPHRASE_CACHE: &str = "RADIO_KEY_PASSPHRASE_CACHE";
+const COMPONENT_RADIO_PURGE_TRASH: &str = "RADIO_PURGE_TRASH";
 const COMPONENT_RADIO_QUIT: &str = "RADIO_QUIT";
 const COMPONENT_RADIO_SORTING: &str = "RADIO_SORTING";
+const COMPONENT_RADIO_STALLED: &str = "RADIO_STALLED";
+const COMPONENT_RADIO_CONFLICT: &str = "RADIO_CONFLICT";
+const COMPONENT_RADIO_JOURNAL: &str = "RADIO_JOURNAL";
+const COMPONENT_RADIO_PASTE: &str = "RADIO_PASTE";
 const COMPONENT_SPAN_STATUS_BAR_LOCAL: &str = "STATUS_BAR_LOCAL";
 const COMPONENT_SPAN_STATUS_BAR_REMOTE: &str = "STATUS_BAR_REMOTE";
 const COMPONENT_LIST_FILEINFO: &str = "LIST_FILEINFO";
+const COMPONENT_LIST_FAVORITES: &str = "LIST_FAVORITES";
+const COMPONENT_LIST_DRIFT: &str = "LIST_DRIFT";
+const COMPONENT_LIST_MANIFEST: &str = "LIST_MANIFEST";
+const COMPONENT_LIST_USAGE: &str = "LIST_USAGE";
+const COMPONENT_LIST_DRY_RUN: &str = "LIST_DRY_RUN";
+const COMPONENT_RULES_EDITOR: &str = "RULES_EDITOR";
+const COMPONENT_RADIO_OPTS_PRESERVE: &str = "RADIO_OPTS_PRESERVE";
+const COMPONENT_RADIO_OPTS_CONFLICT: &str = "RADIO_OPTS_CONFLICT";
+const COMPONENT_INPUT_OPTS_BANDWIDTH: &str = "INPUT_OPTS_BANDWIDTH";
+const COMPONENT_INPUT_OPTS_EXCLUDE: &str = "INPUT_OPTS_EXCLUDE";
+const COMPONENT_RADIO_OPTS_VERIFY: &str = "RADIO_OPTS_VERIFY";
 
 /// ## LogLevel
 ///
@@ -120,42 +175,165 @@ impl LogRecord {
     }
 }
 
+/// ## StallAction
+///
+/// What to do with an operation the remote stopped responding to
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum StallAction {
+    Wait,
+    Retry,
+    Abort,
+}
+
+/// ## DryRunOperation
+///
+/// The operation a dry run report has been made for
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum DryRunOperation {
+    Transfer,
+    Delete,
+}
+
+/// ## ConflictAction
+///
+/// What to do with a file which already exists at the destination of a transfer
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum ConflictAction {
+    Overwrite,
+    Skip,
+    Resume,
+    Rename,
+}
+
+impl ConflictAction {
+    /// ### from_choice
+    ///
+    /// Get the action for the option chosen in the existing file prompt
+    fn from_choice(choice: usize) -> Self {
+        match choice {
+            0 => ConflictAction::Overwrite,
+            1 => ConflictAction::Skip,
+            2 => ConflictAction::Resume,
+            _ => ConflictAction::Rename,
+        }
+    }
+
+    /// ### to_choice
+    ///
+    /// Get the option of the existing file prompt for the action
+    fn to_choice(self) -> usize {
+        match self {
+            ConflictAction::Overwrite => 0,
+            ConflictAction::Skip => 1,
+            ConflictAction::Resume => 2,
+            ConflictAction::Rename => 3,
+        }
+    }
+}
+
+/// ## SessionOptions
+///
+/// Transfer options of the current session. They're initialized from the configuration
+/// and can be changed from the options popup, without editing the configuration
+#[derive(Debug, Clone)]
+pub(crate) struct SessionOptions {
+    pub preserve_mode: bool,  // Apply the mode of remote entries to downloads
+    pub preserve_owner: bool, // Give downloads the owner of remote entries (as root)
+    pub conflict_policy: Option<ConflictAction>, // Answer for all the existing files; `None` asks
+    pub bandwidth: Option<u64>, // Bytes per second; `None` if unlimited
+    pub exclude: TransferFilter, // Entries excluded from transfers besides the transfer filters
+    pub verify: bool,         // Compare transferred files with their source
+}
+
+impl SessionOptions {
+    /// ### new
+    ///
+    /// Instantiates the session options from the configuration
+    pub fn new(config: &ConfigClient) -> Self {
+        Self {
+            preserve_mode: true,
+            preserve_owner: config.get_preserve_owner(),
+            conflict_policy: None,
+            bandwidth: None,
+            exclude: TransferFilter::default(),
+            verify: false,
+        }
+    }
+}
+
 /// ## FileTransferActivity
 ///
 /// FileTransferActivity is the data holder for the file transfer activity
 pub struct FileTransferActivity {
-    exit_reason: Option<ExitReason>,  // Exit reason
-    context: Option<Context>,         // Context holder
-    view: View,                       // View
-    host: Localhost,                  // Localhost
-    client: Box<dyn FileTransfer>,    // File transfer client
-    browser: Browser,                 // Browser
-    log_records: VecDeque<LogRecord>, // Log records
-    transfer: TransferStates,         // Transfer states
-    cache: Option<TempDir>,           // Temporary directory where to store stuff
+    exit_reason: Option<ExitReason>,           // Exit reason
+    context: Option<Context>,                  // Context holder
+    view: View,                                // View
+    modals: ModalStack,                        // Popups displayed over the explorers
+    host: Localhost,                           // Localhost
+    client: Box<dyn FileTransfer>,             // File transfer client
+    capabilities: Capabilities,                // Operations supported by the remote
+    browser: Browser,                          // Browser
+    log_records: VecDeque<LogRecord>,          // Log records
+    transfer: TransferStates,                  // Transfer states
+    cache: Option<TempDir>,                    // Temporary directory where to store stuff
+    history: InputHistory,                     // Input popups history
+    macros: MacroRecorder,                     // Keyboard macros
+    bookmarks: Option<BookmarksClient>,        // Bookmarks client (favorites, mappings, filters)
+    usage: Option<UsageClient>,                // Bandwidth usage statistics
+    filter: TransferFilter,                    // Include/exclude rules for recursive transfers
+    drift_report: Option<DriftReport>,         // Last verify report
+    pending_transfer: Option<PendingTransfer>, // Transfer waiting for manifest confirmation
+    peer: Option<Peer>,                        // Peer session for remote-to-remote transfers
+    peer_params: Option<FileTransferParams>,   // Peer address waiting for password
+    error_report: Option<ErrorReport>,         // Details of the error being displayed
+    error_details: bool,                       // Whether error details are being displayed
+    passphrase_key: Option<String>,            // Key whose passphrase is being prompted
+    passphrase_sent: bool,                     // Whether a passphrase has been tried for the key
+    key_passphrase: Option<String>, // Passphrase accepted for the key; reused by transfer workers
+    remote_locks: HashMap<String, FileLock>, // Locks in the remote working directory by file name
+    stall_action: Option<StallAction>, // Answer to the stalled operation prompt
+    prefetch: Option<Prefetch>,     // Prefetched remote directories; `None` if disabled
+    parallel_jobs: Option<Vec<Job>>, // Files queued for the transfer workers; `None` if sequential
+    conflict_action: Option<ConflictAction>, // Answer to the existing file prompt
+    conflict_policy: Option<ConflictAction>, // Answer for all the existing files of the current transfer
+    banner_acknowledged: bool, // Whether the banner sent before authentication has been read
+    remote_env: Option<RemoteEnv>, // Environment of the remote host, if detected
+    hex_view: Option<HexView>, // Binary file being viewed as hex dump
+    watch: Option<Watch>,      // Local directory whose changes are uploaded
+    journal: Option<JournalClient>, // Batch transfers in progress, to resume them after a restart
+    options: SessionOptions,   // Transfer options of this session
+    pasted: Vec<PathBuf>,      // Local paths pasted into the explorer, waiting for a choice
+    dry_run: Option<DryRunOperation>, // Operation whose dry run report is being displayed
+    trace_next: bool,          // Whether the next transfer must be traced
+    trace: Option<TransferTrace>, // Debug trace of the current transfer
 }
 
 impl FileTransferActivity {
     /// ### new
     ///
-    /// Instantiates a new FileTransferActivity
-    pub fn new(host: Localhost, protocol: FileTransferProtocol) -> FileTransferActivity {
+    /// Instantiates a new FileTransferActivity; fails if the client for `protocol` can't be built.
+    /// In safe mode, the default configuration is used and bookmarks, the bandwidth usage and the journal
+    /// of the batch transfers are not loaded
+    pub fn new(
+        host: Localhost,
+        protocol: FileTransferProtocol,
+        safe_mode: bool,
+    ) -> Result<FileTransferActivity, FileTransferError> {
         // Get config client
-        let config_client: ConfigClient = Self::init_config_client();
-        FileTransferActivity {
+        let config_client: ConfigClient = match safe_mode {
+            true => ConfigClient::degraded(),
+            false => Self::init_config_client(),
+        };
+        set_display_format(config_client.get_display_format());
+        let client: Box<dyn FileTransfer> = Self::make_client(protocol, &config_client)?;
+        Ok(FileTransferActivity {
             exit_reason: None,
             context: None,
             view: View::init(),
+            modals: ModalStack::new(COMPONENT_EXPLORER_LOCAL),
             host,
-            client: match protocol {
-                FileTransferProtocol::Sftp => Box::new(SftpFileTransfer::new(
-                    Self::make_ssh_storage(&config_client),
-                )),
-                FileTransferProtocol::Ftp(ftps) => Box::new(FtpFileTransfer::new(ftps)),
-                FileTransferProtocol::Scp => {
-                    Box::new(ScpFileTransfer::new(Self::make_ssh_storage(&config_client)))
-                }
-            },
+            client,
+            capabilities: Capabilities::default(),
             browser: Browser::new(&config_client),
             log_records: VecDeque::with_capacity(256), // 256 events is enough I guess
             transfer: TransferStates::default(),
@@ -163,7 +341,53 @@ impl FileTransferActivity {
                 Ok(d) => Some(d),
                 Err(_) => None,
             },
-        }
+            history: InputHistory::new(32),
+            macros: MacroRecorder::default(),
+            bookmarks: match safe_mode {
+                true => None,
+                false => Self::init_bookmarks_client(config_client.get_recents_size()),
+            },
+            usage: match safe_mode {
+                true => None,
+                false => Self::init_usage_client(),
+            },
+            filter: TransferFilter::default(),
+            drift_report: None,
+            pending_transfer: None,
+            peer: None,
+            peer_params: None,
+            error_report: None,
+            error_details: false,
+            passphrase_key: None,
+            passphrase_sent: false,
+            key_passphrase: None,
+            remote_locks: HashMap::new(),
+            stall_action: None,
+            prefetch: match config_client.get_prefetch_dirs() {
+                true => Some(Prefetch::new(
+                    PREFETCH_CAPACITY,
+                    Duration::from_secs(PREFETCH_TTL),
+                    Duration::from_millis(PREFETCH_DELAY),
+                )),
+                false => None,
+            },
+            parallel_jobs: None,
+            conflict_action: None,
+            conflict_policy: None,
+            banner_acknowledged: false,
+            remote_env: None,
+            hex_view: None,
+            watch: None,
+            journal: match safe_mode {
+                true => None,
+                false => Self::init_journal_client(),
+            },
+            options: SessionOptions::new(&config_client),
+            pasted: Vec::new(),
+            dry_run: None,
+            trace_next: false,
+            trace: None,
+        })
     }
 
     fn local(&self) -> &FileExplorer {
@@ -231,6 +455,99 @@ impl FileTransferActivity {
         self.context().config()
     }
 
+    /// ### low_bandwidth
+    ///
+    /// Returns whether low bandwidth mode is enabled
+    fn low_bandwidth(&self) -> bool {
+        self.context
+            .as_ref()
+            .map(|x| x.config().get_low_bandwidth())
+            .unwrap_or(false)
+    }
+
+    /// ### slow_link
+    ///
+    /// Returns the network conditions to simulate on transfer streams, capped to the session bandwidth
+    fn slow_link(&self) -> SlowLink {
+        let mut link: SlowLink = self
+            .context
+            .as_ref()
+            .map(|x| x.slow_link())
+            .unwrap_or_default();
+        // Apply the bandwidth cap of the session
+        if let Some(cap) = self.options.bandwidth {
+            link.bandwidth = Some(link.bandwidth.map(|x| x.min(cap)).unwrap_or(cap));
+        }
+        link
+    }
+
+    /// ### delta_transfer
+    ///
+    /// Returns whether existing remote files should be updated sending only the blocks which changed
+    fn delta_transfer(&self) -> bool {
+        self.context
+            .as_ref()
+            .map(|x| x.config().get_delta_transfer())
+            .unwrap_or(false)
+    }
+
+    /// ### skip_unchanged
+    ///
+    /// Returns whether files with the same size and modification time as the destination should be skipped
+    fn skip_unchanged(&self) -> bool {
+        self.context
+            .as_ref()
+            .map(|x| x.config().get_skip_unchanged())
+            .unwrap_or(false)
+    }
+
+    /// ### symlink_mode
+    ///
+    /// Returns what recursive transfers do with symbolic links
+    fn symlink_mode(&self) -> SymlinkMode {
+        self.context
+            .as_ref()
+            .map(|x| x.config().get_symlinks())
+            .unwrap_or(SymlinkMode::Follow)
+    }
+
+    /// ### preserve_owner
+    ///
+    /// Returns whether downloaded entries should get the uid and gid of the remote ones
+    fn preserve_owner(&self) -> bool {
+        self.options.preserve_owner
+    }
+
+    /// ### parallel_transfers
+    ///
+    /// Returns the amount of files to transfer at once; 1 means files are transferred one by one
+    fn parallel_transfers(&self) -> usize {
+        self.context
+            .as_ref()
+            .map(|x| x.config().get_parallel_transfers())
+            .unwrap_or(1)
+    }
+
+    /// ### transfer_retries
+    ///
+    /// Returns the amount of times a file transfer failed because of a transient error is retried
+    fn transfer_retries(&self) -> usize {
+        self.context
+            .as_ref()
+            .map(|x| x.config().get_transfer_retries())
+            .unwrap_or(0)
+    }
+
+    /// ### show_banner
+    ///
+    /// Returns whether the server welcome message should be displayed after connecting
+    fn show_banner(&self) -> bool {
+        self.context
+            .as_ref()
+            .map(|x| x.config().get_show_banner())
+            .unwrap_or(true)
+    }
+
     /// ### theme
     ///
     /// Get a reference to `Theme`
@@ -260,6 +577,8 @@ impl Activity for FileTransferActivity {
         if let Err(err) = enable_raw_mode() {
             error!("Failed to enter raw mode: {}", err);
         }
+        // Unlock bookmarks
+        self.unlock_bookmarks_client();
         // Get files at current pwd
         self.reload_local_dir();
         debug!("Read working directory");
@@ -289,13 +608,28 @@ impl Activity for FileTransferActivity {
             return;
         }
         // Check if connected (popup must be None, otherwise would try reconnecting in loop in case of error)
-        if !self.client.is_connected() && self.view.get_props(COMPONENT_TEXT_FATAL).is_none() {
+        if !self.client.is_connected()
+            && self.view.get_props(COMPONENT_TEXT_FATAL).is_none()
+            && self
+                .view
+                .get_props(COMPONENT_INPUT_KEY_PASSPHRASE)
+                .is_none()
+            && self.view.get_props(COMPONENT_TEXT_AUTH_BANNER).is_none()
+        {
             let params = self.context().ft_params().unwrap();
             info!(
                 "Client is not connected to remote; connecting to {}:{}",
                 params.address, params.port
             );
-            let msg: String = format!("Connecting to {}:{}…", params.address, params.port);
+            let msg: String = match self.security_key_for(params) {
+                Some(key) => format!(
+                    "Connecting to {}:{}… Touch your security key ({}) to authenticate",
+                    params.address,
+                    params.port,
+                    key.display()
+                ),
+                None => format!("Connecting to {}:{}…", params.address, params.port),
+            };
             // Set init state to connecting popup
             self.mount_wait(msg.as_str());
             // Force ui draw
@@ -306,7 +640,15 @@ impl Activity for FileTransferActivity {
             redraw = true;
         }
         // Handle input events (if false, becomes true; otherwise remains true)
-        redraw |= self.read_input_event();
+        let event: bool = self.read_input_event();
+        // Read ahead the highlighted remote directory while idle
+        if !event {
+            self.action_prefetch_remote_dir();
+            redraw |= self.action_watch_local_dir();
+        }
+        redraw |= event;
+        // Repaint after resize or reattach
+        redraw |= self.context_mut().refresh_terminal_size();
         // @! draw interface
         if redraw {
             self.view();
@@ -341,6 +683,10 @@ impl Activity for FileTransferActivity {
         if self.client.is_connected() {
             let _ = self.client.disconnect();
         }
+        // Disconnect peer
+        if let Some(mut peer) = self.peer.take() {
+            let _ = peer.client.disconnect();
+        }
         // Clear terminal and return
         match self.context.take() {
             Some(mut ctx) => {
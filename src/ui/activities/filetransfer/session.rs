@@ -26,18 +26,42 @@
  * SOFTWARE.
  */
 // Locals
-use super::{FileTransferActivity, LogLevel};
-use crate::filetransfer::{FileTransferError, FileTransferErrorType};
+use super::lib::manifest::TransferManifest;
+use super::lib::parallel::{Connector, Job, WorkerEvent, WorkerPool};
+use super::lib::webhook::{self, Notification};
+use super::{ConflictAction, FileTransferActivity, LogLevel, StallAction};
+use crate::filetransfer::delta;
+use crate::filetransfer::{
+    Capability, Fetch, FileTransfer, FileTransferError, FileTransferErrorType, FileTransferParams,
+    Signature, SymlinkMode, TlsOptions,
+};
 use crate::fs::{FsEntry, FsFile};
-use crate::host::HostError;
-use crate::utils::fmt::fmt_millis;
+use crate::host::{HostError, HostErrorType};
+use crate::system::metrics::Outcome;
+use crate::utils::fmt::{fmt_banner, fmt_millis, fmt_size};
+use crate::utils::path::duplicate_name;
+use crate::utils::random::random_alphanumeric_with_len;
 
 // Ext
-use bytesize::ByteSize;
+use std::collections::HashMap;
 use std::io::{Read, Seek, Write};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant, SystemTime};
 use thiserror::Error;
+#[cfg(target_family = "unix")]
+use users::get_effective_uid;
+
+// Progress redraw and input polling intervals
+const INPUT_POLL_INTERVAL: u128 = 500; // ms
+const LOW_BANDWIDTH_INPUT_POLL_INTERVAL: u128 = 2000; // ms
+const LOW_BANDWIDTH_REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+// Segmented downloads
+const SEGMENTED_DOWNLOAD_MIN_SIZE: usize = 32 * 1024 * 1024; // Smaller files are downloaded at once
+const SEGMENTED_DOWNLOAD_MIN_SEGMENT_SIZE: usize = 8 * 1024 * 1024;
+// Transfer retries
+const TRANSFER_RETRY_BACKOFF: Duration = Duration::from_secs(1); // Doubled after each attempt
+const TRANSFER_RETRY_MAX_BACKOFF: Duration = Duration::from_secs(30);
 
 /// ## TransferErrorReason
 ///
@@ -47,15 +71,33 @@ enum TransferErrorReason {
     #[error("File transfer aborted")]
     Abrupted,
     #[error("Failed to seek file: {0}")]
-    CouldNotRewind(std::io::Error),
+    CouldNotRewind(#[source] std::io::Error),
     #[error("I/O error on localhost: {0}")]
-    LocalIoError(std::io::Error),
+    LocalIoError(#[source] std::io::Error),
     #[error("Host error: {0}")]
-    HostError(HostError),
+    HostError(#[source] HostError),
     #[error("I/O error on remote: {0}")]
-    RemoteIoError(std::io::Error),
+    RemoteIoError(#[source] std::io::Error),
     #[error("File transfer error: {0}")]
-    FileTransferError(FileTransferError),
+    FileTransferError(#[source] FileTransferError),
+    #[error("Not connected to peer")]
+    PeerNotConnected,
+}
+
+impl TransferErrorReason {
+    /// ### is_transient
+    ///
+    /// Returns whether the error may not occur again if the transfer is retried
+    fn is_transient(&self) -> bool {
+        match self {
+            TransferErrorReason::RemoteIoError(_) => true,
+            TransferErrorReason::FileTransferError(err) => matches!(
+                err.kind(),
+                FileTransferErrorType::Timeout | FileTransferErrorType::ConnectionError
+            ),
+            _ => false,
+        }
+    }
 }
 
 /// ## TransferPayload
@@ -65,12 +107,66 @@ enum TransferErrorReason {
 /// - Any: Can be any kind of `FsEntry`, but just one
 /// - Many: a list of `FsEntry`
 #[derive(Debug)]
-pub(super) enum TransferPayload {
+pub(crate) enum TransferPayload {
     File(FsFile),
     Any(FsEntry),
     Many(Vec<FsEntry>),
 }
 
+impl TransferPayload {
+    /// ### paths
+    ///
+    /// Get the paths of the entries to transfer
+    pub(super) fn paths(&self) -> Vec<PathBuf> {
+        match self {
+            TransferPayload::File(file) => vec![file.abs_path.clone()],
+            TransferPayload::Any(entry) => vec![entry.get_abs_path()],
+            TransferPayload::Many(entries) => entries.iter().map(|x| x.get_abs_path()).collect(),
+        }
+    }
+
+    /// ### entries
+    ///
+    /// Get the entries to transfer
+    pub(super) fn entries(&self) -> Vec<FsEntry> {
+        match self {
+            TransferPayload::File(file) => vec![FsEntry::File(file.clone())],
+            TransferPayload::Any(entry) => vec![entry.clone()],
+            TransferPayload::Many(entries) => entries.clone(),
+        }
+    }
+}
+
+/// ## TransferDirection
+///
+/// Describes whether entries are sent to or received from remote
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TransferDirection {
+    Upload,
+    Download,
+}
+
+/// ## PendingTransfer
+///
+/// A transfer waiting for the user to confirm its manifest
+pub(super) struct PendingTransfer {
+    pub direction: TransferDirection,
+    pub payload: TransferPayload,
+    pub dest: PathBuf,
+    pub dst_name: Option<String>,
+    pub manifest: TransferManifest,
+    pub remove_source: bool,
+}
+
+/// ## Peer
+///
+/// A second remote session; files on remote can be streamed to it through termscp
+pub(super) struct Peer {
+    pub client: Box<dyn FileTransfer>,
+    pub params: FileTransferParams,
+    pub wrkdir: PathBuf,
+}
+
 impl FileTransferActivity {
     /// ### connect
     ///
@@ -79,6 +175,25 @@ impl FileTransferActivity {
         let params = self.context().ft_params().unwrap().clone();
         let addr: String = params.address.clone();
         let entry_dir: Option<PathBuf> = params.entry_directory.clone();
+        let local_entry_dir: Option<PathBuf> = self.local_directory_for(&params);
+        // Setup proxy, tls, agent forwarding and compression
+        let tls: Option<TlsOptions> = self.tls_for(&params);
+        let agent_forwarding: bool = self.agent_forwarding_for(&params);
+        self.client.set_compression(self.compression_for(&params));
+        if let Err(err) = self
+            .proxy_for(&params)
+            .and_then(|proxy| self.client.set_proxy(proxy).map_err(|e| e.to_string()))
+            .and_then(|_| self.client.set_tls(tls).map_err(|e| e.to_string()))
+            .and_then(|_| {
+                self.client
+                    .set_agent_forwarding(agent_forwarding)
+                    .map_err(|e| e.to_string())
+            })
+        {
+            self.umount_wait();
+            self.mount_fatal(format!("Could not connect to '{}': {}", addr, err).as_str());
+            return;
+        }
         // Connect to remote
         match self.client.connect(
             params.address,
@@ -87,13 +202,16 @@ impl FileTransferActivity {
             params.password,
         ) {
             Ok(welcome) => {
-                if let Some(banner) = welcome {
-                    // Log welcome
-                    self.log(
-                        LogLevel::Info,
-                        format!("Established connection with '{}': \"{}\"", addr, banner),
-                    );
-                }
+                // Log welcome
+                self.log(
+                    LogLevel::Info,
+                    match welcome.as_deref().and_then(|x| x.lines().next()) {
+                        Some(banner) => {
+                            format!("Established connection with '{}': \"{}\"", addr, banner)
+                        }
+                        None => format!("Established connection with '{}'", addr),
+                    },
+                );
                 // Try to change directory to entry directory
                 let mut remote_chdir: Option<PathBuf> = None;
                 if let Some(entry_directory) = &entry_dir {
@@ -102,19 +220,109 @@ impl FileTransferActivity {
                 if let Some(entry_directory) = remote_chdir {
                     self.remote_changedir(entry_directory.as_path(), false);
                 }
+                self.probe_capabilities();
+                self.probe_remote_env();
+                // Change local directory to the one saved in bookmark
+                if let Some(local_entry_directory) = local_entry_dir {
+                    self.local_changedir(local_entry_directory.as_path(), false);
+                }
                 // Set state to explorer
                 self.umount_wait();
                 self.reload_remote_dir();
+                // Setup root mapping and transfer filters
+                self.init_root_mapping();
+                self.init_transfer_filter();
                 // Update file lists
                 self.update_local_filelist();
                 self.update_remote_filelist();
+                // Show welcome message
+                if let Some(banner) = welcome {
+                    if self.show_banner() && !fmt_banner(banner.as_str()).is_empty() {
+                        self.mount_banner(addr.as_str(), banner.as_str());
+                    }
+                }
+                // Offer to resume a batch transfer interrupted by the last exit
+                self.check_journal();
+            }
+            Err(err) if err.kind() == FileTransferErrorType::KeyPassphraseRequired => {
+                self.umount_wait();
+                let key: String = err.msg().unwrap_or_default().to_string();
+                self.on_key_passphrase_required(key);
+            }
+            Err(err) if err.kind() == FileTransferErrorType::BannerAcknowledgementRequired => {
+                // Show the banner; authentication continues once it's acknowledged
+                self.umount_wait();
+                self.mount_auth_banner(addr.as_str(), err.msg().unwrap_or_default());
             }
             Err(err) => {
                 // Set popup fatal error
                 self.umount_wait();
-                self.mount_fatal(&err.to_string());
+                let msg: String = self.mask_password(err.to_string());
+                self.mount_fatal(msg.as_str());
+            }
+        }
+    }
+
+    /// ### on_key_passphrase_required
+    ///
+    /// Handle a connection refused because of a protected key:
+    /// the passphrase cached for this session is tried first, otherwise the user is prompted for it
+    fn on_key_passphrase_required(&mut self, key: String) {
+        let storage_key: String = format!("{}{}", super::STORAGE_KEY_PASSPHRASE_PREFIX, key);
+        let cached: Option<String> = self
+            .context()
+            .store()
+            .get_string(storage_key.as_str())
+            .map(|x| x.to_string());
+        match cached {
+            Some(passphrase) if !self.passphrase_sent => {
+                debug!("Using cached passphrase for key {}", key);
+                self.client.set_key_passphrase(Some(passphrase.clone()));
+                self.key_passphrase = Some(passphrase);
+                self.passphrase_sent = true;
+            }
+            _ => {
+                if self.passphrase_sent {
+                    // Wrong passphrase; don't try it again
+                    self.log(
+                        LogLevel::Warn,
+                        format!("Wrong passphrase for key \"{}\"", key),
+                    );
+                    self.context_mut().store_mut().unset(storage_key.as_str());
+                }
+                self.mount_key_passphrase(key.as_str(), self.passphrase_sent);
+                self.passphrase_key = Some(key);
+            }
+        }
+    }
+
+    /// ### submit_key_passphrase
+    ///
+    /// Use the passphrase typed by the user at the next connection attempt, optionally caching it for this session
+    pub(super) fn submit_key_passphrase(&mut self, passphrase: String, remember: bool) {
+        self.umount_key_passphrase();
+        if let Some(key) = self.passphrase_key.take() {
+            let storage_key: String = format!("{}{}", super::STORAGE_KEY_PASSPHRASE_PREFIX, key);
+            if remember {
+                self.context_mut()
+                    .store_mut()
+                    .set_string(storage_key.as_str(), passphrase.clone());
+            } else {
+                self.context_mut().store_mut().unset(storage_key.as_str());
             }
         }
+        self.client.set_key_passphrase(Some(passphrase.clone()));
+        self.key_passphrase = Some(passphrase);
+        self.passphrase_sent = true;
+    }
+
+    /// ### acknowledge_auth_banner
+    ///
+    /// Let the next connection attempt authenticate past the banner sent by the server
+    pub(super) fn acknowledge_auth_banner(&mut self) {
+        self.umount_auth_banner();
+        self.client.set_banner_acknowledged(true);
+        self.banner_acknowledged = true;
     }
 
     /// ### disconnect
@@ -143,9 +351,30 @@ impl FileTransferActivity {
     ///
     /// Reload remote directory entries and update browser
     pub(super) fn reload_remote_dir(&mut self) {
+        // The remote may have changed: prefetched listings could be stale
+        if let Some(prefetch) = self.prefetch.as_mut() {
+            prefetch.clear();
+        }
+        self.remote_load_wrkdir();
+    }
+
+    /// ### remote_load_wrkdir
+    ///
+    /// Load remote working directory entries into the browser, using the prefetched listing, if any
+    fn remote_load_wrkdir(&mut self) {
         // Get current entries
         if let Ok(wrkdir) = self.client.pwd() {
-            self.remote_scan(wrkdir.as_path());
+            match self
+                .prefetch
+                .as_mut()
+                .and_then(|x| x.take(wrkdir.as_path()))
+            {
+                Some(files) => {
+                    self.remote_mut().set_files(files);
+                    self.refresh_remote_locks();
+                }
+                None => self.remote_scan(wrkdir.as_path()),
+            }
             // Set wrkdir
             self.remote_mut().wrkdir = wrkdir;
         }
@@ -170,10 +399,7 @@ impl FileTransferActivity {
                 self.local_mut().set_files(files);
             }
             Err(err) => {
-                self.log_and_alert(
-                    LogLevel::Error,
-                    format!("Could not scan current directory: {}", err),
-                );
+                self.log_and_alert_error(LogLevel::Error, "Could not scan current directory", &err);
             }
         }
     }
@@ -182,16 +408,248 @@ impl FileTransferActivity {
     ///
     /// Scan current remote directory
     fn remote_scan(&mut self, path: &Path) {
-        match self.client.list_dir(path) {
+        match self.remote_list_dir(path) {
             Ok(files) => {
                 // Set files and sort (sorting is implicit)
                 self.remote_mut().set_files(files);
+                // Read locks on files
+                self.refresh_remote_locks();
             }
             Err(err) => {
-                self.log_and_alert(
-                    LogLevel::Error,
-                    format!("Could not scan current directory: {}", err),
+                self.log_and_alert_error(LogLevel::Error, "Could not scan current directory", &err);
+            }
+        }
+    }
+
+    /// ### remote_list_dir
+    ///
+    /// List `dir` on remote.
+    /// If the remote stops responding, the user is asked whether to list it again or give up
+    pub(super) fn remote_list_dir(
+        &mut self,
+        dir: &Path,
+    ) -> Result<Vec<FsEntry>, FileTransferError> {
+        loop {
+            match self.client.list_dir(dir) {
+                Err(err) if err.is_timeout() => {
+                    let operation: String = format!("listing \"{}\"", dir.display());
+                    // A failed listing can't be resumed: waiting means listing again too
+                    if self.prompt_stalled(operation.as_str()) == StallAction::Abort {
+                        return Err(err);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// ### prompt_stalled
+    ///
+    /// Ask the user what to do with an operation the remote stopped responding to.
+    /// Blocks until an option is chosen
+    fn prompt_stalled(&mut self, operation: &str) -> StallAction {
+        self.log(
+            LogLevel::Warn,
+            format!("The remote stopped responding while {}", operation),
+        );
+        self.stall_action = None;
+        self.mount_radio_stalled(operation);
+        self.view();
+        while self.stall_action.is_none() {
+            if self.read_input_event() {
+                self.view();
+            }
+        }
+        self.stall_action.take().unwrap_or(StallAction::Abort)
+    }
+
+    /// ### wait_retry
+    ///
+    /// Wait before the `attempt`-th retry of the transfer of `name`, which failed with `err`;
+    /// the wait doubles after each attempt. Input events are handled meanwhile.
+    /// Returns whether the transfer should be retried, which is false if it has been aborted
+    fn wait_retry(&mut self, name: &str, err: String, attempt: usize, retries: usize) -> bool {
+        let delay: Duration = (TRANSFER_RETRY_BACKOFF * 2u32.pow((attempt - 1).min(5) as u32))
+            .min(TRANSFER_RETRY_MAX_BACKOFF);
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "Transfer of \"{}\" failed: {}; retrying in {} seconds ({} of {})",
+                name,
+                err,
+                delay.as_secs(),
+                attempt,
+                retries
+            ),
+        );
+        // The failed attempt will be transferred again
+        self.transfer.full.rewind(self.transfer.partial.written());
+        self.view();
+        let started: Instant = Instant::now();
+        while started.elapsed() < delay && !self.transfer.aborted() {
+            if self.read_input_event() {
+                self.view();
+            }
+        }
+        !self.transfer.aborted()
+    }
+
+    /// ### wait_resumed
+    ///
+    /// If the transfer has been paused, handle input events until it's resumed or aborted.
+    /// Streams are kept open meanwhile, without reading or writing them
+    fn wait_resumed(&mut self) {
+        if !self.transfer.paused() {
+            return;
+        }
+        self.view();
+        while self.transfer.paused() && !self.transfer.aborted() {
+            if self.read_input_event() {
+                self.view();
+            }
+        }
+    }
+
+    /// ### prompt_conflict
+    ///
+    /// Ask the user what to do with `dest`, which already exists at the destination of the transfer,
+    /// unless an answer has already been given for all the files of the transfer.
+    /// Blocks until an option is chosen
+    fn prompt_conflict(
+        &mut self,
+        dest: &Path,
+        existing_size: usize,
+        size: usize,
+    ) -> ConflictAction {
+        if let Some(action) = self.conflict_policy {
+            return action;
+        }
+        self.conflict_action = None;
+        self.mount_radio_conflict(dest, existing_size, size);
+        self.view();
+        while self.conflict_action.is_none() {
+            if self.read_input_event() {
+                self.view();
+            }
+        }
+        self.conflict_action.take().unwrap_or(ConflictAction::Skip)
+    }
+
+    /// ### is_unchanged
+    ///
+    /// Returns whether `existing` is up to date with a source of `size` bytes modified at `mtime`:
+    /// same size, and modified at the same time or later. Some protocols don't preserve the
+    /// modification time, so a destination newer than the source counts as unchanged as well
+    pub(super) fn is_unchanged(size: usize, mtime: SystemTime, existing: &FsFile) -> bool {
+        size == existing.size && existing.last_change_time + Duration::from_secs(1) >= mtime
+    }
+
+    /// ### preserve_local_owner
+    ///
+    /// Give the downloaded entry at `local` the uid and gid of the remote entry, if preserving owners is enabled.
+    /// Only root can give files away, so nothing is done otherwise
+    #[cfg(target_family = "unix")]
+    fn preserve_local_owner(&mut self, local: &Path, user: Option<u32>, group: Option<u32>) {
+        if !self.preserve_owner() || get_effective_uid() != 0 {
+            return;
+        }
+        let owner: String = match (user, group) {
+            (Some(user), Some(group)) => format!("{}:{}", user, group),
+            (Some(user), None) => user.to_string(),
+            (None, Some(group)) => format!(":{}", group),
+            (None, None) => return,
+        };
+        if let Err(err) = self.host.chown(local, owner.as_str()) {
+            self.log(
+                LogLevel::Error,
+                format!(
+                    "Could not change owner of \"{}\" to {}: {}",
+                    local.display(),
+                    owner,
+                    err
+                ),
+            );
+        }
+    }
+
+    /// ### remote_destination
+    ///
+    /// Get where to upload `file`, given that `remote` is where it would be written.
+    /// If `remote` already exists, the user is asked what to do.
+    /// Returns the path to write, and whether the transfer should resume the existing file;
+    /// `None` if the file must be skipped
+    fn remote_destination(&mut self, file: &FsFile, remote: &Path) -> Option<(PathBuf, bool)> {
+        let existing: FsFile = match self.client.stat(remote) {
+            Ok(FsEntry::File(existing)) => existing,
+            _ => return Some((remote.to_path_buf(), false)),
+        };
+        if self.skip_unchanged() && Self::is_unchanged(file.size, file.last_change_time, &existing)
+        {
+            self.log(
+                LogLevel::Info,
+                format!("Skipped \"{}\": unchanged", remote.display()),
+            );
+            return None;
+        }
+        match self.prompt_conflict(remote, existing.size, file.size) {
+            ConflictAction::Overwrite => Some((remote.to_path_buf(), false)),
+            ConflictAction::Resume => Some((remote.to_path_buf(), true)),
+            ConflictAction::Rename => {
+                let parent: PathBuf = remote.parent().map(PathBuf::from).unwrap_or_default();
+                let names: Vec<String> = self
+                    .client
+                    .list_dir(parent.as_path())
+                    .map(|x| x.iter().map(|x| x.get_name().to_string()).collect())
+                    .unwrap_or_default();
+                let name: String =
+                    duplicate_name(file.name.as_str(), |x| names.iter().any(|n| n == x));
+                Some((parent.join(name), false))
+            }
+            ConflictAction::Skip => {
+                self.log(
+                    LogLevel::Info,
+                    format!("Skipped \"{}\": already exists", remote.display()),
+                );
+                None
+            }
+        }
+    }
+
+    /// ### local_destination
+    ///
+    /// Get where to download `file`, given that `local` is where it would be written.
+    /// If `local` already exists, the user is asked what to do.
+    /// Returns the path to write, and whether the transfer should resume the existing file;
+    /// `None` if the file must be skipped
+    fn local_destination(&mut self, file: &FsFile, local: &Path) -> Option<(PathBuf, bool)> {
+        let existing: FsFile = match self.host.stat(local) {
+            Ok(FsEntry::File(existing)) => existing,
+            _ => return Some((local.to_path_buf(), false)),
+        };
+        if self.skip_unchanged() && Self::is_unchanged(file.size, file.last_change_time, &existing)
+        {
+            self.log(
+                LogLevel::Info,
+                format!("Skipped \"{}\": unchanged", local.display()),
+            );
+            return None;
+        }
+        match self.prompt_conflict(local, existing.size, file.size) {
+            ConflictAction::Overwrite => Some((local.to_path_buf(), false)),
+            ConflictAction::Resume => Some((local.to_path_buf(), true)),
+            ConflictAction::Rename => {
+                let parent: PathBuf = local.parent().map(PathBuf::from).unwrap_or_default();
+                let name: String = duplicate_name(file.name.as_str(), |x| {
+                    self.host.file_exists(parent.join(x).as_path())
+                });
+                Some((parent.join(name), false))
+            }
+            ConflictAction::Skip => {
+                self.log(
+                    LogLevel::Info,
+                    format!("Skipped \"{}\": already exists", local.display()),
                 );
+                None
             }
         }
     }
@@ -207,8 +665,21 @@ impl FileTransferActivity {
         curr_remote_path: &Path,
         dst_name: Option<String>,
     ) -> Result<(), String> {
+        // Answers to the existing file prompt apply to this transfer only; start from the session default
+        self.conflict_policy = self.options.conflict_policy;
+        // Fail fast if a batch can't be written into the destination
+        let is_batch: bool = match &payload {
+            TransferPayload::Any(entry) => entry.is_dir(),
+            TransferPayload::File(_) => false,
+            TransferPayload::Many(_) => true,
+        };
+        if is_batch {
+            self.check_remote_writable(curr_remote_path)?;
+            self.journal_record(true, payload.paths(), curr_remote_path, dst_name.clone());
+        }
+        self.start_trace(format!("upload to \"{}\"", curr_remote_path.display()));
         // Use different method based on payload
-        match payload {
+        let result = match payload {
             TransferPayload::Any(entry) => {
                 self.filetransfer_send_any(&entry, curr_remote_path, dst_name)
             }
@@ -218,6 +689,39 @@ impl FileTransferActivity {
             TransferPayload::Many(entries) => {
                 self.filetransfer_send_many(entries, curr_remote_path)
             }
+        };
+        self.account_usage(self.transfer.full.written(), 0);
+        self.update_metrics(true, result.is_err());
+        if is_batch {
+            self.journal_clear();
+            self.notify_webhook(true, curr_remote_path);
+        }
+        self.finish_trace(&result);
+        result
+    }
+
+    /// ### check_remote_writable
+    ///
+    /// Check whether entries can be created into the remote directory `dir`.
+    /// Since the transfer may create `dir`, its closest existing ancestor is checked if it doesn't exist
+    fn check_remote_writable(&mut self, dir: &Path) -> Result<(), String> {
+        let mut target: &Path = dir;
+        while self.client.stat(target).is_err() {
+            match target.parent() {
+                Some(parent) => target = parent,
+                None => break,
+            }
+        }
+        match self.client.check_writable(target) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                error!("{} is not writable: {}", target.display(), err);
+                Err(format!(
+                    "\"{}\" is not writable ({}); nothing has been transferred",
+                    target.display(),
+                    err
+                ))
+            }
         }
     }
 
@@ -235,6 +739,7 @@ impl FileTransferActivity {
         // Calculate total size of transfer
         let total_transfer_size: usize = file.size;
         self.transfer.full.init(total_transfer_size);
+        self.transfer.files.init(1);
         // Mount progress bar
         self.mount_progress_bar(format!("Uploading {}…", file.abs_path.display()));
         // Get remote path
@@ -246,7 +751,7 @@ impl FileTransferActivity {
         };
         remote_path.push(remote_file_name);
         // Send
-        let result = self.filetransfer_send_one(file, remote_path.as_path(), file_name);
+        let result = self.filetransfer_send_retry(file, remote_path.as_path(), false);
         // Umount progress bar
         self.umount_progress_bar();
         // Return result
@@ -265,14 +770,19 @@ impl FileTransferActivity {
         // Reset states
         self.transfer.reset();
         // Calculate total size of transfer
-        let total_transfer_size: usize = self.get_total_transfer_size_local(entry);
+        let (total_transfer_size, total_files): (usize, usize) =
+            self.get_total_transfer_size_local(entry);
         self.transfer.full.init(total_transfer_size);
+        self.transfer.files.init(total_files);
         // Mount progress bar
         self.mount_progress_bar(format!("Uploading {}…", entry.get_abs_path().display()));
         // Send recurse
+        self.begin_parallel_jobs();
         self.filetransfer_send_recurse(entry, curr_remote_path, dst_name);
+        self.run_parallel_jobs();
         // Umount progress bar
         self.umount_progress_bar();
+        self.report_denied_entries();
         Ok(())
     }
 
@@ -287,19 +797,23 @@ impl FileTransferActivity {
         // Reset states
         self.transfer.reset();
         // Calculate total size of transfer
-        let total_transfer_size: usize = entries
+        let (total_transfer_size, total_files): (usize, usize) = entries
             .iter()
             .map(|x| self.get_total_transfer_size_local(x))
-            .sum();
+            .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1));
         self.transfer.full.init(total_transfer_size);
+        self.transfer.files.init(total_files);
         // Mount progress bar
         self.mount_progress_bar(format!("Uploading {} entries…", entries.len()));
         // Send recurse
+        self.begin_parallel_jobs();
         entries
             .iter()
             .for_each(|x| self.filetransfer_send_recurse(x, curr_remote_path, None));
+        self.run_parallel_jobs();
         // Umount progress bar
         self.umount_progress_bar();
+        self.report_denied_entries();
         Ok(())
     }
 
@@ -309,6 +823,11 @@ impl FileTransferActivity {
         curr_remote_path: &Path,
         dst_name: Option<String>,
     ) {
+        // Skip entries deselected from the manifest
+        if self.transfer.is_excluded(entry.get_abs_path().as_path()) {
+            debug!("Skipping {}: excluded from manifest", entry.get_name());
+            return;
+        }
         // Write popup
         let file_name: String = match entry {
             FsEntry::Directory(dir) => dir.name.clone(),
@@ -321,47 +840,50 @@ impl FileTransferActivity {
             None => PathBuf::from(file_name.as_str()),
         };
         remote_path.push(remote_file_name);
+        // Skip or recreate symbolic links, unless they're followed
+        if entry.is_symlink() {
+            match self.symlink_mode() {
+                SymlinkMode::Skip => {
+                    self.log(
+                        LogLevel::Info,
+                        format!(
+                            "Skipped symbolic link \"{}\"",
+                            entry.get_abs_path().display()
+                        ),
+                    );
+                    return;
+                }
+                SymlinkMode::Recreate => {
+                    self.filetransfer_send_symlink(entry, remote_path.as_path());
+                    return;
+                }
+                SymlinkMode::Follow => {}
+            }
+        }
         // Match entry
         match entry {
-            FsEntry::File(file) => {
-                if let Err(err) = self.filetransfer_send_one(file, remote_path.as_path(), file_name)
-                {
-                    // Log error
-                    self.log_and_alert(
-                        LogLevel::Error,
-                        format!("Failed to upload file {}: {}", file.name, err),
-                    );
-                    // If transfer was abrupted or there was an IO error on remote, remove file
-                    if matches!(
-                        err,
-                        TransferErrorReason::Abrupted | TransferErrorReason::RemoteIoError(_)
-                    ) {
-                        // Stat file on remote and remove it if exists
-                        match self.client.stat(remote_path.as_path()) {
-                            Err(err) => self.log(
-                                LogLevel::Error,
-                                format!(
-                                    "Could not remove created file {}: {}",
-                                    remote_path.display(),
-                                    err
-                                ),
-                            ),
-                            Ok(entry) => {
-                                if let Err(err) = self.client.remove(&entry) {
-                                    self.log(
-                                        LogLevel::Error,
-                                        format!(
-                                            "Could not remove created file {}: {}",
-                                            remote_path.display(),
-                                            err
-                                        ),
-                                    );
-                                }
-                            }
-                        }
+            FsEntry::File(file) => match self.remote_destination(file, remote_path.as_path()) {
+                // Skipped
+                None => {}
+                // Resumed files are sent by the main connection
+                Some((remote, true)) => {
+                    self.filetransfer_send_or_clean(file, remote.as_path(), true)
+                }
+                Some((remote, false)) => {
+                    // Workers send whole files, so existing files are updated by delta using the main connection
+                    let delta: bool = self.parallel_jobs.is_some()
+                        && self.delta_transfer()
+                        && self.client.stat(remote.as_path()).is_ok();
+                    match self.parallel_jobs.as_mut() {
+                        // Queue file for the transfer workers
+                        Some(jobs) if !delta => jobs.push(Job::Upload {
+                            local: file.clone(),
+                            remote,
+                        }),
+                        _ => self.filetransfer_send_or_clean(file, remote.as_path(), false),
                     }
                 }
-            }
+            },
             FsEntry::Directory(dir) => {
                 // Create directory on remote first
                 match self.client.mkdir(remote_path.as_path()) {
@@ -393,7 +915,7 @@ impl FileTransferActivity {
                     }
                 }
                 // Get files in dir
-                match self.host.scan_dir(dir.abs_path.as_path()) {
+                match self.scan_dir_or_deny(dir.abs_path.as_path(), false) {
                     Ok(entries) => {
                         // Iterate over files
                         for entry in entries.iter() {
@@ -401,6 +923,11 @@ impl FileTransferActivity {
                             if self.transfer.aborted() {
                                 break;
                             }
+                            // Skip entries excluded by transfer filters
+                            if !self.is_transferred(entry) {
+                                debug!("Skipping {}: excluded by filters", entry.get_name());
+                                continue;
+                            }
                             // Send entry; name is always None after first call
                             self.filetransfer_send_recurse(entry, remote_path.as_path(), None);
                         }
@@ -430,6 +957,134 @@ impl FileTransferActivity {
         }
     }
 
+    /// ### filetransfer_send_symlink
+    ///
+    /// Create at `remote` a symbolic link with the same target as the local link `entry`
+    fn filetransfer_send_symlink(&mut self, entry: &FsEntry, remote: &Path) {
+        let local: PathBuf = entry.get_abs_path();
+        let target: PathBuf = match self.host.read_link(local.as_path()) {
+            Ok(target) => target,
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Could not read symbolic link \"{}\": {}",
+                        local.display(),
+                        err
+                    ),
+                );
+                return;
+            }
+        };
+        // Links already pointing to the same target are left as they are
+        if matches!(self.client.read_link(remote), Ok(existing) if existing == target) {
+            self.log(
+                LogLevel::Info,
+                format!("Symbolic link \"{}\" already exists", remote.display()),
+            );
+            return;
+        }
+        match self.client.symlink(remote, target.as_path()) {
+            Ok(_) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Created symbolic link \"{}\" to \"{}\"",
+                    remote.display(),
+                    target.display()
+                ),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!(
+                    "Could not create symbolic link \"{}\": {}",
+                    remote.display(),
+                    err
+                ),
+            ),
+        }
+    }
+
+    /// ### filetransfer_send_or_clean
+    ///
+    /// Send local file to remote path; if the transfer fails midway, the partial file is removed from remote.
+    /// If `resume` is true, only the part of the file which differs from the remote one is sent,
+    /// and the remote file is kept on failure
+    fn filetransfer_send_or_clean(&mut self, file: &FsFile, remote: &Path, resume: bool) {
+        if let Err(err) = self.filetransfer_send_retry(file, remote, resume) {
+            self.transfer.files.fail_file();
+            self.trace_event(format!("FAILED \"{}\": {}", file.name, err));
+            // Log error
+            self.log_and_alert_error(
+                LogLevel::Error,
+                format!("Failed to upload file {}", file.name).as_str(),
+                &err,
+            );
+            // If transfer was abrupted or there was an IO error on remote, remove file
+            if !resume
+                && matches!(
+                    err,
+                    TransferErrorReason::Abrupted | TransferErrorReason::RemoteIoError(_)
+                )
+            {
+                self.remove_partial_remote(remote);
+            }
+        }
+    }
+
+    /// ### filetransfer_send_retry
+    ///
+    /// Send local file to remote path. If the transfer fails because of a transient error,
+    /// it's resumed up to `transfer_retries` times, waiting longer after each attempt
+    fn filetransfer_send_retry(
+        &mut self,
+        file: &FsFile,
+        remote: &Path,
+        resume: bool,
+    ) -> Result<(), TransferErrorReason> {
+        let mut result: Result<(), TransferErrorReason> = match resume {
+            true => self.filetransfer_send_resume(file, remote),
+            false => self.filetransfer_send_one(file, remote, file.name.clone()),
+        };
+        let retries: usize = self.transfer_retries();
+        let mut attempt: usize = 0;
+        while let Err(err) = &result {
+            if attempt >= retries || !err.is_transient() {
+                break;
+            }
+            attempt += 1;
+            self.trace_event(format!("RETRY #{} after error: {}", attempt, err));
+            if !self.wait_retry(file.name.as_str(), err.to_string(), attempt, retries) {
+                return Err(TransferErrorReason::Abrupted);
+            }
+            result = self.filetransfer_send_resume(file, remote);
+        }
+        if result.is_ok() {
+            self.transfer.transferred(file.abs_path.as_path());
+        }
+        result
+    }
+
+    /// ### remove_partial_remote
+    ///
+    /// Remove a file left incomplete on remote by a failed upload
+    fn remove_partial_remote(&mut self, remote: &Path) {
+        // Stat file on remote and remove it if exists
+        if let Err(err) = self
+            .client
+            .stat(remote)
+            .and_then(|x| self.client.remove(&x))
+        {
+            self.log(
+                LogLevel::Error,
+                format!(
+                    "Could not remove created file {}: {}",
+                    remote.display(),
+                    err
+                ),
+            );
+        }
+    }
+
     /// ### filetransfer_send_file
     ///
     /// Send local file and write it to remote path
@@ -439,16 +1094,31 @@ impl FileTransferActivity {
         remote: &Path,
         file_name: String,
     ) -> Result<(), TransferErrorReason> {
+        // Update only the changed blocks of the remote file, if possible
+        if self.delta_transfer()
+            && self.filetransfer_send_delta(local, remote, file_name.as_str())?
+        {
+            self.verify_transferred(local.abs_path.as_path(), remote);
+            return Ok(());
+        }
         // Upload file
         // Try to open local file
         match self.host.open_file_read(local.abs_path.as_path()) {
             Ok(mut fhnd) => match self.client.send_file(local, remote) {
-                Ok(mut rhnd) => {
+                Ok(rhnd) => {
+                    let mut rhnd = self.slow_link().stream(rhnd);
                     // Write file
                     let file_size: usize =
                         fhnd.seek(std::io::SeekFrom::End(0)).unwrap_or(0) as usize;
+                    self.trace_event(format!(
+                        "OPEN \"{}\" -> \"{}\" ({} bytes)",
+                        local.abs_path.display(),
+                        remote.display(),
+                        file_size
+                    ));
                     // Init transfer
                     self.transfer.partial.init(file_size);
+                    self.transfer.files.start_file();
                     // rewind
                     if let Err(err) = fhnd.seek(std::io::SeekFrom::Start(0)) {
                         return Err(TransferErrorReason::CouldNotRewind(err));
@@ -457,6 +1127,7 @@ impl FileTransferActivity {
                     let mut total_bytes_written: usize = 0;
                     let mut last_progress_val: f64 = 0.0;
                     let mut last_input_event_fetch: Option<Instant> = None;
+                    let low_bandwidth: bool = self.low_bandwidth();
                     // While the entire file hasn't been completely written,
                     // Or filetransfer has been aborted
                     while total_bytes_written < file_size && !self.transfer.aborted() {
@@ -466,21 +1137,26 @@ impl FileTransferActivity {
                                 .unwrap_or_else(Instant::now)
                                 .elapsed()
                                 .as_millis()
-                                >= 500
+                                >= Self::input_poll_interval(low_bandwidth)
                         {
                             // Read events
                             self.read_input_event();
                             // Reset instant
                             last_input_event_fetch = Some(Instant::now());
+                            // Hold on while paused
+                            self.wait_resumed();
                         }
                         // Read till you can
                         let mut buffer: [u8; 65536] = [0; 65536];
+                        let read_started: Instant = Instant::now();
                         let delta: usize = match fhnd.read(&mut buffer) {
                             Ok(bytes_read) => {
                                 total_bytes_written += bytes_read;
                                 if bytes_read == 0 {
                                     continue;
                                 } else {
+                                    let read_time: Duration = read_started.elapsed();
+                                    let write_started: Instant = Instant::now();
                                     let mut delta: usize = 0;
                                     while delta < bytes_read {
                                         // Write bytes
@@ -488,6 +1164,29 @@ impl FileTransferActivity {
                                             Ok(bytes) => {
                                                 delta += bytes;
                                             }
+                                            Err(err)
+                                                if err.kind() == std::io::ErrorKind::TimedOut =>
+                                            {
+                                                self.trace_event("STALLED writing remote file");
+                                                match self.prompt_stalled(
+                                                    format!("uploading \"{}\"", file_name).as_str(),
+                                                ) {
+                                                    StallAction::Wait => continue,
+                                                    StallAction::Retry => {
+                                                        drop(rhnd);
+                                                        self.transfer.full.rewind(
+                                                            self.transfer.partial.written(),
+                                                        );
+                                                        return self.filetransfer_send_one(
+                                                            local, remote, file_name,
+                                                        );
+                                                    }
+                                                    StallAction::Abort => {
+                                                        self.transfer.abort();
+                                                        return Err(TransferErrorReason::Abrupted);
+                                                    }
+                                                }
+                                            }
                                             Err(err) => {
                                                 return Err(TransferErrorReason::RemoteIoError(
                                                     err,
@@ -495,6 +1194,12 @@ impl FileTransferActivity {
                                             }
                                         }
                                     }
+                                    self.trace_chunk(
+                                        buffer.len(),
+                                        bytes_read,
+                                        read_time,
+                                        write_started.elapsed(),
+                                    );
                                     delta
                                 }
                             }
@@ -506,7 +1211,7 @@ impl FileTransferActivity {
                         self.transfer.partial.update_progress(delta);
                         self.transfer.full.update_progress(delta);
                         // Draw only if a significant progress has been made (performance improvement)
-                        if last_progress_val < self.transfer.partial.calc_progress() - 0.01 {
+                        if self.should_redraw_progress(low_bandwidth, last_progress_val) {
                             // Draw
                             self.update_progress_bar(format!("Uploading \"{}\"…", file_name));
                             self.view();
@@ -514,7 +1219,12 @@ impl FileTransferActivity {
                         }
                     }
                     // Finalize stream
-                    if let Err(err) = self.client.on_sent(rhnd) {
+                    let finalized = self.client.on_sent(rhnd.into_inner());
+                    self.trace_event(match &finalized {
+                        Ok(_) => String::from("CLOSE"),
+                        Err(err) => format!("CLOSE failed: {}", err),
+                    });
+                    if let Err(err) = finalized {
                         self.log(
                             LogLevel::Warn,
                             format!("Could not finalize remote stream: \"{}\"", err),
@@ -524,6 +1234,7 @@ impl FileTransferActivity {
                     if self.transfer.aborted() {
                         return Err(TransferErrorReason::Abrupted);
                     }
+                    self.transfer.files.end_file();
                     self.log(
                         LogLevel::Info,
                         format!(
@@ -531,9 +1242,10 @@ impl FileTransferActivity {
                             local.abs_path.display(),
                             remote.display(),
                             fmt_millis(self.transfer.partial.started().elapsed()),
-                            ByteSize(self.transfer.partial.calc_bytes_per_second()),
+                            fmt_size(self.transfer.partial.calc_bytes_per_second()),
                         ),
                     );
+                    self.verify_transferred(local.abs_path.as_path(), remote);
                 }
                 Err(err) => return Err(TransferErrorReason::FileTransferError(err)),
             },
@@ -542,46 +1254,370 @@ impl FileTransferActivity {
         Ok(())
     }
 
-    /// ### filetransfer_recv
+    /// ### filetransfer_send_resume
     ///
-    /// Recv fs entry from remote.
-    /// If dst_name is Some, entry will be saved with a different name.
-    /// If entry is a directory, this applies to directory only
-    pub(super) fn filetransfer_recv(
+    /// Complete the existing remote file with the content of `local`, sending only the blocks it's missing.
+    /// The whole file is sent if the remote can't be rebuilt
+    fn filetransfer_send_resume(
         &mut self,
-        payload: TransferPayload,
-        local_path: &Path,
-        dst_name: Option<String>,
-    ) -> Result<(), String> {
-        match payload {
-            TransferPayload::Any(entry) => self.filetransfer_recv_any(&entry, local_path, dst_name),
-            TransferPayload::File(file) => self.filetransfer_recv_file(&file, local_path),
-            TransferPayload::Many(entries) => self.filetransfer_recv_many(entries, local_path),
+        local: &FsFile,
+        remote: &Path,
+    ) -> Result<(), TransferErrorReason> {
+        if self.filetransfer_send_delta(local, remote, local.name.as_str())? {
+            return Ok(());
         }
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "Could not resume \"{}\": the remote doesn't support it; sending the whole file",
+                remote.display()
+            ),
+        );
+        self.filetransfer_send_one(local, remote, local.name.clone())
     }
 
-    /// ### filetransfer_recv_any
+    /// ### filetransfer_send_delta
     ///
-    /// Recv fs entry from remote.
-    /// If dst_name is Some, entry will be saved with a different name.
-    /// If entry is a directory, this applies to directory only
-    fn filetransfer_recv_any(
+    /// Update the existing remote file with the content of `local`, sending only the data it doesn't already have.
+    /// Returns `Ok(false)`, without writing anything, if the remote file doesn't exist or can't be rebuilt.
+    /// The remote file is replaced only once the new one is complete, so it's left untouched on errors and aborts
+    fn filetransfer_send_delta(
         &mut self,
-        entry: &FsEntry,
-        local_path: &Path,
-        dst_name: Option<String>,
-    ) -> Result<(), String> {
-        // Reset states
-        self.transfer.reset();
-        // Calculate total transfer size
-        let total_transfer_size: usize = self.get_total_transfer_size_remote(entry);
-        self.transfer.full.init(total_transfer_size);
-        // Mount progress bar
-        self.mount_progress_bar(format!("Downloading {}…", entry.get_abs_path().display()));
-        // Receive
-        self.filetransfer_recv_recurse(entry, local_path, dst_name);
-        // Umount progress bar
-        self.umount_progress_bar();
+        local: &FsFile,
+        remote: &Path,
+        file_name: &str,
+    ) -> Result<bool, TransferErrorReason> {
+        let mut fhnd = match self.host.open_file_read(local.abs_path.as_path()) {
+            Ok(fhnd) => fhnd,
+            Err(err) => return Err(TransferErrorReason::HostError(err)),
+        };
+        let file_size: u64 = match fhnd.seek(std::io::SeekFrom::End(0)) {
+            Ok(size) => size,
+            Err(err) => return Err(TransferErrorReason::CouldNotRewind(err)),
+        };
+        if let Err(err) = fhnd.seek(std::io::SeekFrom::Start(0)) {
+            return Err(TransferErrorReason::CouldNotRewind(err));
+        }
+        // Get remote signature
+        let block_size: u64 = delta::block_size(file_size);
+        let signature: Signature = match self.client.signature(remote, block_size) {
+            Ok(signature) => signature,
+            Err(err) => {
+                debug!(
+                    "Could not get signature of {}: {}; sending whole file",
+                    remote.display(),
+                    err
+                );
+                return Ok(false);
+            }
+        };
+        // Init transfer
+        self.transfer.partial.init(file_size as usize);
+        self.transfer.files.start_file();
+        let mut last_progress_val: f64 = 0.0;
+        let mut last_input_event_fetch: Option<Instant> = None;
+        let low_bandwidth: bool = self.low_bandwidth();
+        // Look for the remote blocks in the local file; blocks found are already transferred
+        let mut ops: Vec<delta::Op> = Vec::new();
+        let mut matcher = delta::Matcher::new(&signature, &mut fhnd);
+        while !self.transfer.aborted() {
+            // Handle input events
+            if last_input_event_fetch.is_none()
+                || last_input_event_fetch
+                    .unwrap_or_else(Instant::now)
+                    .elapsed()
+                    .as_millis()
+                    >= Self::input_poll_interval(low_bandwidth)
+            {
+                self.read_input_event();
+                last_input_event_fetch = Some(Instant::now());
+                // Hold on while paused
+                self.wait_resumed();
+            }
+            let op: delta::Op = match matcher.next_op() {
+                Ok(Some(op)) => op,
+                Ok(None) => break,
+                Err(err) => return Err(TransferErrorReason::LocalIoError(err)),
+            };
+            if let delta::Op::Copy { count, .. } = op {
+                self.transfer
+                    .partial
+                    .update_progress((count * block_size) as usize);
+                self.transfer
+                    .full
+                    .update_progress((count * block_size) as usize);
+            }
+            delta::push(&mut ops, op);
+            if self.should_redraw_progress(low_bandwidth, last_progress_val) {
+                self.update_progress_bar(format!("Comparing \"{}\"…", file_name));
+                self.view();
+                last_progress_val = self.transfer.partial.calc_progress();
+            }
+        }
+        drop(matcher);
+        if self.transfer.aborted() {
+            return Err(TransferErrorReason::Abrupted);
+        }
+        let bytes_sent: u64 = delta::literal_size(&ops);
+        // The file is rebuilt on the remote from the literal data, uploaded to a temporary file
+        let literal: PathBuf = remote.with_file_name(format!(
+            ".termscp-delta-{}",
+            random_alphanumeric_with_len(8)
+        ));
+        let literal_file: FsFile = FsFile {
+            name: literal
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            abs_path: literal.clone(),
+            size: bytes_sent as usize,
+            ..local.clone()
+        };
+        if let Err(err) =
+            self.filetransfer_send_literal(&mut fhnd, &literal_file, ops.as_slice(), file_name)
+        {
+            // The remote file hasn't been touched yet
+            self.remove_delta_literal(literal.as_path());
+            return Err(err);
+        }
+        // Rebuild the file; the command removes the literal data anyway
+        if let Err(err) = self
+            .client
+            .rebuild(remote, literal.as_path(), block_size, ops.as_slice())
+        {
+            self.remove_delta_literal(literal.as_path());
+            return Err(TransferErrorReason::FileTransferError(err));
+        }
+        self.transfer.files.end_file();
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Updated file \"{}\" on \"{}\" sending {} of {} (took {} seconds)",
+                local.abs_path.display(),
+                remote.display(),
+                fmt_size(bytes_sent),
+                fmt_size(file_size),
+                fmt_millis(self.transfer.partial.started().elapsed()),
+            ),
+        );
+        Ok(true)
+    }
+
+    /// ### remove_delta_literal
+    ///
+    /// Remove the temporary file with the literal data of a delta upload from the remote, if it still exists
+    fn remove_delta_literal(&mut self, literal: &Path) {
+        if let Ok(entry) = self.client.stat(literal) {
+            if let Err(err) = self.client.remove(&entry) {
+                self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "Could not remove temporary file \"{}\": {}",
+                        literal.display(),
+                        err
+                    ),
+                );
+            }
+        }
+    }
+
+    /// ### filetransfer_send_literal
+    ///
+    /// Upload to `literal` the data of `fhnd` described by the literal steps of `ops`, in the same order
+    fn filetransfer_send_literal(
+        &mut self,
+        fhnd: &mut std::fs::File,
+        literal: &FsFile,
+        ops: &[delta::Op],
+        file_name: &str,
+    ) -> Result<(), TransferErrorReason> {
+        let mut rhnd: Box<dyn Write> =
+            match self.client.send_file(literal, literal.abs_path.as_path()) {
+                Ok(rhnd) => rhnd,
+                Err(err) => return Err(TransferErrorReason::FileTransferError(err)),
+            };
+        let mut last_progress_val: f64 = self.transfer.partial.calc_progress();
+        let mut last_input_event_fetch: Instant = Instant::now();
+        let low_bandwidth: bool = self.low_bandwidth();
+        let mut buffer: Vec<u8> = vec![0; delta::MIN_BLOCK_SIZE as usize];
+        for op in ops.iter() {
+            let (offset, len): (u64, u64) = match op {
+                delta::Op::Literal { offset, len } => (*offset, *len),
+                delta::Op::Copy { .. } => continue,
+            };
+            if let Err(err) = fhnd.seek(std::io::SeekFrom::Start(offset)) {
+                return Err(TransferErrorReason::CouldNotRewind(err));
+            }
+            let mut written: u64 = 0;
+            while written < len {
+                // Handle input events
+                if last_input_event_fetch.elapsed().as_millis()
+                    >= Self::input_poll_interval(low_bandwidth)
+                {
+                    self.read_input_event();
+                    last_input_event_fetch = Instant::now();
+                    // Hold on while paused
+                    self.wait_resumed();
+                }
+                if self.transfer.aborted() {
+                    return Err(TransferErrorReason::Abrupted);
+                }
+                // Read chunk
+                let chunk: usize = (len - written).min(buffer.len() as u64) as usize;
+                if let Err(err) = fhnd.read_exact(&mut buffer[..chunk]) {
+                    return Err(TransferErrorReason::LocalIoError(err));
+                }
+                if let Err(err) = rhnd.write_all(&buffer[..chunk]) {
+                    return Err(TransferErrorReason::RemoteIoError(err));
+                }
+                written += chunk as u64;
+                // Increase progress
+                self.transfer.partial.update_progress(chunk);
+                self.transfer.full.update_progress(chunk);
+                if self.should_redraw_progress(low_bandwidth, last_progress_val) {
+                    self.update_progress_bar(format!("Uploading \"{}\"…", file_name));
+                    self.view();
+                    last_progress_val = self.transfer.partial.calc_progress();
+                }
+            }
+        }
+        if let Err(err) = self.client.on_sent(rhnd) {
+            return Err(TransferErrorReason::FileTransferError(err));
+        }
+        Ok(())
+    }
+
+    /// ### filetransfer_recv
+    ///
+    /// Recv fs entry from remote.
+    /// If dst_name is Some, entry will be saved with a different name.
+    /// If entry is a directory, this applies to directory only
+    pub(super) fn filetransfer_recv(
+        &mut self,
+        payload: TransferPayload,
+        local_path: &Path,
+        dst_name: Option<String>,
+    ) -> Result<(), String> {
+        // Answers to the existing file prompt apply to this transfer only; start from the session default
+        self.conflict_policy = self.options.conflict_policy;
+        let is_batch: bool = match &payload {
+            TransferPayload::Any(entry) => entry.is_dir(),
+            TransferPayload::File(_) => false,
+            TransferPayload::Many(_) => true,
+        };
+        if is_batch {
+            self.journal_record(false, payload.paths(), local_path, dst_name.clone());
+        }
+        self.start_trace(format!("download to \"{}\"", local_path.display()));
+        let result = match payload {
+            TransferPayload::Any(entry) => self.filetransfer_recv_any(&entry, local_path, dst_name),
+            TransferPayload::File(file) => self.filetransfer_recv_file(&file, local_path),
+            TransferPayload::Many(entries) => self.filetransfer_recv_many(entries, local_path),
+        };
+        self.account_usage(0, self.transfer.full.written());
+        self.update_metrics(false, result.is_err());
+        if is_batch {
+            self.journal_clear();
+            self.notify_webhook(false, local_path);
+        }
+        // Downloaded files must not be uploaded back
+        if let Some(watch) = self.watch.as_mut() {
+            watch.resync();
+        }
+        self.finish_trace(&result);
+        result
+    }
+
+    /// ### notify_webhook
+    ///
+    /// Notify the configured webhook, if any, that a batch transfer into `path` has finished
+    fn notify_webhook(&self, upload: bool, path: &Path) {
+        let url: String = match self.config().get_webhook_url() {
+            Some(url) => url.to_string(),
+            None => return,
+        };
+        let notification: Notification = Notification {
+            outcome: self.transfer_outcome(false),
+            upload,
+            host: self
+                .context()
+                .ft_params()
+                .map(|x| x.address.clone())
+                .unwrap_or_default(),
+            path: path.display().to_string(),
+            files: self.transfer.files.done(),
+            failed: self.transfer.files.failed(),
+            bytes: self.transfer.full.written(),
+            seconds: self.transfer.full.started().elapsed().as_secs(),
+        };
+        let template: &str = self
+            .config()
+            .get_webhook_template()
+            .unwrap_or(webhook::DEFAULT_TEMPLATE);
+        webhook::send(url, notification.render(template));
+    }
+
+    /// ### update_metrics
+    ///
+    /// Count the transfer which just ended, whose result was an error if `failed`,
+    /// and rewrite the metrics file, if configured
+    fn update_metrics(&self, upload: bool, failed: bool) {
+        let outcome: Outcome = self.transfer_outcome(failed);
+        self.context().metrics().record(
+            upload,
+            outcome,
+            self.transfer.files.done(),
+            self.transfer.files.failed(),
+            self.transfer.full.written(),
+            SystemTime::now(),
+        );
+        if let Some(path) = self.config().get_metrics_file() {
+            if let Err(err) = self.context().metrics().write(path) {
+                error!("Could not write metrics to {}: {}", path.display(), err);
+            }
+        }
+    }
+
+    /// ### transfer_outcome
+    ///
+    /// Get how the transfer which just ended went; `failed` tells whether its result was an error
+    fn transfer_outcome(&self, failed: bool) -> Outcome {
+        if self.transfer.aborted() {
+            Outcome::Aborted
+        } else if failed || self.transfer.files.failed() > 0 {
+            Outcome::Failed
+        } else {
+            Outcome::Completed
+        }
+    }
+
+    /// ### filetransfer_recv_any
+    ///
+    /// Recv fs entry from remote.
+    /// If dst_name is Some, entry will be saved with a different name.
+    /// If entry is a directory, this applies to directory only
+    fn filetransfer_recv_any(
+        &mut self,
+        entry: &FsEntry,
+        local_path: &Path,
+        dst_name: Option<String>,
+    ) -> Result<(), String> {
+        // Reset states
+        self.transfer.reset();
+        // Calculate total transfer size
+        let (total_transfer_size, total_files): (usize, usize) =
+            self.get_total_transfer_size_remote(entry);
+        self.transfer.full.init(total_transfer_size);
+        self.transfer.files.init(total_files);
+        // Mount progress bar
+        self.mount_progress_bar(format!("Downloading {}…", entry.get_abs_path().display()));
+        // Receive
+        self.begin_parallel_jobs();
+        self.filetransfer_recv_recurse(entry, local_path, dst_name);
+        self.run_parallel_jobs();
+        // Umount progress bar
+        self.umount_progress_bar();
+        self.report_denied_entries();
         Ok(())
     }
 
@@ -594,10 +1630,11 @@ impl FileTransferActivity {
         // Calculate total transfer size
         let total_transfer_size: usize = entry.size;
         self.transfer.full.init(total_transfer_size);
+        self.transfer.files.init(1);
         // Mount progress bar
         self.mount_progress_bar(format!("Downloading {}…", entry.abs_path.display()));
         // Receive
-        let result = self.filetransfer_recv_one(local_path, entry, entry.name.clone());
+        let result = self.filetransfer_recv_retry(entry, local_path, false);
         // Umount progress bar
         self.umount_progress_bar();
         // Return result
@@ -615,19 +1652,23 @@ impl FileTransferActivity {
         // Reset states
         self.transfer.reset();
         // Calculate total size of transfer
-        let total_transfer_size: usize = entries
+        let (total_transfer_size, total_files): (usize, usize) = entries
             .iter()
             .map(|x| self.get_total_transfer_size_remote(x))
-            .sum();
+            .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1));
         self.transfer.full.init(total_transfer_size);
+        self.transfer.files.init(total_files);
         // Mount progress bar
         self.mount_progress_bar(format!("Downloading {} entries…", entries.len()));
         // Send recurse
+        self.begin_parallel_jobs();
         entries
             .iter()
             .for_each(|x| self.filetransfer_recv_recurse(x, curr_remote_path, None));
+        self.run_parallel_jobs();
         // Umount progress bar
         self.umount_progress_bar();
+        self.report_denied_entries();
         Ok(())
     }
 
@@ -637,11 +1678,33 @@ impl FileTransferActivity {
         local_path: &Path,
         dst_name: Option<String>,
     ) {
-        // Write popup
-        let file_name: String = match entry {
-            FsEntry::Directory(dir) => dir.name.clone(),
-            FsEntry::File(file) => file.name.clone(),
-        };
+        // Skip entries deselected from the manifest
+        if self.transfer.is_excluded(entry.get_abs_path().as_path()) {
+            debug!("Skipping {}: excluded from manifest", entry.get_name());
+            return;
+        }
+        // Skip or recreate symbolic links, unless they're followed
+        if entry.is_symlink() {
+            match self.symlink_mode() {
+                SymlinkMode::Skip => {
+                    self.log(
+                        LogLevel::Info,
+                        format!(
+                            "Skipped symbolic link \"{}\"",
+                            entry.get_abs_path().display()
+                        ),
+                    );
+                    return;
+                }
+                SymlinkMode::Recreate => {
+                    let local: PathBuf =
+                        local_path.join(dst_name.as_deref().unwrap_or_else(|| entry.get_name()));
+                    self.filetransfer_recv_symlink(entry, local.as_path());
+                    return;
+                }
+                SymlinkMode::Follow => {}
+            }
+        }
         // Match entry
         match entry {
             FsEntry::File(file) => {
@@ -652,43 +1715,21 @@ impl FileTransferActivity {
                     None => file.name.clone(),
                 };
                 local_file_path.push(local_file_name.as_str());
-                // Download file
-                if let Err(err) =
-                    self.filetransfer_recv_one(local_file_path.as_path(), file, file_name)
-                {
-                    self.log_and_alert(
-                        LogLevel::Error,
-                        format!("Could not download file {}: {}", file.name, err),
-                    );
-                    // If transfer was abrupted or there was an IO error on remote, remove file
-                    if matches!(
-                        err,
-                        TransferErrorReason::Abrupted | TransferErrorReason::LocalIoError(_)
-                    ) {
-                        // Stat file
-                        match self.host.stat(local_file_path.as_path()) {
-                            Err(err) => self.log(
-                                LogLevel::Error,
-                                format!(
-                                    "Could not remove created file {}: {}",
-                                    local_file_path.display(),
-                                    err
-                                ),
-                            ),
-                            Ok(entry) => {
-                                if let Err(err) = self.host.remove(&entry) {
-                                    self.log(
-                                        LogLevel::Error,
-                                        format!(
-                                            "Could not remove created file {}: {}",
-                                            local_file_path.display(),
-                                            err
-                                        ),
-                                    );
-                                }
-                            }
-                        }
+                match self.local_destination(file, local_file_path.as_path()) {
+                    // Skipped
+                    None => {}
+                    // Resumed files are received by the main connection
+                    Some((local, true)) => {
+                        self.filetransfer_recv_or_clean(file, local.as_path(), true)
                     }
+                    Some((local, false)) => match self.parallel_jobs.as_mut() {
+                        // Queue file for the transfer workers
+                        Some(jobs) => jobs.push(Job::Download {
+                            remote: file.clone(),
+                            local,
+                        }),
+                        None => self.filetransfer_recv_or_clean(file, local.as_path(), false),
+                    },
                 }
             }
             FsEntry::Directory(dir) => {
@@ -707,7 +1748,9 @@ impl FileTransferActivity {
                             target_os = "macos",
                             target_os = "linux"
                         ))]
-                        if let Some((owner, group, others)) = dir.unix_pex {
+                        if let Some((owner, group, others)) =
+                            dir.unix_pex.filter(|_| self.options.preserve_mode)
+                        {
                             if let Err(err) = self.host.chmod(
                                 local_dir_path.as_path(),
                                 (owner.as_byte(), group.as_byte(), others.as_byte()),
@@ -723,12 +1766,14 @@ impl FileTransferActivity {
                                 );
                             }
                         }
+                        #[cfg(target_family = "unix")]
+                        self.preserve_local_owner(local_dir_path.as_path(), dir.user, dir.group);
                         self.log(
                             LogLevel::Info,
                             format!("Created directory \"{}\"", local_dir_path.display()),
                         );
                         // Get files in dir
-                        match self.client.list_dir(dir.abs_path.as_path()) {
+                        match self.scan_dir_or_deny(dir.abs_path.as_path(), true) {
                             Ok(entries) => {
                                 // Iterate over files
                                 for entry in entries.iter() {
@@ -736,6 +1781,14 @@ impl FileTransferActivity {
                                     if self.transfer.aborted() {
                                         break;
                                     }
+                                    // Skip entries excluded by transfer filters
+                                    if !self.is_transferred(entry) {
+                                        debug!(
+                                            "Skipping {}: excluded by filters",
+                                            entry.get_name()
+                                        );
+                                        continue;
+                                    }
                                     // Receive entry; name is always None after first call
                                     // Local path becomes local_dir_path
                                     self.filetransfer_recv_recurse(
@@ -785,6 +1838,124 @@ impl FileTransferActivity {
         }
     }
 
+    /// ### filetransfer_recv_symlink
+    ///
+    /// Create at `local` a symbolic link with the same target as the remote link `entry`
+    fn filetransfer_recv_symlink(&mut self, entry: &FsEntry, local: &Path) {
+        let remote: PathBuf = entry.get_abs_path();
+        let target: PathBuf = match self.client.read_link(remote.as_path()) {
+            Ok(target) => target,
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Could not read symbolic link \"{}\": {}",
+                        remote.display(),
+                        err
+                    ),
+                );
+                return;
+            }
+        };
+        // Links already pointing to the same target are left as they are
+        if matches!(self.host.read_link(local), Ok(existing) if existing == target) {
+            self.log(
+                LogLevel::Info,
+                format!("Symbolic link \"{}\" already exists", local.display()),
+            );
+            return;
+        }
+        match self.host.symlink(local, target.as_path()) {
+            Ok(_) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Created symbolic link \"{}\" to \"{}\"",
+                    local.display(),
+                    target.display()
+                ),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!(
+                    "Could not create symbolic link \"{}\": {}",
+                    local.display(),
+                    err
+                ),
+            ),
+        }
+    }
+
+    /// ### filetransfer_recv_or_clean
+    ///
+    /// Receive remote file to local path; if the transfer fails midway, the partial file is removed.
+    /// If `resume` is true, only the part of the file missing from the local one is received,
+    /// and the local file is kept on failure
+    fn filetransfer_recv_or_clean(&mut self, file: &FsFile, local: &Path, resume: bool) {
+        if let Err(err) = self.filetransfer_recv_retry(file, local, resume) {
+            self.transfer.files.fail_file();
+            self.trace_event(format!("FAILED \"{}\": {}", file.name, err));
+            self.log_and_alert_error(
+                LogLevel::Error,
+                format!("Could not download file {}", file.name).as_str(),
+                &err,
+            );
+            // If transfer was abrupted or there was an IO error on remote, remove file
+            if !resume
+                && matches!(
+                    err,
+                    TransferErrorReason::Abrupted | TransferErrorReason::LocalIoError(_)
+                )
+            {
+                self.remove_partial_local(local);
+            }
+        }
+    }
+
+    /// ### filetransfer_recv_retry
+    ///
+    /// Receive remote file to local path. If the transfer fails because of a transient error,
+    /// it's resumed up to `transfer_retries` times, waiting longer after each attempt
+    fn filetransfer_recv_retry(
+        &mut self,
+        file: &FsFile,
+        local: &Path,
+        resume: bool,
+    ) -> Result<(), TransferErrorReason> {
+        let mut result: Result<(), TransferErrorReason> = match resume {
+            true => self.filetransfer_recv_resume(local, file),
+            false => self.filetransfer_recv_one(local, file, file.name.clone()),
+        };
+        let retries: usize = self.transfer_retries();
+        let mut attempt: usize = 0;
+        while let Err(err) = &result {
+            if attempt >= retries || !err.is_transient() {
+                break;
+            }
+            attempt += 1;
+            self.trace_event(format!("RETRY #{} after error: {}", attempt, err));
+            if !self.wait_retry(file.name.as_str(), err.to_string(), attempt, retries) {
+                return Err(TransferErrorReason::Abrupted);
+            }
+            result = self.filetransfer_recv_resume(local, file);
+        }
+        if result.is_ok() {
+            self.transfer.transferred(file.abs_path.as_path());
+        }
+        result
+    }
+
+    /// ### remove_partial_local
+    ///
+    /// Remove a file left incomplete on localhost by a failed download
+    fn remove_partial_local(&mut self, local: &Path) {
+        if let Err(err) = self.host.stat(local).and_then(|x| self.host.remove(&x)) {
+            self.log(
+                LogLevel::Error,
+                format!("Could not remove created file {}: {}", local.display(), err),
+            );
+        }
+    }
+
     /// ### filetransfer_recv_one
     ///
     /// Receive file from remote and write it to local path
@@ -799,13 +1970,22 @@ impl FileTransferActivity {
             Ok(mut local_file) => {
                 // Download file from remote
                 match self.client.recv_file(remote) {
-                    Ok(mut rhnd) => {
+                    Ok(rhnd) => {
+                        let mut rhnd = self.slow_link().stream(rhnd);
                         let mut total_bytes_written: usize = 0;
+                        self.trace_event(format!(
+                            "OPEN \"{}\" -> \"{}\" ({} bytes)",
+                            remote.abs_path.display(),
+                            local.display(),
+                            remote.size
+                        ));
                         // Init transfer
                         self.transfer.partial.init(remote.size);
+                        self.transfer.files.start_file();
                         // Write local file
                         let mut last_progress_val: f64 = 0.0;
                         let mut last_input_event_fetch: Option<Instant> = None;
+                        let low_bandwidth: bool = self.low_bandwidth();
                         // While the entire file hasn't been completely read,
                         // Or filetransfer has been aborted
                         while total_bytes_written < remote.size && !self.transfer.aborted() {
@@ -815,21 +1995,26 @@ impl FileTransferActivity {
                                     .unwrap_or_else(Instant::now)
                                     .elapsed()
                                     .as_millis()
-                                    >= 500
+                                    >= Self::input_poll_interval(low_bandwidth)
                             {
                                 // Read events
                                 self.read_input_event();
                                 // Reset instant
                                 last_input_event_fetch = Some(Instant::now());
+                                // Hold on while paused
+                                self.wait_resumed();
                             }
                             // Read till you can
                             let mut buffer: [u8; 65536] = [0; 65536];
+                            let read_started: Instant = Instant::now();
                             let delta: usize = match rhnd.read(&mut buffer) {
                                 Ok(bytes_read) => {
                                     total_bytes_written += bytes_read;
                                     if bytes_read == 0 {
                                         continue;
                                     } else {
+                                        let read_time: Duration = read_started.elapsed();
+                                        let write_started: Instant = Instant::now();
                                         let mut delta: usize = 0;
                                         while delta < bytes_read {
                                             // Write bytes
@@ -842,9 +2027,35 @@ impl FileTransferActivity {
                                                 }
                                             }
                                         }
+                                        self.trace_chunk(
+                                            buffer.len(),
+                                            bytes_read,
+                                            read_time,
+                                            write_started.elapsed(),
+                                        );
                                         delta
                                     }
                                 }
+                                Err(err) if err.kind() == std::io::ErrorKind::TimedOut => {
+                                    self.trace_event("STALLED reading remote file");
+                                    match self.prompt_stalled(
+                                        format!("downloading \"{}\"", file_name).as_str(),
+                                    ) {
+                                        StallAction::Wait => continue,
+                                        StallAction::Retry => {
+                                            drop(rhnd);
+                                            self.transfer
+                                                .full
+                                                .rewind(self.transfer.partial.written());
+                                            return self
+                                                .filetransfer_recv_one(local, remote, file_name);
+                                        }
+                                        StallAction::Abort => {
+                                            self.transfer.abort();
+                                            return Err(TransferErrorReason::Abrupted);
+                                        }
+                                    }
+                                }
                                 Err(err) => {
                                     return Err(TransferErrorReason::RemoteIoError(err));
                                 }
@@ -853,7 +2064,7 @@ impl FileTransferActivity {
                             self.transfer.partial.update_progress(delta);
                             self.transfer.full.update_progress(delta);
                             // Draw only if a significant progress has been made (performance improvement)
-                            if last_progress_val < self.transfer.partial.calc_progress() - 0.01 {
+                            if self.should_redraw_progress(low_bandwidth, last_progress_val) {
                                 // Draw
                                 self.update_progress_bar(format!("Downloading \"{}\"", file_name));
                                 self.view();
@@ -861,7 +2072,12 @@ impl FileTransferActivity {
                             }
                         }
                         // Finalize stream
-                        if let Err(err) = self.client.on_recv(rhnd) {
+                        let finalized = self.client.on_recv(rhnd.into_inner());
+                        self.trace_event(match &finalized {
+                            Ok(_) => String::from("CLOSE"),
+                            Err(err) => format!("CLOSE failed: {}", err),
+                        });
+                        if let Err(err) = finalized {
                             self.log(
                                 LogLevel::Warn,
                                 format!("Could not finalize remote stream: \"{}\"", err),
@@ -871,13 +2087,16 @@ impl FileTransferActivity {
                         if self.transfer.aborted() {
                             return Err(TransferErrorReason::Abrupted);
                         }
+                        self.transfer.files.end_file();
                         // Apply file mode to file
                         #[cfg(any(
                             target_family = "unix",
                             target_os = "macos",
                             target_os = "linux"
                         ))]
-                        if let Some((owner, group, others)) = remote.unix_pex {
+                        if let Some((owner, group, others)) =
+                            remote.unix_pex.filter(|_| self.options.preserve_mode)
+                        {
                             if let Err(err) = self
                                 .host
                                 .chmod(local, (owner.as_byte(), group.as_byte(), others.as_byte()))
@@ -893,6 +2112,8 @@ impl FileTransferActivity {
                                 );
                             }
                         }
+                        #[cfg(target_family = "unix")]
+                        self.preserve_local_owner(local, remote.user, remote.group);
                         // Log
                         self.log(
                             LogLevel::Info,
@@ -901,9 +2122,10 @@ impl FileTransferActivity {
                                 remote.abs_path.display(),
                                 local.display(),
                                 fmt_millis(self.transfer.partial.started().elapsed()),
-                                ByteSize(self.transfer.partial.calc_bytes_per_second()),
+                                fmt_size(self.transfer.partial.calc_bytes_per_second()),
                             ),
                         );
+                        self.verify_transferred(local, remote.abs_path.as_path());
                     }
                     Err(err) => return Err(TransferErrorReason::FileTransferError(err)),
                 }
@@ -913,6 +2135,744 @@ impl FileTransferActivity {
         Ok(())
     }
 
+    /// ### filetransfer_recv_resume
+    ///
+    /// Append to the local file the part of `remote` it's missing.
+    /// The whole file is received if the local file is bigger than the remote one, or if the remote can't read from an offset
+    fn filetransfer_recv_resume(
+        &mut self,
+        local: &Path,
+        remote: &FsFile,
+    ) -> Result<(), TransferErrorReason> {
+        let offset: usize = match self.host.stat(local) {
+            Ok(FsEntry::File(existing)) if existing.size <= remote.size => existing.size,
+            _ => 0,
+        };
+        let rhnd: Option<Box<dyn Read>> = match self.capabilities.supports(Capability::RangedReads)
+        {
+            true if offset > 0 => self
+                .client
+                .recv_file_range(remote, offset as u64, (remote.size - offset) as u64)
+                .map_err(|e| debug!("Could not read {} from offset: {}", remote.name, e))
+                .ok(),
+            _ => None,
+        };
+        let rhnd: Box<dyn Read> = match rhnd {
+            Some(rhnd) => rhnd,
+            None => {
+                self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "Could not resume \"{}\"; receiving the whole file",
+                        local.display()
+                    ),
+                );
+                return self.filetransfer_recv_one(local, remote, remote.name.clone());
+            }
+        };
+        let mut local_file = match self.host.open_file_append(local) {
+            Ok(fhnd) => fhnd,
+            Err(err) => return Err(TransferErrorReason::HostError(err)),
+        };
+        let mut rhnd = self.slow_link().stream(rhnd);
+        // Init transfer; the existing part counts as transferred
+        self.transfer.partial.init(remote.size);
+        self.transfer.files.start_file();
+        self.transfer.partial.update_progress(offset);
+        self.transfer.full.update_progress(offset);
+        let mut total_bytes_written: usize = offset;
+        let mut last_progress_val: f64 = 0.0;
+        let mut last_input_event_fetch: Option<Instant> = None;
+        let low_bandwidth: bool = self.low_bandwidth();
+        while total_bytes_written < remote.size && !self.transfer.aborted() {
+            // Handle input events
+            if last_input_event_fetch.is_none()
+                || last_input_event_fetch
+                    .unwrap_or_else(Instant::now)
+                    .elapsed()
+                    .as_millis()
+                    >= Self::input_poll_interval(low_bandwidth)
+            {
+                self.read_input_event();
+                last_input_event_fetch = Some(Instant::now());
+                // Hold on while paused
+                self.wait_resumed();
+            }
+            let mut buffer: [u8; 65536] = [0; 65536];
+            let bytes_read: usize = match rhnd.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(bytes) => bytes,
+                Err(err) => return Err(TransferErrorReason::RemoteIoError(err)),
+            };
+            if let Err(err) = local_file.write_all(&buffer[..bytes_read]) {
+                return Err(TransferErrorReason::LocalIoError(err));
+            }
+            total_bytes_written += bytes_read;
+            // Set progress
+            self.transfer.partial.update_progress(bytes_read);
+            self.transfer.full.update_progress(bytes_read);
+            if self.should_redraw_progress(low_bandwidth, last_progress_val) {
+                self.update_progress_bar(format!("Downloading \"{}\"", remote.name));
+                self.view();
+                last_progress_val = self.transfer.partial.calc_progress();
+            }
+        }
+        // Finalize stream
+        if let Err(err) = self.client.on_recv(rhnd.into_inner()) {
+            self.log(
+                LogLevel::Warn,
+                format!("Could not finalize remote stream: \"{}\"", err),
+            );
+        }
+        if self.transfer.aborted() {
+            return Err(TransferErrorReason::Abrupted);
+        }
+        self.transfer.files.end_file();
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Resumed file \"{}\" to \"{}\" receiving {} of {} (took {} seconds)",
+                remote.abs_path.display(),
+                local.display(),
+                fmt_size((total_bytes_written - offset) as u64),
+                fmt_size(remote.size as u64),
+                fmt_millis(self.transfer.partial.started().elapsed()),
+            ),
+        );
+        Ok(())
+    }
+
+    // -- parallel transfers
+
+    /// ### begin_parallel_jobs
+    ///
+    /// Make the transfer recursion queue files instead of transferring them, if parallel transfers are enabled
+    fn begin_parallel_jobs(&mut self) {
+        // Traced transfers run sequentially, so that the chunks of each file are traced
+        if self.parallel_transfers() > 1 && self.trace.is_none() {
+            self.parallel_jobs = Some(Vec::new());
+        }
+    }
+
+    /// ### run_parallel_jobs
+    ///
+    /// Transfer the files queued since `begin_parallel_jobs`
+    fn run_parallel_jobs(&mut self) {
+        let jobs: Vec<Job> = match self.parallel_jobs.take() {
+            Some(jobs) => jobs,
+            None => return,
+        };
+        let workers: usize = self.parallel_transfers().min(jobs.len());
+        if let [Job::Download { remote, local }] = jobs.as_slice() {
+            if self.segmented_download(remote) {
+                let (remote, local) = (remote.clone(), local.clone());
+                self.filetransfer_segmented(remote, local);
+                self.on_parallel_jobs_done();
+                return;
+            }
+        }
+        // Opening new connections is not worth it for a single file
+        if workers < 2 {
+            self.filetransfer_jobs(jobs);
+        } else {
+            match self.make_connectors(workers) {
+                Ok(connectors) => {
+                    let remaining: Vec<Job> = self.filetransfer_parallel(jobs, connectors);
+                    if !remaining.is_empty() && !self.transfer.aborted() {
+                        self.log(
+                            LogLevel::Warn,
+                            format!(
+                                "{} files will be transferred using the main connection",
+                                remaining.len()
+                            ),
+                        );
+                        self.filetransfer_jobs(remaining);
+                    }
+                }
+                Err(err) => {
+                    self.log(
+                        LogLevel::Warn,
+                        format!(
+                            "Could not start parallel transfer: {}; files will be transferred one by one",
+                            err
+                        ),
+                    );
+                    self.filetransfer_jobs(jobs);
+                }
+            }
+        }
+        self.on_parallel_jobs_done();
+    }
+
+    /// ### on_parallel_jobs_done
+    ///
+    /// Refresh explorers once the queued files have been transferred
+    fn on_parallel_jobs_done(&mut self) {
+        // Reload directories with the transferred files
+        self.reload_local_dir();
+        self.reload_remote_dir();
+        if self.transfer.aborted() {
+            self.log_and_alert(LogLevel::Warn, String::from("Transfer aborted!"));
+        }
+    }
+
+    /// ### make_connectors
+    ///
+    /// Make `n` connectors, to open as many connections to the remote, with the parameters of the current session
+    fn make_connectors(&self, n: usize) -> Result<Vec<Connector>, String> {
+        let params: FileTransferParams = match self.context().ft_params() {
+            Some(params) => params.clone(),
+            None => return Err(String::from("connection parameters are not available")),
+        };
+        let proxy = self.proxy_for(&params)?;
+        let tls: Option<TlsOptions> = self.tls_for(&params);
+        let agent_forwarding: bool = self.agent_forwarding_for(&params);
+        let compression: bool = self.compression_for(&params);
+        Ok((0..n)
+            .map(|_| Connector {
+                params: params.clone(),
+                ssh_keys: Self::make_ssh_storage(self.config()),
+                proxy: proxy.clone(),
+                tls: tls.clone(),
+                agent_forwarding,
+                compression,
+                key_passphrase: self.key_passphrase.clone(),
+                banner_acknowledged: self.banner_acknowledged,
+                stall_timeout: self.config().get_stall_timeout(),
+            })
+            .collect())
+    }
+
+    /// ### filetransfer_jobs
+    ///
+    /// Transfer jobs one by one using the main connection
+    fn filetransfer_jobs(&mut self, jobs: Vec<Job>) {
+        for job in jobs.into_iter() {
+            if self.transfer.aborted() {
+                break;
+            }
+            match job {
+                Job::Upload { local, remote } => {
+                    self.filetransfer_send_or_clean(&local, remote.as_path(), false)
+                }
+                Job::Download { remote, local } => {
+                    self.filetransfer_recv_or_clean(&remote, local.as_path(), false)
+                }
+                // Segments are never queued by the recursion
+                Job::Segment { .. } => {}
+            }
+        }
+    }
+
+    /// ### filetransfer_parallel
+    ///
+    /// Transfer jobs using a worker, with its own connection, for each connector.
+    /// The progress bar shows the overall progress and the file started last.
+    /// Returns the jobs left over because workers couldn't connect, along with the failed ones,
+    /// which are transferred again using the main connection, retrying on transient errors
+    fn filetransfer_parallel(&mut self, jobs: Vec<Job>, connectors: Vec<Connector>) -> Vec<Job> {
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Transferring {} files over {} connections",
+                jobs.len(),
+                connectors.len()
+            ),
+        );
+        let pool: WorkerPool = WorkerPool::start(
+            jobs,
+            connectors
+                .into_iter()
+                .map(|connector| move || connector.connect().map_err(|e| e.to_string()))
+                .collect(),
+            self.slow_link(),
+        );
+        let low_bandwidth: bool = self.low_bandwidth();
+        let poll_interval: u128 = Self::input_poll_interval(low_bandwidth);
+        let mut last_input_event_fetch: Option<Instant> = None;
+        let mut last_progress_val: f64 = 0.0;
+        // Worker and name of the file displayed in the progress bar
+        let mut displayed: Option<(usize, String)> = None;
+        // Bytes written by each worker for the job it's transferring
+        let mut written: HashMap<usize, usize> = HashMap::new();
+        let mut failed: Vec<Job> = Vec::new();
+        loop {
+            match pool.recv(Duration::from_millis(poll_interval as u64)) {
+                Ok(WorkerEvent::Connected(id)) => debug!("Transfer worker {} connected", id),
+                Ok(WorkerEvent::ConnectionFailed(id, err)) => {
+                    let err: String = self.mask_password(err);
+                    self.log(
+                        LogLevel::Warn,
+                        format!("Transfer worker {} could not connect: {}", id, err),
+                    );
+                }
+                Ok(WorkerEvent::Started(id, job)) => {
+                    if !matches!(job, Job::Segment { .. }) {
+                        self.transfer.files.start_file();
+                    }
+                    self.transfer.partial.init(job.size());
+                    written.insert(id, 0);
+                    last_progress_val = 0.0;
+                    displayed = Some((id, job.file().name.clone()));
+                }
+                Ok(WorkerEvent::Progress(id, bytes)) => {
+                    self.transfer.full.update_progress(bytes);
+                    *written.entry(id).or_insert(0) += bytes;
+                    if matches!(&displayed, Some((x, _)) if *x == id) {
+                        self.transfer.partial.update_progress(bytes);
+                    }
+                }
+                Ok(WorkerEvent::Done(_, job @ Job::Segment { .. }, result)) => {
+                    if let Err(err) = result {
+                        self.log(
+                            LogLevel::Warn,
+                            format!("Could not download segment of {}: {}", job.file().name, err),
+                        );
+                        failed.push(job);
+                    }
+                }
+                Ok(WorkerEvent::Done(id, job, Err(err))) if !self.transfer.aborted() => {
+                    // Transfer the file again using the main connection, which retries it
+                    self.log(
+                        LogLevel::Warn,
+                        format!("Transfer of \"{}\" failed: {}", job.file().name, err),
+                    );
+                    self.transfer
+                        .full
+                        .rewind(written.remove(&id).unwrap_or_default());
+                    failed.push(job);
+                }
+                Ok(WorkerEvent::Done(id, job, result)) => {
+                    debug!("Transfer worker {} done with {}", id, job.file().name);
+                    self.on_parallel_job_done(job, result)
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            // Handle input events
+            if last_input_event_fetch
+                .map(|x| x.elapsed().as_millis() >= poll_interval)
+                .unwrap_or(true)
+            {
+                self.read_input_event();
+                last_input_event_fetch = Some(Instant::now());
+                if self.transfer.aborted() {
+                    pool.cancel();
+                }
+                pool.set_paused(self.transfer.paused());
+            }
+            // Draw
+            if let Some((_, name)) = displayed.as_ref() {
+                if self.should_redraw_progress(low_bandwidth, last_progress_val) {
+                    self.update_progress_bar(format!("Transferring \"{}\"", name));
+                    self.view();
+                    last_progress_val = self.transfer.partial.calc_progress();
+                }
+            }
+        }
+        let mut remaining: Vec<Job> = pool.join();
+        remaining.append(&mut failed);
+        remaining
+    }
+
+    /// ### segmented_download
+    ///
+    /// Returns whether `file` should be downloaded in segments over many connections
+    fn segmented_download(&self, file: &FsFile) -> bool {
+        self.parallel_transfers() > 1
+            && file.size >= SEGMENTED_DOWNLOAD_MIN_SIZE
+            && self.capabilities.supports(Capability::RangedReads)
+    }
+
+    /// ### filetransfer_segmented
+    ///
+    /// Download a large file in segments, each one over its own connection, writing them into the local file
+    /// at their offset. If any segment fails, the whole file is downloaded again using the main connection
+    fn filetransfer_segmented(&mut self, remote: FsFile, local: PathBuf) {
+        // Make the local file as large as the remote one, so that segments can be written in any order
+        if let Err(err) = self.host.open_file_write(local.as_path()).and_then(|f| {
+            f.set_len(remote.size as u64).map_err(|e| {
+                HostError::new(HostErrorType::FileNotAccessible, Some(e), local.as_path())
+            })
+        }) {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not download file {}: {}", remote.name, err),
+            );
+            return;
+        }
+        let n: usize = self
+            .parallel_transfers()
+            .min(remote.size / SEGMENTED_DOWNLOAD_MIN_SEGMENT_SIZE)
+            .max(1);
+        let segments: Vec<Job> = Job::segments(&remote, local.as_path(), n);
+        let connectors: Vec<Connector> = match self.make_connectors(segments.len()) {
+            Ok(connectors) => connectors,
+            Err(err) => {
+                self.log(
+                    LogLevel::Warn,
+                    format!("Could not start segmented download: {}", err),
+                );
+                self.filetransfer_recv_or_clean(&remote, local.as_path(), false);
+                return;
+            }
+        };
+        let written: usize = self.transfer.full.written();
+        self.transfer.files.start_file();
+        let started: Instant = Instant::now();
+        let failed: Vec<Job> = self.filetransfer_parallel(segments, connectors);
+        if self.transfer.aborted() {
+            self.remove_partial_local(local.as_path());
+        } else if !failed.is_empty() {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "{} segments of {} could not be downloaded; downloading the whole file using the main connection",
+                    failed.len(),
+                    remote.name
+                ),
+            );
+            let segments_written: usize = self.transfer.full.written() - written;
+            self.transfer.full.rewind(segments_written);
+            self.filetransfer_recv_or_clean(&remote, local.as_path(), false);
+        } else {
+            self.on_parallel_job_done(Job::Download { remote, local }, Ok(started.elapsed()));
+        }
+    }
+
+    /// ### on_parallel_job_done
+    ///
+    /// Handle the result of a job transferred by a worker
+    fn on_parallel_job_done(&mut self, job: Job, result: Result<Duration, String>) {
+        match (job, result) {
+            (Job::Upload { local, remote }, Ok(elapsed)) => {
+                self.transfer.files.end_file();
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Saved file \"{}\" to \"{}\" (took {} seconds)",
+                        local.abs_path.display(),
+                        remote.display(),
+                        fmt_millis(elapsed),
+                    ),
+                );
+                self.verify_transferred(local.abs_path.as_path(), remote.as_path());
+                self.transfer.transferred(local.abs_path.as_path());
+            }
+            (Job::Download { remote, local }, Ok(elapsed)) => {
+                self.transfer.files.end_file();
+                // Apply file mode to file
+                #[cfg(any(target_family = "unix", target_os = "macos", target_os = "linux"))]
+                if let Some((owner, group, others)) =
+                    remote.unix_pex.filter(|_| self.options.preserve_mode)
+                {
+                    if let Err(err) = self.host.chmod(
+                        local.as_path(),
+                        (owner.as_byte(), group.as_byte(), others.as_byte()),
+                    ) {
+                        self.log(
+                            LogLevel::Error,
+                            format!(
+                                "Could not apply file mode {:?} to \"{}\": {}",
+                                (owner.as_byte(), group.as_byte(), others.as_byte()),
+                                local.display(),
+                                err
+                            ),
+                        );
+                    }
+                }
+                #[cfg(target_family = "unix")]
+                self.preserve_local_owner(local.as_path(), remote.user, remote.group);
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Saved file \"{}\" to \"{}\" (took {} seconds)",
+                        remote.abs_path.display(),
+                        local.display(),
+                        fmt_millis(elapsed),
+                    ),
+                );
+                self.verify_transferred(local.as_path(), remote.abs_path.as_path());
+                self.transfer.transferred(remote.abs_path.as_path());
+            }
+            (Job::Upload { local, remote }, Err(err)) => {
+                self.transfer.files.fail_file();
+                // Failed jobs are retried by the main connection, unless the transfer has been aborted;
+                // don't alert for each file interrupted by the abort
+                self.log(
+                    LogLevel::Error,
+                    format!("Failed to upload file {}: {}", local.name, err),
+                );
+                self.remove_partial_remote(remote.as_path());
+            }
+            (Job::Download { remote, local }, Err(err)) => {
+                self.transfer.files.fail_file();
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not download file {}: {}", remote.name, err),
+                );
+                self.remove_partial_local(local.as_path());
+            }
+            // Segments are handled by `filetransfer_segmented`
+            (Job::Segment { .. }, _) => {}
+        }
+    }
+
+    /// ### filetransfer_relay
+    ///
+    /// Stream remote entries to the working directory of the peer
+    pub(super) fn filetransfer_relay(&mut self, entries: Vec<FsEntry>) -> Result<(), String> {
+        let dest: PathBuf = match self.peer.as_ref() {
+            Some(peer) => peer.wrkdir.clone(),
+            None => return Err(TransferErrorReason::PeerNotConnected.to_string()),
+        };
+        // Reset states
+        self.transfer.reset();
+        // Calculate total size of transfer
+        let (total_transfer_size, total_files): (usize, usize) = entries
+            .iter()
+            .map(|x| self.get_total_transfer_size_remote(x))
+            .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1));
+        self.transfer.full.init(total_transfer_size);
+        self.transfer.files.init(total_files);
+        // Mount progress bar
+        self.mount_progress_bar(format!("Relaying {} entries to peer…", entries.len()));
+        // Relay recurse
+        entries
+            .iter()
+            .for_each(|x| self.filetransfer_relay_recurse(x, dest.as_path()));
+        // Umount progress bar
+        self.umount_progress_bar();
+        self.report_denied_entries();
+        // Relayed data is downloaded from remote
+        self.account_usage(0, self.transfer.full.written());
+        Ok(())
+    }
+
+    fn filetransfer_relay_recurse(&mut self, entry: &FsEntry, curr_peer_path: &Path) {
+        // Get peer path
+        let mut peer_path: PathBuf = PathBuf::from(curr_peer_path);
+        peer_path.push(entry.get_name());
+        // Match entry
+        match entry {
+            FsEntry::File(file) => {
+                if let Err(err) = self.filetransfer_relay_one(file, peer_path.as_path()) {
+                    // Log error
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!("Failed to relay file {}: {}", file.name, err),
+                    );
+                    // If transfer was abrupted or there was an IO error, remove file from peer
+                    if matches!(
+                        err,
+                        TransferErrorReason::Abrupted | TransferErrorReason::RemoteIoError(_)
+                    ) {
+                        if let Some(peer) = self.peer.as_mut() {
+                            if let Err(err) = peer
+                                .client
+                                .stat(peer_path.as_path())
+                                .and_then(|x| peer.client.remove(&x))
+                            {
+                                self.log(
+                                    LogLevel::Error,
+                                    format!(
+                                        "Could not remove created file {}: {}",
+                                        peer_path.display(),
+                                        err
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+            FsEntry::Directory(dir) => {
+                // Create directory on peer first
+                let result = match self.peer.as_mut() {
+                    Some(peer) => peer.client.mkdir(peer_path.as_path()),
+                    None => return,
+                };
+                match result {
+                    Ok(_) => {
+                        self.log(
+                            LogLevel::Info,
+                            format!("Created directory \"{}\" on peer", peer_path.display()),
+                        );
+                    }
+                    Err(err) if err.kind() == FileTransferErrorType::DirectoryAlreadyExists => {
+                        self.log(
+                            LogLevel::Info,
+                            format!(
+                                "Directory \"{}\" already exists on peer",
+                                peer_path.display()
+                            ),
+                        );
+                    }
+                    Err(err) => {
+                        self.log_and_alert(
+                            LogLevel::Error,
+                            format!(
+                                "Failed to create directory \"{}\" on peer: {}",
+                                peer_path.display(),
+                                err
+                            ),
+                        );
+                        return;
+                    }
+                }
+                // Get files in dir
+                match self.scan_dir_or_deny(dir.abs_path.as_path(), true) {
+                    Ok(entries) => {
+                        // Iterate over files
+                        for entry in entries.iter() {
+                            // If aborted; break
+                            if self.transfer.aborted() {
+                                break;
+                            }
+                            // Skip entries excluded by transfer filters
+                            if !self.is_transferred(entry) {
+                                debug!("Skipping {}: excluded by filters", entry.get_name());
+                                continue;
+                            }
+                            self.filetransfer_relay_recurse(entry, peer_path.as_path());
+                        }
+                    }
+                    Err(err) => {
+                        self.log_and_alert(
+                            LogLevel::Error,
+                            format!(
+                                "Could not scan directory \"{}\": {}",
+                                dir.abs_path.display(),
+                                err
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+        // If aborted; show popup
+        if self.transfer.aborted() {
+            // Log abort
+            self.log_and_alert(
+                LogLevel::Warn,
+                format!("Relay aborted for \"{}\"!", entry.get_abs_path().display()),
+            );
+        }
+    }
+
+    /// ### filetransfer_relay_one
+    ///
+    /// Read remote file and write it to the peer at `dest`
+    fn filetransfer_relay_one(
+        &mut self,
+        remote: &FsFile,
+        dest: &Path,
+    ) -> Result<(), TransferErrorReason> {
+        // Open file on peer first
+        let mut whnd = match self.peer.as_mut() {
+            Some(peer) => peer
+                .client
+                .send_file(remote, dest)
+                .map_err(TransferErrorReason::FileTransferError)?,
+            None => return Err(TransferErrorReason::PeerNotConnected),
+        };
+        let mut rhnd = match self.client.recv_file(remote) {
+            Ok(rhnd) => self.slow_link().stream(rhnd),
+            Err(err) => {
+                if let Some(peer) = self.peer.as_mut() {
+                    let _ = peer.client.on_sent(whnd);
+                }
+                return Err(TransferErrorReason::FileTransferError(err));
+            }
+        };
+        // Init transfer
+        self.transfer.partial.init(remote.size);
+        self.transfer.files.start_file();
+        let mut total_bytes_written: usize = 0;
+        let mut last_progress_val: f64 = 0.0;
+        let mut last_input_event_fetch: Option<Instant> = None;
+        let low_bandwidth: bool = self.low_bandwidth();
+        let mut result: Result<(), TransferErrorReason> = Ok(());
+        // While the entire file hasn't been completely relayed,
+        // Or filetransfer has been aborted
+        while total_bytes_written < remote.size && !self.transfer.aborted() {
+            // Handle input events
+            if last_input_event_fetch.is_none()
+                || last_input_event_fetch
+                    .unwrap_or_else(Instant::now)
+                    .elapsed()
+                    .as_millis()
+                    >= Self::input_poll_interval(low_bandwidth)
+            {
+                // Read events
+                self.read_input_event();
+                // Reset instant
+                last_input_event_fetch = Some(Instant::now());
+                // Hold on while paused
+                self.wait_resumed();
+            }
+            // Read till you can
+            let mut buffer: [u8; 65536] = [0; 65536];
+            let bytes_read: usize = match rhnd.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(bytes_read) => bytes_read,
+                Err(err) => {
+                    result = Err(TransferErrorReason::RemoteIoError(err));
+                    break;
+                }
+            };
+            if let Err(err) = whnd.write_all(&buffer[0..bytes_read]) {
+                result = Err(TransferErrorReason::RemoteIoError(err));
+                break;
+            }
+            total_bytes_written += bytes_read;
+            // Increase progress
+            self.transfer.partial.update_progress(bytes_read);
+            self.transfer.full.update_progress(bytes_read);
+            // Draw only if a significant progress has been made (performance improvement)
+            if self.should_redraw_progress(low_bandwidth, last_progress_val) {
+                // Draw
+                self.update_progress_bar(format!("Relaying \"{}\"…", remote.name));
+                self.view();
+                last_progress_val = self.transfer.partial.calc_progress();
+            }
+        }
+        // Finalize streams
+        if let Err(err) = self.client.on_recv(rhnd.into_inner()) {
+            self.log(
+                LogLevel::Warn,
+                format!("Could not finalize remote stream: \"{}\"", err),
+            );
+        }
+        let finalized = match self.peer.as_mut() {
+            Some(peer) => peer.client.on_sent(whnd),
+            None => Ok(()),
+        };
+        if let Err(err) = finalized {
+            self.log(
+                LogLevel::Warn,
+                format!("Could not finalize peer stream: \"{}\"", err),
+            );
+        }
+        result?;
+        // if relay was abrupted, return error
+        if self.transfer.aborted() {
+            return Err(TransferErrorReason::Abrupted);
+        }
+        self.transfer.files.end_file();
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Relayed file \"{}\" to \"{}\" on peer (took {} seconds; at {}/s)",
+                remote.abs_path.display(),
+                dest.display(),
+                fmt_millis(self.transfer.partial.started().elapsed()),
+                fmt_size(self.transfer.partial.calc_bytes_per_second()),
+            ),
+        );
+        Ok(())
+    }
+
     /// ### local_changedir
     ///
     /// Change directory for local
@@ -935,9 +2895,10 @@ impl FileTransferActivity {
             }
             Err(err) => {
                 // Report err
-                self.log_and_alert(
+                self.log_and_alert_error(
                     LogLevel::Error,
-                    format!("Could not change working directory: {}", err),
+                    "Could not change working directory",
+                    &err,
                 );
             }
         }
@@ -954,7 +2915,7 @@ impl FileTransferActivity {
                     format!("Changed directory on remote: {}", path.display()),
                 );
                 // Update files
-                self.reload_remote_dir();
+                self.remote_load_wrkdir();
                 // Push prev_dir to stack
                 if push {
                     self.remote_mut().pushd(prev_dir.as_path())
@@ -962,9 +2923,10 @@ impl FileTransferActivity {
             }
             Err(err) => {
                 // Report err
-                self.log_and_alert(
+                self.log_and_alert_error(
                     LogLevel::Error,
-                    format!("Could not change working directory: {}", err),
+                    "Could not change working directory",
+                    &err,
                 );
             }
         }
@@ -1001,21 +2963,176 @@ impl FileTransferActivity {
         }
     }
 
+    /// ### filetransfer_fetch
+    ///
+    /// Download the HTTP(S) resource at `url` into `local_dir`, showing the transfer progress.
+    /// Returns the path the resource has been saved to
+    pub(super) fn filetransfer_fetch(
+        &mut self,
+        url: &str,
+        local_dir: &Path,
+    ) -> Result<PathBuf, String> {
+        let fetch: Fetch =
+            Fetch::get(url).map_err(|e| format!("Could not fetch {}: {}", url, e))?;
+        let local: PathBuf = local_dir.join(fetch.name.as_str());
+        if self.host.file_exists(local.as_path()) {
+            return Err(format!("\"{}\" already exists", local.display()));
+        }
+        // Reset states
+        self.transfer.reset();
+        self.transfer.full.init(fetch.size.unwrap_or(0));
+        self.transfer.files.init(1);
+        // Mount progress bar
+        self.mount_progress_bar(format!("Fetching {}…", url));
+        let result = self.filetransfer_fetch_one(fetch, local.as_path());
+        // Umount progress bar
+        self.umount_progress_bar();
+        match result {
+            Ok(()) => Ok(local),
+            Err(err) => {
+                // Don't leave a truncated file behind
+                if let Ok(entry) = self.host.stat(local.as_path()) {
+                    let _ = self.host.remove(&entry);
+                }
+                Err(format!("Could not fetch {}: {}", url, err))
+            }
+        }
+    }
+
+    /// ### filetransfer_fetch_one
+    ///
+    /// Stream the fetched resource to `local`.
+    /// When the server doesn't report the content length, the file is read till the end
+    fn filetransfer_fetch_one(
+        &mut self,
+        fetch: Fetch,
+        local: &Path,
+    ) -> Result<(), TransferErrorReason> {
+        let mut local_file = self
+            .host
+            .open_file_write(local)
+            .map_err(TransferErrorReason::HostError)?;
+        let size: Option<usize> = fetch.size;
+        let name: String = fetch.name.clone();
+        let mut rhnd = self.slow_link().stream(fetch);
+        // Init transfer
+        self.transfer.partial.init(size.unwrap_or(0));
+        self.transfer.files.start_file();
+        let mut last_progress_val: f64 = 0.0;
+        let mut last_input_event_fetch: Option<Instant> = None;
+        let low_bandwidth: bool = self.low_bandwidth();
+        let mut buffer: [u8; 65536] = [0; 65536];
+        while !self.transfer.aborted() {
+            // Handle input events (each 500 ms) or is None
+            if last_input_event_fetch.is_none()
+                || last_input_event_fetch
+                    .unwrap_or_else(Instant::now)
+                    .elapsed()
+                    .as_millis()
+                    >= Self::input_poll_interval(low_bandwidth)
+            {
+                self.read_input_event();
+                last_input_event_fetch = Some(Instant::now());
+                // Hold on while paused
+                self.wait_resumed();
+            }
+            let bytes_read: usize = rhnd
+                .read(&mut buffer)
+                .map_err(TransferErrorReason::RemoteIoError)?;
+            if bytes_read == 0 {
+                break;
+            }
+            local_file
+                .write_all(&buffer[..bytes_read])
+                .map_err(TransferErrorReason::LocalIoError)?;
+            // Set progress
+            self.transfer.partial.update_progress(bytes_read);
+            self.transfer.full.update_progress(bytes_read);
+            // Without a content length, the progress can't be told; redraw at regular intervals
+            let redraw: bool = match size {
+                Some(_) => self.should_redraw_progress(low_bandwidth, last_progress_val),
+                None => self.transfer.redraw_due(LOW_BANDWIDTH_REDRAW_INTERVAL),
+            };
+            if redraw {
+                self.update_progress_bar(format!("Downloading \"{}\"", name));
+                self.view();
+                last_progress_val = self.transfer.partial.calc_progress();
+            }
+        }
+        if self.transfer.aborted() {
+            return Err(TransferErrorReason::Abrupted);
+        }
+        self.transfer.files.end_file();
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Saved \"{}\" to \"{}\" (took {} seconds; at {}/s)",
+                name,
+                local.display(),
+                fmt_millis(self.transfer.partial.started().elapsed()),
+                fmt_size(self.transfer.partial.calc_bytes_per_second()),
+            ),
+        );
+        Ok(())
+    }
+
+    // -- refresh rates
+
+    /// ### input_poll_interval
+    ///
+    /// Get interval in milliseconds between input event polls while transferring
+    pub(super) fn input_poll_interval(low_bandwidth: bool) -> u128 {
+        match low_bandwidth {
+            true => LOW_BANDWIDTH_INPUT_POLL_INTERVAL,
+            false => INPUT_POLL_INTERVAL,
+        }
+    }
+
+    /// ### should_redraw_progress
+    ///
+    /// Returns whether the progress bar should be redrawn.
+    /// In low bandwidth mode the progress bar is redrawn at most once per
+    /// `LOW_BANDWIDTH_REDRAW_INTERVAL`, otherwise each time the current file has progressed by 1%
+    pub(super) fn should_redraw_progress(
+        &mut self,
+        low_bandwidth: bool,
+        last_progress_val: f64,
+    ) -> bool {
+        // Always repaint after resize or reattach
+        if self.context_mut().refresh_terminal_size() {
+            return true;
+        }
+        match low_bandwidth {
+            true => self.transfer.redraw_due(LOW_BANDWIDTH_REDRAW_INTERVAL),
+            false => last_progress_val < self.transfer.partial.calc_progress() - 0.01,
+        }
+    }
+
     // -- transfer sizes
 
     /// ### get_total_transfer_size_local
     ///
-    /// Get total size of transfer for localhost
-    fn get_total_transfer_size_local(&mut self, entry: &FsEntry) -> usize {
+    /// Get total size of transfer and amount of files for localhost
+    fn get_total_transfer_size_local(&mut self, entry: &FsEntry) -> (usize, usize) {
+        if self.transfer.is_excluded(entry.get_abs_path().as_path()) {
+            return (0, 0);
+        }
+        // Only followed links transfer data
+        if entry.is_symlink() && self.symlink_mode() != SymlinkMode::Follow {
+            return (0, 0);
+        }
         match entry {
-            FsEntry::File(file) => file.size,
+            FsEntry::File(file) => (file.size, 1),
             FsEntry::Directory(dir) => {
                 // List dir
-                match self.host.scan_dir(dir.abs_path.as_path()) {
+                match self.scan_dir_or_deny(dir.abs_path.as_path(), false) {
                     Ok(files) => files
                         .iter()
-                        .map(|x| self.get_total_transfer_size_local(x))
-                        .sum(),
+                        .map(|x| match self.is_transferred(x) {
+                            true => self.get_total_transfer_size_local(x),
+                            false => (0, 0),
+                        })
+                        .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1)),
                     Err(err) => {
                         self.log(
                             LogLevel::Error,
@@ -1025,7 +3142,7 @@ impl FileTransferActivity {
                                 err
                             ),
                         );
-                        0
+                        (0, 0)
                     }
                 }
             }
@@ -1034,17 +3151,27 @@ impl FileTransferActivity {
 
     /// ### get_total_transfer_size_remote
     ///
-    /// Get total size of transfer for remote host
-    fn get_total_transfer_size_remote(&mut self, entry: &FsEntry) -> usize {
+    /// Get total size of transfer and amount of files for remote host
+    fn get_total_transfer_size_remote(&mut self, entry: &FsEntry) -> (usize, usize) {
+        if self.transfer.is_excluded(entry.get_abs_path().as_path()) {
+            return (0, 0);
+        }
+        // Only followed links transfer data
+        if entry.is_symlink() && self.symlink_mode() != SymlinkMode::Follow {
+            return (0, 0);
+        }
         match entry {
-            FsEntry::File(file) => file.size,
+            FsEntry::File(file) => (file.size, 1),
             FsEntry::Directory(dir) => {
                 // List directory
-                match self.client.list_dir(dir.abs_path.as_path()) {
+                match self.scan_dir_or_deny(dir.abs_path.as_path(), true) {
                     Ok(files) => files
                         .iter()
-                        .map(|x| self.get_total_transfer_size_remote(x))
-                        .sum(),
+                        .map(|x| match self.is_transferred(x) {
+                            true => self.get_total_transfer_size_remote(x),
+                            false => (0, 0),
+                        })
+                        .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1)),
                     Err(err) => {
                         self.log(
                             LogLevel::Error,
@@ -1054,7 +3181,7 @@ impl FileTransferActivity {
                                 err
                             ),
                         );
-                        0
+                        (0, 0)
                     }
                 }
             }
@@ -27,22 +27,38 @@
  */
 // locals
 use super::{
-    actions::SelectedEntry, browser::FileExplorerTab, FileTransferActivity, LogLevel,
-    COMPONENT_EXPLORER_FIND, COMPONENT_EXPLORER_LOCAL, COMPONENT_EXPLORER_REMOTE,
-    COMPONENT_INPUT_COPY, COMPONENT_INPUT_EXEC, COMPONENT_INPUT_FIND, COMPONENT_INPUT_GOTO,
-    COMPONENT_INPUT_MKDIR, COMPONENT_INPUT_NEWFILE, COMPONENT_INPUT_OPEN_WITH,
-    COMPONENT_INPUT_RENAME, COMPONENT_INPUT_SAVEAS, COMPONENT_LIST_FILEINFO, COMPONENT_LOG_BOX,
-    COMPONENT_PROGRESS_BAR_FULL, COMPONENT_PROGRESS_BAR_PARTIAL, COMPONENT_RADIO_DELETE,
-    COMPONENT_RADIO_DISCONNECT, COMPONENT_RADIO_QUIT, COMPONENT_RADIO_SORTING,
-    COMPONENT_TEXT_ERROR, COMPONENT_TEXT_FATAL, COMPONENT_TEXT_HELP,
+    actions::SelectedEntry, browser::FileExplorerTab, lib::hexdump, ConflictAction,
+    DryRunOperation, FileTransferActivity, LogLevel, StallAction, COMPONENT_EXPLORER_FIND,
+    COMPONENT_EXPLORER_LOCAL, COMPONENT_EXPLORER_REMOTE, COMPONENT_INPUT_CHMOD_DIR,
+    COMPONENT_INPUT_CHMOD_FILE, COMPONENT_INPUT_CHOWN, COMPONENT_INPUT_COPY, COMPONENT_INPUT_EXEC,
+    COMPONENT_INPUT_FETCH, COMPONENT_INPUT_FIND, COMPONENT_INPUT_GOTO,
+    COMPONENT_INPUT_HEXDUMP_OFFSET, COMPONENT_INPUT_KEY_PASSPHRASE,
+    COMPONENT_INPUT_MANIFEST_FILTER, COMPONENT_INPUT_MKDIR, COMPONENT_INPUT_NEWFILE,
+    COMPONENT_INPUT_OPEN_WITH, COMPONENT_INPUT_OPTS_BANDWIDTH, COMPONENT_INPUT_OPTS_EXCLUDE,
+    COMPONENT_INPUT_PEER, COMPONENT_INPUT_PEER_PASSWORD, COMPONENT_INPUT_RENAME,
+    COMPONENT_INPUT_SAVEAS, COMPONENT_LIST_DRIFT, COMPONENT_LIST_DRY_RUN, COMPONENT_LIST_FAVORITES,
+    COMPONENT_LIST_FILEINFO, COMPONENT_LIST_MANIFEST, COMPONENT_LIST_USAGE, COMPONENT_LOG_BOX,
+    COMPONENT_PROGRESS_BAR, COMPONENT_RADIO_CHMOD_RECURSIVE, COMPONENT_RADIO_CONFLICT,
+    COMPONENT_RADIO_DELETE, COMPONENT_RADIO_DISCONNECT, COMPONENT_RADIO_JOURNAL,
+    COMPONENT_RADIO_KEY_PASSPHRASE_CACHE, COMPONENT_RADIO_OPTS_CONFLICT,
+    COMPONENT_RADIO_OPTS_PRESERVE, COMPONENT_RADIO_OPTS_VERIFY, COMPONENT_RADIO_PASTE,
+    COMPONENT_RADIO_PURGE_TRASH, COMPONENT_RADIO_QUIT, COMPONENT_RADIO_SORTING,
+    COMPONENT_RADIO_STALLED, COMPONENT_RULES_EDITOR, COMPONENT_TEXT_AUTH_BANNER,
+    COMPONENT_TEXT_BANNER, COMPONENT_TEXT_ERROR, COMPONENT_TEXT_FATAL, COMPONENT_TEXT_HELP,
+    COMPONENT_TEXT_HEXDUMP,
 };
+use crate::filetransfer::Capability;
 use crate::fs::explorer::FileSorting;
 use crate::fs::FsEntry;
-use crate::ui::components::{file_list::FileListPropsBuilder, logbox::LogboxPropsBuilder};
+use crate::ui::components::{
+    data_table::DataTablePropsBuilder,
+    file_list::FileListPropsBuilder,
+    log_list::LogListPropsBuilder,
+    transfer_progress::{FilesProgress, Progress, TransferProgressPropsBuilder},
+};
 use crate::ui::keymap::*;
-use crate::utils::fmt::fmt_path_elide_ex;
+use crate::utils::fmt::{display_format, fmt_path_elide_ex};
 // externals
-use tui_realm_stdlib::progress_bar::ProgressBarPropsBuilder;
 use tuirealm::{
     props::{Alignment, PropsBuilder, TableBuilder, TextSpan},
     tui::style::Color,
@@ -65,7 +81,7 @@ impl Update for FileTransferActivity {
                 // -- local tab
                 (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_RIGHT => {
                     // Change tab
-                    self.view.active(COMPONENT_EXPLORER_REMOTE);
+                    self.focus_panel(COMPONENT_EXPLORER_REMOTE);
                     self.browser.change_tab(FileExplorerTab::Remote);
                     None
                 }
@@ -100,8 +116,24 @@ impl Update for FileTransferActivity {
                 }
                 (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_SPACE => {
                     self.action_local_send();
+                    // Marks may have been consumed
+                    let _ = self.update_local_filelist();
+                    self.update_remote_filelist()
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_SHIFT_S => {
+                    self.action_local_move();
+                    // Moved files have been removed
+                    let _ = self.update_local_filelist();
                     self.update_remote_filelist()
                 }
+                (COMPONENT_EXPLORER_LOCAL, key)
+                    if key == &MSG_KEY_CHAR_M || key == &MSG_KEY_CTRL_A =>
+                {
+                    // Keep track of marked files
+                    self.action_local_mark();
+                    // Reload file list component
+                    self.update_local_filelist()
+                }
                 (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_CHAR_A => {
                     // Toggle hidden files
                     self.local_mut().toggle_hidden_files();
@@ -112,12 +144,26 @@ impl Update for FileTransferActivity {
                 }
                 (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_CHAR_I => {
                     if let SelectedEntry::One(file) = self.get_local_selected_entries() {
-                        self.mount_file_info(&file);
+                        let links: Option<u64> = match file.is_dir() {
+                            true => None,
+                            false => self
+                                .host
+                                .hard_links(file.get_abs_path().as_path())
+                                .map(|(_, links)| links),
+                        };
+                        self.mount_file_info(&file, links);
                     }
                     None
                 }
+                (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_CHAR_P => {
+                    // Jump remote to the directory matching the local one
+                    self.action_jump_remote_to_mapped_dir();
+                    // Reload file list component
+                    self.update_remote_filelist()
+                }
                 (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_CHAR_L => {
-                    // Reload directory
+                    // Reload directory and clear marks
+                    self.local_mut().clear_marks();
                     self.reload_local_dir();
                     // Reload file list component
                     self.update_local_filelist()
@@ -138,7 +184,7 @@ impl Update for FileTransferActivity {
                 // -- remote tab
                 (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_LEFT => {
                     // Change tab
-                    self.view.active(COMPONENT_EXPLORER_LOCAL);
+                    self.focus_panel(COMPONENT_EXPLORER_LOCAL);
                     self.browser.change_tab(FileExplorerTab::Local);
                     None
                 }
@@ -164,8 +210,24 @@ impl Update for FileTransferActivity {
                 }
                 (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_SPACE => {
                     self.action_remote_recv();
+                    // Marks may have been consumed
+                    let _ = self.update_remote_filelist();
                     self.update_local_filelist()
                 }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_SHIFT_S => {
+                    self.action_remote_move();
+                    // Moved files have been removed
+                    let _ = self.update_remote_filelist();
+                    self.update_local_filelist()
+                }
+                (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_CHAR_M || key == &MSG_KEY_CTRL_A =>
+                {
+                    // Keep track of marked files
+                    self.action_remote_mark();
+                    // Reload file list component
+                    self.update_remote_filelist()
+                }
                 (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_BACKSPACE => {
                     // Go to previous directory
                     self.action_go_to_previous_remote_dir(false);
@@ -186,12 +248,55 @@ impl Update for FileTransferActivity {
                 }
                 (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_CHAR_I => {
                     if let SelectedEntry::One(file) = self.get_remote_selected_entries() {
-                        self.mount_file_info(&file);
+                        self.mount_file_info(&file, None);
+                    }
+                    None
+                }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_CHAR_J => {
+                    // Show favorites
+                    self.mount_favorites();
+                    None
+                }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_CHAR_K => {
+                    // Add working directory to favorites
+                    self.action_add_remote_favorite();
+                    None
+                }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_SHIFT_X => {
+                    match self.peer.is_some() {
+                        true => {
+                            // Relay selected entries to peer
+                            self.action_relay_to_peer();
+                            // Marks may have been consumed
+                            self.update_remote_filelist()
+                        }
+                        false => {
+                            // Ask for peer address
+                            self.mount_peer();
+                            None
+                        }
+                    }
+                }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_SHIFT_D => {
+                    match self.remote_trash() {
+                        Some(_) => self.mount_radio_purge_trash(),
+                        None => self.mount_error("No remote trash is set for this host"),
                     }
                     None
                 }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_CTRL_X => {
+                    self.action_disconnect_peer();
+                    None
+                }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_CHAR_P => {
+                    // Jump local to the directory matching the remote one
+                    self.action_jump_local_to_mapped_dir();
+                    // Reload file list component
+                    self.update_local_filelist()
+                }
                 (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_CHAR_L => {
-                    // Reload directory
+                    // Reload directory and clear marks
+                    self.remote_mut().clear_marks();
                     self.reload_remote_dir();
                     // Reload file list component
                     self.update_remote_filelist()
@@ -210,6 +315,16 @@ impl Update for FileTransferActivity {
                     // Reload file list component
                     self.update_remote_filelist()
                 }
+                (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_CHAR_Z => {
+                    // Duplicate selected entries
+                    self.action_local_duplicate();
+                    self.update_local_filelist()
+                }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_CHAR_Z => {
+                    // Duplicate selected entries
+                    self.action_remote_duplicate();
+                    self.update_remote_filelist()
+                }
                 // -- common explorer keys
                 (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
                     if key == &MSG_KEY_CHAR_B =>
@@ -248,6 +363,12 @@ impl Update for FileTransferActivity {
                     self.mount_help();
                     None
                 }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_F2 =>
+                {
+                    self.mount_session_options();
+                    None
+                }
                 (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
                     if key == &MSG_KEY_CHAR_N =>
                 {
@@ -262,6 +383,82 @@ impl Update for FileTransferActivity {
                     self.mount_quit();
                     None
                 }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_CHAR_T =>
+                {
+                    // Show transfer filters editor
+                    self.mount_rules_editor();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_T =>
+                {
+                    // Trace next transfer
+                    self.action_toggle_trace();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_V =>
+                {
+                    // Verify trees
+                    self.action_verify();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_P =>
+                {
+                    // Map working directories to each other
+                    self.action_set_root_mapping();
+                    None
+                }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_SHIFT_A => {
+                    // Toggle agent forwarding for exec
+                    self.action_toggle_agent_forwarding();
+                    None
+                }
+                (COMPONENT_EXPLORER_REMOTE, key) if key == &MSG_KEY_SHIFT_E => {
+                    // Save remote working directory as entry directory
+                    self.action_set_entry_directory();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) if key == &MSG_KEY_SHIFT_E => {
+                    // Save local working directory as local entry directory
+                    self.action_set_local_entry_directory();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_W =>
+                {
+                    // Upload local changes as they happen
+                    self.action_toggle_watch();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_U =>
+                {
+                    // Show bandwidth usage
+                    self.action_show_usage();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_C =>
+                {
+                    // Copy ssh command for remote directory
+                    self.copy_ssh_command();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_Q =>
+                {
+                    self.toggle_macro_recording();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_AT =>
+                {
+                    self.replay_macro();
+                    None
+                }
                 (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
                     if key == &MSG_KEY_CHAR_R =>
                 {
@@ -306,7 +503,11 @@ impl Update for FileTransferActivity {
                     if key == &MSG_KEY_CHAR_X =>
                 {
                     // Mount exec
-                    self.mount_exec();
+                    if matches!(self.browser.tab(), FileExplorerTab::Local)
+                        || self.check_capability(Capability::Exec, "Execute command")
+                    {
+                        self.mount_exec();
+                    }
                     None
                 }
                 (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
@@ -334,6 +535,42 @@ impl Update for FileTransferActivity {
                     self.mount_radio_delete();
                     None
                 }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_G =>
+                {
+                    // Fetch URL into local directory
+                    self.mount_fetch();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_O =>
+                {
+                    self.action_toggle_download_dir();
+                    None
+                }
+                (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
+                    if key == &MSG_KEY_SHIFT_M =>
+                {
+                    let selected: SelectedEntry = match self.browser.tab() {
+                        FileExplorerTab::Local => self.get_local_selected_entries(),
+                        FileExplorerTab::Remote
+                            if self.check_capability(Capability::SetStat, "Change permissions") =>
+                        {
+                            self.get_remote_selected_entries()
+                        }
+                        _ => SelectedEntry::None,
+                    };
+                    match selected {
+                        SelectedEntry::One(entry) => self.mount_chmod(&entry),
+                        SelectedEntry::Many(entries) => {
+                            if let Some(entry) = entries.first() {
+                                self.mount_chmod(entry);
+                            }
+                        }
+                        SelectedEntry::None => {}
+                    }
+                    None
+                }
                 // -- find result explorer
                 (COMPONENT_EXPLORER_FIND, key) if key == &MSG_KEY_ESC => {
                     // Umount find
@@ -371,12 +608,29 @@ impl Update for FileTransferActivity {
                 (COMPONENT_EXPLORER_LOCAL, key) | (COMPONENT_EXPLORER_REMOTE, key)
                     if key == &MSG_KEY_TAB =>
                 {
-                    self.view.active(COMPONENT_LOG_BOX); // Active log box
+                    self.focus_panel(COMPONENT_LOG_BOX); // Active log box
                     None
                 }
                 // -- Log box
                 (COMPONENT_LOG_BOX, key) if key == &MSG_KEY_TAB => {
-                    self.view.blur(); // Blur log box
+                    // Give focus back to the explorer of the current tab
+                    match self.browser.tab() {
+                        FileExplorerTab::Remote | FileExplorerTab::FindRemote => {
+                            self.focus_panel(COMPONENT_EXPLORER_REMOTE)
+                        }
+                        FileExplorerTab::Local | FileExplorerTab::FindLocal => {
+                            self.focus_panel(COMPONENT_EXPLORER_LOCAL)
+                        }
+                    }
+                    None
+                }
+                (COMPONENT_LOG_BOX, key) if key == &MSG_KEY_CHAR_Y => {
+                    // Copy selected record to clipboard
+                    if let Some(Payload::One(Value::Usize(idx))) =
+                        self.view.get_state(COMPONENT_LOG_BOX)
+                    {
+                        self.copy_log_record(idx);
+                    }
                     None
                 }
                 // -- copy popup
@@ -408,6 +662,7 @@ impl Update for FileTransferActivity {
                     None
                 }
                 (COMPONENT_INPUT_EXEC, Msg::OnSubmit(Payload::One(Value::Str(input)))) => {
+                    self.history.push(COMPONENT_INPUT_EXEC, input);
                     // Exex command
                     self.umount_exec();
                     self.mount_blocking_wait(format!("Executing '{}'…", input).as_str());
@@ -425,12 +680,200 @@ impl Update for FileTransferActivity {
                     }
                 }
                 (COMPONENT_INPUT_EXEC, _) => None,
+                // -- fetch popup
+                (COMPONENT_INPUT_FETCH, key) if key == &MSG_KEY_ESC => {
+                    self.umount_fetch();
+                    None
+                }
+                (COMPONENT_INPUT_FETCH, Msg::OnSubmit(Payload::One(Value::Str(input)))) => {
+                    self.history.push(COMPONENT_INPUT_FETCH, input);
+                    self.umount_fetch();
+                    self.action_fetch_url(input.to_string());
+                    self.update_local_filelist()
+                }
+                (COMPONENT_INPUT_FETCH, _) => None,
+                // -- peer popups
+                (COMPONENT_INPUT_PEER, key) if key == &MSG_KEY_ESC => {
+                    self.umount_peer();
+                    None
+                }
+                (COMPONENT_INPUT_PEER, Msg::OnSubmit(Payload::One(Value::Str(input)))) => {
+                    self.history.push(COMPONENT_INPUT_PEER, input);
+                    self.umount_peer();
+                    // Ask for password if address is valid
+                    if self.action_set_peer_address(input) {
+                        self.mount_peer_password();
+                    }
+                    None
+                }
+                (COMPONENT_INPUT_PEER, _) => None,
+                (COMPONENT_INPUT_PEER_PASSWORD, key) if key == &MSG_KEY_ESC => {
+                    self.umount_peer_password();
+                    self.peer_params = None;
+                    None
+                }
+                (
+                    COMPONENT_INPUT_PEER_PASSWORD,
+                    Msg::OnSubmit(Payload::One(Value::Str(password))),
+                ) => {
+                    self.umount_peer_password();
+                    self.mount_blocking_wait("Connecting to peer…");
+                    self.action_connect_peer(password.to_string());
+                    self.umount_wait();
+                    None
+                }
+                (COMPONENT_INPUT_PEER_PASSWORD, _) => None,
+                // -- change permissions popup
+                (COMPONENT_INPUT_CHMOD_FILE, key)
+                | (COMPONENT_INPUT_CHMOD_DIR, key)
+                | (COMPONENT_INPUT_CHOWN, key)
+                | (COMPONENT_RADIO_CHMOD_RECURSIVE, key)
+                    if key == &MSG_KEY_ESC =>
+                {
+                    self.umount_chmod();
+                    None
+                }
+                (COMPONENT_INPUT_CHMOD_FILE, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_INPUT_CHMOD_DIR);
+                    None
+                }
+                (COMPONENT_INPUT_CHMOD_DIR, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_INPUT_CHOWN);
+                    None
+                }
+                (COMPONENT_INPUT_CHOWN, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_RADIO_CHMOD_RECURSIVE);
+                    None
+                }
+                (COMPONENT_RADIO_CHMOD_RECURSIVE, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_INPUT_CHMOD_FILE);
+                    None
+                }
+                (COMPONENT_INPUT_CHMOD_FILE, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_CHMOD_DIR, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_CHOWN, Msg::OnSubmit(_))
+                | (COMPONENT_RADIO_CHMOD_RECURSIVE, Msg::OnSubmit(_)) => {
+                    let opts = self.get_chmod_options();
+                    self.umount_chmod();
+                    match opts {
+                        Err(err) => {
+                            self.mount_error(err.as_str());
+                            None
+                        }
+                        Ok(opts) => match self.browser.tab() {
+                            FileExplorerTab::Local => {
+                                self.action_local_chmod(opts);
+                                self.update_local_filelist()
+                            }
+                            FileExplorerTab::Remote => {
+                                self.action_remote_chmod(opts);
+                                self.update_remote_filelist()
+                            }
+                            _ => None,
+                        },
+                    }
+                }
+                (COMPONENT_INPUT_CHMOD_FILE, _)
+                | (COMPONENT_INPUT_CHMOD_DIR, _)
+                | (COMPONENT_INPUT_CHOWN, _)
+                | (COMPONENT_RADIO_CHMOD_RECURSIVE, _) => None,
+                // -- session options popup
+                (COMPONENT_RADIO_OPTS_PRESERVE, key)
+                | (COMPONENT_RADIO_OPTS_CONFLICT, key)
+                | (COMPONENT_INPUT_OPTS_BANDWIDTH, key)
+                | (COMPONENT_INPUT_OPTS_EXCLUDE, key)
+                | (COMPONENT_RADIO_OPTS_VERIFY, key)
+                    if key == &MSG_KEY_ESC =>
+                {
+                    self.umount_session_options();
+                    None
+                }
+                (COMPONENT_RADIO_OPTS_PRESERVE, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_RADIO_OPTS_CONFLICT);
+                    None
+                }
+                (COMPONENT_RADIO_OPTS_CONFLICT, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_INPUT_OPTS_BANDWIDTH);
+                    None
+                }
+                (COMPONENT_INPUT_OPTS_BANDWIDTH, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_INPUT_OPTS_EXCLUDE);
+                    None
+                }
+                (COMPONENT_INPUT_OPTS_EXCLUDE, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_RADIO_OPTS_VERIFY);
+                    None
+                }
+                (COMPONENT_RADIO_OPTS_VERIFY, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_RADIO_OPTS_PRESERVE);
+                    None
+                }
+                (COMPONENT_RADIO_OPTS_PRESERVE, Msg::OnSubmit(_))
+                | (COMPONENT_RADIO_OPTS_CONFLICT, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_OPTS_BANDWIDTH, Msg::OnSubmit(_))
+                | (COMPONENT_INPUT_OPTS_EXCLUDE, Msg::OnSubmit(_))
+                | (COMPONENT_RADIO_OPTS_VERIFY, Msg::OnSubmit(_)) => {
+                    match self.get_session_options() {
+                        // Keep the popup open if values are invalid
+                        Err(err) => self.mount_error(err.as_str()),
+                        Ok(opts) => {
+                            self.umount_session_options();
+                            self.action_set_session_options(opts);
+                        }
+                    }
+                    None
+                }
+                (COMPONENT_RADIO_OPTS_PRESERVE, _)
+                | (COMPONENT_RADIO_OPTS_CONFLICT, _)
+                | (COMPONENT_INPUT_OPTS_BANDWIDTH, _)
+                | (COMPONENT_INPUT_OPTS_EXCLUDE, _)
+                | (COMPONENT_RADIO_OPTS_VERIFY, _) => None,
+                // -- key passphrase popup
+                (COMPONENT_INPUT_KEY_PASSPHRASE, key)
+                | (COMPONENT_RADIO_KEY_PASSPHRASE_CACHE, key)
+                    if key == &MSG_KEY_ESC =>
+                {
+                    self.umount_key_passphrase();
+                    let key: String = self.passphrase_key.take().unwrap_or_default();
+                    self.mount_fatal(
+                        format!("A passphrase is required to decrypt key \"{}\"", key).as_str(),
+                    );
+                    None
+                }
+                (COMPONENT_INPUT_KEY_PASSPHRASE, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_RADIO_KEY_PASSPHRASE_CACHE);
+                    None
+                }
+                (COMPONENT_RADIO_KEY_PASSPHRASE_CACHE, key) if key == &MSG_KEY_TAB => {
+                    self.focus_popup(COMPONENT_INPUT_KEY_PASSPHRASE);
+                    None
+                }
+                (COMPONENT_INPUT_KEY_PASSPHRASE, Msg::OnSubmit(_))
+                | (COMPONENT_RADIO_KEY_PASSPHRASE_CACHE, Msg::OnSubmit(_)) => {
+                    let passphrase: String =
+                        match self.view.get_state(COMPONENT_INPUT_KEY_PASSPHRASE) {
+                            Some(Payload::One(Value::Str(passphrase))) => passphrase,
+                            _ => String::new(),
+                        };
+                    // Remember is the first option
+                    let remember: bool = matches!(
+                        self.view.get_state(COMPONENT_RADIO_KEY_PASSPHRASE_CACHE),
+                        Some(Payload::One(Value::Usize(0)))
+                    );
+                    // Connection is retried at the next draw
+                    self.submit_key_passphrase(passphrase, remember);
+                    None
+                }
+                (COMPONENT_INPUT_KEY_PASSPHRASE, _) | (COMPONENT_RADIO_KEY_PASSPHRASE_CACHE, _) => {
+                    None
+                }
                 // -- find popup
                 (COMPONENT_INPUT_FIND, key) if key == &MSG_KEY_ESC => {
                     self.umount_find_input();
                     None
                 }
                 (COMPONENT_INPUT_FIND, Msg::OnSubmit(Payload::One(Value::Str(input)))) => {
+                    self.history.push(COMPONENT_INPUT_FIND, input);
                     self.umount_find_input();
                     // Find
                     let res: Result<Vec<FsEntry>, String> = match self.browser.tab() {
@@ -466,6 +909,7 @@ impl Update for FileTransferActivity {
                     None
                 }
                 (COMPONENT_INPUT_GOTO, Msg::OnSubmit(Payload::One(Value::Str(input)))) => {
+                    self.history.push(COMPONENT_INPUT_GOTO, input);
                     match self.browser.tab() {
                         FileExplorerTab::Local => {
                             self.action_change_local_dir(input.to_string(), false)
@@ -520,8 +964,12 @@ impl Update for FileTransferActivity {
                 }
                 (COMPONENT_INPUT_NEWFILE, Msg::OnSubmit(Payload::One(Value::Str(input)))) => {
                     match self.browser.tab() {
-                        FileExplorerTab::Local => self.action_local_newfile(input.to_string()),
-                        FileExplorerTab::Remote => self.action_remote_newfile(input.to_string()),
+                        FileExplorerTab::Local => {
+                            self.action_local_newfile(input.to_string());
+                        }
+                        FileExplorerTab::Remote => {
+                            self.action_remote_newfile(input.to_string());
+                        }
                         _ => panic!("Found tab doesn't support NEWFILE"),
                     }
                     self.umount_newfile();
@@ -532,6 +980,26 @@ impl Update for FileTransferActivity {
                         _ => None,
                     }
                 }
+                (COMPONENT_INPUT_NEWFILE, key) if key == &MSG_KEY_CTRL_E => {
+                    // Create file and open it in editor
+                    match self.view.get_state(COMPONENT_INPUT_NEWFILE) {
+                        Some(Payload::One(Value::Str(input))) if !input.is_empty() => {
+                            self.umount_newfile();
+                            match self.browser.tab() {
+                                FileExplorerTab::Local => {
+                                    self.action_local_newfile_and_edit(input);
+                                    self.update_local_filelist()
+                                }
+                                FileExplorerTab::Remote => {
+                                    self.action_remote_newfile_and_edit(input);
+                                    self.update_remote_filelist()
+                                }
+                                _ => panic!("Found tab doesn't support NEWFILE"),
+                            }
+                        }
+                        _ => None,
+                    }
+                }
                 (COMPONENT_INPUT_NEWFILE, _) => None,
                 // -- open with
                 (COMPONENT_INPUT_OPEN_WITH, key) if key == &MSG_KEY_ESC => {
@@ -556,6 +1024,7 @@ impl Update for FileTransferActivity {
                     None
                 }
                 (COMPONENT_INPUT_RENAME, Msg::OnSubmit(Payload::One(Value::Str(input)))) => {
+                    self.history.push(COMPONENT_INPUT_RENAME, input);
                     self.umount_rename();
                     self.mount_blocking_wait("Moving file(s)…");
                     match self.browser.tab() {
@@ -587,6 +1056,16 @@ impl Update for FileTransferActivity {
                         }
                     }
                     self.umount_saveas();
+                    // Marks may have been consumed
+                    match self.browser.tab() {
+                        FileExplorerTab::Local => {
+                            let _ = self.update_local_filelist();
+                        }
+                        FileExplorerTab::Remote => {
+                            let _ = self.update_remote_filelist();
+                        }
+                        _ => {}
+                    }
                     // Reload files
                     match self.browser.tab() {
                         // NOTE: Swapped is intentional
@@ -597,10 +1076,226 @@ impl Update for FileTransferActivity {
                     }
                 }
                 (COMPONENT_INPUT_SAVEAS, _) => None,
-                // -- fileinfo
-                (COMPONENT_LIST_FILEINFO, key) | (COMPONENT_LIST_FILEINFO, key)
-                    if key == &MSG_KEY_ENTER || key == &MSG_KEY_ESC =>
+                // -- favorites
+                (COMPONENT_LIST_FAVORITES, Msg::OnSubmit(Payload::One(Value::Usize(idx)))) => {
+                    self.umount_favorites();
+                    self.action_enter_remote_favorite(*idx);
+                    // Reload file list component
+                    self.update_remote_filelist()
+                }
+                (COMPONENT_LIST_FAVORITES, key)
+                    if key == &MSG_KEY_DEL || key == &MSG_KEY_CHAR_E =>
                 {
+                    if let Some(Payload::One(Value::Usize(idx))) =
+                        self.view.get_state(COMPONENT_LIST_FAVORITES)
+                    {
+                        self.action_del_remote_favorite(idx);
+                        // Reload favorites
+                        self.umount_favorites();
+                        self.mount_favorites();
+                    }
+                    None
+                }
+                (COMPONENT_LIST_FAVORITES, key) if key == &MSG_KEY_ESC => {
+                    self.umount_favorites();
+                    None
+                }
+                (COMPONENT_LIST_FAVORITES, _) => None,
+                // -- transfer filters
+                (COMPONENT_RULES_EDITOR, Msg::OnSubmit(Payload::Vec(rules))) => {
+                    let rules: Vec<String> = rules
+                        .iter()
+                        .filter_map(|x| match x {
+                            Value::Str(rule) => Some(rule.clone()),
+                            _ => None,
+                        })
+                        .collect();
+                    self.umount_rules_editor();
+                    self.action_set_transfer_filter(rules);
+                    None
+                }
+                (COMPONENT_RULES_EDITOR, key) if key == &MSG_KEY_ESC => {
+                    self.umount_rules_editor();
+                    None
+                }
+                (COMPONENT_RULES_EDITOR, _) => None,
+                // -- drift report
+                (COMPONENT_LIST_DRIFT, key) if key == &MSG_KEY_CHAR_D => {
+                    if let Some(Payload::One(Value::Usize(row))) =
+                        self.view.get_state(COMPONENT_LIST_DRIFT)
+                    {
+                        self.action_diff_drift(row);
+                    }
+                    None
+                }
+                (COMPONENT_LIST_DRIFT, key) if key == &MSG_KEY_CHAR_E => {
+                    self.action_export_drift_report();
+                    None
+                }
+                (COMPONENT_LIST_DRIFT, Msg::OnSubmit(_)) => {
+                    self.umount_drift_report();
+                    None
+                }
+                (COMPONENT_LIST_DRIFT, key) if key == &MSG_KEY_ESC => {
+                    self.umount_drift_report();
+                    None
+                }
+                (COMPONENT_LIST_DRIFT, _) => None,
+                // -- transfer manifest
+                (COMPONENT_LIST_MANIFEST, Msg::OnSubmit(Payload::One(Value::Usize(row)))) => {
+                    self.action_toggle_manifest_collapsed(*row);
+                    self.update_transfer_manifest()
+                }
+                (COMPONENT_LIST_MANIFEST, key)
+                    if key == &MSG_KEY_CHAR_M || key == &MSG_KEY_SPACE =>
+                {
+                    if let Some(Payload::One(Value::Usize(row))) =
+                        self.view.get_state(COMPONENT_LIST_MANIFEST)
+                    {
+                        self.action_toggle_manifest_selected(row);
+                    }
+                    self.update_transfer_manifest()
+                }
+                (COMPONENT_LIST_MANIFEST, key) if key == &MSG_KEY_CHAR_Y => {
+                    self.action_confirm_transfer();
+                    // Reload files; marks may have been consumed
+                    let _ = self.update_local_filelist();
+                    self.update_remote_filelist()
+                }
+                (COMPONENT_LIST_MANIFEST, key) if key == &MSG_KEY_CHAR_F => {
+                    self.mount_manifest_filter();
+                    None
+                }
+                (COMPONENT_LIST_MANIFEST, key) if key == &MSG_KEY_CHAR_D => {
+                    self.action_dry_run_transfer();
+                    None
+                }
+                (COMPONENT_LIST_MANIFEST, key) if key == &MSG_KEY_ESC => {
+                    self.action_cancel_transfer();
+                    None
+                }
+                (COMPONENT_LIST_MANIFEST, _) => None,
+                // -- manifest filter
+                (COMPONENT_INPUT_MANIFEST_FILTER, key) if key == &MSG_KEY_ESC => {
+                    self.umount_manifest_filter();
+                    None
+                }
+                (
+                    COMPONENT_INPUT_MANIFEST_FILTER,
+                    Msg::OnSubmit(Payload::One(Value::Str(input))),
+                ) => {
+                    self.history.push(COMPONENT_INPUT_MANIFEST_FILTER, input);
+                    self.umount_manifest_filter();
+                    self.action_filter_manifest(input);
+                    self.update_transfer_manifest()
+                }
+                (COMPONENT_INPUT_MANIFEST_FILTER, _) => None,
+                // -- dry run report
+                (COMPONENT_LIST_DRY_RUN, key) if key == &MSG_KEY_CHAR_Y => {
+                    match self.action_close_dry_run() {
+                        Some(DryRunOperation::Transfer) => {
+                            self.action_confirm_transfer();
+                            // Reload files; marks may have been consumed
+                            let _ = self.update_local_filelist();
+                            self.update_remote_filelist()
+                        }
+                        Some(DryRunOperation::Delete) => self.delete_selected(),
+                        None => None,
+                    }
+                }
+                (COMPONENT_LIST_DRY_RUN, key) if key == &MSG_KEY_ESC => {
+                    // Focus goes back to the manifest of the transfer, if any
+                    let _ = self.action_close_dry_run();
+                    None
+                }
+                (COMPONENT_LIST_DRY_RUN, _) => None,
+                // -- usage report
+                (COMPONENT_LIST_USAGE, Msg::OnSubmit(_)) => {
+                    self.umount_usage_report();
+                    None
+                }
+                (COMPONENT_LIST_USAGE, key) if key == &MSG_KEY_ESC => {
+                    self.umount_usage_report();
+                    None
+                }
+                (COMPONENT_LIST_USAGE, _) => None,
+                // -- banner
+                (COMPONENT_TEXT_BANNER, key) if key == &MSG_KEY_ESC || key == &MSG_KEY_ENTER => {
+                    self.umount_banner();
+                    None
+                }
+                (COMPONENT_TEXT_BANNER, _) => None,
+                // -- banner before authentication
+                (COMPONENT_TEXT_AUTH_BANNER, key) if key == &MSG_KEY_ENTER => {
+                    // Reconnect, authenticating past the banner
+                    self.acknowledge_auth_banner();
+                    None
+                }
+                (COMPONENT_TEXT_AUTH_BANNER, key) if key == &MSG_KEY_ESC => {
+                    self.umount_auth_banner();
+                    self.exit_reason = Some(super::ExitReason::Disconnect);
+                    None
+                }
+                (COMPONENT_TEXT_AUTH_BANNER, _) => None,
+                // -- hex dump
+                (COMPONENT_TEXT_HEXDUMP, key) if key == &MSG_KEY_ESC => {
+                    self.hex_view = None;
+                    self.umount_hexdump();
+                    None
+                }
+                (COMPONENT_TEXT_HEXDUMP, key) if key == &MSG_KEY_CHAR_N => {
+                    if let Some(true) = self.hex_view.as_mut().map(|x| x.next_block()) {
+                        self.mount_hexdump();
+                    }
+                    None
+                }
+                (COMPONENT_TEXT_HEXDUMP, key) if key == &MSG_KEY_CHAR_P => {
+                    if let Some(true) = self.hex_view.as_mut().map(|x| x.prev_block()) {
+                        self.mount_hexdump();
+                    }
+                    None
+                }
+                (COMPONENT_TEXT_HEXDUMP, key) if key == &MSG_KEY_CHAR_G => {
+                    self.mount_hexdump_offset();
+                    None
+                }
+                (COMPONENT_TEXT_HEXDUMP, _) => None,
+                (COMPONENT_INPUT_HEXDUMP_OFFSET, key) if key == &MSG_KEY_ESC => {
+                    self.umount_hexdump_offset();
+                    None
+                }
+                (
+                    COMPONENT_INPUT_HEXDUMP_OFFSET,
+                    Msg::OnSubmit(Payload::One(Value::Str(input))),
+                ) => {
+                    self.umount_hexdump_offset();
+                    let size: u64 = self.hex_view.as_ref().map(|x| x.size).unwrap_or(0);
+                    match hexdump::parse_offset(input.as_str()) {
+                        Some(offset) if offset < size => {
+                            if let Some(view) = self.hex_view.as_mut() {
+                                view.seek(offset);
+                            }
+                            self.mount_hexdump();
+                        }
+                        Some(_) => {
+                            self.mount_error(
+                                format!("Offset is past the end of the file ({} bytes)", size)
+                                    .as_str(),
+                            );
+                        }
+                        None => {
+                            self.mount_error(format!("Invalid offset \"{}\"", input).as_str());
+                        }
+                    }
+                    None
+                }
+                (COMPONENT_INPUT_HEXDUMP_OFFSET, _) => None,
+                // -- fileinfo
+                (COMPONENT_LIST_FILEINFO, Msg::OnSubmit(_)) => {
+                    self.umount_file_info();
+                    None
+                }
+                (COMPONENT_LIST_FILEINFO, key) if key == &MSG_KEY_ESC => {
                     self.umount_file_info();
                     None
                 }
@@ -616,43 +1311,120 @@ impl Update for FileTransferActivity {
                 (COMPONENT_RADIO_DELETE, Msg::OnSubmit(Payload::One(Value::Usize(0)))) => {
                     // Choice is 'YES'
                     self.umount_radio_delete();
-                    self.mount_blocking_wait("Removing file(s)…");
-                    match self.browser.tab() {
-                        FileExplorerTab::Local => self.action_local_delete(),
-                        FileExplorerTab::Remote => self.action_remote_delete(),
-                        FileExplorerTab::FindLocal | FileExplorerTab::FindRemote => {
-                            // Get entry
-                            self.action_find_delete();
-                            // Delete entries
-                            match self.view.get_state(COMPONENT_EXPLORER_FIND) {
-                                Some(Payload::One(Value::Usize(idx))) => {
-                                    // Reload entries
-                                    self.found_mut().unwrap().del_entry(idx);
-                                }
-                                Some(Payload::Vec(values)) => {
-                                    values
-                                        .iter()
-                                        .map(|x| match x {
-                                            Value::Usize(v) => *v,
-                                            _ => 0,
-                                        })
-                                        .for_each(|x| self.found_mut().unwrap().del_entry(x));
-                                }
-                                _ => {}
-                            }
-                            self.update_find_list();
-                        }
-                    }
+                    self.delete_selected()
+                }
+                (COMPONENT_RADIO_DELETE, Msg::OnSubmit(Payload::One(Value::Usize(2)))) => {
+                    // Choice is 'DRY RUN'
+                    self.umount_radio_delete();
+                    self.action_dry_run_delete();
+                    None
+                }
+                (COMPONENT_RADIO_DELETE, _) => None,
+                // -- purge trash
+                (COMPONENT_RADIO_PURGE_TRASH, key)
+                    if key == &MSG_KEY_ESC
+                        || key == &Msg::OnSubmit(Payload::One(Value::Usize(1))) =>
+                {
+                    self.umount_radio_purge_trash();
+                    None
+                }
+                (COMPONENT_RADIO_PURGE_TRASH, Msg::OnSubmit(Payload::One(Value::Usize(0)))) => {
+                    // Choice is 'YES'
+                    self.umount_radio_purge_trash();
+                    self.mount_blocking_wait("Purging remote trash…");
+                    self.action_remote_purge_trash();
                     self.umount_wait();
-                    // Reload files
-                    match self.browser.tab() {
-                        FileExplorerTab::Local => self.update_local_filelist(),
-                        FileExplorerTab::Remote => self.update_remote_filelist(),
-                        FileExplorerTab::FindLocal => self.update_local_filelist(),
-                        FileExplorerTab::FindRemote => self.update_remote_filelist(),
+                    // Reload files, the working directory may be the trash
+                    self.reload_remote_dir();
+                    self.update_remote_filelist()
+                }
+                (COMPONENT_RADIO_PURGE_TRASH, _) => None,
+                // -- pasted paths
+                (COMPONENT_RADIO_PASTE, key)
+                    if key == &MSG_KEY_ESC
+                        || key == &Msg::OnSubmit(Payload::One(Value::Usize(2))) =>
+                {
+                    self.umount_radio_paste();
+                    self.pasted.clear();
+                    None
+                }
+                (COMPONENT_RADIO_PASTE, Msg::OnSubmit(Payload::One(Value::Usize(0)))) => {
+                    // Choice is 'UPLOAD'
+                    self.umount_radio_paste();
+                    self.action_paste_upload();
+                    self.update_remote_filelist()
+                }
+                (COMPONENT_RADIO_PASTE, Msg::OnSubmit(Payload::One(Value::Usize(1)))) => {
+                    // Choice is 'GO TO'
+                    self.umount_radio_paste();
+                    self.action_paste_goto();
+                    self.focus_panel(COMPONENT_EXPLORER_LOCAL);
+                    self.browser.change_tab(FileExplorerTab::Local);
+                    self.update_local_filelist()
+                }
+                (COMPONENT_RADIO_PASTE, _) => None,
+                // -- interrupted transfer
+                (COMPONENT_RADIO_JOURNAL, key) if key == &MSG_KEY_ESC => {
+                    // Keep the journal; the prompt shows up again on next connection
+                    self.umount_radio_journal();
+                    None
+                }
+                (COMPONENT_RADIO_JOURNAL, Msg::OnSubmit(Payload::One(Value::Usize(0)))) => {
+                    // Choice is 'RESUME'
+                    self.umount_radio_journal();
+                    self.action_resume_journal();
+                    self.update_local_filelist();
+                    self.update_remote_filelist()
+                }
+                (COMPONENT_RADIO_JOURNAL, Msg::OnSubmit(Payload::One(Value::Usize(_)))) => {
+                    // Choice is 'DISCARD'
+                    self.umount_radio_journal();
+                    self.journal_clear();
+                    None
+                }
+                (COMPONENT_RADIO_JOURNAL, _) => None,
+                // -- stalled operation
+                (COMPONENT_RADIO_STALLED, key) if key == &MSG_KEY_ESC => {
+                    self.stall_action = Some(StallAction::Abort);
+                    self.umount_radio_stalled();
+                    None
+                }
+                (COMPONENT_RADIO_STALLED, Msg::OnSubmit(Payload::One(Value::Usize(choice)))) => {
+                    self.stall_action = Some(match choice {
+                        0 => StallAction::Wait,
+                        1 => StallAction::Retry,
+                        _ => StallAction::Abort,
+                    });
+                    self.umount_radio_stalled();
+                    None
+                }
+                (COMPONENT_RADIO_STALLED, _) => None,
+                // -- existing file
+                (COMPONENT_RADIO_CONFLICT, key) if key == &MSG_KEY_ESC => {
+                    // Abort the transfer
+                    self.transfer.abort();
+                    self.conflict_action = Some(ConflictAction::Skip);
+                    self.conflict_policy = Some(ConflictAction::Skip);
+                    self.umount_radio_conflict();
+                    None
+                }
+                (COMPONENT_RADIO_CONFLICT, key) if key == &MSG_KEY_CHAR_A => {
+                    // Apply the highlighted option to all the existing files
+                    if let Some(Payload::One(Value::Usize(choice))) =
+                        self.view.get_state(COMPONENT_RADIO_CONFLICT)
+                    {
+                        self.conflict_action = Some(ConflictAction::from_choice(choice));
+                        self.conflict_policy = self.conflict_action;
+                        self.umount_radio_conflict();
                     }
+                    None
                 }
-                (COMPONENT_RADIO_DELETE, _) => None,
+                (COMPONENT_RADIO_CONFLICT, Msg::OnSubmit(Payload::One(Value::Usize(choice)))) => {
+                    self.conflict_action = Some(ConflictAction::from_choice(*choice));
+                    self.umount_radio_conflict();
+                    None
+                }
+                (COMPONENT_RADIO_CONFLICT, _) => None,
                 // -- disconnect
                 (COMPONENT_RADIO_DISCONNECT, key)
                     if key == &MSG_KEY_ESC
@@ -696,6 +1468,9 @@ impl Update for FileTransferActivity {
                         1 => FileSorting::ModifyTime,
                         2 => FileSorting::CreationTime,
                         3 => FileSorting::Size,
+                        4 => FileSorting::Extension,
+                        5 => FileSorting::Owner,
+                        6 => FileSorting::Permissions,
                         _ => FileSorting::Name,
                     };
                     match self.browser.tab() {
@@ -724,6 +1499,14 @@ impl Update for FileTransferActivity {
                     self.umount_error();
                     None
                 }
+                (COMPONENT_TEXT_ERROR, key) if key == &MSG_KEY_CHAR_D => {
+                    self.toggle_error_details(COMPONENT_TEXT_ERROR);
+                    None
+                }
+                (COMPONENT_TEXT_ERROR, key) if key == &MSG_KEY_CHAR_C => {
+                    self.copy_error_report();
+                    None
+                }
                 (COMPONENT_TEXT_ERROR, _) => None,
                 // -- fatal
                 (COMPONENT_TEXT_FATAL, key) | (COMPONENT_TEXT_FATAL, key)
@@ -732,6 +1515,14 @@ impl Update for FileTransferActivity {
                     self.exit_reason = Some(super::ExitReason::Disconnect);
                     None
                 }
+                (COMPONENT_TEXT_FATAL, key) if key == &MSG_KEY_CHAR_D => {
+                    self.toggle_error_details(COMPONENT_TEXT_FATAL);
+                    None
+                }
+                (COMPONENT_TEXT_FATAL, key) if key == &MSG_KEY_CHAR_C => {
+                    self.copy_error_report();
+                    None
+                }
                 (COMPONENT_TEXT_FATAL, _) => None,
                 // -- help
                 (COMPONENT_TEXT_HELP, key) | (COMPONENT_TEXT_HELP, key)
@@ -742,12 +1533,16 @@ impl Update for FileTransferActivity {
                 }
                 (COMPONENT_TEXT_HELP, _) => None,
                 // -- progress bar
-                (COMPONENT_PROGRESS_BAR_PARTIAL, key) if key == &MSG_KEY_CTRL_C => {
+                (COMPONENT_PROGRESS_BAR, key) if key == &MSG_KEY_CTRL_C => {
                     // Set transfer aborted to True
                     self.transfer.abort();
                     None
                 }
-                (COMPONENT_PROGRESS_BAR_PARTIAL, _) => None,
+                (COMPONENT_PROGRESS_BAR, key) if key == &MSG_KEY_CHAR_P => {
+                    self.toggle_pause();
+                    None
+                }
+                (COMPONENT_PROGRESS_BAR, _) => None,
                 // -- fallback
                 (_, _) => None, // Nothing to do
             },
@@ -781,14 +1576,26 @@ impl FileTransferActivity {
                     hostname,
                     fmt_path_elide_ex(self.local().wrkdir.as_path(), width, hostname.len() + 3) // 3 because of '/…/'
                 );
+                let hostname: String = match self.local().marked_count() {
+                    0 => hostname,
+                    n => format!("{}({} marked) ", hostname, n),
+                };
                 let files: Vec<String> = self
                     .local()
                     .iter_files()
                     .map(|x: &FsEntry| self.local().fmt_file(x))
                     .collect();
+                let names: Vec<String> = self
+                    .local()
+                    .iter_files()
+                    .map(|x: &FsEntry| x.get_name().to_string())
+                    .collect();
+                let marked: Vec<usize> = self.local().marked_indexes();
                 // Update
                 let props = FileListPropsBuilder::from(props)
                     .with_files(files)
+                    .with_names(names)
+                    .with_selected(marked.as_slice())
                     .with_title(hostname, Alignment::Left)
                     .build();
                 // Update
@@ -820,14 +1627,31 @@ impl FileTransferActivity {
                         params.address.len() + 3 // 3 because of '/…/'
                     )
                 );
+                let hostname: String = match self.remote().marked_count() {
+                    0 => hostname,
+                    n => format!("{}({} marked) ", hostname, n),
+                };
                 let files: Vec<String> = self
                     .remote()
                     .iter_files()
-                    .map(|x: &FsEntry| self.remote().fmt_file(x))
+                    .map(|x: &FsEntry| match self.remote_locks.get(x.get_name()) {
+                        Some(lock) => {
+                            format!("{}  [locked by {}]", self.remote().fmt_file(x), lock.owner)
+                        }
+                        None => self.remote().fmt_file(x),
+                    })
+                    .collect();
+                let names: Vec<String> = self
+                    .remote()
+                    .iter_files()
+                    .map(|x: &FsEntry| x.get_name().to_string())
                     .collect();
+                let marked: Vec<usize> = self.remote().marked_indexes();
                 // Update
                 let props = FileListPropsBuilder::from(props)
                     .with_files(files)
+                    .with_names(names)
+                    .with_selected(marked.as_slice())
                     .with_title(hostname, Alignment::Left)
                     .build();
                 self.view.update(super::COMPONENT_EXPLORER_REMOTE, props)
@@ -843,21 +1667,29 @@ impl FileTransferActivity {
         match self.view.get_props(super::COMPONENT_LOG_BOX) {
             Some(props) => {
                 // Make log entries
+                let error_color: Color = self.theme().misc_error_dialog;
+                let warn_color: Color = self.theme().misc_warn_dialog;
+                let info_color: Color = self.theme().transfer_log_window;
                 let mut table: TableBuilder = TableBuilder::default();
+                let mut errors: Vec<usize> = Vec::new();
+                let datetime_fmt: String = display_format().datetime_format(true);
                 for (idx, record) in self.log_records.iter().enumerate() {
                     // Add row if not first row
                     if idx > 0 {
                         table.add_row();
                     }
                     let fg = match record.level {
-                        LogLevel::Error => Color::Red,
-                        LogLevel::Warn => Color::Yellow,
-                        LogLevel::Info => Color::Green,
+                        LogLevel::Error => {
+                            errors.push(idx);
+                            error_color
+                        }
+                        LogLevel::Warn => warn_color,
+                        LogLevel::Info => info_color,
                     };
                     table
                         .add_col(TextSpan::from(format!(
                             "{}",
-                            record.time.format("%Y-%m-%dT%H:%M:%S%Z")
+                            record.time.format(datetime_fmt.as_str())
                         )))
                         .add_col(TextSpan::from(" ["))
                         .add_col(
@@ -878,7 +1710,9 @@ impl FileTransferActivity {
                         .add_col(TextSpan::from(record.msg.as_ref()));
                 }
                 let table = table.build();
-                let props = LogboxPropsBuilder::from(props).with_log(table).build();
+                let props = LogListPropsBuilder::from(props)
+                    .with_log(table, errors.as_slice())
+                    .build();
                 self.view.update(super::COMPONENT_LOG_BOX, props)
             }
             None => None,
@@ -886,26 +1720,83 @@ impl FileTransferActivity {
     }
 
     pub(super) fn update_progress_bar(&mut self, filename: String) -> Option<(String, Msg)> {
-        if let Some(props) = self.view.get_props(COMPONENT_PROGRESS_BAR_FULL) {
-            let props = ProgressBarPropsBuilder::from(props)
-                .with_label(self.transfer.full.to_string())
-                .with_progress(self.transfer.full.calc_progress())
-                .build();
-            let _ = self.view.update(COMPONENT_PROGRESS_BAR_FULL, props);
-        }
-        match self.view.get_props(COMPONENT_PROGRESS_BAR_PARTIAL) {
+        match self.view.get_props(COMPONENT_PROGRESS_BAR) {
             Some(props) => {
-                let props = ProgressBarPropsBuilder::from(props)
-                    .with_title(filename, Alignment::Center)
-                    .with_label(self.transfer.partial.to_string())
-                    .with_progress(self.transfer.partial.calc_progress())
+                let props = TransferProgressPropsBuilder::from(props)
+                    .with_overall(self.transfer.overall_progress())
+                    .with_current(filename, Progress::from(&self.transfer.partial))
+                    .with_files(FilesProgress::from(&self.transfer))
+                    .with_paused(self.transfer.paused())
                     .build();
-                self.view.update(COMPONENT_PROGRESS_BAR_PARTIAL, props)
+                self.view.update(COMPONENT_PROGRESS_BAR, props)
             }
             None => None,
         }
     }
 
+    /// ### delete_selected
+    ///
+    /// Remove the entries selected in the current tab, then reload the explorer
+    fn delete_selected(&mut self) -> Option<(String, Msg)> {
+        self.mount_blocking_wait("Removing file(s)…");
+        match self.browser.tab() {
+            FileExplorerTab::Local => self.action_local_delete(),
+            FileExplorerTab::Remote => self.action_remote_delete(),
+            FileExplorerTab::FindLocal | FileExplorerTab::FindRemote => {
+                // Get entry
+                self.action_find_delete();
+                // Delete entries
+                match self.view.get_state(COMPONENT_EXPLORER_FIND) {
+                    Some(Payload::One(Value::Usize(idx))) => {
+                        // Reload entries
+                        self.found_mut().unwrap().del_entry(idx);
+                    }
+                    Some(Payload::Vec(values)) => {
+                        values
+                            .iter()
+                            .map(|x| match x {
+                                Value::Usize(v) => *v,
+                                _ => 0,
+                            })
+                            .for_each(|x| self.found_mut().unwrap().del_entry(x));
+                    }
+                    _ => {}
+                }
+                self.update_find_list();
+            }
+        }
+        self.umount_wait();
+        // Reload files
+        match self.browser.tab() {
+            FileExplorerTab::Local => self.update_local_filelist(),
+            FileExplorerTab::Remote => self.update_remote_filelist(),
+            FileExplorerTab::FindLocal => self.update_local_filelist(),
+            FileExplorerTab::FindRemote => self.update_remote_filelist(),
+        }
+    }
+
+    /// ### toggle_pause
+    ///
+    /// Pause the transfer, or resume it if it's paused
+    fn toggle_pause(&mut self) {
+        match self.transfer.paused() {
+            true => {
+                self.transfer.resume();
+                self.log(LogLevel::Info, String::from("Transfer resumed"));
+            }
+            false => {
+                self.transfer.pause();
+                self.log(LogLevel::Info, String::from("Transfer paused"));
+            }
+        }
+        if let Some(props) = self.view.get_props(COMPONENT_PROGRESS_BAR) {
+            let props = TransferProgressPropsBuilder::from(props)
+                .with_paused(self.transfer.paused())
+                .build();
+            self.view.update(COMPONENT_PROGRESS_BAR, props);
+        }
+    }
+
     /// ### finalize_find
     ///
     /// Finalize find process
@@ -920,6 +1811,25 @@ impl FileTransferActivity {
         });
     }
 
+    /// ### update_transfer_manifest
+    ///
+    /// Update transfer manifest popup
+    fn update_transfer_manifest(&mut self) -> Option<(String, Msg)> {
+        match (
+            self.view.get_props(COMPONENT_LIST_MANIFEST),
+            self.pending_transfer.as_ref(),
+        ) {
+            (Some(props), Some(pending)) => {
+                let props = DataTablePropsBuilder::from(props)
+                    .with_title(Self::manifest_title(&pending.manifest), Alignment::Left)
+                    .with_table(Self::manifest_table(&pending.manifest))
+                    .build();
+                self.view.update(COMPONENT_LIST_MANIFEST, props)
+            }
+            _ => None,
+        }
+    }
+
     fn update_find_list(&mut self) -> Option<(String, Msg)> {
         match self.view.get_props(COMPONENT_EXPLORER_FIND) {
             None => None,
@@ -931,7 +1841,16 @@ impl FileTransferActivity {
                     .iter_files()
                     .map(|x: &FsEntry| self.found().unwrap().fmt_file(x))
                     .collect();
-                let props = FileListPropsBuilder::from(props).with_files(files).build();
+                let names: Vec<String> = self
+                    .found()
+                    .unwrap()
+                    .iter_files()
+                    .map(|x: &FsEntry| x.get_name().to_string())
+                    .collect();
+                let props = FileListPropsBuilder::from(props)
+                    .with_files(files)
+                    .with_names(names)
+                    .build();
                 self.view.update(COMPONENT_EXPLORER_FIND, props)
             }
         }
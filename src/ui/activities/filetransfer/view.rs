@@ -26,34 +26,53 @@
  * SOFTWARE.
  */
 // locals
-use super::{browser::FileExplorerTab, Context, FileTransferActivity};
+use super::{
+    actions::chmod::ChmodOptions,
+    browser::FileExplorerTab,
+    lib::dry_run::{DryRunAction, DryRunReport},
+    lib::manifest::TransferManifest,
+    lib::report::ErrorReport,
+    lib::verify::DriftReport,
+    ConflictAction, Context, FileTransferActivity, LogLevel, SessionOptions,
+};
+use crate::config::journal::JournalEntry;
+use crate::config::usage::Usage;
+use crate::filetransfer::Capability;
 use crate::fs::explorer::FileSorting;
+use crate::fs::filter::TransferFilter;
 use crate::fs::FsEntry;
 use crate::ui::components::{
+    bookmark_list::{BookmarkList, BookmarkListPropsBuilder},
+    data_table::{ColumnWidth, DataTable, DataTablePropsBuilder},
     file_list::{FileList, FileListPropsBuilder},
-    logbox::{LogBox, LogboxPropsBuilder},
+    log_list::{LogList, LogListPropsBuilder},
+    rules_editor::{RulesEditor, RulesEditorPropsBuilder},
+    spinner::{Spinner, SpinnerPropsBuilder},
+    text_input::{complete_from, TextInput, TextInputPropsBuilder},
+    transfer_progress::{TransferProgress, TransferProgressPropsBuilder},
 };
-use crate::ui::store::Store;
-use crate::utils::fmt::fmt_time;
+use crate::utils::fmt::{display_format, fmt_banner, fmt_path_elide, fmt_size, fmt_time};
+use crate::utils::parser::parse_unix_mode;
 use crate::utils::ui::draw_area_in;
 // Ext
-use bytesize::ByteSize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tui_realm_stdlib::{
     input::{Input, InputPropsBuilder},
     list::{List, ListPropsBuilder},
     paragraph::{Paragraph, ParagraphPropsBuilder},
-    progress_bar::{ProgressBar, ProgressBarPropsBuilder},
     radio::{Radio, RadioPropsBuilder},
     span::{Span, SpanPropsBuilder},
-    table::{Table, TablePropsBuilder},
+    textarea::{Textarea, TextareaPropsBuilder},
+};
+use tuirealm::props::{
+    Alignment, InputType, PropsBuilder, Table as TextTable, TableBuilder, TextSpan,
 };
-use tuirealm::props::{Alignment, PropsBuilder, TableBuilder, TextSpan};
 use tuirealm::tui::{
     layout::{Constraint, Direction, Layout},
     style::Color,
     widgets::{BorderType, Borders, Clear},
 };
+use tuirealm::{Payload, Value};
 #[cfg(target_family = "unix")]
 use users::{get_group_by_gid, get_user_by_uid};
 
@@ -99,8 +118,8 @@ impl FileTransferActivity {
         // Mount log box
         self.view.mount(
             super::COMPONENT_LOG_BOX,
-            Box::new(LogBox::new(
-                LogboxPropsBuilder::default()
+            Box::new(LogList::new(
+                LogListPropsBuilder::default()
                     .with_title("Log", Alignment::Left)
                     .with_background(log_background)
                     .with_borders(Borders::ALL, BorderType::Plain, log_panel)
@@ -123,7 +142,48 @@ impl FileTransferActivity {
         let _ = self.update_local_filelist();
         let _ = self.update_remote_filelist();
         // Give focus to local explorer
-        self.view.active(super::COMPONENT_EXPLORER_LOCAL);
+        self.focus_panel(super::COMPONENT_EXPLORER_LOCAL);
+    }
+
+    // -- focus
+
+    /// ### open_popup
+    ///
+    /// Put the mounted popup `id` on top of the others and give focus to it
+    fn open_popup(&mut self, id: &'static str) {
+        self.modals.open(id);
+        self.view.active(id);
+    }
+
+    /// ### close_popup
+    ///
+    /// Umount the popup `id` and give focus back to the popup below it, or to the explorer
+    /// if no popup is left. Focus never goes to a component which is hidden behind a popup
+    fn close_popup(&mut self, id: &'static str) {
+        self.view.umount(id);
+        if self.modals.is_open(id) {
+            let focus: &'static str = self.modals.close(id);
+            self.view.active(focus);
+        }
+    }
+
+    /// ### focus_popup
+    ///
+    /// Move focus to `component`, which is part of the popup on top (e.g. a form field)
+    pub(super) fn focus_popup(&mut self, component: &'static str) {
+        self.modals.move_focus(component);
+        self.view.active(component);
+    }
+
+    /// ### focus_panel
+    ///
+    /// Give focus to `component`, one of the panels behind the popups (explorers or log box).
+    /// If a popup is displayed, the panel will get focus once all the popups are closed
+    pub(super) fn focus_panel(&mut self, component: &'static str) {
+        self.modals.set_base(component);
+        if self.modals.is_empty() {
+            self.view.active(component);
+        }
     }
 
     // -- view
@@ -133,8 +193,8 @@ impl FileTransferActivity {
     /// View gui
     pub(super) fn view(&mut self) {
         let mut context: Context = self.context.take().unwrap();
-        let store: &mut Store = &mut context.store;
-        let _ = context.terminal.draw(|f| {
+        let mut explorer_width: Option<usize> = None;
+        let _ = context.draw(|f| {
             // Prepare chunks
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -163,10 +223,7 @@ impl FileTransferActivity {
                 .direction(Direction::Horizontal)
                 .horizontal_margin(1)
                 .split(bottom_chunks[0]);
-            // If width is unset in the storage, set width
-            if !store.isset(super::STORAGE_EXPLORER_WIDTH) {
-                store.set_unsigned(super::STORAGE_EXPLORER_WIDTH, tabs_chunks[0].width as usize);
-            }
+            explorer_width = Some(tabs_chunks[0].width as usize);
             // Draw explorers
             // @! Local explorer (Find or default)
             match self.browser.tab() {
@@ -275,33 +332,208 @@ impl FileTransferActivity {
                     self.view.render(super::COMPONENT_INPUT_EXEC, f, popup);
                 }
             }
-            if let Some(props) = self.view.get_props(super::COMPONENT_LIST_FILEINFO) {
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_FETCH) {
                 if props.visible {
-                    let popup = draw_area_in(f.size(), 50, 50);
+                    let popup = draw_area_in(f.size(), 60, 10);
                     f.render_widget(Clear, popup);
                     // make popup
-                    self.view.render(super::COMPONENT_LIST_FILEINFO, f, popup);
+                    self.view.render(super::COMPONENT_INPUT_FETCH, f, popup);
                 }
             }
-            if let Some(props) = self.view.get_props(super::COMPONENT_PROGRESS_BAR_PARTIAL) {
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_PEER) {
                 if props.visible {
-                    let popup = draw_area_in(f.size(), 50, 20);
+                    let popup = draw_area_in(f.size(), 40, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_INPUT_PEER, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_PEER_PASSWORD) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 40, 10);
                     f.render_widget(Clear, popup);
                     // make popup
+                    self.view
+                        .render(super::COMPONENT_INPUT_PEER_PASSWORD, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_CHMOD_FILE) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 40);
+                    f.render_widget(Clear, popup);
+                    let popup_chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints(
+                            [
+                                Constraint::Length(3),
+                                Constraint::Length(3),
+                                Constraint::Length(3),
+                                Constraint::Length(3),
+                            ]
+                            .as_ref(),
+                        )
+                        .split(popup);
+                    self.view
+                        .render(super::COMPONENT_INPUT_CHMOD_FILE, f, popup_chunks[0]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_CHMOD_DIR, f, popup_chunks[1]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_CHOWN, f, popup_chunks[2]);
+                    self.view
+                        .render(super::COMPONENT_RADIO_CHMOD_RECURSIVE, f, popup_chunks[3]);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_OPTS_PRESERVE) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 60, 50);
+                    f.render_widget(Clear, popup);
                     let popup_chunks = Layout::default()
                         .direction(Direction::Vertical)
                         .constraints(
                             [
-                                Constraint::Percentage(50), // Full
-                                Constraint::Percentage(50), // Partial
+                                Constraint::Length(3), // preserve
+                                Constraint::Length(3), // conflict
+                                Constraint::Length(3), // bandwidth
+                                Constraint::Length(3), // exclude
+                                Constraint::Length(3), // verify
                             ]
                             .as_ref(),
                         )
                         .split(popup);
                     self.view
-                        .render(super::COMPONENT_PROGRESS_BAR_FULL, f, popup_chunks[0]);
+                        .render(super::COMPONENT_RADIO_OPTS_PRESERVE, f, popup_chunks[0]);
+                    self.view
+                        .render(super::COMPONENT_RADIO_OPTS_CONFLICT, f, popup_chunks[1]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_OPTS_BANDWIDTH, f, popup_chunks[2]);
+                    self.view
+                        .render(super::COMPONENT_INPUT_OPTS_EXCLUDE, f, popup_chunks[3]);
+                    self.view
+                        .render(super::COMPONENT_RADIO_OPTS_VERIFY, f, popup_chunks[4]);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_KEY_PASSPHRASE) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 20);
+                    f.render_widget(Clear, popup);
+                    let popup_chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Length(3), Constraint::Length(3)].as_ref())
+                        .split(popup);
+                    self.view
+                        .render(super::COMPONENT_INPUT_KEY_PASSPHRASE, f, popup_chunks[0]);
+                    self.view.render(
+                        super::COMPONENT_RADIO_KEY_PASSPHRASE_CACHE,
+                        f,
+                        popup_chunks[1],
+                    );
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_LIST_FAVORITES) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 50);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_LIST_FAVORITES, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_RULES_EDITOR) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 60, 60);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_RULES_EDITOR, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_LIST_DRIFT) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 70, 70);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_LIST_DRIFT, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_LIST_MANIFEST) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 70, 70);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_LIST_MANIFEST, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_LIST_DRY_RUN) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 70, 70);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_LIST_DRY_RUN, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_MANIFEST_FILTER) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 40, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view
+                        .render(super::COMPONENT_INPUT_MANIFEST_FILTER, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_LIST_USAGE) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 50);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_LIST_USAGE, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_BANNER) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 70, 70);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_TEXT_BANNER, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_AUTH_BANNER) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 70, 70);
+                    f.render_widget(Clear, popup);
+                    // make popup
                     self.view
-                        .render(super::COMPONENT_PROGRESS_BAR_PARTIAL, f, popup_chunks[1]);
+                        .render(super::COMPONENT_TEXT_AUTH_BANNER, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_HEXDUMP) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 80, 80);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_TEXT_HEXDUMP, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_HEXDUMP_OFFSET) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 40, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view
+                        .render(super::COMPONENT_INPUT_HEXDUMP_OFFSET, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_LIST_FILEINFO) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 50);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_LIST_FILEINFO, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_PROGRESS_BAR) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 20);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_PROGRESS_BAR, f, popup);
                 }
             }
             if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_DELETE) {
@@ -312,6 +544,31 @@ impl FileTransferActivity {
                     self.view.render(super::COMPONENT_RADIO_DELETE, f, popup);
                 }
             }
+            if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_PURGE_TRASH) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 30, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view
+                        .render(super::COMPONENT_RADIO_PURGE_TRASH, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_PASTE) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_RADIO_PASTE, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_JOURNAL) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 60, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_RADIO_JOURNAL, f, popup);
+                }
+            }
             if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_DISCONNECT) {
                 if props.visible {
                     let popup = draw_area_in(f.size(), 30, 10);
@@ -331,7 +588,7 @@ impl FileTransferActivity {
             }
             if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_SORTING) {
                 if props.visible {
-                    let popup = draw_area_in(f.size(), 50, 10);
+                    let popup = draw_area_in(f.size(), 70, 10);
                     f.render_widget(Clear, popup);
                     // make popup
                     self.view.render(super::COMPONENT_RADIO_SORTING, f, popup);
@@ -339,7 +596,10 @@ impl FileTransferActivity {
             }
             if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_ERROR) {
                 if props.visible {
-                    let popup = draw_area_in(f.size(), 50, 10);
+                    let popup = match self.error_details {
+                        true => draw_area_in(f.size(), 70, 60),
+                        false => draw_area_in(f.size(), 50, 10),
+                    };
                     f.render_widget(Clear, popup);
                     // make popup
                     self.view.render(super::COMPONENT_TEXT_ERROR, f, popup);
@@ -347,7 +607,10 @@ impl FileTransferActivity {
             }
             if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_FATAL) {
                 if props.visible {
-                    let popup = draw_area_in(f.size(), 50, 10);
+                    let popup = match self.error_details {
+                        true => draw_area_in(f.size(), 70, 60),
+                        false => draw_area_in(f.size(), 50, 10),
+                    };
                     f.render_widget(Clear, popup);
                     // make popup
                     self.view.render(super::COMPONENT_TEXT_FATAL, f, popup);
@@ -361,6 +624,23 @@ impl FileTransferActivity {
                     self.view.render(super::COMPONENT_TEXT_WAIT, f, popup);
                 }
             }
+            // Stalled operations may be prompted while waiting
+            if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_STALLED) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_RADIO_STALLED, f, popup);
+                }
+            }
+            if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_CONFLICT) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 50, 10);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_RADIO_CONFLICT, f, popup);
+                }
+            }
             if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_HELP) {
                 if props.visible {
                     let popup = draw_area_in(f.size(), 50, 80);
@@ -370,6 +650,14 @@ impl FileTransferActivity {
                 }
             }
         });
+        // If width is unset in the storage, set width
+        if let Some(width) = explorer_width {
+            if !context.store.isset(super::STORAGE_EXPLORER_WIDTH) {
+                context
+                    .store
+                    .set_unsigned(super::STORAGE_EXPLORER_WIDTH, width);
+            }
+        }
         // Re-give context
         self.context = Some(context);
     }
@@ -380,48 +668,77 @@ impl FileTransferActivity {
     ///
     /// Mount error box
     pub(super) fn mount_error(&mut self, text: &str) {
-        // Mount
-        let error_color = self.theme().misc_error_dialog;
-        self.view.mount(
-            super::COMPONENT_TEXT_ERROR,
-            Box::new(Paragraph::new(
-                ParagraphPropsBuilder::default()
-                    .with_foreground(error_color)
-                    .with_borders(Borders::ALL, BorderType::Rounded, error_color)
-                    .bold()
-                    .with_text_alignment(Alignment::Center)
-                    .with_texts(vec![TextSpan::from(text)])
-                    .build(),
-            )),
-        );
-        // Give focus to error
-        self.view.active(super::COMPONENT_TEXT_ERROR);
+        self.mount_error_report(ErrorReport::new(text));
+    }
+
+    /// ### mount_error_report
+    ///
+    /// Mount error box for `report`
+    pub(super) fn mount_error_report(&mut self, report: ErrorReport) {
+        self.error_report = Some(self.make_error_report(report));
+        self.error_details = false;
+        self.mount_error_popup(super::COMPONENT_TEXT_ERROR);
     }
 
     /// ### umount_error
     ///
     /// Umount error message
     pub(super) fn umount_error(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_ERROR);
+        self.close_popup(super::COMPONENT_TEXT_ERROR);
+        self.error_report = None;
     }
 
     pub(super) fn mount_fatal(&mut self, text: &str) {
-        // Mount
+        self.error_report = Some(self.make_error_report(ErrorReport::new(text)));
+        self.error_details = false;
+        self.mount_error_popup(super::COMPONENT_TEXT_FATAL);
+    }
+
+    /// ### toggle_error_details
+    ///
+    /// Show or hide the technical details of the error displayed in `component`
+    pub(super) fn toggle_error_details(&mut self, component: &'static str) {
+        self.error_details = !self.error_details;
+        self.mount_error_popup(component);
+    }
+
+    /// ### mount_error_popup
+    ///
+    /// Mount the error popup `component` for the current error report, with or without its details
+    fn mount_error_popup(&mut self, component: &'static str) {
+        let report: &ErrorReport = match self.error_report.as_ref() {
+            Some(report) => report,
+            None => return,
+        };
         let error_color = self.theme().misc_error_dialog;
+        let key_color = self.theme().misc_keys;
+        let mut texts: Vec<TextSpan> = vec![TextSpan::new(report.message()).bold()];
+        let alignment: Alignment = match self.error_details {
+            true => {
+                texts.push(TextSpan::from(""));
+                texts.extend(report.lines().iter().map(|x| TextSpan::from(x.as_str())));
+                texts.push(TextSpan::from(""));
+                texts.push(TextSpan::new("<D> hide details · <C> copy report").fg(key_color));
+                Alignment::Left
+            }
+            false => {
+                texts.push(TextSpan::new("<D> show details · <C> copy report").fg(key_color));
+                Alignment::Center
+            }
+        };
         self.view.mount(
-            super::COMPONENT_TEXT_FATAL,
+            component,
             Box::new(Paragraph::new(
                 ParagraphPropsBuilder::default()
                     .with_foreground(error_color)
                     .with_borders(Borders::ALL, BorderType::Rounded, error_color)
-                    .bold()
-                    .with_text_alignment(Alignment::Center)
-                    .with_texts(vec![TextSpan::from(text)])
+                    .with_text_alignment(alignment)
+                    .with_texts(texts)
                     .build(),
             )),
         );
         // Give focus to error
-        self.view.active(super::COMPONENT_TEXT_FATAL);
+        self.open_popup(component);
     }
 
     pub(super) fn mount_wait(&mut self, text: &str) {
@@ -434,24 +751,28 @@ impl FileTransferActivity {
     }
 
     fn mount_wait_ex(&mut self, text: &str, color: Color) {
-        // Mount
-        let mut builder: ParagraphPropsBuilder = ParagraphPropsBuilder::default();
-        builder
+        let mut props = SpinnerPropsBuilder::default();
+        props
             .with_foreground(color)
             .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
             .bold()
             .with_text_alignment(Alignment::Center)
-            .with_texts(vec![TextSpan::from(text)]);
+            .with_text(text);
+        // Don't animate spinner in low bandwidth mode
+        if self.low_bandwidth() {
+            props.still();
+        }
+        // Mount
         self.view.mount(
             super::COMPONENT_TEXT_WAIT,
-            Box::new(Paragraph::new(builder.build())),
+            Box::new(Spinner::new(props.build())),
         );
         // Give focus to info
-        self.view.active(super::COMPONENT_TEXT_WAIT);
+        self.open_popup(super::COMPONENT_TEXT_WAIT);
     }
 
     pub(super) fn umount_wait(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_WAIT);
+        self.close_popup(super::COMPONENT_TEXT_WAIT);
     }
 
     /// ### mount_quit
@@ -473,14 +794,14 @@ impl FileTransferActivity {
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_RADIO_QUIT);
+        self.open_popup(super::COMPONENT_RADIO_QUIT);
     }
 
     /// ### umount_quit
     ///
     /// Umount quit popup
     pub(super) fn umount_quit(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_QUIT);
+        self.close_popup(super::COMPONENT_RADIO_QUIT);
     }
 
     /// ### mount_disconnect
@@ -502,14 +823,14 @@ impl FileTransferActivity {
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_RADIO_DISCONNECT);
+        self.open_popup(super::COMPONENT_RADIO_DISCONNECT);
     }
 
     /// ### umount_disconnect
     ///
     /// Umount disconnect popup
     pub(super) fn umount_disconnect(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_DISCONNECT);
+        self.close_popup(super::COMPONENT_RADIO_DISCONNECT);
     }
 
     pub(super) fn mount_copy(&mut self) {
@@ -524,308 +845,1277 @@ impl FileTransferActivity {
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_COPY);
+        self.open_popup(super::COMPONENT_INPUT_COPY);
     }
 
     pub(super) fn umount_copy(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_COPY);
+        self.close_popup(super::COMPONENT_INPUT_COPY);
     }
 
     pub(super) fn mount_exec(&mut self) {
         let input_color = self.theme().misc_input_dialog;
         self.view.mount(
             super::COMPONENT_INPUT_EXEC,
-            Box::new(Input::new(
-                InputPropsBuilder::default()
+            Box::new(TextInput::new(
+                TextInputPropsBuilder::default()
                     .with_borders(Borders::ALL, BorderType::Rounded, input_color)
                     .with_foreground(input_color)
                     .with_label("Execute command", Alignment::Center)
+                    .with_placeholder("e.g. ls -l")
+                    .with_history(&self.history.get(super::COMPONENT_INPUT_EXEC))
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_EXEC);
+        self.open_popup(super::COMPONENT_INPUT_EXEC);
     }
 
     pub(super) fn umount_exec(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_EXEC);
+        self.close_popup(super::COMPONENT_INPUT_EXEC);
     }
 
-    pub(super) fn mount_find(&mut self, search: &str) {
-        // Get color
-        let (bg, fg, hg): (Color, Color, Color) = match self.browser.tab() {
-            FileExplorerTab::Local | FileExplorerTab::FindLocal => (
-                self.theme().transfer_local_explorer_background,
-                self.theme().transfer_local_explorer_foreground,
-                self.theme().transfer_local_explorer_highlighted,
-            ),
-            FileExplorerTab::Remote | FileExplorerTab::FindRemote => (
-                self.theme().transfer_remote_explorer_background,
-                self.theme().transfer_remote_explorer_foreground,
-                self.theme().transfer_remote_explorer_highlighted,
-            ),
-        };
-        // Mount component
+    pub(super) fn mount_fetch(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
         self.view.mount(
-            super::COMPONENT_EXPLORER_FIND,
-            Box::new(FileList::new(
-                FileListPropsBuilder::default()
-                    .with_title(
-                        format!("Search results for \"{}\"", search),
-                        Alignment::Left,
-                    )
-                    .with_borders(Borders::ALL, BorderType::Plain, hg)
-                    .with_highlight_color(hg)
-                    .with_background(bg)
-                    .with_foreground(fg)
+            super::COMPONENT_INPUT_FETCH,
+            Box::new(TextInput::new(
+                TextInputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Fetch URL into local directory", Alignment::Center)
+                    .with_placeholder("e.g. https://example.com/archive.tar.gz")
+                    .with_history(&self.history.get(super::COMPONENT_INPUT_FETCH))
                     .build(),
             )),
         );
-        // Give focus to explorer findd
-        self.view.active(super::COMPONENT_EXPLORER_FIND);
+        self.open_popup(super::COMPONENT_INPUT_FETCH);
     }
 
-    pub(super) fn umount_find(&mut self) {
-        self.view.umount(super::COMPONENT_EXPLORER_FIND);
+    pub(super) fn umount_fetch(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_FETCH);
     }
 
-    pub(super) fn mount_find_input(&mut self) {
+    pub(super) fn mount_peer(&mut self) {
         let input_color = self.theme().misc_input_dialog;
         self.view.mount(
-            super::COMPONENT_INPUT_FIND,
-            Box::new(Input::new(
-                InputPropsBuilder::default()
+            super::COMPONENT_INPUT_PEER,
+            Box::new(TextInput::new(
+                TextInputPropsBuilder::default()
                     .with_borders(Borders::ALL, BorderType::Rounded, input_color)
                     .with_foreground(input_color)
-                    .with_label("Search files by name", Alignment::Center)
+                    .with_label("Connect to peer", Alignment::Center)
+                    .with_placeholder("e.g. sftp://user@host:22:/home/user")
+                    .with_history(&self.history.get(super::COMPONENT_INPUT_PEER))
                     .build(),
             )),
         );
-        // Give focus to input find
-        self.view.active(super::COMPONENT_INPUT_FIND);
+        self.open_popup(super::COMPONENT_INPUT_PEER);
     }
 
-    pub(super) fn umount_find_input(&mut self) {
-        // Umount input find
-        self.view.umount(super::COMPONENT_INPUT_FIND);
+    pub(super) fn umount_peer(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_PEER);
     }
 
-    pub(super) fn mount_goto(&mut self) {
+    pub(super) fn mount_peer_password(&mut self) {
         let input_color = self.theme().misc_input_dialog;
         self.view.mount(
-            super::COMPONENT_INPUT_GOTO,
+            super::COMPONENT_INPUT_PEER_PASSWORD,
             Box::new(Input::new(
                 InputPropsBuilder::default()
                     .with_borders(Borders::ALL, BorderType::Rounded, input_color)
                     .with_foreground(input_color)
-                    .with_label("Change working directory", Alignment::Center)
+                    .with_label("Peer password (leave empty for none)", Alignment::Center)
+                    .with_input(InputType::Password)
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_GOTO);
+        self.open_popup(super::COMPONENT_INPUT_PEER_PASSWORD);
     }
 
-    pub(super) fn umount_goto(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_GOTO);
+    pub(super) fn umount_peer_password(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_PEER_PASSWORD);
     }
 
-    pub(super) fn mount_mkdir(&mut self) {
+    /// ### mount_chmod
+    ///
+    /// Mount the change permissions popup; modes are initialized with the ones of `entry`
+    pub(super) fn mount_chmod(&mut self, entry: &FsEntry) {
         let input_color = self.theme().misc_input_dialog;
+        let fmt_mode = |default: &str| -> String {
+            match entry.get_unix_pex() {
+                Some((owner, group, others)) => {
+                    format!("{}{}{}", owner.as_byte(), group.as_byte(), others.as_byte())
+                }
+                None => default.to_string(),
+            }
+        };
+        let (file_mode, dir_mode): (String, String) = match entry.is_dir() {
+            true => (String::from("644"), fmt_mode("755")),
+            false => (fmt_mode("644"), String::from("755")),
+        };
         self.view.mount(
-            super::COMPONENT_INPUT_MKDIR,
+            super::COMPONENT_INPUT_CHMOD_FILE,
             Box::new(Input::new(
                 InputPropsBuilder::default()
                     .with_borders(Borders::ALL, BorderType::Rounded, input_color)
                     .with_foreground(input_color)
-                    .with_label("Insert directory name", Alignment::Center)
+                    .with_label("File mode (empty to keep)", Alignment::Center)
+                    .with_input_len(4)
+                    .with_value(file_mode)
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_MKDIR);
+        self.view.mount(
+            super::COMPONENT_INPUT_CHMOD_DIR,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Directory mode (empty to keep)", Alignment::Center)
+                    .with_input_len(4)
+                    .with_value(dir_mode)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_CHOWN,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Owner as user[:group] (empty to keep)", Alignment::Center)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_CHMOD_RECURSIVE,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(input_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_title("Apply recursively?", Alignment::Center)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .with_value(1)
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_CHMOD_FILE);
+    }
+
+    /// ### umount_chmod
+    ///
+    /// Umount the change permissions popup
+    pub(super) fn umount_chmod(&mut self) {
+        self.view.umount(super::COMPONENT_INPUT_CHMOD_DIR);
+        self.view.umount(super::COMPONENT_INPUT_CHOWN);
+        self.view.umount(super::COMPONENT_RADIO_CHMOD_RECURSIVE);
+        self.close_popup(super::COMPONENT_INPUT_CHMOD_FILE);
+    }
+
+    /// ### mount_session_options
+    ///
+    /// Mount the popup to change the transfer options of the current session
+    pub(super) fn mount_session_options(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        let opts: SessionOptions = self.options.clone();
+        self.view.mount(
+            super::COMPONENT_RADIO_OPTS_PRESERVE,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(input_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_title("Preserve attributes of downloads", Alignment::Center)
+                    .with_options(&[
+                        String::from("Mode and owner"),
+                        String::from("Mode"),
+                        String::from("No"),
+                    ])
+                    .with_value(match (opts.preserve_mode, opts.preserve_owner) {
+                        (true, true) => 0,
+                        (true, false) => 1,
+                        (false, _) => 2,
+                    })
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_OPTS_CONFLICT,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(input_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_title("Existing files", Alignment::Center)
+                    .with_options(&[
+                        String::from("Ask"),
+                        String::from("Overwrite"),
+                        String::from("Skip"),
+                        String::from("Resume"),
+                        String::from("Rename"),
+                    ])
+                    .with_value(opts.conflict_policy.map(|x| x.to_choice() + 1).unwrap_or(0))
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_OPTS_BANDWIDTH,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label(
+                        "Bandwidth cap in KB/s (empty for unlimited)",
+                        Alignment::Center,
+                    )
+                    .with_input_len(8)
+                    .with_value(
+                        opts.bandwidth
+                            .map(|x| (x / 1024).to_string())
+                            .unwrap_or_default(),
+                    )
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_OPTS_EXCLUDE,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Exclude (comma separated globs)", Alignment::Center)
+                    .with_value(opts.exclude.to_globs())
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_OPTS_VERIFY,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(input_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_title("Verify transferred files?", Alignment::Center)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .with_value(match opts.verify {
+                        true => 0,
+                        false => 1,
+                    })
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_OPTS_PRESERVE);
+    }
+
+    /// ### umount_session_options
+    ///
+    /// Umount the session options popup
+    pub(super) fn umount_session_options(&mut self) {
+        self.view.umount(super::COMPONENT_RADIO_OPTS_CONFLICT);
+        self.view.umount(super::COMPONENT_INPUT_OPTS_BANDWIDTH);
+        self.view.umount(super::COMPONENT_INPUT_OPTS_EXCLUDE);
+        self.view.umount(super::COMPONENT_RADIO_OPTS_VERIFY);
+        self.close_popup(super::COMPONENT_RADIO_OPTS_PRESERVE);
+    }
+
+    /// ### get_session_options
+    ///
+    /// Collect options from the session options popup
+    pub(super) fn get_session_options(&self) -> Result<SessionOptions, String> {
+        let get_radio = |component: &str| -> usize {
+            match self.view.get_state(component) {
+                Some(Payload::One(Value::Usize(x))) => x,
+                _ => 0,
+            }
+        };
+        let get_input = |component: &str| -> String {
+            match self.view.get_state(component) {
+                Some(Payload::One(Value::Str(x))) => x.trim().to_string(),
+                _ => String::new(),
+            }
+        };
+        let bandwidth: String = get_input(super::COMPONENT_INPUT_OPTS_BANDWIDTH);
+        let bandwidth: Option<u64> = match bandwidth.as_str() {
+            "" => None,
+            cap => match cap.parse::<u64>() {
+                Ok(cap) if cap > 0 => Some(cap * 1024),
+                _ => return Err(format!("Invalid bandwidth cap \"{}\"", cap)),
+            },
+        };
+        let preserve: usize = get_radio(super::COMPONENT_RADIO_OPTS_PRESERVE);
+        Ok(SessionOptions {
+            preserve_mode: preserve < 2,
+            preserve_owner: preserve == 0,
+            conflict_policy: match get_radio(super::COMPONENT_RADIO_OPTS_CONFLICT) {
+                0 => None,
+                choice => Some(ConflictAction::from_choice(choice - 1)),
+            },
+            bandwidth,
+            exclude: TransferFilter::excluding(
+                get_input(super::COMPONENT_INPUT_OPTS_EXCLUDE).as_str(),
+            ),
+            verify: get_radio(super::COMPONENT_RADIO_OPTS_VERIFY) == 0,
+        })
+    }
+
+    /// ### get_chmod_options
+    ///
+    /// Collect options from the change permissions popup
+    pub(super) fn get_chmod_options(&self) -> Result<ChmodOptions, String> {
+        let get_input = |component: &str| -> Option<String> {
+            match self.view.get_state(component) {
+                Some(Payload::One(Value::Str(x))) if !x.trim().is_empty() => {
+                    Some(x.trim().to_string())
+                }
+                _ => None,
+            }
+        };
+        let parse_mode = |mode: Option<String>| -> Result<Option<(u8, u8, u8)>, String> {
+            match mode {
+                None => Ok(None),
+                Some(mode) => parse_unix_mode(mode.as_str())
+                    .map(Some)
+                    .ok_or_else(|| format!("Invalid mode \"{}\"", mode)),
+            }
+        };
+        Ok(ChmodOptions {
+            file_mode: parse_mode(get_input(super::COMPONENT_INPUT_CHMOD_FILE))?,
+            dir_mode: parse_mode(get_input(super::COMPONENT_INPUT_CHMOD_DIR))?,
+            owner: get_input(super::COMPONENT_INPUT_CHOWN),
+            recursive: matches!(
+                self.view.get_state(super::COMPONENT_RADIO_CHMOD_RECURSIVE),
+                Some(Payload::One(Value::Usize(0)))
+            ),
+        })
+    }
+
+    /// ### mount_key_passphrase
+    ///
+    /// Mount the prompt for the passphrase of a protected key
+    pub(super) fn mount_key_passphrase(&mut self, key: &str, wrong: bool) {
+        let input_color = self.theme().misc_input_dialog;
+        let label: String = match wrong {
+            true => format!("Wrong passphrase for {}", key),
+            false => format!("Passphrase for {}", key),
+        };
+        self.view.mount(
+            super::COMPONENT_INPUT_KEY_PASSPHRASE,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label(label.as_str(), Alignment::Center)
+                    .with_input(InputType::Password)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_KEY_PASSPHRASE_CACHE,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(input_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_title("Remember for this session?", Alignment::Center)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .with_value(1)
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_KEY_PASSPHRASE);
+    }
+
+    /// ### umount_key_passphrase
+    ///
+    /// Umount the key passphrase prompt
+    pub(super) fn umount_key_passphrase(&mut self) {
+        self.view
+            .umount(super::COMPONENT_RADIO_KEY_PASSPHRASE_CACHE);
+        self.close_popup(super::COMPONENT_INPUT_KEY_PASSPHRASE);
+    }
+
+    pub(super) fn mount_find(&mut self, search: &str) {
+        // Get color
+        let (bg, fg, hg): (Color, Color, Color) = match self.browser.tab() {
+            FileExplorerTab::Local | FileExplorerTab::FindLocal => (
+                self.theme().transfer_local_explorer_background,
+                self.theme().transfer_local_explorer_foreground,
+                self.theme().transfer_local_explorer_highlighted,
+            ),
+            FileExplorerTab::Remote | FileExplorerTab::FindRemote => (
+                self.theme().transfer_remote_explorer_background,
+                self.theme().transfer_remote_explorer_foreground,
+                self.theme().transfer_remote_explorer_highlighted,
+            ),
+        };
+        // Mount component
+        self.view.mount(
+            super::COMPONENT_EXPLORER_FIND,
+            Box::new(FileList::new(
+                FileListPropsBuilder::default()
+                    .with_title(
+                        format!("Search results for \"{}\"", search),
+                        Alignment::Left,
+                    )
+                    .with_borders(Borders::ALL, BorderType::Plain, hg)
+                    .with_highlight_color(hg)
+                    .with_background(bg)
+                    .with_foreground(fg)
+                    .build(),
+            )),
+        );
+        // Give focus to explorer findd
+        self.open_popup(super::COMPONENT_EXPLORER_FIND);
+    }
+
+    pub(super) fn umount_find(&mut self) {
+        self.close_popup(super::COMPONENT_EXPLORER_FIND);
+    }
+
+    pub(super) fn mount_find_input(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        self.view.mount(
+            super::COMPONENT_INPUT_FIND,
+            Box::new(TextInput::new(
+                TextInputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Search files by name", Alignment::Center)
+                    .with_placeholder("e.g. *.txt")
+                    .with_history(&self.history.get(super::COMPONENT_INPUT_FIND))
+                    .build(),
+            )),
+        );
+        // Give focus to input find
+        self.open_popup(super::COMPONENT_INPUT_FIND);
+    }
+
+    pub(super) fn umount_find_input(&mut self) {
+        // Umount input find
+        self.close_popup(super::COMPONENT_INPUT_FIND);
+    }
+
+    pub(super) fn mount_goto(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        // Complete with directories in the current working directory
+        let explorer = match self.browser.tab() {
+            FileExplorerTab::Local | FileExplorerTab::FindLocal => self.local(),
+            FileExplorerTab::Remote | FileExplorerTab::FindRemote => self.remote(),
+        };
+        let directories: Vec<String> = explorer
+            .iter_files_all()
+            .filter(|x| x.is_dir())
+            .map(|x| format!("{}/", x.get_name()))
+            .collect();
+        self.view.mount(
+            super::COMPONENT_INPUT_GOTO,
+            Box::new(
+                TextInput::new(
+                    TextInputPropsBuilder::default()
+                        .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                        .with_foreground(input_color)
+                        .with_label("Change working directory", Alignment::Center)
+                        .with_placeholder("e.g. /home/user")
+                        .with_history(&self.history.get(super::COMPONENT_INPUT_GOTO))
+                        .build(),
+                )
+                .with_completion(move |input| complete_from(&directories, input)),
+            ),
+        );
+        self.open_popup(super::COMPONENT_INPUT_GOTO);
+    }
+
+    pub(super) fn umount_goto(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_GOTO);
+    }
+
+    pub(super) fn mount_mkdir(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        self.view.mount(
+            super::COMPONENT_INPUT_MKDIR,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Insert directory name", Alignment::Center)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_MKDIR);
+    }
+
+    pub(super) fn umount_mkdir(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_MKDIR);
+    }
+
+    pub(super) fn mount_newfile(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        self.view.mount(
+            super::COMPONENT_INPUT_NEWFILE,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label(
+                        "New file name (<CTRL+E> to create and edit)",
+                        Alignment::Center,
+                    )
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_NEWFILE);
+    }
+
+    pub(super) fn umount_newfile(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_NEWFILE);
+    }
+
+    pub(super) fn mount_openwith(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        self.view.mount(
+            super::COMPONENT_INPUT_OPEN_WITH,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Open file with…", Alignment::Center)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_OPEN_WITH);
+    }
+
+    pub(super) fn umount_openwith(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_OPEN_WITH);
+    }
+
+    pub(super) fn mount_rename(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        self.view.mount(
+            super::COMPONENT_INPUT_RENAME,
+            Box::new(TextInput::new(
+                TextInputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Move file(s) to…", Alignment::Center)
+                    .with_placeholder("e.g. ../backup/")
+                    .with_history(&self.history.get(super::COMPONENT_INPUT_RENAME))
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_RENAME);
+    }
+
+    pub(super) fn umount_rename(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_RENAME);
+    }
+
+    pub(super) fn mount_saveas(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        self.view.mount(
+            super::COMPONENT_INPUT_SAVEAS,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Save as…", Alignment::Center)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_INPUT_SAVEAS);
+    }
+
+    pub(super) fn umount_saveas(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_SAVEAS);
+    }
+
+    pub(super) fn mount_progress_bar(&mut self, root_name: String) {
+        let prog_color_full = self.theme().transfer_progress_bar_full;
+        let prog_color_partial = self.theme().transfer_progress_bar_partial;
+        self.view.mount(
+            super::COMPONENT_PROGRESS_BAR,
+            Box::new(TransferProgress::new(
+                TransferProgressPropsBuilder::default()
+                    .with_progbar_colors(prog_color_full, prog_color_partial)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::Reset)
+                    .with_title(root_name, Alignment::Center)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_PROGRESS_BAR);
+    }
+
+    pub(super) fn umount_progress_bar(&mut self) {
+        self.close_popup(super::COMPONENT_PROGRESS_BAR);
+        // Flush log records held back during the transfer
+        if self.low_bandwidth() {
+            let _ = self.update_logbox();
+        }
+    }
+
+    pub(super) fn mount_file_sorting(&mut self) {
+        let sorting_color = self.theme().transfer_status_sorting;
+        let sorting: FileSorting = match self.browser.tab() {
+            FileExplorerTab::Local => self.local().get_file_sorting(),
+            FileExplorerTab::Remote => self.remote().get_file_sorting(),
+            _ => panic!("You can't mount file sorting when in found result"),
+        };
+        let index: usize = match sorting {
+            FileSorting::CreationTime => 2,
+            FileSorting::ModifyTime => 1,
+            FileSorting::Name => 0,
+            FileSorting::Size => 3,
+            FileSorting::Extension => 4,
+            FileSorting::Owner => 5,
+            FileSorting::Permissions => 6,
+        };
+        self.view.mount(
+            super::COMPONENT_RADIO_SORTING,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(sorting_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, sorting_color)
+                    .with_title("Sort files by", Alignment::Center)
+                    .with_options(&[
+                        String::from("Name"),
+                        String::from("Modify time"),
+                        String::from("Creation time"),
+                        String::from("Size"),
+                        String::from("Extension"),
+                        String::from("Owner"),
+                        String::from("Permissions"),
+                    ])
+                    .with_value(index)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_SORTING);
+    }
+
+    pub(super) fn umount_file_sorting(&mut self) {
+        self.close_popup(super::COMPONENT_RADIO_SORTING);
+    }
+
+    pub(super) fn mount_radio_delete(&mut self) {
+        let warn_color = self.theme().misc_warn_dialog;
+        self.view.mount(
+            super::COMPONENT_RADIO_DELETE,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(warn_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Plain, warn_color)
+                    .with_title("Delete file", Alignment::Center)
+                    .with_options(&[
+                        String::from("Yes"),
+                        String::from("No"),
+                        String::from("Dry run"),
+                    ])
+                    .with_value(1)
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_DELETE);
+    }
+
+    pub(super) fn umount_radio_delete(&mut self) {
+        self.close_popup(super::COMPONENT_RADIO_DELETE);
+    }
+
+    pub(super) fn mount_radio_purge_trash(&mut self) {
+        let warn_color = self.theme().misc_warn_dialog;
+        self.view.mount(
+            super::COMPONENT_RADIO_PURGE_TRASH,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(warn_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Plain, warn_color)
+                    .with_title("Purge remote trash?", Alignment::Center)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .with_value(1)
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_PURGE_TRASH);
+    }
+
+    pub(super) fn umount_radio_purge_trash(&mut self) {
+        self.close_popup(super::COMPONENT_RADIO_PURGE_TRASH);
+    }
+
+    /// ### mount_radio_paste
+    ///
+    /// Mount the prompt asking what to do with the local paths pasted into the explorer
+    pub(super) fn mount_radio_paste(&mut self, paths: &[PathBuf]) {
+        let input_color = self.theme().misc_input_dialog;
+        let title: String = match paths {
+            [path] => format!("Pasted \"{}\"", fmt_path_elide(path.as_path(), 32)),
+            paths => format!("Pasted {} paths", paths.len()),
+        };
+        self.view.mount(
+            super::COMPONENT_RADIO_PASTE,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(input_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Plain, input_color)
+                    .with_title(title, Alignment::Center)
+                    .with_options(&[
+                        String::from("Upload here"),
+                        String::from("Go to"),
+                        String::from("Cancel"),
+                    ])
+                    .with_value(0)
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_PASTE);
+    }
+
+    pub(super) fn umount_radio_paste(&mut self) {
+        self.close_popup(super::COMPONENT_RADIO_PASTE);
+    }
+
+    /// ### mount_radio_journal
+    ///
+    /// Mount the prompt asking whether to resume the batch transfer interrupted by the last exit
+    pub(super) fn mount_radio_journal(&mut self, entry: &JournalEntry) {
+        let warn_color = self.theme().misc_warn_dialog;
+        self.view.mount(
+            super::COMPONENT_RADIO_JOURNAL,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(warn_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Plain, warn_color)
+                    .with_title(
+                        format!(
+                            "Resume {} of {} entries into \"{}\" (started {})?",
+                            match entry.upload {
+                                true => "upload",
+                                false => "download",
+                            },
+                            entry.sources.len(),
+                            entry.dest.display(),
+                            entry.started
+                        )
+                        .as_str(),
+                        Alignment::Center,
+                    )
+                    .with_options(&[String::from("Resume"), String::from("Discard")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_JOURNAL);
+    }
+
+    /// ### umount_radio_journal
+    ///
+    /// Umount the interrupted transfer prompt
+    pub(super) fn umount_radio_journal(&mut self) {
+        self.close_popup(super::COMPONENT_RADIO_JOURNAL);
+    }
+
+    /// ### mount_radio_stalled
+    ///
+    /// Mount the prompt asking what to do with an operation the remote stopped responding to
+    pub(super) fn mount_radio_stalled(&mut self, operation: &str) {
+        let warn_color = self.theme().misc_warn_dialog;
+        self.view.mount(
+            super::COMPONENT_RADIO_STALLED,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(warn_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Plain, warn_color)
+                    .with_title(
+                        format!("No response while {}", operation).as_str(),
+                        Alignment::Center,
+                    )
+                    .with_options(&[
+                        String::from("Wait"),
+                        String::from("Retry"),
+                        String::from("Abort"),
+                    ])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_STALLED);
+    }
+
+    /// ### umount_radio_stalled
+    ///
+    /// Umount the stalled operation prompt
+    pub(super) fn umount_radio_stalled(&mut self) {
+        self.close_popup(super::COMPONENT_RADIO_STALLED);
+    }
+
+    /// ### mount_radio_conflict
+    ///
+    /// Mount the prompt asking what to do with a file which already exists at the destination
+    pub(super) fn mount_radio_conflict(&mut self, dest: &Path, existing_size: usize, size: usize) {
+        let warn_color = self.theme().misc_warn_dialog;
+        let name: String = dest
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| dest.display().to_string());
+        self.view.mount(
+            super::COMPONENT_RADIO_CONFLICT,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(warn_color)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Plain, warn_color)
+                    .with_title(
+                        format!(
+                            "\"{}\" exists ({}, new {}); <A> for all",
+                            name,
+                            fmt_size(existing_size as u64),
+                            fmt_size(size as u64)
+                        )
+                        .as_str(),
+                        Alignment::Center,
+                    )
+                    .with_options(&[
+                        String::from("Overwrite"),
+                        String::from("Skip"),
+                        String::from("Resume"),
+                        String::from("Rename"),
+                    ])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RADIO_CONFLICT);
+    }
+
+    /// ### umount_radio_conflict
+    ///
+    /// Umount the existing file prompt
+    pub(super) fn umount_radio_conflict(&mut self) {
+        self.close_popup(super::COMPONENT_RADIO_CONFLICT);
+    }
+
+    pub(super) fn mount_favorites(&mut self) {
+        let favorites_color = self.theme().transfer_remote_explorer_highlighted;
+        let favorites: Vec<String> = self.remote_favorites();
+        self.view.mount(
+            super::COMPONENT_LIST_FAVORITES,
+            Box::new(BookmarkList::new(
+                BookmarkListPropsBuilder::default()
+                    .with_background(favorites_color)
+                    .with_foreground(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, favorites_color)
+                    .with_title("Favorites", Alignment::Left)
+                    .with_bookmarks(favorites)
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_LIST_FAVORITES);
+    }
+
+    pub(super) fn umount_favorites(&mut self) {
+        self.close_popup(super::COMPONENT_LIST_FAVORITES);
+    }
+
+    pub(super) fn mount_rules_editor(&mut self) {
+        let rules_color = self.theme().transfer_status_sync_browsing;
+        let rules: Vec<String> = self.filter.to_lines();
+        self.view.mount(
+            super::COMPONENT_RULES_EDITOR,
+            Box::new(RulesEditor::new(
+                RulesEditorPropsBuilder::default()
+                    .with_foreground(rules_color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, rules_color)
+                    .with_title(
+                        "Transfer filters (<PGUP/PGDOWN> move, <DEL> remove, <TAB> test)",
+                        Alignment::Left,
+                    )
+                    .with_rules(rules.as_slice())
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_RULES_EDITOR);
+    }
+
+    pub(super) fn umount_rules_editor(&mut self) {
+        self.close_popup(super::COMPONENT_RULES_EDITOR);
+    }
+
+    pub(super) fn mount_drift_report(&mut self, report: &DriftReport) {
+        let mut texts: TableBuilder = TableBuilder::default();
+        if report.is_clean() {
+            texts.add_col(TextSpan::new("No drift found").fg(Color::LightGreen));
+        }
+        for (i, drift) in report.drifts.iter().enumerate() {
+            if i > 0 {
+                texts.add_row();
+            }
+            texts
+                .add_col(TextSpan::new(drift.kind.describe().as_str()).fg(Color::LightRed))
+                .add_col(TextSpan::from(drift.path.display().to_string().as_str()));
+        }
+        self.view.mount(
+            super::COMPONENT_LIST_DRIFT,
+            Box::new(DataTable::new(
+                DataTablePropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                    .with_title(
+                        format!(
+                            "{} drift(s) in {} entries (<D> diff, <E> export to JSON)",
+                            report.drifts.len(),
+                            report.checked
+                        ),
+                        Alignment::Left,
+                    )
+                    .with_widths(&[ColumnWidth::Fixed(32), ColumnWidth::Flex(1)])
+                    .with_table(texts.build())
+                    .build(),
+            )),
+        );
+        self.open_popup(super::COMPONENT_LIST_DRIFT);
     }
 
-    pub(super) fn umount_mkdir(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_MKDIR);
+    pub(super) fn umount_drift_report(&mut self) {
+        self.close_popup(super::COMPONENT_LIST_DRIFT);
     }
 
-    pub(super) fn mount_newfile(&mut self) {
-        let input_color = self.theme().misc_input_dialog;
+    pub(super) fn mount_transfer_manifest(&mut self, manifest: &TransferManifest) {
+        let color: Color = self.theme().transfer_progress_bar_full;
         self.view.mount(
-            super::COMPONENT_INPUT_NEWFILE,
-            Box::new(Input::new(
-                InputPropsBuilder::default()
-                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
-                    .with_foreground(input_color)
-                    .with_label("New file name", Alignment::Center)
+            super::COMPONENT_LIST_MANIFEST,
+            Box::new(DataTable::new(
+                DataTablePropsBuilder::default()
+                    .with_foreground(color)
+                    .with_borders(Borders::ALL, BorderType::Rounded, color)
+                    .with_title(Self::manifest_title(manifest), Alignment::Left)
+                    .with_widths(&[
+                        ColumnWidth::Fixed(4),
+                        ColumnWidth::Flex(1),
+                        ColumnWidth::Fixed(10),
+                    ])
+                    .with_table(Self::manifest_table(manifest))
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_NEWFILE);
+        self.open_popup(super::COMPONENT_LIST_MANIFEST);
     }
 
-    pub(super) fn umount_newfile(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_NEWFILE);
+    pub(super) fn umount_transfer_manifest(&mut self) {
+        self.close_popup(super::COMPONENT_LIST_MANIFEST);
     }
 
-    pub(super) fn mount_openwith(&mut self) {
+    pub(super) fn mount_manifest_filter(&mut self) {
         let input_color = self.theme().misc_input_dialog;
         self.view.mount(
-            super::COMPONENT_INPUT_OPEN_WITH,
-            Box::new(Input::new(
-                InputPropsBuilder::default()
+            super::COMPONENT_INPUT_MANIFEST_FILTER,
+            Box::new(TextInput::new(
+                TextInputPropsBuilder::default()
                     .with_borders(Borders::ALL, BorderType::Rounded, input_color)
                     .with_foreground(input_color)
-                    .with_label("Open file with…", Alignment::Center)
+                    .with_label("Transfer only files matching", Alignment::Center)
+                    .with_placeholder("e.g. *.jpg, *.png")
+                    .with_history(&self.history.get(super::COMPONENT_INPUT_MANIFEST_FILTER))
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_OPEN_WITH);
+        self.open_popup(super::COMPONENT_INPUT_MANIFEST_FILTER);
     }
 
-    pub(super) fn umount_openwith(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_OPEN_WITH);
+    pub(super) fn umount_manifest_filter(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_MANIFEST_FILTER);
     }
 
-    pub(super) fn mount_rename(&mut self) {
-        let input_color = self.theme().misc_input_dialog;
+    /// ### manifest_title
+    ///
+    /// Make title for the transfer manifest popup, reporting the total of the selected files
+    pub(super) fn manifest_title(manifest: &TransferManifest) -> String {
+        format!(
+            "{} file(s), {} (<M> select, <F> only matching, <ENTER> expand, <D> dry run, <Y> start, <ESC> cancel)",
+            manifest.selected_files(),
+            fmt_size(manifest.total_size() as u64)
+        )
+    }
+
+    /// ### manifest_table
+    ///
+    /// Make the rows for the entries of the manifest which are not inside a collapsed directory
+    pub(super) fn manifest_table(manifest: &TransferManifest) -> TextTable {
+        let mut texts: TableBuilder = TableBuilder::default();
+        for (row, idx) in manifest.visible().into_iter().enumerate() {
+            let entry = manifest.get(idx).unwrap();
+            if row > 0 {
+                texts.add_row();
+            }
+            let name: String = match (entry.is_dir, entry.collapsed) {
+                (true, true) => format!("{}▸ {}/", "  ".repeat(entry.depth), entry.name),
+                (true, false) => format!("{}▾ {}/", "  ".repeat(entry.depth), entry.name),
+                (false, _) => format!("{}  {}", "  ".repeat(entry.depth), entry.name),
+            };
+            let size: String = match entry.is_dir {
+                true => String::new(),
+                false => fmt_size(entry.size as u64),
+            };
+            texts
+                .add_col(match entry.selected {
+                    true => TextSpan::new("[x]").fg(Color::LightGreen),
+                    false => TextSpan::new("[ ]").fg(Color::DarkGray),
+                })
+                .add_col(TextSpan::from(name.as_str()))
+                .add_col(TextSpan::from(size.as_str()));
+        }
+        texts.build()
+    }
+
+    /// ### mount_usage_report
+    ///
+    /// Mount the bandwidth used with the remote host, month by month; `current` is the usage of this month
+    pub(super) fn mount_dry_run_report(&mut self, report: &DryRunReport) {
+        let mut texts: TableBuilder = TableBuilder::default();
+        if report.entries.is_empty() {
+            texts.add_col(TextSpan::new("Nothing to do").fg(Color::LightGreen));
+        }
+        for (i, entry) in report.entries.iter().enumerate() {
+            if i > 0 {
+                texts.add_row();
+            }
+            let color: Color = match entry.action {
+                DryRunAction::Mkdir | DryRunAction::Copy => Color::LightGreen,
+                DryRunAction::Skip => Color::DarkGray,
+                DryRunAction::Overwrite
+                | DryRunAction::Resume
+                | DryRunAction::Rename
+                | DryRunAction::Ask => Color::LightYellow,
+                DryRunAction::Delete | DryRunAction::Trash => Color::LightRed,
+            };
+            texts
+                .add_col(TextSpan::new(entry.action.describe()).fg(color))
+                .add_col(TextSpan::from(entry.path.display().to_string().as_str()));
+        }
         self.view.mount(
-            super::COMPONENT_INPUT_RENAME,
-            Box::new(Input::new(
-                InputPropsBuilder::default()
-                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
-                    .with_foreground(input_color)
-                    .with_label("Move file(s) to…", Alignment::Center)
+            super::COMPONENT_LIST_DRY_RUN,
+            Box::new(DataTable::new(
+                DataTablePropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                    .with_title(
+                        format!(
+                            "Dry run: {}; {} to write (<Y> confirm, <ESC> back)",
+                            report.summary(),
+                            fmt_size(report.written() as u64)
+                        ),
+                        Alignment::Left,
+                    )
+                    .with_widths(&[ColumnWidth::Fixed(18), ColumnWidth::Flex(1)])
+                    .with_table(texts.build())
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_RENAME);
+        self.open_popup(super::COMPONENT_LIST_DRY_RUN);
     }
 
-    pub(super) fn umount_rename(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_RENAME);
+    pub(super) fn umount_dry_run_report(&mut self) {
+        self.close_popup(super::COMPONENT_LIST_DRY_RUN);
     }
 
-    pub(super) fn mount_saveas(&mut self) {
-        let input_color = self.theme().misc_input_dialog;
+    pub(super) fn mount_usage_report(&mut self, current: Usage, usage: &[(String, Usage)]) {
+        let mut texts: TableBuilder = TableBuilder::default();
+        texts
+            .add_col(TextSpan::new("Month").bold())
+            .add_col(TextSpan::new("Uploaded").bold())
+            .add_col(TextSpan::new("Downloaded").bold())
+            .add_col(TextSpan::new("Total").bold());
+        for (month, usage) in usage.iter() {
+            texts
+                .add_row()
+                .add_col(TextSpan::from(month.as_str()))
+                .add_col(TextSpan::from(fmt_size(usage.uploaded).as_str()))
+                .add_col(TextSpan::from(fmt_size(usage.downloaded).as_str()))
+                .add_col(TextSpan::new(fmt_size(usage.total()).as_str()).fg(Color::LightYellow));
+        }
         self.view.mount(
-            super::COMPONENT_INPUT_SAVEAS,
-            Box::new(Input::new(
-                InputPropsBuilder::default()
-                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
-                    .with_foreground(input_color)
-                    .with_label("Save as…", Alignment::Center)
+            super::COMPONENT_LIST_USAGE,
+            Box::new(DataTable::new(
+                DataTablePropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                    .with_title(
+                        format!(
+                            "Bandwidth usage: {} this month (<ESC> close)",
+                            fmt_size(current.total())
+                        ),
+                        Alignment::Left,
+                    )
+                    .with_widths(&[
+                        ColumnWidth::Fixed(10),
+                        ColumnWidth::Flex(1),
+                        ColumnWidth::Flex(1),
+                        ColumnWidth::Flex(1),
+                    ])
+                    .with_table(texts.build())
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_SAVEAS);
+        self.open_popup(super::COMPONENT_LIST_USAGE);
     }
 
-    pub(super) fn umount_saveas(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_SAVEAS);
+    pub(super) fn umount_usage_report(&mut self) {
+        self.close_popup(super::COMPONENT_LIST_USAGE);
     }
 
-    pub(super) fn mount_progress_bar(&mut self, root_name: String) {
-        let prog_color_full = self.theme().transfer_progress_bar_full;
-        let prog_color_partial = self.theme().transfer_progress_bar_partial;
+    /// ### mount_banner
+    ///
+    /// Mount the welcome message sent by `address` on connect
+    pub(super) fn mount_banner(&mut self, address: &str, banner: &str) {
+        let spans: Vec<TextSpan> = fmt_banner(banner)
+            .iter()
+            .map(|x| TextSpan::from(x.as_str()))
+            .collect();
         self.view.mount(
-            super::COMPONENT_PROGRESS_BAR_FULL,
-            Box::new(ProgressBar::new(
-                ProgressBarPropsBuilder::default()
-                    .with_progbar_color(prog_color_full)
-                    .with_background(Color::Black)
-                    .with_borders(
-                        Borders::TOP | Borders::RIGHT | Borders::LEFT,
-                        BorderType::Rounded,
-                        Color::Reset,
+            super::COMPONENT_TEXT_BANNER,
+            Box::new(Textarea::new(
+                TextareaPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                    .with_title(
+                        format!("Welcome from {}", address).as_str(),
+                        Alignment::Center,
                     )
-                    .with_title(root_name, Alignment::Center)
+                    .with_texts(spans)
                     .build(),
             )),
         );
+        self.open_popup(super::COMPONENT_TEXT_BANNER);
+    }
+
+    /// ### umount_banner
+    ///
+    /// Umount the welcome message
+    pub(super) fn umount_banner(&mut self) {
+        self.close_popup(super::COMPONENT_TEXT_BANNER);
+    }
+
+    /// ### mount_auth_banner
+    ///
+    /// Mount the banner sent by the server before authentication
+    pub(super) fn mount_auth_banner(&mut self, address: &str, banner: &str) {
+        let spans: Vec<TextSpan> = fmt_banner(banner)
+            .iter()
+            .map(|x| TextSpan::from(x.as_str()))
+            .collect();
         self.view.mount(
-            super::COMPONENT_PROGRESS_BAR_PARTIAL,
-            Box::new(ProgressBar::new(
-                ProgressBarPropsBuilder::default()
-                    .with_progbar_color(prog_color_partial)
-                    .with_background(Color::Black)
-                    .with_borders(
-                        Borders::BOTTOM | Borders::RIGHT | Borders::LEFT,
-                        BorderType::Rounded,
-                        Color::Reset,
+            super::COMPONENT_TEXT_AUTH_BANNER,
+            Box::new(Textarea::new(
+                TextareaPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::Yellow)
+                    .with_title(
+                        format!(
+                            "{} before login: <ENTER> to continue, <ESC> to leave",
+                            address
+                        )
+                        .as_str(),
+                        Alignment::Center,
                     )
-                    .with_title("Please wait", Alignment::Center)
+                    .with_texts(spans)
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_PROGRESS_BAR_PARTIAL);
+        self.open_popup(super::COMPONENT_TEXT_AUTH_BANNER);
     }
 
-    pub(super) fn umount_progress_bar(&mut self) {
-        self.view.umount(super::COMPONENT_PROGRESS_BAR_PARTIAL);
-        self.view.umount(super::COMPONENT_PROGRESS_BAR_FULL);
+    /// ### umount_auth_banner
+    ///
+    /// Umount the banner sent before authentication
+    pub(super) fn umount_auth_banner(&mut self) {
+        self.close_popup(super::COMPONENT_TEXT_AUTH_BANNER);
     }
 
-    pub(super) fn mount_file_sorting(&mut self) {
-        let sorting_color = self.theme().transfer_status_sorting;
-        let sorting: FileSorting = match self.browser.tab() {
-            FileExplorerTab::Local => self.local().get_file_sorting(),
-            FileExplorerTab::Remote => self.remote().get_file_sorting(),
-            _ => panic!("You can't mount file sorting when in found result"),
+    /// ### mount_hexdump
+    ///
+    /// Mount the hex dump of the block being viewed
+    pub(super) fn mount_hexdump(&mut self) {
+        let view = match self.hex_view.as_ref() {
+            Some(view) => view.clone(),
+            None => return,
         };
-        let index: usize = match sorting {
-            FileSorting::CreationTime => 2,
-            FileSorting::ModifyTime => 1,
-            FileSorting::Name => 0,
-            FileSorting::Size => 3,
+        let lines: Vec<String> = match view.read_block() {
+            Ok(lines) => lines,
+            Err(err) => {
+                self.hex_view = None;
+                self.umount_hexdump();
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not read \"{}\": {}", view.name, err),
+                );
+                return;
+            }
         };
+        let spans: Vec<TextSpan> = lines.iter().map(|x| TextSpan::from(x.as_str())).collect();
         self.view.mount(
-            super::COMPONENT_RADIO_SORTING,
-            Box::new(Radio::new(
-                RadioPropsBuilder::default()
-                    .with_color(sorting_color)
-                    .with_inverted_color(Color::Black)
-                    .with_borders(Borders::ALL, BorderType::Rounded, sorting_color)
-                    .with_title("Sort files by", Alignment::Center)
-                    .with_options(&[
-                        String::from("Name"),
-                        String::from("Modify time"),
-                        String::from("Creation time"),
-                        String::from("Size"),
-                    ])
-                    .with_value(index)
+            super::COMPONENT_TEXT_HEXDUMP,
+            Box::new(Textarea::new(
+                TextareaPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightMagenta)
+                    .with_title(
+                        format!(
+                            "{} [{:08x}-{:08x} of {}]: <N>/<P> next/previous block, <G> go to offset",
+                            view.name,
+                            view.offset,
+                            view.end(),
+                            fmt_size(view.size)
+                        )
+                        .as_str(),
+                        Alignment::Center,
+                    )
+                    .with_texts(spans)
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_RADIO_SORTING);
+        self.open_popup(super::COMPONENT_TEXT_HEXDUMP);
     }
 
-    pub(super) fn umount_file_sorting(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_SORTING);
+    /// ### umount_hexdump
+    ///
+    /// Umount the hex dump
+    pub(super) fn umount_hexdump(&mut self) {
+        self.close_popup(super::COMPONENT_TEXT_HEXDUMP);
     }
 
-    pub(super) fn mount_radio_delete(&mut self) {
-        let warn_color = self.theme().misc_warn_dialog;
+    /// ### mount_hexdump_offset
+    ///
+    /// Mount the input to jump to an offset of the hex dump
+    pub(super) fn mount_hexdump_offset(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
         self.view.mount(
-            super::COMPONENT_RADIO_DELETE,
-            Box::new(Radio::new(
-                RadioPropsBuilder::default()
-                    .with_color(warn_color)
-                    .with_inverted_color(Color::Black)
-                    .with_borders(Borders::ALL, BorderType::Plain, warn_color)
-                    .with_title("Delete file", Alignment::Center)
-                    .with_options(&[String::from("Yes"), String::from("No")])
-                    .with_value(1)
-                    .rewind(true)
+            super::COMPONENT_INPUT_HEXDUMP_OFFSET,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, input_color)
+                    .with_foreground(input_color)
+                    .with_label("Go to offset", Alignment::Center)
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_RADIO_DELETE);
+        self.open_popup(super::COMPONENT_INPUT_HEXDUMP_OFFSET);
     }
 
-    pub(super) fn umount_radio_delete(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_DELETE);
+    /// ### umount_hexdump_offset
+    ///
+    /// Umount the hex dump offset input
+    pub(super) fn umount_hexdump_offset(&mut self) {
+        self.close_popup(super::COMPONENT_INPUT_HEXDUMP_OFFSET);
     }
 
-    pub(super) fn mount_file_info(&mut self, file: &FsEntry) {
+    pub(super) fn mount_file_info(&mut self, file: &FsEntry, links: Option<u64>) {
         let mut texts: TableBuilder = TableBuilder::default();
         // Abs path
         let real_path: Option<PathBuf> = {
@@ -849,14 +2139,21 @@ impl FileTransferActivity {
                 .add_col(TextSpan::from("File type: "))
                 .add_col(TextSpan::new(filetype.as_str()).fg(Color::LightGreen));
         }
-        let (bsize, size): (ByteSize, usize) = (ByteSize(file.get_size() as u64), file.get_size());
+        let (bsize, size): (String, usize) = (fmt_size(file.get_size() as u64), file.get_size());
         texts
             .add_row()
             .add_col(TextSpan::from("Size: "))
             .add_col(TextSpan::new(format!("{} ({})", bsize, size).as_str()).fg(Color::Cyan));
-        let ctime: String = fmt_time(file.get_creation_time(), "%b %d %Y %H:%M:%S");
-        let atime: String = fmt_time(file.get_last_access_time(), "%b %d %Y %H:%M:%S");
-        let mtime: String = fmt_time(file.get_creation_time(), "%b %d %Y %H:%M:%S");
+        if let Some(links) = links {
+            texts
+                .add_row()
+                .add_col(TextSpan::from("Hard links: "))
+                .add_col(TextSpan::new(links.to_string().as_str()).fg(Color::LightCyan));
+        }
+        let datetime_fmt: String = display_format().datetime_format(true);
+        let ctime: String = fmt_time(file.get_creation_time(), datetime_fmt.as_str());
+        let atime: String = fmt_time(file.get_last_access_time(), datetime_fmt.as_str());
+        let mtime: String = fmt_time(file.get_creation_time(), datetime_fmt.as_str());
         texts
             .add_row()
             .add_col(TextSpan::from("Creation time: "))
@@ -901,25 +2198,26 @@ impl FileTransferActivity {
             .add_col(TextSpan::new(group.as_str()).fg(Color::Blue));
         self.view.mount(
             super::COMPONENT_LIST_FILEINFO,
-            Box::new(Table::new(
-                TablePropsBuilder::default()
+            Box::new(DataTable::new(
+                DataTablePropsBuilder::default()
                     .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
                     .with_title(file.get_name(), Alignment::Left)
+                    .with_widths(&[ColumnWidth::Fixed(20), ColumnWidth::Flex(1)])
                     .with_table(texts.build())
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_LIST_FILEINFO);
+        self.open_popup(super::COMPONENT_LIST_FILEINFO);
     }
 
     pub(super) fn umount_file_info(&mut self) {
-        self.view.umount(super::COMPONENT_LIST_FILEINFO);
+        self.close_popup(super::COMPONENT_LIST_FILEINFO);
     }
 
     pub(super) fn refresh_local_status_bar(&mut self) {
         let sorting_color = self.theme().transfer_status_sorting;
         let hidden_color = self.theme().transfer_status_hidden;
-        let local_bar_spans: Vec<TextSpan> = vec![
+        let mut local_bar_spans: Vec<TextSpan> = vec![
             TextSpan::new("File sorting: ").fg(sorting_color),
             TextSpan::new(Self::get_file_sorting_str(self.local().get_file_sorting()))
                 .fg(sorting_color)
@@ -931,6 +2229,33 @@ impl FileTransferActivity {
             .fg(hidden_color)
             .reversed(),
         ];
+        if self.context().safe_mode() {
+            local_bar_spans.insert(0, TextSpan::new("SAFE MODE").fg(Color::LightRed).reversed());
+            local_bar_spans.insert(1, TextSpan::from(" "));
+        }
+        if let Some(dir) = self.config().get_download_dir() {
+            let sync_color = self.theme().transfer_status_sync_browsing;
+            let dest: String = match self.browser.download_to_default {
+                true => dir.display().to_string(),
+                false => String::from("HERE"),
+            };
+            local_bar_spans.push(TextSpan::new(" Downloads: ").fg(sync_color));
+            local_bar_spans.push(TextSpan::new(dest.as_str()).fg(sync_color).reversed());
+        }
+        if self.trace_next {
+            local_bar_spans.push(TextSpan::new(" Trace: ").fg(hidden_color));
+            local_bar_spans.push(TextSpan::new("NEXT").fg(hidden_color).reversed());
+        }
+        if let Some(watch) = self.watch.as_ref() {
+            let sync_color = self.theme().transfer_status_sync_browsing;
+            let watching: String = format!(
+                "{} -> {}",
+                watch.local().display(),
+                watch.remote().display()
+            );
+            local_bar_spans.push(TextSpan::new(" Watch: ").fg(sync_color));
+            local_bar_spans.push(TextSpan::new(watching.as_str()).fg(sync_color).reversed());
+        }
         if let Some(props) = self.view.get_props(super::COMPONENT_SPAN_STATUS_BAR_LOCAL) {
             self.view.update(
                 super::COMPONENT_SPAN_STATUS_BAR_LOCAL,
@@ -945,7 +2270,7 @@ impl FileTransferActivity {
         let sorting_color = self.theme().transfer_status_sorting;
         let hidden_color = self.theme().transfer_status_hidden;
         let sync_color = self.theme().transfer_status_sync_browsing;
-        let remote_bar_spans: Vec<TextSpan> = vec![
+        let mut remote_bar_spans: Vec<TextSpan> = vec![
             TextSpan::new("File sorting: ").fg(sorting_color),
             TextSpan::new(Self::get_file_sorting_str(self.remote().get_file_sorting()))
                 .fg(sorting_color)
@@ -964,6 +2289,32 @@ impl FileTransferActivity {
             .fg(sync_color)
             .reversed(),
         ];
+        if let Some(peer) = self.peer.as_ref() {
+            remote_bar_spans.push(TextSpan::new(" Peer: ").fg(sync_color));
+            remote_bar_spans.push(
+                TextSpan::new(
+                    format!("{}:{}", peer.params.address, peer.wrkdir.display()).as_str(),
+                )
+                .fg(sync_color)
+                .reversed(),
+            );
+        }
+        if let Some(env) = self.remote_env.as_ref() {
+            remote_bar_spans.push(TextSpan::new(" Host: ").fg(hidden_color));
+            remote_bar_spans.push(
+                TextSpan::new(format!("{} ({})", env.hostname, env.system()).as_str())
+                    .fg(hidden_color)
+                    .reversed(),
+            );
+            if let Some(disk) = env.disk {
+                remote_bar_spans.push(TextSpan::new(" Disk: ").fg(sorting_color));
+                remote_bar_spans.push(
+                    TextSpan::new(disk.to_string().as_str())
+                        .fg(sorting_color)
+                        .reversed(),
+                );
+            }
+        }
         if let Some(props) = self.view.get_props(super::COMPONENT_SPAN_STATUS_BAR_REMOTE) {
             self.view.update(
                 super::COMPONENT_SPAN_STATUS_BAR_REMOTE,
@@ -979,6 +2330,18 @@ impl FileTransferActivity {
     /// Mount help
     pub(super) fn mount_help(&mut self) {
         let key_color = self.theme().misc_keys;
+        let (exec_key, exec_desc) = self.help_gated_row(
+            "<X>",
+            "             Execute shell command",
+            Capability::Exec,
+            key_color,
+        );
+        let (chmod_key, chmod_desc) = self.help_gated_row(
+            "<SHIFT+M>",
+            "       Change permissions and owner (optionally recursive)",
+            Capability::SetStat,
+            key_color,
+        );
         self.view.mount(
             super::COMPONENT_TEXT_HELP,
             Box::new(List::new(
@@ -1035,18 +2398,35 @@ impl FileTransferActivity {
                             .add_col(TextSpan::new("<I>").bold().fg(key_color))
                             .add_col(TextSpan::from("             Show info about selected file"))
                             .add_row()
+                            .add_col(TextSpan::new("<J>").bold().fg(key_color))
+                            .add_col(TextSpan::from("             Show remote favorites"))
+                            .add_row()
+                            .add_col(TextSpan::new("<K>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "             Add remote directory to favorites",
+                            ))
+                            .add_row()
                             .add_col(TextSpan::new("<L>").bold().fg(key_color))
                             .add_col(TextSpan::from("             Reload directory content"))
                             .add_row()
                             .add_col(TextSpan::new("<M>").bold().fg(key_color))
-                            .add_col(TextSpan::from("             Select file"))
+                            .add_col(TextSpan::from(
+                                "             Mark file; marks are kept across directories",
+                            ))
                             .add_row()
                             .add_col(TextSpan::new("<N>").bold().fg(key_color))
-                            .add_col(TextSpan::from("             Create new file"))
+                            .add_col(TextSpan::from(
+                                "             Create new file; <CTRL+E> to also edit it",
+                            ))
                             .add_row()
                             .add_col(TextSpan::new("<O>").bold().fg(key_color))
                             .add_col(TextSpan::from(
-                                "             Open text file with preferred editor",
+                                "             Open text file with preferred editor; binary as hex dump",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<P>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "             Open matching path in the other pane",
                             ))
                             .add_row()
                             .add_col(TextSpan::new("<Q>").bold().fg(key_color))
@@ -1058,6 +2438,11 @@ impl FileTransferActivity {
                             .add_col(TextSpan::new("<S>").bold().fg(key_color))
                             .add_col(TextSpan::from("             Save file as"))
                             .add_row()
+                            .add_col(TextSpan::new("<T>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "             Edit include/exclude rules for transfers",
+                            ))
+                            .add_row()
                             .add_col(TextSpan::new("<U>").bold().fg(key_color))
                             .add_col(TextSpan::from("             Go to parent directory"))
                             .add_row()
@@ -1071,12 +2456,15 @@ impl FileTransferActivity {
                                 "             Open file with specified application",
                             ))
                             .add_row()
-                            .add_col(TextSpan::new("<X>").bold().fg(key_color))
-                            .add_col(TextSpan::from("             Execute shell command"))
+                            .add_col(exec_key)
+                            .add_col(exec_desc)
                             .add_row()
                             .add_col(TextSpan::new("<Y>").bold().fg(key_color))
                             .add_col(TextSpan::from("             Toggle synchronized browsing"))
                             .add_row()
+                            .add_col(TextSpan::new("<Z>").bold().fg(key_color))
+                            .add_col(TextSpan::from("             Duplicate selected file"))
+                            .add_row()
                             .add_col(TextSpan::new("<DEL|E>").bold().fg(key_color))
                             .add_col(TextSpan::from("         Delete selected file"))
                             .add_row()
@@ -1085,17 +2473,163 @@ impl FileTransferActivity {
                             .add_row()
                             .add_col(TextSpan::new("<CTRL+C>").bold().fg(key_color))
                             .add_col(TextSpan::from("        Interrupt file transfer"))
+                            .add_row()
+                            .add_col(TextSpan::new("<P>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "             Pause / resume file transfer (while transferring)",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("</>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "             Jump to entry by typing its name",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+A>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Toggle ssh agent forwarding for <X> (saved to bookmark)",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+D>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Purge remote trash (if set in bookmark)",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+E>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Enter current directory at connection (saved to bookmark)",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+G>").bold().fg(key_color))
+                            .add_col(TextSpan::from("       Fetch URL into download directory"))
+                            .add_row()
+                            .add_col(chmod_key)
+                            .add_col(chmod_desc)
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+O>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Toggle downloads to default directory / local directory",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+P>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Map directories to each other (saved to bookmark)",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+V>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Verify local against remote directory",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+U>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Show bandwidth used with this host, month by month",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+W>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Start/stop uploading local changes as they happen",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+C>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Copy ssh command to open remote directory in a shell",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+Q>").bold().fg(key_color))
+                            .add_col(TextSpan::from("       Start/stop recording a macro"))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+S>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Move file (upload/download, then remove source)",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+T>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Write a debug trace of the next transfer",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<SHIFT+X>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "       Connect to peer / relay remote files to peer",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<CTRL+X>").bold().fg(key_color))
+                            .add_col(TextSpan::from("        Disconnect from peer"))
+                            .add_row()
+                            .add_col(TextSpan::new("<F2>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "            Change transfer options of the session",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<@>").bold().fg(key_color))
+                            .add_col(TextSpan::from("             Replay recorded macro"))
+                            .add_row()
+                            .add_col(TextSpan::from("Log panel"))
+                            .add_row()
+                            .add_col(TextSpan::new("</>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "             Search log; <N> for next match",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::new("<E>").bold().fg(key_color))
+                            .add_col(TextSpan::from("             Go to next error"))
+                            .add_row()
+                            .add_col(TextSpan::new("<Y>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "             Copy selected line to clipboard",
+                            ))
+                            .add_row()
+                            .add_col(TextSpan::from("Error popup"))
+                            .add_row()
+                            .add_col(TextSpan::new("<D>").bold().fg(key_color))
+                            .add_col(TextSpan::from("             Show/hide technical details"))
+                            .add_row()
+                            .add_col(TextSpan::new("<C>").bold().fg(key_color))
+                            .add_col(TextSpan::from(
+                                "             Copy error report to clipboard",
+                            ))
                             .build(),
                     )
                     .build(),
             )),
         );
         // Active help
-        self.view.active(super::COMPONENT_TEXT_HELP);
+        self.open_popup(super::COMPONENT_TEXT_HELP);
     }
 
     pub(super) fn umount_help(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_HELP);
+        self.close_popup(super::COMPONENT_TEXT_HELP);
+    }
+
+    /// ### help_gated_row
+    ///
+    /// Make the key and description of a help row, greyed out if the remote doesn't support `capability`,
+    /// in which case the action is available on localhost only
+    fn help_gated_row(
+        &self,
+        key: &str,
+        desc: &str,
+        capability: Capability,
+        key_color: Color,
+    ) -> (TextSpan, TextSpan) {
+        match self.capabilities.supports(capability) {
+            true => (
+                TextSpan::new(key).bold().fg(key_color),
+                TextSpan::from(desc),
+            ),
+            false => (
+                TextSpan::new(key).bold().fg(Color::DarkGray),
+                TextSpan::new(
+                    format!(
+                        "{} (local only: the server can't {})",
+                        desc,
+                        capability.description()
+                    )
+                    .as_str(),
+                )
+                .fg(Color::DarkGray),
+            ),
+        }
     }
 
     fn get_file_sorting_str(mode: FileSorting) -> &'static str {
@@ -1104,6 +2638,9 @@ impl FileTransferActivity {
             FileSorting::CreationTime => "By creation time",
             FileSorting::ModifyTime => "By modify time",
             FileSorting::Size => "By size",
+            FileSorting::Extension => "By extension",
+            FileSorting::Owner => "By owner",
+            FileSorting::Permissions => "By permissions",
         }
     }
 
@@ -33,6 +33,9 @@ pub mod auth;
 pub mod filetransfer;
 pub mod setup;
 
+/// Store key of the master password the bookmarks have been unlocked with; shared between activities
+pub(crate) const STORE_KEY_BOOKMARKS_MASTER_PASSWORD: &str = "BOOKMARKS_MASTER_PASSWORD";
+
 // -- Exit reason
 
 pub enum ExitReason {
@@ -28,6 +28,7 @@
  */
 // Locals
 use super::{SetupActivity, ViewLayout};
+use crate::utils::parser::parse_color;
 // Ext
 use crate::config::themes::Theme;
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
@@ -176,7 +177,10 @@ impl SetupActivity {
         };
         // Prepare text editor
         env::set_var("EDITOR", self.config().get_text_editor());
-        let placeholder: String = format!("# Type private SSH key for {}@{}\n", username, host);
+        let placeholder: String = format!(
+            "# Type private SSH key for {}@{}, optionally followed by its OpenSSH certificate\n",
+            username, host
+        );
         // Put input mode back to normal
         if let Err(err) = disable_raw_mode() {
             error!("Failed to disable raw mode: {}", err);
@@ -199,10 +203,16 @@ impl SetupActivity {
                     // Report error: empty key
                     self.mount_error("SSH key is empty!");
                 } else {
+                    // Split certificate from the private key
+                    let (rsa_key, certificate): (String, Option<String>) =
+                        Self::split_ssh_certificate(rsa_key.as_str());
                     // Add key
-                    if let Err(err) =
-                        self.add_ssh_key(host.as_str(), username.as_str(), rsa_key.as_str())
-                    {
+                    if let Err(err) = self.add_ssh_key(
+                        host.as_str(),
+                        username.as_str(),
+                        rsa_key.as_str(),
+                        certificate.as_deref(),
+                    ) {
                         self.mount_error(
                             format!("Could not create new private key: {}", err).as_str(),
                         );
@@ -224,6 +234,20 @@ impl SetupActivity {
         }
     }
 
+    /// ### action_submit_color_palette
+    ///
+    /// Write the color chosen in the color palette into the theme field which opened it
+    pub(super) fn action_submit_color_palette(&mut self, color: &str) {
+        if let (Some(component), Some(color)) =
+            (self.color_palette_target.take(), parse_color(color))
+        {
+            self.update_color(component.as_str(), color);
+            self.action_save_color(component.as_str(), color);
+            // Set unsaved changes to true
+            self.set_config_changed(true);
+        }
+    }
+
     /// ### set_color
     ///
     /// Given a component and a color, save the color into the theme
@@ -28,6 +28,7 @@
  */
 // Locals
 use super::SetupActivity;
+use crate::system::sshkey_storage::SshKeyStorage;
 // Ext
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use std::env;
@@ -137,15 +138,35 @@ impl SetupActivity {
 
     /// ### add_ssh_key
     ///
-    /// Add provided ssh key to config client
+    /// Add provided ssh key, along with its certificate if any, to config client
     pub(super) fn add_ssh_key(
         &mut self,
         host: &str,
         username: &str,
         rsa_key: &str,
+        certificate: Option<&str>,
     ) -> Result<(), String> {
         self.config_mut()
             .add_ssh_key(host, username, rsa_key)
-            .map_err(|e| format!("Could not add SSH key: {}", e))
+            .map_err(|e| format!("Could not add SSH key: {}", e))?;
+        self.config_mut()
+            .set_ssh_certificate(host, username, certificate)
+            .map_err(|e| format!("Could not add SSH certificate: {}", e))
+    }
+
+    /// ### split_ssh_certificate
+    ///
+    /// Split the OpenSSH certificate lines out of the text typed in the ssh key editor.
+    /// Returns the private key and the certificate, if any
+    pub(super) fn split_ssh_certificate(text: &str) -> (String, Option<String>) {
+        let (certificate, key): (Vec<&str>, Vec<&str>) =
+            text.lines().partition(|x| SshKeyStorage::is_certificate(x));
+        match certificate.is_empty() {
+            true => (text.to_string(), None),
+            false => (
+                format!("{}\n", key.join("\n")),
+                Some(format!("{}\n", certificate.join("\n"))),
+            ),
+        }
     }
 }
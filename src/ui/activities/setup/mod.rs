@@ -37,7 +37,10 @@ use super::{Activity, Context, ExitReason};
 use crate::config::themes::Theme;
 use crate::system::config_client::ConfigClient;
 use crate::system::theme_provider::ThemeProvider;
+use crate::ui::focus::FocusRing;
+use crate::ui::modal::ModalStack;
 // Ext
+use crossterm::event::Event;
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use tuirealm::{Update, View};
 
@@ -53,10 +56,37 @@ const COMPONENT_RADIO_TAB: &str = "RADIO_TAB";
 const COMPONENT_INPUT_TEXT_EDITOR: &str = "INPUT_TEXT_EDITOR";
 const COMPONENT_RADIO_DEFAULT_PROTOCOL: &str = "RADIO_DEFAULT_PROTOCOL";
 const COMPONENT_RADIO_HIDDEN_FILES: &str = "RADIO_HIDDEN_FILES";
+const COMPONENT_RADIO_SYMLINKS: &str = "RADIO_SYMLINKS";
 const COMPONENT_RADIO_UPDATES: &str = "RADIO_CHECK_UPDATES";
+const COMPONENT_INPUT_TRANSFER_RETRIES: &str = "INPUT_TRANSFER_RETRIES";
+const COMPONENT_RADIO_LOW_BANDWIDTH: &str = "RADIO_LOW_BANDWIDTH";
+const COMPONENT_RADIO_COMPRESSION: &str = "RADIO_COMPRESSION";
+const COMPONENT_RADIO_DELTA_TRANSFER: &str = "RADIO_DELTA_TRANSFER";
+const COMPONENT_RADIO_SKIP_UNCHANGED: &str = "RADIO_SKIP_UNCHANGED";
+const COMPONENT_RADIO_SHOW_BANNER: &str = "RADIO_SHOW_BANNER";
+const COMPONENT_RADIO_PREFETCH_DIRS: &str = "RADIO_PREFETCH_DIRS";
 const COMPONENT_RADIO_GROUP_DIRS: &str = "RADIO_GROUP_DIRS";
+const COMPONENT_RADIO_PRESERVE_OWNER: &str = "RADIO_PRESERVE_OWNER";
+const COMPONENT_RADIO_FILE_SORTING: &str = "RADIO_FILE_SORTING";
+const COMPONENT_RADIO_SIZE_UNITS: &str = "RADIO_SIZE_UNITS";
+const COMPONENT_RADIO_CLOCK: &str = "RADIO_CLOCK";
+const COMPONENT_INPUT_DATE_FORMAT: &str = "INPUT_DATE_FORMAT";
 const COMPONENT_INPUT_LOCAL_FILE_FMT: &str = "INPUT_LOCAL_FILE_FMT";
 const COMPONENT_INPUT_REMOTE_FILE_FMT: &str = "INPUT_REMOTE_FILE_FMT";
+const COMPONENT_INPUT_PROXY: &str = "INPUT_PROXY";
+const COMPONENT_INPUT_STALL_TIMEOUT: &str = "INPUT_STALL_TIMEOUT";
+const COMPONENT_INPUT_DOWNLOAD_DIR: &str = "INPUT_DOWNLOAD_DIR";
+const COMPONENT_INPUT_PARALLEL_TRANSFERS: &str = "INPUT_PARALLEL_TRANSFERS";
+const COMPONENT_INPUT_RECENTS_SIZE: &str = "INPUT_RECENTS_SIZE";
+const COMPONENT_INPUT_RECENTS_MAX_AGE: &str = "INPUT_RECENTS_MAX_AGE";
+const COMPONENT_INPUT_DIFF_TOOL: &str = "INPUT_DIFF_TOOL";
+const COMPONENT_INPUT_MERGE_TOOL: &str = "INPUT_MERGE_TOOL";
+const COMPONENT_INPUT_WEBHOOK_URL: &str = "INPUT_WEBHOOK_URL";
+const COMPONENT_INPUT_WEBHOOK_TEMPLATE: &str = "INPUT_WEBHOOK_TEMPLATE";
+const COMPONENT_INPUT_METRICS_FILE: &str = "INPUT_METRICS_FILE";
+const COMPONENT_INPUT_METRICS_PORT: &str = "INPUT_METRICS_PORT";
+const COMPONENT_INPUT_ON_DISCONNECT: &str = "INPUT_ON_DISCONNECT";
+const COMPONENT_INPUT_ON_QUIT: &str = "INPUT_ON_QUIT";
 // -- ssh keys
 const COMPONENT_LIST_SSH_KEYS: &str = "LIST_SSH_KEYS";
 const COMPONENT_INPUT_SSH_HOST: &str = "INPUT_SSH_HOST";
@@ -99,6 +129,76 @@ const COMPONENT_COLOR_TRANSFER_LOG_WIN: &str = "COMPONENT_COLOR_TRANSFER_LOG_WIN
 const COMPONENT_COLOR_TRANSFER_STATUS_SORTING: &str = "COMPONENT_COLOR_TRANSFER_STATUS_SORTING";
 const COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN: &str = "COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN";
 const COMPONENT_COLOR_TRANSFER_STATUS_SYNC: &str = "COMPONENT_COLOR_TRANSFER_STATUS_SYNC";
+const COMPONENT_THEME_PREVIEW: &str = "COMPONENT_THEME_PREVIEW";
+const COMPONENT_COLOR_PALETTE: &str = "COMPONENT_COLOR_PALETTE";
+
+// -- focus
+const FOCUS_RING_SETUP: FocusRing = FocusRing::new(&[
+    COMPONENT_INPUT_TEXT_EDITOR,
+    COMPONENT_RADIO_DEFAULT_PROTOCOL,
+    COMPONENT_RADIO_HIDDEN_FILES,
+    COMPONENT_RADIO_SYMLINKS,
+    COMPONENT_RADIO_UPDATES,
+    COMPONENT_INPUT_TRANSFER_RETRIES,
+    COMPONENT_RADIO_LOW_BANDWIDTH,
+    COMPONENT_RADIO_COMPRESSION,
+    COMPONENT_RADIO_DELTA_TRANSFER,
+    COMPONENT_RADIO_SKIP_UNCHANGED,
+    COMPONENT_RADIO_SHOW_BANNER,
+    COMPONENT_RADIO_PREFETCH_DIRS,
+    COMPONENT_RADIO_GROUP_DIRS,
+    COMPONENT_RADIO_PRESERVE_OWNER,
+    COMPONENT_RADIO_FILE_SORTING,
+    COMPONENT_RADIO_SIZE_UNITS,
+    COMPONENT_RADIO_CLOCK,
+    COMPONENT_INPUT_DATE_FORMAT,
+    COMPONENT_INPUT_LOCAL_FILE_FMT,
+    COMPONENT_INPUT_REMOTE_FILE_FMT,
+    COMPONENT_INPUT_PROXY,
+    COMPONENT_INPUT_STALL_TIMEOUT,
+    COMPONENT_INPUT_DOWNLOAD_DIR,
+    COMPONENT_INPUT_PARALLEL_TRANSFERS,
+    COMPONENT_INPUT_RECENTS_SIZE,
+    COMPONENT_INPUT_RECENTS_MAX_AGE,
+    COMPONENT_INPUT_DIFF_TOOL,
+    COMPONENT_INPUT_MERGE_TOOL,
+    COMPONENT_INPUT_WEBHOOK_URL,
+    COMPONENT_INPUT_WEBHOOK_TEMPLATE,
+    COMPONENT_INPUT_METRICS_FILE,
+    COMPONENT_INPUT_METRICS_PORT,
+    COMPONENT_INPUT_ON_DISCONNECT,
+    COMPONENT_INPUT_ON_QUIT,
+]);
+const FOCUS_RING_SSH_KEY: FocusRing =
+    FocusRing::with_tab(&[COMPONENT_INPUT_SSH_HOST, COMPONENT_INPUT_SSH_USERNAME]);
+const FOCUS_RING_THEME: FocusRing = FocusRing::new(&[
+    COMPONENT_COLOR_AUTH_PROTOCOL,
+    COMPONENT_COLOR_AUTH_ADDR,
+    COMPONENT_COLOR_AUTH_PORT,
+    COMPONENT_COLOR_AUTH_USERNAME,
+    COMPONENT_COLOR_AUTH_PASSWORD,
+    COMPONENT_COLOR_AUTH_BOOKMARKS,
+    COMPONENT_COLOR_AUTH_RECENTS,
+    COMPONENT_COLOR_MISC_ERROR,
+    COMPONENT_COLOR_MISC_INPUT,
+    COMPONENT_COLOR_MISC_KEYS,
+    COMPONENT_COLOR_MISC_QUIT,
+    COMPONENT_COLOR_MISC_SAVE,
+    COMPONENT_COLOR_MISC_WARN,
+    COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_BG,
+    COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_FG,
+    COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_HG,
+    COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_BG,
+    COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_FG,
+    COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_HG,
+    COMPONENT_COLOR_TRANSFER_PROG_BAR_FULL,
+    COMPONENT_COLOR_TRANSFER_PROG_BAR_PARTIAL,
+    COMPONENT_COLOR_TRANSFER_LOG_BG,
+    COMPONENT_COLOR_TRANSFER_LOG_WIN,
+    COMPONENT_COLOR_TRANSFER_STATUS_SORTING,
+    COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN,
+    COMPONENT_COLOR_TRANSFER_STATUS_SYNC,
+]);
 
 // -- store
 const STORE_CONFIG_CHANGED: &str = "SETUP_CONFIG_CHANGED";
@@ -118,9 +218,11 @@ enum ViewLayout {
 /// Setup activity states holder
 pub struct SetupActivity {
     exit_reason: Option<ExitReason>,
-    context: Option<Context>, // Context holder
-    view: View,               // View
-    layout: ViewLayout,       // View layout
+    context: Option<Context>,             // Context holder
+    view: View,                           // View
+    modals: ModalStack,                   // Popups displayed on top of the current tab
+    layout: ViewLayout,                   // View layout
+    color_palette_target: Option<String>, // Theme field edited through the color palette
     redraw: bool,
 }
 
@@ -130,7 +232,9 @@ impl Default for SetupActivity {
             exit_reason: None,
             context: None,
             view: View::init(),
+            modals: ModalStack::new(COMPONENT_INPUT_TEXT_EDITOR),
             layout: ViewLayout::SetupForm,
+            color_palette_target: None,
             redraw: true, // Draw at first `on_draw`
         }
     }
@@ -229,10 +333,17 @@ impl Activity for SetupActivity {
         if let Ok(Some(event)) = self.context().input_hnd().read_event() {
             // Set redraw to true
             self.redraw = true;
+            if let Event::Resize(_, _) = event {
+                self.context_mut().on_resize();
+            }
             // Handle event
             let msg = self.view.on(event);
             self.update(msg);
         }
+        // Repaint after resize or reattach
+        if self.context_mut().refresh_terminal_size() {
+            self.redraw = true;
+        }
         // Redraw if necessary
         if self.redraw {
             // View
@@ -28,24 +28,12 @@
  */
 // locals
 use super::{
-    SetupActivity, ViewLayout, COMPONENT_COLOR_AUTH_ADDR, COMPONENT_COLOR_AUTH_BOOKMARKS,
-    COMPONENT_COLOR_AUTH_PASSWORD, COMPONENT_COLOR_AUTH_PORT, COMPONENT_COLOR_AUTH_PROTOCOL,
-    COMPONENT_COLOR_AUTH_RECENTS, COMPONENT_COLOR_AUTH_USERNAME, COMPONENT_COLOR_MISC_ERROR,
-    COMPONENT_COLOR_MISC_INPUT, COMPONENT_COLOR_MISC_KEYS, COMPONENT_COLOR_MISC_QUIT,
-    COMPONENT_COLOR_MISC_SAVE, COMPONENT_COLOR_MISC_WARN,
-    COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_BG, COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_FG,
-    COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_HG, COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_BG,
-    COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_FG, COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_HG,
-    COMPONENT_COLOR_TRANSFER_LOG_BG, COMPONENT_COLOR_TRANSFER_LOG_WIN,
-    COMPONENT_COLOR_TRANSFER_PROG_BAR_FULL, COMPONENT_COLOR_TRANSFER_PROG_BAR_PARTIAL,
-    COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN, COMPONENT_COLOR_TRANSFER_STATUS_SORTING,
-    COMPONENT_COLOR_TRANSFER_STATUS_SYNC, COMPONENT_INPUT_LOCAL_FILE_FMT,
-    COMPONENT_INPUT_REMOTE_FILE_FMT, COMPONENT_INPUT_SSH_HOST, COMPONENT_INPUT_SSH_USERNAME,
-    COMPONENT_INPUT_TEXT_EDITOR, COMPONENT_LIST_SSH_KEYS, COMPONENT_RADIO_DEFAULT_PROTOCOL,
-    COMPONENT_RADIO_DEL_SSH_KEY, COMPONENT_RADIO_GROUP_DIRS, COMPONENT_RADIO_HIDDEN_FILES,
-    COMPONENT_RADIO_QUIT, COMPONENT_RADIO_SAVE, COMPONENT_RADIO_UPDATES, COMPONENT_TEXT_ERROR,
-    COMPONENT_TEXT_HELP,
+    SetupActivity, ViewLayout, COMPONENT_COLOR_PALETTE, COMPONENT_INPUT_SSH_HOST,
+    COMPONENT_INPUT_SSH_USERNAME, COMPONENT_LIST_SSH_KEYS, COMPONENT_RADIO_DEL_SSH_KEY,
+    COMPONENT_RADIO_QUIT, COMPONENT_RADIO_SAVE, COMPONENT_TEXT_ERROR, COMPONENT_TEXT_HELP,
+    FOCUS_RING_SETUP, FOCUS_RING_SSH_KEY, FOCUS_RING_THEME,
 };
+use crate::ui::focus::FocusRing;
 use crate::ui::keymap::*;
 use crate::utils::parser::parse_color;
 
@@ -58,6 +46,22 @@ impl Update for SetupActivity {
     /// Update auth activity model based on msg
     /// The function exits when returns None
     fn update(&mut self, msg: Option<(String, Msg)>) -> Option<(String, Msg)> {
+        // Move focus through the current form
+        let focus_ring: &FocusRing = match self.layout {
+            ViewLayout::SetupForm => &FOCUS_RING_SETUP,
+            ViewLayout::SshKeys => &FOCUS_RING_SSH_KEY,
+            ViewLayout::Theme => &FOCUS_RING_THEME,
+        };
+        if let Some((component, msg)) = msg.as_ref() {
+            if let Some(next) = focus_ring.on_msg(component, msg) {
+                // The ssh key form is a popup, while the other forms are behind the popups
+                match self.layout {
+                    ViewLayout::SshKeys => self.focus_popup(next),
+                    ViewLayout::SetupForm | ViewLayout::Theme => self.focus_panel(next),
+                }
+                return None;
+            }
+        }
         match self.layout {
             ViewLayout::SetupForm => self.update_setup(msg),
             ViewLayout::SshKeys => self.update_ssh_keys(msg),
@@ -73,64 +77,6 @@ impl SetupActivity {
         match ref_msg {
             None => None,
             Some(msg) => match msg {
-                // Input field <DOWN>
-                (COMPONENT_INPUT_TEXT_EDITOR, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_RADIO_DEFAULT_PROTOCOL);
-                    None
-                }
-                (COMPONENT_RADIO_DEFAULT_PROTOCOL, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_RADIO_HIDDEN_FILES);
-                    None
-                }
-                (COMPONENT_RADIO_HIDDEN_FILES, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_RADIO_UPDATES);
-                    None
-                }
-                (COMPONENT_RADIO_UPDATES, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_RADIO_GROUP_DIRS);
-                    None
-                }
-                (COMPONENT_RADIO_GROUP_DIRS, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_INPUT_LOCAL_FILE_FMT);
-                    None
-                }
-                (COMPONENT_INPUT_LOCAL_FILE_FMT, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_INPUT_REMOTE_FILE_FMT);
-                    None
-                }
-                (COMPONENT_INPUT_REMOTE_FILE_FMT, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_INPUT_TEXT_EDITOR);
-                    None
-                }
-                // Input field <UP>
-                (COMPONENT_INPUT_REMOTE_FILE_FMT, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_INPUT_LOCAL_FILE_FMT);
-                    None
-                }
-                (COMPONENT_INPUT_LOCAL_FILE_FMT, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_RADIO_GROUP_DIRS);
-                    None
-                }
-                (COMPONENT_RADIO_GROUP_DIRS, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_RADIO_UPDATES);
-                    None
-                }
-                (COMPONENT_RADIO_UPDATES, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_RADIO_HIDDEN_FILES);
-                    None
-                }
-                (COMPONENT_RADIO_HIDDEN_FILES, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_RADIO_DEFAULT_PROTOCOL);
-                    None
-                }
-                (COMPONENT_RADIO_DEFAULT_PROTOCOL, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_INPUT_TEXT_EDITOR);
-                    None
-                }
-                (COMPONENT_INPUT_TEXT_EDITOR, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_INPUT_REMOTE_FILE_FMT);
-                    None
-                }
                 // Error <ENTER> or <ESC>
                 (COMPONENT_TEXT_ERROR, key) | (COMPONENT_TEXT_ERROR, key)
                     if key == &MSG_KEY_ESC || key == &MSG_KEY_ENTER =>
@@ -313,28 +259,6 @@ impl SetupActivity {
                     self.mount_help();
                     None
                 }
-                // New key <DOWN>
-                (COMPONENT_INPUT_SSH_HOST, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_INPUT_SSH_USERNAME);
-                    None
-                }
-                (COMPONENT_INPUT_SSH_USERNAME, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_INPUT_SSH_HOST);
-                    None
-                }
-                // New key <UP>
-                (COMPONENT_INPUT_SSH_USERNAME, key) | (COMPONENT_INPUT_SSH_USERNAME, key)
-                    if key == &MSG_KEY_UP || key == &MSG_KEY_TAB =>
-                {
-                    self.view.active(COMPONENT_INPUT_SSH_HOST);
-                    None
-                }
-                (COMPONENT_INPUT_SSH_HOST, key) | (COMPONENT_INPUT_SSH_HOST, key)
-                    if key == &MSG_KEY_UP || key == &MSG_KEY_TAB =>
-                {
-                    self.view.active(COMPONENT_INPUT_SSH_USERNAME);
-                    None
-                }
                 // New key <ENTER>
                 (COMPONENT_INPUT_SSH_HOST, Msg::OnSubmit(_))
                 | (COMPONENT_INPUT_SSH_USERNAME, Msg::OnSubmit(_)) => {
@@ -411,221 +335,6 @@ impl SetupActivity {
         match ref_msg {
             None => None,
             Some(msg) => match msg {
-                // Input fields
-                (COMPONENT_COLOR_AUTH_PROTOCOL, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_AUTH_ADDR);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_ADDR, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_AUTH_PORT);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_PORT, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_AUTH_USERNAME);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_USERNAME, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_AUTH_PASSWORD);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_PASSWORD, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_AUTH_BOOKMARKS);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_BOOKMARKS, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_AUTH_RECENTS);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_RECENTS, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_MISC_ERROR);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_ERROR, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_MISC_INPUT);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_INPUT, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_MISC_KEYS);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_KEYS, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_MISC_QUIT);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_QUIT, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_MISC_SAVE);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_SAVE, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_MISC_WARN);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_WARN, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_BG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_BG, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_FG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_FG, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_HG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_HG, key) if key == &MSG_KEY_DOWN => {
-                    self.view
-                        .active(COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_BG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_BG, key) if key == &MSG_KEY_DOWN => {
-                    self.view
-                        .active(COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_FG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_FG, key) if key == &MSG_KEY_DOWN => {
-                    self.view
-                        .active(COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_HG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_HG, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_PROG_BAR_FULL);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_PROG_BAR_FULL, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_PROG_BAR_PARTIAL);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_PROG_BAR_PARTIAL, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_LOG_BG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_LOG_BG, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_LOG_WIN);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_LOG_WIN, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_STATUS_SORTING);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_STATUS_SORTING, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_STATUS_SYNC);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_STATUS_SYNC, key) if key == &MSG_KEY_DOWN => {
-                    self.view.active(COMPONENT_COLOR_AUTH_PROTOCOL);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_PROTOCOL, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_STATUS_SYNC);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_ADDR, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_AUTH_PROTOCOL);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_PORT, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_AUTH_ADDR);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_USERNAME, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_AUTH_PORT);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_PASSWORD, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_AUTH_USERNAME);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_BOOKMARKS, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_AUTH_PASSWORD);
-                    None
-                }
-                (COMPONENT_COLOR_AUTH_RECENTS, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_AUTH_BOOKMARKS);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_ERROR, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_AUTH_RECENTS);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_INPUT, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_MISC_ERROR);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_KEYS, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_MISC_INPUT);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_QUIT, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_MISC_KEYS);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_SAVE, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_MISC_QUIT);
-                    None
-                }
-                (COMPONENT_COLOR_MISC_WARN, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_MISC_SAVE);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_BG, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_MISC_WARN);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_FG, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_BG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_HG, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_FG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_BG, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_HG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_FG, key) if key == &MSG_KEY_UP => {
-                    self.view
-                        .active(COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_BG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_HG, key) if key == &MSG_KEY_UP => {
-                    self.view
-                        .active(COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_FG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_PROG_BAR_FULL, key) if key == &MSG_KEY_UP => {
-                    self.view
-                        .active(COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_HG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_PROG_BAR_PARTIAL, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_PROG_BAR_FULL);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_LOG_BG, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_PROG_BAR_PARTIAL);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_LOG_WIN, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_LOG_BG);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_STATUS_SORTING, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_LOG_WIN);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_STATUS_SORTING);
-                    None
-                }
-                (COMPONENT_COLOR_TRANSFER_STATUS_SYNC, key) if key == &MSG_KEY_UP => {
-                    self.view.active(COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN);
-                    None
-                }
                 // On color change
                 (component, Msg::OnChange(Payload::One(Value::Str(color)))) => {
                     if let Some(color) = parse_color(color) {
@@ -690,7 +399,22 @@ impl SetupActivity {
                     None
                 }
                 (COMPONENT_RADIO_SAVE, _) => None,
-                // Edit SSH Key
+                // Color palette
+                (COMPONENT_COLOR_PALETTE, Msg::OnSubmit(Payload::One(Value::Str(color)))) => {
+                    self.action_submit_color_palette(color.as_str());
+                    self.umount_color_palette();
+                    None
+                }
+                (COMPONENT_COLOR_PALETTE, key) if key == &MSG_KEY_ESC => {
+                    self.umount_color_palette();
+                    None
+                }
+                (COMPONENT_COLOR_PALETTE, _) => None,
+                // <CTRL+P> Open color palette for current field
+                (component, key) if key == &MSG_KEY_CTRL_P => {
+                    self.mount_color_palette(component);
+                    None
+                }
                 // <CTRL+H> Show help
                 (_, key) if key == &MSG_KEY_CTRL_H => {
                     // Show help
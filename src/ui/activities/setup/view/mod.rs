@@ -52,6 +52,8 @@ impl SetupActivity {
 
     pub(super) fn init(&mut self, layout: ViewLayout) {
         self.layout = layout;
+        // The view is rebuilt, so no popup is left
+        self.modals.clear();
         match self.layout {
             ViewLayout::SetupForm => self.init_setup(),
             ViewLayout::SshKeys => self.init_ssh_keys(),
@@ -70,6 +72,47 @@ impl SetupActivity {
         }
     }
 
+    // -- focus
+
+    /// ### open_popup
+    ///
+    /// Put the mounted popup `id` on top of the others and give focus to it
+    fn open_popup(&mut self, id: &'static str) {
+        self.modals.open(id);
+        self.view.active(id);
+    }
+
+    /// ### close_popup
+    ///
+    /// Umount the popup `id` and give focus back to the popup below it, or to the current tab
+    /// if no popup is left. Focus never goes to a component which is hidden behind a popup
+    fn close_popup(&mut self, id: &'static str) {
+        self.view.umount(id);
+        if self.modals.is_open(id) {
+            let focus: &'static str = self.modals.close(id);
+            self.view.active(focus);
+        }
+    }
+
+    /// ### focus_popup
+    ///
+    /// Move focus to `component`, which is part of the popup on top (e.g. a form field)
+    pub(super) fn focus_popup(&mut self, component: &'static str) {
+        self.modals.move_focus(component);
+        self.view.active(component);
+    }
+
+    /// ### focus_panel
+    ///
+    /// Give focus to `component`, one of the fields of the current tab.
+    /// If a popup is displayed, the field will get focus once all the popups are closed
+    pub(super) fn focus_panel(&mut self, component: &'static str) {
+        self.modals.set_base(component);
+        if self.modals.is_empty() {
+            self.view.active(component);
+        }
+    }
+
     // -- mount
 
     /// ### mount_error
@@ -90,14 +133,14 @@ impl SetupActivity {
             )),
         );
         // Give focus to error
-        self.view.active(super::COMPONENT_TEXT_ERROR);
+        self.open_popup(super::COMPONENT_TEXT_ERROR);
     }
 
     /// ### umount_error
     ///
     /// Umount error message
     pub(super) fn umount_error(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_ERROR);
+        self.close_popup(super::COMPONENT_TEXT_ERROR);
     }
 
     /// ### mount_quit
@@ -125,14 +168,14 @@ impl SetupActivity {
             )),
         );
         // Active
-        self.view.active(super::COMPONENT_RADIO_QUIT);
+        self.open_popup(super::COMPONENT_RADIO_QUIT);
     }
 
     /// ### umount_quit
     ///
     /// Umount quit
     pub(super) fn umount_quit(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_QUIT);
+        self.close_popup(super::COMPONENT_RADIO_QUIT);
     }
 
     /// ### mount_save_popup
@@ -153,14 +196,14 @@ impl SetupActivity {
             )),
         );
         // Active
-        self.view.active(super::COMPONENT_RADIO_SAVE);
+        self.open_popup(super::COMPONENT_RADIO_SAVE);
     }
 
     /// ### umount_quit
     ///
     /// Umount quit
     pub(super) fn umount_save_popup(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_SAVE);
+        self.close_popup(super::COMPONENT_RADIO_SAVE);
     }
 
     pub(self) fn mount_header_tab(&mut self, idx: usize) {
@@ -235,6 +278,9 @@ impl SetupActivity {
                             .add_col(TextSpan::new("<CTRL+N>").bold().fg(Color::Cyan))
                             .add_col(TextSpan::from("        New SSH key"))
                             .add_row()
+                            .add_col(TextSpan::new("<CTRL+P>").bold().fg(Color::Cyan))
+                            .add_col(TextSpan::from("        Open color palette"))
+                            .add_row()
                             .add_col(TextSpan::new("<CTRL+R>").bold().fg(Color::Cyan))
                             .add_col(TextSpan::from("        Revert changes"))
                             .add_row()
@@ -246,13 +292,60 @@ impl SetupActivity {
             )),
         );
         // Active help
-        self.view.active(super::COMPONENT_TEXT_HELP);
+        self.open_popup(super::COMPONENT_TEXT_HELP);
     }
 
     /// ### umount_help
     ///
     /// Umount help
     pub(super) fn umount_help(&mut self) {
-        self.view.umount(super::COMPONENT_TEXT_HELP);
+        self.close_popup(super::COMPONENT_TEXT_HELP);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::system::config_client::ConfigClient;
+    use crate::test_support::{ActivityDriver, Snapshot};
+
+    use crossterm::event::KeyCode;
+
+    /// ### snapshot
+    ///
+    /// Take a snapshot of the driver screen, hiding the default text editor, which depends on the environment
+    fn snapshot(driver: &ActivityDriver<SetupActivity>) -> Snapshot {
+        let editor = ConfigClient::degraded().get_text_editor();
+        driver
+            .snapshot()
+            .redact(editor.to_string_lossy().as_ref(), "<editor>")
+    }
+
+    #[test]
+    fn test_ui_activities_setup_view_snapshots() {
+        let mut driver = ActivityDriver::new(SetupActivity::default(), None);
+        for (width, height) in [(160, 48), (80, 24)].iter() {
+            driver.resize(*width, *height);
+            assert!(driver.run().is_none());
+            let form: Snapshot = snapshot(&driver);
+            assert!(form.contains("Default file transfer protocol"));
+            form.assert_matches(format!("setup_form_{}x{}", width, height).as_str());
+            // Ssh keys
+            driver.key(KeyCode::Tab);
+            assert!(driver.run().is_none());
+            let ssh_keys: Snapshot = snapshot(&driver);
+            assert!(ssh_keys.contains("SSH keys"));
+            ssh_keys.assert_matches(format!("setup_ssh_keys_{}x{}", width, height).as_str());
+            // Theme
+            driver.key(KeyCode::Tab);
+            assert!(driver.run().is_none());
+            let theme: Snapshot = snapshot(&driver);
+            assert!(theme.contains("Misc styles"));
+            theme.assert_matches(format!("setup_theme_{}x{}", width, height).as_str());
+            // Back to setup form
+            driver.key(KeyCode::Tab);
+            assert!(driver.run().is_none());
+        }
     }
 }
@@ -28,10 +28,12 @@
  */
 // Locals
 use super::{Context, SetupActivity};
-use crate::filetransfer::FileTransferProtocol;
-use crate::fs::explorer::GroupDirs;
+use crate::filetransfer::{registry, FileTransferProtocol, SymlinkMode};
+use crate::fs::explorer::{FileSorting, GroupDirs};
+use crate::utils::fmt::{ClockFormat, SizeUnits};
 use crate::utils::ui::draw_area_in;
 // Ext
+use std::convert::TryFrom;
 use std::path::PathBuf;
 use tui_realm_stdlib::{
     input::{Input, InputPropsBuilder},
@@ -43,7 +45,7 @@ use tuirealm::tui::{
     widgets::{BorderType, Borders, Clear},
 };
 use tuirealm::{
-    props::{Alignment, PropsBuilder},
+    props::{Alignment, InputType, PropsBuilder},
     Payload, Value, View,
 };
 
@@ -72,7 +74,7 @@ impl SetupActivity {
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_TEXT_EDITOR); // <-- Focus
+        self.focus_panel(super::COMPONENT_INPUT_TEXT_EDITOR); // <-- Focus
         self.view.mount(
             super::COMPONENT_RADIO_DEFAULT_PROTOCOL,
             Box::new(Radio::new(
@@ -81,12 +83,12 @@ impl SetupActivity {
                     .with_inverted_color(Color::Black)
                     .with_borders(Borders::ALL, BorderType::Rounded, Color::LightCyan)
                     .with_title("Default file transfer protocol", Alignment::Left)
-                    .with_options(&[
-                        String::from("SFTP"),
-                        String::from("SCP"),
-                        String::from("FTP"),
-                        String::from("FTPS"),
-                    ])
+                    .with_options(
+                        &registry::protocols()
+                            .iter()
+                            .map(|x| x.to_string())
+                            .collect::<Vec<String>>(),
+                    )
                     .rewind(true)
                     .build(),
             )),
@@ -104,6 +106,23 @@ impl SetupActivity {
                     .build(),
             )),
         );
+        self.view.mount(
+            super::COMPONENT_RADIO_SYMLINKS,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightRed)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightRed)
+                    .with_title("Symbolic links in transfers", Alignment::Left)
+                    .with_options(&[
+                        String::from("Skip"),
+                        String::from("Follow"),
+                        String::from("Recreate"),
+                    ])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
         self.view.mount(
             super::COMPONENT_RADIO_UPDATES,
             Box::new(Radio::new(
@@ -117,6 +136,95 @@ impl SetupActivity {
                     .build(),
             )),
         );
+        self.view.mount(
+            super::COMPONENT_INPUT_TRANSFER_RETRIES,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightGreen)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightGreen)
+                    .with_label("Transfer retries (0 disables)", Alignment::Left)
+                    .with_input(InputType::Number)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_LOW_BANDWIDTH,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightBlue)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightBlue)
+                    .with_title("Low bandwidth mode?", Alignment::Left)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_COMPRESSION,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightBlue)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightBlue)
+                    .with_title("Compress SSH transport (SFTP/SCP)?", Alignment::Left)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_DELTA_TRANSFER,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightCyan)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightCyan)
+                    .with_title("Delta upload (SFTP/SCP)?", Alignment::Left)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_SKIP_UNCHANGED,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightCyan)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightCyan)
+                    .with_title("Skip unchanged files?", Alignment::Left)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_SHOW_BANNER,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightGreen)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightGreen)
+                    .with_title("Show server welcome message?", Alignment::Left)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_PREFETCH_DIRS,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightGreen)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightGreen)
+                    .with_title("Prefetch highlighted remote directories?", Alignment::Left)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
         self.view.mount(
             super::COMPONENT_RADIO_GROUP_DIRS,
             Box::new(Radio::new(
@@ -134,6 +242,76 @@ impl SetupActivity {
                     .build(),
             )),
         );
+        self.view.mount(
+            super::COMPONENT_RADIO_PRESERVE_OWNER,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightMagenta)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightMagenta)
+                    .with_title("Preserve owner of downloads (as root)?", Alignment::Left)
+                    .with_options(&[String::from("Yes"), String::from("No")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_FILE_SORTING,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightMagenta)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightMagenta)
+                    .with_title("Sort files by", Alignment::Left)
+                    .with_options(&[
+                        String::from("Name"),
+                        String::from("Modify time"),
+                        String::from("Creation time"),
+                        String::from("Size"),
+                        String::from("Extension"),
+                        String::from("Owner"),
+                        String::from("Permissions"),
+                    ])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_SIZE_UNITS,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightYellow)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightYellow)
+                    .with_title("Size units", Alignment::Left)
+                    .with_options(&[String::from("SI (MB)"), String::from("Binary (MiB)")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_RADIO_CLOCK,
+            Box::new(Radio::new(
+                RadioPropsBuilder::default()
+                    .with_color(Color::LightCyan)
+                    .with_inverted_color(Color::Black)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightCyan)
+                    .with_title("Clock", Alignment::Left)
+                    .with_options(&[String::from("24h"), String::from("12h")])
+                    .rewind(true)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_DATE_FORMAT,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightMagenta)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightMagenta)
+                    .with_label("Date format", Alignment::Left)
+                    .build(),
+            )),
+        );
         self.view.mount(
             super::COMPONENT_INPUT_LOCAL_FILE_FMT,
             Box::new(Input::new(
@@ -154,13 +332,161 @@ impl SetupActivity {
                     .build(),
             )),
         );
+        self.view.mount(
+            super::COMPONENT_INPUT_PROXY,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightYellow)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightYellow)
+                    .with_label(
+                        "Proxy (socks5://host:port, http://host:port)",
+                        Alignment::Left,
+                    )
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_STALL_TIMEOUT,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightYellow)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightYellow)
+                    .with_label("Stall timeout (seconds; 0 disables)", Alignment::Left)
+                    .with_input(InputType::Number)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_DOWNLOAD_DIR,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightCyan)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightCyan)
+                    .with_label("Default download directory", Alignment::Left)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_PARALLEL_TRANSFERS,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightCyan)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightCyan)
+                    .with_label("Files transferred at once", Alignment::Left)
+                    .with_input(InputType::Number)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_RECENTS_SIZE,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightMagenta)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightMagenta)
+                    .with_label("Recent connections to keep (0 disables)", Alignment::Left)
+                    .with_input(InputType::Number)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_RECENTS_MAX_AGE,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightMagenta)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightMagenta)
+                    .with_label("Forget recent connections after (days)", Alignment::Left)
+                    .with_input(InputType::Number)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_DIFF_TOOL,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightBlue)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightBlue)
+                    .with_label("Diff tool (e.g. vimdiff {local} {remote})", Alignment::Left)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_MERGE_TOOL,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightBlue)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightBlue)
+                    .with_label("Merge tool (e.g. meld {local} {remote})", Alignment::Left)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_WEBHOOK_URL,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightGreen)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightGreen)
+                    .with_label("Webhook url", Alignment::Left)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_WEBHOOK_TEMPLATE,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightGreen)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightGreen)
+                    .with_label("Webhook JSON template (empty for default)", Alignment::Left)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_METRICS_FILE,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightGreen)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightGreen)
+                    .with_label("Prometheus metrics file", Alignment::Left)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_METRICS_PORT,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightGreen)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightGreen)
+                    .with_label("Metrics port on localhost", Alignment::Left)
+                    .with_input(InputType::Number)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_ON_DISCONNECT,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightRed)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightRed)
+                    .with_label("Run on disconnect", Alignment::Left)
+                    .build(),
+            )),
+        );
+        self.view.mount(
+            super::COMPONENT_INPUT_ON_QUIT,
+            Box::new(Input::new(
+                InputPropsBuilder::default()
+                    .with_foreground(Color::LightRed)
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::LightRed)
+                    .with_label("Run on quit", Alignment::Left)
+                    .build(),
+            )),
+        );
         // Load values
         self.load_input_values();
     }
 
     pub(super) fn view_setup(&mut self) {
         let mut ctx: Context = self.context.take().unwrap();
-        let _ = ctx.terminal().draw(|f| {
+        let _ = ctx.draw(|f| {
             // Prepare main chunks
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -168,7 +494,7 @@ impl SetupActivity {
                 .constraints(
                     [
                         Constraint::Length(3),  // Current tab
-                        Constraint::Length(21), // Main body
+                        Constraint::Length(57), // Main body
                         Constraint::Length(3),  // Help footer
                     ]
                     .as_ref(),
@@ -184,11 +510,23 @@ impl SetupActivity {
                     [
                         Constraint::Length(3), // Text editor
                         Constraint::Length(3), // Protocol tab
-                        Constraint::Length(3), // Hidden files
-                        Constraint::Length(3), // Updates tab
-                        Constraint::Length(3), // Group dirs
+                        Constraint::Length(3), // Hidden files and symlinks
+                        Constraint::Length(3), // Updates and transfer retries
+                        Constraint::Length(3), // Low bandwidth and compression
+                        Constraint::Length(3), // Delta transfer and skip unchanged
+                        Constraint::Length(3), // Show banner and prefetch dirs
+                        Constraint::Length(3), // Group dirs and preserve owner
+                        Constraint::Length(3), // File sorting
+                        Constraint::Length(3), // Size units, clock and date format
                         Constraint::Length(3), // Local Format input
                         Constraint::Length(3), // Remote Format input
+                        Constraint::Length(3), // Proxy and stall timeout inputs
+                        Constraint::Length(3), // Download dir and parallel transfers inputs
+                        Constraint::Length(3), // Recents inputs
+                        Constraint::Length(3), // Diff and merge tool inputs
+                        Constraint::Length(3), // Webhook inputs
+                        Constraint::Length(3), // Metrics inputs
+                        Constraint::Length(3), // Exit hooks inputs
                     ]
                     .as_ref(),
                 )
@@ -197,16 +535,142 @@ impl SetupActivity {
                 .render(super::COMPONENT_INPUT_TEXT_EDITOR, f, ui_cfg_chunks[0]);
             self.view
                 .render(super::COMPONENT_RADIO_DEFAULT_PROTOCOL, f, ui_cfg_chunks[1]);
+            let hidden_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[2]);
+            self.view
+                .render(super::COMPONENT_RADIO_HIDDEN_FILES, f, hidden_chunks[0]);
+            self.view
+                .render(super::COMPONENT_RADIO_SYMLINKS, f, hidden_chunks[1]);
+            let updates_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[3]);
+            self.view
+                .render(super::COMPONENT_RADIO_UPDATES, f, updates_chunks[0]);
+            self.view.render(
+                super::COMPONENT_INPUT_TRANSFER_RETRIES,
+                f,
+                updates_chunks[1],
+            );
+            let bandwidth_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[4]);
+            self.view
+                .render(super::COMPONENT_RADIO_LOW_BANDWIDTH, f, bandwidth_chunks[0]);
+            self.view
+                .render(super::COMPONENT_RADIO_COMPRESSION, f, bandwidth_chunks[1]);
+            let transfer_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[5]);
+            self.view
+                .render(super::COMPONENT_RADIO_DELTA_TRANSFER, f, transfer_chunks[0]);
+            self.view
+                .render(super::COMPONENT_RADIO_SKIP_UNCHANGED, f, transfer_chunks[1]);
+            let banner_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[6]);
+            self.view
+                .render(super::COMPONENT_RADIO_SHOW_BANNER, f, banner_chunks[0]);
+            self.view
+                .render(super::COMPONENT_RADIO_PREFETCH_DIRS, f, banner_chunks[1]);
+            let dirs_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[7]);
+            self.view
+                .render(super::COMPONENT_RADIO_GROUP_DIRS, f, dirs_chunks[0]);
+            self.view
+                .render(super::COMPONENT_RADIO_PRESERVE_OWNER, f, dirs_chunks[1]);
+            self.view
+                .render(super::COMPONENT_RADIO_FILE_SORTING, f, ui_cfg_chunks[8]);
+            let locale_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(33),
+                        Constraint::Percentage(33),
+                        Constraint::Percentage(34),
+                    ]
+                    .as_ref(),
+                )
+                .split(ui_cfg_chunks[9]);
+            self.view
+                .render(super::COMPONENT_RADIO_SIZE_UNITS, f, locale_chunks[0]);
+            self.view
+                .render(super::COMPONENT_RADIO_CLOCK, f, locale_chunks[1]);
+            self.view
+                .render(super::COMPONENT_INPUT_DATE_FORMAT, f, locale_chunks[2]);
+            self.view
+                .render(super::COMPONENT_INPUT_LOCAL_FILE_FMT, f, ui_cfg_chunks[10]);
+            self.view
+                .render(super::COMPONENT_INPUT_REMOTE_FILE_FMT, f, ui_cfg_chunks[11]);
+            let network_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+                .split(ui_cfg_chunks[12]);
+            self.view
+                .render(super::COMPONENT_INPUT_PROXY, f, network_chunks[0]);
+            self.view
+                .render(super::COMPONENT_INPUT_STALL_TIMEOUT, f, network_chunks[1]);
+            let download_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+                .split(ui_cfg_chunks[13]);
+            self.view
+                .render(super::COMPONENT_INPUT_DOWNLOAD_DIR, f, download_chunks[0]);
+            self.view.render(
+                super::COMPONENT_INPUT_PARALLEL_TRANSFERS,
+                f,
+                download_chunks[1],
+            );
+            let recents_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[14]);
+            self.view
+                .render(super::COMPONENT_INPUT_RECENTS_SIZE, f, recents_chunks[0]);
             self.view
-                .render(super::COMPONENT_RADIO_HIDDEN_FILES, f, ui_cfg_chunks[2]);
+                .render(super::COMPONENT_INPUT_RECENTS_MAX_AGE, f, recents_chunks[1]);
+            let tool_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[15]);
             self.view
-                .render(super::COMPONENT_RADIO_UPDATES, f, ui_cfg_chunks[3]);
+                .render(super::COMPONENT_INPUT_DIFF_TOOL, f, tool_chunks[0]);
             self.view
-                .render(super::COMPONENT_RADIO_GROUP_DIRS, f, ui_cfg_chunks[4]);
+                .render(super::COMPONENT_INPUT_MERGE_TOOL, f, tool_chunks[1]);
+            let webhook_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[16]);
             self.view
-                .render(super::COMPONENT_INPUT_LOCAL_FILE_FMT, f, ui_cfg_chunks[5]);
+                .render(super::COMPONENT_INPUT_WEBHOOK_URL, f, webhook_chunks[0]);
+            self.view.render(
+                super::COMPONENT_INPUT_WEBHOOK_TEMPLATE,
+                f,
+                webhook_chunks[1],
+            );
+            let metrics_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+                .split(ui_cfg_chunks[17]);
             self.view
-                .render(super::COMPONENT_INPUT_REMOTE_FILE_FMT, f, ui_cfg_chunks[6]);
+                .render(super::COMPONENT_INPUT_METRICS_FILE, f, metrics_chunks[0]);
+            self.view
+                .render(super::COMPONENT_INPUT_METRICS_PORT, f, metrics_chunks[1]);
+            let hooks_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(ui_cfg_chunks[18]);
+            self.view
+                .render(super::COMPONENT_INPUT_ON_DISCONNECT, f, hooks_chunks[0]);
+            self.view
+                .render(super::COMPONENT_INPUT_ON_QUIT, f, hooks_chunks[1]);
             // Popups
             if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_ERROR) {
                 if props.visible {
@@ -260,12 +724,11 @@ impl SetupActivity {
         }
         // Protocol
         if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_DEFAULT_PROTOCOL) {
-            let protocol: usize = match self.config().get_default_protocol() {
-                FileTransferProtocol::Sftp => 0,
-                FileTransferProtocol::Scp => 1,
-                FileTransferProtocol::Ftp(false) => 2,
-                FileTransferProtocol::Ftp(true) => 3,
-            };
+            let default_protocol: FileTransferProtocol = self.config().get_default_protocol();
+            let protocol: usize = registry::protocols()
+                .iter()
+                .position(|x| *x == default_protocol)
+                .unwrap_or(0);
             let props = RadioPropsBuilder::from(props).with_value(protocol).build();
             let _ = self
                 .view
@@ -280,6 +743,16 @@ impl SetupActivity {
             let props = RadioPropsBuilder::from(props).with_value(hidden).build();
             let _ = self.view.update(super::COMPONENT_RADIO_HIDDEN_FILES, props);
         }
+        // Symlinks
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_SYMLINKS) {
+            let symlinks: usize = match self.config().get_symlinks() {
+                SymlinkMode::Skip => 0,
+                SymlinkMode::Follow => 1,
+                SymlinkMode::Recreate => 2,
+            };
+            let props = RadioPropsBuilder::from(props).with_value(symlinks).build();
+            let _ = self.view.update(super::COMPONENT_RADIO_SYMLINKS, props);
+        }
         // Updates
         if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_UPDATES) {
             let updates: usize = match self.config().get_check_for_updates() {
@@ -289,6 +762,86 @@ impl SetupActivity {
             let props = RadioPropsBuilder::from(props).with_value(updates).build();
             let _ = self.view.update(super::COMPONENT_RADIO_UPDATES, props);
         }
+        // Transfer retries
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_TRANSFER_RETRIES) {
+            let retries: String = self.config().get_transfer_retries().to_string();
+            let props = InputPropsBuilder::from(props).with_value(retries).build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_INPUT_TRANSFER_RETRIES, props);
+        }
+        // Low bandwidth
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_LOW_BANDWIDTH) {
+            let low_bandwidth: usize = match self.config().get_low_bandwidth() {
+                true => 0,
+                false => 1,
+            };
+            let props = RadioPropsBuilder::from(props)
+                .with_value(low_bandwidth)
+                .build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_RADIO_LOW_BANDWIDTH, props);
+        }
+        // Compression
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_COMPRESSION) {
+            let compression: usize = match self.config().get_compression() {
+                true => 0,
+                false => 1,
+            };
+            let props = RadioPropsBuilder::from(props)
+                .with_value(compression)
+                .build();
+            let _ = self.view.update(super::COMPONENT_RADIO_COMPRESSION, props);
+        }
+        // Delta transfer
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_DELTA_TRANSFER) {
+            let delta_transfer: usize = match self.config().get_delta_transfer() {
+                true => 0,
+                false => 1,
+            };
+            let props = RadioPropsBuilder::from(props)
+                .with_value(delta_transfer)
+                .build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_RADIO_DELTA_TRANSFER, props);
+        }
+        // Skip unchanged
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_SKIP_UNCHANGED) {
+            let skip_unchanged: usize = match self.config().get_skip_unchanged() {
+                true => 0,
+                false => 1,
+            };
+            let props = RadioPropsBuilder::from(props)
+                .with_value(skip_unchanged)
+                .build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_RADIO_SKIP_UNCHANGED, props);
+        }
+        // Show banner
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_SHOW_BANNER) {
+            let show_banner: usize = match self.config().get_show_banner() {
+                true => 0,
+                false => 1,
+            };
+            let props = RadioPropsBuilder::from(props)
+                .with_value(show_banner)
+                .build();
+            let _ = self.view.update(super::COMPONENT_RADIO_SHOW_BANNER, props);
+        }
+        // Prefetch dirs
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_PREFETCH_DIRS) {
+            let prefetch: usize = match self.config().get_prefetch_dirs() {
+                true => 0,
+                false => 1,
+            };
+            let props = RadioPropsBuilder::from(props).with_value(prefetch).build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_RADIO_PREFETCH_DIRS, props);
+        }
         // Group dirs
         if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_GROUP_DIRS) {
             let dirs: usize = match self.config().get_group_dirs() {
@@ -299,6 +852,59 @@ impl SetupActivity {
             let props = RadioPropsBuilder::from(props).with_value(dirs).build();
             let _ = self.view.update(super::COMPONENT_RADIO_GROUP_DIRS, props);
         }
+        // Preserve owner
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_PRESERVE_OWNER) {
+            let preserve_owner: usize = match self.config().get_preserve_owner() {
+                true => 0,
+                false => 1,
+            };
+            let props = RadioPropsBuilder::from(props)
+                .with_value(preserve_owner)
+                .build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_RADIO_PRESERVE_OWNER, props);
+        }
+        // File sorting
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_FILE_SORTING) {
+            let sorting: usize = match self.config().get_file_sorting() {
+                FileSorting::Name => 0,
+                FileSorting::ModifyTime => 1,
+                FileSorting::CreationTime => 2,
+                FileSorting::Size => 3,
+                FileSorting::Extension => 4,
+                FileSorting::Owner => 5,
+                FileSorting::Permissions => 6,
+            };
+            let props = RadioPropsBuilder::from(props).with_value(sorting).build();
+            let _ = self.view.update(super::COMPONENT_RADIO_FILE_SORTING, props);
+        }
+        // Size units
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_SIZE_UNITS) {
+            let units: usize = match self.config().get_size_units() {
+                SizeUnits::Si => 0,
+                SizeUnits::Binary => 1,
+            };
+            let props = RadioPropsBuilder::from(props).with_value(units).build();
+            let _ = self.view.update(super::COMPONENT_RADIO_SIZE_UNITS, props);
+        }
+        // Clock
+        if let Some(props) = self.view.get_props(super::COMPONENT_RADIO_CLOCK) {
+            let clock: usize = match self.config().get_clock() {
+                ClockFormat::H24 => 0,
+                ClockFormat::H12 => 1,
+            };
+            let props = RadioPropsBuilder::from(props).with_value(clock).build();
+            let _ = self.view.update(super::COMPONENT_RADIO_CLOCK, props);
+        }
+        // Date format
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_DATE_FORMAT) {
+            let date_format: String = self.config().get_date_format();
+            let props = InputPropsBuilder::from(props)
+                .with_value(date_format)
+                .build();
+            let _ = self.view.update(super::COMPONENT_INPUT_DATE_FORMAT, props);
+        }
         // Local File Fmt
         if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_LOCAL_FILE_FMT) {
             let file_fmt: String = self.config().get_local_file_fmt().unwrap_or_default();
@@ -315,6 +921,141 @@ impl SetupActivity {
                 .view
                 .update(super::COMPONENT_INPUT_REMOTE_FILE_FMT, props);
         }
+        // Proxy
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_PROXY) {
+            let proxy: String = self.config().get_proxy().unwrap_or_default();
+            let props = InputPropsBuilder::from(props).with_value(proxy).build();
+            let _ = self.view.update(super::COMPONENT_INPUT_PROXY, props);
+        }
+        // Stall timeout
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_STALL_TIMEOUT) {
+            let timeout: String = self
+                .config()
+                .get_stall_timeout()
+                .map(|x| x.as_secs())
+                .unwrap_or(0)
+                .to_string();
+            let props = InputPropsBuilder::from(props).with_value(timeout).build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_INPUT_STALL_TIMEOUT, props);
+        }
+        // Download dir
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_DOWNLOAD_DIR) {
+            let download_dir: String = self
+                .config()
+                .get_download_dir()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let props = InputPropsBuilder::from(props)
+                .with_value(download_dir)
+                .build();
+            let _ = self.view.update(super::COMPONENT_INPUT_DOWNLOAD_DIR, props);
+        }
+        // Parallel transfers
+        if let Some(props) = self
+            .view
+            .get_props(super::COMPONENT_INPUT_PARALLEL_TRANSFERS)
+        {
+            let parallel: String = self.config().get_parallel_transfers().to_string();
+            let props = InputPropsBuilder::from(props).with_value(parallel).build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_INPUT_PARALLEL_TRANSFERS, props);
+        }
+        // Recents
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_RECENTS_SIZE) {
+            let size: String = self.config().get_recents_size().to_string();
+            let props = InputPropsBuilder::from(props).with_value(size).build();
+            let _ = self.view.update(super::COMPONENT_INPUT_RECENTS_SIZE, props);
+        }
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_RECENTS_MAX_AGE) {
+            let max_age: String = self
+                .config()
+                .get_recents_max_age()
+                .map(|x| x.to_string())
+                .unwrap_or_default();
+            let props = InputPropsBuilder::from(props).with_value(max_age).build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_INPUT_RECENTS_MAX_AGE, props);
+        }
+        // Diff and merge tools
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_DIFF_TOOL) {
+            let tool: String = self
+                .config()
+                .get_diff_tool()
+                .unwrap_or_default()
+                .to_string();
+            let props = InputPropsBuilder::from(props).with_value(tool).build();
+            let _ = self.view.update(super::COMPONENT_INPUT_DIFF_TOOL, props);
+        }
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_MERGE_TOOL) {
+            let tool: String = self
+                .config()
+                .get_merge_tool()
+                .unwrap_or_default()
+                .to_string();
+            let props = InputPropsBuilder::from(props).with_value(tool).build();
+            let _ = self.view.update(super::COMPONENT_INPUT_MERGE_TOOL, props);
+        }
+        // Webhook
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_WEBHOOK_URL) {
+            let url: String = self
+                .config()
+                .get_webhook_url()
+                .unwrap_or_default()
+                .to_string();
+            let props = InputPropsBuilder::from(props).with_value(url).build();
+            let _ = self.view.update(super::COMPONENT_INPUT_WEBHOOK_URL, props);
+        }
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_WEBHOOK_TEMPLATE) {
+            let template: String = self
+                .config()
+                .get_webhook_template()
+                .unwrap_or_default()
+                .to_string();
+            let props = InputPropsBuilder::from(props).with_value(template).build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_INPUT_WEBHOOK_TEMPLATE, props);
+        }
+        // Metrics
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_METRICS_FILE) {
+            let path: String = self
+                .config()
+                .get_metrics_file()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let props = InputPropsBuilder::from(props).with_value(path).build();
+            let _ = self.view.update(super::COMPONENT_INPUT_METRICS_FILE, props);
+        }
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_METRICS_PORT) {
+            let port: String = self
+                .config()
+                .get_metrics_port()
+                .map(|x| x.to_string())
+                .unwrap_or_default();
+            let props = InputPropsBuilder::from(props).with_value(port).build();
+            let _ = self.view.update(super::COMPONENT_INPUT_METRICS_PORT, props);
+        }
+        // Exit hooks
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_ON_DISCONNECT) {
+            let command: String = self
+                .config()
+                .get_on_disconnect()
+                .unwrap_or_default()
+                .to_string();
+            let props = InputPropsBuilder::from(props).with_value(command).build();
+            let _ = self
+                .view
+                .update(super::COMPONENT_INPUT_ON_DISCONNECT, props);
+        }
+        if let Some(props) = self.view.get_props(super::COMPONENT_INPUT_ON_QUIT) {
+            let command: String = self.config().get_on_quit().unwrap_or_default().to_string();
+            let props = InputPropsBuilder::from(props).with_value(command).build();
+            let _ = self.view.update(super::COMPONENT_INPUT_ON_QUIT, props);
+        }
     }
 
     /// ### collect_input_values
@@ -330,12 +1071,10 @@ impl SetupActivity {
         if let Some(Payload::One(Value::Usize(protocol))) =
             self.view.get_state(super::COMPONENT_RADIO_DEFAULT_PROTOCOL)
         {
-            let protocol: FileTransferProtocol = match protocol {
-                1 => FileTransferProtocol::Scp,
-                2 => FileTransferProtocol::Ftp(false),
-                3 => FileTransferProtocol::Ftp(true),
-                _ => FileTransferProtocol::Sftp,
-            };
+            let protocol: FileTransferProtocol = registry::protocols()
+                .get(protocol)
+                .copied()
+                .unwrap_or(FileTransferProtocol::Sftp);
             self.config_mut().set_default_protocol(protocol);
         }
         if let Some(Payload::One(Value::Usize(opt))) =
@@ -344,12 +1083,86 @@ impl SetupActivity {
             let show: bool = matches!(opt, 0);
             self.config_mut().set_show_hidden_files(show);
         }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_SYMLINKS)
+        {
+            let symlinks: SymlinkMode = match opt {
+                0 => SymlinkMode::Skip,
+                2 => SymlinkMode::Recreate,
+                _ => SymlinkMode::Follow,
+            };
+            self.config_mut().set_symlinks(symlinks);
+        }
         if let Some(Payload::One(Value::Usize(opt))) =
             self.view.get_state(super::COMPONENT_RADIO_UPDATES)
         {
             let check: bool = matches!(opt, 0);
             self.config_mut().set_check_for_updates(check);
         }
+        if let Some(Payload::One(Value::Usize(retries))) =
+            self.view.get_state(super::COMPONENT_INPUT_TRANSFER_RETRIES)
+        {
+            self.config_mut().set_transfer_retries(retries);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_LOW_BANDWIDTH)
+        {
+            let low_bandwidth: bool = matches!(opt, 0);
+            self.config_mut().set_low_bandwidth(low_bandwidth);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_COMPRESSION)
+        {
+            let compression: bool = matches!(opt, 0);
+            self.config_mut().set_compression(compression);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_DELTA_TRANSFER)
+        {
+            let delta_transfer: bool = matches!(opt, 0);
+            self.config_mut().set_delta_transfer(delta_transfer);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_SKIP_UNCHANGED)
+        {
+            let skip_unchanged: bool = matches!(opt, 0);
+            self.config_mut().set_skip_unchanged(skip_unchanged);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_SHOW_BANNER)
+        {
+            let show_banner: bool = matches!(opt, 0);
+            self.config_mut().set_show_banner(show_banner);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_PREFETCH_DIRS)
+        {
+            let prefetch: bool = matches!(opt, 0);
+            self.config_mut().set_prefetch_dirs(prefetch);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_SIZE_UNITS)
+        {
+            let units: SizeUnits = match opt {
+                1 => SizeUnits::Binary,
+                _ => SizeUnits::Si,
+            };
+            self.config_mut().set_size_units(units);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_CLOCK)
+        {
+            let clock: ClockFormat = match opt {
+                1 => ClockFormat::H12,
+                _ => ClockFormat::H24,
+            };
+            self.config_mut().set_clock(clock);
+        }
+        if let Some(Payload::One(Value::Str(fmt))) =
+            self.view.get_state(super::COMPONENT_INPUT_DATE_FORMAT)
+        {
+            self.config_mut().set_date_format(fmt);
+        }
         if let Some(Payload::One(Value::Str(fmt))) =
             self.view.get_state(super::COMPONENT_INPUT_LOCAL_FILE_FMT)
         {
@@ -360,6 +1173,80 @@ impl SetupActivity {
         {
             self.config_mut().set_remote_file_fmt(fmt);
         }
+        if let Some(Payload::One(Value::Str(proxy))) =
+            self.view.get_state(super::COMPONENT_INPUT_PROXY)
+        {
+            self.config_mut().set_proxy(proxy);
+        }
+        if let Some(Payload::One(Value::Str(dir))) =
+            self.view.get_state(super::COMPONENT_INPUT_DOWNLOAD_DIR)
+        {
+            self.config_mut().set_download_dir(dir);
+        }
+        if let Some(Payload::One(Value::Str(tool))) =
+            self.view.get_state(super::COMPONENT_INPUT_DIFF_TOOL)
+        {
+            self.config_mut().set_diff_tool(tool);
+        }
+        if let Some(Payload::One(Value::Str(tool))) =
+            self.view.get_state(super::COMPONENT_INPUT_MERGE_TOOL)
+        {
+            self.config_mut().set_merge_tool(tool);
+        }
+        if let Some(Payload::One(Value::Str(url))) =
+            self.view.get_state(super::COMPONENT_INPUT_WEBHOOK_URL)
+        {
+            self.config_mut().set_webhook_url(url);
+        }
+        if let Some(Payload::One(Value::Str(template))) =
+            self.view.get_state(super::COMPONENT_INPUT_WEBHOOK_TEMPLATE)
+        {
+            self.config_mut().set_webhook_template(template);
+        }
+        if let Some(Payload::One(Value::Str(path))) =
+            self.view.get_state(super::COMPONENT_INPUT_METRICS_FILE)
+        {
+            self.config_mut().set_metrics_file(path);
+        }
+        // An empty or invalid port disables the endpoint
+        let metrics_port: u16 = match self.view.get_state(super::COMPONENT_INPUT_METRICS_PORT) {
+            Some(Payload::One(Value::Usize(port))) => u16::try_from(port).unwrap_or(0),
+            _ => 0,
+        };
+        self.config_mut().set_metrics_port(metrics_port);
+        if let Some(Payload::One(Value::Str(command))) =
+            self.view.get_state(super::COMPONENT_INPUT_ON_DISCONNECT)
+        {
+            self.config_mut().set_on_disconnect(command);
+        }
+        if let Some(Payload::One(Value::Str(command))) =
+            self.view.get_state(super::COMPONENT_INPUT_ON_QUIT)
+        {
+            self.config_mut().set_on_quit(command);
+        }
+        // Number inputs report their value as `Usize`; an empty input reports nothing
+        if let Some(Payload::One(Value::Usize(secs))) =
+            self.view.get_state(super::COMPONENT_INPUT_STALL_TIMEOUT)
+        {
+            self.config_mut().set_stall_timeout(secs as u64);
+        }
+        if let Some(Payload::One(Value::Usize(size))) =
+            self.view.get_state(super::COMPONENT_INPUT_RECENTS_SIZE)
+        {
+            self.config_mut().set_recents_size(size);
+        }
+        if let Some(Payload::One(Value::Usize(parallel))) = self
+            .view
+            .get_state(super::COMPONENT_INPUT_PARALLEL_TRANSFERS)
+        {
+            self.config_mut().set_parallel_transfers(parallel);
+        }
+        let max_age: Option<u64> = match self.view.get_state(super::COMPONENT_INPUT_RECENTS_MAX_AGE)
+        {
+            Some(Payload::One(Value::Usize(days))) if days > 0 => Some(days as u64),
+            _ => None,
+        };
+        self.config_mut().set_recents_max_age(max_age);
         if let Some(Payload::One(Value::Usize(opt))) =
             self.view.get_state(super::COMPONENT_RADIO_GROUP_DIRS)
         {
@@ -370,5 +1257,25 @@ impl SetupActivity {
             };
             self.config_mut().set_group_dirs(dirs);
         }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_PRESERVE_OWNER)
+        {
+            let preserve_owner: bool = matches!(opt, 0);
+            self.config_mut().set_preserve_owner(preserve_owner);
+        }
+        if let Some(Payload::One(Value::Usize(opt))) =
+            self.view.get_state(super::COMPONENT_RADIO_FILE_SORTING)
+        {
+            let sorting: FileSorting = match opt {
+                1 => FileSorting::ModifyTime,
+                2 => FileSorting::CreationTime,
+                3 => FileSorting::Size,
+                4 => FileSorting::Extension,
+                5 => FileSorting::Owner,
+                6 => FileSorting::Permissions,
+                _ => FileSorting::Name,
+            };
+            self.config_mut().set_file_sorting(sorting);
+        }
     }
 }
@@ -72,14 +72,14 @@ impl SetupActivity {
             )),
         );
         // Give focus
-        self.view.active(super::COMPONENT_LIST_SSH_KEYS);
+        self.focus_panel(super::COMPONENT_LIST_SSH_KEYS);
         // Load keys
         self.reload_ssh_keys();
     }
 
     pub(crate) fn view_ssh_keys(&mut self) {
         let mut ctx: Context = self.context.take().unwrap();
-        let _ = ctx.terminal().draw(|f| {
+        let _ = ctx.draw(|f| {
             // Prepare main chunks
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -187,14 +187,14 @@ impl SetupActivity {
             )),
         );
         // Active
-        self.view.active(super::COMPONENT_RADIO_DEL_SSH_KEY);
+        self.open_popup(super::COMPONENT_RADIO_DEL_SSH_KEY);
     }
 
     /// ### umount_del_ssh_key
     ///
     /// Umount delete ssh key
     pub(crate) fn umount_del_ssh_key(&mut self) {
-        self.view.umount(super::COMPONENT_RADIO_DEL_SSH_KEY);
+        self.close_popup(super::COMPONENT_RADIO_DEL_SSH_KEY);
     }
 
     /// ### mount_new_ssh_key
@@ -227,15 +227,15 @@ impl SetupActivity {
                     .build(),
             )),
         );
-        self.view.active(super::COMPONENT_INPUT_SSH_HOST);
+        self.open_popup(super::COMPONENT_INPUT_SSH_HOST);
     }
 
     /// ### umount_new_ssh_key
     ///
     /// Umount new ssh key prompt
     pub(crate) fn umount_new_ssh_key(&mut self) {
-        self.view.umount(super::COMPONENT_INPUT_SSH_HOST);
         self.view.umount(super::COMPONENT_INPUT_SSH_USERNAME);
+        self.close_popup(super::COMPONENT_INPUT_SSH_HOST);
     }
 
     /// ### reload_ssh_keys
@@ -29,7 +29,9 @@
 // Locals
 use super::{Context, SetupActivity};
 use crate::config::themes::Theme;
+use crate::ui::components::color_palette::{ColorPalette, ColorPalettePropsBuilder};
 use crate::ui::components::color_picker::{ColorPicker, ColorPickerPropsBuilder};
+use crate::ui::components::theme_preview::{ThemePreview, ThemePreviewPropsBuilder};
 use crate::utils::parser::parse_color;
 use crate::utils::ui::draw_area_in;
 // Ext
@@ -131,15 +133,26 @@ impl SetupActivity {
             super::COMPONENT_COLOR_TRANSFER_STATUS_SYNC,
             "Synchronized browsing",
         );
+        // Preview
+        self.view.mount(
+            super::COMPONENT_THEME_PREVIEW,
+            Box::new(ThemePreview::new(
+                ThemePreviewPropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::Reset)
+                    .with_title("Preview", Alignment::Left)
+                    .build(),
+            )),
+        );
         // Load styles
         self.load_styles();
         // Active first field
-        self.view.active(super::COMPONENT_COLOR_AUTH_PROTOCOL);
+        self.focus_panel(super::COMPONENT_COLOR_AUTH_PROTOCOL);
     }
 
     pub(super) fn view_theme(&mut self) {
+        self.refresh_theme_preview();
         let mut ctx: Context = self.context.take().unwrap();
-        let _ = ctx.terminal().draw(|f| {
+        let _ = ctx.draw(|f| {
             // Prepare main chunks
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -148,6 +161,7 @@ impl SetupActivity {
                     [
                         Constraint::Length(3),  // Current tab
                         Constraint::Length(22), // Main body
+                        Constraint::Length(9),  // Preview
                         Constraint::Length(3),  // Help footer
                     ]
                     .as_ref(),
@@ -155,7 +169,9 @@ impl SetupActivity {
                 .split(f.size());
             // Render common widget
             self.view.render(super::COMPONENT_RADIO_TAB, f, chunks[0]);
-            self.view.render(super::COMPONENT_TEXT_FOOTER, f, chunks[2]);
+            self.view
+                .render(super::COMPONENT_THEME_PREVIEW, f, chunks[2]);
+            self.view.render(super::COMPONENT_TEXT_FOOTER, f, chunks[3]);
             // Make chunks
             let colors_layout = Layout::default()
                 .direction(Direction::Horizontal)
@@ -355,6 +371,14 @@ impl SetupActivity {
                 transfer_colors_layout_col2[7],
             );
             // Popups
+            if let Some(props) = self.view.get_props(super::COMPONENT_COLOR_PALETTE) {
+                if props.visible {
+                    let popup = draw_area_in(f.size(), 70, 60);
+                    f.render_widget(Clear, popup);
+                    // make popup
+                    self.view.render(super::COMPONENT_COLOR_PALETTE, f, popup);
+                }
+            }
             if let Some(props) = self.view.get_props(super::COMPONENT_TEXT_ERROR) {
                 if props.visible {
                     let popup = draw_area_in(f.size(), 50, 10);
@@ -392,6 +416,33 @@ impl SetupActivity {
         self.context = Some(ctx);
     }
 
+    /// ### mount_color_palette
+    ///
+    /// Mount the color palette to choose the color of the theme field `component`
+    pub(crate) fn mount_color_palette(&mut self, component: &str) {
+        let color: Color = self.get_color(component).unwrap_or(Color::Reset);
+        self.view.mount(
+            super::COMPONENT_COLOR_PALETTE,
+            Box::new(ColorPalette::new(
+                ColorPalettePropsBuilder::default()
+                    .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                    .with_title("Choose a color", Alignment::Center)
+                    .with_color(&color)
+                    .build(),
+            )),
+        );
+        self.color_palette_target = Some(component.to_string());
+        self.open_popup(super::COMPONENT_COLOR_PALETTE);
+    }
+
+    /// ### umount_color_palette
+    ///
+    /// Umount the color palette
+    pub(crate) fn umount_color_palette(&mut self) {
+        self.close_popup(super::COMPONENT_COLOR_PALETTE);
+        self.color_palette_target = None;
+    }
+
     /// ### load_styles
     ///
     /// Load values from theme into input fields
@@ -581,10 +632,93 @@ impl SetupActivity {
         Ok(())
     }
 
+    /// ### refresh_theme_preview
+    ///
+    /// Update the preview with the colors being edited.
+    /// Colors which are not valid yet are previewed with the current theme color
+    fn refresh_theme_preview(&mut self) {
+        let mut theme: Theme = self.theme().clone();
+        let color =
+            |component: &str, fallback: Color| self.get_color(component).unwrap_or(fallback);
+        theme.auth_address = color(super::COMPONENT_COLOR_AUTH_ADDR, theme.auth_address);
+        theme.auth_bookmarks = color(super::COMPONENT_COLOR_AUTH_BOOKMARKS, theme.auth_bookmarks);
+        theme.auth_password = color(super::COMPONENT_COLOR_AUTH_PASSWORD, theme.auth_password);
+        theme.auth_port = color(super::COMPONENT_COLOR_AUTH_PORT, theme.auth_port);
+        theme.auth_protocol = color(super::COMPONENT_COLOR_AUTH_PROTOCOL, theme.auth_protocol);
+        theme.auth_recents = color(super::COMPONENT_COLOR_AUTH_RECENTS, theme.auth_recents);
+        theme.auth_username = color(super::COMPONENT_COLOR_AUTH_USERNAME, theme.auth_username);
+        theme.misc_error_dialog = color(super::COMPONENT_COLOR_MISC_ERROR, theme.misc_error_dialog);
+        theme.misc_input_dialog = color(super::COMPONENT_COLOR_MISC_INPUT, theme.misc_input_dialog);
+        theme.misc_keys = color(super::COMPONENT_COLOR_MISC_KEYS, theme.misc_keys);
+        theme.misc_quit_dialog = color(super::COMPONENT_COLOR_MISC_QUIT, theme.misc_quit_dialog);
+        theme.misc_save_dialog = color(super::COMPONENT_COLOR_MISC_SAVE, theme.misc_save_dialog);
+        theme.misc_warn_dialog = color(super::COMPONENT_COLOR_MISC_WARN, theme.misc_warn_dialog);
+        theme.transfer_local_explorer_background = color(
+            super::COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_BG,
+            theme.transfer_local_explorer_background,
+        );
+        theme.transfer_local_explorer_foreground = color(
+            super::COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_FG,
+            theme.transfer_local_explorer_foreground,
+        );
+        theme.transfer_local_explorer_highlighted = color(
+            super::COMPONENT_COLOR_TRANSFER_EXPLORER_LOCAL_HG,
+            theme.transfer_local_explorer_highlighted,
+        );
+        theme.transfer_remote_explorer_background = color(
+            super::COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_BG,
+            theme.transfer_remote_explorer_background,
+        );
+        theme.transfer_remote_explorer_foreground = color(
+            super::COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_FG,
+            theme.transfer_remote_explorer_foreground,
+        );
+        theme.transfer_remote_explorer_highlighted = color(
+            super::COMPONENT_COLOR_TRANSFER_EXPLORER_REMOTE_HG,
+            theme.transfer_remote_explorer_highlighted,
+        );
+        theme.transfer_log_background = color(
+            super::COMPONENT_COLOR_TRANSFER_LOG_BG,
+            theme.transfer_log_background,
+        );
+        theme.transfer_log_window = color(
+            super::COMPONENT_COLOR_TRANSFER_LOG_WIN,
+            theme.transfer_log_window,
+        );
+        theme.transfer_progress_bar_full = color(
+            super::COMPONENT_COLOR_TRANSFER_PROG_BAR_FULL,
+            theme.transfer_progress_bar_full,
+        );
+        theme.transfer_progress_bar_partial = color(
+            super::COMPONENT_COLOR_TRANSFER_PROG_BAR_PARTIAL,
+            theme.transfer_progress_bar_partial,
+        );
+        theme.transfer_status_hidden = color(
+            super::COMPONENT_COLOR_TRANSFER_STATUS_HIDDEN,
+            theme.transfer_status_hidden,
+        );
+        theme.transfer_status_sorting = color(
+            super::COMPONENT_COLOR_TRANSFER_STATUS_SORTING,
+            theme.transfer_status_sorting,
+        );
+        theme.transfer_status_sync_browsing = color(
+            super::COMPONENT_COLOR_TRANSFER_STATUS_SYNC,
+            theme.transfer_status_sync_browsing,
+        );
+        if let Some(props) = self.view.get_props(super::COMPONENT_THEME_PREVIEW) {
+            self.view.update(
+                super::COMPONENT_THEME_PREVIEW,
+                ThemePreviewPropsBuilder::from(props)
+                    .with_theme(&theme)
+                    .build(),
+            );
+        }
+    }
+
     /// ### update_color
     ///
     /// Update color for provided component
-    fn update_color(&mut self, component: &str, color: Color) {
+    pub(crate) fn update_color(&mut self, component: &str, color: Color) {
         if let Some(props) = self.view.get_props(component) {
             self.view.update(
                 component,
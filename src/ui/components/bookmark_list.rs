@@ -39,6 +39,7 @@ use tuirealm::{Component, Frame, Msg, Payload, PropPayload, PropValue, Value};
 
 // -- props
 const PROP_BOOKMARKS: &str = "bookmarks";
+const PROP_INDEX: &str = "index";
 
 pub struct BookmarkListPropsBuilder {
     props: Option<Props>,
@@ -134,6 +135,18 @@ impl BookmarkListPropsBuilder {
         }
         self
     }
+
+    /// ### with_index
+    ///
+    /// Select the entry at `index` on the next update, instead of the first one
+    pub fn with_index(&mut self, index: usize) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props
+                .own
+                .insert(PROP_INDEX, PropPayload::One(PropValue::Usize(index)));
+        }
+        self
+    }
 }
 
 // -- states
@@ -199,6 +212,16 @@ impl OwnStates {
     pub fn reset_list_index(&mut self) {
         self.list_index = 0;
     }
+
+    /// ### set_list_index
+    ///
+    /// Set list index, keeping it inside the list
+    pub fn set_list_index(&mut self, index: usize) {
+        self.list_index = match self.list_len {
+            0 => 0,
+            len => std::cmp::min(index, len - 1),
+        };
+    }
 }
 
 // -- Component
@@ -276,8 +299,11 @@ impl Component for BookmarkList {
         self.props = props;
         // re-Set list length
         self.states.set_list_len(Self::bookmarks_len(&self.props));
-        // Reset list index
-        self.states.reset_list_index();
+        // Select requested index (just once) or reset list index
+        match self.props.own.remove(PROP_INDEX) {
+            Some(PropPayload::One(PropValue::Usize(index))) => self.states.set_list_index(index),
+            _ => self.states.reset_list_index(),
+        }
         Msg::None
     }
 
@@ -410,6 +436,23 @@ mod tests {
         // Verify states
         assert_eq!(component.states.list_index, 0);
         assert_eq!(component.states.list_len, 3);
+        // Update keeping index
+        component.update(
+            BookmarkListPropsBuilder::from(component.get_props())
+                .with_index(2)
+                .build(),
+        );
+        assert_eq!(component.states.list_index, 2);
+        assert!(!component.props.own.contains_key(PROP_INDEX));
+        component.update(
+            BookmarkListPropsBuilder::from(component.get_props())
+                .with_index(8)
+                .build(),
+        );
+        assert_eq!(component.states.list_index, 2);
+        // Index is applied just once
+        component.update(BookmarkListPropsBuilder::from(component.get_props()).build());
+        assert_eq!(component.states.list_index, 0);
         // get value
         assert_eq!(component.get_state(), Payload::One(Value::Usize(0)));
         // Render
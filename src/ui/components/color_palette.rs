@@ -0,0 +1,541 @@
+//! ## ColorPalette
+//!
+//! `ColorPalette` component allows to choose a color from a grid of named colors or composing it with RGB sliders
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use crate::utils::fmt::fmt_color;
+// ext
+use tui_realm_stdlib::utils::get_block;
+use tuirealm::event::{Event, KeyCode};
+use tuirealm::props::{Alignment, BlockTitle, BordersProps, Props, PropsBuilder};
+use tuirealm::tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{BorderType, Borders, Gauge, Paragraph},
+};
+use tuirealm::{Component, Frame, Msg, Payload, Value};
+
+// -- props
+
+const PALETTE_COLOR: &str = "color";
+
+/// Named colors, as displayed in the grid
+const NAMED_COLORS: [Color; 17] = [
+    Color::Reset,
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+const GRID_COLUMNS: usize = 3;
+const SLIDER_STEP: u8 = 16;
+
+pub struct ColorPalettePropsBuilder {
+    props: Option<Props>,
+}
+
+impl Default for ColorPalettePropsBuilder {
+    fn default() -> Self {
+        ColorPalettePropsBuilder {
+            props: Some(Props::default()),
+        }
+    }
+}
+
+impl PropsBuilder for ColorPalettePropsBuilder {
+    fn build(&mut self) -> Props {
+        self.props.take().unwrap()
+    }
+
+    fn hidden(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = false;
+        }
+        self
+    }
+
+    fn visible(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = true;
+        }
+        self
+    }
+}
+
+impl From<Props> for ColorPalettePropsBuilder {
+    fn from(props: Props) -> Self {
+        ColorPalettePropsBuilder { props: Some(props) }
+    }
+}
+
+impl ColorPalettePropsBuilder {
+    /// ### with_borders
+    ///
+    /// Set component borders style
+    pub fn with_borders(
+        &mut self,
+        borders: Borders,
+        variant: BorderType,
+        color: Color,
+    ) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.borders = BordersProps {
+                borders,
+                variant,
+                color,
+            }
+        }
+        self
+    }
+
+    /// ### with_title
+    ///
+    /// Set title
+    pub fn with_title<S: AsRef<str>>(&mut self, text: S, alignment: Alignment) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.title = Some(BlockTitle::new(text, alignment));
+        }
+        self
+    }
+
+    /// ### with_color
+    ///
+    /// Set the color selected when the palette is opened
+    pub fn with_color(&mut self, color: &Color) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.palette.insert(PALETTE_COLOR, *color);
+        }
+        self
+    }
+}
+
+// -- states
+
+/// ## Section
+///
+/// Section of the palette which has focus
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Section {
+    Grid,
+    Channel(usize), // 0: red, 1: green, 2: blue
+}
+
+/// ## OwnStates
+///
+/// OwnStates contains states for this component
+struct OwnStates {
+    section: Section,
+    grid_index: usize, // Selected named color
+    rgb: [u8; 3],      // Sliders value
+    color: Color,      // Chosen color
+}
+
+impl OwnStates {
+    /// ### new
+    ///
+    /// Instantiate states starting from `color`
+    fn new(color: Color) -> Self {
+        match color {
+            Color::Rgb(r, g, b) => Self {
+                section: Section::Channel(0),
+                grid_index: 0,
+                rgb: [r, g, b],
+                color,
+            },
+            color => {
+                let grid_index: usize = NAMED_COLORS.iter().position(|x| *x == color).unwrap_or(0);
+                Self {
+                    section: Section::Grid,
+                    grid_index,
+                    rgb: [0, 0, 0],
+                    color: NAMED_COLORS[grid_index],
+                }
+            }
+        }
+    }
+
+    /// ### next_section
+    ///
+    /// Move focus to the next section
+    fn next_section(&mut self) {
+        self.section = match self.section {
+            Section::Grid => Section::Channel(0),
+            Section::Channel(2) => Section::Grid,
+            Section::Channel(channel) => Section::Channel(channel + 1),
+        };
+    }
+
+    /// ### move_grid
+    ///
+    /// Move grid selection by `offset` and choose the selected named color
+    fn move_grid(&mut self, offset: isize) {
+        let index: isize = self.grid_index as isize + offset;
+        if index >= 0 && (index as usize) < NAMED_COLORS.len() {
+            self.grid_index = index as usize;
+        }
+        self.color = NAMED_COLORS[self.grid_index];
+    }
+
+    /// ### move_channel
+    ///
+    /// Increment (or decrement if `incr` is false) `channel` by `step` and choose the sliders color
+    fn move_channel(&mut self, channel: usize, step: u8, incr: bool) {
+        self.rgb[channel] = match incr {
+            true => self.rgb[channel].saturating_add(step),
+            false => self.rgb[channel].saturating_sub(step),
+        };
+        self.color = Color::Rgb(self.rgb[0], self.rgb[1], self.rgb[2]);
+    }
+}
+
+// -- component
+
+/// ## ColorPalette
+///
+/// Color picker popup: a named colors grid and RGB sliders. Submitting returns the chosen color as a string
+pub struct ColorPalette {
+    props: Props,
+    states: OwnStates,
+}
+
+impl ColorPalette {
+    /// ### new
+    ///
+    /// Instantiates a new `ColorPalette`
+    pub fn new(props: Props) -> Self {
+        let states: OwnStates = OwnStates::new(Self::initial_color(&props));
+        Self { props, states }
+    }
+
+    /// ### initial_color
+    ///
+    /// Get color to select when the palette is opened
+    fn initial_color(props: &Props) -> Color {
+        props
+            .palette
+            .get(PALETTE_COLOR)
+            .copied()
+            .unwrap_or(Color::Reset)
+    }
+
+    /// ### grid_lines
+    ///
+    /// Render named colors grid
+    fn grid_lines(&self) -> Vec<Spans<'_>> {
+        NAMED_COLORS
+            .chunks(GRID_COLUMNS)
+            .enumerate()
+            .map(|(row, colors)| {
+                let spans: Vec<Span> = colors
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(col, color)| {
+                        let index: usize = row * GRID_COLUMNS + col;
+                        let mut style: Style = Style::default();
+                        if index == self.states.grid_index {
+                            style = match self.states.section {
+                                Section::Grid => style.add_modifier(Modifier::REVERSED),
+                                Section::Channel(_) => style.add_modifier(Modifier::UNDERLINED),
+                            };
+                        }
+                        vec![
+                            Span::styled("██", Style::default().fg(*color)),
+                            Span::styled(format!(" {:<13}", fmt_color(color)), style),
+                        ]
+                    })
+                    .collect();
+                Spans::from(spans)
+            })
+            .collect()
+    }
+}
+
+impl Component for ColorPalette {
+    #[cfg(not(tarpaulin_include))]
+    fn render(&self, render: &mut Frame, area: Rect) {
+        if self.props.visible {
+            let block = get_block(&self.props.borders, self.props.title.as_ref(), true);
+            let inner: Rect = block.inner(area);
+            render.render_widget(block, area);
+            let rows: u16 = ((NAMED_COLORS.len() + GRID_COLUMNS - 1) / GRID_COLUMNS) as u16;
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .horizontal_margin(1)
+                .constraints(
+                    [
+                        Constraint::Length(rows), // Grid
+                        Constraint::Length(1),    // Spacing
+                        Constraint::Length(1),    // Red
+                        Constraint::Length(1),    // Green
+                        Constraint::Length(1),    // Blue
+                        Constraint::Length(1),    // Spacing
+                        Constraint::Length(1),    // Chosen color
+                        Constraint::Min(0),
+                    ]
+                    .as_ref(),
+                )
+                .split(inner);
+            render.render_widget(Paragraph::new(self.grid_lines()), chunks[0]);
+            let channels: [(&str, Color); 3] =
+                [("R", Color::Red), ("G", Color::Green), ("B", Color::Blue)];
+            for (channel, (name, color)) in channels.iter().enumerate() {
+                let value: u8 = self.states.rgb[channel];
+                let mut label_style: Style = Style::default();
+                if self.states.section == Section::Channel(channel) {
+                    label_style = label_style.add_modifier(Modifier::REVERSED);
+                }
+                let slider = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(6), Constraint::Min(0)].as_ref())
+                    .split(chunks[2 + channel]);
+                render.render_widget(
+                    Paragraph::new(Span::styled(format!("{} {:>3}", name, value), label_style)),
+                    slider[0],
+                );
+                render.render_widget(
+                    Gauge::default()
+                        .gauge_style(Style::default().fg(*color).bg(Color::Black))
+                        .label("")
+                        .ratio(value as f64 / 255.0),
+                    slider[1],
+                );
+            }
+            render.render_widget(
+                Paragraph::new(Spans::from(vec![
+                    Span::styled("██", Style::default().fg(self.states.color)),
+                    Span::raw(" "),
+                    Span::styled(
+                        fmt_color(&self.states.color),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                ])),
+                chunks[6],
+            );
+        }
+    }
+
+    fn update(&mut self, props: Props) -> Msg {
+        self.states = OwnStates::new(Self::initial_color(&props));
+        self.props = props;
+        Msg::None
+    }
+
+    fn get_props(&self) -> Props {
+        self.props.clone()
+    }
+
+    fn on(&mut self, ev: Event) -> Msg {
+        if let Event::Key(key) = ev {
+            match (self.states.section, key.code) {
+                (_, KeyCode::Tab) => {
+                    self.states.next_section();
+                    Msg::None
+                }
+                (_, KeyCode::Enter) => Msg::OnSubmit(self.get_state()),
+                (Section::Grid, KeyCode::Left) => {
+                    self.states.move_grid(-1);
+                    Msg::None
+                }
+                (Section::Grid, KeyCode::Right) => {
+                    self.states.move_grid(1);
+                    Msg::None
+                }
+                (Section::Grid, KeyCode::Up) => {
+                    self.states.move_grid(-(GRID_COLUMNS as isize));
+                    Msg::None
+                }
+                (Section::Grid, KeyCode::Down) => {
+                    self.states.move_grid(GRID_COLUMNS as isize);
+                    Msg::None
+                }
+                (Section::Channel(channel), KeyCode::Left) => {
+                    self.states.move_channel(channel, 1, false);
+                    Msg::None
+                }
+                (Section::Channel(channel), KeyCode::Right) => {
+                    self.states.move_channel(channel, 1, true);
+                    Msg::None
+                }
+                (Section::Channel(channel), KeyCode::PageDown) => {
+                    self.states.move_channel(channel, SLIDER_STEP, false);
+                    Msg::None
+                }
+                (Section::Channel(channel), KeyCode::PageUp) => {
+                    self.states.move_channel(channel, SLIDER_STEP, true);
+                    Msg::None
+                }
+                (Section::Channel(0), KeyCode::Up) => {
+                    self.states.section = Section::Grid;
+                    Msg::None
+                }
+                (Section::Channel(channel), KeyCode::Up) => {
+                    self.states.section = Section::Channel(channel - 1);
+                    Msg::None
+                }
+                (Section::Channel(channel), KeyCode::Down) => {
+                    self.states.section = Section::Channel((channel + 1).min(2));
+                    Msg::None
+                }
+                _ => Msg::OnKey(key),
+            }
+        } else {
+            Msg::None
+        }
+    }
+
+    fn get_state(&self) -> Payload {
+        Payload::One(Value::Str(fmt_color(&self.states.color)))
+    }
+
+    fn blur(&mut self) {}
+
+    fn active(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::event::KeyEvent;
+
+    #[test]
+    fn test_ui_components_color_palette_grid() {
+        let mut component: ColorPalette = ColorPalette::new(
+            ColorPalettePropsBuilder::default()
+                .hidden()
+                .visible()
+                .with_borders(Borders::ALL, BorderType::Rounded, Color::Reset)
+                .with_title("Color", Alignment::Center)
+                .with_color(&Color::Red)
+                .build(),
+        );
+        assert!(component.props.visible);
+        assert_eq!(component.states.section, Section::Grid);
+        assert_eq!(component.states.grid_index, 2);
+        assert_eq!(
+            component.get_state(),
+            Payload::One(Value::Str(String::from("Red")))
+        );
+        // Move
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Right))),
+            Msg::None
+        );
+        assert_eq!(component.states.color, Color::Green);
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Down))),
+            Msg::None
+        );
+        assert_eq!(component.states.color, Color::Magenta);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Up)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(component.states.color, Color::Reset);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Left)));
+        assert_eq!(component.states.grid_index, 0);
+        for _ in 0..10 {
+            component.on(Event::Key(KeyEvent::from(KeyCode::Down)));
+        }
+        assert_eq!(component.states.color, Color::LightCyan);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Right)));
+        assert_eq!(component.states.color, Color::White);
+        // Submit
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::OnSubmit(Payload::One(Value::Str(String::from("White"))))
+        );
+        // Other keys
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Esc))),
+            Msg::OnKey(KeyEvent::from(KeyCode::Esc))
+        );
+        assert_eq!(component.on(Event::Resize(0, 0)), Msg::None);
+        // Update resets states
+        let props = ColorPalettePropsBuilder::from(component.get_props())
+            .with_color(&Color::Indexed(3))
+            .build();
+        assert_eq!(component.update(props), Msg::None);
+        assert_eq!(component.states.color, Color::Reset);
+        assert_eq!(component.grid_lines().len(), 6);
+    }
+
+    #[test]
+    fn test_ui_components_color_palette_sliders() {
+        let mut component: ColorPalette = ColorPalette::new(
+            ColorPalettePropsBuilder::default()
+                .with_color(&Color::Rgb(204, 170, 0))
+                .build(),
+        );
+        assert_eq!(component.states.section, Section::Channel(0));
+        assert_eq!(component.states.rgb, [204, 170, 0]);
+        // Red
+        component.on(Event::Key(KeyEvent::from(KeyCode::Right)));
+        assert_eq!(component.states.color, Color::Rgb(205, 170, 0));
+        component.on(Event::Key(KeyEvent::from(KeyCode::PageUp)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::PageUp)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::PageUp)));
+        assert_eq!(component.states.color, Color::Rgb(253, 170, 0));
+        // Saturates
+        component.on(Event::Key(KeyEvent::from(KeyCode::PageUp)));
+        assert_eq!(component.states.color, Color::Rgb(255, 170, 0));
+        // Green
+        component.on(Event::Key(KeyEvent::from(KeyCode::Down)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::PageDown)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Left)));
+        assert_eq!(component.states.color, Color::Rgb(255, 153, 0));
+        // Blue
+        component.on(Event::Key(KeyEvent::from(KeyCode::Tab)));
+        assert_eq!(component.states.section, Section::Channel(2));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Left)));
+        assert_eq!(component.states.color, Color::Rgb(255, 153, 0));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Down)));
+        assert_eq!(component.states.section, Section::Channel(2));
+        assert_eq!(
+            component.get_state(),
+            Payload::One(Value::Str(String::from("#ff9900")))
+        );
+        // Back to grid
+        component.on(Event::Key(KeyEvent::from(KeyCode::Tab)));
+        assert_eq!(component.states.section, Section::Grid);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Tab)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(component.states.section, Section::Grid);
+    }
+}
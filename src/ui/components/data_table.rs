@@ -0,0 +1,641 @@
+//! ## DataTable
+//!
+//! `DataTable` component renders a table with fixed and flexible column widths, selection and
+//! horizontal scrolling
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// ext
+use tui_realm_stdlib::utils::get_block;
+use tuirealm::event::{Event, KeyCode};
+use tuirealm::props::{
+    Alignment, BlockTitle, BordersProps, Props, PropsBuilder, Table as TextTable,
+};
+use tuirealm::tui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::Span,
+    widgets::{BorderType, Borders, Cell, Row, Table, TableState},
+};
+use tuirealm::{Component, Frame, Msg, Payload, PropPayload, PropValue, Value};
+
+// -- props
+
+const PROP_HEADERS: &str = "headers";
+const PROP_TABLE: &str = "table";
+const PROP_WIDTHS: &str = "widths";
+
+const COLUMN_SPACING: u16 = 1;
+
+/// ## ColumnWidth
+///
+/// Describes how a column is sized
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnWidth {
+    /// Column takes exactly the provided amount of cells
+    Fixed(u16),
+    /// Column takes a share of the space left by fixed columns, proportional to its weight
+    Flex(u16),
+}
+
+impl From<ColumnWidth> for PropPayload {
+    fn from(width: ColumnWidth) -> Self {
+        match width {
+            ColumnWidth::Fixed(w) => PropPayload::Tup2((PropValue::Bool(false), PropValue::U16(w))),
+            ColumnWidth::Flex(w) => PropPayload::Tup2((PropValue::Bool(true), PropValue::U16(w))),
+        }
+    }
+}
+
+pub struct DataTablePropsBuilder {
+    props: Option<Props>,
+}
+
+impl Default for DataTablePropsBuilder {
+    fn default() -> Self {
+        DataTablePropsBuilder {
+            props: Some(Props::default()),
+        }
+    }
+}
+
+impl PropsBuilder for DataTablePropsBuilder {
+    fn build(&mut self) -> Props {
+        self.props.take().unwrap()
+    }
+
+    fn hidden(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = false;
+        }
+        self
+    }
+
+    fn visible(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = true;
+        }
+        self
+    }
+}
+
+impl From<Props> for DataTablePropsBuilder {
+    fn from(props: Props) -> Self {
+        DataTablePropsBuilder { props: Some(props) }
+    }
+}
+
+impl DataTablePropsBuilder {
+    /// ### with_foreground
+    ///
+    /// Set foreground color for area
+    #[allow(dead_code)]
+    pub fn with_foreground(&mut self, color: Color) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.foreground = color;
+        }
+        self
+    }
+
+    /// ### with_background
+    ///
+    /// Set background color for area
+    #[allow(dead_code)]
+    pub fn with_background(&mut self, color: Color) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.background = color;
+        }
+        self
+    }
+
+    /// ### with_borders
+    ///
+    /// Set component borders style
+    pub fn with_borders(
+        &mut self,
+        borders: Borders,
+        variant: BorderType,
+        color: Color,
+    ) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.borders = BordersProps {
+                borders,
+                variant,
+                color,
+            }
+        }
+        self
+    }
+
+    /// ### with_title
+    ///
+    /// Set block title
+    pub fn with_title<S: AsRef<str>>(&mut self, text: S, alignment: Alignment) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.title = Some(BlockTitle::new(text, alignment));
+        }
+        self
+    }
+
+    /// ### with_headers
+    ///
+    /// Set column headers
+    #[allow(dead_code)]
+    pub fn with_headers<S: AsRef<str>>(&mut self, headers: &[S]) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_HEADERS,
+                PropPayload::Vec(
+                    headers
+                        .iter()
+                        .map(|x| PropValue::Str(x.as_ref().to_string()))
+                        .collect(),
+                ),
+            );
+        }
+        self
+    }
+
+    /// ### with_widths
+    ///
+    /// Set column widths
+    pub fn with_widths(&mut self, widths: &[ColumnWidth]) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_WIDTHS,
+                PropPayload::Linked(widths.iter().map(|x| PropPayload::from(*x)).collect()),
+            );
+        }
+        self
+    }
+
+    /// ### with_table
+    ///
+    /// Set table rows
+    pub fn with_table(&mut self, table: TextTable) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props
+                .own
+                .insert(PROP_TABLE, PropPayload::One(PropValue::Table(table)));
+        }
+        self
+    }
+}
+
+// -- states
+
+/// ## OwnStates
+///
+/// OwnStates contains states for this component
+#[derive(Clone, Default)]
+struct OwnStates {
+    list_index: usize, // Index of selected row
+    list_len: usize,   // Amount of rows
+    col_offset: usize, // First visible column
+    col_len: usize,    // Amount of columns
+    focus: bool,       // Has focus?
+}
+
+impl OwnStates {
+    /// ### set_dimensions
+    ///
+    /// Set table dimensions, fixing indexes if out of bounds
+    pub fn set_dimensions(&mut self, rows: usize, cols: usize) {
+        self.list_len = rows;
+        self.col_len = cols;
+        if self.list_index >= rows {
+            self.list_index = rows.saturating_sub(1);
+        }
+        if self.col_offset >= cols {
+            self.col_offset = cols.saturating_sub(1);
+        }
+    }
+
+    /// ### incr_list_index
+    ///
+    /// Increment list index
+    pub fn incr_list_index(&mut self) {
+        if self.list_index + 1 < self.list_len {
+            self.list_index += 1;
+        }
+    }
+
+    /// ### decr_list_index
+    ///
+    /// Decrement list index
+    pub fn decr_list_index(&mut self) {
+        if self.list_index > 0 {
+            self.list_index -= 1;
+        }
+    }
+
+    /// ### scroll_right
+    ///
+    /// Scroll columns right
+    pub fn scroll_right(&mut self) {
+        if self.col_offset + 1 < self.col_len {
+            self.col_offset += 1;
+        }
+    }
+
+    /// ### scroll_left
+    ///
+    /// Scroll columns left
+    pub fn scroll_left(&mut self) {
+        if self.col_offset > 0 {
+            self.col_offset -= 1;
+        }
+    }
+}
+
+// -- Component
+
+/// ## DataTable
+///
+/// DataTable component
+pub struct DataTable {
+    props: Props,
+    states: OwnStates,
+}
+
+impl DataTable {
+    /// ### new
+    ///
+    /// Instantiates a new DataTable starting from Props
+    pub fn new(props: Props) -> Self {
+        let mut states: OwnStates = OwnStates::default();
+        states.set_dimensions(Self::rows(&props), Self::widths(&props).len());
+        DataTable { props, states }
+    }
+
+    fn rows(props: &Props) -> usize {
+        match props.own.get(PROP_TABLE) {
+            Some(PropPayload::One(PropValue::Table(table))) => table.len(),
+            _ => 0,
+        }
+    }
+
+    /// ### widths
+    ///
+    /// Get column widths from props. If unset, all columns of the first row are flex
+    fn widths(props: &Props) -> Vec<ColumnWidth> {
+        match props.own.get(PROP_WIDTHS) {
+            Some(PropPayload::Linked(widths)) => widths
+                .iter()
+                .map(|x| match x {
+                    PropPayload::Tup2((PropValue::Bool(true), PropValue::U16(w))) => {
+                        ColumnWidth::Flex(*w)
+                    }
+                    PropPayload::Tup2((_, PropValue::U16(w))) => ColumnWidth::Fixed(*w),
+                    _ => ColumnWidth::Flex(1),
+                })
+                .collect(),
+            _ => match props.own.get(PROP_TABLE) {
+                Some(PropPayload::One(PropValue::Table(table))) => {
+                    vec![ColumnWidth::Flex(1); table.first().map(|x| x.len()).unwrap_or(0)]
+                }
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    /// ### layout_columns
+    ///
+    /// Calculate the width of the visible columns, starting from `offset`, to fit in `width`.
+    /// Fixed columns take their width, then flex columns share what's left;
+    /// the last visible column is truncated to fit the available space.
+    fn layout_columns(columns: &[ColumnWidth], offset: usize, width: u16) -> Vec<u16> {
+        let columns: &[ColumnWidth] = columns.get(offset..).unwrap_or(&[]);
+        if columns.is_empty() {
+            return Vec::new();
+        }
+        let spacing: u16 = COLUMN_SPACING * (columns.len() as u16 - 1);
+        let fixed: u16 = columns
+            .iter()
+            .map(|x| match x {
+                ColumnWidth::Fixed(w) => *w,
+                ColumnWidth::Flex(_) => 0,
+            })
+            .fold(spacing, |acc, w| acc.saturating_add(w));
+        let flex_space: u32 = width.saturating_sub(fixed) as u32;
+        let weights: u32 = columns
+            .iter()
+            .map(|x| match x {
+                ColumnWidth::Flex(w) => *w as u32,
+                ColumnWidth::Fixed(_) => 0,
+            })
+            .sum();
+        let mut widths: Vec<u16> = Vec::with_capacity(columns.len());
+        let mut used: u16 = 0;
+        for column in columns.iter() {
+            let col_width: u16 = match column {
+                ColumnWidth::Fixed(w) => *w,
+                ColumnWidth::Flex(w) if weights > 0 => (flex_space * *w as u32 / weights) as u16,
+                ColumnWidth::Flex(_) => 0,
+            };
+            let space: u16 = width.saturating_sub(used);
+            if col_width >= space {
+                widths.push(space);
+                break;
+            }
+            widths.push(col_width);
+            used += col_width + COLUMN_SPACING;
+        }
+        widths
+    }
+}
+
+impl Component for DataTable {
+    #[cfg(not(tarpaulin_include))]
+    fn render(&self, render: &mut Frame, area: Rect) {
+        if self.props.visible {
+            let block = get_block(
+                &self.props.borders,
+                self.props.title.as_ref(),
+                self.states.focus,
+            );
+            let inner: Rect = block.inner(area);
+            let widths: Vec<Constraint> = Self::layout_columns(
+                &Self::widths(&self.props),
+                self.states.col_offset,
+                inner.width.saturating_sub(3), // Highlight symbol
+            )
+            .into_iter()
+            .map(Constraint::Length)
+            .collect();
+            let offset: usize = self.states.col_offset;
+            let rows: Vec<Row> = match self.props.own.get(PROP_TABLE) {
+                Some(PropPayload::One(PropValue::Table(table))) => table
+                    .iter()
+                    .map(|row| {
+                        Row::new(row.iter().skip(offset).map(|x| {
+                            Cell::from(Span::styled(
+                                x.content.clone(),
+                                Style::default().fg(x.fg).bg(x.bg).add_modifier(x.modifiers),
+                            ))
+                        }))
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let mut table = Table::new(rows)
+                .block(block)
+                .widths(&widths)
+                .column_spacing(COLUMN_SPACING)
+                .style(
+                    Style::default()
+                        .fg(self.props.foreground)
+                        .bg(self.props.background),
+                )
+                .highlight_symbol(">> ")
+                .highlight_style(match self.states.focus {
+                    true => Style::default().add_modifier(Modifier::REVERSED),
+                    false => Style::default(),
+                });
+            if let Some(PropPayload::Vec(headers)) = self.props.own.get(PROP_HEADERS) {
+                let headers: Vec<Cell> = headers
+                    .iter()
+                    .skip(offset)
+                    .map(|x| match x {
+                        PropValue::Str(s) => Cell::from(s.clone()),
+                        _ => Cell::from(""),
+                    })
+                    .collect();
+                table = table
+                    .header(Row::new(headers).style(Style::default().add_modifier(Modifier::BOLD)));
+            }
+            let mut state: TableState = TableState::default();
+            state.select(Some(self.states.list_index));
+            render.render_stateful_widget(table, area, &mut state);
+        }
+    }
+
+    fn update(&mut self, props: Props) -> Msg {
+        self.props = props;
+        self.states
+            .set_dimensions(Self::rows(&self.props), Self::widths(&self.props).len());
+        Msg::None
+    }
+
+    fn get_props(&self) -> Props {
+        self.props.clone()
+    }
+
+    fn on(&mut self, ev: Event) -> Msg {
+        if let Event::Key(key) = ev {
+            match key.code {
+                KeyCode::Down => {
+                    self.states.incr_list_index();
+                    Msg::None
+                }
+                KeyCode::Up => {
+                    self.states.decr_list_index();
+                    Msg::None
+                }
+                KeyCode::PageDown => {
+                    for _ in 0..8 {
+                        self.states.incr_list_index();
+                    }
+                    Msg::None
+                }
+                KeyCode::PageUp => {
+                    for _ in 0..8 {
+                        self.states.decr_list_index();
+                    }
+                    Msg::None
+                }
+                KeyCode::Home => {
+                    self.states.list_index = 0;
+                    Msg::None
+                }
+                KeyCode::End => {
+                    self.states.list_index = self.states.list_len.saturating_sub(1);
+                    Msg::None
+                }
+                KeyCode::Right => {
+                    self.states.scroll_right();
+                    Msg::None
+                }
+                KeyCode::Left => {
+                    self.states.scroll_left();
+                    Msg::None
+                }
+                KeyCode::Enter => Msg::OnSubmit(self.get_state()),
+                _ => Msg::OnKey(key),
+            }
+        } else {
+            Msg::None
+        }
+    }
+
+    fn get_state(&self) -> Payload {
+        Payload::One(Value::Usize(self.states.list_index))
+    }
+
+    fn blur(&mut self) {
+        self.states.focus = false;
+    }
+
+    fn active(&mut self) {
+        self.states.focus = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::event::KeyEvent;
+    use tuirealm::props::{TableBuilder, TextSpan};
+
+    #[test]
+    fn test_ui_components_data_table() {
+        let mut component: DataTable = DataTable::new(
+            DataTablePropsBuilder::default()
+                .hidden()
+                .visible()
+                .with_foreground(Color::Yellow)
+                .with_background(Color::Black)
+                .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                .with_title("files", Alignment::Left)
+                .with_headers(&["name", "size", "owner"])
+                .with_widths(&[
+                    ColumnWidth::Flex(1),
+                    ColumnWidth::Fixed(10),
+                    ColumnWidth::Fixed(8),
+                ])
+                .with_table(
+                    TableBuilder::default()
+                        .add_col(TextSpan::from("README.md"))
+                        .add_col(TextSpan::from("2 KB"))
+                        .add_col(TextSpan::from("omar"))
+                        .add_row()
+                        .add_col(TextSpan::from("Cargo.toml"))
+                        .add_col(TextSpan::from("1 KB"))
+                        .add_col(TextSpan::from("omar"))
+                        .build(),
+                )
+                .build(),
+        );
+        assert_eq!(component.props.visible, true);
+        assert_eq!(component.props.foreground, Color::Yellow);
+        assert_eq!(component.props.background, Color::Black);
+        assert_eq!(component.states.list_len, 2);
+        assert_eq!(component.states.col_len, 3);
+        assert_eq!(
+            DataTable::widths(&component.props),
+            vec![
+                ColumnWidth::Flex(1),
+                ColumnWidth::Fixed(10),
+                ColumnWidth::Fixed(8)
+            ]
+        );
+        // Focus
+        component.active();
+        assert_eq!(component.states.focus, true);
+        component.blur();
+        assert_eq!(component.states.focus, false);
+        // Selection
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Down))),
+            Msg::None
+        );
+        assert_eq!(component.get_state(), Payload::One(Value::Usize(1)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Down)));
+        assert_eq!(component.get_state(), Payload::One(Value::Usize(1)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(component.get_state(), Payload::One(Value::Usize(0)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::End)));
+        assert_eq!(component.get_state(), Payload::One(Value::Usize(1)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::PageUp)));
+        assert_eq!(component.get_state(), Payload::One(Value::Usize(0)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::PageDown)));
+        assert_eq!(component.get_state(), Payload::One(Value::Usize(1)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Home)));
+        assert_eq!(component.get_state(), Payload::One(Value::Usize(0)));
+        // Horizontal scroll
+        component.on(Event::Key(KeyEvent::from(KeyCode::Left)));
+        assert_eq!(component.states.col_offset, 0);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Right)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Right)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Right)));
+        assert_eq!(component.states.col_offset, 2);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Left)));
+        assert_eq!(component.states.col_offset, 1);
+        // Submit
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::OnSubmit(Payload::One(Value::Usize(0)))
+        );
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char('a')))),
+            Msg::OnKey(KeyEvent::from(KeyCode::Char('a')))
+        );
+        // Update with less rows and no widths
+        component.states.list_index = 1;
+        component.states.col_offset = 2;
+        let mut props = DataTablePropsBuilder::from(component.get_props())
+            .with_table(
+                TableBuilder::default()
+                    .add_col(TextSpan::from("README.md"))
+                    .add_col(TextSpan::from("2 KB"))
+                    .build(),
+            )
+            .build();
+        props.own.remove(PROP_WIDTHS);
+        assert_eq!(component.update(props), Msg::None);
+        assert_eq!(component.states.list_index, 0);
+        assert_eq!(component.states.col_offset, 1);
+        assert_eq!(
+            DataTable::widths(&component.props),
+            vec![ColumnWidth::Flex(1), ColumnWidth::Flex(1)]
+        );
+    }
+
+    #[test]
+    fn test_ui_components_data_table_layout() {
+        let columns: Vec<ColumnWidth> = vec![
+            ColumnWidth::Fixed(10),
+            ColumnWidth::Flex(1),
+            ColumnWidth::Flex(3),
+            ColumnWidth::Fixed(6),
+        ];
+        // 10 + 1 + flex + 1 + flex + 1 + 6 => flex = 41 - 19 = 21 (5 + 15)
+        assert_eq!(
+            DataTable::layout_columns(&columns, 0, 40),
+            vec![10, 5, 15, 6]
+        );
+        // Offset
+        assert_eq!(DataTable::layout_columns(&columns, 2, 20), vec![13, 6]);
+        assert!(DataTable::layout_columns(&columns, 4, 20).is_empty());
+        // Not enough space: last column is truncated
+        assert_eq!(
+            DataTable::layout_columns(&columns, 0, 14),
+            vec![10, 0, 0, 1]
+        );
+        assert_eq!(DataTable::layout_columns(&columns, 0, 8), vec![8]);
+    }
+}
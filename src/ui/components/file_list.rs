@@ -26,6 +26,8 @@
  * SOFTWARE.
  */
 // ext
+use std::cell::Cell;
+use std::time::{Duration, Instant};
 use tui_realm_stdlib::utils::get_block;
 use tuirealm::event::{Event, KeyCode, KeyModifiers};
 use tuirealm::props::{
@@ -42,6 +44,11 @@ use tuirealm::{Component, Frame, Msg, Payload, Value};
 // -- props
 
 const PROP_FILES: &str = "files";
+const PROP_NAMES: &str = "names";
+const PROP_SELECTED: &str = "selected";
+
+/// Type-ahead keystrokes older than this start a new prefix
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
 const PALETTE_HIGHLIGHT_COLOR: &str = "props-highlight-color";
 
 pub struct FileListPropsBuilder {
@@ -146,6 +153,30 @@ impl FileListPropsBuilder {
         }
         self
     }
+
+    /// ### with_names
+    ///
+    /// Set file names, used to jump to entries by typing their prefix
+    pub fn with_names(&mut self, names: Vec<String>) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            let names: Vec<PropValue> = names.into_iter().map(PropValue::Str).collect();
+            props.own.insert(PROP_NAMES, PropPayload::Vec(names));
+        }
+        self
+    }
+
+    /// ### with_selected
+    ///
+    /// Set the indexes of the files which are selected when files are loaded
+    pub fn with_selected(&mut self, selected: &[usize]) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_SELECTED,
+                PropPayload::Vec(selected.iter().map(|x| PropValue::Usize(*x)).collect()),
+            );
+        }
+        self
+    }
 }
 
 // -- states
@@ -155,9 +186,11 @@ impl FileListPropsBuilder {
 /// OwnStates contains states for this component
 #[derive(Clone)]
 struct OwnStates {
-    list_index: usize,    // Index of selected element in list
-    selected: Vec<usize>, // Selected files
-    focus: bool,          // Has focus?
+    list_index: usize,                     // Index of selected element in list
+    selected: Vec<usize>,                  // Selected files
+    focus: bool,                           // Has focus?
+    offset: Cell<usize>,                   // Index of the first rendered element; updated on render
+    type_ahead: Option<(String, Instant)>, // Type-ahead prefix and last keystroke time
 }
 
 impl Default for OwnStates {
@@ -166,6 +199,8 @@ impl Default for OwnStates {
             list_index: 0,
             selected: Vec::new(),
             focus: false,
+            offset: Cell::new(0),
+            type_ahead: None,
         }
     }
 }
@@ -234,6 +269,28 @@ impl OwnStates {
         self.selected.clone()
     }
 
+    /// ### window
+    ///
+    /// Returns the range of entries to render in a list with `height` rows.
+    /// The previous window is kept as long as the current entry is visible, otherwise it is scrolled
+    /// just enough to show it
+    pub fn window(&self, height: usize) -> std::ops::Range<usize> {
+        if height == 0 {
+            return 0..0;
+        }
+        let len: usize = self.list_len();
+        let mut offset: usize = self.offset.get();
+        if self.list_index < offset {
+            offset = self.list_index;
+        } else if self.list_index >= offset + height {
+            offset = self.list_index + 1 - height;
+        }
+        // Don't leave empty rows at the end of the list
+        offset = offset.min(len.saturating_sub(height));
+        self.offset.set(offset);
+        offset..(offset + height).min(len)
+    }
+
     /// ### fix_list_index
     ///
     /// Keep index if possible, otherwise set to lenght - 1
@@ -305,7 +362,24 @@ impl FileList {
         let mut states: OwnStates = OwnStates::default();
         // Init list states
         states.init_list_states(Self::files_len(&props));
-        FileList { props, states }
+        let mut component = FileList { props, states };
+        component.restore_selection();
+        component
+    }
+
+    /// ### restore_selection
+    ///
+    /// Select the files set in `PROP_SELECTED`
+    fn restore_selection(&mut self) {
+        if let Some(PropPayload::Vec(selected)) = self.props.own.get(PROP_SELECTED) {
+            let len: usize = self.states.list_len();
+            for idx in selected.iter().filter_map(|x| match x {
+                PropValue::Usize(i) if *i < len => Some(*i),
+                _ => None,
+            }) {
+                self.states.select(idx);
+            }
+        }
     }
 
     fn files_len(props: &Props) -> usize {
@@ -314,18 +388,92 @@ impl FileList {
             Some(files) => files.unwrap_vec().len(),
         }
     }
+
+    /// ### type_ahead
+    ///
+    /// Append `ch` to type-ahead prefix and jump to the first entry starting with it.
+    /// If the last keystroke is older than `TYPE_AHEAD_TIMEOUT`, a new prefix is started
+    fn type_ahead(&mut self, ch: char) {
+        let mut prefix: String = match self.states.type_ahead.take() {
+            Some((prefix, last)) if last.elapsed() < TYPE_AHEAD_TIMEOUT => prefix,
+            _ => String::new(),
+        };
+        prefix.push(ch);
+        self.jump_to_prefix(prefix.as_str());
+        self.states.type_ahead = Some((prefix, Instant::now()));
+    }
+
+    /// ### jump_to_prefix
+    ///
+    /// Move to the first entry whose name starts with `prefix` (case insensitive)
+    fn jump_to_prefix(&mut self, prefix: &str) {
+        let prefix: String = prefix.to_lowercase();
+        if let Some(PropPayload::Vec(names)) = self.props.own.get(PROP_NAMES) {
+            if let Some(idx) = names
+                .iter()
+                .position(|x| x.unwrap_str().to_lowercase().starts_with(prefix.as_str()))
+            {
+                self.states.list_index = idx;
+            }
+        }
+    }
+
+    /// ### on_type_ahead
+    ///
+    /// Handle key while in type-ahead mode.
+    /// Returns whether the key has been consumed
+    fn on_type_ahead(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match code {
+            KeyCode::Char(ch)
+                if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.type_ahead(ch);
+                true
+            }
+            KeyCode::Backspace => {
+                if let Some((prefix, last)) = self.states.type_ahead.as_mut() {
+                    prefix.pop();
+                    *last = Instant::now();
+                }
+                true
+            }
+            KeyCode::Esc => {
+                self.states.type_ahead = None;
+                true
+            }
+            _ => {
+                // Leave type-ahead mode; key is handled as usual
+                self.states.type_ahead = None;
+                false
+            }
+        }
+    }
 }
 
 impl Component for FileList {
     #[cfg(not(tarpaulin_include))]
     fn render(&self, render: &mut Frame, area: Rect) {
         if self.props.visible {
+            // Show type-ahead prefix in title
+            let title: Option<BlockTitle> =
+                match (self.props.title.as_ref(), self.states.type_ahead.as_ref()) {
+                    (Some(title), Some((prefix, _))) => Some(BlockTitle::new(
+                        format!("{}/{}", title.text(), prefix),
+                        title.alignment(),
+                    )),
+                    (title, _) => title.cloned(),
+                };
+            let block = get_block(&self.props.borders, title.as_ref(), self.states.focus);
+            // Only make items for the visible entries
+            let window = self.states.window(block.inner(area).height as usize);
+            let offset: usize = window.start;
             // Make list
             let list_item: Vec<ListItem> = match self.props.own.get(PROP_FILES) {
                 Some(PropPayload::Vec(lines)) => lines
                     .iter()
-                    .enumerate()
-                    .map(|(num, line)| {
+                    .skip(offset)
+                    .zip(window)
+                    .map(|(line, num)| {
                         let to_display: String = match self.states.is_selected(num) {
                             true => format!("*{}", line.unwrap_str()),
                             false => line.unwrap_str().to_string(),
@@ -345,14 +493,10 @@ impl Component for FileList {
             };
             // Render
             let mut state: ListState = ListState::default();
-            state.select(Some(self.states.list_index));
+            state.select(Some(self.states.list_index - offset));
             render.render_stateful_widget(
                 List::new(list_item)
-                    .block(get_block(
-                        &self.props.borders,
-                        self.props.title.as_ref(),
-                        self.states.focus,
-                    ))
+                    .block(block)
                     .start_corner(Corner::TopLeft)
                     .style(
                         Style::default()
@@ -375,6 +519,7 @@ impl Component for FileList {
         self.props = props;
         // re-Set list states
         self.states.init_list_states(Self::files_len(&self.props));
+        self.restore_selection();
         Msg::None
     }
 
@@ -385,6 +530,9 @@ impl Component for FileList {
     fn on(&mut self, ev: Event) -> Msg {
         // Match event
         if let Event::Key(key) = ev {
+            if self.states.type_ahead.is_some() && self.on_type_ahead(key.code, key.modifiers) {
+                return Msg::None;
+            }
             match key.code {
                 KeyCode::Down => {
                     // Update states
@@ -413,18 +561,23 @@ impl Component for FileList {
                 KeyCode::Char('a') => match key.modifiers.intersects(KeyModifiers::CONTROL) {
                     // CTRL+A
                     true => {
-                        // Select all
+                        // Select all; report to activity, which keeps track of marks
                         self.states.select_all();
-                        Msg::None
+                        Msg::OnKey(key)
                     }
                     false => Msg::OnKey(key),
                 },
                 KeyCode::Char('m') => {
-                    // Toggle current file in selection
+                    // Toggle current file in selection; report to activity, which keeps track of marks
                     self.states.toggle_file(self.states.list_index());
-                    Msg::None
+                    Msg::OnKey(key)
                 }
                 KeyCode::Enter => Msg::OnSubmit(self.get_state()),
+                KeyCode::Char('/') if key.modifiers.is_empty() => {
+                    // Enter type-ahead mode
+                    self.states.type_ahead = Some((String::new(), Instant::now()));
+                    Msg::None
+                }
                 _ => {
                     // Return key event to activity
                     Msg::OnKey(key)
@@ -545,6 +698,34 @@ mod tests {
         assert_eq!(states.focus, true);
     }
 
+    #[test]
+    fn test_ui_components_file_list_window() {
+        let mut states: OwnStates = OwnStates::default();
+        assert_eq!(states.window(10), 0..0);
+        states.init_list_states(50_000);
+        assert_eq!(states.window(0), 0..0);
+        assert_eq!(states.window(40), 0..40);
+        // Moving inside the window keeps it
+        states.list_index = 39;
+        assert_eq!(states.window(40), 0..40);
+        // Scroll down
+        states.list_index = 40;
+        assert_eq!(states.window(40), 1..41);
+        states.list_index = 30_000;
+        assert_eq!(states.window(40), 29_961..30_001);
+        // Scroll up
+        states.list_index = 29_000;
+        assert_eq!(states.window(40), 29_000..29_040);
+        // Window shrinks after the list has been updated
+        states.init_list_states(29_010);
+        assert_eq!(states.list_index(), 29_000);
+        assert_eq!(states.window(40), 28_970..29_010);
+        // List shorter than area
+        states.init_list_states(10);
+        assert_eq!(states.list_index(), 9);
+        assert_eq!(states.window(40), 0..10);
+    }
+
     #[test]
     fn test_ui_components_file_list() {
         // Make component
@@ -671,7 +852,7 @@ mod tests {
                 KeyCode::Char('a'),
                 KeyModifiers::CONTROL
             ))),
-            Msg::None
+            Msg::OnKey(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL))
         );
         assert_eq!(component.states.selected.len(), component.states.list_len());
     }
@@ -693,14 +874,14 @@ mod tests {
         // Select one
         assert_eq!(
             component.on(Event::Key(KeyEvent::from(KeyCode::Char('m')))),
-            Msg::None
+            Msg::OnKey(KeyEvent::from(KeyCode::Char('m')))
         );
         // Now should be a vec
         assert_eq!(component.get_state(), Payload::Vec(vec![Value::Usize(0)]));
         // De-select
         assert_eq!(
             component.on(Event::Key(KeyEvent::from(KeyCode::Char('m')))),
-            Msg::None
+            Msg::OnKey(KeyEvent::from(KeyCode::Char('m')))
         );
         assert_eq!(component.get_state(), Payload::One(Value::Usize(0)));
         // Go down
@@ -711,7 +892,7 @@ mod tests {
         // Select
         assert_eq!(
             component.on(Event::Key(KeyEvent::from(KeyCode::Char('m')))),
-            Msg::None
+            Msg::OnKey(KeyEvent::from(KeyCode::Char('m')))
         );
         assert_eq!(component.get_state(), Payload::Vec(vec![Value::Usize(1)]));
         // Go down and select
@@ -721,7 +902,7 @@ mod tests {
         );
         assert_eq!(
             component.on(Event::Key(KeyEvent::from(KeyCode::Char('m')))),
-            Msg::None
+            Msg::OnKey(KeyEvent::from(KeyCode::Char('m')))
         );
         assert_eq!(
             component.get_state(),
@@ -733,7 +914,10 @@ mod tests {
                 code: KeyCode::Char('a'),
                 modifiers: KeyModifiers::CONTROL,
             })),
-            Msg::None
+            Msg::OnKey(KeyEvent {
+                code: KeyCode::Char('a'),
+                modifiers: KeyModifiers::CONTROL,
+            })
         );
         // All selected
         assert_eq!(
@@ -748,5 +932,85 @@ mod tests {
         );
         // Selection should now be empty
         assert_eq!(component.get_state(), Payload::One(Value::Usize(1)));
+        // Update files with selection; out of range indexes are ignored
+        component.update(
+            FileListPropsBuilder::from(component.get_props())
+                .with_files(vec![
+                    String::from("file1"),
+                    String::from("file2"),
+                    String::from("file3"),
+                ])
+                .with_selected(&[0, 2, 5])
+                .build(),
+        );
+        assert_eq!(
+            component.get_state(),
+            Payload::Vec(vec![Value::Usize(0), Value::Usize(2)])
+        );
+    }
+
+    #[test]
+    fn test_ui_components_file_list_type_ahead() {
+        let files: Vec<String> = vec![
+            String::from("Desktop"),
+            String::from("Documents"),
+            String::from("Downloads"),
+            String::from("Music"),
+        ];
+        let mut component: FileList = FileList::new(
+            FileListPropsBuilder::default()
+                .with_files(files.clone())
+                .with_names(files)
+                .build(),
+        );
+        // Letters are not consumed outside of type-ahead mode
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char('d')))),
+            Msg::OnKey(KeyEvent::from(KeyCode::Char('d')))
+        );
+        // Enter type-ahead mode
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char('/')))),
+            Msg::None
+        );
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char('m')))),
+            Msg::None
+        );
+        assert_eq!(component.states.list_index(), 3);
+        // Timeout expired; start a new prefix
+        component.states.type_ahead.as_mut().unwrap().1 =
+            Instant::now().checked_sub(TYPE_AHEAD_TIMEOUT).unwrap();
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('D'))));
+        assert_eq!(component.states.list_index(), 0);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('o'))));
+        assert_eq!(component.states.list_index(), 1);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('w'))));
+        assert_eq!(component.states.list_index(), 2);
+        // No match; stay
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('x'))));
+        assert_eq!(component.states.list_index(), 2);
+        assert_eq!(
+            component.states.type_ahead.as_ref().unwrap().0.as_str(),
+            "Dowx"
+        );
+        component.on(Event::Key(KeyEvent::from(KeyCode::Backspace)));
+        assert_eq!(
+            component.states.type_ahead.as_ref().unwrap().0.as_str(),
+            "Dow"
+        );
+        // Esc leaves type-ahead mode
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Esc))),
+            Msg::None
+        );
+        assert!(component.states.type_ahead.is_none());
+        // Other keys leave type-ahead mode and are handled as usual
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('/'))));
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::OnSubmit(Payload::One(Value::Usize(2)))
+        );
+        assert!(component.states.type_ahead.is_none());
     }
 }
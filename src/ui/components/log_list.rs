@@ -1,6 +1,6 @@
-//! ## LogBox
+//! ## LogList
 //!
-//! `LogBox` component renders a log box view
+//! `LogList` component renders the log records and allows to search through them
 
 /**
  * MIT License
@@ -27,7 +27,7 @@
  */
 // ext
 use tui_realm_stdlib::utils::{get_block, wrap_spans};
-use tuirealm::event::{Event, KeyCode};
+use tuirealm::event::{Event, KeyCode, KeyModifiers};
 use tuirealm::props::{
     Alignment, BlockTitle, BordersProps, Props, PropsBuilder, Table as TextTable,
 };
@@ -41,20 +41,21 @@ use tuirealm::{Component, Frame, Msg, Payload, PropPayload, PropValue, Value};
 // -- props
 
 const PROP_TABLE: &str = "table";
+const PROP_ERRORS: &str = "errors";
 
-pub struct LogboxPropsBuilder {
+pub struct LogListPropsBuilder {
     props: Option<Props>,
 }
 
-impl Default for LogboxPropsBuilder {
+impl Default for LogListPropsBuilder {
     fn default() -> Self {
-        LogboxPropsBuilder {
+        LogListPropsBuilder {
             props: Some(Props::default()),
         }
     }
 }
 
-impl PropsBuilder for LogboxPropsBuilder {
+impl PropsBuilder for LogListPropsBuilder {
     fn build(&mut self) -> Props {
         self.props.take().unwrap()
     }
@@ -74,13 +75,13 @@ impl PropsBuilder for LogboxPropsBuilder {
     }
 }
 
-impl From<Props> for LogboxPropsBuilder {
+impl From<Props> for LogListPropsBuilder {
     fn from(props: Props) -> Self {
-        LogboxPropsBuilder { props: Some(props) }
+        LogListPropsBuilder { props: Some(props) }
     }
 }
 
-impl LogboxPropsBuilder {
+impl LogListPropsBuilder {
     /// ### with_borders
     ///
     /// Set component borders style
@@ -117,11 +118,19 @@ impl LogboxPropsBuilder {
         self
     }
 
-    pub fn with_log(&mut self, table: TextTable) -> &mut Self {
+    /// ### with_log
+    ///
+    /// Set log records; the first row is the most recent one.
+    /// `errors` contains the indexes of the rows reporting an error
+    pub fn with_log(&mut self, table: TextTable, errors: &[usize]) -> &mut Self {
         if let Some(props) = self.props.as_mut() {
             props
                 .own
                 .insert(PROP_TABLE, PropPayload::One(PropValue::Table(table)));
+            props.own.insert(
+                PROP_ERRORS,
+                PropPayload::Vec(errors.iter().map(|x| PropValue::Usize(*x)).collect()),
+            );
         }
         self
     }
@@ -132,21 +141,13 @@ impl LogboxPropsBuilder {
 /// ## OwnStates
 ///
 /// OwnStates contains states for this component
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct OwnStates {
-    list_index: usize, // Index of selected element in list
-    list_len: usize,   // Length of file list
-    focus: bool,       // Has focus?
-}
-
-impl Default for OwnStates {
-    fn default() -> Self {
-        OwnStates {
-            list_index: 0,
-            list_len: 0,
-            focus: false,
-        }
-    }
+    list_index: usize,      // Index of selected element in list
+    list_len: usize,        // Length of file list
+    focus: bool,            // Has focus?
+    search: Option<String>, // Search being typed
+    query: Option<String>,  // Last submitted search
 }
 
 impl OwnStates {
@@ -194,18 +195,23 @@ impl OwnStates {
 
 // -- Component
 
-/// ## LogBox
+/// ## LogList
 ///
-/// LogBox list component
-pub struct LogBox {
+/// LogList list component.
+/// Besides scrolling, it supports:
+///
+/// - `/`: search records; `<ENTER>` submits the search, `<ESC>` cancels it
+/// - `n`: go to the next record matching the last search
+/// - `e`: go to the next error
+pub struct LogList {
     props: Props,
     states: OwnStates,
 }
 
-impl LogBox {
+impl LogList {
     /// ### new
     ///
-    /// Instantiates a new FileList starting from Props
+    /// Instantiates a new LogList starting from Props
     /// The method also initializes the component states.
     pub fn new(props: Props) -> Self {
         // Initialize states
@@ -214,7 +220,7 @@ impl LogBox {
         states.set_list_len(Self::table_len(&props));
         // Reset list index
         states.reset_list_index();
-        LogBox { props, states }
+        LogList { props, states }
     }
 
     fn table_len(props: &Props) -> usize {
@@ -223,9 +229,105 @@ impl LogBox {
             _ => 0,
         }
     }
+
+    /// ### errors
+    ///
+    /// Get indexes of rows reporting an error
+    fn errors(&self) -> Vec<usize> {
+        match self.props.own.get(PROP_ERRORS) {
+            Some(PropPayload::Vec(errors)) => errors
+                .iter()
+                .filter_map(|x| match x {
+                    PropValue::Usize(i) => Some(*i),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// ### row_matches
+    ///
+    /// Returns whether row at `idx` contains `query` (case insensitive)
+    fn row_matches(&self, idx: usize, query: &str) -> bool {
+        match self.props.own.get(PROP_TABLE) {
+            Some(PropPayload::One(PropValue::Table(table))) => match table.get(idx) {
+                Some(row) => row
+                    .iter()
+                    .map(|x| x.content.as_str())
+                    .collect::<String>()
+                    .to_lowercase()
+                    .contains(query.to_lowercase().as_str()),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// ### find_next
+    ///
+    /// Find the next row, starting from the one after the current one and wrapping around,
+    /// which satisfies `predicate`; if any, select it
+    fn find_next<F>(&mut self, predicate: F)
+    where
+        F: Fn(&Self, usize) -> bool,
+    {
+        let len: usize = self.states.list_len;
+        if let Some(idx) = (1..=len)
+            .map(|x| (self.states.list_index + x) % len)
+            .find(|x| predicate(self, *x))
+        {
+            self.states.list_index = idx;
+        }
+    }
+
+    /// ### next_match
+    ///
+    /// Go to the next row matching the last submitted search
+    fn next_match(&mut self) {
+        if let Some(query) = self.states.query.clone() {
+            self.find_next(|list, idx| list.row_matches(idx, query.as_str()));
+        }
+    }
+
+    /// ### next_error
+    ///
+    /// Go to the next error
+    fn next_error(&mut self) {
+        let errors: Vec<usize> = self.errors();
+        self.find_next(|_, idx| errors.contains(&idx));
+    }
+
+    /// ### on_search
+    ///
+    /// Handle key while typing a search
+    fn on_search(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc => {
+                self.states.search = None;
+            }
+            KeyCode::Enter => {
+                self.states.query = self.states.search.take().filter(|x| !x.is_empty());
+                self.next_match();
+            }
+            KeyCode::Backspace => {
+                if let Some(search) = self.states.search.as_mut() {
+                    search.pop();
+                }
+            }
+            KeyCode::Char(ch)
+                if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                if let Some(search) = self.states.search.as_mut() {
+                    search.push(ch);
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
-impl Component for LogBox {
+impl Component for LogList {
     #[cfg(not(tarpaulin_include))]
     fn render(&self, render: &mut Frame, area: Rect) {
         if self.props.visible {
@@ -238,10 +340,18 @@ impl Component for LogBox {
                     .collect(), // Make List item from TextSpan
                 _ => Vec::new(),
             };
+            // Show search in title
+            let title: Option<BlockTitle> = match (self.props.title.as_ref(), &self.states.search) {
+                (Some(title), Some(search)) => Some(BlockTitle::new(
+                    format!("{} /{}", title.text(), search),
+                    title.alignment(),
+                )),
+                (title, _) => title.cloned(),
+            };
             let w = List::new(list_items)
                 .block(get_block(
                     &self.props.borders,
-                    self.props.title.as_ref(),
+                    title.as_ref(),
                     self.states.focus,
                 ))
                 .start_corner(Corner::BottomLeft)
@@ -270,6 +380,11 @@ impl Component for LogBox {
     fn on(&mut self, ev: Event) -> Msg {
         // Match event
         if let Event::Key(key) = ev {
+            // While searching, keys are consumed by search
+            if self.states.search.is_some() {
+                self.on_search(key.code, key.modifiers);
+                return Msg::None;
+            }
             match key.code {
                 KeyCode::Up => {
                     // Update states
@@ -295,6 +410,18 @@ impl Component for LogBox {
                     }
                     Msg::None
                 }
+                KeyCode::Char('/') if key.modifiers.is_empty() => {
+                    self.states.search = Some(String::new());
+                    Msg::None
+                }
+                KeyCode::Char('n') if key.modifiers.is_empty() => {
+                    self.next_match();
+                    Msg::None
+                }
+                KeyCode::Char('e') if key.modifiers.is_empty() => {
+                    self.next_error();
+                    Msg::None
+                }
                 _ => {
                     // Return key event to activity
                     Msg::OnKey(key)
@@ -330,9 +457,9 @@ mod tests {
     use tuirealm::tui::style::Color;
 
     #[test]
-    fn test_ui_components_logbox() {
-        let mut component: LogBox = LogBox::new(
-            LogboxPropsBuilder::default()
+    fn test_ui_components_log_list() {
+        let mut component: LogList = LogList::new(
+            LogListPropsBuilder::default()
                 .hidden()
                 .visible()
                 .with_borders(Borders::ALL, BorderType::Double, Color::Red)
@@ -346,6 +473,7 @@ mod tests {
                         .add_col(TextSpan::from("12:38"))
                         .add_col(TextSpan::from("system alive"))
                         .build(),
+                    &[0],
                 )
                 .build(),
         );
@@ -362,7 +490,7 @@ mod tests {
         component.blur();
         assert_eq!(component.states.focus, false);
         // Update
-        let props = LogboxPropsBuilder::from(component.get_props())
+        let props = LogListPropsBuilder::from(component.get_props())
             .hidden()
             .build();
         assert_eq!(component.update(props), Msg::None);
@@ -372,7 +500,7 @@ mod tests {
         assert_eq!(component.states.list_index, 1);
         // Update
         component.update(
-            LogboxPropsBuilder::from(component.get_props())
+            LogListPropsBuilder::from(component.get_props())
                 .with_log(
                     TableBuilder::default()
                         .add_col(TextSpan::from("12:29"))
@@ -384,6 +512,7 @@ mod tests {
                         .add_col(TextSpan::from("12:41"))
                         .add_col(TextSpan::from("system is going down for REBOOT"))
                         .build(),
+                    &[0],
                 )
                 .build(),
         );
@@ -429,5 +558,50 @@ mod tests {
             component.on(Event::Key(KeyEvent::from(KeyCode::Backspace))),
             Msg::OnKey(KeyEvent::from(KeyCode::Backspace))
         );
+        // Jump to error
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char('e')))),
+            Msg::None
+        );
+        assert_eq!(component.states.list_index, 0);
+        component.states.list_index = 2;
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('e'))));
+        assert_eq!(component.states.list_index, 0);
+        // Search
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char('/')))),
+            Msg::None
+        );
+        for ch in "ALIVX".chars() {
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char(ch))));
+        }
+        assert_eq!(component.states.search.as_deref(), Some("ALIVX"));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Backspace)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('e'))));
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::None
+        );
+        assert_eq!(component.states.search, None);
+        assert_eq!(component.states.query.as_deref(), Some("ALIVe"));
+        assert_eq!(component.states.list_index, 1);
+        // Next match; there's only one
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('n'))));
+        assert_eq!(component.states.list_index, 1);
+        // Search for "system"
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('/'))));
+        for ch in "system".chars() {
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char(ch))));
+        }
+        component.on(Event::Key(KeyEvent::from(KeyCode::Enter)));
+        assert_eq!(component.states.list_index, 2);
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('n'))));
+        assert_eq!(component.states.list_index, 0);
+        // Cancel search
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('/'))));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('x'))));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Esc)));
+        assert_eq!(component.states.search, None);
+        assert_eq!(component.states.query.as_deref(), Some("system"));
     }
 }
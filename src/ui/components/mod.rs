@@ -27,6 +27,14 @@
  */
 // exports
 pub mod bookmark_list;
+pub mod color_palette;
 pub mod color_picker;
+pub mod data_table;
 pub mod file_list;
-pub mod logbox;
+pub mod log_list;
+pub mod rules_editor;
+pub mod spinner;
+pub mod text_input;
+pub mod theme_preview;
+pub mod toast;
+pub mod transfer_progress;
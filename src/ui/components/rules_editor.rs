@@ -0,0 +1,628 @@
+//! ## RulesEditor
+//!
+//! `RulesEditor` component edits an ordered list of include/exclude rules and tests them against a sample
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use crate::fs::filter::{FilterRule, TransferFilter};
+// ext
+use std::str::FromStr;
+use tui_realm_stdlib::utils::get_block;
+use tuirealm::event::{Event, KeyCode, KeyModifiers};
+use tuirealm::props::{Alignment, BlockTitle, BordersProps, Props, PropsBuilder};
+use tuirealm::tui::{
+    layout::{Constraint, Corner, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph},
+};
+use tuirealm::{Component, Frame, Msg, Payload, PropPayload, PropValue, Value};
+
+// -- props
+
+const PROP_RULES: &str = "rules";
+
+pub struct RulesEditorPropsBuilder {
+    props: Option<Props>,
+}
+
+impl Default for RulesEditorPropsBuilder {
+    fn default() -> Self {
+        RulesEditorPropsBuilder {
+            props: Some(Props::default()),
+        }
+    }
+}
+
+impl PropsBuilder for RulesEditorPropsBuilder {
+    fn build(&mut self) -> Props {
+        self.props.take().unwrap()
+    }
+
+    fn hidden(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = false;
+        }
+        self
+    }
+
+    fn visible(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = true;
+        }
+        self
+    }
+}
+
+impl From<Props> for RulesEditorPropsBuilder {
+    fn from(props: Props) -> Self {
+        RulesEditorPropsBuilder { props: Some(props) }
+    }
+}
+
+impl RulesEditorPropsBuilder {
+    /// ### with_foreground
+    ///
+    /// Set highlight color for the focused field and the selected rule
+    pub fn with_foreground(&mut self, color: Color) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.foreground = color;
+        }
+        self
+    }
+
+    /// ### with_borders
+    ///
+    /// Set component borders style
+    pub fn with_borders(
+        &mut self,
+        borders: Borders,
+        variant: BorderType,
+        color: Color,
+    ) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.borders = BordersProps {
+                borders,
+                variant,
+                color,
+            }
+        }
+        self
+    }
+
+    pub fn with_title<S: AsRef<str>>(&mut self, text: S, alignment: Alignment) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.title = Some(BlockTitle::new(text, alignment));
+        }
+        self
+    }
+
+    /// ### with_rules
+    ///
+    /// Set rules to edit; rules are written as `+ GLOB` or `- GLOB`
+    pub fn with_rules(&mut self, rules: &[String]) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_RULES,
+                PropPayload::Vec(rules.iter().cloned().map(PropValue::Str).collect()),
+            );
+        }
+        self
+    }
+}
+
+// -- states
+
+/// ## Field
+///
+/// Input field being edited
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+enum Field {
+    #[default]
+    Rule,
+    Sample,
+}
+
+/// ## OwnStates
+///
+/// OwnStates contains states for this component
+#[derive(Clone, Default)]
+struct OwnStates {
+    rules: Vec<FilterRule>, // Rules being edited
+    list_index: usize,      // Selected rule
+    rule: String,           // Rule being typed
+    sample: String,         // Sample to test rules against
+    field: Field,           // Field being edited
+    error: Option<String>,  // Error for last typed rule
+    focus: bool,            // Has focus?
+}
+
+impl OwnStates {
+    /// ### set_rules
+    ///
+    /// Set rules; invalid rules are discarded
+    pub fn set_rules(&mut self, rules: &[String]) {
+        self.rules = rules
+            .iter()
+            .filter_map(|x| FilterRule::from_str(x.as_str()).ok())
+            .collect();
+        self.list_index = 0;
+    }
+
+    /// ### incr_list_index
+    ///
+    /// Incremenet list index
+    pub fn incr_list_index(&mut self) {
+        if self.list_index + 1 < self.rules.len() {
+            self.list_index += 1;
+        }
+    }
+
+    /// ### decr_list_index
+    ///
+    /// Decrement list index
+    pub fn decr_list_index(&mut self) {
+        if self.list_index > 0 {
+            self.list_index -= 1;
+        }
+    }
+
+    /// ### move_rule_up
+    ///
+    /// Move selected rule one position up
+    pub fn move_rule_up(&mut self) {
+        if self.list_index > 0 && self.list_index < self.rules.len() {
+            self.rules.swap(self.list_index, self.list_index - 1);
+            self.list_index -= 1;
+        }
+    }
+
+    /// ### move_rule_down
+    ///
+    /// Move selected rule one position down
+    pub fn move_rule_down(&mut self) {
+        if self.list_index + 1 < self.rules.len() {
+            self.rules.swap(self.list_index, self.list_index + 1);
+            self.list_index += 1;
+        }
+    }
+
+    /// ### del_rule
+    ///
+    /// Delete selected rule
+    pub fn del_rule(&mut self) {
+        if self.list_index < self.rules.len() {
+            self.rules.remove(self.list_index);
+            if self.list_index > 0 && self.list_index >= self.rules.len() {
+                self.list_index -= 1;
+            }
+        }
+    }
+
+    /// ### push_rule
+    ///
+    /// Parse typed rule and add it after the selected one
+    pub fn push_rule(&mut self) {
+        match FilterRule::from_str(self.rule.as_str()) {
+            Ok(rule) => {
+                let idx: usize = match self.rules.is_empty() {
+                    true => 0,
+                    false => self.list_index + 1,
+                };
+                self.rules.insert(idx, rule);
+                self.list_index = idx;
+                self.rule.clear();
+                self.error = None;
+            }
+            Err(err) => {
+                self.error = Some(err);
+            }
+        }
+    }
+
+    /// ### buffer_mut
+    ///
+    /// Get the buffer of the field being edited
+    pub fn buffer_mut(&mut self) -> &mut String {
+        self.error = None;
+        match self.field {
+            Field::Rule => &mut self.rule,
+            Field::Sample => &mut self.sample,
+        }
+    }
+
+    /// ### preview
+    ///
+    /// Test sample against rules. Samples ending with `/` are considered directories.
+    /// Returns whether sample would be transferred and the index of the matching rule
+    pub fn preview(&self) -> Option<(bool, Option<usize>)> {
+        if self.sample.is_empty() {
+            return None;
+        }
+        let (name, is_dir): (&str, bool) = match self.sample.strip_suffix('/') {
+            Some(name) => (name, true),
+            None => (self.sample.as_str(), false),
+        };
+        let filter: TransferFilter = TransferFilter::new(self.rules.clone());
+        Some((
+            filter.is_included(name, is_dir),
+            filter.matching_rule(name, is_dir),
+        ))
+    }
+}
+
+// -- Component
+
+/// ## RulesEditor
+///
+/// Editor for ordered include/exclude rules:
+///
+/// - `<UP>`/`<DOWN>`: select rule
+/// - `<PGUP>`/`<PGDOWN>`: move selected rule
+/// - `<DEL>`: delete selected rule
+/// - `<TAB>`: switch between the rule input and the sample input
+/// - `<ENTER>`: add typed rule; if no rule is being typed, submit rules
+pub struct RulesEditor {
+    props: Props,
+    states: OwnStates,
+}
+
+impl RulesEditor {
+    /// ### new
+    ///
+    /// Instantiates a new RulesEditor starting from Props
+    /// The method also initializes the component states.
+    pub fn new(props: Props) -> Self {
+        let mut states: OwnStates = OwnStates::default();
+        states.set_rules(Self::rules(&props).as_slice());
+        RulesEditor { props, states }
+    }
+
+    fn rules(props: &Props) -> Vec<String> {
+        match props.own.get(PROP_RULES) {
+            Some(PropPayload::Vec(rules)) => rules
+                .iter()
+                .filter_map(|x| match x {
+                    PropValue::Str(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// ### field_block
+    ///
+    /// Make block for input field
+    #[cfg(not(tarpaulin_include))]
+    fn field_block(&self, title: &str, field: Field) -> Block<'_> {
+        let color: Color = match self.states.focus && self.states.field == field {
+            true => self.props.foreground,
+            false => Color::Reset,
+        };
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(color))
+            .title(title.to_string())
+    }
+}
+
+impl Component for RulesEditor {
+    #[cfg(not(tarpaulin_include))]
+    fn render(&self, render: &mut Frame, area: Rect) {
+        if self.props.visible {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Min(3),
+                        Constraint::Length(3),
+                        Constraint::Length(3),
+                    ]
+                    .as_ref(),
+                )
+                .split(area);
+            // Rules
+            let list_items: Vec<ListItem> = self
+                .states
+                .rules
+                .iter()
+                .enumerate()
+                .map(|(i, rule)| {
+                    let color: Color = match rule.is_include() {
+                        true => Color::LightGreen,
+                        false => Color::LightRed,
+                    };
+                    ListItem::new(Spans::from(vec![
+                        Span::raw(format!("{:>2}. ", i + 1)),
+                        Span::styled(rule.to_string(), Style::default().fg(color)),
+                    ]))
+                })
+                .collect();
+            let mut state: ListState = ListState::default();
+            if !self.states.rules.is_empty() {
+                state.select(Some(self.states.list_index));
+            }
+            render.render_stateful_widget(
+                List::new(list_items)
+                    .block(get_block(
+                        &self.props.borders,
+                        self.props.title.as_ref(),
+                        self.states.focus,
+                    ))
+                    .start_corner(Corner::TopLeft)
+                    .highlight_symbol(">> ")
+                    .highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+                chunks[0],
+                &mut state,
+            );
+            // Rule input
+            let rule: Spans = match &self.states.error {
+                Some(err) => Spans::from(vec![
+                    Span::raw(self.states.rule.clone()),
+                    Span::styled(format!("  ({})", err), Style::default().fg(Color::Red)),
+                ]),
+                None if self.states.rule.is_empty() => Spans::from(Span::styled(
+                    "+ GLOB to include, - GLOB to exclude; <ENTER> on empty rule saves",
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::ITALIC),
+                )),
+                None => Spans::from(Span::raw(self.states.rule.clone())),
+            };
+            render.render_widget(
+                Paragraph::new(rule).block(self.field_block("Rule", Field::Rule)),
+                chunks[1],
+            );
+            // Sample and preview
+            let preview: Span = match self.states.preview() {
+                None => Span::styled(
+                    "type a file name (dirs end with '/') to test rules",
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::ITALIC),
+                ),
+                Some((true, Some(idx))) => Span::styled(
+                    format!("  => included by rule {}", idx + 1),
+                    Style::default().fg(Color::LightGreen),
+                ),
+                Some((true, None)) => Span::styled(
+                    "  => included (no matching rule)",
+                    Style::default().fg(Color::LightGreen),
+                ),
+                Some((false, idx)) => Span::styled(
+                    format!("  => excluded by rule {}", idx.unwrap_or(0) + 1),
+                    Style::default().fg(Color::LightRed),
+                ),
+            };
+            render.render_widget(
+                Paragraph::new(Spans::from(vec![
+                    Span::raw(self.states.sample.clone()),
+                    preview,
+                ]))
+                .block(self.field_block("Test", Field::Sample)),
+                chunks[2],
+            );
+        }
+    }
+
+    fn update(&mut self, props: Props) -> Msg {
+        self.props = props;
+        self.states.set_rules(Self::rules(&self.props).as_slice());
+        Msg::None
+    }
+
+    fn get_props(&self) -> Props {
+        self.props.clone()
+    }
+
+    fn on(&mut self, ev: Event) -> Msg {
+        if let Event::Key(key) = ev {
+            match key.code {
+                KeyCode::Up => {
+                    self.states.decr_list_index();
+                    Msg::None
+                }
+                KeyCode::Down => {
+                    self.states.incr_list_index();
+                    Msg::None
+                }
+                KeyCode::PageUp => {
+                    self.states.move_rule_up();
+                    Msg::None
+                }
+                KeyCode::PageDown => {
+                    self.states.move_rule_down();
+                    Msg::None
+                }
+                KeyCode::Delete => {
+                    self.states.del_rule();
+                    Msg::None
+                }
+                KeyCode::Tab => {
+                    self.states.field = match self.states.field {
+                        Field::Rule => Field::Sample,
+                        Field::Sample => Field::Rule,
+                    };
+                    Msg::None
+                }
+                KeyCode::Backspace => {
+                    self.states.buffer_mut().pop();
+                    Msg::None
+                }
+                KeyCode::Enter
+                    if self.states.field == Field::Rule && !self.states.rule.is_empty() =>
+                {
+                    self.states.push_rule();
+                    Msg::None
+                }
+                KeyCode::Enter => Msg::OnSubmit(self.get_state()),
+                KeyCode::Char(ch)
+                    if !key
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    self.states.buffer_mut().push(ch);
+                    Msg::None
+                }
+                _ => Msg::OnKey(key),
+            }
+        } else {
+            Msg::None
+        }
+    }
+
+    /// ### get_state
+    ///
+    /// Returns rules as `+ GLOB`/`- GLOB` strings
+    fn get_state(&self) -> Payload {
+        Payload::Vec(
+            self.states
+                .rules
+                .iter()
+                .map(|x| Value::Str(x.to_string()))
+                .collect(),
+        )
+    }
+
+    fn blur(&mut self) {
+        self.states.focus = false;
+    }
+
+    fn active(&mut self) {
+        self.states.focus = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::event::KeyEvent;
+
+    fn type_str(component: &mut RulesEditor, s: &str) {
+        for ch in s.chars() {
+            assert_eq!(
+                component.on(Event::Key(KeyEvent::from(KeyCode::Char(ch)))),
+                Msg::None
+            );
+        }
+    }
+
+    #[test]
+    fn test_ui_components_rules_editor() {
+        let mut component: RulesEditor = RulesEditor::new(
+            RulesEditorPropsBuilder::default()
+                .hidden()
+                .visible()
+                .with_foreground(Color::Yellow)
+                .with_borders(Borders::ALL, BorderType::Double, Color::Yellow)
+                .with_title("Rules", Alignment::Left)
+                .with_rules(&[
+                    String::from("- *.log"),
+                    String::from("bad rule"),
+                    String::from("- target/"),
+                ])
+                .build(),
+        );
+        assert_eq!(component.props.visible, true);
+        assert_eq!(component.props.foreground, Color::Yellow);
+        assert_eq!(component.props.title.as_ref().unwrap().text(), "Rules");
+        // Invalid rules are discarded
+        assert_eq!(
+            component.get_state(),
+            Payload::Vec(vec![
+                Value::Str(String::from("- *.log")),
+                Value::Str(String::from("- target/")),
+            ])
+        );
+        // Add rule after the selected one
+        type_str(&mut component, "+ keep.log");
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::None
+        );
+        assert_eq!(component.states.list_index, 1);
+        assert!(component.states.rule.is_empty());
+        // Bad rule reports error
+        type_str(&mut component, "keep.log");
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::None
+        );
+        assert!(component.states.error.is_some());
+        for _ in 0..8 {
+            component.on(Event::Key(KeyEvent::from(KeyCode::Backspace)));
+        }
+        assert!(component.states.error.is_none());
+        // Move rule before exclude
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::PageUp))),
+            Msg::None
+        );
+        assert_eq!(component.states.list_index, 0);
+        // Test sample
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Tab))),
+            Msg::None
+        );
+        assert_eq!(component.states.preview(), None);
+        type_str(&mut component, "keep.log");
+        assert_eq!(component.states.preview(), Some((true, Some(0))));
+        component.states.sample = String::from("debug.log");
+        assert_eq!(component.states.preview(), Some((false, Some(1))));
+        component.states.sample = String::from("target/");
+        assert_eq!(component.states.preview(), Some((false, Some(2))));
+        component.states.sample = String::from("target");
+        assert_eq!(component.states.preview(), Some((true, None)));
+        // Delete last rule
+        component.on(Event::Key(KeyEvent::from(KeyCode::Down)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Down)));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Down)));
+        assert_eq!(component.states.list_index, 2);
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Delete))),
+            Msg::None
+        );
+        assert_eq!(component.states.list_index, 1);
+        // Submit
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::OnSubmit(Payload::Vec(vec![
+                Value::Str(String::from("+ keep.log")),
+                Value::Str(String::from("- *.log")),
+            ]))
+        );
+        // Other keys
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Esc))),
+            Msg::OnKey(KeyEvent::from(KeyCode::Esc))
+        );
+        assert_eq!(component.on(Event::Resize(0, 0)), Msg::None);
+    }
+}
@@ -0,0 +1,343 @@
+//! ## Spinner
+//!
+//! `Spinner` component renders an animated spinner followed by a text
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// ext
+use tui_realm_stdlib::utils::get_block;
+use tuirealm::event::Event;
+use tuirealm::props::{Alignment, BordersProps, Props, PropsBuilder};
+use tuirealm::tui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{BorderType, Borders, Paragraph},
+};
+use tuirealm::{Component, Frame, Msg, Payload, PropPayload, PropValue};
+
+use std::time::{Duration, Instant};
+
+// -- props
+
+const PROP_ALIGNMENT: &str = "alignment";
+const PROP_STILL: &str = "still";
+const PROP_TEXT: &str = "text";
+
+const FRAMES: [char; 8] = ['⣾', '⣽', '⣻', '⢿', '⡿', '⣟', '⣯', '⣷'];
+const FRAME_INTERVAL: u128 = 80; // ms
+
+pub struct SpinnerPropsBuilder {
+    props: Option<Props>,
+}
+
+impl Default for SpinnerPropsBuilder {
+    fn default() -> Self {
+        SpinnerPropsBuilder {
+            props: Some(Props::default()),
+        }
+    }
+}
+
+impl PropsBuilder for SpinnerPropsBuilder {
+    fn build(&mut self) -> Props {
+        self.props.take().unwrap()
+    }
+
+    fn hidden(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = false;
+        }
+        self
+    }
+
+    fn visible(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = true;
+        }
+        self
+    }
+}
+
+impl From<Props> for SpinnerPropsBuilder {
+    fn from(props: Props) -> Self {
+        SpinnerPropsBuilder { props: Some(props) }
+    }
+}
+
+impl SpinnerPropsBuilder {
+    /// ### with_foreground
+    ///
+    /// Set foreground color for spinner and text
+    pub fn with_foreground(&mut self, color: Color) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.foreground = color;
+        }
+        self
+    }
+
+    /// ### with_borders
+    ///
+    /// Set component borders style
+    pub fn with_borders(
+        &mut self,
+        borders: Borders,
+        variant: BorderType,
+        color: Color,
+    ) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.borders = BordersProps {
+                borders,
+                variant,
+                color,
+            }
+        }
+        self
+    }
+
+    /// ### bold
+    ///
+    /// Set bold modifier for text
+    pub fn bold(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.modifiers |= Modifier::BOLD;
+        }
+        self
+    }
+
+    /// ### with_text
+    ///
+    /// Set text to display next to the spinner
+    pub fn with_text<S: AsRef<str>>(&mut self, text: S) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_TEXT,
+                PropPayload::One(PropValue::Str(text.as_ref().to_string())),
+            );
+        }
+        self
+    }
+
+    /// ### still
+    ///
+    /// Disable animation; the spinner will always display the same frame
+    pub fn still(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props
+                .own
+                .insert(PROP_STILL, PropPayload::One(PropValue::Bool(true)));
+        }
+        self
+    }
+
+    /// ### with_text_alignment
+    ///
+    /// Set alignment for spinner and text
+    pub fn with_text_alignment(&mut self, alignment: Alignment) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_ALIGNMENT,
+                PropPayload::One(PropValue::Alignment(alignment)),
+            );
+        }
+        self
+    }
+}
+
+// -- states
+
+/// ## OwnStates
+///
+/// OwnStates contains states for this component
+struct OwnStates {
+    started: Instant, // Instant the spinner started spinning
+}
+
+impl Default for OwnStates {
+    fn default() -> Self {
+        OwnStates {
+            started: Instant::now(),
+        }
+    }
+}
+
+// -- Component
+
+/// ## Spinner
+///
+/// Spinner component; the frame to display is derived from the time elapsed since the component
+/// has been mounted, so it keeps spinning as long as the view gets redrawn.
+pub struct Spinner {
+    props: Props,
+    states: OwnStates,
+}
+
+impl Spinner {
+    /// ### new
+    ///
+    /// Instantiates a new Spinner starting from Props
+    pub fn new(props: Props) -> Self {
+        Spinner {
+            props,
+            states: OwnStates::default(),
+        }
+    }
+
+    /// ### frame_at
+    ///
+    /// Get the frame to display after `elapsed` time
+    fn frame_at(elapsed: Duration) -> char {
+        FRAMES[((elapsed.as_millis() / FRAME_INTERVAL) % FRAMES.len() as u128) as usize]
+    }
+
+    /// ### frame
+    ///
+    /// Get the frame to display now
+    fn frame(&self) -> char {
+        match self.props.own.get(PROP_STILL) {
+            Some(PropPayload::One(PropValue::Bool(true))) => FRAMES[0],
+            _ => Self::frame_at(self.states.started.elapsed()),
+        }
+    }
+}
+
+impl Component for Spinner {
+    #[cfg(not(tarpaulin_include))]
+    fn render(&self, render: &mut Frame, area: Rect) {
+        if self.props.visible {
+            let style: Style = Style::default()
+                .fg(self.props.foreground)
+                .add_modifier(self.props.modifiers);
+            let mut spans: Vec<Span> = vec![Span::styled(self.frame().to_string(), style)];
+            if let Some(PropPayload::One(PropValue::Str(text))) = self.props.own.get(PROP_TEXT) {
+                spans.push(Span::styled(format!(" {}", text), style));
+            }
+            let alignment: Alignment = match self.props.own.get(PROP_ALIGNMENT) {
+                Some(PropPayload::One(PropValue::Alignment(alignment))) => *alignment,
+                _ => Alignment::Left,
+            };
+            render.render_widget(
+                Paragraph::new(Spans::from(spans))
+                    .block(get_block(
+                        &self.props.borders,
+                        self.props.title.as_ref(),
+                        false,
+                    ))
+                    .alignment(alignment),
+                area,
+            );
+        }
+    }
+
+    fn update(&mut self, props: Props) -> Msg {
+        self.props = props;
+        Msg::None
+    }
+
+    fn get_props(&self) -> Props {
+        self.props.clone()
+    }
+
+    fn on(&mut self, ev: Event) -> Msg {
+        // Return key event to activity
+        if let Event::Key(key) = ev {
+            Msg::OnKey(key)
+        } else {
+            Msg::None
+        }
+    }
+
+    fn get_state(&self) -> Payload {
+        Payload::None
+    }
+
+    fn blur(&mut self) {}
+
+    fn active(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::event::{KeyCode, KeyEvent};
+
+    #[test]
+    fn test_ui_components_spinner() {
+        let mut component: Spinner = Spinner::new(
+            SpinnerPropsBuilder::default()
+                .hidden()
+                .visible()
+                .with_foreground(Color::Yellow)
+                .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                .with_text("Loading…")
+                .with_text_alignment(Alignment::Center)
+                .bold()
+                .build(),
+        );
+        assert_eq!(component.props.visible, true);
+        assert_eq!(component.props.foreground, Color::Yellow);
+        // Frames
+        assert_eq!(Spinner::frame_at(Duration::ZERO), '⣾');
+        assert_eq!(Spinner::frame_at(Duration::from_millis(79)), '⣾');
+        assert_eq!(Spinner::frame_at(Duration::from_millis(80)), '⣽');
+        assert_eq!(Spinner::frame_at(Duration::from_millis(640)), '⣾');
+        // Still
+        component.states.started = Instant::now()
+            .checked_sub(Duration::from_millis(100))
+            .unwrap();
+        assert_eq!(component.frame(), '⣽');
+        assert_eq!(
+            component.update(
+                SpinnerPropsBuilder::from(component.get_props())
+                    .still()
+                    .build()
+            ),
+            Msg::None
+        );
+        assert_eq!(component.frame(), '⣾');
+        // Update
+        assert_eq!(
+            component.update(
+                SpinnerPropsBuilder::from(component.get_props())
+                    .with_text("Connecting…")
+                    .build()
+            ),
+            Msg::None
+        );
+        assert_eq!(
+            component.props.own.get(PROP_TEXT).unwrap(),
+            &PropPayload::One(PropValue::Str(String::from("Connecting…")))
+        );
+        // Events
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::OnKey(KeyEvent::from(KeyCode::Enter))
+        );
+        assert_eq!(component.on(Event::Resize(0, 0)), Msg::None);
+        assert_eq!(component.get_state(), Payload::None);
+    }
+}
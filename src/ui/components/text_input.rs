@@ -0,0 +1,601 @@
+//! ## TextInput
+//!
+//! `TextInput` component extends `Input` with history, placeholder, validation and completion
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// ext
+use tui_realm_stdlib::input::{Input, InputPropsBuilder};
+use tui_realm_stdlib::utils::get_block;
+use tuirealm::event::{Event, KeyCode};
+use tuirealm::props::{Alignment, Props, PropsBuilder};
+use tuirealm::tui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{BorderType, Borders, Paragraph},
+};
+use tuirealm::{Component, Frame, Msg, Payload, PropPayload, PropValue, Value};
+
+// -- props
+
+const PROP_HISTORY: &str = "history";
+const PROP_PLACEHOLDER: &str = "placeholder";
+
+/// ## TextInputPropsBuilder
+///
+/// Props builder for `TextInput`; input properties are set through an `InputPropsBuilder`
+pub struct TextInputPropsBuilder {
+    props: Option<Props>,
+}
+
+impl Default for TextInputPropsBuilder {
+    fn default() -> Self {
+        TextInputPropsBuilder {
+            props: Some(InputPropsBuilder::default().build()),
+        }
+    }
+}
+
+impl PropsBuilder for TextInputPropsBuilder {
+    fn build(&mut self) -> Props {
+        self.props.take().unwrap()
+    }
+
+    fn hidden(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = false;
+        }
+        self
+    }
+
+    fn visible(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = true;
+        }
+        self
+    }
+}
+
+impl From<Props> for TextInputPropsBuilder {
+    fn from(props: Props) -> Self {
+        TextInputPropsBuilder { props: Some(props) }
+    }
+}
+
+impl TextInputPropsBuilder {
+    /// ### with_foreground
+    ///
+    /// Set foreground color for input
+    pub fn with_foreground(&mut self, color: Color) -> &mut Self {
+        self.puppet(|builder| {
+            builder.with_foreground(color);
+        })
+    }
+
+    /// ### with_borders
+    ///
+    /// Set component borders style
+    pub fn with_borders(
+        &mut self,
+        borders: Borders,
+        variant: BorderType,
+        color: Color,
+    ) -> &mut Self {
+        self.puppet(|builder| {
+            builder.with_borders(borders, variant, color);
+        })
+    }
+
+    /// ### with_label
+    ///
+    /// Set input label
+    pub fn with_label<S: AsRef<str>>(&mut self, label: S, alignment: Alignment) -> &mut Self {
+        self.puppet(|builder| {
+            builder.with_label(label, alignment);
+        })
+    }
+
+    /// ### with_value
+    ///
+    /// Set initial value for input
+    #[allow(dead_code)]
+    pub fn with_value(&mut self, value: String) -> &mut Self {
+        self.puppet(|builder| {
+            builder.with_value(value);
+        })
+    }
+
+    /// ### with_placeholder
+    ///
+    /// Set text to display while input is empty
+    pub fn with_placeholder<S: AsRef<str>>(&mut self, placeholder: S) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_PLACEHOLDER,
+                PropPayload::One(PropValue::Str(placeholder.as_ref().to_string())),
+            );
+        }
+        self
+    }
+
+    /// ### with_history
+    ///
+    /// Set history entries, from the oldest to the most recent
+    pub fn with_history(&mut self, history: &[String]) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_HISTORY,
+                PropPayload::Vec(history.iter().cloned().map(PropValue::Str).collect()),
+            );
+        }
+        self
+    }
+
+    /// ### puppet
+    ///
+    /// Edit props through an `InputPropsBuilder`
+    fn puppet<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut InputPropsBuilder),
+    {
+        if let Some(props) = self.props.take() {
+            let mut builder: InputPropsBuilder = InputPropsBuilder::from(props);
+            f(&mut builder);
+            self.props = Some(builder.build());
+        }
+        self
+    }
+}
+
+// -- states
+
+/// ## OwnStates
+///
+/// OwnStates contains states for this component
+#[derive(Default)]
+struct OwnStates {
+    history_index: Option<usize>, // Index of the history entry being displayed
+    draft: String,                // Value the user was typing before browsing history
+    valid: bool,                  // Whether current value is valid
+}
+
+// -- component
+
+/// Completion callback: takes the current value and returns the completed value, if any
+pub type CompletionFn = Box<dyn Fn(&str) -> Option<String>>;
+/// Validation callback: returns whether the current value is valid
+pub type ValidatorFn = Box<dyn Fn(&str) -> bool>;
+
+/// ## TextInput
+///
+/// a wrapper component of `Input` which adds:
+///
+/// - history recall with `<UP>` and `<DOWN>`
+/// - a placeholder, displayed while the input is empty
+/// - validation; when the value is invalid, the input is highlighted in red and can't be submitted
+/// - completion on `<TAB>`
+pub struct TextInput {
+    input: Input,
+    states: OwnStates,
+    completion: Option<CompletionFn>,
+    validator: Option<ValidatorFn>,
+}
+
+impl TextInput {
+    /// ### new
+    ///
+    /// Instantiate a new `TextInput`
+    pub fn new(props: Props) -> Self {
+        Self {
+            input: Input::new(props),
+            states: OwnStates {
+                valid: true,
+                ..OwnStates::default()
+            },
+            completion: None,
+            validator: None,
+        }
+    }
+
+    /// ### with_completion
+    ///
+    /// Set completion callback, called on `<TAB>`
+    pub fn with_completion<F>(mut self, completion: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + 'static,
+    {
+        self.completion = Some(Box::new(completion));
+        self
+    }
+
+    /// ### with_validator
+    ///
+    /// Set validation callback, called each time the value changes
+    #[allow(dead_code)]
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self.validate();
+        self
+    }
+
+    /// ### value
+    ///
+    /// Get current input value
+    fn value(&self) -> String {
+        match self.input.get_state() {
+            Payload::One(Value::Str(s)) => s,
+            _ => String::new(),
+        }
+    }
+
+    /// ### set_value
+    ///
+    /// Set input value; returns `OnChange` if value has changed
+    fn set_value(&mut self, value: String) -> Msg {
+        let props = InputPropsBuilder::from(self.input.get_props())
+            .with_value(value)
+            .build();
+        let msg: Msg = self.input.update(props);
+        self.validate();
+        msg
+    }
+
+    /// ### history
+    ///
+    /// Get history entries from props
+    fn history(&self) -> Vec<String> {
+        match self.input.get_props().own.get(PROP_HISTORY) {
+            Some(PropPayload::Vec(entries)) => entries
+                .iter()
+                .filter_map(|x| match x {
+                    PropValue::Str(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// ### history_prev
+    ///
+    /// Recall previous history entry
+    fn history_prev(&mut self) -> Msg {
+        let history: Vec<String> = self.history();
+        let index: usize = match self.states.history_index {
+            None if history.is_empty() => return Msg::None,
+            None => {
+                self.states.draft = self.value();
+                history.len() - 1
+            }
+            Some(0) => return Msg::None,
+            Some(i) => i - 1,
+        };
+        self.states.history_index = Some(index);
+        self.set_value(history[index].clone())
+    }
+
+    /// ### history_next
+    ///
+    /// Recall next history entry; after the most recent entry, restore draft
+    fn history_next(&mut self) -> Msg {
+        let history: Vec<String> = self.history();
+        match self.states.history_index {
+            None => Msg::None,
+            Some(i) if i + 1 < history.len() => {
+                self.states.history_index = Some(i + 1);
+                self.set_value(history[i + 1].clone())
+            }
+            Some(_) => {
+                self.states.history_index = None;
+                let draft: String = std::mem::take(&mut self.states.draft);
+                self.set_value(draft)
+            }
+        }
+    }
+
+    /// ### validate
+    ///
+    /// Validate current value; invalid values are rendered in red
+    fn validate(&mut self) {
+        self.states.valid = match self.validator.as_ref() {
+            Some(validator) => validator(self.value().as_str()),
+            None => true,
+        };
+    }
+
+    /// ### placeholder
+    ///
+    /// Get placeholder to display, if input is empty
+    fn placeholder(&self) -> Option<String> {
+        match self.input.get_props().own.get(PROP_PLACEHOLDER) {
+            Some(PropPayload::One(PropValue::Str(placeholder))) if self.value().is_empty() => {
+                Some(placeholder.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// ### complete_from
+///
+/// Complete `value` with the longest common prefix among the `candidates` starting with it.
+/// Returns `None` if there is nothing to complete
+pub fn complete_from(candidates: &[String], value: &str) -> Option<String> {
+    let mut matches = candidates.iter().filter(|x| x.starts_with(value));
+    let first: &String = matches.next()?;
+    let prefix: &str = matches.fold(first.as_str(), |prefix, candidate| {
+        let len: usize = prefix
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map(|((i, c), _)| i + c.len_utf8())
+            .unwrap_or(0);
+        &prefix[..len]
+    });
+    match prefix.len() > value.len() {
+        true => Some(prefix.to_string()),
+        false => None,
+    }
+}
+
+impl Component for TextInput {
+    /// ### render
+    ///
+    /// Based on the current properties and states, renders a widget using the provided render engine in the provided Area
+    /// If focused, cursor is also set (if supported by widget)
+    #[cfg(not(tarpaulin_include))]
+    fn render(&self, render: &mut Frame, area: Rect) {
+        let props: Props = self.input.get_props();
+        if !props.visible {
+            return;
+        }
+        if !self.states.valid {
+            // Render with error colors
+            let mut props: Props = props;
+            props.foreground = Color::Red;
+            props.borders.color = Color::Red;
+            Input::new(props).render(render, area);
+        } else if let Some(placeholder) = self.placeholder() {
+            render.render_widget(
+                Paragraph::new(placeholder)
+                    .style(
+                        Style::default()
+                            .fg(Color::DarkGray)
+                            .add_modifier(Modifier::ITALIC),
+                    )
+                    .block(get_block(&props.borders, props.title.as_ref(), true)),
+                area,
+            );
+            render.set_cursor(area.x + 1, area.y + 1);
+        } else {
+            self.input.render(render, area);
+        }
+    }
+
+    /// ### update
+    ///
+    /// Update component properties
+    fn update(&mut self, props: Props) -> Msg {
+        let msg: Msg = self.input.update(props);
+        self.validate();
+        msg
+    }
+
+    /// ### get_props
+    ///
+    /// Returns component properties
+    fn get_props(&self) -> Props {
+        self.input.get_props()
+    }
+
+    /// ### on
+    ///
+    /// Handle input event and update internal states.
+    /// Returns a Msg to the view
+    fn on(&mut self, ev: Event) -> Msg {
+        if let Event::Key(key) = ev {
+            match key.code {
+                KeyCode::Up => self.history_prev(),
+                KeyCode::Down => self.history_next(),
+                KeyCode::Tab => match self.completion.as_ref() {
+                    None => Msg::OnKey(key),
+                    Some(completion) => match completion(self.value().as_str()) {
+                        Some(completed) => self.set_value(completed),
+                        None => Msg::None,
+                    },
+                },
+                KeyCode::Enter if !self.states.valid => Msg::None,
+                _ => {
+                    let msg: Msg = self.input.on(ev);
+                    if let Msg::OnChange(_) = msg {
+                        self.validate();
+                    }
+                    msg
+                }
+            }
+        } else {
+            self.input.on(ev)
+        }
+    }
+
+    /// ### get_state
+    ///
+    /// Get current state from component; the current value
+    fn get_state(&self) -> Payload {
+        self.input.get_state()
+    }
+
+    // -- events
+
+    /// ### blur
+    ///
+    /// Blur component; basically remove focus
+    fn blur(&mut self) {
+        self.input.blur();
+    }
+
+    /// ### active
+    ///
+    /// Active component; basically give focus
+    fn active(&mut self) {
+        self.input.active();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crossterm::event::{KeyCode, KeyEvent};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_components_text_input() {
+        let mut component: TextInput = TextInput::new(
+            TextInputPropsBuilder::default()
+                .hidden()
+                .visible()
+                .with_foreground(Color::Yellow)
+                .with_borders(Borders::ALL, BorderType::Rounded, Color::Yellow)
+                .with_label("Change working directory", Alignment::Center)
+                .with_placeholder("/home/omar")
+                .with_history(&[String::from("/tmp"), String::from("/home")])
+                .build(),
+        )
+        .with_completion(|value| match value {
+            "/ho" => Some(String::from("/home/")),
+            _ => None,
+        })
+        .with_validator(|value| !value.contains(' '));
+        component.blur();
+        component.active();
+        assert_eq!(component.get_props().foreground, Color::Yellow);
+        assert_eq!(component.placeholder().as_deref(), Some("/home/omar"));
+        // Type
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char('/')))),
+            Msg::OnChange(Payload::One(Value::Str(String::from("/"))))
+        );
+        assert_eq!(component.placeholder(), None);
+        // History
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Up))),
+            Msg::OnChange(Payload::One(Value::Str(String::from("/home"))))
+        );
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Up))),
+            Msg::OnChange(Payload::One(Value::Str(String::from("/tmp"))))
+        );
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Up))),
+            Msg::None
+        );
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Down))),
+            Msg::OnChange(Payload::One(Value::Str(String::from("/home"))))
+        );
+        // Restore draft
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Down))),
+            Msg::OnChange(Payload::One(Value::Str(String::from("/"))))
+        );
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Down))),
+            Msg::None
+        );
+        // Completion
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('h'))));
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char('o'))));
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Tab))),
+            Msg::OnChange(Payload::One(Value::Str(String::from("/home/"))))
+        );
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Tab))),
+            Msg::None
+        );
+        // Validation
+        component.on(Event::Key(KeyEvent::from(KeyCode::Char(' '))));
+        assert_eq!(component.states.valid, false);
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::None
+        );
+        component.on(Event::Key(KeyEvent::from(KeyCode::Backspace)));
+        assert_eq!(component.states.valid, true);
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Enter))),
+            Msg::OnSubmit(Payload::One(Value::Str(String::from("/home/"))))
+        );
+        // Update
+        let props = TextInputPropsBuilder::from(component.get_props())
+            .with_value(String::from("a b"))
+            .build();
+        assert_eq!(
+            component.update(props),
+            Msg::OnChange(Payload::One(Value::Str(String::from("a b"))))
+        );
+        assert_eq!(component.states.valid, false);
+        // Without completion, tab is forwarded
+        let mut component: TextInput = TextInput::new(TextInputPropsBuilder::default().build());
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Tab))),
+            Msg::OnKey(KeyEvent::from(KeyCode::Tab))
+        );
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Up))),
+            Msg::None
+        );
+        assert_eq!(
+            component.get_state(),
+            Payload::One(Value::Str(String::new()))
+        );
+    }
+
+    #[test]
+    fn test_ui_components_text_input_complete_from() {
+        let candidates: Vec<String> = vec![
+            String::from("docs"),
+            String::from("documents"),
+            String::from("downloads"),
+        ];
+        assert_eq!(complete_from(&candidates, "d"), Some(String::from("do")));
+        assert_eq!(complete_from(&candidates, "doc"), None);
+        assert_eq!(
+            complete_from(&candidates, "docu"),
+            Some(String::from("documents"))
+        );
+        assert_eq!(
+            complete_from(&candidates, "dow"),
+            Some(String::from("downloads"))
+        );
+        assert_eq!(complete_from(&candidates, "docs"), None);
+        assert_eq!(complete_from(&candidates, "x"), None);
+        assert_eq!(complete_from(&[], ""), None);
+    }
+}
@@ -0,0 +1,462 @@
+//! ## ThemePreview
+//!
+//! `ThemePreview` component renders miniature mock-ups of termscp views with the colors of a theme
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use crate::config::themes::Theme;
+// ext
+use tui_realm_stdlib::utils::get_block;
+use tuirealm::event::Event;
+use tuirealm::props::{Alignment, BlockTitle, BordersProps, Props, PropsBuilder};
+use tuirealm::tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+use tuirealm::{Component, Frame, Msg, Payload};
+
+// -- props
+
+const PALETTE_AUTH_ADDRESS: &str = "auth-address";
+const PALETTE_AUTH_BOOKMARKS: &str = "auth-bookmarks";
+const PALETTE_AUTH_PASSWORD: &str = "auth-password";
+const PALETTE_AUTH_PORT: &str = "auth-port";
+const PALETTE_AUTH_PROTOCOL: &str = "auth-protocol";
+const PALETTE_AUTH_RECENTS: &str = "auth-recents";
+const PALETTE_AUTH_USERNAME: &str = "auth-username";
+const PALETTE_MISC_ERROR: &str = "misc-error";
+const PALETTE_MISC_INPUT: &str = "misc-input";
+const PALETTE_MISC_KEYS: &str = "misc-keys";
+const PALETTE_MISC_QUIT: &str = "misc-quit";
+const PALETTE_MISC_SAVE: &str = "misc-save";
+const PALETTE_MISC_WARN: &str = "misc-warn";
+const PALETTE_LOCAL_BG: &str = "local-explorer-background";
+const PALETTE_LOCAL_FG: &str = "local-explorer-foreground";
+const PALETTE_LOCAL_HG: &str = "local-explorer-highlighted";
+const PALETTE_REMOTE_BG: &str = "remote-explorer-background";
+const PALETTE_REMOTE_FG: &str = "remote-explorer-foreground";
+const PALETTE_REMOTE_HG: &str = "remote-explorer-highlighted";
+const PALETTE_LOG_BG: &str = "log-background";
+const PALETTE_LOG_WIN: &str = "log-window";
+const PALETTE_PROG_BAR_FULL: &str = "progress-bar-full";
+const PALETTE_PROG_BAR_PARTIAL: &str = "progress-bar-partial";
+const PALETTE_STATUS_HIDDEN: &str = "status-hidden";
+const PALETTE_STATUS_SORTING: &str = "status-sorting";
+const PALETTE_STATUS_SYNC: &str = "status-sync-browsing";
+
+pub struct ThemePreviewPropsBuilder {
+    props: Option<Props>,
+}
+
+impl Default for ThemePreviewPropsBuilder {
+    fn default() -> Self {
+        ThemePreviewPropsBuilder {
+            props: Some(Props::default()),
+        }
+    }
+}
+
+impl PropsBuilder for ThemePreviewPropsBuilder {
+    fn build(&mut self) -> Props {
+        self.props.take().unwrap()
+    }
+
+    fn hidden(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = false;
+        }
+        self
+    }
+
+    fn visible(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = true;
+        }
+        self
+    }
+}
+
+impl From<Props> for ThemePreviewPropsBuilder {
+    fn from(props: Props) -> Self {
+        ThemePreviewPropsBuilder { props: Some(props) }
+    }
+}
+
+impl ThemePreviewPropsBuilder {
+    /// ### with_borders
+    ///
+    /// Set component borders style
+    pub fn with_borders(
+        &mut self,
+        borders: Borders,
+        variant: BorderType,
+        color: Color,
+    ) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.borders = BordersProps {
+                borders,
+                variant,
+                color,
+            }
+        }
+        self
+    }
+
+    /// ### with_title
+    ///
+    /// Set title
+    pub fn with_title<S: AsRef<str>>(&mut self, text: S, alignment: Alignment) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.title = Some(BlockTitle::new(text, alignment));
+        }
+        self
+    }
+
+    /// ### with_theme
+    ///
+    /// Set the theme whose colors are previewed
+    pub fn with_theme(&mut self, theme: &Theme) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            let colors: [(&'static str, Color); 26] = [
+                (PALETTE_AUTH_ADDRESS, theme.auth_address),
+                (PALETTE_AUTH_BOOKMARKS, theme.auth_bookmarks),
+                (PALETTE_AUTH_PASSWORD, theme.auth_password),
+                (PALETTE_AUTH_PORT, theme.auth_port),
+                (PALETTE_AUTH_PROTOCOL, theme.auth_protocol),
+                (PALETTE_AUTH_RECENTS, theme.auth_recents),
+                (PALETTE_AUTH_USERNAME, theme.auth_username),
+                (PALETTE_MISC_ERROR, theme.misc_error_dialog),
+                (PALETTE_MISC_INPUT, theme.misc_input_dialog),
+                (PALETTE_MISC_KEYS, theme.misc_keys),
+                (PALETTE_MISC_QUIT, theme.misc_quit_dialog),
+                (PALETTE_MISC_SAVE, theme.misc_save_dialog),
+                (PALETTE_MISC_WARN, theme.misc_warn_dialog),
+                (PALETTE_LOCAL_BG, theme.transfer_local_explorer_background),
+                (PALETTE_LOCAL_FG, theme.transfer_local_explorer_foreground),
+                (PALETTE_LOCAL_HG, theme.transfer_local_explorer_highlighted),
+                (PALETTE_REMOTE_BG, theme.transfer_remote_explorer_background),
+                (PALETTE_REMOTE_FG, theme.transfer_remote_explorer_foreground),
+                (
+                    PALETTE_REMOTE_HG,
+                    theme.transfer_remote_explorer_highlighted,
+                ),
+                (PALETTE_LOG_BG, theme.transfer_log_background),
+                (PALETTE_LOG_WIN, theme.transfer_log_window),
+                (PALETTE_PROG_BAR_FULL, theme.transfer_progress_bar_full),
+                (
+                    PALETTE_PROG_BAR_PARTIAL,
+                    theme.transfer_progress_bar_partial,
+                ),
+                (PALETTE_STATUS_HIDDEN, theme.transfer_status_hidden),
+                (PALETTE_STATUS_SORTING, theme.transfer_status_sorting),
+                (PALETTE_STATUS_SYNC, theme.transfer_status_sync_browsing),
+            ];
+            for (key, color) in colors.iter() {
+                props.palette.insert(*key, *color);
+            }
+        }
+        self
+    }
+}
+
+// -- component
+
+/// ## ThemePreview
+///
+/// Miniature mock-ups of the authentication form, of the explorers and of the popups, drawn with the theme colors
+pub struct ThemePreview {
+    props: Props,
+}
+
+impl ThemePreview {
+    /// ### new
+    ///
+    /// Instantiates a new `ThemePreview`
+    pub fn new(props: Props) -> Self {
+        Self { props }
+    }
+
+    /// ### color
+    ///
+    /// Get color from palette
+    fn color(&self, key: &str) -> Color {
+        self.props.palette.get(key).copied().unwrap_or(Color::Reset)
+    }
+
+    /// ### fg
+    ///
+    /// Get a style with the palette color `key` as foreground
+    fn fg(&self, key: &str) -> Style {
+        Style::default().fg(self.color(key))
+    }
+
+    /// ### selected
+    ///
+    /// Get the style of a selected item with the palette color `key`
+    fn selected(&self, key: &str) -> Style {
+        Style::default().fg(Color::Black).bg(self.color(key))
+    }
+
+    /// ### auth_lines
+    ///
+    /// Mock-up of the authentication form
+    fn auth_lines(&self) -> Vec<Spans<'_>> {
+        vec![
+            Spans::from(vec![
+                Span::styled("SFTP", self.selected(PALETTE_AUTH_PROTOCOL)),
+                Span::styled(" SCP FTP", self.fg(PALETTE_AUTH_PROTOCOL)),
+            ]),
+            Spans::from(Span::styled(
+                "Address  192.168.1.31",
+                self.fg(PALETTE_AUTH_ADDRESS),
+            )),
+            Spans::from(Span::styled("Port     22", self.fg(PALETTE_AUTH_PORT))),
+            Spans::from(Span::styled(
+                "Username omar",
+                self.fg(PALETTE_AUTH_USERNAME),
+            )),
+            Spans::from(Span::styled(
+                "Password ******",
+                self.fg(PALETTE_AUTH_PASSWORD),
+            )),
+            Spans::from(vec![
+                Span::styled("Bookmarks", self.fg(PALETTE_AUTH_BOOKMARKS)),
+                Span::raw(" "),
+                Span::styled("Recents", self.fg(PALETTE_AUTH_RECENTS)),
+            ]),
+        ]
+    }
+
+    /// ### explorer
+    ///
+    /// Mock-up of an explorer with the provided palette colors
+    fn explorer<'a>(&self, title: &'a str, bg: &str, fg: &str, hg: &str) -> Paragraph<'a> {
+        let style: Style = Style::default().fg(self.color(fg)).bg(self.color(bg));
+        Paragraph::new(vec![
+            Spans::from(Span::styled("Documents/", self.selected(hg))),
+            Spans::from(Span::styled("omar.txt", style)),
+        ])
+        .style(style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.fg(hg))
+                .title(title),
+        )
+    }
+
+    /// ### transfer_lines
+    ///
+    /// Mock-up of the progress bars, of the status bar and of the log window
+    fn transfer_lines(&self) -> Vec<Spans<'_>> {
+        vec![
+            Spans::from(vec![
+                Span::styled("██████", self.fg(PALETTE_PROG_BAR_FULL)),
+                Span::raw(" "),
+                Span::styled("███", self.fg(PALETTE_PROG_BAR_PARTIAL)),
+            ]),
+            Spans::from(vec![
+                Span::styled("Sorting", self.fg(PALETTE_STATUS_SORTING)),
+                Span::raw(" "),
+                Span::styled("Hidden", self.fg(PALETTE_STATUS_HIDDEN)),
+                Span::raw(" "),
+                Span::styled("Sync", self.fg(PALETTE_STATUS_SYNC)),
+            ]),
+            Spans::from(Span::styled(
+                "Log: Connected",
+                Style::default()
+                    .fg(self.color(PALETTE_LOG_WIN))
+                    .bg(self.color(PALETTE_LOG_BG)),
+            )),
+        ]
+    }
+
+    /// ### popup_lines
+    ///
+    /// Mock-up of the popups
+    fn popup_lines(&self) -> Vec<Spans<'_>> {
+        vec![
+            Spans::from(Span::styled(
+                "Could not connect",
+                self.fg(PALETTE_MISC_ERROR),
+            )),
+            Spans::from(vec![
+                Span::styled("Find: ", self.fg(PALETTE_MISC_INPUT)),
+                Span::styled(
+                    "*.txt",
+                    self.fg(PALETTE_MISC_INPUT)
+                        .add_modifier(Modifier::UNDERLINED),
+                ),
+            ]),
+            Spans::from(vec![
+                Span::styled("Quit?", self.fg(PALETTE_MISC_QUIT)),
+                Span::raw(" "),
+                Span::styled("Yes", self.selected(PALETTE_MISC_QUIT)),
+                Span::styled(" No", self.fg(PALETTE_MISC_QUIT)),
+            ]),
+            Spans::from(vec![
+                Span::styled("Save?", self.fg(PALETTE_MISC_SAVE)),
+                Span::raw(" "),
+                Span::styled("Yes", self.selected(PALETTE_MISC_SAVE)),
+                Span::styled(" No", self.fg(PALETTE_MISC_SAVE)),
+            ]),
+            Spans::from(Span::styled(
+                "File already exists",
+                self.fg(PALETTE_MISC_WARN),
+            )),
+            Spans::from(vec![
+                Span::styled("<CTRL+H>", self.fg(PALETTE_MISC_KEYS)),
+                Span::raw(" Help"),
+            ]),
+        ]
+    }
+}
+
+impl Component for ThemePreview {
+    #[cfg(not(tarpaulin_include))]
+    fn render(&self, render: &mut Frame, area: Rect) {
+        if self.props.visible {
+            let block = get_block(&self.props.borders, self.props.title.as_ref(), false);
+            let inner: Rect = block.inner(area);
+            render.render_widget(block, area);
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .horizontal_margin(1)
+                .constraints(
+                    [
+                        Constraint::Percentage(30), // Auth
+                        Constraint::Percentage(40), // Explorers
+                        Constraint::Percentage(30), // Popups
+                    ]
+                    .as_ref(),
+                )
+                .split(inner);
+            render.render_widget(Paragraph::new(self.auth_lines()), columns[0]);
+            // Explorers
+            let transfer_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(4), Constraint::Min(0)].as_ref())
+                .split(columns[1]);
+            let explorer_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(transfer_chunks[0]);
+            render.render_widget(
+                self.explorer(
+                    "Local",
+                    PALETTE_LOCAL_BG,
+                    PALETTE_LOCAL_FG,
+                    PALETTE_LOCAL_HG,
+                ),
+                explorer_chunks[0],
+            );
+            render.render_widget(
+                self.explorer(
+                    "Remote",
+                    PALETTE_REMOTE_BG,
+                    PALETTE_REMOTE_FG,
+                    PALETTE_REMOTE_HG,
+                ),
+                explorer_chunks[1],
+            );
+            render.render_widget(Paragraph::new(self.transfer_lines()), transfer_chunks[1]);
+            render.render_widget(Paragraph::new(self.popup_lines()), columns[2]);
+        }
+    }
+
+    fn update(&mut self, props: Props) -> Msg {
+        self.props = props;
+        Msg::None
+    }
+
+    fn get_props(&self) -> Props {
+        self.props.clone()
+    }
+
+    fn on(&mut self, ev: Event) -> Msg {
+        match ev {
+            Event::Key(key) => Msg::OnKey(key),
+            _ => Msg::None,
+        }
+    }
+
+    fn get_state(&self) -> Payload {
+        Payload::None
+    }
+
+    fn blur(&mut self) {}
+
+    fn active(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_components_theme_preview() {
+        let mut theme: Theme = Theme {
+            auth_protocol: Color::LightGreen,
+            transfer_remote_explorer_highlighted: Color::Rgb(255, 128, 0),
+            ..Theme::default()
+        };
+        let mut component: ThemePreview = ThemePreview::new(
+            ThemePreviewPropsBuilder::default()
+                .hidden()
+                .visible()
+                .with_borders(Borders::ALL, BorderType::Rounded, Color::Reset)
+                .with_title("Preview", Alignment::Left)
+                .with_theme(&theme)
+                .build(),
+        );
+        assert!(component.props.visible);
+        assert_eq!(component.props.palette.len(), 26);
+        assert_eq!(component.color(PALETTE_AUTH_PROTOCOL), Color::LightGreen);
+        assert_eq!(
+            component.selected(PALETTE_REMOTE_HG),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Rgb(255, 128, 0))
+        );
+        assert_eq!(component.auth_lines().len(), 6);
+        assert_eq!(component.popup_lines().len(), 6);
+        // Update
+        theme.auth_protocol = Color::Red;
+        let props = ThemePreviewPropsBuilder::from(component.get_props())
+            .with_theme(&theme)
+            .build();
+        assert_eq!(component.update(props), Msg::None);
+        assert_eq!(
+            component.fg(PALETTE_AUTH_PROTOCOL),
+            Style::default().fg(Color::Red)
+        );
+        assert_eq!(component.get_state(), Payload::None);
+    }
+}
@@ -0,0 +1,343 @@
+//! ## Toast
+//!
+//! `Toast` component renders transient notifications, stacked in the bottom-right corner
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// ext
+use tui_realm_stdlib::utils::get_block;
+use tuirealm::event::Event;
+use tuirealm::props::{BordersProps, Props, PropsBuilder, Table as TextTable, TextSpan};
+use tuirealm::tui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{BorderType, Borders, Clear, Paragraph},
+};
+use tuirealm::{Component, Frame, Msg, Payload, PropPayload, PropValue};
+
+use std::collections::VecDeque;
+
+// -- props
+
+const PROP_TOASTS: &str = "toasts";
+
+pub struct ToastPropsBuilder {
+    props: Option<Props>,
+}
+
+impl Default for ToastPropsBuilder {
+    fn default() -> Self {
+        ToastPropsBuilder {
+            props: Some(Props::default()),
+        }
+    }
+}
+
+impl PropsBuilder for ToastPropsBuilder {
+    fn build(&mut self) -> Props {
+        self.props.take().unwrap()
+    }
+
+    fn hidden(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = false;
+        }
+        self
+    }
+
+    fn visible(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = true;
+        }
+        self
+    }
+}
+
+impl From<Props> for ToastPropsBuilder {
+    fn from(props: Props) -> Self {
+        ToastPropsBuilder { props: Some(props) }
+    }
+}
+
+impl ToastPropsBuilder {
+    /// ### with_borders
+    ///
+    /// Set borders style for each toast
+    pub fn with_borders(
+        &mut self,
+        borders: Borders,
+        variant: BorderType,
+        color: Color,
+    ) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.borders = BordersProps {
+                borders,
+                variant,
+                color,
+            }
+        }
+        self
+    }
+
+    /// ### with_toasts
+    ///
+    /// Set toasts to display; each row is a toast. The last row is displayed at the bottom
+    pub fn with_toasts(&mut self, toasts: TextTable) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props
+                .own
+                .insert(PROP_TOASTS, PropPayload::One(PropValue::Table(toasts)));
+        }
+        self
+    }
+}
+
+// -- queue
+
+/// ## ToastQueue
+///
+/// ToastQueue holds the toasts to display, each with its remaining lifetime in ticks.
+/// The activity must call `tick` once per UI tick and refresh the component when it returns `true`
+pub struct ToastQueue {
+    toasts: VecDeque<(TextSpan, usize)>,
+    ttl: usize,
+    capacity: usize,
+}
+
+impl ToastQueue {
+    /// ### new
+    ///
+    /// Instantiate a new `ToastQueue`; each toast lives for `ttl` ticks,
+    /// while at most `capacity` toasts are stacked at once
+    pub fn new(ttl: usize, capacity: usize) -> Self {
+        Self {
+            toasts: VecDeque::with_capacity(capacity),
+            ttl,
+            capacity,
+        }
+    }
+
+    /// ### push
+    ///
+    /// Push a new toast to the stack. If the stack is full, the oldest toast is dismissed
+    pub fn push(&mut self, toast: TextSpan) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.toasts.len() >= self.capacity {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back((toast, self.ttl));
+    }
+
+    /// ### tick
+    ///
+    /// Decrement lifetime of each toast, dismissing expired ones.
+    /// Returns whether at least a toast has been dismissed
+    pub fn tick(&mut self) -> bool {
+        let len: usize = self.toasts.len();
+        self.toasts.iter_mut().for_each(|(_, ttl)| {
+            *ttl = ttl.saturating_sub(1);
+        });
+        self.toasts.retain(|(_, ttl)| *ttl > 0);
+        len != self.toasts.len()
+    }
+
+    /// ### is_empty
+    ///
+    /// Returns whether there are no toasts to display
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// ### table
+    ///
+    /// Make a table from the current toasts, to be passed to the `Toast` component
+    pub fn table(&self) -> TextTable {
+        self.toasts
+            .iter()
+            .map(|(toast, _)| vec![toast.clone()])
+            .collect()
+    }
+}
+
+// -- Component
+
+/// ## Toast
+///
+/// Toast component; it's rendered over the provided area and never takes focus
+pub struct Toast {
+    props: Props,
+}
+
+impl Toast {
+    /// ### new
+    ///
+    /// Instantiates a new Toast starting from Props
+    pub fn new(props: Props) -> Self {
+        Toast { props }
+    }
+
+    /// ### toast_areas
+    ///
+    /// Calculate the areas for each toast, stacked from the bottom-right corner of `area`
+    fn toast_areas(table: &TextTable, area: Rect) -> Vec<Rect> {
+        let mut areas: Vec<Rect> = Vec::with_capacity(table.len());
+        let mut bottom: u16 = area.y + area.height;
+        for row in table.iter().rev() {
+            if bottom < area.y + 3 {
+                break;
+            }
+            let text_len: usize = row.iter().map(|x| x.content.chars().count()).sum();
+            let width: u16 = std::cmp::min(text_len as u16 + 4, area.width);
+            bottom -= 3;
+            areas.push(Rect::new(area.x + area.width - width, bottom, width, 3));
+        }
+        areas
+    }
+}
+
+impl Component for Toast {
+    #[cfg(not(tarpaulin_include))]
+    fn render(&self, render: &mut Frame, area: Rect) {
+        if self.props.visible {
+            if let Some(PropPayload::One(PropValue::Table(table))) = self.props.own.get(PROP_TOASTS)
+            {
+                for (row, toast_area) in table.iter().rev().zip(Self::toast_areas(table, area)) {
+                    let spans: Vec<Span> = row
+                        .iter()
+                        .map(|x| {
+                            Span::styled(
+                                x.content.clone(),
+                                Style::default().fg(x.fg).add_modifier(x.modifiers),
+                            )
+                        })
+                        .collect();
+                    render.render_widget(Clear, toast_area);
+                    render.render_widget(
+                        Paragraph::new(Spans::from(spans)).block(get_block(
+                            &self.props.borders,
+                            None,
+                            false,
+                        )),
+                        toast_area,
+                    );
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, props: Props) -> Msg {
+        self.props = props;
+        Msg::None
+    }
+
+    fn get_props(&self) -> Props {
+        self.props.clone()
+    }
+
+    fn on(&mut self, ev: Event) -> Msg {
+        // Return key event to activity
+        if let Event::Key(key) = ev {
+            Msg::OnKey(key)
+        } else {
+            Msg::None
+        }
+    }
+
+    fn get_state(&self) -> Payload {
+        Payload::None
+    }
+
+    fn blur(&mut self) {}
+
+    fn active(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::event::{KeyCode, KeyEvent};
+
+    #[test]
+    fn test_ui_components_toast() {
+        let mut queue: ToastQueue = ToastQueue::new(2, 2);
+        assert!(queue.is_empty());
+        queue.push(TextSpan::from("bookmark saved"));
+        assert_eq!(queue.tick(), false);
+        queue.push(TextSpan::from("bookmark removed").fg(Color::Red));
+        queue.push(TextSpan::from("recent removed"));
+        // Oldest has been dismissed
+        let table: TextTable = queue.table();
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[0][0].content.as_str(), "bookmark removed");
+        assert_eq!(table[0][0].fg, Color::Red);
+        assert_eq!(table[1][0].content.as_str(), "recent removed");
+        // Make component
+        let mut component: Toast = Toast::new(
+            ToastPropsBuilder::default()
+                .hidden()
+                .visible()
+                .with_borders(Borders::ALL, BorderType::Rounded, Color::White)
+                .with_toasts(queue.table())
+                .build(),
+        );
+        assert_eq!(component.props.visible, true);
+        // Areas
+        let areas: Vec<Rect> = Toast::toast_areas(&table, Rect::new(0, 0, 80, 24));
+        assert_eq!(areas.len(), 2);
+        assert_eq!(areas[0], Rect::new(62, 21, 18, 3));
+        assert_eq!(areas[1], Rect::new(60, 18, 20, 3));
+        // Not enough room
+        assert_eq!(
+            Toast::toast_areas(&table, Rect::new(0, 0, 10, 4)),
+            vec![Rect::new(0, 1, 10, 3)]
+        );
+        // Expire
+        assert_eq!(queue.tick(), false);
+        assert_eq!(queue.table().len(), 2);
+        assert_eq!(queue.tick(), true);
+        assert!(queue.is_empty());
+        assert_eq!(queue.tick(), false);
+        // Update
+        assert_eq!(
+            component.update(
+                ToastPropsBuilder::from(component.get_props())
+                    .with_toasts(queue.table())
+                    .build()
+            ),
+            Msg::None
+        );
+        // Events
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Esc))),
+            Msg::OnKey(KeyEvent::from(KeyCode::Esc))
+        );
+        assert_eq!(component.get_state(), Payload::None);
+    }
+}
@@ -0,0 +1,517 @@
+//! ## TransferProgress
+//!
+//! `TransferProgress` component renders the overall and the current file progress of a transfer
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use crate::utils::fmt::fmt_size;
+// ext
+use tui_realm_stdlib::utils::get_block;
+use tuirealm::event::Event;
+use tuirealm::props::{Alignment, BlockTitle, BordersProps, Props, PropsBuilder};
+use tuirealm::tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{BorderType, Borders, Gauge, Paragraph},
+};
+use tuirealm::{Component, Frame, Msg, Payload, PropPayload, PropValue};
+
+// -- props
+
+const PROP_OVERALL: &str = "overall";
+const PROP_CURRENT: &str = "current";
+const PROP_CURRENT_NAME: &str = "current-name";
+const PROP_OVERALL_COLOR: &str = "overall-color";
+const PROP_CURRENT_COLOR: &str = "current-color";
+const PROP_FILES: &str = "files";
+const PROP_PAUSED: &str = "paused";
+
+/// ## Progress
+///
+/// Progress of a transfer, as displayed by `TransferProgress`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Progress {
+    pub written: u64, // Bytes written
+    pub total: u64,   // Bytes to write
+    pub rate: u64,    // Bytes per second
+    pub eta: u64,     // Estimated seconds left
+}
+
+impl Progress {
+    /// ### ratio
+    ///
+    /// Get progress in a range between 0.0 to 1.0
+    fn ratio(&self) -> f64 {
+        match self.total {
+            0 => 0.0,
+            total => (self.written as f64 / total as f64).min(1.0),
+        }
+    }
+
+    /// ### label
+    ///
+    /// Format progress label
+    fn label(&self) -> String {
+        let eta: String = match self.eta {
+            0 => String::from("--:--"),
+            seconds => format!("{:02}:{:02}", seconds / 60, seconds % 60),
+        };
+        format!(
+            "{:.2}% - {}/{} - ETA {} ({}/s)",
+            self.ratio() * 100.0,
+            fmt_size(self.written),
+            fmt_size(self.total),
+            eta,
+            fmt_size(self.rate)
+        )
+    }
+}
+
+impl From<Progress> for PropPayload {
+    fn from(progress: Progress) -> Self {
+        PropPayload::Tup4((
+            PropValue::U64(progress.written),
+            PropValue::U64(progress.total),
+            PropValue::U64(progress.rate),
+            PropValue::U64(progress.eta),
+        ))
+    }
+}
+
+impl From<Option<&PropPayload>> for Progress {
+    fn from(payload: Option<&PropPayload>) -> Self {
+        match payload {
+            Some(PropPayload::Tup4((
+                PropValue::U64(written),
+                PropValue::U64(total),
+                PropValue::U64(rate),
+                PropValue::U64(eta),
+            ))) => Progress {
+                written: *written,
+                total: *total,
+                rate: *rate,
+                eta: *eta,
+            },
+            _ => Progress::default(),
+        }
+    }
+}
+
+/// ## FilesProgress
+///
+/// Amount of files transferred, as displayed by `TransferProgress`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FilesProgress {
+    pub done: u64,  // Files transferred
+    pub total: u64, // Files to transfer
+    pub rate: f64,  // Files per second
+}
+
+impl FilesProgress {
+    /// ### label
+    ///
+    /// Format files progress label
+    fn label(&self) -> String {
+        format!(
+            "{}/{} files ({:.2} files/s)",
+            self.done, self.total, self.rate
+        )
+    }
+}
+
+impl From<FilesProgress> for PropPayload {
+    fn from(progress: FilesProgress) -> Self {
+        PropPayload::Tup3((
+            PropValue::U64(progress.done),
+            PropValue::U64(progress.total),
+            PropValue::F64(progress.rate),
+        ))
+    }
+}
+
+impl From<Option<&PropPayload>> for FilesProgress {
+    fn from(payload: Option<&PropPayload>) -> Self {
+        match payload {
+            Some(PropPayload::Tup3((
+                PropValue::U64(done),
+                PropValue::U64(total),
+                PropValue::F64(rate),
+            ))) => FilesProgress {
+                done: *done,
+                total: *total,
+                rate: *rate,
+            },
+            _ => FilesProgress::default(),
+        }
+    }
+}
+
+pub struct TransferProgressPropsBuilder {
+    props: Option<Props>,
+}
+
+impl Default for TransferProgressPropsBuilder {
+    fn default() -> Self {
+        TransferProgressPropsBuilder {
+            props: Some(Props::default()),
+        }
+    }
+}
+
+impl PropsBuilder for TransferProgressPropsBuilder {
+    fn build(&mut self) -> Props {
+        self.props.take().unwrap()
+    }
+
+    fn hidden(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = false;
+        }
+        self
+    }
+
+    fn visible(&mut self) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.visible = true;
+        }
+        self
+    }
+}
+
+impl From<Props> for TransferProgressPropsBuilder {
+    fn from(props: Props) -> Self {
+        TransferProgressPropsBuilder { props: Some(props) }
+    }
+}
+
+impl TransferProgressPropsBuilder {
+    /// ### with_borders
+    ///
+    /// Set component borders style
+    pub fn with_borders(
+        &mut self,
+        borders: Borders,
+        variant: BorderType,
+        color: Color,
+    ) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.borders = BordersProps {
+                borders,
+                variant,
+                color,
+            }
+        }
+        self
+    }
+
+    /// ### with_title
+    ///
+    /// Set title, which describes the whole transfer
+    pub fn with_title<S: AsRef<str>>(&mut self, text: S, alignment: Alignment) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.title = Some(BlockTitle::new(text, alignment));
+        }
+        self
+    }
+
+    /// ### with_progbar_colors
+    ///
+    /// Set colors for overall and current file progress bars
+    pub fn with_progbar_colors(&mut self, overall: Color, current: Color) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_OVERALL_COLOR,
+                PropPayload::One(PropValue::Style(Style::default().fg(overall))),
+            );
+            props.own.insert(
+                PROP_CURRENT_COLOR,
+                PropPayload::One(PropValue::Style(Style::default().fg(current))),
+            );
+        }
+        self
+    }
+
+    /// ### with_overall
+    ///
+    /// Set progress for the whole transfer
+    pub fn with_overall(&mut self, progress: Progress) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(PROP_OVERALL, progress.into());
+        }
+        self
+    }
+
+    /// ### with_current
+    ///
+    /// Set name and progress of the file being transferred
+    pub fn with_current<S: AsRef<str>>(&mut self, name: S, progress: Progress) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(
+                PROP_CURRENT_NAME,
+                PropPayload::One(PropValue::Str(name.as_ref().to_string())),
+            );
+            props.own.insert(PROP_CURRENT, progress.into());
+        }
+        self
+    }
+
+    /// ### with_files
+    ///
+    /// Set amount of files transferred. Displayed only if the transfer has more than one file
+    pub fn with_files(&mut self, progress: FilesProgress) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props.own.insert(PROP_FILES, progress.into());
+        }
+        self
+    }
+
+    /// ### with_paused
+    ///
+    /// Set whether the transfer is paused
+    pub fn with_paused(&mut self, paused: bool) -> &mut Self {
+        if let Some(props) = self.props.as_mut() {
+            props
+                .own
+                .insert(PROP_PAUSED, PropPayload::One(PropValue::Bool(paused)));
+        }
+        self
+    }
+}
+
+// -- component
+
+/// ## TransferProgress
+///
+/// Dual progress bar, reporting the progress for the whole transfer and for the current file
+pub struct TransferProgress {
+    props: Props,
+}
+
+impl TransferProgress {
+    /// ### new
+    ///
+    /// Instantiates a new `TransferProgress`
+    pub fn new(props: Props) -> Self {
+        Self { props }
+    }
+
+    /// ### progbar_style
+    ///
+    /// Get style for progress bar
+    fn progbar_style(&self, key: &str) -> Style {
+        let style: Style = match self.props.own.get(key) {
+            Some(PropPayload::One(PropValue::Style(style))) => *style,
+            _ => Style::default(),
+        };
+        style.bg(Color::Black)
+    }
+
+    /// ### current_name
+    ///
+    /// Get name of file being transferred
+    fn current_name(&self) -> &str {
+        match self.props.own.get(PROP_CURRENT_NAME) {
+            Some(PropPayload::One(PropValue::Str(name))) => name.as_str(),
+            _ => "Please wait",
+        }
+    }
+
+    /// ### current_label
+    ///
+    /// Get the label displayed above the progress of the file being transferred
+    fn current_label(&self) -> String {
+        match self.props.own.get(PROP_PAUSED) {
+            Some(PropPayload::One(PropValue::Bool(true))) => {
+                format!("{} (paused; press <P> to resume)", self.current_name())
+            }
+            _ => self.current_name().to_string(),
+        }
+    }
+}
+
+impl Component for TransferProgress {
+    #[cfg(not(tarpaulin_include))]
+    fn render(&self, render: &mut Frame, area: Rect) {
+        if self.props.visible {
+            let block = get_block(&self.props.borders, self.props.title.as_ref(), true);
+            let inner: Rect = block.inner(area);
+            render.render_widget(block, area);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Length(1), // Overall
+                        Constraint::Length(1), // Files
+                        Constraint::Length(1), // Current file name
+                        Constraint::Length(1), // Current
+                        Constraint::Min(0),
+                    ]
+                    .as_ref(),
+                )
+                .split(inner);
+            let overall: Progress = Progress::from(self.props.own.get(PROP_OVERALL));
+            let current: Progress = Progress::from(self.props.own.get(PROP_CURRENT));
+            let files: FilesProgress = FilesProgress::from(self.props.own.get(PROP_FILES));
+            render.render_widget(
+                Gauge::default()
+                    .gauge_style(self.progbar_style(PROP_OVERALL_COLOR))
+                    .label(overall.label())
+                    .ratio(overall.ratio()),
+                chunks[0],
+            );
+            if files.total > 1 {
+                render.render_widget(
+                    Paragraph::new(files.label()).alignment(Alignment::Center),
+                    chunks[1],
+                );
+            }
+            render.render_widget(
+                Paragraph::new(self.current_label()).alignment(Alignment::Center),
+                chunks[2],
+            );
+            render.render_widget(
+                Gauge::default()
+                    .gauge_style(self.progbar_style(PROP_CURRENT_COLOR))
+                    .label(current.label())
+                    .ratio(current.ratio()),
+                chunks[3],
+            );
+        }
+    }
+
+    fn update(&mut self, props: Props) -> Msg {
+        self.props = props;
+        Msg::None
+    }
+
+    fn get_props(&self) -> Props {
+        self.props.clone()
+    }
+
+    fn on(&mut self, ev: Event) -> Msg {
+        match ev {
+            Event::Key(key) => Msg::OnKey(key),
+            _ => Msg::None,
+        }
+    }
+
+    fn get_state(&self) -> Payload {
+        Payload::None
+    }
+
+    fn blur(&mut self) {}
+
+    fn active(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::event::{KeyCode, KeyEvent};
+
+    #[test]
+    fn test_ui_components_transfer_progress() {
+        let mut component: TransferProgress = TransferProgress::new(
+            TransferProgressPropsBuilder::default()
+                .hidden()
+                .visible()
+                .with_borders(Borders::ALL, BorderType::Rounded, Color::Reset)
+                .with_title("Uploading 2 entries…", Alignment::Center)
+                .with_progbar_colors(Color::Green, Color::Blue)
+                .build(),
+        );
+        assert_eq!(component.props.visible, true);
+        assert_eq!(component.current_name(), "Please wait");
+        assert_eq!(
+            component.progbar_style(PROP_OVERALL_COLOR),
+            Style::default().fg(Color::Green).bg(Color::Black)
+        );
+        assert_eq!(
+            Progress::from(component.props.own.get(PROP_OVERALL)),
+            Progress::default()
+        );
+        // Update
+        let current: Progress = Progress {
+            written: 256,
+            total: 1024,
+            rate: 64,
+            eta: 12,
+        };
+        let props = TransferProgressPropsBuilder::from(component.get_props())
+            .with_overall(Progress {
+                written: 256,
+                total: 4096,
+                rate: 64,
+                eta: 60,
+            })
+            .with_current("omar.txt", current)
+            .with_files(FilesProgress {
+                done: 3,
+                total: 12,
+                rate: 1.5,
+            })
+            .build();
+        assert_eq!(component.update(props), Msg::None);
+        assert_eq!(component.current_name(), "omar.txt");
+        assert_eq!(component.current_label().as_str(), "omar.txt");
+        assert_eq!(
+            Progress::from(component.props.own.get(PROP_CURRENT)),
+            current
+        );
+        // Progress
+        assert_eq!(current.ratio(), 0.25);
+        assert_eq!(
+            current.label().as_str(),
+            "25.00% - 256 B/1.0 KB - ETA 00:12 (64 B/s)"
+        );
+        assert_eq!(Progress::default().ratio(), 0.0);
+        assert_eq!(
+            Progress::default().label().as_str(),
+            "0.00% - 0 B/0 B - ETA --:-- (0 B/s)"
+        );
+        // Files
+        let files: FilesProgress = FilesProgress::from(component.props.own.get(PROP_FILES));
+        assert_eq!(files.total, 12);
+        assert_eq!(files.label().as_str(), "3/12 files (1.50 files/s)");
+        // Paused
+        let props = TransferProgressPropsBuilder::from(component.get_props())
+            .with_paused(true)
+            .build();
+        assert_eq!(component.update(props), Msg::None);
+        assert_eq!(
+            component.current_label().as_str(),
+            "omar.txt (paused; press <P> to resume)"
+        );
+        // Events
+        assert_eq!(
+            component.on(Event::Key(KeyEvent::from(KeyCode::Char('c')))),
+            Msg::OnKey(KeyEvent::from(KeyCode::Char('c')))
+        );
+        assert_eq!(component.get_state(), Payload::None);
+    }
+}
@@ -26,33 +26,60 @@
  * SOFTWARE.
  */
 // Locals
+#[cfg(any(test, feature = "test-support"))]
+use super::input::EventQueue;
 use super::input::InputHandler;
+use super::resize::ResizeWatcher;
 use super::store::Store;
-use crate::filetransfer::FileTransferParams;
+use crate::filetransfer::{FileTransferParams, SlowLink};
 use crate::system::config_client::ConfigClient;
+use crate::system::metrics::Metrics;
 use crate::system::theme_provider::ThemeProvider;
 
 // Includes
 use crossterm::event::DisableMouseCapture;
 use crossterm::execute;
-use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
-use std::io::{stdout, Stdout};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+#[cfg(any(test, feature = "test-support"))]
+use std::cell::RefCell;
+use std::io::{self, stdout, Stdout};
+#[cfg(any(test, feature = "test-support"))]
+use std::rc::Rc;
+use std::time::Duration;
 use tuirealm::tui::backend::CrosstermBackend;
-use tuirealm::tui::Terminal;
+#[cfg(any(test, feature = "test-support"))]
+use tuirealm::tui::buffer::Buffer;
+use tuirealm::tui::layout::Rect;
+use tuirealm::tui::{Frame, Terminal};
+#[cfg(any(test, feature = "test-support"))]
+use tuirealm::tui::{TerminalOptions, Viewport};
 
 type TuiTerminal = Terminal<CrosstermBackend<Stdout>>;
 
+/// In-memory screen shared between an headless context and whoever wants to inspect what has been drawn
+#[cfg(any(test, feature = "test-support"))]
+pub type HeadlessScreen = Rc<RefCell<Buffer>>;
+
+const RESIZE_SETTLE_INTERVAL: Duration = Duration::from_millis(100);
+const TERMINAL_SIZE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// ## Context
 ///
 /// Context holds data structures used by the ui
 pub struct Context {
     ft_params: Option<FileTransferParams>,
+    slow_link: SlowLink,
+    safe_mode: bool,
     config_client: ConfigClient,
+    metrics: Metrics, // Transfers made since termscp started
     pub(crate) store: Store,
     input_hnd: InputHandler,
     pub(crate) terminal: TuiTerminal,
+    resize_watcher: Option<ResizeWatcher>,
     theme_provider: ThemeProvider,
     error: Option<String>,
+    #[cfg(any(test, feature = "test-support"))]
+    screen: Option<HeadlessScreen>,
 }
 
 impl Context {
@@ -69,21 +96,81 @@ impl Context {
         assert!(execute!(stdout, EnterAlternateScreen).is_ok());
         Context {
             ft_params: None,
+            slow_link: SlowLink::default(),
+            safe_mode: false,
             config_client,
+            metrics: Metrics::default(),
             store: Store::init(),
             input_hnd: InputHandler::new(),
             terminal: Terminal::new(CrosstermBackend::new(stdout)).unwrap(),
+            resize_watcher: Some(ResizeWatcher::new(
+                RESIZE_SETTLE_INTERVAL,
+                TERMINAL_SIZE_POLL_INTERVAL,
+            )),
             theme_provider,
             error,
+            #[cfg(any(test, feature = "test-support"))]
+            screen: None,
+        }
+    }
+
+    /// ### headless
+    ///
+    /// Instantiates a new Context which doesn't take control of the terminal.
+    /// The ui is drawn into `screen`, instead of stdout, and input events are read from `script`.
+    /// Resizing `screen` resizes the ui at the next draw
+    #[cfg(any(test, feature = "test-support"))]
+    pub(crate) fn headless(
+        config_client: ConfigClient,
+        theme_provider: ThemeProvider,
+        script: EventQueue,
+        screen: HeadlessScreen,
+    ) -> Context {
+        let area: Rect = screen.borrow().area;
+        Context {
+            ft_params: None,
+            slow_link: SlowLink::default(),
+            safe_mode: false,
+            config_client,
+            metrics: Metrics::default(),
+            store: Store::init(),
+            input_hnd: InputHandler::scripted(script),
+            terminal: Self::headless_terminal(area),
+            resize_watcher: None,
+            theme_provider,
+            error: None,
+            screen: Some(screen),
         }
     }
 
+    /// ### headless_terminal
+    ///
+    /// Instantiates a terminal laid out on a fixed `area`, which never queries the user's terminal
+    #[cfg(any(test, feature = "test-support"))]
+    fn headless_terminal(area: Rect) -> TuiTerminal {
+        let options = TerminalOptions {
+            viewport: Viewport::fixed(area),
+        };
+        Terminal::with_options(CrosstermBackend::new(stdout()), options).unwrap()
+    }
+
     // -- getters
 
     pub fn ft_params(&self) -> Option<&FileTransferParams> {
         self.ft_params.as_ref()
     }
 
+    pub fn slow_link(&self) -> SlowLink {
+        self.slow_link
+    }
+
+    /// ### safe_mode
+    ///
+    /// Returns whether termscp has been started without loading configuration, bookmarks and theme
+    pub fn safe_mode(&self) -> bool {
+        self.safe_mode
+    }
+
     pub fn config(&self) -> &ConfigClient {
         &self.config_client
     }
@@ -92,6 +179,10 @@ impl Context {
         &mut self.config_client
     }
 
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
     pub(crate) fn input_hnd(&self) -> &InputHandler {
         &self.input_hnd
     }
@@ -112,8 +203,50 @@ impl Context {
         &mut self.theme_provider
     }
 
-    pub fn terminal(&mut self) -> &mut TuiTerminal {
-        &mut self.terminal
+    /// ### draw
+    ///
+    /// Draw a frame on the terminal
+    pub fn draw<F>(&mut self, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut Frame<CrosstermBackend<Stdout>>),
+    {
+        #[cfg(any(test, feature = "test-support"))]
+        if let Some(screen) = self.screen.clone() {
+            self.draw_headless(&screen, f);
+            return Ok(());
+        }
+        self.terminal.draw(f).map(|_| ())
+    }
+
+    /// ### draw_headless
+    ///
+    /// Render a frame into the in-memory `screen`, without flushing anything to stdout
+    #[cfg(any(test, feature = "test-support"))]
+    fn draw_headless<F>(&mut self, screen: &HeadlessScreen, f: F)
+    where
+        F: FnOnce(&mut Frame<CrosstermBackend<Stdout>>),
+    {
+        let area: Rect = screen.borrow().area;
+        if self.terminal.get_frame().size() != area {
+            self.terminal = Self::headless_terminal(area);
+        }
+        f(&mut self.terminal.get_frame());
+        let buffer: &mut Buffer = self.terminal.current_buffer_mut();
+        screen.borrow_mut().clone_from(buffer);
+        buffer.reset();
+    }
+
+    /// ### is_headless
+    ///
+    /// Returns whether the context doesn't control the user's terminal
+    #[cfg(any(test, feature = "test-support"))]
+    fn is_headless(&self) -> bool {
+        self.screen.is_some()
+    }
+
+    #[cfg(not(any(test, feature = "test-support")))]
+    fn is_headless(&self) -> bool {
+        false
     }
 
     // -- setter
@@ -122,6 +255,14 @@ impl Context {
         self.ft_params = Some(params);
     }
 
+    pub fn set_slow_link(&mut self, link: SlowLink) {
+        self.slow_link = link;
+    }
+
+    pub fn set_safe_mode(&mut self, safe_mode: bool) {
+        self.safe_mode = safe_mode;
+    }
+
     // -- error
 
     /// ### set_error
@@ -143,6 +284,9 @@ impl Context {
     /// Enter alternate screen (gui window)
     #[cfg(not(target_os = "windows"))]
     pub fn enter_alternate_screen(&mut self) {
+        if self.is_headless() {
+            return;
+        }
         match execute!(
             self.terminal.backend_mut(),
             EnterAlternateScreen,
@@ -157,6 +301,9 @@ impl Context {
     ///
     /// Go back to normal screen (gui window)
     pub fn leave_alternate_screen(&mut self) {
+        if self.is_headless() {
+            return;
+        }
         match execute!(
             self.terminal.backend_mut(),
             LeaveAlternateScreen,
@@ -171,11 +318,50 @@ impl Context {
     ///
     /// Clear terminal screen
     pub fn clear_screen(&mut self) {
+        if self.is_headless() {
+            return;
+        }
         match self.terminal.clear() {
             Err(err) => error!("Failed to clear screen: {}", err),
             Ok(_) => info!("Cleared screen"),
         }
     }
+
+    /// ### on_resize
+    ///
+    /// Report a terminal resize event
+    pub(crate) fn on_resize(&mut self) {
+        if let Some(watcher) = self.resize_watcher.as_mut() {
+            watcher.on_resize();
+        }
+    }
+
+    /// ### refresh_terminal_size
+    ///
+    /// Check whether the terminal has been resized or reattached (e.g. tmux/mosh) and, in case,
+    /// resize the terminal to its actual size and clear it, so that the next draw repaints everything.
+    /// Returns whether the ui must be redrawn
+    pub(crate) fn refresh_terminal_size(&mut self) -> bool {
+        let watcher: &mut ResizeWatcher = match self.resize_watcher.as_mut() {
+            Some(watcher) if watcher.should_poll() => watcher,
+            _ => return false,
+        };
+        let (width, height) = match terminal::size() {
+            Ok(size) => size,
+            Err(err) => {
+                error!("Failed to get terminal size: {}", err);
+                return false;
+            }
+        };
+        if !watcher.check((width, height)) {
+            return false;
+        }
+        match self.terminal.resize(Rect::new(0, 0, width, height)) {
+            Err(err) => error!("Failed to resize terminal: {}", err),
+            Ok(_) => debug!("Terminal resized to {}x{}", width, height),
+        }
+        true
+    }
 }
 
 impl Drop for Context {
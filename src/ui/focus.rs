@@ -0,0 +1,137 @@
+//! ## Focus
+//!
+//! `focus` is the module which provides the focus ring, used to move focus between the
+//! components of a form
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// locals
+use super::keymap::{MSG_KEY_DOWN, MSG_KEY_TAB, MSG_KEY_UP};
+// ext
+use tuirealm::Msg;
+
+/// ## FocusRing
+///
+/// FocusRing describes the order in which the components of a form get focus.
+/// `<DOWN>` gives focus to the next component, `<UP>` to the previous one; if enabled, `<TAB>`
+/// behaves as `<DOWN>`. Focus wraps around at both ends of the ring.
+pub struct FocusRing {
+    components: &'static [&'static str],
+    tab: bool,
+}
+
+impl FocusRing {
+    /// ### new
+    ///
+    /// Instantiate a new `FocusRing` with the provided components
+    pub const fn new(components: &'static [&'static str]) -> Self {
+        Self {
+            components,
+            tab: false,
+        }
+    }
+
+    /// ### with_tab
+    ///
+    /// Instantiate a new `FocusRing` where `<TAB>` moves focus to the next component too
+    pub const fn with_tab(components: &'static [&'static str]) -> Self {
+        Self {
+            components,
+            tab: true,
+        }
+    }
+
+    /// ### next
+    ///
+    /// Get the component after `current`
+    pub fn next(&self, current: &str) -> Option<&'static str> {
+        let pos: usize = self.position(current)?;
+        self.components
+            .get((pos + 1) % self.components.len())
+            .copied()
+    }
+
+    /// ### previous
+    ///
+    /// Get the component before `current`
+    pub fn previous(&self, current: &str) -> Option<&'static str> {
+        let pos: usize = self.position(current)?;
+        self.components
+            .get((pos + self.components.len() - 1) % self.components.len())
+            .copied()
+    }
+
+    /// ### on_msg
+    ///
+    /// Get the component which should get focus after `component` has raised `msg`.
+    /// Returns `None` if `component` is not part of the ring or `msg` doesn't move focus
+    pub fn on_msg(&self, component: &str, msg: &Msg) -> Option<&'static str> {
+        if msg == &MSG_KEY_DOWN || (self.tab && msg == &MSG_KEY_TAB) {
+            self.next(component)
+        } else if msg == &MSG_KEY_UP {
+            self.previous(component)
+        } else {
+            None
+        }
+    }
+
+    fn position(&self, component: &str) -> Option<usize> {
+        self.components.iter().position(|x| *x == component)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ui::keymap::MSG_KEY_ENTER;
+
+    use pretty_assertions::assert_eq;
+
+    const RING: FocusRing = FocusRing::new(&["ADDR", "PORT", "USERNAME"]);
+
+    #[test]
+    fn test_ui_focus_ring() {
+        assert_eq!(RING.next("ADDR"), Some("PORT"));
+        assert_eq!(RING.next("USERNAME"), Some("ADDR"));
+        assert_eq!(RING.previous("ADDR"), Some("USERNAME"));
+        assert_eq!(RING.previous("PORT"), Some("ADDR"));
+        assert_eq!(RING.next("PASSWORD"), None);
+        assert_eq!(RING.previous("PASSWORD"), None);
+        // Messages
+        assert_eq!(RING.on_msg("PORT", &MSG_KEY_DOWN), Some("USERNAME"));
+        assert_eq!(RING.on_msg("PORT", &MSG_KEY_UP), Some("ADDR"));
+        assert_eq!(RING.on_msg("PORT", &MSG_KEY_TAB), None);
+        assert_eq!(RING.on_msg("PORT", &MSG_KEY_ENTER), None);
+        assert_eq!(RING.on_msg("PASSWORD", &MSG_KEY_DOWN), None);
+        // With tab
+        let ring: FocusRing = FocusRing::with_tab(&["HOST", "USERNAME"]);
+        assert_eq!(ring.on_msg("HOST", &MSG_KEY_TAB), Some("USERNAME"));
+        assert_eq!(ring.on_msg("USERNAME", &MSG_KEY_TAB), Some("HOST"));
+        assert_eq!(ring.on_msg("USERNAME", &MSG_KEY_UP), Some("HOST"));
+        // Empty
+        let ring: FocusRing = FocusRing::new(&[]);
+        assert_eq!(ring.on_msg("HOST", &MSG_KEY_DOWN), None);
+    }
+}
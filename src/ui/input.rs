@@ -25,21 +25,48 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-use crossterm::event::{poll, read, Event};
+use crossterm::event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+#[cfg(any(test, feature = "test-support"))]
+use std::rc::Rc;
 use std::time::Duration;
 
+/// Queue of scripted input events
+#[cfg(any(test, feature = "test-support"))]
+pub(crate) type EventQueue = Rc<RefCell<VecDeque<Event>>>;
+
 /// ## InputHandler
 ///
 /// InputHandler is the struct which runs a thread which waits for
 /// input events from the user and reports them through a receiver
-pub(crate) struct InputHandler;
+pub(crate) struct InputHandler {
+    pending: RefCell<VecDeque<Event>>, // Events read ahead, returned before reading new ones
+    #[cfg(any(test, feature = "test-support"))]
+    script: Option<EventQueue>, // Read events from here instead of the terminal
+}
 
 impl InputHandler {
     /// ### InputHandler
     ///
     ///
     pub(crate) fn new() -> InputHandler {
-        InputHandler {}
+        InputHandler {
+            pending: RefCell::new(VecDeque::new()),
+            #[cfg(any(test, feature = "test-support"))]
+            script: None,
+        }
+    }
+
+    /// ### scripted
+    ///
+    /// Instantiates a new InputHandler which reads events from `script` instead of the terminal
+    #[cfg(any(test, feature = "test-support"))]
+    pub(crate) fn scripted(script: EventQueue) -> InputHandler {
+        InputHandler {
+            pending: RefCell::new(VecDeque::new()),
+            script: Some(script),
+        }
     }
 
     /// ### fetch_events
@@ -64,6 +91,13 @@ impl InputHandler {
     ///
     /// Read event from input listener
     pub(crate) fn read_event(&self) -> Result<Option<Event>, ()> {
+        if let Some(ev) = self.pending.borrow_mut().pop_front() {
+            return Ok(Some(ev));
+        }
+        #[cfg(any(test, feature = "test-support"))]
+        if let Some(script) = self.script.as_ref() {
+            return Ok(script.borrow_mut().pop_front());
+        }
         if let Ok(available) = poll(Duration::from_millis(10)) {
             match available {
                 true => {
@@ -80,6 +114,51 @@ impl InputHandler {
             Err(())
         }
     }
+
+    /// ### read_burst
+    ///
+    /// Read the characters typed which are already available, without waiting.
+    /// Typed keys come one at a time, while text pasted into the terminal (e.g. the paths of files
+    /// dropped onto it) comes at once: the events read after a character tell whether it has been pasted.
+    /// The event ending the burst is kept for the next `read_event`. Scripted input never bursts
+    pub(crate) fn read_burst(&self) -> Vec<Event> {
+        let mut burst: Vec<Event> = Vec::new();
+        #[cfg(any(test, feature = "test-support"))]
+        if self.script.is_some() {
+            return burst;
+        }
+        while self.pending.borrow().is_empty() && matches!(poll(Duration::ZERO), Ok(true)) {
+            match read() {
+                Ok(ev) if is_typed_char(&ev) => burst.push(ev),
+                Ok(ev) => self.pending.borrow_mut().push_back(ev),
+                Err(_) => break,
+            }
+        }
+        burst
+    }
+
+    /// ### unread
+    ///
+    /// Put `events` back, so that they're returned by the next calls to `read_event`, in the same order
+    pub(crate) fn unread(&self, events: Vec<Event>) {
+        let mut pending = self.pending.borrow_mut();
+        for ev in events.into_iter().rev() {
+            pending.push_front(ev);
+        }
+    }
+}
+
+/// ### is_typed_char
+///
+/// Returns whether `ev` is a printable character, possibly typed with shift
+pub(crate) fn is_typed_char(ev: &Event) -> bool {
+    matches!(
+        ev,
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(_),
+            modifiers,
+        }) if (*modifiers - KeyModifiers::SHIFT).is_empty()
+    )
 }
 
 #[cfg(test)]
@@ -92,6 +171,57 @@ mod tests {
         let _: InputHandler = InputHandler::new();
     }
 
+    #[test]
+    fn test_ui_input_scripted() {
+        use crossterm::event::{KeyCode, KeyEvent};
+        let script: EventQueue = Rc::new(RefCell::new(VecDeque::new()));
+        let input_hnd: InputHandler = InputHandler::scripted(script.clone());
+        assert_eq!(input_hnd.read_event().ok().unwrap(), None);
+        script
+            .borrow_mut()
+            .push_back(Event::Key(KeyEvent::from(KeyCode::Enter)));
+        assert_eq!(
+            input_hnd.read_event().ok().unwrap(),
+            Some(Event::Key(KeyEvent::from(KeyCode::Enter)))
+        );
+        assert_eq!(input_hnd.fetch_events().ok().unwrap().len(), 0);
+        // Unread
+        assert!(input_hnd.read_burst().is_empty());
+        script
+            .borrow_mut()
+            .push_back(Event::Key(KeyEvent::from(KeyCode::Esc)));
+        input_hnd.unread(vec![
+            Event::Key(KeyEvent::from(KeyCode::Char('a'))),
+            Event::Key(KeyEvent::from(KeyCode::Char('b'))),
+        ]);
+        assert_eq!(
+            input_hnd.fetch_events().ok().unwrap(),
+            vec![
+                Event::Key(KeyEvent::from(KeyCode::Char('a'))),
+                Event::Key(KeyEvent::from(KeyCode::Char('b'))),
+                Event::Key(KeyEvent::from(KeyCode::Esc)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ui_input_is_typed_char() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        assert!(is_typed_char(&Event::Key(KeyEvent::from(KeyCode::Char(
+            '/'
+        )))));
+        assert!(is_typed_char(&Event::Key(KeyEvent::new(
+            KeyCode::Char('A'),
+            KeyModifiers::SHIFT
+        ))));
+        assert!(!is_typed_char(&Event::Key(KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL
+        ))));
+        assert!(!is_typed_char(&Event::Key(KeyEvent::from(KeyCode::Enter))));
+        assert!(!is_typed_char(&Event::Resize(80, 24)));
+    }
+
     /* ERRORS ON GITHUB ACTIONS
     #[test]
     fn test_ui_input_fetch() {
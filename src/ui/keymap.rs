@@ -70,6 +70,21 @@ pub const MSG_KEY_SPACE: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char(' '),
     modifiers: KeyModifiers::NONE,
 });
+pub const MSG_KEY_AT: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('@'),
+    modifiers: KeyModifiers::NONE,
+});
+pub const MSG_KEY_SLASH: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('/'),
+    modifiers: KeyModifiers::NONE,
+});
+
+// -- function keys
+
+pub const MSG_KEY_F2: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::F(2),
+    modifiers: KeyModifiers::NONE,
+});
 
 // -- char keys
 
@@ -109,7 +124,6 @@ pub const MSG_KEY_CHAR_I: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('i'),
     modifiers: KeyModifiers::NONE,
 });
-/*
 pub const MSG_KEY_CHAR_J: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('j'),
     modifiers: KeyModifiers::NONE,
@@ -118,17 +132,14 @@ pub const MSG_KEY_CHAR_K: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('k'),
     modifiers: KeyModifiers::NONE,
 });
-*/
 pub const MSG_KEY_CHAR_L: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('l'),
     modifiers: KeyModifiers::NONE,
 });
-/*
-pub const MSG_KEY_CHAR_M: Msg = Msg::OnKey(KeyEvent { NOTE: used for mark
+pub const MSG_KEY_CHAR_M: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('m'),
     modifiers: KeyModifiers::NONE,
 });
-*/
 pub const MSG_KEY_CHAR_N: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('n'),
     modifiers: KeyModifiers::NONE,
@@ -137,12 +148,10 @@ pub const MSG_KEY_CHAR_O: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('o'),
     modifiers: KeyModifiers::NONE,
 });
-/*
 pub const MSG_KEY_CHAR_P: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('p'),
     modifiers: KeyModifiers::NONE,
 });
-*/
 pub const MSG_KEY_CHAR_Q: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('q'),
     modifiers: KeyModifiers::NONE,
@@ -155,12 +164,10 @@ pub const MSG_KEY_CHAR_S: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('s'),
     modifiers: KeyModifiers::NONE,
 });
-/*
 pub const MSG_KEY_CHAR_T: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('t'),
     modifiers: KeyModifiers::NONE,
 });
-*/
 pub const MSG_KEY_CHAR_U: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('u'),
     modifiers: KeyModifiers::NONE,
@@ -181,14 +188,78 @@ pub const MSG_KEY_CHAR_Y: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('y'),
     modifiers: KeyModifiers::NONE,
 });
-/*
 pub const MSG_KEY_CHAR_Z: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('z'),
     modifiers: KeyModifiers::NONE,
 });
-*/
+
+// -- shift
+pub const MSG_KEY_SHIFT_A: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('A'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_C: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('C'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_D: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('D'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_E: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('E'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_G: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('G'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_M: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('M'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_O: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('O'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_P: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('P'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_S: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('S'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_T: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('T'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_U: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('U'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_V: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('V'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_Q: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('Q'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_W: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('W'),
+    modifiers: KeyModifiers::SHIFT,
+});
+pub const MSG_KEY_SHIFT_X: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('X'),
+    modifiers: KeyModifiers::SHIFT,
+});
 
 // -- control
+pub const MSG_KEY_CTRL_A: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('a'),
+    modifiers: KeyModifiers::CONTROL,
+});
 pub const MSG_KEY_CTRL_C: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('c'),
     modifiers: KeyModifiers::CONTROL,
@@ -205,6 +276,10 @@ pub const MSG_KEY_CTRL_N: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('n'),
     modifiers: KeyModifiers::CONTROL,
 });
+pub const MSG_KEY_CTRL_P: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('p'),
+    modifiers: KeyModifiers::CONTROL,
+});
 pub const MSG_KEY_CTRL_R: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('r'),
     modifiers: KeyModifiers::CONTROL,
@@ -213,3 +288,19 @@ pub const MSG_KEY_CTRL_S: Msg = Msg::OnKey(KeyEvent {
     code: KeyCode::Char('s'),
     modifiers: KeyModifiers::CONTROL,
 });
+pub const MSG_KEY_CTRL_T: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('t'),
+    modifiers: KeyModifiers::CONTROL,
+});
+pub const MSG_KEY_CTRL_U: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('u'),
+    modifiers: KeyModifiers::CONTROL,
+});
+pub const MSG_KEY_CTRL_V: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('v'),
+    modifiers: KeyModifiers::CONTROL,
+});
+pub const MSG_KEY_CTRL_X: Msg = Msg::OnKey(KeyEvent {
+    code: KeyCode::Char('x'),
+    modifiers: KeyModifiers::CONTROL,
+});
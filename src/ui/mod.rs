@@ -29,6 +29,9 @@
 pub mod activities;
 pub(crate) mod components;
 pub mod context;
+pub(crate) mod focus;
 pub(crate) mod input;
 pub(crate) mod keymap;
+pub(crate) mod modal;
+pub(crate) mod resize;
 pub(crate) mod store;
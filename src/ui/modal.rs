@@ -0,0 +1,180 @@
+//! ## Modal
+//!
+//! `modal` is the module which provides the modal stack, used to keep track of the popups
+//! displayed on top of an activity and of the component which must get focus when they're closed
+
+/*
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+/// ## ModalStack
+///
+/// ModalStack keeps the popups currently displayed, from the bottom to the top one.
+/// Only the popup on top can have focus; once it is closed, focus goes back to the popup below it,
+/// or, if no popup is left, to the base component of the activity (e.g. the explorer).
+/// Each popup is identified by the component it has been opened with, but the focus
+/// may be moved to another component of the same popup (e.g. in a form).
+pub struct ModalStack {
+    base: &'static str,
+    modals: Vec<Modal>,
+}
+
+/// ## Modal
+///
+/// A popup in the modal stack
+struct Modal {
+    id: &'static str,
+    focus: &'static str,
+}
+
+impl ModalStack {
+    /// ### new
+    ///
+    /// Instantiate a new empty `ModalStack`, with `base` as the component to focus when no popup is displayed
+    pub fn new(base: &'static str) -> Self {
+        Self {
+            base,
+            modals: Vec::new(),
+        }
+    }
+
+    /// ### open
+    ///
+    /// Put the popup `id` on top of the stack. If the popup was already open, it is moved on top
+    pub fn open(&mut self, id: &'static str) {
+        self.modals.retain(|x| x.id != id);
+        self.modals.push(Modal { id, focus: id });
+    }
+
+    /// ### close
+    ///
+    /// Remove the popup `id` from the stack, wherever it is.
+    /// Returns the component which must get focus afterwards
+    pub fn close(&mut self, id: &str) -> &'static str {
+        self.modals.retain(|x| x.id != id);
+        self.focus()
+    }
+
+    /// ### move_focus
+    ///
+    /// Give focus to `component`, which belongs to the popup on top of the stack.
+    /// If no popup is displayed, `component` becomes the base component instead
+    pub fn move_focus(&mut self, component: &'static str) {
+        match self.modals.last_mut() {
+            Some(top) => top.focus = component,
+            None => self.base = component,
+        }
+    }
+
+    /// ### clear
+    ///
+    /// Remove all the popups from the stack, e.g. once the view has been rebuilt
+    pub fn clear(&mut self) {
+        self.modals.clear();
+    }
+
+    /// ### set_base
+    ///
+    /// Set the component to focus when no popup is displayed
+    pub fn set_base(&mut self, base: &'static str) {
+        self.base = base;
+    }
+
+    /// ### focus
+    ///
+    /// Get the component which should have focus
+    pub fn focus(&self) -> &'static str {
+        self.modals.last().map(|x| x.focus).unwrap_or(self.base)
+    }
+
+    /// ### is_open
+    ///
+    /// Returns whether the popup `id` is in the stack
+    pub fn is_open(&self, id: &str) -> bool {
+        self.modals.iter().any(|x| x.id == id)
+    }
+
+    /// ### is_empty
+    ///
+    /// Returns whether no popup is displayed
+    pub fn is_empty(&self) -> bool {
+        self.modals.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_modal_stack() {
+        let mut stack: ModalStack = ModalStack::new("EXPLORER");
+        assert!(stack.is_empty());
+        assert_eq!(stack.focus(), "EXPLORER");
+        // Nested popups
+        stack.open("QUEUE");
+        stack.open("PROGRESS");
+        stack.open("ERROR");
+        assert_eq!(stack.is_empty(), false);
+        assert!(stack.is_open("PROGRESS"));
+        assert_eq!(stack.focus(), "ERROR");
+        // Close popup in the middle; focus stays on top
+        assert_eq!(stack.close("PROGRESS"), "ERROR");
+        assert_eq!(stack.is_open("PROGRESS"), false);
+        assert_eq!(stack.close("ERROR"), "QUEUE");
+        // Closing a popup which is not open doesn't change anything
+        assert_eq!(stack.close("ERROR"), "QUEUE");
+        assert_eq!(stack.close("QUEUE"), "EXPLORER");
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_ui_modal_stack_focus() {
+        let mut stack: ModalStack = ModalStack::new("EXPLORER");
+        // Move focus inside form
+        stack.open("FORM_NAME");
+        stack.move_focus("FORM_MODE");
+        assert_eq!(stack.focus(), "FORM_MODE");
+        stack.open("ERROR");
+        assert_eq!(stack.close("ERROR"), "FORM_MODE");
+        // Reopen moves popup on top and resets focus
+        stack.open("WAIT");
+        stack.open("FORM_NAME");
+        assert_eq!(stack.focus(), "FORM_NAME");
+        assert_eq!(stack.close("FORM_NAME"), "WAIT");
+        assert_eq!(stack.close("WAIT"), "EXPLORER");
+        // Base
+        stack.move_focus("LOG");
+        assert_eq!(stack.focus(), "LOG");
+        stack.set_base("REMOTE");
+        assert_eq!(stack.focus(), "REMOTE");
+        // Clear
+        stack.open("ERROR");
+        stack.clear();
+        assert!(stack.is_empty());
+        assert_eq!(stack.focus(), "REMOTE");
+    }
+}
@@ -0,0 +1,124 @@
+//! ## Resize
+//!
+//! `resize` is the module which detects terminal resizes and reattaches
+
+/**
+ * MIT License
+ *
+ * termscp - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::time::{Duration, Instant};
+
+/// ## ResizeWatcher
+///
+/// ResizeWatcher keeps track of the terminal size, in order to detect when the ui must be laid out
+/// and repainted from scratch.
+/// This happens after a resize, but also after reattaching a tmux/mosh session, where the terminal
+/// may either send a storm of resize events, reporting stale sizes, or no event at all.
+pub(crate) struct ResizeWatcher {
+    size: Option<(u16, u16)>, // Size the ui has been laid out for
+    pending: Option<Instant>, // Time of the last resize event not handled yet
+    last_poll: Instant,       // Last time the terminal size has been polled
+    settle: Duration,         // Time to wait after the last resize event, before repainting
+    poll_interval: Duration,  // Interval between terminal size polls, when no event is pending
+}
+
+impl ResizeWatcher {
+    /// ### new
+    ///
+    /// Instantiates a new `ResizeWatcher`
+    pub(crate) fn new(settle: Duration, poll_interval: Duration) -> Self {
+        Self {
+            size: None,
+            pending: None,
+            last_poll: Instant::now(),
+            settle,
+            poll_interval,
+        }
+    }
+
+    /// ### on_resize
+    ///
+    /// Report a resize event.
+    /// The size reported by the event is ignored, since it may be stale
+    pub(crate) fn on_resize(&mut self) {
+        self.pending = Some(Instant::now());
+    }
+
+    /// ### should_poll
+    ///
+    /// Returns whether the terminal size should be polled now
+    pub(crate) fn should_poll(&self) -> bool {
+        match self.pending {
+            Some(t) => t.elapsed() >= self.settle,
+            None => self.last_poll.elapsed() >= self.poll_interval,
+        }
+    }
+
+    /// ### check
+    ///
+    /// Check the actual terminal size.
+    /// Returns whether the ui must be laid out and fully repainted
+    pub(crate) fn check(&mut self, size: (u16, u16)) -> bool {
+        self.last_poll = Instant::now();
+        let resized: bool = self.pending.take().is_some();
+        let changed: bool = self.size.map(|x| x != size).unwrap_or(false);
+        self.size = Some(size);
+        resized || changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ui_resize_watcher() {
+        let mut watcher: ResizeWatcher =
+            ResizeWatcher::new(Duration::from_secs(0), Duration::from_secs(0));
+        assert_eq!(watcher.should_poll(), true);
+        // First check only records the size
+        assert_eq!(watcher.check((80, 24)), false);
+        assert_eq!(watcher.check((80, 24)), false);
+        // Size changed without any event (e.g. reattach)
+        assert_eq!(watcher.check((120, 40)), true);
+        assert_eq!(watcher.check((120, 40)), false);
+        // Resize event with the same size must repaint anyway
+        watcher.on_resize();
+        watcher.on_resize();
+        assert_eq!(watcher.should_poll(), true);
+        assert_eq!(watcher.check((120, 40)), true);
+        assert_eq!(watcher.check((120, 40)), false);
+    }
+
+    #[test]
+    fn test_ui_resize_watcher_settle() {
+        let mut watcher: ResizeWatcher =
+            ResizeWatcher::new(Duration::from_secs(60), Duration::from_secs(60));
+        assert_eq!(watcher.should_poll(), false);
+        // Wait for events to settle
+        watcher.on_resize();
+        assert_eq!(watcher.should_poll(), false);
+    }
+}
@@ -168,6 +168,13 @@ impl Store {
     pub fn set(&mut self, key: &str) {
         self.store.insert(key.to_string(), StoreState::Flag);
     }
+
+    /// ### unset
+    ///
+    /// Remove a key from the store
+    pub fn unset(&mut self, key: &str) {
+        self.store.remove(key);
+    }
 }
 
 #[cfg(test)]
@@ -201,6 +208,9 @@ mod tests {
         // Test flag
         store.set("myflag");
         assert_eq!(store.isset("myflag"), true);
+        // Test unset
+        store.unset("myflag");
+        assert_eq!(store.isset("myflag"), false);
         // Test unexisting
         assert!(store.get_boolean("unexisting-key").is_none());
         assert!(store.get_float("unexisting-key").is_none());
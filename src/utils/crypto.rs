@@ -26,7 +26,39 @@
  * SOFTWARE.
  */
 // Ext
-use magic_crypt::MagicCryptTrait;
+use hmac::{Hmac, Mac, NewMac};
+use magic_crypt::{MagicCryptError, MagicCryptTrait};
+use pbkdf2::pbkdf2;
+use rand::{thread_rng, RngCore};
+use sha2::Sha256;
+use thiserror::Error;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const ENVELOPE_PREFIX: &str = "$kdf1$";
+const ENVELOPE_ROUNDS: u32 = 10_000;
+const ENVELOPE_SALT_LEN: usize = 16;
+/// Envelopes sealed with a password store the amount of rounds after the prefix
+const PASSWORD_ENVELOPE_PREFIX: &str = "$kdf2$";
+#[cfg(not(test))]
+const PASSWORD_ENVELOPE_ROUNDS: u32 = 310_000;
+#[cfg(test)]
+const PASSWORD_ENVELOPE_ROUNDS: u32 = 1_000;
+/// Bound to the rounds of an envelope to open, so that a forged envelope can't hang termscp
+const PASSWORD_ENVELOPE_MAX_ROUNDS: u32 = 10_000_000;
+
+/// ## CryptoError
+///
+/// Describes an error while opening a secret
+#[derive(Debug, Error)]
+pub enum CryptoError {
+    #[error("Malformed secret")]
+    Malformed,
+    #[error("Integrity check failed")]
+    Integrity,
+    #[error("Decryption failed: {0}")]
+    Decrypt(#[from] MagicCryptError),
+}
 
 /// ### aes128_b64_crypt
 ///
@@ -44,6 +76,173 @@ pub fn aes128_b64_decrypt(key: &str, secret: &str) -> Result<String, magic_crypt
     crypter.decrypt_base64_to_string(secret.to_string())
 }
 
+/// ### seal
+///
+/// Encrypt `input` into an envelope: a random salt, combined with `context` (e.g. the host the secret belongs to),
+/// is used to derive, through PBKDF2-HMAC-SHA256, a key to encrypt `input` with and a key to authenticate it with.
+/// The envelope can be opened only with the same `key` and `context`.
+/// `key` must be a random key; use `seal_with_password` to encrypt with a password chosen by the user
+pub fn seal(key: &str, context: &str, input: &str) -> String {
+    format!(
+        "{}{}",
+        ENVELOPE_PREFIX,
+        seal_envelope(key, context, input, ENVELOPE_ROUNDS)
+    )
+}
+
+/// ### seal_with_password
+///
+/// Encrypt `input` into an envelope as `seal` does, but derive the keys from `password` with
+/// many more rounds, to slow down guessing it. The amount of rounds is stored in the envelope
+pub fn seal_with_password(password: &str, context: &str, input: &str) -> String {
+    format!(
+        "{}{}${}",
+        PASSWORD_ENVELOPE_PREFIX,
+        PASSWORD_ENVELOPE_ROUNDS,
+        seal_envelope(password, context, input, PASSWORD_ENVELOPE_ROUNDS)
+    )
+}
+
+/// ### open
+///
+/// Open an envelope made with `seal` or `seal_with_password`, checking its integrity
+pub fn open(key: &str, context: &str, envelope: &str) -> Result<String, CryptoError> {
+    if let Some(envelope) = envelope.strip_prefix(PASSWORD_ENVELOPE_PREFIX) {
+        let (rounds, envelope): (&str, &str) =
+            envelope.split_once('$').ok_or(CryptoError::Malformed)?;
+        let rounds: u32 = rounds.parse().map_err(|_| CryptoError::Malformed)?;
+        if rounds == 0 || rounds > PASSWORD_ENVELOPE_MAX_ROUNDS {
+            return Err(CryptoError::Malformed);
+        }
+        return open_envelope(key, context, envelope, rounds);
+    }
+    let envelope: &str = envelope
+        .strip_prefix(ENVELOPE_PREFIX)
+        .ok_or(CryptoError::Malformed)?;
+    open_envelope(key, context, envelope, ENVELOPE_ROUNDS)
+}
+
+/// ### is_sealed
+///
+/// Returns whether `secret` is an envelope made with `seal` or `seal_with_password`
+pub fn is_sealed(secret: &str) -> bool {
+    secret.starts_with(ENVELOPE_PREFIX) || secret.starts_with(PASSWORD_ENVELOPE_PREFIX)
+}
+
+/// ### seal_envelope
+///
+/// Encrypt `input`, deriving the keys with `rounds`; returns salt, secret and mac separated by `$`
+fn seal_envelope(key: &str, context: &str, input: &str, rounds: u32) -> String {
+    let mut salt: [u8; ENVELOPE_SALT_LEN] = [0; ENVELOPE_SALT_LEN];
+    thread_rng().fill_bytes(&mut salt);
+    let (crypt_key, mac_key): (String, [u8; 32]) = envelope_keys(key, context, &salt, rounds);
+    let secret: String = new_magic_crypt!(crypt_key, 256).encrypt_str_to_base64(input);
+    let mac = envelope_mac(&mac_key, context, &salt, secret.as_str()).finalize();
+    format!(
+        "{}${}${}",
+        base64::encode(salt),
+        secret,
+        base64::encode(mac.into_bytes())
+    )
+}
+
+/// ### open_envelope
+///
+/// Open the salt, secret and mac sealed by `seal_envelope` with the same `rounds`
+fn open_envelope(
+    key: &str,
+    context: &str,
+    envelope: &str,
+    rounds: u32,
+) -> Result<String, CryptoError> {
+    let tokens: Vec<&str> = envelope.split('$').collect();
+    let (salt, secret, mac): (Vec<u8>, &str, Vec<u8>) = match tokens.as_slice() {
+        [salt, secret, mac] => (
+            base64::decode(salt).map_err(|_| CryptoError::Malformed)?,
+            *secret,
+            base64::decode(mac).map_err(|_| CryptoError::Malformed)?,
+        ),
+        _ => return Err(CryptoError::Malformed),
+    };
+    let (crypt_key, mac_key): (String, [u8; 32]) = envelope_keys(key, context, &salt, rounds);
+    // Mac is compared in constant time
+    envelope_mac(&mac_key, context, &salt, secret)
+        .verify(&mac)
+        .map_err(|_| CryptoError::Integrity)?;
+    new_magic_crypt!(crypt_key, 256)
+        .decrypt_base64_to_string(secret)
+        .map_err(CryptoError::from)
+}
+
+/// ### envelope_keys
+///
+/// Derive the encryption key (as hex string) and the authentication key for an envelope
+fn envelope_keys(key: &str, context: &str, salt: &[u8], rounds: u32) -> (String, [u8; 32]) {
+    let mut salt: Vec<u8> = salt.to_vec();
+    salt.extend_from_slice(context.as_bytes());
+    let mut derived: [u8; 64] = [0; 64];
+    pbkdf2::<HmacSha256>(key.as_bytes(), &salt, rounds, &mut derived);
+    let crypt_key: String = derived[..32].iter().map(|x| format!("{:02x}", x)).collect();
+    let mut mac_key: [u8; 32] = [0; 32];
+    mac_key.copy_from_slice(&derived[32..]);
+    (crypt_key, mac_key)
+}
+
+/// ### envelope_mac
+///
+/// Authenticate the content of an envelope with `mac_key`
+fn envelope_mac(mac_key: &[u8], context: &str, salt: &[u8], secret: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_varkey(mac_key).expect("HMAC accepts keys of any size");
+    mac.update(context.as_bytes());
+    mac.update(salt);
+    mac.update(secret.as_bytes());
+    mac
+}
+
+/// ## PasswordStrength
+///
+/// How hard a password is to guess
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordStrength {
+    Weak,
+    Fair,
+    Strong,
+}
+
+impl PasswordStrength {
+    /// ### as_str
+    ///
+    /// Get the strength name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Weak => "weak",
+            Self::Fair => "fair",
+            Self::Strong => "strong",
+        }
+    }
+}
+
+/// ### password_strength
+///
+/// Estimate the strength of `password` from its length and the classes of characters it uses
+/// (lowercase and uppercase letters, digits and symbols)
+pub fn password_strength(password: &str) -> PasswordStrength {
+    let classes: usize = [
+        password.chars().any(|c| c.is_lowercase()),
+        password.chars().any(|c| c.is_uppercase()),
+        password.chars().any(|c| c.is_numeric()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ]
+    .iter()
+    .filter(|x| **x)
+    .count();
+    match (password.chars().count(), classes) {
+        (len, classes) if len >= 16 || (len >= 12 && classes >= 3) => PasswordStrength::Strong,
+        (len, classes) if len >= 8 && classes >= 2 => PasswordStrength::Fair,
+        _ => PasswordStrength::Weak,
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -65,4 +264,99 @@ mod tests {
             input
         );
     }
+
+    #[test]
+    fn test_utils_crypto_envelope() {
+        let key: &str = "MYSUPERSECRETKEY";
+        let context: &str = "SFTP://pi@192.168.1.31:22";
+        let envelope: String = seal(key, context, "Hello world!");
+        assert!(is_sealed(envelope.as_str()));
+        assert!(!is_sealed("z4Z6LpcpYqBW4+bkIok+5A=="));
+        assert_eq!(
+            open(key, context, envelope.as_str()).ok().unwrap().as_str(),
+            "Hello world!"
+        );
+        // Salt is random
+        assert_ne!(envelope, seal(key, context, "Hello world!"));
+        // Wrong key or context
+        assert!(open("ANOTHERKEY", context, envelope.as_str()).is_err());
+        assert!(open(key, "SFTP://pi@192.168.1.32:22", envelope.as_str()).is_err());
+        // Tampered envelope
+        let tampered: String = envelope.replacen("$kdf1$", "$kdf1$AAAA", 1);
+        assert!(matches!(
+            open(key, context, tampered.as_str()),
+            Err(CryptoError::Integrity) | Err(CryptoError::Malformed)
+        ));
+        assert!(matches!(
+            open(key, context, "$kdf1$bidoof"),
+            Err(CryptoError::Malformed)
+        ));
+        assert!(open(key, context, "z4Z6LpcpYqBW4+bkIok+5A==").is_err());
+    }
+
+    #[test]
+    fn test_utils_crypto_password_envelope() {
+        let context: &str = "bookmarks";
+        let envelope: String = seal_with_password("omar", context, "Hello world!");
+        assert!(is_sealed(envelope.as_str()));
+        assert!(envelope.starts_with(
+            format!("{}{}$", PASSWORD_ENVELOPE_PREFIX, PASSWORD_ENVELOPE_ROUNDS).as_str()
+        ));
+        assert_eq!(
+            open("omar", context, envelope.as_str())
+                .ok()
+                .unwrap()
+                .as_str(),
+            "Hello world!"
+        );
+        assert!(open("pippo", context, envelope.as_str()).is_err());
+        // Rounds are authenticated through the derived keys
+        let tampered: String = envelope.replacen(
+            format!("${}$", PASSWORD_ENVELOPE_ROUNDS).as_str(),
+            "$1001$",
+            1,
+        );
+        assert!(matches!(
+            open("omar", context, tampered.as_str()),
+            Err(CryptoError::Integrity)
+        ));
+        // Rounds out of bounds
+        for rounds in ["0", "4294967295", "many"].iter() {
+            let forged: String = envelope.replacen(
+                format!("${}$", PASSWORD_ENVELOPE_ROUNDS).as_str(),
+                format!("${}$", rounds).as_str(),
+                1,
+            );
+            assert!(matches!(
+                open("omar", context, forged.as_str()),
+                Err(CryptoError::Malformed)
+            ));
+        }
+        // Envelopes sealed with a key are still opened
+        let envelope: String = seal("omar", context, "Hello world!");
+        assert_eq!(
+            open("omar", context, envelope.as_str())
+                .ok()
+                .unwrap()
+                .as_str(),
+            "Hello world!"
+        );
+    }
+
+    #[test]
+    fn test_utils_crypto_password_strength() {
+        assert_eq!(password_strength(""), PasswordStrength::Weak);
+        assert_eq!(password_strength("password"), PasswordStrength::Weak);
+        assert_eq!(password_strength("omar1234"), PasswordStrength::Fair);
+        assert_eq!(password_strength("Omar-1234"), PasswordStrength::Fair);
+        assert_eq!(
+            password_strength("Omar-1234-pippo"),
+            PasswordStrength::Strong
+        );
+        assert_eq!(
+            password_strength("correcthorsebatterystaple"),
+            PasswordStrength::Strong
+        );
+        assert_eq!(PasswordStrength::Fair.as_str(), "fair");
+    }
 }
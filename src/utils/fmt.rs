@@ -27,11 +27,183 @@
  */
 use crate::fs::UnixPex;
 
+use bytesize::ByteSize;
+use chrono::format::{Item, StrftimeItems};
 use chrono::prelude::*;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::RwLock;
 use std::time::{Duration, SystemTime};
 use tuirealm::tui::style::Color;
 
+/// Date format used when none is configured
+pub const DEFAULT_DATE_FORMAT: &str = "%b %d %Y";
+/// Marker secrets are replaced with by `mask_secret`
+const SECRET_MARKER: &str = "********";
+
+lazy_static! {
+    static ref DISPLAY_FORMAT: RwLock<DisplayFormat> = RwLock::new(DisplayFormat::default());
+}
+
+/// ## SizeUnits
+///
+/// Units byte sizes are displayed with
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SizeUnits {
+    Si,     // Powers of 1000 (kB, MB, ...)
+    Binary, // Powers of 1024 (KiB, MiB, ...)
+}
+
+impl std::fmt::Display for SizeUnits {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            SizeUnits::Si => "si",
+            SizeUnits::Binary => "binary",
+        })
+    }
+}
+
+impl FromStr for SizeUnits {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "si" => Ok(SizeUnits::Si),
+            "binary" => Ok(SizeUnits::Binary),
+            _ => Err(()),
+        }
+    }
+}
+
+/// ## ClockFormat
+///
+/// Clock times are displayed with
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ClockFormat {
+    H24,
+    H12,
+}
+
+impl std::fmt::Display for ClockFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            ClockFormat::H24 => "24h",
+            ClockFormat::H12 => "12h",
+        })
+    }
+}
+
+impl FromStr for ClockFormat {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "24h" => Ok(ClockFormat::H24),
+            "12h" => Ok(ClockFormat::H12),
+            _ => Err(()),
+        }
+    }
+}
+
+/// ## DisplayFormat
+///
+/// Conventions used to display sizes and times across the interface
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DisplayFormat {
+    pub size_units: SizeUnits,
+    pub clock: ClockFormat,
+    pub date_format: String, // strftime syntax
+}
+
+impl Default for DisplayFormat {
+    fn default() -> Self {
+        Self {
+            size_units: SizeUnits::Si,
+            clock: ClockFormat::H24,
+            date_format: String::from(DEFAULT_DATE_FORMAT),
+        }
+    }
+}
+
+impl DisplayFormat {
+    /// ### size
+    ///
+    /// Format `bytes` with the configured units
+    pub fn size(&self, bytes: u64) -> String {
+        match self.size_units {
+            SizeUnits::Si => ByteSize(bytes).to_string(),
+            SizeUnits::Binary => {
+                // NOTE: bytesize prints binary units as "kiB"
+                const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+                if bytes < 1024 {
+                    return format!("{} B", bytes);
+                }
+                let mut size: f64 = bytes as f64 / 1024.0;
+                let mut unit: usize = 0;
+                while size >= 1024.0 && unit < UNITS.len() - 1 {
+                    size /= 1024.0;
+                    unit += 1;
+                }
+                format!("{:.1} {}", size, UNITS[unit])
+            }
+        }
+    }
+
+    /// ### time_format
+    ///
+    /// Get the strftime syntax of the clock time, with or without seconds
+    pub fn time_format(&self, seconds: bool) -> &'static str {
+        match (self.clock, seconds) {
+            (ClockFormat::H24, false) => "%H:%M",
+            (ClockFormat::H24, true) => "%H:%M:%S",
+            (ClockFormat::H12, false) => "%I:%M %p",
+            (ClockFormat::H12, true) => "%I:%M:%S %p",
+        }
+    }
+
+    /// ### datetime_format
+    ///
+    /// Get the strftime syntax of date and clock time, with or without seconds
+    pub fn datetime_format(&self, seconds: bool) -> String {
+        format!("{} {}", self.date_format, self.time_format(seconds))
+    }
+}
+
+/// ### set_display_format
+///
+/// Set the conventions used by `fmt_size` and `fmt_datetime`
+pub fn set_display_format(format: DisplayFormat) {
+    if let Ok(mut current) = DISPLAY_FORMAT.write() {
+        *current = format;
+    }
+}
+
+/// ### display_format
+///
+/// Get the conventions used to display sizes and times
+pub fn display_format() -> DisplayFormat {
+    DISPLAY_FORMAT.read().map(|x| x.clone()).unwrap_or_default()
+}
+
+/// ### is_valid_time_format
+///
+/// Returns whether `fmt` is a strftime syntax chrono is able to format
+pub fn is_valid_time_format(fmt: &str) -> bool {
+    !StrftimeItems::new(fmt).any(|x| x == Item::Error)
+}
+
+/// ### fmt_size
+///
+/// Format `bytes` with the configured units
+pub fn fmt_size(bytes: u64) -> String {
+    display_format().size(bytes)
+}
+
+/// ### fmt_datetime
+///
+/// Format `time` with the configured date and clock formats, without seconds
+pub fn fmt_datetime(time: SystemTime) -> String {
+    fmt_time(time, display_format().datetime_format(false).as_str())
+}
+
 /// ### fmt_pex
 ///
 /// Convert permissions bytes of permissions value into ls notation (e.g. rwx,-wx,--x)
@@ -61,6 +233,28 @@ pub fn fmt_time(time: SystemTime, fmt: &str) -> String {
     format!("{}", datetime.format(fmt))
 }
 
+/// ### fmt_path_template
+///
+/// Expand date placeholders in a path template using `time`.
+/// Supported placeholders are `{YYYY}`, `{MM}`, `{DD}`, `{hh}`, `{mm}` and `{ss}`
+pub fn fmt_path_template(template: &str, time: SystemTime) -> String {
+    [
+        ("{YYYY}", "%Y"),
+        ("{MM}", "%m"),
+        ("{DD}", "%d"),
+        ("{hh}", "%H"),
+        ("{mm}", "%M"),
+        ("{ss}", "%S"),
+    ]
+    .iter()
+    .fold(template.to_string(), |acc, (placeholder, fmt)| {
+        match acc.contains(placeholder) {
+            true => acc.replace(placeholder, fmt_time(time, fmt).as_str()),
+            false => acc,
+        }
+    })
+}
+
 /// ### fmt_millis
 ///
 /// Format duration as {secs}.{millis}
@@ -280,6 +474,34 @@ pub fn fmt_color(color: &Color) -> String {
     }
 }
 
+/// ### fmt_ssh_cd_command
+///
+/// Make a shell command which opens an interactive ssh session on `address` starting in `path`.
+/// Characters with a special meaning inside double quotes are escaped in `path`
+pub fn fmt_ssh_cd_command(username: Option<&str>, address: &str, port: u16, path: &Path) -> String {
+    let path: String = path
+        .to_string_lossy()
+        .chars()
+        .map(|x| match x {
+            '"' | '$' | '`' | '\\' => format!("\\{}", x),
+            '\'' => String::from("'\\''"),
+            x => x.to_string(),
+        })
+        .collect();
+    let target: String = match username {
+        Some(username) => format!("{}@{}", username, address),
+        None => address.to_string(),
+    };
+    let port: String = match port {
+        22 => String::new(),
+        port => format!("-p {} ", port),
+    };
+    format!(
+        "ssh -t {}{} 'cd \"{}\" && exec \"$SHELL\" -l'",
+        port, target, path
+    )
+}
+
 /// ### shadow_password
 ///
 /// Return a string with the same length of input string, but each character is replaced by '*'
@@ -287,6 +509,64 @@ pub fn shadow_password(s: &str) -> String {
     (0..s.len()).map(|_| '*').collect()
 }
 
+/// ### mask_secret
+///
+/// Replace each occurrence of `secret` in `s` with a fixed marker, which doesn't tell the secret length.
+/// Short secrets are masked too, even if they mask unrelated text; an empty secret is ignored
+pub fn mask_secret(s: &str, secret: &str) -> String {
+    match secret.is_empty() {
+        true => s.to_string(),
+        false => s.replace(secret, SECRET_MARKER),
+    }
+}
+
+/// ### fmt_banner
+///
+/// Split a server banner into lines which can be displayed as they are:
+/// terminal escape sequences and control characters are removed, tabs are expanded and
+/// the empty lines at the end are dropped
+pub fn fmt_banner(banner: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in banner.lines() {
+        let mut out: String = String::with_capacity(line.len());
+        let mut chars = line.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\x1b' => match chars.next() {
+                    // CSI: parameters until the final byte
+                    Some('[') => {
+                        for c in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&c) {
+                                break;
+                            }
+                        }
+                    }
+                    // OSC: until BEL or ST
+                    Some(']') => {
+                        while let Some(c) = chars.next() {
+                            if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                                break;
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                '\t' => {
+                    let spaces: usize = 8 - (out.chars().count() % 8);
+                    out.extend(std::iter::repeat_n(' ', spaces));
+                }
+                c if c.is_control() => {}
+                c => out.push(c),
+            }
+        }
+        lines.push(out.trim_end().to_string());
+    }
+    while lines.last().map(|x| x.is_empty()).unwrap_or(false) {
+        lines.pop();
+    }
+    lines
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -310,6 +590,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_utils_fmt_display_format() {
+        let mut format: DisplayFormat = DisplayFormat::default();
+        assert_eq!(format.size(8192).as_str(), "8.2 KB");
+        assert_eq!(format.datetime_format(false).as_str(), "%b %d %Y %H:%M");
+        assert_eq!(format.datetime_format(true).as_str(), "%b %d %Y %H:%M:%S");
+        format.size_units = SizeUnits::Binary;
+        format.clock = ClockFormat::H12;
+        format.date_format = String::from("%d/%m/%Y");
+        assert_eq!(format.size(8192).as_str(), "8.0 KiB");
+        assert_eq!(format.size(512).as_str(), "512 B");
+        assert_eq!(format.size(1572864).as_str(), "1.5 MiB");
+        assert_eq!(format.datetime_format(false).as_str(), "%d/%m/%Y %I:%M %p");
+        assert_eq!(
+            format.datetime_format(true).as_str(),
+            "%d/%m/%Y %I:%M:%S %p"
+        );
+        // Parse
+        assert_eq!(
+            SizeUnits::from_str("binary").ok().unwrap(),
+            SizeUnits::Binary
+        );
+        assert_eq!(SizeUnits::from_str("SI").ok().unwrap(), SizeUnits::Si);
+        assert!(SizeUnits::from_str("iec").is_err());
+        assert_eq!(ClockFormat::from_str("12h").ok().unwrap(), ClockFormat::H12);
+        assert_eq!(ClockFormat::H24.to_string().as_str(), "24h");
+        assert!(ClockFormat::from_str("am/pm").is_err());
+    }
+
+    #[test]
+    fn test_utils_fmt_is_valid_time_format() {
+        assert_eq!(is_valid_time_format("%Y-%m-%d"), true);
+        assert_eq!(is_valid_time_format("%d.%m.%y"), true);
+        assert_eq!(is_valid_time_format("%Q"), false);
+        assert_eq!(is_valid_time_format("%"), false);
+    }
+
+    #[test]
+    fn test_utils_fmt_path_template() {
+        let time: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(86400 * 45);
+        let year: String = fmt_time(time, "%Y");
+        let month: String = fmt_time(time, "%m");
+        assert_eq!(
+            fmt_path_template("logs/{YYYY}/{MM}", time),
+            format!("logs/{}/{}", year, month)
+        );
+        assert_eq!(
+            fmt_path_template("{DD}-{hh}{mm}{ss}", time),
+            fmt_time(time, "%d-%H%M%S")
+        );
+        assert_eq!(
+            fmt_path_template("plain/dir", time),
+            String::from("plain/dir")
+        );
+    }
+
+    #[test]
+    fn test_utils_fmt_ssh_cd_command() {
+        assert_eq!(
+            fmt_ssh_cd_command(Some("omar"), "192.168.1.31", 22, Path::new("/home/omar")),
+            String::from("ssh -t omar@192.168.1.31 'cd \"/home/omar\" && exec \"$SHELL\" -l'")
+        );
+        assert_eq!(
+            fmt_ssh_cd_command(None, "example.com", 4022, Path::new("/tmp/my dir")),
+            String::from("ssh -t -p 4022 example.com 'cd \"/tmp/my dir\" && exec \"$SHELL\" -l'")
+        );
+        assert_eq!(
+            fmt_ssh_cd_command(None, "example.com", 22, Path::new("/tmp/$it's\"")),
+            String::from("ssh -t example.com 'cd \"/tmp/\\$it'\\''s\\\"\" && exec \"$SHELL\" -l'")
+        );
+    }
+
     #[test]
     fn test_utils_fmt_millis() {
         assert_eq!(
@@ -595,8 +947,43 @@ mod tests {
         assert_eq!(fmt_color(&Color::Rgb(154, 205, 50)).as_str(), "yellowgreen");
     }
 
+    #[test]
+    fn test_utils_fmt_banner() {
+        assert_eq!(
+            fmt_banner("\x1b[1;32mWelcome\x1b[0m to omar's server\r\n\n  Uptime:\t3 days\x07\n\x1b]0;title\x07Bye\n\n\n"),
+            vec![
+                String::from("Welcome to omar's server"),
+                String::new(),
+                String::from("  Uptime:       3 days"),
+                String::from("Bye"),
+            ]
+        );
+        assert!(fmt_banner("\n\n").is_empty());
+    }
+
     #[test]
     fn test_utils_fmt_shadow_password() {
         assert_eq!(shadow_password("foobar"), String::from("******"));
     }
+
+    #[test]
+    fn test_utils_fmt_mask_secret() {
+        assert_eq!(
+            mask_secret("could not login as omar:foobar", "foobar"),
+            String::from("could not login as omar:********")
+        );
+        assert_eq!(
+            mask_secret("bad password 'secret' for secret", "secret"),
+            String::from("bad password '********' for ********")
+        );
+        // Short secrets are masked too
+        assert_eq!(
+            mask_secret("bad password 'ab' for abc", "ab"),
+            String::from("bad password '********' for ********c")
+        );
+        assert_eq!(
+            mask_secret("could not login", ""),
+            String::from("could not login")
+        );
+    }
 }
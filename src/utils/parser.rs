@@ -26,7 +26,7 @@
  * SOFTWARE.
  */
 // Locals
-use crate::filetransfer::{FileTransferParams, FileTransferProtocol};
+use crate::filetransfer::{registry, FileTransferParams, FileTransferProtocol};
 #[cfg(not(test))] // NOTE: don't use configuration during tests
 use crate::system::config_client::ConfigClient;
 #[cfg(not(test))] // NOTE: don't use configuration during tests
@@ -35,6 +35,7 @@ use crate::system::environment;
 // Ext
 use chrono::format::ParseError;
 use chrono::prelude::*;
+use percent_encoding::percent_decode_str;
 use regex::Regex;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -55,8 +56,7 @@ lazy_static! {
     /**
      * Regex matches:
      * - group 1: Version
-     * E.g. termscp-0.3.2 => 0.3.2
-     *      v0.4.0 => 0.4.0
+     * E.g. termscp-0.3.2 => 0.3.2, v0.4.0 => 0.4.0
      */
     static ref SEMVER_REGEX: Regex = Regex::new(r".*(:?[0-9]\.[0-9]\.[0-9])").unwrap();
     /**
@@ -83,6 +83,11 @@ lazy_static! {
 /// If port is missing default port will be used for each protocol
 ///     SFTP => 22
 ///     FTP => 21
+///     WEBDAV => 80
+///     WEBDAVS => 443
+///     HTTP => 80
+///     HTTPS => 443
+///     custom protocols => the port reported by their backend
 /// The option string has the following syntax
 /// [protocol://][username@]{address}[:port][:path]
 /// The only argument which is mandatory is address
@@ -119,17 +124,11 @@ pub fn parse_remote_opt(remote: &str) -> Result<FileTransferParams, String> {
             let mut port: u16 = 22;
             if let Some(group) = groups.get(1) {
                 // Set protocol from group
-                let (m_protocol, m_port) = match FileTransferProtocol::from_str(group.as_str()) {
-                    Ok(proto) => match proto {
-                        FileTransferProtocol::Ftp(_) => (proto, 21),
-                        FileTransferProtocol::Scp => (proto, 22),
-                        FileTransferProtocol::Sftp => (proto, 22),
-                    },
+                protocol = match FileTransferProtocol::from_str(group.as_str()) {
+                    Ok(proto) => proto,
                     Err(_) => return Err(format!("Unknown protocol \"{}\"", group.as_str())),
                 };
-                // NOTE: tuple destructuring assignment is not supported yet :(
-                protocol = m_protocol;
-                port = m_port;
+                port = registry::default_port(protocol).unwrap_or(22);
             }
             // Match user
             let username: Option<String> = match groups.get(2) {
@@ -225,6 +224,72 @@ pub fn parse_semver(haystack: &str) -> Option<String> {
     }
 }
 
+/// ### parse_unix_mode
+///
+/// Parse an octal unix mode (e.g. `644` or `0755`) into owner, group and others permissions
+pub fn parse_unix_mode(mode: &str) -> Option<(u8, u8, u8)> {
+    let mode: &str = mode.trim();
+    let mode: &str = match mode.len() {
+        4 => mode.strip_prefix('0')?,
+        _ => mode,
+    };
+    if mode.len() != 3 {
+        return None;
+    }
+    let mut digits = mode.chars().map(|x| x.to_digit(8).map(|x| x as u8));
+    Some((digits.next()??, digits.next()??, digits.next()??))
+}
+
+/// ### parse_pasted_paths
+///
+/// Parse text pasted into the terminal, such as the paths of the files dropped onto it by a file manager,
+/// into a list of absolute paths. Paths may be quoted, have their spaces escaped or be `file://` urls;
+/// several paths are separated by whitespaces. Returns `None` unless the text is made of absolute paths only
+pub fn parse_pasted_paths(text: &str) -> Option<Vec<PathBuf>> {
+    let mut words: Vec<String> = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some(q), ch) if ch == q => quote = None,
+            (Some(_), ch) => word.get_or_insert_with(String::new).push(ch),
+            (None, '\'') | (None, '"') => {
+                quote = Some(ch);
+                word.get_or_insert_with(String::new);
+            }
+            // Backslashes are path separators on Windows
+            (None, '\\') if cfg!(target_family = "unix") => {
+                if let Some(escaped) = chars.next() {
+                    word.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            (None, ch) if ch.is_whitespace() => words.extend(word.take()),
+            (None, ch) => word.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    if quote.is_some() {
+        return None;
+    }
+    words.extend(word);
+    if words.is_empty() {
+        return None;
+    }
+    words
+        .into_iter()
+        .map(|word| {
+            let path: PathBuf = match word.strip_prefix("file://") {
+                Some(url) => PathBuf::from(percent_decode_str(url).decode_utf8().ok()?.as_ref()),
+                None => PathBuf::from(word),
+            };
+            match path.is_absolute() {
+                true => Some(path),
+                false => None,
+            }
+        })
+        .collect()
+}
+
 /// ### parse_color
 ///
 /// Parse color from string into a `Color` enum.
@@ -421,8 +486,8 @@ pub fn parse_color(color: &str) -> Option<Color> {
 ///
 /// Try to parse a color in hex format, such as:
 ///
-///     - #f0ab05
-///     - #AA33BC
+/// - #f0ab05
+/// - #AA33BC
 fn parse_hex_color(color: &str) -> Option<Color> {
     COLOR_HEX_REGEX.captures(color).map(|groups| {
         Color::Rgb(
@@ -443,9 +508,9 @@ fn parse_hex_color(color: &str) -> Option<Color> {
 ///
 /// Try to parse a color in rgb format, such as:
 ///
-///     - rgb(255, 64, 32)
-///     - rgb(255,64,32)
-///     - 255, 64, 32
+/// - rgb(255, 64, 32)
+/// - rgb(255,64,32)
+/// - 255, 64, 32
 fn parse_rgb_color(color: &str) -> Option<Color> {
     COLOR_RGB_REGEX.captures(color).map(|groups| {
         Color::Rgb(
@@ -537,6 +602,33 @@ mod tests {
         assert_eq!(result.protocol, FileTransferProtocol::Ftp(true));
         assert_eq!(result.username.unwrap(), String::from("anon"));
         assert!(result.entry_directory.is_none());
+        // WebDAV
+        let result: FileTransferParams = parse_remote_opt(&String::from(
+            "webdavs://omar@cloud.example.com:/remote.php/dav/files/omar",
+        ))
+        .ok()
+        .unwrap();
+        assert_eq!(result.address, String::from("cloud.example.com"));
+        assert_eq!(result.port, 443); // Fallback to https default
+        assert_eq!(result.protocol, FileTransferProtocol::WebDav(true));
+        assert_eq!(result.username.unwrap(), String::from("omar"));
+        assert_eq!(
+            result.entry_directory.unwrap(),
+            PathBuf::from("/remote.php/dav/files/omar")
+        );
+        // HTTP
+        let result: FileTransferParams =
+            parse_remote_opt(&String::from("https://mirror.example.com:/pub/releases"))
+                .ok()
+                .unwrap();
+        assert_eq!(result.address, String::from("mirror.example.com"));
+        assert_eq!(result.port, 443);
+        assert_eq!(result.protocol, FileTransferProtocol::Http(true));
+        assert!(result.username.is_none());
+        assert_eq!(
+            result.entry_directory.unwrap(),
+            PathBuf::from("/pub/releases")
+        );
         // Path
         let result: FileTransferParams =
             parse_remote_opt(&String::from("root@172.26.104.1:8022:/var"))
@@ -646,6 +738,46 @@ mod tests {
         assert!(parse_semver("v1.1").is_none());
     }
 
+    #[test]
+    fn test_utils_parse_unix_mode() {
+        assert_eq!(parse_unix_mode("644").unwrap(), (6, 4, 4));
+        assert_eq!(parse_unix_mode("0755").unwrap(), (7, 5, 5));
+        assert_eq!(parse_unix_mode(" 700 ").unwrap(), (7, 0, 0));
+        assert!(parse_unix_mode("").is_none());
+        assert!(parse_unix_mode("64").is_none());
+        assert!(parse_unix_mode("1755").is_none());
+        assert!(parse_unix_mode("648").is_none());
+        assert!(parse_unix_mode("rwx").is_none());
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_utils_parse_pasted_paths() {
+        assert_eq!(
+            parse_pasted_paths("/home/omar/README.md").unwrap(),
+            vec![PathBuf::from("/home/omar/README.md")]
+        );
+        // Quoted, escaped and urls; trailing space
+        assert_eq!(
+            parse_pasted_paths(
+                "'/home/omar/my docs' \"/tmp/it's.txt\" /tmp/a\\ b.txt file:///tmp/c%20d.txt "
+            )
+            .unwrap(),
+            vec![
+                PathBuf::from("/home/omar/my docs"),
+                PathBuf::from("/tmp/it's.txt"),
+                PathBuf::from("/tmp/a b.txt"),
+                PathBuf::from("/tmp/c d.txt"),
+            ]
+        );
+        // Not paths
+        assert!(parse_pasted_paths("").is_none());
+        assert!(parse_pasted_paths("   ").is_none());
+        assert!(parse_pasted_paths("jjjk").is_none());
+        assert!(parse_pasted_paths("/tmp/a.txt README.md").is_none());
+        assert!(parse_pasted_paths("'/tmp/a.txt").is_none());
+    }
+
     #[test]
     fn test_utils_parse_color_hex() {
         assert_eq!(
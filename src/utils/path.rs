@@ -10,6 +10,8 @@ use std::path::{Path, PathBuf};
 /// For example:
 ///
 /// ```rust
+/// # use std::path::Path;
+/// # use termscp::utils::path::absolutize;
 /// assert_eq!(absolutize(&Path::new("/home/omar"), &Path::new("readme.txt")).as_path(), Path::new("/home/omar/readme.txt"));
 /// assert_eq!(absolutize(&Path::new("/home/omar"), &Path::new("/tmp/readme.txt")).as_path(), Path::new("/tmp/readme.txt"));
 /// ```
@@ -24,6 +26,44 @@ pub fn absolutize(wrkdir: &Path, target: &Path) -> PathBuf {
     }
 }
 
+/// ### duplicate_name
+///
+/// Get a name for a copy of `name` placed in the same directory.
+/// The first candidate is `copy_of_{name}`; if `exists` reports it as taken, a numbered suffix is added before the extension.
+/// For example:
+///
+/// ```rust
+/// # use termscp::utils::path::duplicate_name;
+/// assert_eq!(duplicate_name("omar.txt", |_| false).as_str(), "copy_of_omar.txt");
+/// assert_eq!(duplicate_name("omar.txt", |x| x == "copy_of_omar.txt").as_str(), "copy_of_omar_2.txt");
+/// ```
+pub fn duplicate_name<F>(name: &str, exists: F) -> String
+where
+    F: Fn(&str) -> bool,
+{
+    let candidate: String = format!("copy_of_{}", name);
+    if !exists(candidate.as_str()) {
+        return candidate;
+    }
+    let path: &Path = Path::new(name);
+    let stem: String = path
+        .file_stem()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_else(|| name.to_string());
+    let extension: String = path
+        .extension()
+        .map(|x| format!(".{}", x.to_string_lossy()))
+        .unwrap_or_default();
+    let mut n: usize = 2;
+    loop {
+        let candidate: String = format!("copy_of_{}_{}{}", stem, n, extension);
+        if !exists(candidate.as_str()) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -40,4 +80,30 @@ mod test {
             Path::new("/tmp/readme.txt")
         );
     }
+
+    #[test]
+    fn duplicate_file_name() {
+        assert_eq!(
+            duplicate_name("omar.txt", |_| false).as_str(),
+            "copy_of_omar.txt"
+        );
+        assert_eq!(
+            duplicate_name("omar.txt", |x| x == "copy_of_omar.txt").as_str(),
+            "copy_of_omar_2.txt"
+        );
+        assert_eq!(
+            duplicate_name("omar.txt", |x| x == "copy_of_omar.txt"
+                || x == "copy_of_omar_2.txt")
+            .as_str(),
+            "copy_of_omar_3.txt"
+        );
+        assert_eq!(
+            duplicate_name("docs", |x| x == "copy_of_docs").as_str(),
+            "copy_of_docs_2"
+        );
+        assert_eq!(
+            duplicate_name(".bashrc", |x| x == "copy_of_.bashrc").as_str(),
+            "copy_of_.bashrc_2"
+        );
+    }
 }
@@ -25,6 +25,7 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+use std::io::{self, Write};
 use tuirealm::tui::layout::{Constraint, Direction, Layout, Rect};
 
 /// ### draw_area_in
@@ -55,6 +56,23 @@ pub fn draw_area_in(parent: Rect, width: u16, height: u16) -> Rect {
         .split(new_area[1])[1]
 }
 
+/// ### copy_to_clipboard
+///
+/// Copy text to the system clipboard through the terminal, using the OSC 52 escape sequence.
+/// This works over ssh too, but requires the terminal emulator to support it
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()
+}
+
+/// ### osc52_sequence
+///
+/// Make OSC 52 sequence to set clipboard to `text`
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64::encode(text))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -71,4 +89,12 @@ mod tests {
         assert_eq!(child.width, 271);
         assert_eq!(child.height, 54);
     }
+
+    #[test]
+    fn test_utils_ui_osc52_sequence() {
+        assert_eq!(
+            osc52_sequence("termscp"),
+            String::from("\x1b]52;c;dGVybXNjcA==\x07")
+        );
+    }
 }